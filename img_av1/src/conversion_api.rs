@@ -7,6 +7,7 @@ use crate::detection_api::{CompressionType, DetectedFormat, DetectionResult, Ima
 use crate::{ImgQualityError, Result};
 use serde::{Deserialize, Serialize};
 use shared_utils::image_analyzer::get_animation_duration_for_path;
+use shared_utils::SkipReason;
 use std::path::{Path, PathBuf};
 use std::process::Command;
 
@@ -38,6 +39,11 @@ pub struct ConversionConfig {
     pub compress: bool,
     /// When true, JXL uses --compress_boxes=0 for Apple compatibility.
     pub apple_compat: bool,
+    /// `--strip-metadata` / `--strip-timestamps`: encoders drop embedded metadata and the
+    /// metadata step skips what is stripped.
+    pub strip: shared_utils::StripPolicy,
+    /// `--on-collision`: what happens when the output path already exists.
+    pub on_collision: shared_utils::CollisionPolicy,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -49,6 +55,9 @@ pub struct ConversionOutput {
     pub original_size: u64,
     pub output_size: Option<u64>,
     pub size_reduction: Option<f32>,
+    /// Why the file was skipped; `None` for conversions.
+    #[serde(default)]
+    pub skip_reason: Option<SkipReason>,
 }
 
 impl ConversionOutput {
@@ -132,8 +141,9 @@ pub fn determine_strategy(detection: &DetectionResult) -> Result<ConversionStrat
                 reason: "Animated lossless image, recommend AV1 MP4 with CRF 0 (visually lossless)"
                     .to_string(),
                 command: Some(format!(
-                    "ffmpeg -y -i '{}' -c:v libsvtav1 -crf 0 -preset 6 -r {:.3} -pix_fmt yuv420p '{}'",
+                    "ffmpeg -y -i '{}' -c:v {} -crf 0 -r {:.3} -pix_fmt yuv420p '{}'",
                     input_path,
+                    shared_utils::av1_backend().ffmpeg_name(),
                     fps,
                     output_path.display()
                 )),
@@ -203,6 +213,7 @@ pub fn execute_conversion(
             original_size: detection.file_size,
             output_size: None,
             size_reduction: None,
+            skip_reason: Some(SkipReason::ModernLossy),
         });
     }
 
@@ -213,11 +224,21 @@ pub fn execute_conversion(
         TargetFormat::NoConversion => unreachable!("NoConversion handled by early return above"),
     };
 
-    let output_path = resolve_output_path(input_path, config.output_dir.as_deref(), extension)?;
+    let output_path = resolve_output_path(
+        input_path,
+        config.output_dir.as_deref(),
+        extension,
+        config.on_collision,
+    )?;
     shared_utils::conversion::validate_output_path(&output_path, config.base_dir.as_deref())
         .map_err(ImgQualityError::ConversionError)?;
 
-    if output_path.exists() && !config.force {
+    if shared_utils::conversion::keeps_existing_output(
+        input_path,
+        &output_path,
+        config.force,
+        config.on_collision,
+    ) {
         return Ok(ConversionOutput {
             original_path: detection.file_path.clone(),
             output_path: output_path.display().to_string(),
@@ -226,6 +247,7 @@ pub fn execute_conversion(
             original_size: detection.file_size,
             output_size: None,
             size_reduction: None,
+            skip_reason: Some(SkipReason::AlreadyProcessed),
         });
     }
 
@@ -260,6 +282,8 @@ pub fn execute_conversion(
         &output_path,
         config.force,
         Some(input_path),
+        config.strip,
+        config.on_collision,
     )
     .map_err(|e: std::io::Error| ImgQualityError::ConversionError(e.to_string()))?
     {
@@ -271,6 +295,7 @@ pub fn execute_conversion(
             original_size: detection.file_size,
             output_size: None,
             size_reduction: None,
+            skip_reason: Some(SkipReason::AlreadyProcessed),
         });
     }
 
@@ -318,12 +343,13 @@ pub fn execute_conversion(
                 original_size: detection.file_size,
                 output_size: Some(detection.file_size),
                 size_reduction: Some(0.0),
+                skip_reason: Some(SkipReason::OutputLarger),
             });
         }
     }
 
     if config.preserve_metadata || config.preserve_timestamps {
-        shared_utils::copy_metadata(input_path, &output_path);
+        shared_utils::copy_metadata(input_path, &output_path, config.strip);
     }
 
     if config.delete_original {
@@ -357,6 +383,7 @@ pub fn execute_conversion(
         original_size: detection.file_size,
         output_size,
         size_reduction,
+        skip_reason: None,
     })
 }
 
@@ -370,6 +397,7 @@ fn resolve_output_path(
     input: &Path,
     output_dir: Option<&Path>,
     extension: &str,
+    collision: shared_utils::CollisionPolicy,
 ) -> Result<PathBuf> {
     let file_stem = input.file_stem().ok_or_else(|| {
         ImgQualityError::ConversionError("Invalid file path: no file stem".to_string())
@@ -379,6 +407,8 @@ fn resolve_output_path(
     } else {
         input.with_extension(extension)
     };
+    let output = shared_utils::conversion::resolve_output_collision(input, output, collision)
+        .map_err(ImgQualityError::ConversionError)?;
     shared_utils::conversion::validate_output_path(&output, None)
         .map_err(ImgQualityError::ConversionError)?;
     Ok(output)
@@ -413,15 +443,15 @@ fn convert_to_jxl(
     if *format == DetectedFormat::JPEG {
         cmd.args(["--lossless_jpeg=1", "-j"]);
         cmd.arg(max_threads.to_string());
-        cmd.arg("--");
     } else {
         cmd.args(["-d", "0.0", "-e", "7", "-j"]);
         cmd.arg(max_threads.to_string());
-        cmd.arg("--");
     }
     if config.apple_compat {
         cmd.arg("--compress_boxes=0");
     }
+    cmd.args(config.strip.cjxl_args());
+    cmd.arg("--");
     let status = cmd
         .arg(shared_utils::safe_path_arg(&input_abs).as_ref())
         .arg(shared_utils::safe_path_arg(&output_abs).as_ref())
@@ -474,6 +504,7 @@ fn convert_to_avif(
     let output_abs = resolve_output_absolute(output);
 
     let status = Command::new("avifenc")
+        .args(config.strip.avifenc_args())
         .arg(shared_utils::safe_path_arg(&input_abs).as_ref())
         .arg(shared_utils::safe_path_arg(&output_abs).as_ref())
         .args(["-q", &q])
@@ -524,7 +555,18 @@ fn convert_to_av1_mp4(
     })?;
     let fps_str = fps_val.to_string();
     let max_threads = shared_utils::thread_manager::get_ffmpeg_threads();
-    let svt_params = format!("tune=0:film-grain=0:lp={}", max_threads);
+    let backend = shared_utils::av1_backend();
+    let speed_args = match backend {
+        shared_utils::Av1Backend::Svt => vec![
+            "-preset".to_string(),
+            "6".to_string(),
+            "-svtav1-params".to_string(),
+            format!("tune=0:film-grain=0:lp={}", max_threads),
+        ],
+        shared_utils::Av1Backend::Aom => {
+            shared_utils::libaom_args(false, shared_utils::EncoderPreset::Medium, max_threads)
+        }
+    };
     let input_abs = canonicalize_input(input);
     let output_abs = resolve_output_absolute(output);
 
@@ -534,20 +576,10 @@ fn convert_to_av1_mp4(
         .arg(max_threads.to_string())
         .arg("-i")
         .arg(shared_utils::safe_path_arg(&input_abs).as_ref())
-        .args([
-            "-c:v",
-            "libsvtav1",
-            "-crf",
-            "0",
-            "-preset",
-            "6",
-            "-svtav1-params",
-            &svt_params,
-            "-r",
-            &fps_str,
-            "-pix_fmt",
-            "yuv420p",
-        ])
+        .args(["-c:v", backend.ffmpeg_name(), "-crf", "0"])
+        .args(&speed_args)
+        .args(["-r", &fps_str, "-pix_fmt", "yuv420p"])
+        .args(config.strip.ffmpeg_args())
         .arg(shared_utils::safe_path_arg(&output_abs).as_ref())
         .output()?;
 
@@ -613,6 +645,8 @@ pub fn simple_convert(path: &Path, output_dir: Option<&Path>) -> Result<Conversi
         preserve_metadata: true,   // Changed: Always preserve metadata by default
         compress: false,
         apple_compat: false,
+        strip: shared_utils::StripPolicy::default(),
+        on_collision: shared_utils::CollisionPolicy::default(),
     };
     smart_convert(path, &config)
 }
@@ -761,6 +795,7 @@ mod tests {
             original_size: detection.file_size,
             output_size: None,
             size_reduction: None,
+            skip_reason: Some(SkipReason::ModernLossy),
         };
         assert!(rec.skipped);
         assert!(rec.message.contains("lossy"));
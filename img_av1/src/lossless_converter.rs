@@ -49,6 +49,7 @@ fn finalize_with_size_check(
     options: &ConvertOptions,
     format_label: &str,
     extra_info: Option<String>,
+    lossless: bool,
 ) -> Result<ConversionResult> {
     // Commit temp file to final output WITH METADATA PRESERVATION
     if !shared_utils::conversion::commit_temp_to_output_with_metadata(
@@ -56,6 +57,8 @@ fn finalize_with_size_check(
         output,
         options.force,
         Some(input),
+        options.strip,
+        options.on_collision,
     )? {
         return Ok(ConversionResult::skipped_exists(input, output));
     }
@@ -72,8 +75,14 @@ fn finalize_with_size_check(
         return Ok(skipped);
     }
 
-    // Finalize with metadata preservation
-    finalize_conversion(
+    // Finalize with metadata preservation; lossless targets must round-trip pixel-exact
+    // before the original may be deleted.
+    let finalize = if lossless {
+        shared_utils::conversion::finalize_lossless_conversion
+    } else {
+        finalize_conversion
+    };
+    finalize(
         input,
         output,
         input_size,
@@ -119,16 +128,43 @@ pub fn convert_to_jxl(
         fs::create_dir_all(parent)?;
     }
 
-    if output.exists() && !options.force {
+    if shared_utils::conversion::keeps_existing_output(
+        input,
+        &output,
+        options.force,
+        options.on_collision,
+    ) {
         return Ok(ConversionResult::skipped_exists(input, &output));
     }
 
     let temp_output = shared_utils::conversion::temp_path_for_output(&output);
 
+    // 12/16-bit sources (medical/scientific TIFF, 16-bit PNG) keep their depth through cjxl.
+    let source_depth = shared_utils::image_analyzer::read_bit_depth(input);
     let (actual_input, _temp_file_guard) = prepare_input_for_cjxl(input, options, hdr_info)?;
 
-    // Extract ICC Profile from original input for preservation
-    let _icc_temp = shared_utils::jxl_utils::extract_icc_profile(input);
+    // --auto-grayscale: feed cjxl a single-channel copy. The source's RGB ICC profile cannot
+    // describe it, so that profile is not carried over.
+    let gray_input = options
+        .grayscale
+        .then(|| shared_utils::jxl_utils::convert_to_gray_png(&actual_input))
+        .flatten();
+    let grayscale = gray_input.is_some();
+    let (actual_input, _temp_file_guard) = match gray_input {
+        Some((path, temp)) => (path, Some(temp)),
+        None => (actual_input, _temp_file_guard),
+    };
+    if grayscale && options.verbose {
+        eprintln!("   ⚫ Grayscale content: encoding single-channel JXL");
+    }
+
+    // Carry the original input's ICC profile over (--preserve-icc)
+    let _icc_temp = shared_utils::jxl_utils::icc_for_cjxl(
+        input,
+        options.preserve_icc,
+        grayscale,
+        options.verbose,
+    );
     let icc_path = _icc_temp.as_ref().map(|t| t.path());
 
     let max_threads = if options.child_threads > 0 {
@@ -136,32 +172,18 @@ pub fn convert_to_jxl(
     } else {
         shared_utils::thread_manager::get_optimal_threads()
     };
-    let mut cmd = Command::new("cjxl");
-    cmd.arg("-d")
-        .arg(format!("{:.2}", distance))
-        .arg("-e")
-        .arg("7")
-        .arg("-j")
-        .arg(max_threads.to_string());
-
-    if options.apple_compat {
-        cmd.arg("--compress_boxes=0");
-    }
-
-    // Add HDR metadata via CICP if available
-    if let Some(hdr) = hdr_info {
-        if let Some(cicp) = shared_utils::color_info_to_cicp(hdr) {
-            cmd.arg(format!("--cicp={}", cicp));
-        }
-    }
-
-    shared_utils::jxl_utils::add_icc_to_cjxl(&mut cmd, icc_path);
-
-    cmd.arg("--")
-        .arg(shared_utils::safe_path_arg(&actual_input).as_ref())
-        .arg(shared_utils::safe_path_arg(&temp_output).as_ref());
-
-    let cmd_result = cmd.output();
+    let cmd_result = Command::new("cjxl")
+        .args(cjxl_distance_args(
+            &actual_input,
+            &temp_output,
+            distance,
+            max_threads,
+            source_depth,
+            hdr_info,
+            options,
+            icc_path,
+        ))
+        .output();
 
     let result = match &cmd_result {
         Ok(output_cmd) if !output_cmd.status.success() => {
@@ -173,6 +195,7 @@ pub fn convert_to_jxl(
                     distance,
                     max_threads,
                     options.apple_compat,
+                    options.strip,
                 )
                 .is_ok()
                 {
@@ -182,33 +205,17 @@ pub fn convert_to_jxl(
                         cleanup_temp_output(&temp_output, input);
                         return Err(e);
                     }
-                    if !shared_utils::conversion::commit_temp_to_output_with_metadata(
-                        &temp_output,
-                        &output,
-                        options.force,
-                        Some(input),
-                    )? {
-                        return Ok(ConversionResult::skipped_exists(input, &output));
-                    }
-                    if let Some(skipped) = check_size_tolerance(
+                    return finalize_with_size_check(
                         input,
+                        &temp_output,
                         &output,
                         input_size,
                         output_size,
                         options,
                         "JXL",
-                    ) {
-                        return Ok(skipped);
-                    }
-                    return finalize_conversion(
-                        input,
-                        &output,
-                        input_size,
-                        "JXL",
-                        Some("(imagemagick fallback)"),
-                        options,
-                    )
-                    .map_err(ImgQualityError::IoError);
+                        Some("(imagemagick fallback)".to_string()),
+                        distance == 0.0,
+                    );
                 }
                 cmd_result
             } else {
@@ -226,6 +233,14 @@ pub fn convert_to_jxl(
                 cleanup_temp_output(&temp_output, input);
                 return Err(e);
             }
+            if distance == 0.0 {
+                if let Err(e) =
+                    shared_utils::jxl_utils::verify_jxl_bit_depth(source_depth, &temp_output)
+                {
+                    cleanup_temp_output(&temp_output, input);
+                    return Err(ImgQualityError::ConversionError(e));
+                }
+            }
 
             finalize_with_size_check(
                 input,
@@ -236,6 +251,7 @@ pub fn convert_to_jxl(
                 options,
                 "JXL",
                 None,
+                distance == 0.0,
             )
         }
         Ok(output_cmd) => {
@@ -269,36 +285,95 @@ fn run_cjxl_jpeg_transcode(
     let _icc_temp = shared_utils::jxl_utils::extract_icc_profile(input);
     let icc_path = _icc_temp.as_ref().map(|t| t.path());
 
+    Command::new("cjxl")
+        .args(jpeg_transcode_cjxl_args(
+            input,
+            temp_output,
+            options,
+            allow_jpeg_reconstruction,
+            hdr_info,
+            icc_path,
+        ))
+        .output()
+}
+
+/// cjxl arguments of the JPEG transcode in [`run_cjxl_jpeg_transcode`].
+fn jpeg_transcode_cjxl_args(
+    input: &Path,
+    output: &Path,
+    options: &ConvertOptions,
+    allow_jpeg_reconstruction: Option<u8>,
+    hdr_info: Option<&shared_utils::ColorInfo>,
+    icc_path: Option<&Path>,
+) -> Vec<String> {
     let max_threads = shared_utils::thread_manager::get_ffmpeg_threads();
-    let mut cmd = Command::new("cjxl");
-    cmd.arg("--lossless_jpeg=1")
-        .arg("-j")
-        .arg(max_threads.to_string());
+    let mut args = vec![
+        "--lossless_jpeg=1".to_string(),
+        "-j".to_string(),
+        max_threads.to_string(),
+    ];
     if let Some(v) = allow_jpeg_reconstruction {
-        cmd.arg("--allow_jpeg_reconstruction").arg(v.to_string());
+        args.extend(["--allow_jpeg_reconstruction".to_string(), v.to_string()]);
     }
     if options.apple_compat {
-        cmd.arg("--compress_boxes=0");
+        args.push("--compress_boxes=0".to_string());
     }
+    args.extend(options.strip.cjxl_args().iter().map(|s| s.to_string()));
 
-    if let Some(hdr) = hdr_info {
-        if let Some(cicp) = shared_utils::color_info_to_cicp(hdr) {
-            cmd.arg(format!("--cicp={}", cicp));
-        }
+    if let Some(cicp) = hdr_info.and_then(shared_utils::color_info_to_cicp) {
+        args.push(format!("--cicp={}", cicp));
     }
 
-    shared_utils::jxl_utils::add_icc_to_cjxl(&mut cmd, icc_path);
-
-    cmd.arg("--")
-        .arg(shared_utils::safe_path_arg(input).as_ref())
-        .arg(shared_utils::safe_path_arg(temp_output).as_ref());
-    cmd.output()
+    args.extend(shared_utils::jxl_utils::icc_cjxl_args(icc_path));
+    args.extend(shared_utils::jxl_utils::cjxl_io_args(input, output));
+    args
 }
 
 pub fn convert_jpeg_to_jxl(
     input: &Path,
     options: &ConvertOptions,
     hdr_info: Option<&shared_utils::ColorInfo>,
+) -> Result<ConversionResult> {
+    // --normalize-orientation: transcode an upright copy; the original stays the metadata source.
+    let upright = if options.normalize_orientation {
+        shared_utils::jxl_utils::normalize_jpeg_orientation_to_temp(input).unwrap_or_else(|e| {
+            eprintln!(
+                "⚠️ [img-av1] Orientation check failed for {}: {}",
+                input.display(),
+                e
+            );
+            None
+        })
+    } else {
+        None
+    };
+    let Some((upright_path, _upright_guard)) = upright else {
+        return transcode_jpeg_to_jxl(input, input, options, hdr_info);
+    };
+    if options.verbose {
+        eprintln!("   🔄 Rotated upright from EXIF orientation (lossless)");
+    }
+
+    let result = transcode_jpeg_to_jxl(input, &upright_path, options, hdr_info)?;
+    // The metadata step copied the original Orientation tag back onto the output.
+    if result.success && !result.skipped {
+        if let Some(ref output) = result.output_path {
+            if let Err(e) = shared_utils::reset_orientation_tag(Path::new(output)) {
+                eprintln!(
+                    "⚠️ [img-av1] Failed to reset EXIF orientation on {}: {}",
+                    output, e
+                );
+            }
+        }
+    }
+    Ok(result)
+}
+
+fn transcode_jpeg_to_jxl(
+    input: &Path,
+    source: &Path,
+    options: &ConvertOptions,
+    hdr_info: Option<&shared_utils::ColorInfo>,
 ) -> Result<ConversionResult> {
     if !options.force && is_already_processed(input) {
         return Ok(ConversionResult::skipped_duplicate(input));
@@ -311,13 +386,18 @@ pub fn convert_jpeg_to_jxl(
         fs::create_dir_all(parent)?;
     }
 
-    if output.exists() && !options.force {
+    if shared_utils::conversion::keeps_existing_output(
+        input,
+        &output,
+        options.force,
+        options.on_collision,
+    ) {
         return Ok(ConversionResult::skipped_exists(input, &output));
     }
 
     let temp_output = shared_utils::conversion::temp_path_for_output(&output);
 
-    let result = run_cjxl_jpeg_transcode(input, &temp_output, options, None, hdr_info);
+    let result = run_cjxl_jpeg_transcode(source, &temp_output, options, None, hdr_info);
 
     let output_cmd = match result {
         Ok(out) => out,
@@ -344,6 +424,7 @@ pub fn convert_jpeg_to_jxl(
             options,
             "JPEG lossless transcode",
             None,
+            false,
         );
     }
 
@@ -353,9 +434,9 @@ pub fn convert_jpeg_to_jxl(
     if is_jpeg_reconstruction_cjxl_error(&stderr) {
         // 1) Fix: strip trailing data after JPEG EOI so cjxl can use bitstream reconstruction
         let (source_to_use, _guard): (std::path::PathBuf, Option<tempfile::NamedTempFile>) =
-            match shared_utils::jxl_utils::strip_jpeg_tail_to_temp(input) {
+            match shared_utils::jxl_utils::strip_jpeg_tail_to_temp(source) {
                 Ok(Some((cleaned, guard))) => (cleaned, Some(guard)),
-                _ => (input.to_path_buf(), None),
+                _ => (source.to_path_buf(), None),
             };
 
         // 2) Retry with original cjxl flags (no --allow_jpeg_reconstruction 0) on fixed or original
@@ -372,10 +453,12 @@ pub fn convert_jpeg_to_jxl(
                     &output,
                     options.force,
                     Some(input),
+                    options.strip,
+                    options.on_collision,
                 )? {
                     return Ok(ConversionResult::skipped_exists(input, &output));
                 }
-                let label = if source_to_use != input {
+                let label = if source_to_use != source {
                     "JPEG lossless transcode (sanitized tail)"
                 } else {
                     "JPEG lossless transcode"
@@ -400,6 +483,8 @@ pub fn convert_jpeg_to_jxl(
                     &output,
                     options.force,
                     Some(input),
+                    options.strip,
+                    options.on_collision,
                 )? {
                     return Ok(ConversionResult::skipped_exists(input, &output));
                 }
@@ -457,7 +542,12 @@ pub fn convert_to_avif(
         fs::create_dir_all(parent)?;
     }
 
-    if output.exists() && !options.force {
+    if shared_utils::conversion::keeps_existing_output(
+        input,
+        &output,
+        options.force,
+        options.on_collision,
+    ) {
         return Ok(ConversionResult::skipped_exists(input, &output));
     }
 
@@ -473,6 +563,7 @@ pub fn convert_to_avif(
         .arg("all")
         .arg("-q")
         .arg(q.to_string())
+        .args(options.strip.avifenc_args())
         .arg("--")
         .arg(shared_utils::safe_path_arg(input).as_ref())
         .arg(shared_utils::safe_path_arg(&temp_output).as_ref())
@@ -494,6 +585,7 @@ pub fn convert_to_avif(
                 options,
                 "AVIF",
                 None,
+                false,
             )
         }
         Ok(output_cmd) => {
@@ -516,6 +608,23 @@ pub fn convert_to_av1_mp4(input: &Path, options: &ConvertOptions) -> Result<Conv
         .map_err(|e| ImgQualityError::ConversionError(e.to_string()))
 }
 
+/// Convert an animated image to animated WebP (libwebp_anim), preserving alpha.
+/// See `shared_utils::animated_webp` for the quality-matching behaviour.
+pub fn convert_to_animated_webp(
+    input: &Path,
+    options: &ConvertOptions,
+) -> Result<ConversionResult> {
+    shared_utils::animated_webp::convert_to_animated_webp(input, options)
+        .map_err(ImgQualityError::ConversionError)
+}
+
+/// Convert a short animated GIF to lossless APNG (FFmpeg `apng` muxer).
+/// No SSIM judge; the original is copied through unless the APNG is smaller.
+pub fn convert_to_apng(input: &Path, options: &ConvertOptions) -> Result<ConversionResult> {
+    shared_utils::animated_apng::convert_to_apng(input, options)
+        .map_err(ImgQualityError::ConversionError)
+}
+
 pub fn convert_to_avif_lossless(
     input: &Path,
     options: &ConvertOptions,
@@ -551,7 +660,12 @@ pub fn convert_to_avif_lossless(
         fs::create_dir_all(parent)?;
     }
 
-    if output.exists() && !options.force {
+    if shared_utils::conversion::keeps_existing_output(
+        input,
+        &output,
+        options.force,
+        options.on_collision,
+    ) {
         return Ok(ConversionResult::skipped_exists(input, &output));
     }
 
@@ -563,6 +677,7 @@ pub fn convert_to_avif_lossless(
         .arg("4")
         .arg("-j")
         .arg("all")
+        .args(options.strip.avifenc_args())
         .arg("--")
         .arg(shared_utils::safe_path_arg(input).as_ref())
         .arg(shared_utils::safe_path_arg(&temp_output).as_ref())
@@ -584,6 +699,7 @@ pub fn convert_to_avif_lossless(
                 options,
                 "Lossless AVIF",
                 None,
+                true,
             )
         }
         Ok(output_cmd) => {
@@ -724,7 +840,12 @@ pub fn convert_to_jxl_matched(
         fs::create_dir_all(parent)?;
     }
 
-    if output.exists() && !options.force {
+    if shared_utils::conversion::keeps_existing_output(
+        input,
+        &output,
+        options.force,
+        options.on_collision,
+    ) {
         return Ok(ConversionResult::skipped_exists(input, &output));
     }
 
@@ -733,43 +854,24 @@ pub fn convert_to_jxl_matched(
     let distance = calculate_matched_distance_for_static(analysis, input_size)?;
     eprintln!("   🎯 Matched JXL distance: {:.2}", distance);
 
-    let max_threads = shared_utils::thread_manager::get_optimal_threads();
-    let mut cmd = Command::new("cjxl");
-    cmd.arg("-d")
-        .arg(format!("{:.2}", distance))
-        .arg("-e")
-        .arg("7")
-        .arg("-j")
-        .arg(max_threads.to_string());
-
-    if options.apple_compat {
-        cmd.arg("--compress_boxes=0");
-    }
-
-    // `analysis` passed in doesn't have hdr_info in signature, we get it from analysis
-    if let Some(ref hdr) = analysis.hdr_info {
-        if let Some(cicp) = shared_utils::color_info_to_cicp(hdr) {
-            cmd.arg(format!("--cicp={}", cicp));
-        }
-    }
-
-    // Only disable lossless JPEG mode when input is actually JPEG and we want lossy encoding.
-    if distance > 0.0 {
-        let is_jpeg = options
-            .input_format
-            .as_deref()
-            .map(|f| f.eq_ignore_ascii_case("jpeg") || f.eq_ignore_ascii_case("jpg"))
-            .unwrap_or(false);
-        if is_jpeg {
-            cmd.arg("--lossless_jpeg=0");
-        }
-    }
-
-    cmd.arg("--")
-        .arg(shared_utils::safe_path_arg(input).as_ref())
-        .arg(shared_utils::safe_path_arg(&temp_output).as_ref());
-
-    let result = cmd.output();
+    // --auto-grayscale: feed cjxl a single-channel copy of the image.
+    let gray_input = options
+        .grayscale
+        .then(|| shared_utils::jxl_utils::convert_to_gray_png(input))
+        .flatten();
+    let cjxl_input = gray_input
+        .as_ref()
+        .map_or(input, |(path, _)| path.as_path());
+
+    let result = Command::new("cjxl")
+        .args(matched_cjxl_args(
+            cjxl_input,
+            &temp_output,
+            distance,
+            analysis.hdr_info.as_ref(),
+            options,
+        ))
+        .output();
 
     match result {
         Ok(output_cmd) if output_cmd.status.success() => {
@@ -790,6 +892,7 @@ pub fn convert_to_jxl_matched(
                 options,
                 "Quality-matched JXL",
                 Some(extra),
+                false,
             )
         }
         Ok(output_cmd) => {
@@ -836,6 +939,187 @@ fn convert_to_temp_png(
     .map_err(ImgQualityError::IoError)
 }
 
+/// Still-image JXL encode a `--print-command` line is rendered for.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum JxlRoute {
+    /// [`convert_to_jxl`] at this distance
+    Distance(f32),
+    /// [`convert_jpeg_to_jxl`]
+    JpegTranscode,
+    /// [`convert_to_jxl_matched`]
+    Matched,
+}
+
+/// `--print-command`: the cjxl line `route` runs for `input`, without converting anything.
+/// Steps that write temporary files (a PNG decode, the grayscale copy, the extracted ICC
+/// profile) are `#` notes above it; the fallbacks taken when cjxl fails are not shown.
+pub fn jxl_command_preview(
+    input: &Path,
+    options: &ConvertOptions,
+    analysis: &crate::ImageAnalysis,
+    route: JxlRoute,
+) -> Result<Vec<String>> {
+    let input_size = fs::metadata(input)?.len();
+    let is_small_png = input
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("png"))
+        && input_size < crate::constants::SMALL_PNG_THRESHOLD_BYTES;
+    if matches!(route, JxlRoute::Distance(_)) && is_small_png {
+        return Ok(vec!["# skipped: small PNG (< 500KB)".to_string()]);
+    }
+    let output = get_output_path(input, "jxl", options)?;
+    if shared_utils::conversion::keeps_existing_output(
+        input,
+        &output,
+        options.force,
+        options.on_collision,
+    ) {
+        return Ok(vec![format!(
+            "# skipped: output exists ({})",
+            output.display()
+        )]);
+    }
+
+    let hdr_info = analysis.hdr_info.as_ref();
+    let (mut lines, args) = match route {
+        JxlRoute::Distance(distance) => {
+            let decoded = if shared_utils::needs_hdr_decode(hdr_info) {
+                Some("a 16-bit PNG decode (HDR)")
+            } else if matches!(
+                analysis.format.to_ascii_lowercase().as_str(),
+                "webp" | "tiff" | "bmp" | "heic" | "heif"
+            ) {
+                Some("a PNG decode")
+            } else {
+                None
+            };
+            let max_threads = if options.child_threads > 0 {
+                options.child_threads
+            } else {
+                shared_utils::thread_manager::get_optimal_threads()
+            };
+            (
+                shared_utils::jxl_utils::cjxl_preview_notes(
+                    decoded,
+                    options.grayscale,
+                    options.preserve_icc && !options.grayscale,
+                ),
+                cjxl_distance_args(
+                    input,
+                    &output,
+                    distance,
+                    max_threads,
+                    shared_utils::image_analyzer::read_bit_depth(input),
+                    hdr_info,
+                    options,
+                    None,
+                ),
+            )
+        }
+        JxlRoute::JpegTranscode => {
+            let mut notes = shared_utils::jxl_utils::cjxl_preview_notes(None, false, true);
+            if options.normalize_orientation {
+                notes.push(
+                    "# --normalize-orientation: a rotated JPEG is first turned upright with jpegtran"
+                        .to_string(),
+                );
+            }
+            (
+                notes,
+                jpeg_transcode_cjxl_args(input, &output, options, None, hdr_info, None),
+            )
+        }
+        JxlRoute::Matched => {
+            let distance = calculate_matched_distance_for_static(analysis, input_size)?;
+            (
+                shared_utils::jxl_utils::cjxl_preview_notes(None, options.grayscale, false),
+                matched_cjxl_args(input, &output, distance, hdr_info, options),
+            )
+        }
+    };
+    lines.push(shared_utils::shell_command_string("cjxl", &args));
+    Ok(lines)
+}
+
+/// cjxl arguments of the distance encode in [`convert_to_jxl`]: `cjxl_input` is the source or
+/// its preprocessed copy, `icc_path` the extracted source ICC profile.
+fn cjxl_distance_args(
+    cjxl_input: &Path,
+    output: &Path,
+    distance: f32,
+    max_threads: usize,
+    source_depth: Option<u8>,
+    hdr_info: Option<&shared_utils::ColorInfo>,
+    options: &ConvertOptions,
+    icc_path: Option<&Path>,
+) -> Vec<String> {
+    let mut args = vec![
+        "-d".to_string(),
+        format!("{:.2}", distance),
+        "-e".to_string(),
+        "7".to_string(),
+        "-j".to_string(),
+        max_threads.to_string(),
+    ];
+    args.extend(shared_utils::jxl_utils::cjxl_bit_depth_arg(source_depth));
+
+    if options.apple_compat {
+        args.push("--compress_boxes=0".to_string());
+    }
+    args.extend(options.strip.cjxl_args().iter().map(|s| s.to_string()));
+
+    // Add HDR metadata via CICP if available
+    if let Some(cicp) = hdr_info.and_then(shared_utils::color_info_to_cicp) {
+        args.push(format!("--cicp={}", cicp));
+    }
+
+    args.extend(shared_utils::jxl_utils::icc_cjxl_args(icc_path));
+    args.extend(shared_utils::jxl_utils::cjxl_io_args(cjxl_input, output));
+    args
+}
+
+/// cjxl arguments of the matched-distance encode in [`convert_to_jxl_matched`].
+fn matched_cjxl_args(
+    cjxl_input: &Path,
+    output: &Path,
+    distance: f32,
+    hdr_info: Option<&shared_utils::ColorInfo>,
+    options: &ConvertOptions,
+) -> Vec<String> {
+    let max_threads = shared_utils::thread_manager::get_optimal_threads();
+    let mut args = vec![
+        "-d".to_string(),
+        format!("{:.2}", distance),
+        "-e".to_string(),
+        "7".to_string(),
+        "-j".to_string(),
+        max_threads.to_string(),
+    ];
+
+    if options.apple_compat {
+        args.push("--compress_boxes=0".to_string());
+    }
+    args.extend(options.strip.cjxl_args().iter().map(|s| s.to_string()));
+
+    if let Some(cicp) = hdr_info.and_then(shared_utils::color_info_to_cicp) {
+        args.push(format!("--cicp={}", cicp));
+    }
+
+    // Only disable lossless JPEG mode when input is actually JPEG and we want lossy encoding.
+    if distance > 0.0 {
+        let is_jpeg = options
+            .input_format
+            .as_deref()
+            .map(|f| f.eq_ignore_ascii_case("jpeg") || f.eq_ignore_ascii_case("jpg"))
+            .unwrap_or(false);
+        if is_jpeg {
+            args.push("--lossless_jpeg=0".to_string());
+        }
+    }
+    args.extend(shared_utils::jxl_utils::cjxl_io_args(cjxl_input, output));
+    args
+}
+
 fn prepare_input_for_cjxl(
     input: &Path,
     options: &ConvertOptions,
@@ -915,7 +1199,9 @@ fn prepare_input_for_cjxl(
                     style("Corrupted JPEG header detected, using ImageMagick to sanitize").yellow()
                 );
 
-                let temp_png_file = tempfile::Builder::new().suffix(".png").tempfile()?;
+                let temp_png_file = tempfile::Builder::new()
+                    .suffix(".png")
+                    .tempfile_in(shared_utils::conversion::scratch_dir())?;
                 let temp_png = temp_png_file.path().to_path_buf();
 
                 let result = Command::new("magick")
@@ -957,13 +1243,20 @@ fn prepare_input_for_cjxl(
             "WebP detected, using dwebp for ICC profile compatibility",
         ),
 
-        "tiff" | "tif" => convert_to_temp_png(
-            input,
-            "magick",
-            &["--"],
-            &["-depth", "16", "__OUTPUT__"],
-            "TIFF detected, using ImageMagick for cjxl compatibility",
-        ),
+        "tiff" | "tif" => {
+            // 8-bit stays 8-bit; deeper sources (12/16-bit, float) are handed over as 16-bit.
+            let depth = match shared_utils::image_analyzer::read_bit_depth(input) {
+                Some(8) => "8",
+                _ => "16",
+            };
+            convert_to_temp_png(
+                input,
+                "magick",
+                &["--"],
+                &["-depth", depth, "__OUTPUT__"],
+                "TIFF detected, using ImageMagick for cjxl compatibility",
+            )
+        }
 
         "bmp" => convert_to_temp_png(
             input,
@@ -976,7 +1269,9 @@ fn prepare_input_for_cjxl(
         "heic" | "heif" => {
             eprintln!("   🔧 PRE-PROCESSING: HEIC/HEIF detected, using sips/ImageMagick for cjxl compatibility");
 
-            let temp_png_file = tempfile::Builder::new().suffix(".png").tempfile()?;
+            let temp_png_file = tempfile::Builder::new()
+                .suffix(".png")
+                .tempfile_in(shared_utils::conversion::scratch_dir())?;
             let temp_png = temp_png_file.path().to_path_buf();
 
             eprintln!("   🍎 Trying macOS sips first...");
@@ -1033,11 +1328,17 @@ fn get_output_path(
             base,
             extension,
             &options.output_dir,
+            options.on_collision,
         )
         .map_err(ImgQualityError::ConversionError)
     } else {
-        shared_utils::conversion::determine_output_path(input, extension, &options.output_dir)
-            .map_err(ImgQualityError::ConversionError)
+        shared_utils::conversion::determine_output_path(
+            input,
+            extension,
+            &options.output_dir,
+            options.on_collision,
+        )
+        .map_err(ImgQualityError::ConversionError)
     }
 }
 
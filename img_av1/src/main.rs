@@ -1,10 +1,16 @@
 use clap::{Parser, Subcommand};
-use img_av1::{calculate_psnr, calculate_ssim, psnr_quality_description, ssim_quality_description};
+use img_av1::lossless_converter::{jxl_command_preview, ConvertOptions, JxlRoute};
+use img_av1::{
+    calculate_animation_ms_ssim, calculate_ms_ssim_bounded, calculate_psnr, calculate_ssim,
+    calculate_ssim_dark_weighted, ms_ssim_quality_description, ms_ssim_working_size,
+    psnr_quality_description, ssim_quality_description,
+};
 use shared_utils::analysis_cache::AnalysisCache;
 use shared_utils::modern_ui::{colors, symbols};
 use shared_utils::{
-    check_dangerous_directory, disk_full_pause_reason, print_summary_report, BatchPauseController,
-    BatchResult,
+    check_dangerous_directory, disk_full_pause_reason, print_summary_report, BatchAbortController,
+    BatchPauseController, BatchResult, FailurePolicy, OverrideTarget, RoutingAction,
+    RoutingOverrides, SkipReason,
 };
 use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicUsize, Ordering};
@@ -30,7 +36,37 @@ struct AutoConvertConfig {
     base_dir: Option<PathBuf>,
     child_threads: usize,
     allow_size_tolerance: bool,
+    animated_target: AnimatedTarget,
+    failure_policy: FailurePolicy,
+    retry_policy: shared_utils::RetryPolicy,
+    jpeg_strategy: shared_utils::JpegStrategy,
+    metadata_only: shared_utils::MetadataOnlyFormats,
+    normalize_orientation: bool,
+    entropy_skip_threshold: f64,
     cache: Option<Arc<AnalysisCache>>,
+    overrides: Option<Arc<RoutingOverrides>>,
+    extension_filter: shared_utils::ExtensionFilter,
+    min_ssim: Option<f64>,
+    /// `--limit N`: only the first N pending files are converted.
+    limit: Option<usize>,
+    /// Encode images without chroma as single-channel grayscale JXL.
+    auto_grayscale: bool,
+    /// `--preserve-icc`: attach the source ICC profile to JXL output.
+    preserve_icc: bool,
+    /// `--strip-metadata` / `--strip-timestamps` for every converted output.
+    strip: shared_utils::StripPolicy,
+    /// `--on-collision`: what happens when an output path already exists.
+    on_collision: shared_utils::CollisionPolicy,
+    /// `--dedup`: contents claimed so far in this run.
+    dedup: Option<Arc<shared_utils::Deduper>>,
+    /// `--min-animation-duration`: duration cutoff for animated→video.
+    min_animation_duration: Option<f32>,
+    /// `--only-larger-than-source`: list outputs that did not shrink after the summary.
+    only_larger_than_source: bool,
+    /// `--sort-report-by`: per-file table after the summary (by name in verbose mode).
+    sort_report_by: Option<shared_utils::ReportSortKey>,
+    /// `--preserve-alpha`: transparent animations go to animated WebP instead of MP4.
+    preserve_alpha: bool,
 }
 
 #[derive(Parser)]
@@ -41,6 +77,17 @@ struct Cli {
     command: Commands,
 }
 
+/// Output target for animated sources.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+enum AnimatedTarget {
+    /// Meme-score routing (keep GIF or convert to video)
+    Auto,
+    /// Animated WebP via libwebp_anim, for browser delivery
+    Webp,
+    /// Lossless APNG for GIFs under 3s; longer animations use auto routing
+    Apng,
+}
+
 #[derive(Subcommand)]
 enum Commands {
     #[command(name = "run")]
@@ -90,7 +137,12 @@ enum Commands {
         #[arg(short, long)]
         verbose: bool,
 
-        #[arg(long, default_value_t = 0)]
+        /// Parallel files to process (0 = automatic)
+        #[arg(long, value_name = "N", default_value_t = 0)]
+        jobs: usize,
+
+        /// Threads per encoder process (0 = automatic)
+        #[arg(long, value_name = "N", default_value_t = 0)]
         child_threads: usize,
 
         #[arg(long, default_value_t = true)]
@@ -103,6 +155,10 @@ enum Commands {
         #[arg(long)]
         force_video: bool,
 
+        /// Output target for animated sources: auto (GIF/video routing), webp, or apng (short GIFs)
+        #[arg(long, value_enum, default_value_t = AnimatedTarget::Auto)]
+        target: AnimatedTarget,
+
         /// Resume from last run: skip files already in progress file (default).
         #[arg(long, default_value_t = true)]
         resume: bool,
@@ -110,12 +166,186 @@ enum Commands {
         /// Start fresh: ignore previous progress file, process all files.
         #[arg(long)]
         no_resume: bool,
+
+        /// Abort the whole batch on the first real conversion failure (non-zero exit).
+        #[arg(long, conflicts_with = "keep_going")]
+        fail_fast: bool,
+
+        /// Continue past conversion failures (default).
+        #[arg(long)]
+        keep_going: bool,
+
+        /// Ultimate mode: stop the per-file search after this many seconds (best-so-far result)
+        #[arg(long, value_name = "SECS")]
+        ultimate_max_seconds: Option<u64>,
+
+        /// Ultimate mode: stop the per-file search after this many encode iterations
+        #[arg(long, value_name = "N")]
+        ultimate_max_iterations: Option<u32>,
+
+        /// Only process files with these extensions in directory runs (comma-separated, e.g. png,gif)
+        #[arg(long, value_name = "EXT", value_delimiter = ',')]
+        include_ext: Vec<String>,
+
+        /// Skip files with these extensions in directory runs (comma-separated, e.g. gif)
+        #[arg(long, value_name = "EXT", value_delimiter = ',')]
+        exclude_ext: Vec<String>,
+
+        /// Minimum SSIM for animated→video conversion, in (0, 1] (default: derived from the matched CRF, about 0.95)
+        #[arg(long, value_name = "SSIM", value_parser = shared_utils::parse_min_ssim)]
+        min_ssim: Option<f64>,
+
+        /// XMP sidecars: embed into the output, copy beside it as <output-stem>.xmp, or both
+        #[arg(long, value_name = "MODE", default_value = "embed", value_parser = shared_utils::parse_sidecar_mode)]
+        xmp_mode: shared_utils::SidecarMode,
+        /// When a file has several sidecars or existing tags: prefer-sidecar, prefer-existing or merge-union (lists appended)
+        #[arg(long, value_name = "POLICY", default_value = "prefer-sidecar", value_parser = shared_utils::parse_xmp_merge_policy)]
+        xmp_merge_policy: shared_utils::XmpMergePolicy,
+        /// Sidecar extensions to merge, in precedence order, e.g. xmp,thm,yml (default: xmp)
+        #[arg(
+            long,
+            value_name = "EXTS",
+            value_delimiter = ',',
+            default_value = "xmp"
+        )]
+        sidecar_extensions: Vec<String>,
+        /// Live Photo pairs (HEIC + MOV with the same name): skip both halves, or convert both and keep them paired
+        #[arg(long, value_name = "MODE", default_value = "skip", value_parser = shared_utils::parse_live_photo_mode)]
+        live_photos: shared_utils::LivePhotoMode,
+
+        /// Output filename template, e.g. "{stem}_av1.{ext}" (placeholders: {stem} {ext} {codec} {crf} {parent})
+        #[arg(long, value_name = "TEMPLATE", value_parser = shared_utils::OutputTemplate::parse)]
+        output_template: Option<shared_utils::OutputTemplate>,
+
+        /// Retry a file up to N times when it fails with a recoverable error (busy GPU/device, EAGAIN)
+        #[arg(long, value_name = "N", default_value_t = 1)]
+        max_retries: u32,
+
+        /// JPEG→JXL route: lossless (bit-exact transcode), matched (lossy at matched distance), or auto (by chroma subsampling and estimated quality)
+        #[arg(long, value_name = "STRATEGY", default_value = "auto", value_parser = shared_utils::parse_jpeg_strategy)]
+        jpeg_strategy: shared_utils::JpegStrategy,
+
+        /// Never re-encode these extensions; copy them to --output with their XMP sidecar merged (comma-separated, e.g. jxl,heic; default under --apple-compat: jxl,heic,heif; "none" disables)
+        #[arg(long, value_name = "EXT", value_delimiter = ',')]
+        metadata_only_formats: Vec<String>,
+
+        /// Losslessly rotate JPEGs with EXIF orientation ≠ 1 upright (jpegtran -perfect) before the JXL transcode, and reset the tag
+        #[arg(long)]
+        normalize_orientation: bool,
+
+        /// Skip lossless→JXL for PNGs whose luma entropy (bits, max 8) is at least this and that already store ≥85% of their raw pixel size
+        #[arg(long, value_name = "BITS", default_value_t = shared_utils::image_analyzer::DEFAULT_ENTROPY_SKIP_THRESHOLD, value_parser = shared_utils::parse_entropy_threshold)]
+        entropy_skip_threshold: f64,
+
+        /// Process only the first N pending files in queue order (files finished by --resume do not count)
+        #[arg(long, value_name = "N", value_parser = clap::value_parser!(u32).range(1..))]
+        limit: Option<u32>,
+
+        /// Encode black-and-white images stored as RGB as grayscale JXL (default)
+        #[arg(long, default_value_t = true)]
+        auto_grayscale: bool,
+
+        /// Keep the color container for images without chroma
+        #[arg(long)]
+        no_auto_grayscale: bool,
+
+        /// Carry the source's ICC profile (Display P3, Adobe RGB, …) into JXL output (default)
+        #[arg(long, default_value_t = true)]
+        preserve_icc: bool,
+
+        /// Do not re-attach the source ICC profile; wide-gamut sources are warned about
+        #[arg(long)]
+        no_preserve_icc: bool,
+
+        /// Create scratch files (decoded frames, intermediate streams) here instead of the system temp dir
+        #[arg(long, value_name = "PATH")]
+        temp_dir: Option<PathBuf>,
+
+        /// Drop EXIF/GPS/XMP from outputs instead of preserving them (file timestamps are still kept)
+        #[arg(long)]
+        strip_metadata: bool,
+
+        /// With --strip-metadata: also leave output file timestamps at conversion time
+        #[arg(long, requires = "strip_metadata")]
+        strip_timestamps: bool,
+
+        /// Convert animations at least SECS long to video and keep shorter ones, instead of the GIF meme-score (very short loops may not compress well)
+        #[arg(long, value_name = "SECS", value_parser = shared_utils::parse_min_animation_duration)]
+        min_animation_duration: Option<f32>,
+
+        /// After the summary, list every output not smaller than its source (kept under --allow-size-tolerance) with the total bytes wasted
+        #[arg(long)]
+        only_larger_than_source: bool,
+
+        /// Order of the per-file table printed after the summary: size-reduction (least saved first), original-size (largest first), ssim (lowest first; images have none) or name; the table is also shown, by name, in verbose mode
+        #[arg(long, value_name = "KEY", value_parser = shared_utils::ReportSortKey::parse)]
+        sort_report_by: Option<shared_utils::ReportSortKey>,
+
+        /// Descend into symlinked directories and include symlinked files; each canonical path is visited once, so loops and symlink farms are not double-processed
+        #[arg(long)]
+        follow_symlinks: bool,
+
+        /// Ignore symlinks while collecting files (default)
+        #[arg(long)]
+        no_follow_symlinks: bool,
+
+        /// Append every converted output to this tar (.tar, .tar.gz/.tgz, or .tar.zst via the zstd tool); the file must not exist yet
+        #[arg(long, value_name = "PATH")]
+        archive: Option<PathBuf>,
+
+        /// With --archive: delete each loose output once it is in the archive
+        #[arg(long, requires = "archive")]
+        archive_delete_outputs: bool,
+
+        /// Skip a file only while its existing output is at least as new as it (outputs keep the source mtime); edited sources are converted again, overwriting the stale output
+        #[arg(long, conflicts_with = "force")]
+        skip_up_to_date: bool,
+
+        /// Embed a JSON note (tool, version, source codec, CRF, SSIM, date) in each output: the container comment for video, EXIF/XMP UserComment for images
+        #[arg(long)]
+        provenance: bool,
+
+        /// Put every output directly in --output instead of recreating the source folders; sources from subfolders get a hash of their folder appended (name-a1b2c3d4.jxl) so same-named files cannot collide
+        #[arg(long, requires = "output")]
+        flatten_output: bool,
+
+        /// AV1 encoder: svt (SVT-AV1, default) or aom (libaom, slower); falls back to the other when this ffmpeg lacks it
+        #[arg(long, value_name = "ENCODER", default_value = "svt", value_parser = shared_utils::Av1Backend::parse)]
+        av1_encoder: shared_utils::Av1Backend,
+
+        /// After the batch, re-open every output (decode, at least one frame); delete any that fail and keep their originals (--delete-original waits for this check)
+        #[arg(long, conflicts_with = "archive")]
+        verify_after: bool,
+
+        /// Keep transparency of animations that would become MP4 (which has no alpha): transparent ones are written as animated WebP instead; without it a warning is printed
+        #[arg(long)]
+        preserve_alpha: bool,
+
+        /// What to do when an output already exists: skip (default; with --skip-up-to-date only while current), overwrite, rename (write "name (1).ext" next to it) or error; --force still overwrites
+        #[arg(long, value_name = "POLICY", default_value = "skip", value_parser = shared_utils::CollisionPolicy::parse, conflicts_with = "force")]
+        on_collision: shared_utils::CollisionPolicy,
+
+        /// Leave skipped and failed files out of --output instead of copying them through, so it holds converted files only rather than a complete mirror of the input (matters for sync tools that mirror the output dir)
+        #[arg(long)]
+        no_copy_skipped: bool,
+
+        /// Skip files whose content matches one already converted in this run (perceptual hash of the image, or of frames sampled across a video), copying them through instead; the summary reports how many were skipped
+        #[arg(long)]
+        dedup: bool,
+
+        /// Print the cjxl command of each still image's JXL encode without converting; skipped and animated files get a `#` comment
+        #[arg(long)]
+        print_command: bool,
     },
 
     Verify {
         original: PathBuf,
 
         converted: PathBuf,
+
+        /// Also report SSIM weighted toward dark regions, where shadow banding hides in plain SSIM
+        #[arg(long)]
+        perceptual_ssim: bool,
     },
 
     RestoreTimestamps {
@@ -126,8 +356,20 @@ enum Commands {
         output: PathBuf,
     },
 
+    /// Re-apply metadata (EXIF, xattr, timestamps, ACL, XMP sidecar) from SOURCE onto DEST without re-encoding
+    CopyMetadata {
+        #[arg(value_name = "SOURCE")]
+        source: PathBuf,
+
+        #[arg(value_name = "DEST")]
+        dest: PathBuf,
+    },
+
     /// Display cache statistics
     CacheStats,
+
+    /// Check tools, ffmpeg encoders, GPU and CPU before a run (exits 1 if something required is missing)
+    Doctor,
 }
 
 fn main() -> anyhow::Result<()> {
@@ -170,16 +412,100 @@ fn main() -> anyhow::Result<()> {
             cpu,
             base_dir,
             verbose,
+            jobs,
             child_threads,
             allow_size_tolerance,
             no_allow_size_tolerance,
             force_video,
+            target,
             resume: resume_flag,
             no_resume,
+            fail_fast,
+            keep_going,
+            ultimate_max_seconds,
+            ultimate_max_iterations,
+            include_ext,
+            exclude_ext,
+            min_ssim,
+            xmp_mode,
+            xmp_merge_policy,
+            sidecar_extensions,
+            live_photos,
+            output_template,
+            max_retries,
+            jpeg_strategy,
+            metadata_only_formats,
+            normalize_orientation,
+            entropy_skip_threshold,
+            limit,
+            auto_grayscale,
+            no_auto_grayscale,
+            preserve_icc,
+            no_preserve_icc,
+            temp_dir,
+            strip_metadata,
+            strip_timestamps,
+            min_animation_duration,
+            only_larger_than_source,
+            sort_report_by,
+            follow_symlinks,
+            no_follow_symlinks,
+            archive,
+            archive_delete_outputs,
+            skip_up_to_date,
+            provenance,
+            flatten_output,
+            av1_encoder,
+            verify_after,
+            preserve_alpha,
+            on_collision,
+            no_copy_skipped,
+            dedup,
+            print_command,
         } => {
+            if let Some(dir) = &temp_dir {
+                if let Err(e) = shared_utils::conversion::validate_temp_dir(dir) {
+                    eprintln!("Invalid --temp-dir: {}", e);
+                    std::process::exit(1);
+                }
+                shared_utils::conversion::set_temp_dir(dir.clone());
+            }
+            shared_utils::set_sidecar_mode(xmp_mode);
+            shared_utils::set_xmp_merge_policy(xmp_merge_policy);
+            shared_utils::set_live_photo_mode(live_photos);
+            match shared_utils::parse_sidecar_extensions(&sidecar_extensions.join(",")) {
+                Ok(extensions) => shared_utils::set_sidecar_extensions(extensions),
+                Err(e) => {
+                    eprintln!("Invalid --sidecar-extensions: {}", e);
+                    std::process::exit(1);
+                }
+            }
+            shared_utils::set_follow_symlinks(follow_symlinks && !no_follow_symlinks);
+            shared_utils::set_skip_up_to_date(skip_up_to_date);
+            shared_utils::set_provenance(provenance);
+            shared_utils::conversion::set_flatten_output(flatten_output);
+            shared_utils::set_av1_backend(av1_encoder);
+            shared_utils::set_verify_after(verify_after);
+            shared_utils::set_copy_skipped(!no_copy_skipped);
+            if let Some(template) = output_template {
+                shared_utils::set_output_template(template.with_video_codec("av1"));
+            }
+            shared_utils::set_global_ultimate_budget(shared_utils::UltimateBudget {
+                max_seconds: ultimate_max_seconds,
+                max_iterations: ultimate_max_iterations,
+            });
+            shared_utils::thread_manager::apply_cli_thread_overrides(
+                jobs,
+                child_threads,
+                shared_utils::thread_manager::WorkloadType::Image,
+            );
             let resume = resume_flag && !no_resume;
             let apple_compat = apple_compat && !no_apple_compat;
+            let metadata_only =
+                shared_utils::MetadataOnlyFormats::resolve(&metadata_only_formats, apple_compat);
             let allow_size_tolerance = allow_size_tolerance && !no_allow_size_tolerance;
+            let auto_grayscale = auto_grayscale && !no_auto_grayscale;
+            let preserve_icc = preserve_icc && !no_preserve_icc;
             let should_delete = delete_original || in_place;
 
             let flag_mode = match shared_utils::validate_flags_result_with_ultimate(
@@ -221,6 +547,15 @@ fn main() -> anyhow::Result<()> {
                 ));
                 std::env::set_var("MODERN_FORMAT_BOOST_APPLE_COMPAT", "1");
             }
+            if !metadata_only.is_empty() {
+                shared_utils::progress_mode::emit_stderr(&format!(
+                    "{} Metadata-only: {}{}{} (copied through, XMP sidecar merged)",
+                    symbols::SHIELD,
+                    colors::BOLD,
+                    metadata_only.describe(),
+                    colors::RESET
+                ));
+            }
             if force_video {
                 shared_utils::progress_mode::emit_stderr(&format!(
                     "{} Force Video: {}ENABLED{} (skip meme-score)",
@@ -230,6 +565,23 @@ fn main() -> anyhow::Result<()> {
                 ));
                 std::env::set_var("MODERN_FORMAT_BOOST_FORCE_VIDEO", "1");
             }
+            if target == AnimatedTarget::Webp {
+                shared_utils::progress_mode::emit_stderr(&format!(
+                    "{} Animated Target: {}WebP{} (libwebp_anim, skip meme-score)",
+                    symbols::IMAGE,
+                    colors::BOLD,
+                    colors::RESET
+                ));
+            }
+            if target == AnimatedTarget::Apng {
+                shared_utils::progress_mode::emit_stderr(&format!(
+                    "{} Animated Target: {}APNG{} (lossless, GIFs under {:.0}s)",
+                    symbols::IMAGE,
+                    colors::BOLD,
+                    colors::RESET,
+                    shared_utils::animated_apng::ANIMATION_DURATION_THRESHOLD_SECS
+                ));
+            }
             if in_place {
                 shared_utils::progress_mode::emit_stderr(&format!(
                     "{} In-place mode: {}ENABLED{} (auto-delete original)",
@@ -254,8 +606,35 @@ fn main() -> anyhow::Result<()> {
                     colors::RESET
                 ));
             }
+            if !auto_grayscale {
+                shared_utils::progress_mode::emit_stderr(&format!(
+                    "{} Auto Grayscale: {}DISABLED{} (keep color container)",
+                    symbols::IMAGE,
+                    colors::BOLD,
+                    colors::RESET
+                ));
+            }
+            if !preserve_icc {
+                shared_utils::progress_mode::emit_stderr(&format!(
+                    "{} ICC Preservation: {}DISABLED{} (wide-gamut sources may clip to sRGB)",
+                    symbols::IMAGE,
+                    colors::BOLD,
+                    colors::RESET
+                ));
+            }
+            let av1_backend = shared_utils::av1_backend();
+            if av1_backend != shared_utils::Av1Backend::Svt {
+                shared_utils::log_eprintln!(
+                    "🎞️  AV1 encoder: {} ({})",
+                    av1_backend.label(),
+                    av1_backend.ffmpeg_name()
+                );
+            }
             if cpu {
-                shared_utils::log_eprintln!("🖥️  CPU Encoding: ENABLED (libaom for maximum SSIM)");
+                shared_utils::log_eprintln!(
+                    "🖥️  CPU Encoding: ENABLED ({} for maximum SSIM)",
+                    av1_backend.ffmpeg_name()
+                );
             }
 
             let workload = if input.is_dir() {
@@ -285,13 +664,77 @@ fn main() -> anyhow::Result<()> {
                     thread_config.child_threads
                 },
                 allow_size_tolerance,
+                animated_target: target,
+                failure_policy: FailurePolicy::from_flags(fail_fast, keep_going),
+                retry_policy: shared_utils::RetryPolicy::new(max_retries),
+                jpeg_strategy,
+                metadata_only,
+                normalize_orientation,
+                entropy_skip_threshold,
                 cache: cache.clone(),
+                overrides: None,
+                extension_filter: shared_utils::ExtensionFilter::new(
+                    &include_ext,
+                    &exclude_ext,
+                    shared_utils::SUPPORTED_IMAGE_EXTENSIONS,
+                ),
+                min_ssim,
+                limit: limit.map(|n| n as usize),
+                auto_grayscale,
+                preserve_icc,
+                strip: shared_utils::StripPolicy {
+                    metadata: strip_metadata,
+                    timestamps: strip_timestamps,
+                },
+                on_collision,
+                dedup: dedup.then(|| Arc::new(shared_utils::Deduper::new())),
+                min_animation_duration,
+                only_larger_than_source,
+                sort_report_by,
+                preserve_alpha,
             };
 
-            if input.is_file() {
-                auto_convert_single_file(&input, &config)?;
+            if print_command {
+                let files = if input.is_dir() {
+                    config.extension_filter.apply(
+                        shared_utils::collect_image_files_for_perceived_speed(
+                            &input,
+                            shared_utils::SUPPORTED_IMAGE_EXTENSIONS,
+                            config.recursive,
+                        ),
+                    )
+                } else {
+                    vec![input.clone()]
+                };
+                shared_utils::cli_runner::print_commands(&files, |file| {
+                    print_command_lines(file, &config)
+                });
+                shared_utils::progress_mode::flush_log_file();
+                return Ok(());
+            }
+
+            if let Some(ref path) = archive {
+                match shared_utils::OutputArchive::create(
+                    path,
+                    output.as_deref().unwrap_or(&input),
+                    archive_delete_outputs,
+                ) {
+                    Ok(archive) => shared_utils::set_output_archive(archive),
+                    Err(e) => {
+                        eprintln!("❌ Cannot create --archive: {}", e);
+                        std::process::exit(1);
+                    }
+                }
+            }
+            let outcome = if input.is_file() {
+                auto_convert_with_retries(&input, &config).map(|result| {
+                    if !result.skipped && !result.output_path.is_empty() {
+                        shared_utils::archive_output(Path::new(&result.output_path));
+                        shared_utils::record_verify_output(&input, Path::new(&result.output_path));
+                    }
+                })
             } else if input.is_dir() {
-                auto_convert_directory(&input, &config, resume)?;
+                auto_convert_directory(&input, &config, resume)
             } else {
                 shared_utils::log_eprintln!(
                     "❌ {}: {}",
@@ -299,14 +742,28 @@ fn main() -> anyhow::Result<()> {
                     input.display()
                 );
                 std::process::exit(1);
+            };
+            shared_utils::finish_output_archive_with_log();
+            if input.is_file() {
+                if let Some(report) = shared_utils::finish_verify_after_with_log() {
+                    if let Some((path, reason)) = report.reverted.first() {
+                        anyhow::bail!("❌ Verify-after reverted {}: {}", path.display(), reason);
+                    }
+                }
             }
+            outcome?;
         }
 
         Commands::Verify {
             original,
             converted,
+            perceptual_ssim,
         } => {
-            verify_conversion(&original, &converted, cache.as_deref())?;
+            verify_conversion(&original, &converted, perceptual_ssim, cache.as_deref())?;
+        }
+
+        Commands::CopyMetadata { source, dest } => {
+            shared_utils::cli_runner::run_copy_metadata_command(&source, &dest)?;
         }
 
         Commands::RestoreTimestamps { source, output } => {
@@ -321,6 +778,17 @@ fn main() -> anyhow::Result<()> {
             }
         }
 
+        Commands::Doctor => {
+            let report = shared_utils::run_doctor(
+                shared_utils::thread_manager::WorkloadType::Image,
+                &[shared_utils::av1_backend().ffmpeg_name()],
+            );
+            report.print();
+            if !report.is_ready() {
+                std::process::exit(1);
+            }
+        }
+
         Commands::CacheStats => {
             if let Some(cache) = cache {
                 match cache.get_statistics() {
@@ -395,6 +863,7 @@ fn main() -> anyhow::Result<()> {
 fn verify_conversion(
     original: &Path,
     converted: &Path,
+    perceptual_ssim: bool,
     cache: Option<&AnalysisCache>,
 ) -> anyhow::Result<()> {
     println!("🔍 Verifying conversion quality...");
@@ -441,6 +910,48 @@ fn verify_conversion(
     if let Some(ssim) = calculate_ssim(&orig_img, &conv_img) {
         println!("   SSIM: {:.6} ({})", ssim, ssim_quality_description(ssim));
     }
+    if perceptual_ssim {
+        if let Some(ssim) = calculate_ssim_dark_weighted(&orig_img, &conv_img) {
+            println!(
+                "   SSIM (dark-weighted): {:.6} ({})",
+                ssim,
+                ssim_quality_description(ssim)
+            );
+        }
+    }
+
+    if original_analysis.is_animated {
+        // Animations are scored frame by frame, sampled like video MS-SSIM.
+        let strategy = shared_utils::SamplingStrategy::from_duration(
+            original_analysis.duration_secs.unwrap_or(0.0) as f64,
+        );
+        match calculate_animation_ms_ssim(original, converted, strategy) {
+            Some((ms_ssim, frame_count)) => println!(
+                "   MS-SSIM: {:.6} ({}, {} frames, {})",
+                ms_ssim,
+                ms_ssim_quality_description(ms_ssim),
+                frame_count,
+                strategy.accuracy_description()
+            ),
+            None => println!(
+                "   MS-SSIM: skipped (animation frames not decodable, or too long to sample)"
+            ),
+        }
+    } else if let Some(ms_ssim) = calculate_ms_ssim_bounded(&orig_img, &conv_img) {
+        let (w, h) = (orig_img.width(), orig_img.height());
+        let scored_at = ms_ssim_working_size(w, h);
+        let note = if scored_at != (w, h) {
+            format!(", scored at {}x{}", scored_at.0, scored_at.1)
+        } else {
+            String::new()
+        };
+        println!(
+            "   MS-SSIM: {:.6} ({}{})",
+            ms_ssim,
+            ms_ssim_quality_description(ms_ssim),
+            note
+        );
+    }
 
     println!("\n✅ Verification complete");
 
@@ -458,7 +969,7 @@ fn load_image_safe(path: &Path) -> anyhow::Result<image::DynamicImage> {
 
         let temp_png_file = tempfile::Builder::new()
             .suffix(".png")
-            .tempfile()
+            .tempfile_in(shared_utils::conversion::scratch_dir())
             .map_err(|e| anyhow::anyhow!("Failed to create temp file: {}", e))?;
 
         let temp_path = temp_png_file.path();
@@ -502,7 +1013,142 @@ fn convert_result_to_output(result: shared_utils::ConversionResult) -> Conversio
         original_size: result.input_size,
         output_size: result.output_size,
         size_reduction: result.size_reduction.map(|r| r as f32),
+        skip_reason: result
+            .skipped
+            .then(|| SkipReason::from_code(result.skip_reason.as_deref().unwrap_or_default())),
+    }
+}
+
+/// Per-file [`ConvertOptions`] of `input`: `config` plus what the pixel-level analysis of a
+/// still finds (content type for the quality label, grayscale).
+fn convert_options(
+    input: &Path,
+    config: &AutoConvertConfig,
+    analysis: &img_av1::ImageAnalysis,
+) -> ConvertOptions {
+    // 完整接入图像质量分析：静态图始终做像素级分析，用于路由 + 质量输出（自动写入 run log）
+    // 针对性：JPEG 这种已经明确走 lossless transcode 到 JXL 的不需要开启昂贵的像素级分析
+    let pixel_analysis = if !analysis.is_animated && analysis.format != "JPEG" {
+        shared_utils::image_quality_detector::analyze_image_quality_with_cache(
+            input,
+            config.cache.as_deref(),
+        )
+    } else {
+        None
+    };
+    if let Some(ref q) = pixel_analysis {
+        shared_utils::log_media_info_for_image_quality(q, input);
+    }
+
+    let mut quality_label = analysis.quality_summary();
+    if let Some(ref pa) = pixel_analysis {
+        let ct_str = pa.content_type.name.to_uppercase();
+        quality_label = if quality_label.is_empty() {
+            ct_str
+        } else {
+            format!("{}: {}", ct_str, quality_label)
+        };
+    }
+
+    // HDR stills keep their color pipeline (CICP, 16-bit decode).
+    let grayscale = config.auto_grayscale
+        && pixel_analysis.as_ref().is_some_and(|pa| pa.is_grayscale)
+        && !analysis.hdr_info.as_ref().is_some_and(|hdr| hdr.is_hdr());
+
+    ConvertOptions {
+        force: config.force,
+        output_dir: config.output_dir.clone(),
+        base_dir: config.base_dir.clone(),
+        delete_original: config.delete_original,
+        in_place: config.in_place,
+        explore: config.explore,
+        match_quality: config.match_quality,
+        compress: config.compress,
+        apple_compat: config.apple_compat,
+        use_gpu: config.use_gpu,
+        ultimate: config.ultimate,
+        allow_size_tolerance: config.allow_size_tolerance,
+        verbose: config.verbose,
+        child_threads: config.child_threads,
+        input_format: Some(analysis.format.clone()),
+        quality_label: Some(quality_label),
+        min_ssim: config.min_ssim,
+        normalize_orientation: config.normalize_orientation,
+        grayscale,
+        min_animation_duration: config.min_animation_duration,
+        preserve_icc: config.preserve_icc,
+        strip: config.strip,
+        on_collision: config.on_collision,
+    }
+}
+
+/// `--print-command`: the cjxl command `input` gets on the still-image JXL route, or a `#`
+/// comment for files that are skipped, copied through or animated (routed at run time).
+fn print_command_lines(input: &Path, config: &AutoConvertConfig) -> anyhow::Result<Vec<String>> {
+    if config.metadata_only.matches(input) {
+        return Ok(vec![
+            "# metadata-only: copied through, not encoded".to_string()
+        ]);
+    }
+    let analysis =
+        shared_utils::image_analyzer::analyze_image_with_cache(input, config.cache.as_deref())?;
+    if analysis.is_animated {
+        return Ok(vec![format!(
+            "# animated {}: routed to video, GIF, WebP or APNG at run time, not previewed",
+            analysis.format
+        )]);
     }
+    if analysis.format.eq_ignore_ascii_case("JXL") {
+        return Ok(vec!["# skipped: already JPEG XL".to_string()]);
+    }
+    let skip =
+        shared_utils::should_skip_image_format(analysis.format.as_str(), analysis.is_lossless);
+    if skip.should_skip {
+        return Ok(vec![format!("# skipped: {}", skip.reason)]);
+    }
+
+    let route = match (analysis.format.as_str(), analysis.is_lossless) {
+        ("JPEG", _)
+            if config
+                .jpeg_strategy
+                .prefers_lossless(analysis.jpeg_analysis.as_ref()) =>
+        {
+            JxlRoute::JpegTranscode
+        }
+        ("JPEG", _) => JxlRoute::Matched,
+        ("PNG", true)
+            if analysis
+                .features
+                .is_near_incompressible(config.entropy_skip_threshold) =>
+        {
+            return Ok(vec![
+                "# skipped: near-incompressible PNG, lossless JXL would not shrink it".to_string(),
+            ]);
+        }
+        (_, true) => JxlRoute::Distance(0.0),
+        (_, false) if config.match_quality => JxlRoute::Matched,
+        (_, false) => JxlRoute::Distance(0.1),
+    };
+    let options = convert_options(input, config, &analysis);
+    jxl_command_preview(input, &options, &analysis, route).map_err(|e| anyhow::anyhow!(e))
+}
+
+/// `auto_convert_single_file` under `--max-retries`: recoverable errors are retried.
+fn auto_convert_with_retries(
+    input: &Path,
+    config: &AutoConvertConfig,
+) -> anyhow::Result<ConversionOutput> {
+    let outcome = config.retry_policy.run(
+        input,
+        || auto_convert_single_file(input, config),
+        |outcome| outcome.as_ref().err().map(|e| format!("{:#}", e)),
+    );
+    if outcome.is_err() {
+        if let Some(dedup) = &config.dedup {
+            dedup.release_claim(input);
+        }
+    }
+    outcome
 }
 
 fn auto_convert_single_file(
@@ -510,8 +1156,8 @@ fn auto_convert_single_file(
     config: &AutoConvertConfig,
 ) -> anyhow::Result<ConversionOutput> {
     use img_av1::lossless_converter::{
-        convert_jpeg_to_jxl, convert_to_av1_mp4, convert_to_av1_mp4_matched, convert_to_jxl,
-        convert_to_jxl_matched, ConvertOptions,
+        convert_jpeg_to_jxl, convert_to_animated_webp, convert_to_apng, convert_to_av1_mp4,
+        convert_to_av1_mp4_matched, convert_to_jxl, convert_to_jxl_matched,
     };
 
     // Pause if the user is being prompted to exit via Ctrl+C
@@ -523,12 +1169,69 @@ fn auto_convert_single_file(
         std::process::exit(1);
     }
 
+    let override_action = config
+        .overrides
+        .as_deref()
+        .and_then(|overrides| overrides.lookup(input));
+
+    // Explicit override beats every automatic routing decision below; a
+    // force_skip leaves the file untouched (no extension fix-up either).
+    if override_action == Some(RoutingAction::ForceSkip) {
+        let reason = "Skipped by .mfb_overrides (force_skip)";
+        shared_utils::progress_mode::image_skipped(reason);
+        let file_size = std::fs::metadata(input).map(|m| m.len()).unwrap_or(0);
+        // Copied by design, so `--no-copy-skipped` does not apply.
+        shared_utils::copy_through(
+            input,
+            config.output_dir.as_deref(),
+            config.base_dir.as_deref(),
+            config.verbose,
+        )?;
+        return Ok(ConversionOutput {
+            original_path: input.display().to_string(),
+            output_path: input.display().to_string(),
+            skipped: true,
+            message: reason.to_string(),
+            original_size: file_size,
+            output_size: None,
+            size_reduction: None,
+            skip_reason: Some(SkipReason::UserOverride),
+        });
+    }
+
     let fixed_input = shared_utils::fix_extension_if_mismatch(input)?;
     let input = fixed_input.as_path();
+    // Analysis, routing and the encode all probe the input; ffprobe runs once per file.
+    let _probe_cache =
+        shared_utils::file_probe_cache::install(shared_utils::FileProbeCache::new(input));
+
+    let forced = override_action.is_some_and(|a| a.forces_conversion());
+    let animated_target = match override_action {
+        Some(RoutingAction::Target(OverrideTarget::Webp)) => AnimatedTarget::Webp,
+        Some(RoutingAction::Target(OverrideTarget::Hevc)) => {
+            shared_utils::log_eprintln!(
+                "⚠️ [Overrides] target=hevc is not supported by img-av1; converting with the default AV1 route"
+            );
+            config.animated_target
+        }
+        _ => config.animated_target,
+    };
+    if let Some(action) = override_action {
+        if config.verbose {
+            println!("📋 Override {}: {}", action, input.display());
+        }
+    }
+
+    // Live Photo stills follow --live-photos so the pair matches what the video tools do
+    // with the MOV half: skipped together, or converted together (still → JXL).
+    let live_photo_convert = !forced
+        && shared_utils::live_photo_mode() == shared_utils::LivePhotoMode::Convert
+        && shared_utils::is_live_photo(input);
+    let forced = forced || live_photo_convert;
 
     // Always skip HEIC/HEIF: Lossless is extremely rare, and re-encoding lossy HEIC causes generational loss.
     // Apple ecosystem also heavily relies on original HEIC/HEIF files.
-    if shared_utils::image_heic_analysis::is_heic_file(input) {
+    if !forced && shared_utils::image_heic_analysis::is_heic_file(input) {
         let file_size = std::fs::metadata(input).map(|m| m.len()).unwrap_or(0);
         copy_original_if_adjacent_mode(input, config)?;
         return Ok(ConversionOutput {
@@ -539,6 +1242,46 @@ fn auto_convert_single_file(
             original_size: file_size,
             output_size: None,
             size_reduction: None,
+            skip_reason: Some(SkipReason::ModernLossy),
+        });
+    }
+
+    // --metadata-only-formats: no routing at all, only the copy-through with sidecar merge.
+    if !forced && config.metadata_only.matches(input) {
+        let reason = "Metadata-only format, copied through without re-encoding";
+        shared_utils::progress_mode::image_skipped(reason);
+        let file_size = std::fs::metadata(input).map(|m| m.len()).unwrap_or(0);
+        copy_original_if_adjacent_mode(input, config)?;
+        return Ok(ConversionOutput {
+            original_path: input.display().to_string(),
+            output_path: input.display().to_string(),
+            skipped: true,
+            message: reason.to_string(),
+            original_size: file_size,
+            output_size: None,
+            size_reduction: None,
+            skip_reason: Some(SkipReason::MetadataOnly),
+        });
+    }
+
+    if let Some(first) = config
+        .dedup
+        .as_ref()
+        .and_then(|dedup| dedup.check_duplicate(input))
+    {
+        let reason = format!("Duplicate of {}, copied through", first.display());
+        shared_utils::progress_mode::image_skipped(&reason);
+        let file_size = std::fs::metadata(input).map(|m| m.len()).unwrap_or(0);
+        copy_original_if_adjacent_mode(input, config)?;
+        return Ok(ConversionOutput {
+            original_path: input.display().to_string(),
+            output_path: input.display().to_string(),
+            skipped: true,
+            message: reason,
+            original_size: file_size,
+            output_size: None,
+            size_reduction: None,
+            skip_reason: Some(SkipReason::DuplicateContent),
         });
     }
 
@@ -546,7 +1289,7 @@ fn auto_convert_single_file(
         shared_utils::image_analyzer::analyze_image_with_cache(input, config.cache.as_deref())?;
 
     // Single source of truth for static skip: JXL + modern lossy (avoid generational loss).
-    if !analysis.is_animated {
+    if !analysis.is_animated && !forced {
         let skip =
             shared_utils::should_skip_image_format(analysis.format.as_str(), analysis.is_lossless);
         if skip.should_skip {
@@ -562,52 +1305,12 @@ fn auto_convert_single_file(
                 original_size: analysis.file_size,
                 output_size: None,
                 size_reduction: None,
+                skip_reason: skip.kind,
             });
         }
     }
 
-    // 完整接入图像质量分析：静态图始终做像素级分析，用于路由 + 质量输出（自动写入 run log）
-    // 针对性：JPEG 这种已经明确走 lossless transcode 到 JXL 的不需要开启昂贵的像素级分析
-    let pixel_analysis = if !analysis.is_animated && analysis.format != "JPEG" {
-        shared_utils::image_quality_detector::analyze_image_quality_with_cache(
-            input,
-            config.cache.as_deref(),
-        )
-    } else {
-        None
-    };
-    if let Some(ref q) = pixel_analysis {
-        shared_utils::log_media_info_for_image_quality(q, input);
-    }
-
-    let mut quality_label = analysis.quality_summary();
-    if let Some(ref pa) = pixel_analysis {
-        let ct_str = pa.content_type.name.to_uppercase();
-        quality_label = if quality_label.is_empty() {
-            ct_str
-        } else {
-            format!("{}: {}", ct_str, quality_label)
-        };
-    }
-
-    let options = ConvertOptions {
-        force: config.force,
-        output_dir: config.output_dir.clone(),
-        base_dir: config.base_dir.clone(),
-        delete_original: config.delete_original,
-        in_place: config.in_place,
-        explore: config.explore,
-        match_quality: config.match_quality,
-        compress: config.compress,
-        apple_compat: config.apple_compat,
-        use_gpu: config.use_gpu,
-        ultimate: config.ultimate,
-        allow_size_tolerance: config.allow_size_tolerance,
-        verbose: config.verbose,
-        child_threads: config.child_threads,
-        input_format: Some(analysis.format.clone()),
-        quality_label: Some(quality_label),
-    };
+    let options = convert_options(input, config, &analysis);
 
     macro_rules! verbose_log {
         ($($arg:tt)*) => {
@@ -617,7 +1320,7 @@ fn auto_convert_single_file(
         };
     }
 
-    let make_skipped = |msg: &str| -> ConversionOutput {
+    let make_skipped = |msg: &str, reason: SkipReason| -> ConversionOutput {
         ConversionOutput {
             original_path: input.display().to_string(),
             output_path: input.display().to_string(),
@@ -626,6 +1329,7 @@ fn auto_convert_single_file(
             original_size: analysis.file_size,
             output_size: None,
             size_reduction: None,
+            skip_reason: Some(reason),
         }
     };
 
@@ -644,7 +1348,10 @@ fn auto_convert_single_file(
         }
         // Static modern lossy / JXL already handled by should_skip_image_format above.
         ("JPEG", _, false) => {
-            if config.match_quality {
+            if !config
+                .jpeg_strategy
+                .prefers_lossless(analysis.jpeg_analysis.as_ref())
+            {
                 verbose_log!("🔄 JPEG→JXL (MATCH QUALITY): {}", input.display());
                 convert_to_jxl_matched(input, &options, &analysis)?
             } else {
@@ -653,6 +1360,25 @@ fn auto_convert_single_file(
             }
         }
         (_, true, false) => {
+            // Noise-like PNGs: predict the size-check discard instead of paying for the encode.
+            if !forced
+                && analysis.format == "PNG"
+                && analysis
+                    .features
+                    .is_near_incompressible(config.entropy_skip_threshold)
+            {
+                verbose_log!(
+                    "⏭️ Near-incompressible PNG (entropy {:.2} bits, {:.0}% of raw size): {}",
+                    analysis.features.entropy,
+                    analysis.features.compression_ratio * 100.0,
+                    input.display()
+                );
+                copy_original_if_adjacent_mode(input, config)?;
+                return Ok(make_skipped(
+                    "Already near-incompressible (high entropy), lossless JXL would not shrink it",
+                    SkipReason::OutputLarger,
+                ));
+            }
             verbose_log!("🔄 Legacy Lossless→JXL: {}", input.display());
             convert_to_jxl(input, &options, 0.0, analysis.hdr_info.as_ref())?
         }
@@ -670,7 +1396,7 @@ fn auto_convert_single_file(
                 false
             };
 
-            if should_skip_modern {
+            if should_skip_modern && !forced {
                 verbose_log!(
                     "⏭️ Skipping modern lossy animated format (avoid generational loss): {}",
                     input.display()
@@ -683,7 +1409,10 @@ fn auto_convert_single_file(
                     );
                 }
                 copy_original_if_adjacent_mode(input, config)?;
-                return Ok(make_skipped("Skipping modern lossy animated format"));
+                return Ok(make_skipped(
+                    "Skipping modern lossy animated format",
+                    SkipReason::ModernLossy,
+                ));
             }
 
             let duration = match analysis.duration_secs {
@@ -692,7 +1421,7 @@ fn auto_convert_single_file(
                     let is_modern = matches!(format, "WebP" | "AVIF" | "JXL" | "HEIC" | "HEIF");
                     let use_lossless = analysis.is_lossless;
 
-                    if is_modern && !use_lossless {
+                    if is_modern && !use_lossless && !forced {
                         verbose_log!(
                             "⏭️ Skipping static-disguised modern format (lossy): {}",
                             input.display()
@@ -700,6 +1429,7 @@ fn auto_convert_single_file(
                         copy_original_if_adjacent_mode(input, config)?;
                         return Ok(make_skipped(
                             "Skipping static modern format to avoid generational loss",
+                            SkipReason::ModernLossy,
                         ));
                     }
 
@@ -738,7 +1468,10 @@ fn auto_convert_single_file(
                             "   💡 Suggestion: install ffprobe: brew install ffmpeg"
                         );
                         copy_original_if_adjacent_mode(input, config)?;
-                        return Ok(make_skipped("Cannot get animation duration"));
+                        return Ok(make_skipped(
+                            "Cannot get animation duration",
+                            SkipReason::Corrupt,
+                        ));
                     }
                 }
             };
@@ -756,9 +1489,24 @@ fn auto_convert_single_file(
                     None
                 }
             };
-            let meme_keep = if force_video {
-                // Force video mode: always convert to video, skip meme-score
+            // --target apng only covers short GIFs; everything else keeps the normal route.
+            let use_apng = animated_target == AnimatedTarget::Apng
+                && shared_utils::animated_apng::routes_to_apng(format, duration);
+            if animated_target == AnimatedTarget::Apng && !use_apng {
+                verbose_log!(
+                    "   💡 --target apng applies to GIFs under {:.0}s ({} {:.1}s); using default routing",
+                    shared_utils::animated_apng::ANIMATION_DURATION_THRESHOLD_SECS,
+                    format,
+                    duration
+                );
+            }
+            let explicit_target = animated_target == AnimatedTarget::Webp || use_apng;
+            let meme_keep = if force_video || forced || explicit_target {
+                // Force video / override / WebP / APNG target: always convert, skip meme-score
                 false
+            } else if let Some(min) = options.min_animation_duration {
+                // --min-animation-duration: a plain duration cutoff replaces the meme-score.
+                duration < min
             } else if let Some(ref p) = probe {
                 if let Some(mut meta) =
                     shared_utils::gif_meta_from_probe_with_path(p, analysis.file_size, input)
@@ -784,9 +1532,57 @@ fn auto_convert_single_file(
                 true
             };
 
-            if meme_keep {
+            // MP4 has no alpha channel: a transparent animation sent to video loses its transparency.
+            let transparent_to_video = !explicit_target
+                && !meme_keep
+                && analysis.has_alpha
+                && shared_utils::animated_webp::animation_has_transparency(input);
+            if transparent_to_video && !config.preserve_alpha {
+                shared_utils::log_eprintln!(
+                    "⚠️  Transparency will be LOST: {} is a transparent animation and MP4 cannot carry alpha (use --preserve-alpha to keep it as animated WebP)",
+                    input.display()
+                );
+            }
+            let alpha_to_webp = transparent_to_video && config.preserve_alpha;
+            if alpha_to_webp && format == "WebP" {
+                // Already an alpha-capable animation; re-encoding it would only add loss.
                 copy_original_if_adjacent_mode(input, config)?;
-                return Ok(make_skipped("GIF meme-score: keep as GIF"));
+                return Ok(make_skipped(
+                    "Transparent animated WebP kept (--preserve-alpha)",
+                    SkipReason::AlreadyOptimal,
+                ));
+            }
+
+            if animated_target == AnimatedTarget::Webp || alpha_to_webp {
+                shared_utils::progress_mode::emit_stderr(&format!(
+                    "🌐 Animated {}→WebP ({}{:.1}s): {}",
+                    format,
+                    if alpha_to_webp {
+                        "alpha preserved, "
+                    } else {
+                        ""
+                    },
+                    duration,
+                    input.display()
+                ));
+                convert_to_animated_webp(input, &options)?
+            } else if use_apng {
+                shared_utils::progress_mode::emit_stderr(&format!(
+                    "🖼️  Animated GIF→APNG (lossless, {:.1}s): {}",
+                    duration,
+                    input.display()
+                ));
+                convert_to_apng(input, &options)?
+            } else if meme_keep {
+                copy_original_if_adjacent_mode(input, config)?;
+                return Ok(if options.min_animation_duration.is_some() {
+                    make_skipped(
+                        "Shorter than --min-animation-duration: keep as GIF",
+                        SkipReason::ShortAnimation,
+                    )
+                } else {
+                    make_skipped("GIF meme-score: keep as GIF", SkipReason::AlreadyOptimal)
+                });
             } else {
                 if is_lossless {
                     shared_utils::progress_mode::emit_stderr(&format!(
@@ -827,6 +1623,9 @@ fn auto_convert_single_file(
         original_size: result.input_size,
         output_size: result.output_size,
         size_reduction: result.size_reduction.map(|r| r as f32),
+        skip_reason: result
+            .skipped
+            .then(|| SkipReason::from_code(result.skip_reason.as_deref().unwrap_or_default())),
     };
 
     if output.skipped {
@@ -869,6 +1668,18 @@ fn auto_convert_directory(
     let pool_size = thread_config.parallel_tasks;
     config_with_base.child_threads = thread_config.child_threads;
 
+    // Precedence: .mfb_overrides rule > automatic routing > defaults.
+    if let Some(overrides) = RoutingOverrides::load(input)? {
+        shared_utils::log_eprintln!(
+            "📋 Routing overrides: {} rule(s) from {}",
+            overrides.len(),
+            input
+                .join(shared_utils::routing_overrides::OVERRIDES_FILE_NAME)
+                .display()
+        );
+        config_with_base.overrides = Some(Arc::new(overrides));
+    }
+
     let config = &config_with_base;
 
     let start_time = Instant::now();
@@ -890,6 +1701,7 @@ fn auto_convert_directory(
         shared_utils::SUPPORTED_IMAGE_EXTENSIONS,
         config.recursive,
     );
+    let files = config.extension_filter.apply(files);
 
     let total = files.len();
     if total == 0 {
@@ -943,6 +1755,12 @@ fn auto_convert_directory(
         None
     };
 
+    // Applied after the checkpoint is loaded so files finished by an earlier run do not count.
+    let (files, limit_note) = shared_utils::apply_limit(files, config.limit, |f| {
+        checkpoint.as_ref().is_some_and(|cp| cp.is_completed(f))
+    });
+    let total = files.len();
+
     if std::env::var("MFB_SKIP_DISK_PRECHECK").as_deref() != Ok("1") {
         let total_input_size: u64 = files
             .iter()
@@ -986,20 +1804,25 @@ fn auto_convert_directory(
 
     let success = AtomicUsize::new(0);
     let skipped = AtomicUsize::new(0);
+    let skip_reasons = std::sync::Mutex::new(Vec::new());
     let failed = AtomicUsize::new(0);
     let processed = AtomicUsize::new(0);
     let actual_input_bytes = std::sync::atomic::AtomicU64::new(0);
     let actual_output_bytes = std::sync::atomic::AtomicU64::new(0);
+    let growth_audit = shared_utils::GrowthAudit::new();
+    let file_report = shared_utils::FileReport::new();
     let pause_controller = Arc::new(BatchPauseController::new());
+    let abort_controller = BatchAbortController::new();
 
     // Initialize Ctrl+C guard for long-running batch operations
     shared_utils::ctrlc_guard::init();
 
     shared_utils::progress_mode::enable_quiet_mode();
-    let progress_bar = Arc::new(shared_utils::CoarseProgressBar::new(
-        total as u64,
-        "Running",
-    ));
+    let file_sizes = shared_utils::file_sizes(&files);
+    let progress_bar = Arc::new(
+        shared_utils::CoarseProgressBar::new(total as u64, "Running")
+            .with_total_bytes(file_sizes.iter().sum()),
+    );
 
     let max_threads = pool_size;
     let child_threads = thread_config.child_threads;
@@ -1045,9 +1868,12 @@ fn auto_convert_directory(
             for _ in 0..max_threads {
                 let next_index = &next_index;
                 scope.spawn(|_| loop {
-                    if pause_controller.is_paused() {
+                    if pause_controller.is_paused() || abort_controller.is_aborted() {
                         break;
                     }
+                    // A fail-fast abort also kills the encoder children of files in flight.
+                    let _cancel_guard =
+                        shared_utils::cancellation::install(Some(abort_controller.token()));
 
                     let index = next_index.fetch_add(1, Ordering::Relaxed);
                     if index >= total {
@@ -1061,6 +1887,7 @@ fn auto_convert_directory(
                     if let Some(cp) = checkpoint.as_ref() {
                         if cp.is_completed(path) {
                             skipped.fetch_add(1, Ordering::Relaxed);
+                            skip_reasons.lock().unwrap().push(SkipReason::AlreadyProcessed);
                             let current = processed.fetch_add(1, Ordering::Relaxed) + 1;
                             shared_utils::progress_mode::write_progress_line_to_run_log(
                                 start_time.elapsed().as_secs(),
@@ -1068,21 +1895,41 @@ fn auto_convert_directory(
                                 total as u64,
                                 &path.file_name().unwrap_or_default().to_string_lossy(),
                             );
+                            progress_bar.add_bytes(file_sizes[index]);
                             progress_bar.set(current as u64);
                             continue;
                         }
                     }
 
-                    match auto_convert_single_file(path, config) {
+                    match auto_convert_with_retries(path, config) {
                         Ok(result) => {
                             if result.skipped {
                                 skipped.fetch_add(1, Ordering::Relaxed);
+                                skip_reasons
+                                    .lock()
+                                    .unwrap()
+                                    .push(result.skip_reason.unwrap_or(SkipReason::Other));
                             } else {
                                 success.fetch_add(1, Ordering::Relaxed);
                                 shared_utils::progress_mode::image_processed_success();
                                 actual_input_bytes.fetch_add(result.original_size, Ordering::Relaxed);
                                 if let Some(out_size) = result.output_size {
                                     actual_output_bytes.fetch_add(out_size, Ordering::Relaxed);
+                                    growth_audit.record(
+                                        path,
+                                        (!result.output_path.is_empty())
+                                            .then(|| Path::new(&result.output_path)),
+                                        result.original_size,
+                                        out_size,
+                                    );
+                                    file_report.record(path, result.original_size, out_size, None);
+                                }
+                                if !result.output_path.is_empty() {
+                                    shared_utils::archive_output(Path::new(&result.output_path));
+                                    shared_utils::record_verify_output(
+                                        path,
+                                        Path::new(&result.output_path),
+                                    );
                                 }
                                 // Mark as completed in checkpoint manager on success (thread-safe)
                                 if let Some(cp) = checkpoint.as_ref() {
@@ -1100,6 +1947,7 @@ fn auto_convert_directory(
                             let msg = e.to_string();
                             if msg.contains("Skipped") || msg.contains("skip") {
                                 skipped.fetch_add(1, Ordering::Relaxed);
+                                skip_reasons.lock().unwrap().push(SkipReason::Other);
                             } else if let Some(reason) = disk_full_pause_reason(&msg) {
                                 if pause_controller.request_pause(path, reason.clone()) {
                                     shared_utils::log_eprintln!(
@@ -1115,6 +1963,14 @@ fn auto_convert_directory(
                                 shared_utils::progress_mode::log_conversion_failure(path, &err_str);
                                 failed.fetch_add(1, Ordering::Relaxed);
                                 shared_utils::progress_mode::image_processed_failure();
+                                if config.failure_policy.is_fail_fast()
+                                    && abort_controller.request_abort(path, format!("{:#}", e))
+                                {
+                                    shared_utils::log_eprintln!(
+                                        "⛔ [Batch] Fail-fast: stopping after failure on {}",
+                                        path.display()
+                                    );
+                                }
 
                                 if let Some(ref output_dir) = config.output_dir {
                                     if let Err(copy_err) = shared_utils::copy_on_skip_or_fail(
@@ -1140,6 +1996,7 @@ fn auto_convert_directory(
                         total as u64,
                         &path.file_name().unwrap_or_default().to_string_lossy(),
                     );
+                    progress_bar.add_bytes(file_sizes[index]);
                     progress_bar.set(current as u64);
                 });
             }
@@ -1160,7 +2017,18 @@ fn auto_convert_directory(
     result.succeeded = success_count;
     result.failed = failed_count;
     result.skipped = skipped_count;
+    for reason in skip_reasons.into_inner().unwrap_or_default() {
+        result.record_skip_reason(reason);
+    }
     result.total = processed_count;
+    if let Some(report) = shared_utils::finish_verify_after() {
+        if let Some(cp) = checkpoint.as_ref() {
+            for (path, _) in &report.reverted {
+                cp.unmark_completed(path);
+            }
+        }
+        result.apply_verify_after(&report);
+    }
     if let Some(pause) = pause_controller.pause_info() {
         result.pause(
             pause.path,
@@ -1179,6 +2047,18 @@ fn auto_convert_directory(
         final_output_bytes,
         "Image Conversion",
     );
+    if let Some(ref note) = limit_note {
+        shared_utils::log_eprintln!("{}", note);
+    }
+    if config.only_larger_than_source {
+        shared_utils::print_growth_audit(&growth_audit);
+    }
+    if config.sort_report_by.is_some() || shared_utils::progress_mode::is_verbose_mode() {
+        shared_utils::print_file_report(&file_report, config.sort_report_by.unwrap_or_default());
+    }
+    if let Some(dedup) = &config.dedup {
+        dedup.report_with_log();
+    }
 
     if !result.paused {
         if let Some(ref output_dir) = config.output_dir {
@@ -1200,13 +2080,13 @@ fn auto_convert_directory(
         shared_utils::log_eprintln!("✅ Directory timestamps restored");
     }
 
-    // Finalize checkpoint only on 100% success
+    // Finalize checkpoint only on 100% success; a --limit run keeps it for the files it left.
     if let Some(cp) = checkpoint {
         if result.paused {
             if let Err(e) = cp.release_lock() {
                 shared_utils::log_eprintln!("⚠️ [checkpoint] Release lock failed: {}", e);
             }
-        } else if failed_count == 0 {
+        } else if result.failed == 0 && limit_note.is_none() {
             if let Err(e) = cp.cleanup() {
                 shared_utils::log_eprintln!("⚠️ [checkpoint] Cleanup failed: {}", e);
             }
@@ -1215,5 +2095,9 @@ fn auto_convert_directory(
         }
     }
 
+    if let Some((path, error)) = abort_controller.abort_info() {
+        anyhow::bail!("{}", shared_utils::fail_fast_message(&path, &error));
+    }
+
     Ok(())
 }
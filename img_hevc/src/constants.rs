@@ -16,9 +16,9 @@ pub const STDERR_BUFFER_MAX: usize = 1024 * 1024;
 /// Prevents memory overflow from verbose output
 pub const STDERR_MAX_LINES: usize = 100_000;
 
-/// Animation duration threshold (3.0 seconds)
-/// Animations shorter than this may be converted to static images
-pub const ANIMATION_DURATION_THRESHOLD_SECS: f32 = 3.0;
+/// Animation duration threshold (3.0 seconds), shared with img_av1
+/// GIFs shorter than this may use --target apng
+pub use shared_utils::animated_apng::ANIMATION_DURATION_THRESHOLD_SECS;
 
 /// High quality CRF value for HEVC encoding
 pub const CRF_HIGH_QUALITY: f32 = 18.0;
@@ -6,6 +6,7 @@
 use crate::detection_api::{CompressionType, DetectedFormat, DetectionResult, ImageType};
 use crate::{ImgQualityError, Result};
 use serde::{Deserialize, Serialize};
+use shared_utils::SkipReason;
 use std::path::{Path, PathBuf};
 use std::process::Command;
 
@@ -37,6 +38,11 @@ pub struct ConversionConfig {
     pub compress: bool,
     /// When true, JXL uses --compress_boxes=0 for Apple compatibility.
     pub apple_compat: bool,
+    /// `--strip-metadata` / `--strip-timestamps`: encoders drop embedded metadata and the
+    /// metadata step skips what is stripped.
+    pub strip: shared_utils::StripPolicy,
+    /// `--on-collision`: what happens when the output path already exists.
+    pub on_collision: shared_utils::CollisionPolicy,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -48,6 +54,9 @@ pub struct ConversionOutput {
     pub original_size: u64,
     pub output_size: Option<u64>,
     pub size_reduction: Option<f32>,
+    /// Why the file was skipped; `None` for conversions.
+    #[serde(default)]
+    pub skip_reason: Option<SkipReason>,
 }
 
 impl ConversionOutput {
@@ -197,6 +206,7 @@ pub fn execute_conversion(
             original_size: detection.file_size,
             output_size: None,
             size_reduction: None,
+            skip_reason: Some(SkipReason::ModernLossy),
         });
     }
 
@@ -211,11 +221,21 @@ pub fn execute_conversion(
         }
     };
 
-    let output_path = resolve_output_path(input_path, config.output_dir.as_deref(), extension)?;
+    let output_path = resolve_output_path(
+        input_path,
+        config.output_dir.as_deref(),
+        extension,
+        config.on_collision,
+    )?;
     shared_utils::conversion::validate_output_path(&output_path, config.base_dir.as_deref())
         .map_err(ImgQualityError::ConversionError)?;
 
-    if output_path.exists() && !config.force {
+    if shared_utils::conversion::keeps_existing_output(
+        input_path,
+        &output_path,
+        config.force,
+        config.on_collision,
+    ) {
         return Ok(ConversionOutput {
             original_path: detection.file_path.clone(),
             output_path: output_path.display().to_string(),
@@ -224,6 +244,7 @@ pub fn execute_conversion(
             original_size: detection.file_size,
             output_size: None,
             size_reduction: None,
+            skip_reason: Some(SkipReason::AlreadyProcessed),
         });
     }
 
@@ -258,6 +279,8 @@ pub fn execute_conversion(
         &output_path,
         config.force,
         Some(input_path),
+        config.strip,
+        config.on_collision,
     )
     .map_err(|e| ImgQualityError::ConversionError(e.to_string()))?
     {
@@ -269,6 +292,7 @@ pub fn execute_conversion(
             original_size: detection.file_size,
             output_size: None,
             size_reduction: None,
+            skip_reason: Some(SkipReason::AlreadyProcessed),
         });
     }
 
@@ -302,16 +326,17 @@ pub fn execute_conversion(
                 original_size: detection.file_size,
                 output_size: None,
                 size_reduction: None,
+                skip_reason: Some(SkipReason::OutputLarger),
             });
         }
     }
 
     if config.preserve_metadata {
-        preserve_metadata(input_path, &output_path)?;
+        preserve_metadata(input_path, &output_path, config.strip)?;
     }
 
     if config.preserve_timestamps {
-        preserve_timestamps(input_path, &output_path)?;
+        preserve_timestamps(input_path, &output_path, config.strip)?;
     }
 
     if config.delete_original {
@@ -341,6 +366,7 @@ pub fn execute_conversion(
         original_size: detection.file_size,
         output_size,
         size_reduction,
+        skip_reason: None,
     })
 }
 
@@ -354,6 +380,7 @@ fn resolve_output_path(
     input: &Path,
     output_dir: Option<&Path>,
     extension: &str,
+    collision: shared_utils::CollisionPolicy,
 ) -> Result<PathBuf> {
     let file_stem = input.file_stem().ok_or_else(|| {
         ImgQualityError::ConversionError("Invalid file path: no file stem".to_string())
@@ -363,6 +390,8 @@ fn resolve_output_path(
     } else {
         input.with_extension(extension)
     };
+    let output = shared_utils::conversion::resolve_output_collision(input, output, collision)
+        .map_err(ImgQualityError::ConversionError)?;
     shared_utils::conversion::validate_output_path(&output, None)
         .map_err(ImgQualityError::ConversionError)?;
     Ok(output)
@@ -392,6 +421,7 @@ fn convert_to_jxl(
     if config.apple_compat {
         cmd.arg("--compress_boxes=0");
     }
+    cmd.args(config.strip.cjxl_args());
     cmd.arg("--");
     let status = cmd
         .arg(shared_utils::safe_path_arg(&input_abs).as_ref())
@@ -467,6 +497,7 @@ fn convert_to_avif(
     let output_abs = resolve_output_absolute(output);
 
     let status = Command::new("avifenc")
+        .args(config.strip.avifenc_args())
         .arg(shared_utils::safe_path_arg(&input_abs).as_ref())
         .arg(shared_utils::safe_path_arg(&output_abs).as_ref())
         .args(["-q", &q])
@@ -557,6 +588,7 @@ fn convert_to_hevc_mp4(
         cmd.arg(arg);
     }
     cmd.arg("-pix_fmt").arg("yuv420p");
+    cmd.args(config.strip.ffmpeg_args());
     cmd.arg(shared_utils::safe_path_arg(&output_abs).as_ref());
 
     let process = FfmpegProcess::spawn(&mut cmd)
@@ -602,13 +634,13 @@ fn convert_to_hevc_mp4(
     Ok(())
 }
 
-fn preserve_timestamps(source: &Path, dest: &Path) -> Result<()> {
-    shared_utils::copy_metadata(source, dest);
+fn preserve_timestamps(source: &Path, dest: &Path, strip: shared_utils::StripPolicy) -> Result<()> {
+    shared_utils::copy_metadata(source, dest, strip);
     Ok(())
 }
 
-fn preserve_metadata(source: &Path, dest: &Path) -> Result<()> {
-    shared_utils::metadata::copy_metadata(source, dest);
+fn preserve_metadata(source: &Path, dest: &Path, strip: shared_utils::StripPolicy) -> Result<()> {
+    shared_utils::metadata::copy_metadata(source, dest, strip);
     Ok(())
 }
 
@@ -634,6 +666,8 @@ pub fn simple_convert(path: &Path, output_dir: Option<&Path>) -> Result<Conversi
         preserve_metadata: true,   // Changed: Always preserve metadata by default
         compress: false,
         apple_compat: false,
+        strip: shared_utils::StripPolicy::default(),
+        on_collision: shared_utils::CollisionPolicy::default(),
     };
     smart_convert(path, &config)
 }
@@ -18,8 +18,10 @@ pub use lossless_converter::{
     convert_to_gif_apple_compat, is_high_quality_animated, ConversionResult, ConvertOptions,
 };
 pub use metrics::{
-    calculate_ms_ssim, calculate_psnr, calculate_ssim, psnr_quality_description,
-    ssim_quality_description,
+    calculate_animation_ms_ssim, calculate_ms_ssim, calculate_ms_ssim_bounded,
+    calculate_ms_ssim_frames, calculate_psnr, calculate_ssim, calculate_ssim_dark_weighted,
+    load_animation_frames, ms_ssim_quality_description, ms_ssim_working_size,
+    psnr_quality_description, ssim_quality_description,
 };
 pub use recommender::{get_recommendation, UpgradeRecommendation};
 
@@ -55,6 +55,7 @@ fn finalize_with_size_check(
     options: &ConvertOptions,
     format_label: &str,
     extra_info: Option<String>,
+    lossless: bool,
 ) -> Result<ConversionResult> {
     // Commit temp file to final output WITH METADATA PRESERVATION
     if !shared_utils::conversion::commit_temp_to_output_with_metadata(
@@ -62,6 +63,8 @@ fn finalize_with_size_check(
         output,
         options.force,
         Some(input),
+        options.strip,
+        options.on_collision,
     )? {
         return Ok(ConversionResult::skipped_exists(input, output));
     }
@@ -78,8 +81,14 @@ fn finalize_with_size_check(
         return Ok(skipped);
     }
 
-    // Finalize with metadata preservation
-    finalize_conversion(
+    // Finalize with metadata preservation; lossless targets must round-trip pixel-exact
+    // before the original may be deleted.
+    let finalize = if lossless {
+        shared_utils::conversion::finalize_lossless_conversion
+    } else {
+        finalize_conversion
+    };
+    finalize(
         input,
         output,
         input_size,
@@ -99,6 +108,7 @@ fn finalize_fallback_jxl(
     input_size: u64,
     options: &ConvertOptions,
     label: &str,
+    lossless: bool,
 ) -> Result<ConversionResult> {
     let output_size = fs::metadata(temp_output)?.len();
     if let Err(e) = verify_jxl_health(temp_output) {
@@ -114,6 +124,7 @@ fn finalize_fallback_jxl(
         options,
         "JXL",
         Some(label.to_string()),
+        lossless,
     )
 }
 
@@ -187,16 +198,43 @@ pub fn convert_to_jxl(
         fs::create_dir_all(parent)?;
     }
 
-    if output.exists() && !options.force {
+    if shared_utils::conversion::keeps_existing_output(
+        input,
+        &output,
+        options.force,
+        options.on_collision,
+    ) {
         return Ok(ConversionResult::skipped_exists(input, &output));
     }
 
     let temp_output = shared_utils::conversion::temp_path_for_output(&output);
 
+    // 12/16-bit sources (medical/scientific TIFF, 16-bit PNG) keep their depth through cjxl.
+    let source_depth = shared_utils::image_analyzer::read_bit_depth(input);
     let (actual_input, _temp_file_guard) = prepare_input_for_cjxl(input, options, hdr_info)?;
 
-    // Extract ICC Profile from original input for preservation
-    let _icc_temp = shared_utils::jxl_utils::extract_icc_profile(input);
+    // --auto-grayscale: feed cjxl a single-channel copy. The source's RGB ICC profile cannot
+    // describe it, so that profile is not carried over.
+    let gray_input = options
+        .grayscale
+        .then(|| shared_utils::jxl_utils::convert_to_gray_png(&actual_input))
+        .flatten();
+    let grayscale = gray_input.is_some();
+    let (actual_input, _temp_file_guard) = match gray_input {
+        Some((path, temp)) => (path, Some(temp)),
+        None => (actual_input, _temp_file_guard),
+    };
+    if grayscale && options.verbose {
+        eprintln!("   ⚫ Grayscale content: encoding single-channel JXL");
+    }
+
+    // Carry the original input's ICC profile over (--preserve-icc)
+    let _icc_temp = shared_utils::jxl_utils::icc_for_cjxl(
+        input,
+        options.preserve_icc,
+        grayscale,
+        options.verbose,
+    );
     let icc_path = _icc_temp.as_ref().map(|t| t.path());
 
     // Cache thread count calculation (avoid repeated calls)
@@ -206,33 +244,22 @@ pub fn convert_to_jxl(
         shared_utils::thread_manager::get_optimal_threads()
     };
 
-    let mut cmd = Command::new("cjxl");
-    cmd.arg("-d")
-        .arg(format!("{:.2}", distance))
-        .arg("-e")
-        .arg("7")
-        .arg("-j")
-        .arg(max_threads.to_string());
-
-    // Add HDR metadata via CICP if available
-    if let Some(hdr) = hdr_info {
-        if let Some(cicp) = shared_utils::color_info_to_cicp(hdr) {
-            cmd.arg(format!("--cicp={}", cicp));
-            if options.verbose {
-                eprintln!("   🌈 HDR detected: applying CICP {}", cicp);
-            }
+    if options.verbose {
+        if let Some(cicp) = hdr_info.and_then(shared_utils::color_info_to_cicp) {
+            eprintln!("   🌈 HDR detected: applying CICP {}", cicp);
         }
     }
-
-    if options.apple_compat {
-        cmd.arg("--compress_boxes=0");
-    }
-
-    shared_utils::jxl_utils::add_icc_to_cjxl(&mut cmd, icc_path);
-
-    cmd.arg("--")
-        .arg(shared_utils::safe_path_arg(&actual_input).as_ref())
-        .arg(shared_utils::safe_path_arg(&temp_output).as_ref());
+    let mut cmd = Command::new("cjxl");
+    cmd.args(cjxl_distance_args(
+        &actual_input,
+        &temp_output,
+        distance,
+        max_threads,
+        source_depth,
+        hdr_info,
+        options,
+        icc_path,
+    ));
 
     if options.verbose {
         eprintln!(
@@ -257,6 +284,8 @@ pub fn convert_to_jxl(
             {
                 use std::process::Stdio;
 
+                let pix_fmt = shared_utils::image_analyzer::read_color_type(input)
+                    .and_then(shared_utils::jxl_utils::png16_pix_fmt);
                 let ffmpeg_result = Command::new("ffmpeg")
                     .arg("-threads")
                     .arg(max_threads.to_string())
@@ -266,6 +295,7 @@ pub fn convert_to_jxl(
                     .arg("1")
                     .arg("-vcodec")
                     .arg("png")
+                    .args(pix_fmt.iter().flat_map(|f| ["-pix_fmt", *f]))
                     .arg("-f")
                     .arg("image2pipe")
                     .arg("-")
@@ -285,10 +315,12 @@ pub fn convert_to_jxl(
                                 .arg("7")
                                 .arg("-j")
                                 .arg(max_threads.to_string());
+                            cmd.args(shared_utils::jxl_utils::cjxl_bit_depth_arg(source_depth));
 
                             if options.apple_compat {
                                 cmd.arg("--compress_boxes=0");
                             }
+                            cmd.args(options.strip.cjxl_args());
 
                             let cjxl_result =
                                 cmd.stdin(ffmpeg_stdout).stderr(Stdio::piped()).spawn();
@@ -437,6 +469,7 @@ pub fn convert_to_jxl(
                                             options,
                                             "JXL",
                                             Some("(ffmpeg fallback)".to_string()),
+                                            distance == 0.0,
                                         );
                                     } else {
                                         let line = format!(
@@ -453,6 +486,7 @@ pub fn convert_to_jxl(
                                             distance,
                                             max_threads,
                                             options.apple_compat,
+                                            options.strip,
                                         )
                                         .is_ok()
                                         {
@@ -463,6 +497,7 @@ pub fn convert_to_jxl(
                                                 input_size,
                                                 options,
                                                 "(imagemagick fallback)",
+                                                distance == 0.0,
                                             );
                                         }
                                         result
@@ -487,6 +522,7 @@ pub fn convert_to_jxl(
                                         distance,
                                         max_threads,
                                         options.apple_compat,
+                                        options.strip,
                                     )
                                     .is_ok()
                                     {
@@ -497,6 +533,7 @@ pub fn convert_to_jxl(
                                             input_size,
                                             options,
                                             "(imagemagick fallback)",
+                                            distance == 0.0,
                                         );
                                     }
                                     result
@@ -522,6 +559,7 @@ pub fn convert_to_jxl(
                                 distance,
                                 max_threads,
                                 options.apple_compat,
+                                options.strip,
                             )
                             .is_ok()
                             {
@@ -532,6 +570,7 @@ pub fn convert_to_jxl(
                                     input_size,
                                     options,
                                     "(imagemagick fallback)",
+                                    distance == 0.0,
                                 );
                             }
                             result
@@ -552,6 +591,7 @@ pub fn convert_to_jxl(
                             distance,
                             max_threads,
                             options.apple_compat,
+                            options.strip,
                         )
                         .is_ok()
                         {
@@ -562,6 +602,7 @@ pub fn convert_to_jxl(
                                 input_size,
                                 options,
                                 "(imagemagick fallback)",
+                                distance == 0.0,
                             );
                         }
                         result
@@ -582,6 +623,14 @@ pub fn convert_to_jxl(
                 cleanup_temp_output(&temp_output, input);
                 return Err(e);
             }
+            if distance == 0.0 {
+                if let Err(e) =
+                    shared_utils::jxl_utils::verify_jxl_bit_depth(source_depth, &temp_output)
+                {
+                    cleanup_temp_output(&temp_output, input);
+                    return Err(ImgQualityError::ConversionError(e));
+                }
+            }
 
             finalize_with_size_check(
                 input,
@@ -592,6 +641,7 @@ pub fn convert_to_jxl(
                 options,
                 "JXL",
                 None,
+                distance == 0.0,
             )
         }
         Ok(output_cmd) => {
@@ -630,31 +680,50 @@ fn run_cjxl_jpeg_transcode(
     let _icc_temp = shared_utils::jxl_utils::extract_icc_profile(input);
     let icc_path = _icc_temp.as_ref().map(|t| t.path());
 
-    let mut cmd = Command::new("cjxl");
-    cmd.arg("--lossless_jpeg=1")
-        .arg("-j")
-        .arg(max_threads.to_string());
+    Command::new("cjxl")
+        .args(jpeg_transcode_cjxl_args(
+            input,
+            temp_output,
+            options,
+            max_threads,
+            allow_jpeg_reconstruction,
+            hdr_info,
+            icc_path,
+        ))
+        .output()
+}
+
+/// cjxl arguments of the JPEG transcode in [`run_cjxl_jpeg_transcode`].
+fn jpeg_transcode_cjxl_args(
+    input: &Path,
+    output: &Path,
+    options: &ConvertOptions,
+    max_threads: usize,
+    allow_jpeg_reconstruction: Option<u8>,
+    hdr_info: Option<&shared_utils::ColorInfo>,
+    icc_path: Option<&Path>,
+) -> Vec<String> {
+    let mut args = vec![
+        "--lossless_jpeg=1".to_string(),
+        "-j".to_string(),
+        max_threads.to_string(),
+    ];
     if let Some(v) = allow_jpeg_reconstruction {
-        cmd.arg("--allow_jpeg_reconstruction").arg(v.to_string());
+        args.extend(["--allow_jpeg_reconstruction".to_string(), v.to_string()]);
     }
 
     // Add HDR metadata via CICP if available (for wide-gamut JPEG)
-    if let Some(hdr) = hdr_info {
-        if let Some(cicp) = shared_utils::color_info_to_cicp(hdr) {
-            cmd.arg(format!("--cicp={}", cicp));
-        }
+    if let Some(cicp) = hdr_info.and_then(shared_utils::color_info_to_cicp) {
+        args.push(format!("--cicp={}", cicp));
     }
 
     if options.apple_compat {
-        cmd.arg("--compress_boxes=0");
+        args.push("--compress_boxes=0".to_string());
     }
-
-    shared_utils::jxl_utils::add_icc_to_cjxl(&mut cmd, icc_path);
-
-    cmd.arg("--")
-        .arg(shared_utils::safe_path_arg(input).as_ref())
-        .arg(shared_utils::safe_path_arg(temp_output).as_ref());
-    cmd.output()
+    args.extend(options.strip.cjxl_args().iter().map(|s| s.to_string()));
+    args.extend(shared_utils::jxl_utils::icc_cjxl_args(icc_path));
+    args.extend(shared_utils::jxl_utils::cjxl_io_args(input, output));
+    args
 }
 
 fn commit_jpeg_to_jxl_success(
@@ -679,6 +748,7 @@ fn commit_jpeg_to_jxl_success(
         options,
         label,
         None,
+        false,
     )
 }
 
@@ -694,6 +764,8 @@ fn commit_jpeg_to_jxl_success(
 ///
 /// # Behavior
 /// - Uses `cjxl --lossless_jpeg=1` for bitstream reconstruction
+/// - With `normalize_orientation`: transcodes a losslessly rotated upright copy and resets
+///   the output's EXIF Orientation to 1
 /// - On reconstruction failure: strips JPEG tail and retries
 /// - On corruption: uses ImageMagick fallback to sanitize
 /// - Verifies JXL health and checks size tolerance
@@ -707,6 +779,47 @@ pub fn convert_jpeg_to_jxl(
     input: &Path,
     options: &ConvertOptions,
     hdr_info: Option<&shared_utils::ColorInfo>,
+) -> Result<ConversionResult> {
+    // --normalize-orientation: transcode an upright copy; the original stays the metadata source.
+    let upright = if options.normalize_orientation {
+        shared_utils::jxl_utils::normalize_jpeg_orientation_to_temp(input).unwrap_or_else(|e| {
+            eprintln!(
+                "⚠️ [img-hevc] Orientation check failed for {}: {}",
+                input.display(),
+                e
+            );
+            None
+        })
+    } else {
+        None
+    };
+    let Some((upright_path, _upright_guard)) = upright else {
+        return transcode_jpeg_to_jxl(input, input, options, hdr_info);
+    };
+    if options.verbose {
+        eprintln!("   🔄 Rotated upright from EXIF orientation (lossless)");
+    }
+
+    let result = transcode_jpeg_to_jxl(input, &upright_path, options, hdr_info)?;
+    // The metadata step copied the original Orientation tag back onto the output.
+    if result.success && !result.skipped {
+        if let Some(ref output) = result.output_path {
+            if let Err(e) = shared_utils::reset_orientation_tag(Path::new(output)) {
+                eprintln!(
+                    "⚠️ [img-hevc] Failed to reset EXIF orientation on {}: {}",
+                    output, e
+                );
+            }
+        }
+    }
+    Ok(result)
+}
+
+fn transcode_jpeg_to_jxl(
+    input: &Path,
+    source: &Path,
+    options: &ConvertOptions,
+    hdr_info: Option<&shared_utils::ColorInfo>,
 ) -> Result<ConversionResult> {
     // Validate input file
     if let Err(e) = shared_utils::conversion::validate_input_file(input) {
@@ -720,14 +833,20 @@ pub fn convert_jpeg_to_jxl(
     let input_size = fs::metadata(input)?.len();
     let output = get_output_path(input, "jxl", options)?;
 
-    if output.exists() && !options.force {
+    if shared_utils::conversion::keeps_existing_output(
+        input,
+        &output,
+        options.force,
+        options.on_collision,
+    ) {
         return Ok(ConversionResult::skipped_exists(input, &output));
     }
 
     let temp_output = shared_utils::conversion::temp_path_for_output(&output);
     let max_threads = shared_utils::thread_manager::get_optimal_threads();
 
-    let result = run_cjxl_jpeg_transcode(input, &temp_output, options, max_threads, None, hdr_info);
+    let result =
+        run_cjxl_jpeg_transcode(source, &temp_output, options, max_threads, None, hdr_info);
 
     let output_cmd = match result {
         Ok(out) => out,
@@ -756,14 +875,14 @@ pub fn convert_jpeg_to_jxl(
     if is_jpeg_reconstruction_cjxl_error(&stderr) {
         // 1) Fix: strip trailing data after JPEG EOI so cjxl can use bitstream reconstruction
         let (source_to_use, _guard): (std::path::PathBuf, Option<tempfile::NamedTempFile>) =
-            match shared_utils::jxl_utils::strip_jpeg_tail_to_temp(input) {
+            match shared_utils::jxl_utils::strip_jpeg_tail_to_temp(source) {
                 Ok(Some((cleaned, guard))) => {
                     if options.verbose {
                         eprintln!("   🔧 Stripped JPEG tail; retrying with original cjxl flags");
                     }
                     (cleaned, Some(guard))
                 }
-                _ => (input.to_path_buf(), None),
+                _ => (source.to_path_buf(), None),
             };
 
         // 2) Retry with original cjxl flags (no --allow_jpeg_reconstruction 0) on fixed or original
@@ -777,7 +896,7 @@ pub fn convert_jpeg_to_jxl(
         );
         if let Ok(out) = retry_original {
             if out.status.success() {
-                let label = if source_to_use != input {
+                let label = if source_to_use != source {
                     "JPEG lossless (sanitized tail)"
                 } else {
                     "JPEG lossless"
@@ -827,11 +946,12 @@ pub fn convert_jpeg_to_jxl(
         || stderr.contains("Premature end")
     {
         match shared_utils::jxl_utils::try_imagemagick_fallback(
-            input,
+            source,
             &temp_output,
             0.0,
             max_threads,
             options.apple_compat,
+            options.strip,
         ) {
             Ok(_) => commit_jpeg_to_jxl_success(
                 input,
@@ -851,11 +971,12 @@ pub fn convert_jpeg_to_jxl(
             "   🔄 JPEG transcode failed, trying ImageMagick pipeline...",
         );
         match shared_utils::jxl_utils::try_imagemagick_fallback(
-            input,
+            source,
             &temp_output,
             0.0,
             max_threads,
             options.apple_compat,
+            options.strip,
         ) {
             Ok(_) => commit_jpeg_to_jxl_success(
                 input,
@@ -905,7 +1026,12 @@ pub fn convert_to_avif(
     let input_size = fs::metadata(input)?.len();
     let output = get_output_path(input, "avif", options)?;
 
-    if output.exists() && !options.force {
+    if shared_utils::conversion::keeps_existing_output(
+        input,
+        &output,
+        options.force,
+        options.on_collision,
+    ) {
         return Ok(ConversionResult::skipped_exists(input, &output));
     }
 
@@ -919,6 +1045,7 @@ pub fn convert_to_avif(
         .arg("all")
         .arg("-q")
         .arg(q.to_string())
+        .args(options.strip.avifenc_args())
         .arg("--")
         .arg(shared_utils::safe_path_arg(input).as_ref())
         .arg(shared_utils::safe_path_arg(&temp_output).as_ref())
@@ -943,6 +1070,7 @@ pub fn convert_to_avif(
                 options,
                 "AVIF",
                 None,
+                false,
             )
         }
         Ok(output_cmd) => {
@@ -988,7 +1116,12 @@ pub fn convert_to_avif_lossless(
     let input_size = fs::metadata(input)?.len();
     let output = get_output_path(input, "avif", options)?;
 
-    if output.exists() && !options.force {
+    if shared_utils::conversion::keeps_existing_output(
+        input,
+        &output,
+        options.force,
+        options.on_collision,
+    ) {
         return Ok(ConversionResult::skipped_exists(input, &output));
     }
 
@@ -1000,6 +1133,7 @@ pub fn convert_to_avif_lossless(
         .arg("4")
         .arg("-j")
         .arg("all")
+        .args(options.strip.avifenc_args())
         .arg("--")
         .arg(shared_utils::safe_path_arg(input).as_ref())
         .arg(shared_utils::safe_path_arg(&temp_output).as_ref())
@@ -1024,6 +1158,7 @@ pub fn convert_to_avif_lossless(
                 options,
                 "Lossless AVIF",
                 None,
+                true,
             )
         }
         Ok(output_cmd) => {
@@ -1152,7 +1287,12 @@ pub fn convert_to_jxl_matched(
         fs::create_dir_all(parent)?;
     }
 
-    if output.exists() && !options.force {
+    if shared_utils::conversion::keeps_existing_output(
+        input,
+        &output,
+        options.force,
+        options.on_collision,
+    ) {
         return Ok(ConversionResult::skipped_exists(input, &output));
     }
 
@@ -1166,36 +1306,24 @@ pub fn convert_to_jxl_matched(
     } else {
         shared_utils::thread_manager::get_optimal_threads()
     };
-    let mut cmd = Command::new("cjxl");
-    cmd.arg("-d")
-        .arg(format!("{:.2}", distance))
-        .arg("-e")
-        .arg("7")
-        .arg("-j")
-        .arg(max_threads.to_string());
-
-    if options.apple_compat {
-        cmd.arg("--compress_boxes=0");
-    }
-
-    // Only disable lossless JPEG mode when input is actually JPEG and we want lossy encoding.
-    // For non-JPEG inputs this flag is a no-op, but omitting it keeps the command clean.
-    if distance > 0.0 {
-        let is_jpeg = options
-            .input_format
-            .as_deref()
-            .map(|f| f.eq_ignore_ascii_case("jpeg") || f.eq_ignore_ascii_case("jpg"))
-            .unwrap_or(false);
-        if is_jpeg {
-            cmd.arg("--lossless_jpeg=0");
-        }
-    }
-
-    cmd.arg("--")
-        .arg(shared_utils::safe_path_arg(input).as_ref())
-        .arg(shared_utils::safe_path_arg(&temp_output).as_ref());
-
-    let result = cmd.output();
+    // --auto-grayscale: feed cjxl a single-channel copy of the image.
+    let gray_input = options
+        .grayscale
+        .then(|| shared_utils::jxl_utils::convert_to_gray_png(input))
+        .flatten();
+    let cjxl_input = gray_input
+        .as_ref()
+        .map_or(input, |(path, _)| path.as_path());
+
+    let result = Command::new("cjxl")
+        .args(matched_cjxl_args(
+            cjxl_input,
+            &temp_output,
+            distance,
+            max_threads,
+            options,
+        ))
+        .output();
 
     match result {
         Ok(output_cmd) if output_cmd.status.success() => {
@@ -1216,6 +1344,7 @@ pub fn convert_to_jxl_matched(
                 options,
                 "Quality-matched JXL",
                 Some(extra),
+                false,
             )
         }
         Ok(output_cmd) => {
@@ -1247,6 +1376,7 @@ fn try_imagemagick_fallback(
     distance: f32,
     max_threads: usize,
     apple_compat: bool,
+    strip: shared_utils::StripPolicy,
 ) -> std::result::Result<(), std::io::Error> {
     shared_utils::jxl_utils::try_imagemagick_fallback(
         input,
@@ -1254,6 +1384,7 @@ fn try_imagemagick_fallback(
         distance,
         max_threads,
         apple_compat,
+        strip,
     )
 }
 
@@ -1274,6 +1405,190 @@ fn convert_to_temp_png(
     .map_err(ImgQualityError::IoError)
 }
 
+/// Still-image JXL encode a `--print-command` line is rendered for.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum JxlRoute {
+    /// [`convert_to_jxl`] at this distance
+    Distance(f32),
+    /// [`convert_jpeg_to_jxl`]
+    JpegTranscode,
+    /// [`convert_to_jxl_matched`]
+    Matched,
+}
+
+/// `--print-command`: the cjxl line `route` runs for `input`, without converting anything.
+/// Steps that write temporary files (a PNG decode, the grayscale copy, the extracted ICC
+/// profile) are `#` notes above it; the fallbacks taken when cjxl fails are not shown.
+pub fn jxl_command_preview(
+    input: &Path,
+    options: &ConvertOptions,
+    analysis: &crate::ImageAnalysis,
+    route: JxlRoute,
+) -> Result<Vec<String>> {
+    let input_size = fs::metadata(input)?.len();
+    let is_small_png = input
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("png"))
+        && input_size < crate::constants::SMALL_PNG_THRESHOLD_BYTES;
+    if matches!(route, JxlRoute::Distance(_)) && is_small_png {
+        return Ok(vec!["# skipped: small PNG (< 500KB)".to_string()]);
+    }
+    let output = get_output_path(input, "jxl", options)?;
+    if shared_utils::conversion::keeps_existing_output(
+        input,
+        &output,
+        options.force,
+        options.on_collision,
+    ) {
+        return Ok(vec![format!(
+            "# skipped: output exists ({})",
+            output.display()
+        )]);
+    }
+
+    let hdr_info = analysis.hdr_info.as_ref();
+    let max_threads = if options.child_threads > 0 {
+        options.child_threads
+    } else {
+        shared_utils::thread_manager::get_optimal_threads()
+    };
+    let (mut lines, args) = match route {
+        JxlRoute::Distance(distance) => {
+            let decoded = if shared_utils::needs_hdr_decode(hdr_info) {
+                Some("a 16-bit PNG decode (HDR)")
+            } else if matches!(
+                analysis.format.to_ascii_lowercase().as_str(),
+                "webp" | "tiff" | "bmp" | "heic" | "heif" | "gif"
+            ) {
+                Some("a PNG decode")
+            } else {
+                None
+            };
+            (
+                shared_utils::jxl_utils::cjxl_preview_notes(
+                    decoded,
+                    options.grayscale,
+                    options.preserve_icc && !options.grayscale,
+                ),
+                cjxl_distance_args(
+                    input,
+                    &output,
+                    distance,
+                    max_threads,
+                    shared_utils::image_analyzer::read_bit_depth(input),
+                    hdr_info,
+                    options,
+                    None,
+                ),
+            )
+        }
+        JxlRoute::JpegTranscode => {
+            let mut notes = shared_utils::jxl_utils::cjxl_preview_notes(None, false, true);
+            if options.normalize_orientation {
+                notes.push(
+                    "# --normalize-orientation: a rotated JPEG is first turned upright with jpegtran"
+                        .to_string(),
+                );
+            }
+            (
+                notes,
+                jpeg_transcode_cjxl_args(
+                    input,
+                    &output,
+                    options,
+                    shared_utils::thread_manager::get_optimal_threads(),
+                    None,
+                    hdr_info,
+                    None,
+                ),
+            )
+        }
+        JxlRoute::Matched => {
+            let distance = calculate_matched_distance_for_static(analysis, input_size)?;
+            (
+                shared_utils::jxl_utils::cjxl_preview_notes(None, options.grayscale, false),
+                matched_cjxl_args(input, &output, distance, max_threads, options),
+            )
+        }
+    };
+    lines.push(shared_utils::shell_command_string("cjxl", &args));
+    Ok(lines)
+}
+
+/// cjxl arguments of the distance encode in [`convert_to_jxl`]: `cjxl_input` is the source or
+/// its preprocessed copy, `icc_path` the extracted source ICC profile.
+fn cjxl_distance_args(
+    cjxl_input: &Path,
+    output: &Path,
+    distance: f32,
+    max_threads: usize,
+    source_depth: Option<u8>,
+    hdr_info: Option<&shared_utils::ColorInfo>,
+    options: &ConvertOptions,
+    icc_path: Option<&Path>,
+) -> Vec<String> {
+    let mut args = vec![
+        "-d".to_string(),
+        format!("{:.2}", distance),
+        "-e".to_string(),
+        "7".to_string(),
+        "-j".to_string(),
+        max_threads.to_string(),
+    ];
+    args.extend(shared_utils::jxl_utils::cjxl_bit_depth_arg(source_depth));
+
+    // Add HDR metadata via CICP if available
+    if let Some(cicp) = hdr_info.and_then(shared_utils::color_info_to_cicp) {
+        args.push(format!("--cicp={}", cicp));
+    }
+
+    if options.apple_compat {
+        args.push("--compress_boxes=0".to_string());
+    }
+    args.extend(options.strip.cjxl_args().iter().map(|s| s.to_string()));
+    args.extend(shared_utils::jxl_utils::icc_cjxl_args(icc_path));
+    args.extend(shared_utils::jxl_utils::cjxl_io_args(cjxl_input, output));
+    args
+}
+
+/// cjxl arguments of the matched-distance encode in [`convert_to_jxl_matched`].
+fn matched_cjxl_args(
+    cjxl_input: &Path,
+    output: &Path,
+    distance: f32,
+    max_threads: usize,
+    options: &ConvertOptions,
+) -> Vec<String> {
+    let mut args = vec![
+        "-d".to_string(),
+        format!("{:.2}", distance),
+        "-e".to_string(),
+        "7".to_string(),
+        "-j".to_string(),
+        max_threads.to_string(),
+    ];
+
+    if options.apple_compat {
+        args.push("--compress_boxes=0".to_string());
+    }
+    args.extend(options.strip.cjxl_args().iter().map(|s| s.to_string()));
+
+    // Only disable lossless JPEG mode when input is actually JPEG and we want lossy encoding.
+    // For non-JPEG inputs this flag is a no-op, but omitting it keeps the command clean.
+    if distance > 0.0 {
+        let is_jpeg = options
+            .input_format
+            .as_deref()
+            .map(|f| f.eq_ignore_ascii_case("jpeg") || f.eq_ignore_ascii_case("jpg"))
+            .unwrap_or(false);
+        if is_jpeg {
+            args.push("--lossless_jpeg=0".to_string());
+        }
+    }
+    args.extend(shared_utils::jxl_utils::cjxl_io_args(cjxl_input, output));
+    args
+}
+
 fn prepare_input_for_cjxl(
     input: &Path,
     options: &ConvertOptions,
@@ -1356,7 +1671,9 @@ fn prepare_input_for_cjxl(
                     style("Corrupted JPEG header detected, using ImageMagick to sanitize").yellow()
                 );
 
-                let temp_png_file = tempfile::Builder::new().suffix(".png").tempfile()?;
+                let temp_png_file = tempfile::Builder::new()
+                    .suffix(".png")
+                    .tempfile_in(shared_utils::conversion::scratch_dir())?;
                 let temp_png = temp_png_file.path().to_path_buf();
 
                 let result = Command::new("magick")
@@ -1398,13 +1715,20 @@ fn prepare_input_for_cjxl(
             "WebP detected, using dwebp for ICC profile compatibility",
         ),
 
-        "tiff" | "tif" => convert_to_temp_png(
-            input,
-            "magick",
-            &["--"],
-            &["-depth", "16", "__OUTPUT__"],
-            "TIFF detected, using ImageMagick for cjxl compatibility",
-        ),
+        "tiff" | "tif" => {
+            // 8-bit stays 8-bit; deeper sources (12/16-bit, float) are handed over as 16-bit.
+            let depth = match shared_utils::image_analyzer::read_bit_depth(input) {
+                Some(8) => "8",
+                _ => "16",
+            };
+            convert_to_temp_png(
+                input,
+                "magick",
+                &["--"],
+                &["-depth", depth, "__OUTPUT__"],
+                "TIFF detected, using ImageMagick for cjxl compatibility",
+            )
+        }
 
         "bmp" => convert_to_temp_png(
             input,
@@ -1422,7 +1746,9 @@ fn prepare_input_for_cjxl(
                 style("HEIC/HEIF detected, using sips/ImageMagick for cjxl compatibility").dim()
             );
 
-            let temp_png_file = tempfile::Builder::new().suffix(".png").tempfile()?;
+            let temp_png_file = tempfile::Builder::new()
+                .suffix(".png")
+                .tempfile_in(shared_utils::conversion::scratch_dir())?;
             let temp_png = temp_png_file.path().to_path_buf();
 
             eprintln!("   🍎 Trying macOS sips first...");
@@ -1482,7 +1808,7 @@ fn prepare_input_for_cjxl(
 
                     let temp_aligned_file = tempfile::Builder::new()
                         .suffix(&format!(".{}", ext))
-                        .tempfile()?;
+                        .tempfile_in(shared_utils::conversion::scratch_dir())?;
                     let temp_path = temp_aligned_file.path().to_path_buf();
 
                     if std::fs::copy(input, &temp_path).is_ok() {
@@ -1511,11 +1837,17 @@ fn get_output_path(
             base,
             extension,
             &options.output_dir,
+            options.on_collision,
         )
         .map_err(ImgQualityError::ConversionError)?
     } else {
-        shared_utils::conversion::determine_output_path(input, extension, &options.output_dir)
-            .map_err(ImgQualityError::ConversionError)?
+        shared_utils::conversion::determine_output_path(
+            input,
+            extension,
+            &options.output_dir,
+            options.on_collision,
+        )
+        .map_err(ImgQualityError::ConversionError)?
     };
 
     // Validate output path (check path traversal, symlinks)
@@ -1537,6 +1869,23 @@ pub fn is_high_quality_animated(width: u32, height: u32) -> bool {
     vid_hevc::animated_image::is_high_quality_animated(width, height)
 }
 
+/// Convert an animated image to animated WebP (libwebp_anim), preserving alpha.
+/// See `shared_utils::animated_webp` for the quality-matching behaviour.
+pub fn convert_to_animated_webp(
+    input: &Path,
+    options: &ConvertOptions,
+) -> Result<ConversionResult> {
+    shared_utils::animated_webp::convert_to_animated_webp(input, options)
+        .map_err(ImgQualityError::ConversionError)
+}
+
+/// Convert a short animated GIF to lossless APNG (FFmpeg `apng` muxer).
+/// No SSIM judge; the original is copied through unless the APNG is smaller.
+pub fn convert_to_apng(input: &Path, options: &ConvertOptions) -> Result<ConversionResult> {
+    shared_utils::animated_apng::convert_to_apng(input, options)
+        .map_err(ImgQualityError::ConversionError)
+}
+
 fn verify_jxl_health(path: &Path) -> Result<()> {
     shared_utils::jxl_utils::verify_jxl_health(path).map_err(ImgQualityError::ConversionError)
 }
@@ -1558,6 +1907,65 @@ mod tests {
         assert_eq!(output, Path::new("/path/to/image.JXL"));
     }
 
+    #[test]
+    fn test_cjxl_args_attach_icc_before_the_paths() {
+        let options = ConvertOptions {
+            apple_compat: true,
+            ..Default::default()
+        };
+        let args = cjxl_distance_args(
+            Path::new("in.png"),
+            Path::new("out.jxl"),
+            0.0,
+            4,
+            None,
+            None,
+            &options,
+            Some(Path::new("/tmp/src.icc")),
+        );
+        assert_eq!(
+            args,
+            [
+                "-d",
+                "0.00",
+                "-e",
+                "7",
+                "-j",
+                "4",
+                "--compress_boxes=0",
+                "-x",
+                "icc_pathname=/tmp/src.icc",
+                "--",
+                "in.png",
+                "out.jxl"
+            ]
+        );
+
+        let args = jpeg_transcode_cjxl_args(
+            Path::new("in.jpg"),
+            Path::new("out.jxl"),
+            &options,
+            4,
+            Some(0),
+            None,
+            None,
+        );
+        assert_eq!(
+            args,
+            [
+                "--lossless_jpeg=1",
+                "-j",
+                "4",
+                "--allow_jpeg_reconstruction",
+                "0",
+                "--compress_boxes=0",
+                "--",
+                "in.jpg",
+                "out.jxl"
+            ]
+        );
+    }
+
     #[test]
     fn test_get_output_path_with_dir() {
         let input = Path::new("/path/to/image.png");
@@ -1682,4 +2090,162 @@ mod tests {
             );
         }
     }
+
+    /// Minimal ICC v2 display profile with Display P3 primaries and a gamma 2.2 curve.
+    fn display_p3_icc() -> Vec<u8> {
+        fn s15(v: f64) -> [u8; 4] {
+            ((v * 65536.0).round() as i32).to_be_bytes()
+        }
+        fn xyz(x: f64, y: f64, z: f64) -> Vec<u8> {
+            [b"XYZ \0\0\0\0".as_slice(), &s15(x), &s15(y), &s15(z)].concat()
+        }
+        let text = b"Display P3\0";
+        let desc = [
+            b"desc\0\0\0\0".as_slice(),
+            &(text.len() as u32).to_be_bytes(),
+            text,
+            &[0u8; 78],
+        ]
+        .concat();
+        let curv = [
+            b"curv\0\0\0\0".as_slice(),
+            &1u32.to_be_bytes(),
+            &[0x02, 0x33, 0, 0],
+        ]
+        .concat();
+        let tags: [(&[u8; 4], Vec<u8>); 8] = [
+            (b"desc", desc),
+            (b"wtpt", xyz(0.9642, 1.0, 0.8249)),
+            (b"rXYZ", xyz(0.5151, 0.2412, -0.0011)),
+            (b"gXYZ", xyz(0.2919, 0.6922, 0.0419)),
+            (b"bXYZ", xyz(0.1572, 0.0666, 0.7841)),
+            (b"rTRC", curv.clone()),
+            (b"gTRC", curv.clone()),
+            (b"bTRC", curv),
+        ];
+
+        let base = 128 + 4 + tags.len() * 12;
+        let mut table = (tags.len() as u32).to_be_bytes().to_vec();
+        let mut data = Vec::new();
+        for (signature, body) in &tags {
+            while data.len() % 4 != 0 {
+                data.push(0);
+            }
+            table.extend_from_slice(*signature);
+            table.extend_from_slice(&((base + data.len()) as u32).to_be_bytes());
+            table.extend_from_slice(&(body.len() as u32).to_be_bytes());
+            data.extend_from_slice(body);
+        }
+
+        let mut icc = vec![0u8; 128];
+        icc[8..12].copy_from_slice(&[2, 0x10, 0, 0]);
+        icc[12..16].copy_from_slice(b"mntr");
+        icc[16..20].copy_from_slice(b"RGB ");
+        icc[20..24].copy_from_slice(b"XYZ ");
+        icc[36..40].copy_from_slice(b"acsp");
+        icc[68..80].copy_from_slice(&xyz(0.9642, 1.0, 0.8249)[8..]);
+        icc.extend(table);
+        icc.extend(data);
+        let size = icc.len() as u32;
+        icc[0..4].copy_from_slice(&size.to_be_bytes());
+        icc
+    }
+
+    #[test]
+    fn test_p3_png_to_jxl_keeps_icc_profile() {
+        use image::ImageEncoder;
+        use shared_utils::image_analyzer::{analyze_image, detect_icc_profile, ColorGamut};
+
+        let temp = tempfile::TempDir::new().unwrap();
+        let png = temp.path().join("p3.png");
+
+        // Noise, so the PNG stays above the small-PNG skip threshold.
+        let (width, height) = (640u32, 640u32);
+        let mut seed = 0x2545_f491_u32;
+        let pixels: Vec<u8> = (0..width * height * 3)
+            .map(|_| {
+                seed ^= seed << 13;
+                seed ^= seed >> 17;
+                seed ^= seed << 5;
+                (seed >> 24) as u8
+            })
+            .collect();
+        let mut encoder = image::codecs::png::PngEncoder::new(std::fs::File::create(&png).unwrap());
+        encoder.set_icc_profile(display_p3_icc()).unwrap();
+        encoder
+            .write_image(&pixels, width, height, image::ExtendedColorType::Rgb8)
+            .unwrap();
+
+        let profile = analyze_image(&png).unwrap().icc_profile.unwrap();
+        assert_eq!(profile.description, "Display P3");
+        assert_eq!(profile.gamut, ColorGamut::DisplayP3);
+        assert!(profile.gamut.is_wide());
+
+        if which::which("cjxl").is_err() || which::which("djxl").is_err() {
+            eprintln!("cjxl/djxl not found, skipping JXL round trip");
+            return;
+        }
+        let options = ConvertOptions {
+            force: true,
+            output_dir: Some(temp.path().join("out")),
+            ..Default::default()
+        };
+        let result = convert_to_jxl(&png, &options, 1.0, None).unwrap();
+        let jxl = PathBuf::from(result.output_path.expect("JXL written"));
+
+        let decoded = temp.path().join("decoded.png");
+        let status = Command::new("djxl")
+            .arg(&jxl)
+            .arg(&decoded)
+            .status()
+            .unwrap();
+        assert!(status.success());
+        let kept = detect_icc_profile(&decoded).expect("decoded JXL has an ICC profile");
+        assert_eq!(kept.gamut, ColorGamut::DisplayP3);
+    }
+
+    #[test]
+    fn test_16bit_gray_tiff_to_lossless_jxl_round_trip() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let tiff = temp.path().join("scan.tif");
+
+        // Every 16-bit value once, as a compressible ramp: any reduction to 8 (or 12) bits
+        // changes the low bits.
+        let (width, height) = (256u32, 256u32);
+        let pixels: Vec<u16> = (0..width * height).map(|i| i as u16).collect();
+        let source =
+            image::ImageBuffer::<image::Luma<u16>, _>::from_raw(width, height, pixels).unwrap();
+        source.save(&tiff).unwrap();
+        assert_eq!(
+            shared_utils::image_analyzer::read_bit_depth(&tiff),
+            Some(16)
+        );
+
+        if ["cjxl", "djxl", "magick"]
+            .iter()
+            .any(|tool| which::which(tool).is_err())
+        {
+            eprintln!("cjxl/djxl/magick not found, skipping 16-bit JXL round trip");
+            return;
+        }
+        let options = ConvertOptions {
+            force: true,
+            output_dir: Some(temp.path().join("out")),
+            ..Default::default()
+        };
+        let result = convert_to_jxl(&tiff, &options, 0.0, None).unwrap();
+        let jxl = PathBuf::from(result.output_path.expect("JXL written"));
+
+        let decoded = temp.path().join("decoded.png");
+        let status = Command::new("djxl")
+            .arg(&jxl)
+            .arg(&decoded)
+            .status()
+            .unwrap();
+        assert!(status.success());
+        let decoded = image::open(&decoded).unwrap();
+        assert_eq!(decoded.color(), image::ColorType::L16);
+        // PSNR ∞: every sample identical.
+        assert!(decoded.to_luma16().as_raw() == source.as_raw());
+    }
 }
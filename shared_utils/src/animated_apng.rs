@@ -0,0 +1,220 @@
+//! Animated PNG (APNG) Output
+//!
+//! Re-muxes short animated GIFs into APNG with FFmpeg's `apng` muxer, a lossless
+//! alternative to the HEVC/AV1 video route for clips too short to be worth a video.
+//!
+//! - Frames are stored as `rgba` when the source has (or may have) transparency, so
+//!   palette colours and the transparent index survive exactly; `rgb24` otherwise.
+//! - No SSIM judge: the encode is lossless by construction. The APNG is kept only when
+//!   it is strictly smaller than the input; otherwise the original is copied through.
+
+use crate::conversion::{
+    commit_temp_to_output_with_metadata, determine_output_path, determine_output_path_with_base,
+    finalize_conversion, is_already_processed, temp_path_for_output, validate_input_file,
+    validate_output_path, ConversionResult, ConvertOptions,
+};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// GIFs shorter than this (seconds) go to APNG under `--target apng`; longer ones keep the
+/// normal video route.
+pub const ANIMATION_DURATION_THRESHOLD_SECS: f32 = 3.0;
+
+/// `--target apng` routing: only short GIFs are re-muxed to APNG. `format` is the detected
+/// image format name (`"GIF"`).
+pub fn routes_to_apng(format: &str, duration_secs: f32) -> bool {
+    format.eq_ignore_ascii_case("gif") && duration_secs < ANIMATION_DURATION_THRESHOLD_SECS
+}
+
+/// Pixel format passed to FFmpeg's `apng` encoder.
+///
+/// Per-frame GIF palettes cannot share a single PLTE chunk, so palette sources are
+/// expanded to RGBA rather than kept as `pal8`.
+fn apng_pix_fmt(has_alpha: bool) -> &'static str {
+    if has_alpha {
+        "rgba"
+    } else {
+        "rgb24"
+    }
+}
+
+fn source_has_alpha(input: &Path) -> bool {
+    match crate::probe_video(input) {
+        Ok(probe) => crate::animated_webp::pix_fmt_has_alpha(&probe.pix_fmt),
+        // Unknown: keep alpha rather than risk flattening transparency onto black.
+        Err(_) => true,
+    }
+}
+
+fn encode_apng(
+    input: &Path,
+    output: &Path,
+    has_alpha: bool,
+    threads: usize,
+    strip: crate::metadata::StripPolicy,
+) -> Result<(), String> {
+    let out = Command::new("ffmpeg")
+        .arg("-y")
+        .arg("-i")
+        .arg(crate::safe_path_arg(input).as_ref())
+        .arg("-map")
+        .arg("0:v:0")
+        .arg("-an")
+        .arg("-vsync")
+        .arg("passthrough")
+        .arg("-c:v")
+        .arg("apng")
+        .arg("-pred")
+        .arg("mixed")
+        .arg("-pix_fmt")
+        .arg(apng_pix_fmt(has_alpha))
+        .arg("-plays")
+        .arg("0")
+        .arg("-threads")
+        .arg(threads.to_string())
+        .arg("-f")
+        .arg("apng")
+        .args(strip.ffmpeg_args())
+        .arg(crate::safe_path_arg(output).as_ref())
+        .output()
+        .map_err(|e| format!("ffmpeg not found: {}", e))?;
+    if !out.status.success() {
+        let stderr = String::from_utf8_lossy(&out.stderr);
+        return Err(format!("ffmpeg apng failed: {}", stderr.trim()));
+    }
+    if fs::metadata(output).map(|m| m.len()).unwrap_or(0) == 0 {
+        return Err("ffmpeg apng produced empty output".to_string());
+    }
+    Ok(())
+}
+
+fn cleanup_temp_output(temp_output: &Path) {
+    if let Err(e) = fs::remove_file(temp_output) {
+        if e.kind() != std::io::ErrorKind::NotFound {
+            eprintln!(
+                "⚠️ [apng] Failed to remove temporary output {}: {}",
+                temp_output.display(),
+                e
+            );
+        }
+    }
+}
+
+fn get_output_path(input: &Path, options: &ConvertOptions) -> Result<PathBuf, String> {
+    let output = if let Some(ref base) = options.base_dir {
+        determine_output_path_with_base(
+            input,
+            base,
+            "png",
+            &options.output_dir,
+            options.on_collision,
+        )?
+    } else {
+        determine_output_path(input, "png", &options.output_dir, options.on_collision)?
+    };
+    validate_output_path(&output, options.base_dir.as_deref())?;
+    Ok(output)
+}
+
+/// Convert an animated GIF to lossless APNG.
+///
+/// Returns a skipped result (with the original copied to the output directory) when the
+/// APNG is not smaller than the input.
+pub fn convert_to_apng(input: &Path, options: &ConvertOptions) -> Result<ConversionResult, String> {
+    validate_input_file(input)?;
+
+    if !options.force && is_already_processed(input) {
+        return Ok(ConversionResult::skipped_duplicate(input));
+    }
+
+    let input_size = fs::metadata(input).map_err(|e| e.to_string())?.len();
+
+    let output = get_output_path(input, options)?;
+    if crate::conversion::keeps_existing_output(input, &output, options.force, options.on_collision)
+    {
+        return Ok(ConversionResult::skipped_exists(input, &output));
+    }
+    if let Some(parent) = output.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+
+    let temp_output = temp_path_for_output(&output);
+    let has_alpha = source_has_alpha(input);
+    let threads = if options.child_threads > 0 {
+        options.child_threads
+    } else {
+        2
+    };
+
+    if let Err(e) = encode_apng(input, &temp_output, has_alpha, threads, options.strip) {
+        cleanup_temp_output(&temp_output);
+        return Err(e);
+    }
+
+    let output_size = fs::metadata(&temp_output).map_err(|e| e.to_string())?.len();
+    if output_size >= input_size {
+        cleanup_temp_output(&temp_output);
+        if options.verbose {
+            eprintln!(
+                "   ⏭️  APNG not smaller than source ({} → {} bytes), keeping original",
+                input_size, output_size
+            );
+        }
+        crate::copy_on_skip_or_fail(
+            input,
+            options.output_dir.as_deref(),
+            options.base_dir.as_deref(),
+            options.verbose,
+        )
+        .map_err(|e| e.to_string())?;
+        return Ok(ConversionResult::skipped_custom(
+            input,
+            input_size,
+            "Skipped: APNG not smaller than source",
+            "apng_larger",
+        ));
+    }
+
+    if !commit_temp_to_output_with_metadata(
+        &temp_output,
+        &output,
+        options.force,
+        Some(input),
+        options.strip,
+        options.on_collision,
+    )
+    .map_err(|e| e.to_string())?
+    {
+        return Ok(ConversionResult::skipped_exists(input, &output));
+    }
+
+    let extra = if has_alpha {
+        "lossless, alpha"
+    } else {
+        "lossless"
+    };
+    finalize_conversion(input, &output, input_size, "APNG", Some(extra), options)
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apng_pix_fmt_keeps_transparency() {
+        assert_eq!(apng_pix_fmt(true), "rgba");
+        assert_eq!(apng_pix_fmt(false), "rgb24");
+    }
+
+    #[test]
+    fn test_routes_only_short_gifs_to_apng() {
+        assert!(routes_to_apng("GIF", 1.5));
+        assert!(routes_to_apng("GIF", 0.0));
+        assert!(!routes_to_apng("GIF", ANIMATION_DURATION_THRESHOLD_SECS));
+        assert!(!routes_to_apng("GIF", 12.0));
+        assert!(!routes_to_apng("WebP", 1.5));
+        assert!(!routes_to_apng("PNG", 1.5));
+    }
+}
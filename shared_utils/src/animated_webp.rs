@@ -0,0 +1,419 @@
+//! Animated WebP Output
+//!
+//! Encodes animated sources (GIF, APNG, animated AVIF/HEIC) to animated WebP with
+//! FFmpeg's `libwebp_anim` encoder, for users who want broad browser support rather
+//! than an HEVC/AV1 video container.
+//!
+//! - Alpha is preserved (`yuva420p` for lossy, `bgra` for lossless) when the source has it.
+//! - With `match_quality`, the WebP `-quality` value is binary-searched for the lowest
+//!   setting whose SSIM against the source still meets the threshold.
+//! - Output larger than the input is discarded via `check_size_tolerance`, as on the
+//!   other animated paths.
+
+use crate::conversion::{
+    check_size_tolerance, commit_temp_to_output_with_metadata, determine_output_path,
+    determine_output_path_with_base, finalize_conversion, is_already_processed, mark_as_processed,
+    temp_path_for_output, validate_input_file, validate_output_path, ConversionResult,
+    ConvertOptions,
+};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Lowest WebP quality considered by the quality-matching search.
+pub const WEBP_QUALITY_MIN: u8 = 40;
+/// Highest WebP quality (lossy) considered by the quality-matching search.
+pub const WEBP_QUALITY_MAX: u8 = 100;
+/// Quality used when SSIM cannot be measured (e.g. FFmpeg build cannot decode animated WebP).
+pub const WEBP_QUALITY_FALLBACK: u8 = 90;
+/// Minimum SSIM (All) a quality-matched WebP must reach.
+pub const WEBP_MIN_SSIM: f64 = 0.95;
+/// Minimum SSIM in ultimate mode.
+pub const WEBP_MIN_SSIM_ULTIMATE: f64 = 0.98;
+
+/// Returns true when an FFmpeg pixel format carries an alpha channel.
+/// `pal8` is included because GIF/PNG palettes may contain a transparent entry.
+pub fn pix_fmt_has_alpha(pix_fmt: &str) -> bool {
+    let fmt = pix_fmt.to_ascii_lowercase();
+    fmt == "pal8"
+        || fmt.starts_with("yuva")
+        || fmt.starts_with("gbrap")
+        || fmt.starts_with("ya")
+        || matches!(
+            fmt.trim_end_matches("le").trim_end_matches("be"),
+            "rgba" | "bgra" | "argb" | "abgr" | "rgba64" | "bgra64"
+        )
+}
+
+fn source_has_alpha(input: &Path) -> bool {
+    match crate::probe_video(input) {
+        Ok(probe) => pix_fmt_has_alpha(&probe.pix_fmt),
+        // Unknown: keep alpha rather than risk flattening transparency onto black.
+        Err(_) => true,
+    }
+}
+
+/// Frames decoded by [`animation_has_transparency`]; a transparent background shows at once.
+const TRANSPARENCY_PROBE_FRAMES: u32 = 10;
+
+/// Whether an animated source actually shows transparency. An alpha-capable pixel format
+/// alone does not count (GIF always decodes with one): one of the first frames must have a
+/// pixel with alpha below 255. Animated WebP, which FFmpeg may not decode, answers from its
+/// VP8X alpha flag.
+pub fn animation_has_transparency(input: &Path) -> bool {
+    if let Some(alpha) = webp_alpha_flag(input) {
+        return alpha;
+    }
+    if !source_has_alpha(input) {
+        return false;
+    }
+    let decoded = Command::new("ffmpeg")
+        .args(["-v", "error", "-i"])
+        .arg(crate::safe_path_arg(input).as_ref())
+        .arg("-frames:v")
+        .arg(TRANSPARENCY_PROBE_FRAMES.to_string())
+        .args([
+            "-vf",
+            "alphaextract,scale='min(iw,320)':-2:flags=area",
+            "-f",
+            "rawvideo",
+            "-pix_fmt",
+            "gray",
+            "-",
+        ])
+        .output();
+    match decoded {
+        Ok(out) if out.status.success() => out.stdout.iter().any(|&alpha| alpha < u8::MAX),
+        // Undecodable: assume the alpha channel is used, as `source_has_alpha` does.
+        _ => true,
+    }
+}
+
+/// The alpha flag of an extended (VP8X) WebP header; `None` for anything else.
+fn webp_alpha_flag(input: &Path) -> Option<bool> {
+    use std::io::Read;
+    let mut header = [0u8; 21];
+    fs::File::open(input).ok()?.read_exact(&mut header).ok()?;
+    let is_vp8x =
+        &header[0..4] == b"RIFF" && &header[8..12] == b"WEBP" && &header[12..16] == b"VP8X";
+    is_vp8x.then_some(header[20] & 0x10 != 0)
+}
+
+/// Pixel format passed to libwebp_anim.
+fn webp_pix_fmt(lossless: bool, has_alpha: bool) -> &'static str {
+    if lossless {
+        "bgra"
+    } else if has_alpha {
+        "yuva420p"
+    } else {
+        "yuv420p"
+    }
+}
+
+/// Binary search for the lowest quality in `[lo, hi]` for which `meets` returns `Some(true)`.
+/// Assumes quality is monotonic. Returns `hi` when nothing lower qualifies, and `None` as
+/// soon as `meets` cannot judge a candidate (SSIM unavailable).
+fn search_min_quality<F>(lo: u8, hi: u8, mut meets: F) -> Option<u8>
+where
+    F: FnMut(u8) -> Option<bool>,
+{
+    let (mut lo, mut hi) = (lo, hi);
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+        if meets(mid)? {
+            hi = mid;
+        } else {
+            lo = mid + 1;
+        }
+    }
+    Some(hi)
+}
+
+fn encode_animated_webp(
+    input: &Path,
+    output: &Path,
+    quality: Option<u8>,
+    has_alpha: bool,
+    threads: usize,
+    strip: crate::metadata::StripPolicy,
+) -> Result<(), String> {
+    let mut cmd = Command::new("ffmpeg");
+    cmd.arg("-y")
+        .arg("-i")
+        .arg(crate::safe_path_arg(input).as_ref())
+        .arg("-map")
+        .arg("0:v:0")
+        .arg("-an")
+        .arg("-vsync")
+        .arg("vfr")
+        .arg("-c:v")
+        .arg("libwebp_anim")
+        .arg("-loop")
+        .arg("0")
+        .arg("-compression_level")
+        .arg("6")
+        .arg("-threads")
+        .arg(threads.to_string());
+    match quality {
+        Some(q) => {
+            cmd.arg("-quality").arg(q.to_string());
+        }
+        None => {
+            cmd.arg("-lossless").arg("1");
+        }
+    }
+    cmd.arg("-pix_fmt")
+        .arg(webp_pix_fmt(quality.is_none(), has_alpha))
+        .args(strip.ffmpeg_args())
+        .arg(crate::safe_path_arg(output).as_ref());
+
+    let out = cmd
+        .output()
+        .map_err(|e| format!("ffmpeg not found: {}", e))?;
+    if !out.status.success() {
+        let stderr = String::from_utf8_lossy(&out.stderr);
+        return Err(format!("ffmpeg libwebp_anim failed: {}", stderr.trim()));
+    }
+    if fs::metadata(output).map(|m| m.len()).unwrap_or(0) == 0 {
+        return Err("ffmpeg libwebp_anim produced empty output".to_string());
+    }
+    Ok(())
+}
+
+fn cleanup_temp_output(temp_output: &Path) {
+    if let Err(e) = fs::remove_file(temp_output) {
+        if e.kind() != std::io::ErrorKind::NotFound {
+            eprintln!(
+                "⚠️ [animated-webp] Failed to remove temporary output {}: {}",
+                temp_output.display(),
+                e
+            );
+        }
+    }
+}
+
+fn get_output_path(input: &Path, options: &ConvertOptions) -> Result<PathBuf, String> {
+    let output = if let Some(ref base) = options.base_dir {
+        determine_output_path_with_base(
+            input,
+            base,
+            "webp",
+            &options.output_dir,
+            options.on_collision,
+        )?
+    } else {
+        determine_output_path(input, "webp", &options.output_dir, options.on_collision)?
+    };
+    validate_output_path(&output, options.base_dir.as_deref())?;
+    Ok(output)
+}
+
+/// Convert an animated image to animated WebP.
+///
+/// Lossless (`-lossless 1`) unless `options.match_quality` is set, in which case the
+/// lowest lossy quality meeting [`WEBP_MIN_SSIM`] (or [`WEBP_MIN_SSIM_ULTIMATE`]) is used.
+pub fn convert_to_animated_webp(
+    input: &Path,
+    options: &ConvertOptions,
+) -> Result<ConversionResult, String> {
+    validate_input_file(input)?;
+
+    if !options.force && is_already_processed(input) {
+        return Ok(ConversionResult::skipped_duplicate(input));
+    }
+
+    let input_size = fs::metadata(input).map_err(|e| e.to_string())?.len();
+
+    let input_ext = input
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_lowercase())
+        .unwrap_or_default();
+    match input_ext.as_str() {
+        "webp" => {
+            mark_as_processed(input);
+            return Ok(ConversionResult::skipped_custom(
+                input,
+                input_size,
+                "Skipped: Already animated WebP",
+                "already_webp",
+            ));
+        }
+        // FFmpeg's jpegxl_anim decoder is incomplete; see the GIF apple-compat path.
+        "jxl" => {
+            return Ok(ConversionResult::skipped_custom(
+                input,
+                input_size,
+                "Skipped: Animated JXL → WebP is not supported",
+                "unsupported_source",
+            ));
+        }
+        _ => {}
+    }
+
+    let output = get_output_path(input, options)?;
+    if crate::conversion::keeps_existing_output(input, &output, options.force, options.on_collision)
+    {
+        return Ok(ConversionResult::skipped_exists(input, &output));
+    }
+    if let Some(parent) = output.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+
+    let temp_output = temp_path_for_output(&output);
+    let has_alpha = source_has_alpha(input);
+    let threads = if options.child_threads > 0 {
+        options.child_threads
+    } else {
+        2
+    };
+
+    let quality = if options.match_quality {
+        let min_ssim = if options.ultimate {
+            WEBP_MIN_SSIM_ULTIMATE
+        } else {
+            WEBP_MIN_SSIM
+        };
+        let searched = search_min_quality(WEBP_QUALITY_MIN, WEBP_QUALITY_MAX, |q| {
+            if let Err(e) = encode_animated_webp(
+                input,
+                &temp_output,
+                Some(q),
+                has_alpha,
+                threads,
+                options.strip,
+            ) {
+                if options.verbose {
+                    eprintln!("   ⚠️  WebP q={} encode failed: {}", q, e);
+                }
+                return None;
+            }
+            let ssim = crate::video_explorer::calculate_ssim_all(input, &temp_output, &[])
+                .map(|(_, _, _, all)| all)?;
+            if options.verbose {
+                eprintln!("   🔍 WebP q={} → SSIM {:.4}", q, ssim);
+            }
+            Some(ssim >= min_ssim)
+        });
+        Some(searched.unwrap_or_else(|| {
+            eprintln!(
+                "   ⚠️  WebP SSIM unavailable, using quality {}",
+                WEBP_QUALITY_FALLBACK
+            );
+            WEBP_QUALITY_FALLBACK
+        }))
+    } else {
+        None
+    };
+
+    if let Err(e) = encode_animated_webp(
+        input,
+        &temp_output,
+        quality,
+        has_alpha,
+        threads,
+        options.strip,
+    ) {
+        cleanup_temp_output(&temp_output);
+        return Err(e);
+    }
+
+    let output_size = fs::metadata(&temp_output).map_err(|e| e.to_string())?.len();
+
+    if !commit_temp_to_output_with_metadata(
+        &temp_output,
+        &output,
+        options.force,
+        Some(input),
+        options.strip,
+        options.on_collision,
+    )
+    .map_err(|e| e.to_string())?
+    {
+        return Ok(ConversionResult::skipped_exists(input, &output));
+    }
+
+    if let Some(skipped) =
+        check_size_tolerance(input, &output, input_size, output_size, options, "WebP")
+    {
+        return Ok(skipped);
+    }
+
+    let extra = match quality {
+        Some(q) => format!("q={}{}", q, if has_alpha { ", alpha" } else { "" }),
+        None => format!("lossless{}", if has_alpha { ", alpha" } else { "" }),
+    };
+    finalize_conversion(input, &output, input_size, "WebP", Some(&extra), options)
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_webp_alpha_flag() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let webp = |flags: u8| {
+            let mut bytes = b"RIFF\0\0\0\0WEBPVP8X\x0a\0\0\0".to_vec();
+            bytes.push(flags);
+            bytes.extend_from_slice(&[0; 9]);
+            bytes
+        };
+        let transparent = dir.path().join("transparent.webp");
+        let opaque = dir.path().join("opaque.webp");
+        let gif = dir.path().join("anim.gif");
+        fs::write(&transparent, webp(0x12)).unwrap();
+        fs::write(&opaque, webp(0x02)).unwrap();
+        fs::write(&gif, b"GIF89a\x01\0\x01\0\0\0\0\0\0\0\0\0\0\0\0").unwrap();
+
+        assert_eq!(webp_alpha_flag(&transparent), Some(true));
+        assert_eq!(webp_alpha_flag(&opaque), Some(false));
+        assert_eq!(webp_alpha_flag(&gif), None);
+    }
+
+    #[test]
+    fn test_pix_fmt_has_alpha() {
+        assert!(pix_fmt_has_alpha("rgba"));
+        assert!(pix_fmt_has_alpha("bgra"));
+        assert!(pix_fmt_has_alpha("pal8"));
+        assert!(pix_fmt_has_alpha("yuva420p"));
+        assert!(pix_fmt_has_alpha("rgba64le"));
+        assert!(pix_fmt_has_alpha("gbrap10le"));
+        assert!(!pix_fmt_has_alpha("yuv420p"));
+        assert!(!pix_fmt_has_alpha("rgb24"));
+        assert!(!pix_fmt_has_alpha("gray"));
+    }
+
+    #[test]
+    fn test_webp_pix_fmt() {
+        assert_eq!(webp_pix_fmt(true, true), "bgra");
+        assert_eq!(webp_pix_fmt(false, true), "yuva420p");
+        assert_eq!(webp_pix_fmt(false, false), "yuv420p");
+    }
+
+    #[test]
+    fn test_search_min_quality_finds_threshold() {
+        let mut probes = 0;
+        let q = search_min_quality(40, 100, |q| {
+            probes += 1;
+            Some(q >= 73)
+        });
+        assert_eq!(q, Some(73));
+        assert!(
+            probes <= 7,
+            "binary search should need ≤7 probes, got {}",
+            probes
+        );
+    }
+
+    #[test]
+    fn test_search_min_quality_nothing_meets() {
+        assert_eq!(search_min_quality(40, 100, |_| Some(false)), Some(100));
+    }
+
+    #[test]
+    fn test_search_min_quality_aborts_without_ssim() {
+        assert_eq!(search_min_quality(40, 100, |_| None), None);
+    }
+}
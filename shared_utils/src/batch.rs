@@ -5,18 +5,20 @@
 //!
 //! 🔥 v7.5: 添加文件排序功能，优先处理小文件
 
+use crate::conversion_types::{EncodeBenchmark, SkipReason};
 use crate::file_sorter::{sort_by_size_ascending, SortStrategy};
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
 use std::fs;
 use std::io;
 use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Mutex;
-use std::time::UNIX_EPOCH;
+use std::time::{Duration, UNIX_EPOCH};
 use tracing::{debug, warn};
 use walkdir::WalkDir;
 
-const PATH_TREE_CACHE_SCHEMA_VERSION: u32 = 1;
+const PATH_TREE_CACHE_SCHEMA_VERSION: u32 = 2;
 const PATH_TREE_CACHE_DIR: &str = "path_tree";
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -39,6 +41,7 @@ struct CachedImageTreeSnapshot {
     schema_version: u32,
     root: PathBuf,
     recursive: bool,
+    follow_symlinks: bool,
     extensions: Vec<String>,
     directories: Vec<CachedDirectoryState>,
     files: Vec<CachedImageSortEntry>,
@@ -60,17 +63,58 @@ struct CachedVideoTreeSnapshot {
     schema_version: u32,
     root: PathBuf,
     recursive: bool,
+    follow_symlinks: bool,
     extensions: Vec<String>,
     directories: Vec<CachedDirectoryState>,
     files: Vec<CachedVideoSortEntry>,
 }
 
-pub fn collect_files(dir: &Path, extensions: &[&str], recursive: bool) -> Vec<PathBuf> {
+/// `--follow-symlinks`: directory walks descend into symlinked directories and pick up
+/// symlinked files. Off by default, so symlinks are ignored during collection.
+static FOLLOW_SYMLINKS: AtomicBool = AtomicBool::new(false);
+
+pub fn set_follow_symlinks(enabled: bool) {
+    FOLLOW_SYMLINKS.store(enabled, Ordering::Relaxed);
+}
+
+pub fn follow_symlinks() -> bool {
+    FOLLOW_SYMLINKS.load(Ordering::Relaxed)
+}
+
+/// Walk `root` (only its direct entries unless `recursive`) under the `--follow-symlinks` policy.
+///
+/// When following, a link back to an ancestor is reported as a walkdir loop error instead of being
+/// descended, and every other entry is keyed by its canonical path so that two links to the same
+/// directory (or file) yield it once.
+pub fn walk_dir(
+    root: &Path,
+    recursive: bool,
+) -> impl Iterator<Item = walkdir::Result<walkdir::DirEntry>> {
+    let follow = follow_symlinks();
+    let walker = WalkDir::new(root).follow_links(follow);
     let walker = if recursive {
-        WalkDir::new(dir).follow_links(true)
+        walker
     } else {
-        WalkDir::new(dir).max_depth(1)
+        walker.max_depth(1)
     };
+    let mut visited: HashSet<PathBuf> = HashSet::new();
+    walker.into_iter().filter_entry(move |entry| {
+        if !follow {
+            return true;
+        }
+        let Ok(canonical) = entry.path().canonicalize() else {
+            return true;
+        };
+        let first_visit = visited.insert(canonical);
+        if !first_visit {
+            debug!(path = %entry.path().display(), "Skipping path already visited through a symlink");
+        }
+        first_visit
+    })
+}
+
+pub fn collect_files(dir: &Path, extensions: &[&str], recursive: bool) -> Vec<PathBuf> {
+    let walker = walk_dir(dir, recursive);
 
     let mut files = Vec::new();
     for entry in walker.into_iter() {
@@ -113,6 +157,159 @@ pub fn collect_files_small_first(dir: &Path, extensions: &[&str], recursive: boo
     collect_files_sorted(dir, extensions, recursive, SortStrategy::SizeAscending)
 }
 
+/// `--limit N`: keep the first `limit` collected files (queue order) that still need work.
+///
+/// Files `is_done` reports as completed by an earlier `--resume` run are dropped first, so
+/// they do not use up the limit. Returns the files to process and, when the limit left
+/// files out, a line for the summary report.
+pub fn apply_limit<F>(
+    files: Vec<PathBuf>,
+    limit: Option<usize>,
+    is_done: F,
+) -> (Vec<PathBuf>, Option<String>)
+where
+    F: Fn(&Path) -> bool,
+{
+    let Some(limit) = limit else {
+        return (files, None);
+    };
+    let mut pending: Vec<PathBuf> = files.into_iter().filter(|f| !is_done(f)).collect();
+    let matched = pending.len();
+    if matched <= limit {
+        return (pending, None);
+    }
+    pending.truncate(limit);
+    let note = format!(
+        "✂️  --limit {}: processed the first {} of {} pending files; {} left untouched",
+        limit,
+        limit,
+        matched,
+        matched - limit
+    );
+    (pending, Some(note))
+}
+
+/// On-disk size of each collected file (0 when unreadable), index-aligned with `files`.
+/// Feeds `CoarseProgressBar::with_total_bytes` for a size-weighted ETA.
+pub fn file_sizes(files: &[PathBuf]) -> Vec<u64> {
+    files
+        .iter()
+        .map(|f| std::fs::metadata(f).map(|m| m.len()).unwrap_or(0))
+        .collect()
+}
+
+/// `--include-ext` / `--exclude-ext` post-filter for collected files.
+///
+/// Extensions are compared case-insensitively; a leading dot is ignored. An empty include
+/// list means "everything the tool supports". Exclusions win over inclusions.
+#[derive(Debug, Clone, Default)]
+pub struct ExtensionFilter {
+    include: Vec<String>,
+    exclude: Vec<String>,
+}
+
+impl ExtensionFilter {
+    /// Normalize the CLI lists and warn about extensions outside `supported`
+    /// (they can never match a collected file).
+    pub fn new(include: &[String], exclude: &[String], supported: &[&str]) -> Self {
+        let normalize = |flag: &str, list: &[String]| -> Vec<String> {
+            let mut out: Vec<String> = Vec::new();
+            for raw in list {
+                let ext = raw.trim().trim_start_matches('.').to_ascii_lowercase();
+                if ext.is_empty() || out.contains(&ext) {
+                    continue;
+                }
+                if !supported.iter().any(|s| s.eq_ignore_ascii_case(&ext)) {
+                    crate::log_eprintln!(
+                        "⚠️  {} {}: not a supported extension (supported: {})",
+                        flag,
+                        ext,
+                        supported.join(", ")
+                    );
+                }
+                out.push(ext);
+            }
+            out
+        };
+        Self {
+            include: normalize("--include-ext", include),
+            exclude: normalize("--exclude-ext", exclude),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.include.is_empty() && self.exclude.is_empty()
+    }
+
+    pub fn matches(&self, path: &Path) -> bool {
+        let ext = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(str::to_ascii_lowercase)
+            .unwrap_or_default();
+        (self.include.is_empty() || self.include.contains(&ext)) && !self.exclude.contains(&ext)
+    }
+
+    /// Keep only files that pass the filter, preserving the collection order.
+    pub fn apply(&self, files: Vec<PathBuf>) -> Vec<PathBuf> {
+        if self.is_empty() {
+            return files;
+        }
+        files.into_iter().filter(|f| self.matches(f)).collect()
+    }
+}
+
+/// Formats `--metadata-only-formats` falls back to under `--apple-compat` (and the video tools'
+/// `--preset archive`) when no list is given: already-optimal outputs that re-encoding would
+/// only degrade.
+pub const APPLE_COMPAT_METADATA_ONLY_FORMATS: &[&str] = &["jxl", "heic", "heif"];
+
+/// `--metadata-only-formats`: extensions that are never re-encoded. Matching files are copied
+/// through to the output directory like any routing skip, which merges their XMP sidecar.
+#[derive(Debug, Clone, Default)]
+pub struct MetadataOnlyFormats {
+    extensions: Vec<String>,
+}
+
+impl MetadataOnlyFormats {
+    /// Normalize the CLI list. An empty list in an apple-compat or archival run (`use_default`)
+    /// picks `APPLE_COMPAT_METADATA_ONLY_FORMATS`; `none` turns the mode off entirely.
+    pub fn resolve(list: &[String], use_default: bool) -> Self {
+        let mut extensions: Vec<String> = Vec::new();
+        for raw in list {
+            let ext = raw.trim().trim_start_matches('.').to_ascii_lowercase();
+            if ext == "none" {
+                return Self::default();
+            }
+            if !ext.is_empty() && !extensions.contains(&ext) {
+                extensions.push(ext);
+            }
+        }
+        if list.is_empty() && use_default {
+            extensions = APPLE_COMPAT_METADATA_ONLY_FORMATS
+                .iter()
+                .map(|e| e.to_string())
+                .collect();
+        }
+        Self { extensions }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.extensions.is_empty()
+    }
+
+    pub fn matches(&self, path: &Path) -> bool {
+        path.extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|ext| self.extensions.iter().any(|m| m.eq_ignore_ascii_case(ext)))
+    }
+
+    /// Comma-separated list for the startup banner.
+    pub fn describe(&self) -> String {
+        self.extensions.join(",")
+    }
+}
+
 pub fn collect_image_files_for_perceived_speed(
     dir: &Path,
     extensions: &[&str],
@@ -162,11 +359,7 @@ pub fn calculate_directory_size_by_extensions(
     extensions: &[&str],
     recursive: bool,
 ) -> u64 {
-    let walker = if recursive {
-        WalkDir::new(dir).follow_links(true)
-    } else {
-        WalkDir::new(dir).max_depth(1)
-    };
+    let walker = walk_dir(dir, recursive);
 
     let mut total = 0u64;
     for entry in walker.into_iter() {
@@ -251,6 +444,145 @@ impl BatchPauseController {
     }
 }
 
+/// What a directory run does after a real (non-skip) conversion failure.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FailurePolicy {
+    /// Count the failure and continue with the remaining files (default).
+    #[default]
+    KeepGoing,
+    /// Stop scheduling new files and exit non-zero on the first failure (CI use).
+    FailFast,
+}
+
+impl FailurePolicy {
+    pub fn from_flags(fail_fast: bool, keep_going: bool) -> Self {
+        if fail_fast && !keep_going {
+            Self::FailFast
+        } else {
+            Self::KeepGoing
+        }
+    }
+
+    pub fn is_fail_fast(self) -> bool {
+        self == Self::FailFast
+    }
+}
+
+/// Retries for conversions that fail with a transient error (`is_recoverable_error`:
+/// busy GPU/device, EAGAIN, interrupted I/O). Other failures are returned immediately.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    pub max_retries: u32,
+    /// Wait before the first retry; grows linearly with each attempt.
+    pub backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::new(1)
+    }
+}
+
+impl RetryPolicy {
+    pub fn new(max_retries: u32) -> Self {
+        Self {
+            max_retries,
+            backoff: Duration::from_secs(2),
+        }
+    }
+
+    pub fn delay_for(&self, attempt: u32) -> Duration {
+        self.backoff * attempt
+    }
+
+    /// Runs `op` until it succeeds, fails with a non-recoverable error, or retries run out.
+    /// `failure_text` returns the error text of a failed outcome, `None` for success or skip.
+    pub fn run<T>(
+        &self,
+        path: &Path,
+        mut op: impl FnMut() -> T,
+        failure_text: impl Fn(&T) -> Option<String>,
+    ) -> T {
+        let mut attempt = 0;
+        loop {
+            let outcome = op();
+            match failure_text(&outcome) {
+                Some(text)
+                    if attempt < self.max_retries
+                        && crate::ffmpeg_process::is_recoverable_error(&text)
+                        && crate::cancellation::check().is_ok() =>
+                {
+                    attempt += 1;
+                    let delay = self.delay_for(attempt);
+                    warn!(
+                        "🔁 Recoverable error on {}, retry {}/{} in {}s: {}",
+                        path.display(),
+                        attempt,
+                        self.max_retries,
+                        delay.as_secs(),
+                        crate::ffmpeg_process::format_ffmpeg_error(&text)
+                    );
+                    std::thread::sleep(delay);
+                }
+                _ => return outcome,
+            }
+        }
+    }
+}
+
+/// Records the first failure of a fail-fast batch so workers stop picking up new files.
+/// Workers install `token()` so encodes already in flight are killed too.
+#[derive(Debug, Default)]
+pub struct BatchAbortController {
+    aborted: AtomicBool,
+    info: Mutex<Option<(PathBuf, String)>>,
+    token: crate::cancellation::CancellationToken,
+}
+
+impl BatchAbortController {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_aborted(&self) -> bool {
+        self.aborted.load(Ordering::Relaxed)
+    }
+
+    /// Fires on the first failure; install it on each worker thread.
+    pub fn token(&self) -> crate::cancellation::CancellationToken {
+        self.token.clone()
+    }
+
+    /// Returns true only for the first failure; later failures from in-flight tasks are ignored.
+    pub fn request_abort(&self, path: &Path, error: impl Into<String>) -> bool {
+        let newly_aborted = self
+            .aborted
+            .compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst)
+            .is_ok();
+
+        if newly_aborted {
+            let mut info = self.info.lock().unwrap_or_else(|e| e.into_inner());
+            *info = Some((path.to_path_buf(), error.into()));
+            self.token.cancel();
+        }
+
+        newly_aborted
+    }
+
+    pub fn abort_info(&self) -> Option<(PathBuf, String)> {
+        self.info.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+}
+
+/// Exit message for a batch aborted by `FailurePolicy::FailFast`.
+pub fn fail_fast_message(path: &Path, error: &str) -> String {
+    format!(
+        "❌ Fail-fast: batch aborted on first failure\n   📄 File: {}\n   💥 Error: {}",
+        path.display(),
+        error
+    )
+}
+
 pub fn disk_full_pause_reason(message: &str) -> Option<String> {
     let lower = message.to_lowercase();
     let disk_full = [
@@ -286,6 +618,12 @@ pub struct BatchResult {
     pub paused: bool,
     pub pause_info: Option<BatchPauseInfo>,
     pub paused_remaining: usize,
+    /// `--benchmark`: one entry per converted file, in completion order.
+    pub benchmarks: Vec<EncodeBenchmark>,
+    /// Skip counts per reason; may cover fewer files than `skipped` when a caller only counts.
+    pub skip_reasons: BTreeMap<SkipReason, usize>,
+    /// `--verify-after`: (verified, reverted) output counts of the post-pass.
+    pub verify_after: Option<(usize, usize)>,
 }
 
 impl BatchResult {
@@ -299,9 +637,34 @@ impl BatchResult {
             paused: false,
             pause_info: None,
             paused_remaining: 0,
+            benchmarks: Vec::new(),
+            skip_reasons: BTreeMap::new(),
+            verify_after: None,
         }
     }
 
+    pub fn record_benchmark(&mut self, benchmark: EncodeBenchmark) {
+        self.benchmarks.push(benchmark);
+    }
+
+    /// Benchmarks summed per encoder label with their file counts, in first-seen order.
+    pub fn benchmark_totals(&self) -> Vec<(EncodeBenchmark, usize)> {
+        let mut totals: Vec<(EncodeBenchmark, usize)> = Vec::new();
+        for benchmark in &self.benchmarks {
+            match totals
+                .iter_mut()
+                .find(|(total, _)| total.encoder == benchmark.encoder)
+            {
+                Some((total, files)) => {
+                    total.accumulate(benchmark);
+                    *files += 1;
+                }
+                None => totals.push((benchmark.clone(), 1)),
+            }
+        }
+        totals
+    }
+
     pub fn success(&mut self) {
         self.total += 1;
         self.succeeded += 1;
@@ -318,6 +681,36 @@ impl BatchResult {
         self.skipped += 1;
     }
 
+    /// [`skip`](Self::skip) and tally the reason for the summary breakdown.
+    pub fn skip_because(&mut self, reason: SkipReason) {
+        self.skip();
+        self.record_skip_reason(reason);
+    }
+
+    /// Tally a skip reason without touching the counters (for callers that count separately).
+    pub fn record_skip_reason(&mut self, reason: SkipReason) {
+        *self.skip_reasons.entry(reason).or_insert(0) += 1;
+    }
+
+    /// Skip reasons, most frequent first.
+    pub fn skip_breakdown(&self) -> Vec<(SkipReason, usize)> {
+        let mut breakdown: Vec<(SkipReason, usize)> =
+            self.skip_reasons.iter().map(|(r, n)| (*r, *n)).collect();
+        breakdown.sort_by(|a, b| b.1.cmp(&a.1));
+        breakdown
+    }
+
+    /// Fold in the `--verify-after` pass: each reverted file moves from succeeded to failed.
+    pub fn apply_verify_after(&mut self, report: &crate::verify_after::VerifyAfterReport) {
+        for (path, reason) in &report.reverted {
+            self.succeeded = self.succeeded.saturating_sub(1);
+            self.failed += 1;
+            self.errors
+                .push((path.clone(), format!("Verify-after: {}", reason)));
+        }
+        self.verify_after = Some((report.verified, report.reverted.len()));
+    }
+
     pub fn pause(&mut self, path: PathBuf, reason: String, remaining: usize) {
         self.paused = true;
         self.pause_info = Some(BatchPauseInfo { path, reason });
@@ -507,6 +900,7 @@ fn validate_cached_image_tree(
     let expected_root = dir.canonicalize().unwrap_or_else(|_| dir.to_path_buf());
     if snapshot.root != expected_root
         || snapshot.recursive != recursive
+        || snapshot.follow_symlinks != follow_symlinks()
         || snapshot.extensions != normalized_extensions(extensions)
     {
         return false;
@@ -531,6 +925,7 @@ fn validate_cached_video_tree(
     let expected_root = dir.canonicalize().unwrap_or_else(|_| dir.to_path_buf());
     if snapshot.root != expected_root
         || snapshot.recursive != recursive
+        || snapshot.follow_symlinks != follow_symlinks()
         || snapshot.extensions != normalized_extensions(extensions)
     {
         return false;
@@ -548,11 +943,7 @@ fn scan_image_tree_snapshot(
     recursive: bool,
 ) -> CachedImageTreeSnapshot {
     let root = dir.canonicalize().unwrap_or_else(|_| dir.to_path_buf());
-    let walker = if recursive {
-        WalkDir::new(&root).follow_links(true)
-    } else {
-        WalkDir::new(&root).max_depth(1)
-    };
+    let walker = walk_dir(&root, recursive);
 
     let mut directories = Vec::new();
     let mut files = Vec::new();
@@ -601,6 +992,7 @@ fn scan_image_tree_snapshot(
         schema_version: PATH_TREE_CACHE_SCHEMA_VERSION,
         root,
         recursive,
+        follow_symlinks: follow_symlinks(),
         extensions: normalized_extensions(extensions),
         directories,
         files,
@@ -703,11 +1095,7 @@ fn scan_video_tree_snapshot(
     recursive: bool,
 ) -> CachedVideoTreeSnapshot {
     let root = dir.canonicalize().unwrap_or_else(|_| dir.to_path_buf());
-    let walker = if recursive {
-        WalkDir::new(&root).follow_links(true)
-    } else {
-        WalkDir::new(&root).max_depth(1)
-    };
+    let walker = walk_dir(&root, recursive);
 
     let mut directories = Vec::new();
     let mut files = Vec::new();
@@ -756,6 +1144,7 @@ fn scan_video_tree_snapshot(
         schema_version: PATH_TREE_CACHE_SCHEMA_VERSION,
         root,
         recursive,
+        follow_symlinks: follow_symlinks(),
         extensions: normalized_extensions(extensions),
         directories,
         files,
@@ -786,6 +1175,203 @@ mod tests {
         image.save_with_format(path, format).unwrap();
     }
 
+    #[test]
+    fn test_benchmark_totals_group_by_encoder() {
+        let bench = |encoder: &str, wall_secs: f64, frames: u64| EncodeBenchmark {
+            encoder: encoder.to_string(),
+            wall_secs,
+            frames,
+            input_bytes: 4 * 1024 * 1024,
+            output_bytes: 1024 * 1024,
+        };
+        let mut result = BatchResult::new();
+        result.record_benchmark(bench("hevc (GPU)", 1.0, 300));
+        result.record_benchmark(bench("hevc (CPU)", 4.0, 300));
+        result.record_benchmark(bench("hevc (GPU)", 3.0, 300));
+
+        let totals = result.benchmark_totals();
+        assert_eq!(totals.len(), 2);
+        let (gpu, gpu_files) = &totals[0];
+        assert_eq!(gpu.encoder, "hevc (GPU)");
+        assert_eq!(*gpu_files, 2);
+        assert_eq!(gpu.frames, 600);
+        assert!((gpu.fps() - 150.0).abs() < 1e-9);
+        assert!((gpu.input_mb_per_sec() - 2.0).abs() < 1e-9);
+        let (cpu, cpu_files) = &totals[1];
+        assert_eq!(*cpu_files, 1);
+        assert!((cpu.fps() - 75.0).abs() < 1e-9);
+        assert!((cpu.output_mb_per_sec() - 0.25).abs() < 1e-9);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_walk_dir_symlink_policy() {
+        let dir = TempDir::new().unwrap();
+        let photos = dir.path().join("photos");
+        fs::create_dir(&photos).unwrap();
+        fs::write(photos.join("a.jpg"), b"x").unwrap();
+        // A link back to the root and a second name for the same directory.
+        std::os::unix::fs::symlink(dir.path(), photos.join("loop")).unwrap();
+        std::os::unix::fs::symlink(&photos, dir.path().join("alias")).unwrap();
+
+        set_follow_symlinks(false);
+        assert_eq!(collect_files(dir.path(), &["jpg"], true).len(), 1);
+
+        set_follow_symlinks(true);
+        let followed = collect_files(dir.path(), &["jpg"], true);
+        set_follow_symlinks(false);
+        assert_eq!(followed.len(), 1, "each canonical file is collected once");
+    }
+
+    #[test]
+    fn test_apply_limit_skips_completed_files() {
+        let files: Vec<PathBuf> = ["a.mp4", "b.mp4", "c.mp4", "d.mp4"]
+            .iter()
+            .map(PathBuf::from)
+            .collect();
+
+        let (all, note) = apply_limit(files.clone(), None, |_| false);
+        assert_eq!(all, files);
+        assert!(note.is_none());
+
+        // a.mp4 was finished by an earlier resume run and does not count.
+        let (kept, note) = apply_limit(files.clone(), Some(2), |f| f == Path::new("a.mp4"));
+        assert_eq!(kept, vec![PathBuf::from("b.mp4"), PathBuf::from("c.mp4")]);
+        assert!(note.unwrap().contains("2 of 3 pending"));
+
+        let (kept, note) = apply_limit(files, Some(10), |_| false);
+        assert_eq!(kept.len(), 4);
+        assert!(note.is_none());
+    }
+
+    #[test]
+    fn test_extension_filter_include_and_exclude() {
+        let supported = ["mp4", "mov", "mkv", "gif"];
+        let files = vec![
+            PathBuf::from("a.MOV"),
+            PathBuf::from("b.mp4"),
+            PathBuf::from("c.mkv"),
+            PathBuf::from("d.gif"),
+        ];
+
+        let include = ExtensionFilter::new(&[".mov".into(), "MKV".into()], &[], &supported);
+        assert_eq!(
+            include.apply(files.clone()),
+            vec![PathBuf::from("a.MOV"), PathBuf::from("c.mkv")]
+        );
+
+        let exclude = ExtensionFilter::new(&[], &["gif".into()], &supported);
+        assert_eq!(exclude.apply(files.clone()).len(), 3);
+        assert!(!exclude.matches(Path::new("d.GIF")));
+
+        let both = ExtensionFilter::new(&["mov".into(), "gif".into()], &["gif".into()], &supported);
+        assert_eq!(both.apply(files.clone()), vec![PathBuf::from("a.MOV")]);
+
+        assert!(ExtensionFilter::default().is_empty());
+        assert_eq!(ExtensionFilter::default().apply(files.clone()), files);
+    }
+
+    #[test]
+    fn test_metadata_only_formats_resolve() {
+        let explicit = MetadataOnlyFormats::resolve(&[".JXL".into(), "mp4".into()], false);
+        assert!(explicit.matches(Path::new("a.jxl")));
+        assert!(explicit.matches(Path::new("b.MP4")));
+        assert!(!explicit.matches(Path::new("c.heic")));
+        assert!(!explicit.matches(Path::new("noext")));
+
+        assert!(MetadataOnlyFormats::resolve(&[], false).is_empty());
+        let apple = MetadataOnlyFormats::resolve(&[], true);
+        assert!(apple.matches(Path::new("IMG_0001.HEIC")));
+        assert_eq!(apple.describe(), "jxl,heic,heif");
+
+        // An explicit list replaces the default; `none` disables it.
+        let png_only = MetadataOnlyFormats::resolve(&["png".into()], true);
+        assert!(!png_only.matches(Path::new("a.jxl")));
+        assert!(MetadataOnlyFormats::resolve(&["none".into()], true).is_empty());
+    }
+
+    #[test]
+    fn test_retry_policy_retries_only_recoverable_errors() {
+        let policy = RetryPolicy {
+            max_retries: 2,
+            backoff: Duration::ZERO,
+        };
+        let path = Path::new("clip.mov");
+
+        let mut calls = 0;
+        let outcome = policy.run(
+            path,
+            || {
+                calls += 1;
+                if calls < 3 {
+                    Err("Device or resource busy")
+                } else {
+                    Ok(())
+                }
+            },
+            |r| r.err().map(str::to_string),
+        );
+        assert_eq!(outcome, Ok(()));
+        assert_eq!(calls, 3);
+
+        let mut calls = 0;
+        let outcome: Result<(), &str> = policy.run(
+            path,
+            || {
+                calls += 1;
+                Err("Invalid data found when processing input")
+            },
+            |r| r.err().map(str::to_string),
+        );
+        assert!(outcome.is_err());
+        assert_eq!(calls, 1);
+
+        let mut calls = 0;
+        let _: Result<(), &str> = policy.run(
+            path,
+            || {
+                calls += 1;
+                Err("Resource temporarily unavailable")
+            },
+            |r| r.err().map(str::to_string),
+        );
+        assert_eq!(calls, 3, "gives up after max_retries");
+    }
+
+    #[test]
+    fn test_failure_policy_from_flags() {
+        assert_eq!(FailurePolicy::default(), FailurePolicy::KeepGoing);
+        assert_eq!(
+            FailurePolicy::from_flags(false, false),
+            FailurePolicy::KeepGoing
+        );
+        assert_eq!(
+            FailurePolicy::from_flags(true, false),
+            FailurePolicy::FailFast
+        );
+        assert_eq!(
+            FailurePolicy::from_flags(true, true),
+            FailurePolicy::KeepGoing
+        );
+        assert!(FailurePolicy::FailFast.is_fail_fast());
+    }
+
+    #[test]
+    fn test_batch_abort_controller_keeps_first_failure() {
+        let abort = BatchAbortController::new();
+        assert!(!abort.is_aborted());
+        assert!(!abort.token().is_cancelled());
+        assert!(abort.request_abort(Path::new("/a.mp4"), "boom"));
+        assert!(!abort.request_abort(Path::new("/b.mp4"), "later"));
+        assert!(abort.is_aborted());
+        assert!(abort.token().is_cancelled());
+        let (path, error) = abort.abort_info().unwrap();
+        assert_eq!(path, PathBuf::from("/a.mp4"));
+        assert_eq!(error, "boom");
+        let msg = fail_fast_message(&path, &error);
+        assert!(msg.contains("/a.mp4") && msg.contains("boom"));
+    }
+
     #[test]
     fn test_batch_result_new() {
         let result = BatchResult::new();
@@ -829,6 +1415,24 @@ mod tests {
         assert_eq!(result.skipped, 1);
     }
 
+    #[test]
+    fn test_batch_result_skip_breakdown() {
+        let mut result = BatchResult::new();
+        result.skip_because(SkipReason::AlreadyProcessed);
+        result.skip_because(SkipReason::ModernLossy);
+        result.skip_because(SkipReason::ModernLossy);
+        result.skip();
+
+        assert_eq!(result.skipped, 4);
+        assert_eq!(
+            result.skip_breakdown(),
+            vec![
+                (SkipReason::ModernLossy, 2),
+                (SkipReason::AlreadyProcessed, 1)
+            ]
+        );
+    }
+
     #[test]
     fn test_batch_result_mixed() {
         let mut result = BatchResult::new();
@@ -843,6 +1447,23 @@ mod tests {
         assert_eq!(result.skipped, 1);
     }
 
+    #[test]
+    fn test_apply_verify_after_moves_reverted_to_failed() {
+        let mut result = BatchResult::new();
+        result.success();
+        result.success();
+        result.apply_verify_after(&crate::verify_after::VerifyAfterReport {
+            verified: 1,
+            reverted: vec![(PathBuf::from("b.mov"), "Output has zero frames".to_string())],
+        });
+
+        assert_eq!(result.total, 2);
+        assert_eq!(result.succeeded, 1);
+        assert_eq!(result.failed, 1);
+        assert_eq!(result.verify_after, Some((1, 1)));
+        assert_eq!(result.errors[0].0, PathBuf::from("b.mov"));
+    }
+
     #[test]
     fn test_success_rate_empty() {
         let result = BatchResult::new();
@@ -0,0 +1,278 @@
+//! Cooperative Cancellation for Library Consumers
+//!
+//! Embedders (e.g. a GUI) set `ConversionConfig::cancel_token` and call
+//! `CancellationToken::cancel` from any thread to abort a running conversion.
+//!
+//! Like `progress_sink`, the explorer does not take the config directly, so the
+//! conversion entry point installs the token for the current thread (`install`):
+//!
+//! - the explore loop calls `check` before every encode
+//! - spawned ffmpeg/x265 children are watched by a `ChildWatcher`, which kills
+//!   them as soon as the token fires instead of waiting for the encode to finish
+//!
+//! A cancelled conversion surfaces as `UnifiedError::Cancelled`; temp outputs are
+//! removed by the usual `TempOutputGuard`.
+//!
+//! Batch workers install the token of their `BatchAbortController`, so a `--fail-fast`
+//! failure kills the encodes still running on the other workers.
+//!
+//! `Deadline` backs `ConversionConfig::per_file_timeout`: a per-file token that fires
+//! when the caller's token does or when the time budget runs out.
+
+use std::cell::RefCell;
+use std::process::Child;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+const WATCH_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Shared flag; clones observe the same cancellation.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+/// Error returned by `check` (downcast to tell cancellation apart from encode failures).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cancelled;
+
+impl std::fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Conversion cancelled")
+    }
+}
+
+impl std::error::Error for Cancelled {}
+
+thread_local! {
+    static CURRENT_TOKEN: RefCell<Option<CancellationToken>> = const { RefCell::new(None) };
+}
+
+/// Restores the previously installed token on drop.
+pub struct CancellationGuard {
+    previous: Option<CancellationToken>,
+}
+
+impl Drop for CancellationGuard {
+    fn drop(&mut self) {
+        let previous = self.previous.take();
+        CURRENT_TOKEN.with(|t| *t.borrow_mut() = previous);
+    }
+}
+
+/// Install `token` for the current thread until the returned guard is dropped.
+pub fn install(token: Option<CancellationToken>) -> CancellationGuard {
+    let previous = CURRENT_TOKEN.with(|t| std::mem::replace(&mut *t.borrow_mut(), token));
+    CancellationGuard { previous }
+}
+
+/// The token installed on this thread, if any.
+pub fn current() -> Option<CancellationToken> {
+    CURRENT_TOKEN.with(|t| t.borrow().clone())
+}
+
+/// Fail with `Cancelled` if the token installed on this thread has fired.
+pub fn check() -> anyhow::Result<()> {
+    if current().is_some_and(|t| t.is_cancelled()) {
+        return Err(Cancelled.into());
+    }
+    Ok(())
+}
+
+/// True if `err` (or anything in its chain) is a `Cancelled`.
+pub fn is_cancelled_error(err: &anyhow::Error) -> bool {
+    err.chain().any(|e| e.is::<Cancelled>())
+}
+
+/// Kills a child process when the token fires. Stops watching on drop.
+pub struct ChildWatcher {
+    stop: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl ChildWatcher {
+    /// Watch `child` against the token installed on this thread; `None` when there is none.
+    pub fn for_current(child: &Child) -> Option<Self> {
+        current().map(|token| Self::new(child, token))
+    }
+
+    pub fn new(child: &Child, token: CancellationToken) -> Self {
+        let pid = child.id();
+        let stop = Arc::new(AtomicBool::new(false));
+        let stop_flag = Arc::clone(&stop);
+        let handle = std::thread::spawn(move || {
+            while !stop_flag.load(Ordering::SeqCst) {
+                if token.is_cancelled() {
+                    kill_pid(pid);
+                    return;
+                }
+                std::thread::sleep(WATCH_INTERVAL);
+            }
+        });
+        Self {
+            stop,
+            handle: Some(handle),
+        }
+    }
+}
+
+impl Drop for ChildWatcher {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+/// Per-file token that fires when `parent` does or once `timeout` has elapsed.
+/// A fresh token is used so a timeout never cancels the caller's own token.
+pub struct Deadline {
+    token: CancellationToken,
+    timed_out: Arc<AtomicBool>,
+    stop: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl Deadline {
+    pub fn start(parent: Option<CancellationToken>, timeout: Duration) -> Self {
+        let token = CancellationToken::new();
+        let timed_out = Arc::new(AtomicBool::new(false));
+        let stop = Arc::new(AtomicBool::new(false));
+        let handle = {
+            let token = token.clone();
+            let timed_out = Arc::clone(&timed_out);
+            let stop = Arc::clone(&stop);
+            let expires = Instant::now() + timeout;
+            std::thread::spawn(move || {
+                while !stop.load(Ordering::SeqCst) {
+                    if Instant::now() >= expires {
+                        timed_out.store(true, Ordering::SeqCst);
+                        token.cancel();
+                        return;
+                    }
+                    if parent.as_ref().is_some_and(|p| p.is_cancelled()) {
+                        token.cancel();
+                        return;
+                    }
+                    std::thread::sleep(WATCH_INTERVAL);
+                }
+            })
+        };
+        Self {
+            token,
+            timed_out,
+            stop,
+            handle: Some(handle),
+        }
+    }
+
+    pub fn token(&self) -> CancellationToken {
+        self.token.clone()
+    }
+
+    /// True once the timeout (not the parent token) has fired.
+    pub fn timed_out(&self) -> bool {
+        self.timed_out.load(Ordering::SeqCst)
+    }
+}
+
+impl Drop for Deadline {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+fn kill_pid(pid: u32) {
+    #[cfg(unix)]
+    // SAFETY: pid is a child we spawned and are still waiting on, so it has not been reaped.
+    unsafe {
+        if libc::kill(pid as i32, libc::SIGKILL) != 0 {
+            tracing::debug!(
+                "Failed to kill PID {} on cancel: {}",
+                pid,
+                std::io::Error::last_os_error()
+            );
+        }
+    }
+
+    #[cfg(windows)]
+    {
+        let _ = std::process::Command::new("taskkill")
+            .args(["/PID", &pid.to_string(), "/F"])
+            .output();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_follows_installed_token() {
+        assert!(check().is_ok());
+        let token = CancellationToken::new();
+        {
+            let _guard = install(Some(token.clone()));
+            assert!(check().is_ok());
+            token.cancel();
+            let err = check().unwrap_err();
+            assert!(is_cancelled_error(&err));
+        }
+        assert!(current().is_none());
+        assert!(check().is_ok());
+    }
+
+    #[test]
+    fn test_deadline_times_out_without_touching_parent() {
+        let parent = CancellationToken::new();
+        let deadline = Deadline::start(Some(parent.clone()), Duration::from_millis(50));
+        let start = Instant::now();
+        while !deadline.token().is_cancelled() && start.elapsed() < Duration::from_secs(5) {
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        assert!(deadline.timed_out());
+        assert!(!parent.is_cancelled());
+
+        let deadline = Deadline::start(Some(parent.clone()), Duration::from_secs(60));
+        parent.cancel();
+        let start = Instant::now();
+        while !deadline.token().is_cancelled() && start.elapsed() < Duration::from_secs(5) {
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        assert!(deadline.token().is_cancelled());
+        assert!(!deadline.timed_out());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_child_watcher_kills_on_cancel() {
+        let Ok(mut child) = std::process::Command::new("sleep").arg("30").spawn() else {
+            return;
+        };
+        let token = CancellationToken::new();
+        let _watcher = ChildWatcher::new(&child, token.clone());
+        let start = Instant::now();
+        token.cancel();
+        let status = child.wait().unwrap();
+        assert!(!status.success());
+        assert!(start.elapsed() < Duration::from_secs(10));
+    }
+}
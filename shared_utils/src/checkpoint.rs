@@ -619,6 +619,11 @@ impl CheckpointManager {
         Ok(())
     }
 
+    /// Forget a completed file so a resumed run converts it again (its output was reverted).
+    pub fn unmark_completed(&self, path: &Path) {
+        self.drop_completed_entry(&Self::normalize_path(path));
+    }
+
     pub fn sync_to_processed_list(&self) {
         let completed = self.completed.lock().unwrap_or_else(|e| e.into_inner());
         for path_str in completed.keys() {
@@ -936,20 +941,187 @@ pub fn verify_output_integrity(output: &Path, min_size: u64) -> Result<(), Strin
     Ok(())
 }
 
-pub fn safe_delete_original(input: &Path, output: &Path, min_output_size: u64) -> io::Result<()> {
-    if let Err(reason) = verify_output_integrity(output, min_output_size) {
-        eprintln!("   ⚠️  Output integrity check FAILED: {}", reason);
-        eprintln!("   🛡️  Original file PROTECTED: {}", input.display());
-        return Err(io::Error::new(
-            io::ErrorKind::InvalidData,
-            format!("Output integrity check failed: {}", reason),
+/// Content-level check: the output must actually decode, not just exist.
+/// Raster formats the `image` crate reads are fully decoded; JXL goes through
+/// `verify_jxl_health`; everything else (AVIF, HEIC, video containers) must be
+/// opened by ffprobe with at least one stream.
+pub fn verify_output_decodable(output: &Path) -> Result<(), String> {
+    let ext = output
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_ascii_lowercase();
+
+    match ext.as_str() {
+        "png" | "jpg" | "jpeg" | "gif" | "webp" | "bmp" | "tif" | "tiff" => {
+            image::ImageReader::open(output)
+                .and_then(|r| r.with_guessed_format())
+                .map_err(|e| format!("Cannot open output image: {}", e))?
+                .decode()
+                .map(|_| ())
+                .map_err(|e| format!("Output image does not decode: {}", e))
+        }
+        "jxl" => crate::jxl_utils::verify_jxl_health(output),
+        _ => {
+            let probe = std::process::Command::new("ffprobe")
+                .args([
+                    "-v",
+                    "error",
+                    "-show_entries",
+                    "stream=codec_type",
+                    "-of",
+                    "csv=p=0",
+                ])
+                .arg(crate::safe_path_arg(output).as_ref())
+                .output()
+                .map_err(|e| format!("Cannot run ffprobe to verify output: {}", e))?;
+            if !probe.status.success() {
+                return Err(format!(
+                    "ffprobe cannot read output: {}",
+                    String::from_utf8_lossy(&probe.stderr).trim()
+                ));
+            }
+            if String::from_utf8_lossy(&probe.stdout).trim().is_empty() {
+                return Err("Output has no decodable streams".to_string());
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Lossless targets only: decoded output must be bit-identical to the input
+/// (PSNR = ∞ over the whole clip).
+pub fn verify_lossless_output(input: &Path, output: &Path) -> Result<(), String> {
+    let result = std::process::Command::new("ffmpeg")
+        .args(["-hide_banner", "-nostats", "-i"])
+        .arg(crate::safe_path_arg(input).as_ref())
+        .arg("-i")
+        .arg(crate::safe_path_arg(output).as_ref())
+        .args(["-lavfi", "[0:v][1:v]psnr", "-f", "null", "-"])
+        .output()
+        .map_err(|e| format!("Cannot run ffmpeg PSNR check: {}", e))?;
+    if !result.status.success() {
+        return Err("ffmpeg PSNR check failed".to_string());
+    }
+
+    let stderr = String::from_utf8_lossy(&result.stderr);
+    let average = stderr
+        .lines()
+        .filter(|l| l.contains("PSNR"))
+        .find_map(|l| l.split("average:").nth(1))
+        .and_then(|rest| rest.split_whitespace().next())
+        .ok_or_else(|| "PSNR average not found in ffmpeg output".to_string())?;
+    if average == "inf" {
+        Ok(())
+    } else {
+        Err(format!(
+            "Output is not lossless (PSNR average {} dB)",
+            average
+        ))
+    }
+}
+
+/// Lossless JXL: `djxl` back to PNG and compare pixels with the input. Falls back to
+/// the ffmpeg PSNR check on the decoded PNG when the `image` crate can't read the input.
+pub fn verify_lossless_jxl_output(input: &Path, output: &Path) -> Result<(), String> {
+    let decoded = tempfile::Builder::new()
+        .prefix(".mfb_lossless_check")
+        .suffix(".png")
+        .tempfile_in(crate::conversion::scratch_dir())
+        .map_err(|e| format!("Cannot create scratch file: {}", e))?;
+
+    let status = std::process::Command::new("djxl")
+        .arg(crate::safe_path_arg(output).as_ref())
+        .arg(crate::safe_path_arg(decoded.path()).as_ref())
+        .output()
+        .map_err(|e| format!("Cannot run djxl: {}", e))?;
+    if !status.status.success() {
+        return Err(format!(
+            "djxl failed: {}",
+            String::from_utf8_lossy(&status.stderr).trim()
         ));
     }
 
-    fs::remove_file(input)?;
+    let Ok(original) = image::open(input) else {
+        return verify_lossless_output(input, decoded.path());
+    };
+    let roundtrip =
+        image::open(decoded.path()).map_err(|e| format!("Cannot read decoded JXL: {}", e))?;
+
+    if (original.width(), original.height()) != (roundtrip.width(), roundtrip.height()) {
+        return Err(format!(
+            "Output dimensions {}x{} differ from input {}x{}",
+            roundtrip.width(),
+            roundtrip.height(),
+            original.width(),
+            original.height()
+        ));
+    }
+    if original.to_rgba16().as_raw() != roundtrip.to_rgba16().as_raw() {
+        return Err("Output pixels differ from input".to_string());
+    }
     Ok(())
 }
 
+fn protect_original(input: &Path, what: &str, reason: String) -> io::Error {
+    eprintln!("   ⚠️  {} FAILED: {}", what, reason);
+    eprintln!("   🛡️  Original file PROTECTED: {}", input.display());
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("{} failed: {}", what, reason),
+    )
+}
+
+/// Delete `input` only after the output passed the size/readability check AND decodes.
+pub fn safe_delete_original(input: &Path, output: &Path, min_output_size: u64) -> io::Result<()> {
+    if let Err(reason) = verify_output_integrity(output, min_output_size) {
+        return Err(protect_original(input, "Output integrity check", reason));
+    }
+    if let Err(reason) = verify_output_decodable(output) {
+        return Err(protect_original(input, "Output decode check", reason));
+    }
+
+    remove_original(input, output)
+}
+
+/// `safe_delete_original` for lossless targets: additionally requires PSNR = ∞ against the
+/// input (pixel-exact round trip for JXL).
+pub fn safe_delete_original_lossless(
+    input: &Path,
+    output: &Path,
+    min_output_size: u64,
+) -> io::Result<()> {
+    if let Err(reason) = verify_output_integrity(output, min_output_size) {
+        return Err(protect_original(input, "Output integrity check", reason));
+    }
+    if let Err(reason) = verify_output_decodable(output) {
+        return Err(protect_original(input, "Output decode check", reason));
+    }
+    let is_jxl = output
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| e.eq_ignore_ascii_case("jxl"));
+    let verified = if is_jxl {
+        verify_lossless_jxl_output(input, output)
+    } else {
+        verify_lossless_output(input, output)
+    };
+    if let Err(reason) = verified {
+        return Err(protect_original(input, "Lossless verification", reason));
+    }
+
+    remove_original(input, output)
+}
+
+/// Under `--verify-after` the delete waits for the post-pass to re-check `output`.
+fn remove_original(input: &Path, output: &Path) -> io::Result<()> {
+    if crate::verify_after::verify_after_enabled() {
+        crate::verify_after::defer_delete_original(input, output);
+        return Ok(());
+    }
+    fs::remove_file(input)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1157,6 +1329,27 @@ mod tests {
         teardown_test_env(guard);
     }
 
+    #[test]
+    fn test_is_completed_reprocesses_source_changed_after_marking() {
+        let (temp, _progress, guard) = setup_test_env();
+        let target = temp.path();
+        let changed = target.join("long.mkv");
+        let untouched = target.join("other.mkv");
+        create_test_file(&changed);
+        create_test_file(&untouched);
+
+        let checkpoint = CheckpointManager::new(target).unwrap();
+        checkpoint.mark_completed(&changed).unwrap();
+        checkpoint.mark_completed(&untouched).unwrap();
+
+        fs::write(&changed, b"checkpoint-test-re-exported").unwrap();
+
+        assert!(!checkpoint.is_completed(&changed));
+        assert!(checkpoint.is_completed(&untouched));
+        assert_eq!(checkpoint.completed_count(), 1);
+        teardown_test_env(guard);
+    }
+
     #[test]
     fn test_checkpoint_cleanup() {
         let temp_target = TempDir::new().unwrap();
@@ -1243,11 +1436,13 @@ mod tests {
     #[test]
     fn test_safe_delete_original_success() {
         let temp = TempDir::new().unwrap();
-        let input = temp.path().join("input.mp4");
-        let output = temp.path().join("output.mp4");
+        let input = temp.path().join("input.png");
+        let output = temp.path().join("output.png");
 
         fs::write(&input, b"original content").unwrap();
-        fs::write(&output, b"converted content that is valid").unwrap();
+        image::RgbImage::from_pixel(16, 16, image::Rgb([10, 20, 30]))
+            .save(&output)
+            .unwrap();
 
         assert!(safe_delete_original(&input, &output, 10).is_ok());
 
@@ -1255,6 +1450,25 @@ mod tests {
         assert!(output.exists());
     }
 
+    #[test]
+    fn test_safe_delete_original_protects_on_corrupt_output() {
+        let temp = TempDir::new().unwrap();
+        let input = temp.path().join("input.png");
+        let output = temp.path().join("output.png");
+
+        fs::write(&input, b"original content").unwrap();
+        // Valid PNG signature, garbage body: passes the size check but does not decode.
+        let mut corrupt = vec![0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A];
+        corrupt.extend([0xABu8; 256]);
+        fs::write(&output, &corrupt).unwrap();
+
+        assert!(verify_output_integrity(&output, 10).is_ok());
+        let err = safe_delete_original(&input, &output, 10).unwrap_err();
+        assert!(err.to_string().contains("decode"));
+
+        assert!(input.exists());
+    }
+
     #[test]
     fn test_safe_delete_original_protects_on_invalid_output() {
         let temp = TempDir::new().unwrap();
@@ -1282,6 +1496,43 @@ mod tests {
         assert!(input.exists());
     }
 
+    #[test]
+    fn test_safe_delete_original_lossless_protects_on_lossy_jxl() {
+        let cjxl_available = std::process::Command::new("cjxl")
+            .arg("--version")
+            .output()
+            .is_ok_and(|o| o.status.success());
+        if !cjxl_available {
+            return;
+        }
+
+        let temp = TempDir::new().unwrap();
+        let input = temp.path().join("input.png");
+        let output = temp.path().join("output.jxl");
+
+        image::RgbImage::from_fn(64, 64, |x, y| {
+            image::Rgb([
+                (x * 37 % 256) as u8,
+                (y * 91 % 256) as u8,
+                ((x ^ y) * 13) as u8,
+            ])
+        })
+        .save(&input)
+        .unwrap();
+        let encoded = std::process::Command::new("cjxl")
+            .arg(&input)
+            .arg(&output)
+            .args(["-d", "3.0"])
+            .output()
+            .unwrap();
+        assert!(encoded.status.success());
+
+        let err = safe_delete_original_lossless(&input, &output, 10).unwrap_err();
+        assert!(err.to_string().contains("Lossless verification"));
+
+        assert!(input.exists());
+    }
+
     #[test]
     fn test_full_workflow_with_interruption() {
         let (temp, _progress, guard) = setup_test_env();
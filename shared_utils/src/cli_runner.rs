@@ -1,14 +1,22 @@
-use crate::batch::{disk_full_pause_reason, BatchPauseController, BatchResult};
+use crate::batch::{
+    disk_full_pause_reason, fail_fast_message, BatchAbortController, BatchPauseController,
+    BatchResult, ExtensionFilter, FailurePolicy, MetadataOnlyFormats, RetryPolicy,
+};
 use crate::common_utils::has_extension;
+use crate::conversion_types::SkipReason;
 use crate::file_copier::{
-    copy_unsupported_files, verify_output_completeness, SUPPORTED_VIDEO_EXTENSIONS,
+    copy_unsupported_files, verify_output_completeness_excluding, SUPPORTED_VIDEO_EXTENSIONS,
+};
+use crate::report::{
+    print_benchmark_summary, print_file_report, print_growth_audit, print_stream_ratio_summary,
+    print_summary_report, reduction_pct, FileReport, GrowthAudit, ReportSortKey,
 };
-use crate::report::print_summary_report;
 use crate::smart_file_copier::fix_extension_if_mismatch;
-use anyhow::Result;
+use anyhow::{Context, Result};
 use log::{error, info, warn};
 use std::path::{Path, PathBuf};
-use std::time::Instant;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 pub trait CliProcessingResult {
     fn is_skipped(&self) -> bool;
@@ -19,6 +27,18 @@ pub trait CliProcessingResult {
     fn input_size(&self) -> u64;
     fn output_size(&self) -> Option<u64>;
     fn message(&self) -> &str;
+    /// `--benchmark` figures for a converted file; `None` when benchmarking is off.
+    fn benchmark(&self) -> Option<&crate::conversion_types::EncodeBenchmark> {
+        None
+    }
+    /// Category of a skip for the summary breakdown; only meaningful when `is_skipped()`.
+    fn skip_kind(&self) -> crate::conversion_types::SkipReason {
+        crate::conversion_types::SkipReason::from_code(self.skip_reason().unwrap_or_default())
+    }
+    /// SSIM of the output, for the per-file report; `None` when it was not measured.
+    fn ssim(&self) -> Option<f64> {
+        None
+    }
 }
 
 impl CliProcessingResult for crate::conversion::ConversionResult {
@@ -55,6 +75,21 @@ pub struct CliRunnerConfig {
     pub label: String,
     pub base_dir: Option<PathBuf>,
     pub resume: bool,
+    pub failure_policy: FailurePolicy,
+    pub extension_filter: ExtensionFilter,
+    /// `--max-retries`: re-run a file whose conversion failed with a recoverable error.
+    pub retry_policy: RetryPolicy,
+    /// `--metadata-only-formats`: matching files are copied through instead of converted.
+    pub metadata_only: MetadataOnlyFormats,
+    /// `--limit N`: only the first N pending files in queue order are processed.
+    pub limit: Option<usize>,
+    /// `--only-larger-than-source`: end the run with a list of outputs that did not shrink.
+    pub only_larger_than_source: bool,
+    /// `--sort-report-by`: end the run with a per-file table in this order (also shown,
+    /// by name, in verbose mode).
+    pub sort_report_by: Option<ReportSortKey>,
+    /// `--dedup`: copy through files whose content matches one already converted in the run.
+    pub dedup: bool,
 }
 
 /// Resolve base_dir for video `run` command. Shared by vid_hevc and vid_av1 to reduce duplication.
@@ -74,21 +109,156 @@ pub fn resolve_video_run_base_dir(
     }
 }
 
+/// `copy-metadata SOURCE DEST`: re-apply metadata from an original onto an already-converted
+/// file without re-encoding. Prints one line per category; errors if any category failed.
+pub fn run_copy_metadata_command(source: &Path, dest: &Path) -> Result<()> {
+    for (label, path) in [("Source", source), ("Destination", dest)] {
+        if !path.is_file() {
+            anyhow::bail!("❌ {} file not found: {}", label, path.display());
+        }
+    }
+
+    let report = crate::metadata::copy_metadata_with_report(source, dest);
+    println!("📋 Metadata: {} → {}", source.display(), dest.display());
+    for line in report.lines() {
+        println!("   {}", line);
+    }
+
+    if !report.all_ok() {
+        anyhow::bail!("❌ Some metadata categories were not copied");
+    }
+    Ok(())
+}
+
 pub fn run_auto_command<F, R>(config: CliRunnerConfig, converter: F) -> Result<()>
 where
-    F: Fn(&Path) -> Result<R>,
+    F: Fn(&Path) -> Result<R> + Sync,
     R: CliProcessingResult,
 {
-    if config.input.is_dir() {
-        process_directory(&config, converter)
+    let outcome = if config.input.is_dir() {
+        with_directory_timestamps_preserved(&config.input, || process_directory(&config, converter))
     } else {
         process_single_file(&config, converter)
+    };
+    crate::output_archive::finish_output_archive_with_log();
+    outcome
+}
+
+/// `--dry-run`: estimate output sizes without encoding. `estimate` returns the input size, the
+/// estimated output size and a short target label for one file; files it fails on are listed
+/// and left out of the total.
+pub fn run_estimate_command<F>(config: &CliRunnerConfig, estimate: F) -> Result<()>
+where
+    F: Fn(&Path) -> Result<(u64, u64, String)>,
+{
+    let files = preview_files(config)?;
+
+    println!("📏 Dry run: estimated {} output sizes (±25%)", config.label);
+    let (mut total_in, mut total_out, mut failed) = (0u64, 0u64, 0usize);
+    for file in &files {
+        match estimate(file) {
+            Ok((input_size, output_size, target)) => {
+                println!(
+                    "   {} → ~{} ({})  {}",
+                    crate::format_bytes(input_size),
+                    crate::format_bytes(output_size),
+                    target,
+                    file.display()
+                );
+                total_in += input_size;
+                total_out += output_size;
+            }
+            Err(e) => {
+                println!("   ⚠️ {}: {}", file.display(), e);
+                failed += 1;
+            }
+        }
+    }
+
+    println!(
+        "📊 Estimated total: {} → ~{} ({:.1}% smaller) across {} file(s)",
+        crate::format_bytes(total_in),
+        crate::format_bytes(total_out),
+        reduction_pct(total_in, total_out),
+        files.len() - failed
+    );
+    if failed > 0 {
+        println!("   {} file(s) could not be analyzed", failed);
+    }
+    Ok(())
+}
+
+/// `--print-command`: print the commands `commands` renders for every file, under a `# file`
+/// header so the output can be saved as a shell script. Nothing is converted.
+pub fn run_print_command<F>(config: &CliRunnerConfig, commands: F) -> Result<()>
+where
+    F: Fn(&Path) -> Result<Vec<String>>,
+{
+    print_commands(&preview_files(config)?, commands);
+    Ok(())
+}
+
+/// [`run_print_command`] for a file list the caller collected (the image tools).
+pub fn print_commands<F>(files: &[PathBuf], commands: F)
+where
+    F: Fn(&Path) -> Result<Vec<String>>,
+{
+    for file in files {
+        println!("# {}", file.display());
+        match commands(file) {
+            Ok(lines) => {
+                for line in lines {
+                    println!("{}", line);
+                }
+            }
+            Err(e) => println!("# ⚠️ could not build the command: {}", e),
+        }
     }
 }
 
+/// Files a dry run looks at: the filtered video files under a directory input, or the input.
+fn preview_files(config: &CliRunnerConfig) -> Result<Vec<PathBuf>> {
+    let files = if config.input.is_dir() {
+        let files = crate::collect_video_files_for_perceived_speed(
+            &config.input,
+            SUPPORTED_VIDEO_EXTENSIONS,
+            config.recursive,
+        );
+        config.extension_filter.apply(files)
+    } else {
+        vec![config.input.clone()]
+    };
+    if files.is_empty() {
+        anyhow::bail!("❌ No video files found in: {}", config.input.display());
+    }
+    Ok(files)
+}
+
+/// Snapshot directory timestamps under `root` before `run` and restore them afterwards, so
+/// in-place runs (outputs written next to sources, originals deleted) don't bump folder mtimes.
+fn with_directory_timestamps_preserved<T>(root: &Path, run: impl FnOnce() -> T) -> T {
+    let saved = match crate::save_directory_timestamps(root) {
+        Ok(saved) => Some(saved),
+        Err(e) => {
+            warn!(
+                "⚠️ [Metadata] Failed to snapshot directory timestamps for {}: {}",
+                root.display(),
+                e
+            );
+            None
+        }
+    };
+    let result = run();
+    if let Some(ref saved) = saved {
+        crate::restore_directory_timestamps(saved);
+        info!("✅ Directory timestamps restored");
+    }
+    result
+}
+
 fn process_directory<F, R>(config: &CliRunnerConfig, converter: F) -> Result<()>
 where
-    F: Fn(&Path) -> Result<R>,
+    F: Fn(&Path) -> Result<R> + Sync,
     R: CliProcessingResult,
 {
     let input = &config.input;
@@ -104,6 +274,7 @@ where
         SUPPORTED_VIDEO_EXTENSIONS,
         recursive,
     );
+    let files = config.extension_filter.apply(files);
 
     if files.is_empty() {
         anyhow::bail!(
@@ -122,7 +293,7 @@ where
     // This catches "No space left on device" before encoding starts rather than mid-encode.
     // Skip if MFB_SKIP_DISK_PRECHECK=1 (script has already done the check).
     // Initialize checkpoint manager if resume is enabled
-    let mut checkpoint = if config.resume {
+    let checkpoint = if config.resume {
         match crate::checkpoint::CheckpointManager::new_with_context(input, config.output.as_deref())
         {
             Ok(cp) => {
@@ -131,6 +302,7 @@ where
                         "📂 Resume: skipping {} already completed files",
                         cp.completed_count()
                     );
+                    cp.sync_to_processed_list();
                 } else {
                     crate::clear_processed_list();
                 }
@@ -142,9 +314,15 @@ where
             }
         }
     } else {
+        crate::clear_processed_list();
         None
     };
 
+    // Applied after the checkpoint is loaded so files finished by an earlier run do not count.
+    let (files, limit_note) = crate::batch::apply_limit(files, config.limit, |f| {
+        checkpoint.as_ref().is_some_and(|cp| cp.is_completed(f))
+    });
+
     if std::env::var("MFB_SKIP_DISK_PRECHECK").as_deref() != Ok("1") {
         let total_input_size: u64 = files
             .iter()
@@ -185,28 +363,392 @@ where
     }
 
     let start_time = Instant::now();
-    let mut batch_result = BatchResult::new();
-    let mut total_input_bytes: u64 = 0;
-    let mut total_output_bytes: u64 = 0;
-    let pause_controller = BatchPauseController::new();
+    // Pure video-stream totals, only gathered in verbose mode (one ffprobe per file).
+    let track_streams = crate::progress_mode::is_verbose_mode();
     let total_files = files.len();
-    let progress_bar = crate::CoarseProgressBar::new(total_files as u64, "Running");
-    let mut pending_files = files;
-    let mut recent_success_ext: Option<String> = None;
-    let mut recent_success_parent: Option<PathBuf> = None;
+    let total_bytes: u64 = crate::batch::file_sizes(&files).iter().sum();
+    // Bounded by the Video thread allocation (`--jobs`, memory pressure, multi-instance); each
+    // encode keeps the `child_threads` of that same allocation, so the CPU is not oversubscribed.
+    let jobs = crate::thread_manager::get_balanced_thread_config(
+        crate::thread_manager::WorkloadType::Video,
+    )
+    .parallel_tasks
+    .clamp(1, total_files.max(1));
+    // Several encodes at once: each also waits for its estimated memory to fit the budget.
+    let memory_budget_mb = (jobs > 1)
+        .then(crate::thread_manager::memory_budget_mb)
+        .flatten();
+    if jobs > 1 {
+        info!("⚡ Parallel encodes: {} files at a time", jobs);
+    }
+    if let Some(mb) = memory_budget_mb {
+        info!(
+            "🧠 Memory budget: {:.1} GB for concurrent encodes (estimated from resolution and bit depth)",
+            mb as f64 / 1024.0
+        );
+    }
+
+    let run = DirectoryRun {
+        config,
+        converter: &converter,
+        checkpoint: checkpoint.as_ref(),
+        track_streams,
+        throttle_memory: memory_budget_mb.is_some(),
+        growth_audit: GrowthAudit::new(),
+        file_report: FileReport::new(),
+        pause_controller: BatchPauseController::new(),
+        abort_controller: BatchAbortController::new(),
+        dedup: config.dedup.then(crate::dedup::Deduper::new),
+        progress_bar: Arc::new(
+            crate::CoarseProgressBar::new(total_files as u64, "Running")
+                .with_total_bytes(total_bytes),
+        ),
+        state: Mutex::new(DirectoryRunState::new(files)),
+    };
+    std::thread::scope(|scope| {
+        for _ in 0..jobs {
+            scope.spawn(|| run.work());
+        }
+    });
+    let DirectoryRun {
+        progress_bar,
+        growth_audit,
+        file_report,
+        abort_controller,
+        dedup,
+        state,
+        ..
+    } = run;
+    let DirectoryRunState {
+        mut batch_result,
+        total_input_bytes,
+        total_output_bytes,
+        stream_input_bytes,
+        stream_output_bytes,
+        ..
+    } = state.into_inner().unwrap_or_else(|e| e.into_inner());
+
+    if let Some(report) = crate::verify_after::finish_verify_after() {
+        if let Some(cp) = checkpoint.as_ref() {
+            for (path, _) in &report.reverted {
+                cp.unmark_completed(path);
+            }
+        }
+        batch_result.apply_verify_after(&report);
+    }
+
+    if batch_result.paused {
+        progress_bar.finish_and_clear();
+    } else {
+        progress_bar.finish();
+    }
+
+    // Cleanup checkpoint only on 100% success; a --limit run keeps it for the files it left.
+    if let Some(cp) = checkpoint {
+        if batch_result.paused {
+            if let Err(err) = cp.release_lock() {
+                warn!("⚠️ Failed to release checkpoint lock after pause: {}", err);
+            }
+        } else if batch_result.failed == 0 && limit_note.is_none() {
+            if let Err(err) = cp.cleanup() {
+                warn!("⚠️ Failed to clean up checkpoint state: {}", err);
+            }
+        } else {
+            if let Err(err) = cp.release_lock() {
+                warn!(
+                    "⚠️ Failed to release checkpoint lock after failure: {}",
+                    err
+                );
+            }
+        }
+    }
+
+    print_summary_report(
+        &batch_result,
+        start_time.elapsed(),
+        total_input_bytes,
+        total_output_bytes,
+        &config.label,
+    );
+    if let Some(ref note) = limit_note {
+        info!("{}", note);
+    }
+    if track_streams && batch_result.succeeded > 0 {
+        print_stream_ratio_summary(
+            total_input_bytes,
+            total_output_bytes,
+            stream_input_bytes,
+            stream_output_bytes,
+        );
+    }
+    if config.only_larger_than_source {
+        print_growth_audit(&growth_audit);
+    }
+    if config.sort_report_by.is_some() || crate::progress_mode::is_verbose_mode() {
+        print_file_report(&file_report, config.sort_report_by.unwrap_or_default());
+    }
+    if !batch_result.benchmarks.is_empty() {
+        print_benchmark_summary(&batch_result);
+    }
+    if let Some(dedup) = dedup {
+        dedup.report_with_log();
+    }
+
+    if batch_result.paused {
+        return Ok(());
+    }
+
+    if let Some((path, error)) = abort_controller.abort_info() {
+        anyhow::bail!("{}", fail_fast_message(&path, &error));
+    }
 
-    while !pending_files.is_empty() {
-        if pause_controller.is_paused() {
-            break;
+    // The rest of the tree was deliberately left out, so it is neither copied nor verified.
+    if limit_note.is_some() {
+        return Ok(());
+    }
+
+    if let Some(ref output_dir) = config.output {
+        copy_through_non_video_metadata_only(config, output_dir);
+
+        if crate::smart_file_copier::copy_skipped_enabled() {
+            info!("\n📦 Copying unsupported files...");
+            let copy_result = copy_unsupported_files(input, output_dir, recursive);
+            if copy_result.copied > 0 {
+                info!("📦 Copied {} unsupported files", copy_result.copied);
+            }
+            if copy_result.failed > 0 {
+                error!("❌ Failed to copy {} files", copy_result.failed);
+            }
+
+            match crate::output_archive::open_output_archive() {
+                // Converted outputs went into the archive, so the directory cannot mirror the input.
+                Some((_, true)) => info!(
+                    "\n🗄️  --archive-delete-outputs: outputs are in the archive, completeness check skipped"
+                ),
+                archive => {
+                    info!("\n🔍 Verifying output completeness...");
+                    let verify = verify_output_completeness_excluding(
+                        input,
+                        output_dir,
+                        recursive,
+                        archive.as_ref().map(|(path, _)| path.as_path()),
+                    );
+                    info!("{}", verify.message);
+                    if !verify.passed {
+                        warn!("⚠️  Some files may be missing from output!");
+                    }
+                }
+            }
+        } else {
+            // A converted-only output is not expected to mirror the input.
+            info!(
+                "\n📦 --no-copy-skipped: unsupported files not copied, completeness check skipped"
+            );
         }
 
-        let next_index = select_hot_start_file_index(
-            &pending_files,
-            recent_success_ext.as_deref(),
-            recent_success_parent.as_deref(),
+        if let Some(ref base_dir) = config.base_dir {
+            info!("\n📁 Preserving directory metadata...");
+            if let Err(e) = crate::metadata::preserve_directory_metadata(base_dir, output_dir) {
+                error!("⚠️ Failed to preserve directory metadata: {}", e);
+            } else {
+                info!("✅ Directory metadata preserved");
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Batch totals and the pending queue of one `process_directory` run. Workers take files and
+/// report results under a single lock, so the summary matches a serial run.
+#[derive(Debug)]
+struct DirectoryRunState {
+    pending: Vec<PathBuf>,
+    batch_result: BatchResult,
+    total_input_bytes: u64,
+    total_output_bytes: u64,
+    stream_input_bytes: u64,
+    stream_output_bytes: u64,
+    recent_success_ext: Option<String>,
+    recent_success_parent: Option<PathBuf>,
+}
+
+/// What happened to one file; applied to [`DirectoryRunState`] by [`DirectoryRunState::record`].
+#[derive(Debug)]
+enum FileOutcome {
+    Skipped(SkipReason),
+    Succeeded {
+        path: PathBuf,
+        input_size: u64,
+        output_size: u64,
+        /// (input, output) video-stream sizes when both were measured.
+        stream_sizes: Option<(u64, u64)>,
+        benchmark: Option<crate::conversion_types::EncodeBenchmark>,
+    },
+    Failed {
+        path: PathBuf,
+        error: String,
+        /// Set for conversion failures, which count toward `--fail-fast`.
+        fail_fast_detail: Option<String>,
+    },
+    Paused {
+        path: PathBuf,
+        reason: String,
+    },
+}
+
+impl DirectoryRunState {
+    fn new(pending: Vec<PathBuf>) -> Self {
+        Self {
+            pending,
+            batch_result: BatchResult::new(),
+            total_input_bytes: 0,
+            total_output_bytes: 0,
+            stream_input_bytes: 0,
+            stream_output_bytes: 0,
+            recent_success_ext: None,
+            recent_success_parent: None,
+        }
+    }
+
+    /// Next file in hot-start order; `None` once the queue is empty or the batch is stopping.
+    fn take_next(&mut self, stopping: bool) -> Option<PathBuf> {
+        if stopping || self.pending.is_empty() {
+            return None;
+        }
+        let index = select_hot_start_file_index(
+            &self.pending,
+            self.recent_success_ext.as_deref(),
+            self.recent_success_parent.as_deref(),
         );
-        let file = pending_files.remove(next_index);
-        progress_bar.set_message(&file.file_name().unwrap_or_default().to_string_lossy());
+        Some(self.pending.remove(index))
+    }
+
+    /// `abort` is set under `--fail-fast`; a counted failure fires it.
+    fn record(&mut self, outcome: FileOutcome, abort: Option<&BatchAbortController>) {
+        match outcome {
+            FileOutcome::Skipped(reason) => self.batch_result.skip_because(reason),
+            FileOutcome::Succeeded {
+                path,
+                input_size,
+                output_size,
+                stream_sizes,
+                benchmark,
+            } => {
+                self.batch_result.success();
+                if let Some(benchmark) = benchmark {
+                    self.batch_result.record_benchmark(benchmark);
+                }
+                self.total_input_bytes += input_size;
+                self.total_output_bytes += output_size;
+                if let Some((stream_in, stream_out)) = stream_sizes {
+                    self.stream_input_bytes += stream_in;
+                    self.stream_output_bytes += stream_out;
+                }
+                self.recent_success_ext = extension_lower(&path);
+                self.recent_success_parent = path.parent().map(Path::to_path_buf);
+            }
+            FileOutcome::Failed {
+                path,
+                error,
+                fail_fast_detail,
+            } => {
+                self.batch_result.fail(path.clone(), error);
+                if let (Some(detail), Some(abort)) = (fail_fast_detail, abort) {
+                    if abort.request_abort(&path, detail) {
+                        warn!(
+                            "⛔ Fail-fast: stopping after failure on {}, killing in-flight encodes",
+                            path.display()
+                        );
+                    }
+                }
+            }
+            FileOutcome::Paused { path, reason } => {
+                if !self.batch_result.paused {
+                    let remaining = self.pending.len().saturating_add(1);
+                    self.batch_result.pause(path, reason, remaining);
+                }
+            }
+        }
+    }
+}
+
+/// One directory run shared by the `--jobs` workers.
+struct DirectoryRun<'a, R> {
+    config: &'a CliRunnerConfig,
+    converter: &'a (dyn Fn(&Path) -> Result<R> + Sync),
+    checkpoint: Option<&'a crate::checkpoint::CheckpointManager>,
+    track_streams: bool,
+    /// Hold each encode until its estimated memory fits next to the running ones.
+    throttle_memory: bool,
+    growth_audit: GrowthAudit,
+    file_report: FileReport,
+    pause_controller: BatchPauseController,
+    /// `--fail-fast`: its token is installed on every worker, so the first failure also
+    /// kills the encodes still running on the others.
+    abort_controller: BatchAbortController,
+    /// Contents claimed so far under `--dedup`.
+    dedup: Option<crate::dedup::Deduper>,
+    /// Also the progress sink of every worker, so explore iterations show on the batch line.
+    progress_bar: Arc<crate::CoarseProgressBar>,
+    state: Mutex<DirectoryRunState>,
+}
+
+impl<R: CliProcessingResult> DirectoryRun<'_, R> {
+    fn lock_state(&self) -> std::sync::MutexGuard<'_, DirectoryRunState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn work(&self) {
+        let _sink_guard = crate::progress_sink::install(Some(self.progress_bar.clone()));
+        let _cancel_guard = crate::cancellation::install(Some(self.abort_controller.token()));
+        let fail_fast = self
+            .config
+            .failure_policy
+            .is_fail_fast()
+            .then_some(&self.abort_controller);
+        loop {
+            let next = self
+                .lock_state()
+                .take_next(self.pause_controller.is_paused() || self.abort_controller.is_aborted());
+            let Some(file) = next else {
+                break;
+            };
+            self.progress_bar
+                .set_message(&file.file_name().unwrap_or_default().to_string_lossy());
+            // Taken before conversion: in-place runs replace the original.
+            let file_bytes = std::fs::metadata(&file).map(|m| m.len()).unwrap_or(0);
+            let outcome = self.process(file);
+            let paused = matches!(outcome, FileOutcome::Paused { .. });
+
+            let mut state = self.lock_state();
+            state.record(outcome, fail_fast);
+            if !paused {
+                self.progress_bar.add_bytes(file_bytes);
+                self.progress_bar.set(state.batch_result.total as u64);
+            }
+        }
+    }
+
+    /// A failed file gives back its `--dedup` claim, so a later copy of it is converted.
+    fn release_duplicate_claim(&self, path: &Path) {
+        if let Some(dedup) = &self.dedup {
+            dedup.release_claim(path);
+        }
+    }
+
+    fn pause(&self, path: &Path, reason: String) -> FileOutcome {
+        if self.pause_controller.request_pause(path, reason.clone()) {
+            warn!("⏸️ Batch paused at {}: {}", path.display(), reason);
+        }
+        FileOutcome::Paused {
+            path: path.to_path_buf(),
+            reason,
+        }
+    }
+
+    fn process(&self, file: PathBuf) -> FileOutcome {
+        let config = self.config;
 
         // Fix extension by content first; after fix, only treat as video if extension still in list (avoids disguised-extension panic).
         let fixed = match fix_extension_if_mismatch(&file) {
@@ -214,15 +756,13 @@ where
             Err(e) => {
                 error!("❌ Extension fix failed for {}: {}", file.display(), e);
                 if let Some(reason) = disk_full_pause_reason(&e.to_string()) {
-                    if pause_controller.request_pause(&file, reason.clone()) {
-                        warn!("⏸️ Batch paused at {}: {}", file.display(), reason);
-                    }
-                    batch_result.pause(file.clone(), reason, pending_files.len().saturating_add(1));
-                    break;
+                    return self.pause(&file, reason);
                 }
-                batch_result.fail(file.clone(), e.to_string());
-                progress_bar.set(batch_result.total as u64);
-                continue;
+                return FileOutcome::Failed {
+                    path: file,
+                    error: e.to_string(),
+                    fail_fast_detail: None,
+                };
             }
         };
         if !has_extension(&fixed, SUPPORTED_VIDEO_EXTENSIONS) {
@@ -241,21 +781,62 @@ where
                     );
                 }
             }
-            batch_result.skip();
-            progress_bar.set(batch_result.total as u64);
-            continue;
+            return FileOutcome::Skipped(SkipReason::Other);
         }
 
         // Skip if already processed
-        if let Some(ref cp) = checkpoint {
-            if cp.is_completed(&fixed) {
-                batch_result.skip();
-                progress_bar.set(batch_result.total as u64);
-                continue;
+        if self.checkpoint.is_some_and(|cp| cp.is_completed(&fixed)) {
+            return FileOutcome::Skipped(SkipReason::AlreadyProcessed);
+        }
+
+        // Dedup, the memory estimate, detection and the encoder setup all probe the input;
+        // ffprobe runs once per file.
+        let _probe_cache =
+            crate::file_probe_cache::install(crate::file_probe_cache::FileProbeCache::new(&fixed));
+
+        if config.metadata_only.matches(&fixed) {
+            copy_through_metadata_only(config, &fixed);
+            return FileOutcome::Skipped(SkipReason::MetadataOnly);
+        }
+
+        if let Some(first) = self
+            .dedup
+            .as_ref()
+            .and_then(|dedup| dedup.check_duplicate(&fixed))
+        {
+            info!(
+                "⏭️ {} → SKIP (duplicate of {})",
+                fixed.file_name().unwrap_or_default().to_string_lossy(),
+                first.display()
+            );
+            if let Err(copy_err) = crate::smart_file_copier::copy_on_skip_or_fail(
+                &fixed,
+                config.output.as_deref(),
+                config.base_dir.as_deref(),
+                true,
+            ) {
+                error!("❌ Failed to copy {}: {}", fixed.display(), copy_err);
             }
+            return FileOutcome::Skipped(SkipReason::DuplicateContent);
         }
 
-        match converter(fixed.as_path()) {
+        // Measured up front: in-place runs delete the original on success.
+        let input_stream_size = if self.track_streams {
+            crate::stream_size::extract_stream_sizes(&fixed).video_stream_size
+        } else {
+            0
+        };
+
+        let _memory = self.throttle_memory.then(|| {
+            // Unprobeable sources are budgeted as 1080p 8-bit.
+            let estimate = crate::probe_video(&fixed).map_or_else(
+                |_| crate::thread_manager::estimate_task_memory_mb(1920, 1080, 8),
+                |p| crate::thread_manager::estimate_task_memory_mb(p.width, p.height, p.bit_depth),
+            );
+            crate::thread_manager::reserve_task_memory(estimate)
+        });
+
+        match convert_with_retries(config, &fixed, &self.converter) {
             Ok(result) => {
                 if result.is_skipped() {
                     info!(
@@ -263,7 +844,7 @@ where
                         fixed.file_name().unwrap_or_default().to_string_lossy(),
                         result.skip_reason().unwrap_or("unknown")
                     );
-                    batch_result.skip();
+                    FileOutcome::Skipped(result.skip_kind())
                 } else if result.is_success() {
                     info!(
                         "{} → {} ({}) ✅",
@@ -271,15 +852,38 @@ where
                         result.output_path().unwrap_or("?"),
                         result.message()
                     );
-                    batch_result.success();
                     crate::progress_mode::video_processed_success();
-                    total_input_bytes += result.input_size();
-                    total_output_bytes += result.output_size().unwrap_or(result.input_size());
-                    recent_success_ext = extension_lower(&fixed);
-                    recent_success_parent = fixed.parent().map(Path::to_path_buf);
+                    if let Some(output_size) = result.output_size() {
+                        self.growth_audit.record(
+                            &fixed,
+                            result.output_path().map(Path::new),
+                            result.input_size(),
+                            output_size,
+                        );
+                        self.file_report.record(
+                            &fixed,
+                            result.input_size(),
+                            output_size,
+                            result.ssim(),
+                        );
+                    }
+                    let stream_sizes = if self.track_streams {
+                        let output_stream_size = result
+                            .output_path()
+                            .map(|p| crate::stream_size::get_output_video_stream_size(Path::new(p)))
+                            .unwrap_or(0);
+                        (input_stream_size > 0 && output_stream_size > 0)
+                            .then_some((input_stream_size, output_stream_size))
+                    } else {
+                        None
+                    };
+                    if let Some(output) = result.output_path() {
+                        crate::output_archive::archive_output(Path::new(output));
+                        crate::verify_after::record_verify_output(&fixed, Path::new(output));
+                    }
 
                     // Mark as completed
-                    if let Some(ref mut cp) = checkpoint {
+                    if let Some(cp) = self.checkpoint {
                         if let Err(err) = cp.mark_completed(&fixed) {
                             warn!(
                                 "⚠️ Failed to mark checkpoint complete for {}: {}",
@@ -288,25 +892,29 @@ where
                             );
                         }
                     }
+                    FileOutcome::Succeeded {
+                        input_size: result.input_size(),
+                        output_size: result.output_size().unwrap_or(result.input_size()),
+                        stream_sizes,
+                        benchmark: result.benchmark().cloned(),
+                        path: fixed,
+                    }
                 } else {
                     if let Some(reason) = disk_full_pause_reason(result.message()) {
-                        if pause_controller.request_pause(&fixed, reason.clone()) {
-                            warn!("⏸️ Batch paused at {}: {}", fixed.display(), reason);
-                        }
-                        batch_result.pause(
-                            fixed.clone(),
-                            reason,
-                            pending_files.len().saturating_add(1),
-                        );
-                        break;
+                        return self.pause(&fixed, reason);
                     }
                     info!(
                         "{} → FAILED ({}) ❌",
                         fixed.file_name().unwrap_or_default().to_string_lossy(),
                         result.message()
                     );
-                    batch_result.fail(fixed.clone(), result.message().to_string());
                     crate::progress_mode::video_processed_failure();
+                    self.release_duplicate_claim(&fixed);
+                    FileOutcome::Failed {
+                        path: fixed,
+                        error: result.message().to_string(),
+                        fail_fast_detail: Some(result.message().to_string()),
+                    }
                 }
             }
             Err(e) => {
@@ -316,20 +924,11 @@ where
                         "⏭️ {} → SKIP (output exists)",
                         fixed.file_name().unwrap_or_default().to_string_lossy()
                     );
-                    batch_result.skip();
+                    FileOutcome::Skipped(SkipReason::AlreadyProcessed)
                 } else if let Some(reason) = disk_full_pause_reason(&error_msg) {
-                    if pause_controller.request_pause(&fixed, reason.clone()) {
-                        warn!("⏸️ Batch paused at {}: {}", fixed.display(), reason);
-                    }
-                    batch_result.pause(
-                        fixed.clone(),
-                        reason,
-                        pending_files.len().saturating_add(1),
-                    );
-                    break;
+                    self.pause(&fixed, reason)
                 } else {
                     info!("❌ {} failed: {}", fixed.display(), e);
-                    batch_result.fail(fixed.clone(), e.to_string());
 
                     if let Err(copy_err) = crate::smart_file_copier::copy_on_skip_or_fail(
                         &fixed,
@@ -345,79 +944,16 @@ where
                         );
                     }
                     crate::progress_mode::video_processed_failure();
+                    self.release_duplicate_claim(&fixed);
+                    FileOutcome::Failed {
+                        path: fixed,
+                        error: error_msg,
+                        fail_fast_detail: Some(format!("{:#}", e)),
+                    }
                 }
             }
         }
-
-        progress_bar.set(batch_result.total as u64);
-    }
-
-    if batch_result.paused {
-        progress_bar.finish_and_clear();
-    } else {
-        progress_bar.finish();
     }
-
-    // Cleanup checkpoint only on 100% success
-    if let Some(cp) = checkpoint {
-        if batch_result.paused {
-            if let Err(err) = cp.release_lock() {
-                warn!("⚠️ Failed to release checkpoint lock after pause: {}", err);
-            }
-        } else if batch_result.failed == 0 {
-            if let Err(err) = cp.cleanup() {
-                warn!("⚠️ Failed to clean up checkpoint state: {}", err);
-            }
-        } else {
-            if let Err(err) = cp.release_lock() {
-                warn!(
-                    "⚠️ Failed to release checkpoint lock after failure: {}",
-                    err
-                );
-            }
-        }
-    }
-
-    print_summary_report(
-        &batch_result,
-        start_time.elapsed(),
-        total_input_bytes,
-        total_output_bytes,
-        &config.label,
-    );
-
-    if batch_result.paused {
-        return Ok(());
-    }
-
-    if let Some(ref output_dir) = config.output {
-        info!("\n📦 Copying unsupported files...");
-        let copy_result = copy_unsupported_files(input, output_dir, recursive);
-        if copy_result.copied > 0 {
-            info!("📦 Copied {} unsupported files", copy_result.copied);
-        }
-        if copy_result.failed > 0 {
-            error!("❌ Failed to copy {} files", copy_result.failed);
-        }
-
-        info!("\n🔍 Verifying output completeness...");
-        let verify = verify_output_completeness(input, output_dir, recursive);
-        info!("{}", verify.message);
-        if !verify.passed {
-            warn!("⚠️  Some files may be missing from output!");
-        }
-
-        if let Some(ref base_dir) = config.base_dir {
-            info!("\n📁 Preserving directory metadata...");
-            if let Err(e) = crate::metadata::preserve_directory_metadata(base_dir, output_dir) {
-                error!("⚠️ Failed to preserve directory metadata: {}", e);
-            } else {
-                info!("✅ Directory metadata preserved");
-            }
-        }
-    }
-
-    Ok(())
 }
 
 fn extension_lower(path: &Path) -> Option<String> {
@@ -460,6 +996,26 @@ fn select_hot_start_file_index(
     best_index
 }
 
+/// One conversion under `config.retry_policy`. Both `Err` results and failed (non-skipped)
+/// results are retried when their message is recoverable.
+fn convert_with_retries<F, R>(config: &CliRunnerConfig, file: &Path, converter: &F) -> Result<R>
+where
+    F: Fn(&Path) -> Result<R>,
+    R: CliProcessingResult,
+{
+    config.retry_policy.run(
+        file,
+        || converter(file),
+        |outcome| match outcome {
+            Err(e) => Some(format!("{:#}", e)),
+            Ok(result) if !result.is_success() && !result.is_skipped() => {
+                Some(result.message().to_string())
+            }
+            Ok(_) => None,
+        },
+    )
+}
+
 fn process_single_file<F, R>(config: &CliRunnerConfig, converter: F) -> Result<()>
 where
     F: Fn(&Path) -> Result<R>,
@@ -505,7 +1061,12 @@ where
         );
     }
 
-    let result = match converter(input) {
+    if config.metadata_only.matches(input) {
+        copy_through_metadata_only(config, input);
+        return Ok(());
+    }
+
+    let result = match convert_with_retries(config, input, &converter) {
         Ok(r) => r,
         Err(e) => {
             if let Some(ref output_dir) = config.output {
@@ -542,10 +1103,187 @@ where
         );
     }
     info!("   Result: {}", result.message());
+    if let Some(benchmark) = result.benchmark() {
+        info!("   Throughput: {}", benchmark);
+    }
+    if result.is_success() && !result.is_skipped() {
+        if let Some(out_path) = result.output_path() {
+            crate::output_archive::archive_output(Path::new(out_path));
+            crate::verify_after::record_verify_output(input, Path::new(out_path));
+        }
+    }
+    if let Some(report) = crate::verify_after::finish_verify_after_with_log() {
+        if let Some((path, reason)) = report.reverted.first() {
+            anyhow::bail!("❌ Verify-after reverted {}: {}", path.display(), reason);
+        }
+    }
+
+    Ok(())
+}
+
+/// Metadata-only formats the video walk never collects (the `--preset archive` default is
+/// JXL/HEIC/HEIF) are copied through after the batch, so their sidecars are merged too.
+fn copy_through_non_video_metadata_only(config: &CliRunnerConfig, output_dir: &Path) {
+    if config.metadata_only.is_empty() {
+        return;
+    }
+    let output_dir = output_dir
+        .canonicalize()
+        .unwrap_or_else(|_| output_dir.to_path_buf());
+    for entry in crate::batch::walk_dir(&config.input, config.recursive).flatten() {
+        let path = entry.path();
+        if entry.file_type().is_file()
+            && config.metadata_only.matches(path)
+            && !has_extension(path, SUPPORTED_VIDEO_EXTENSIONS)
+            && !path
+                .canonicalize()
+                .is_ok_and(|p| p.starts_with(&output_dir))
+        {
+            copy_through_metadata_only(config, path);
+        }
+    }
+}
+
+/// Copy a `--metadata-only-formats` file to the output directory; the copy merges its XMP
+/// sidecar. Without `--output` the file is simply left alone.
+fn copy_through_metadata_only(config: &CliRunnerConfig, file: &Path) {
+    let name = file.file_name().unwrap_or_default().to_string_lossy();
+    let Some(ref out) = config.output else {
+        info!("⏭️ {} → SKIP (metadata-only format)", name);
+        return;
+    };
+    match crate::smart_file_copier::copy_through(file, Some(out), config.base_dir.as_deref(), false)
+    {
+        Ok(_) => info!("📋 {} → copied through (metadata-only format)", name),
+        Err(e) => error!("❌ Failed to copy {}: {}", file.display(), e),
+    }
+}
+
+/// `watch DIR`: convert every new video under `config.input` once it has stopped growing.
+/// Files already in DIR are left alone. After a successful conversion the source is moved
+/// into `done_dir` (same relative path) when one is given; deletion follows the conversion
+/// config exactly as in `run`. Failures are logged and the watch continues.
+pub fn run_watch_command<F, R>(
+    config: CliRunnerConfig,
+    settle: Duration,
+    done_dir: Option<PathBuf>,
+    converter: F,
+) -> Result<()>
+where
+    F: Fn(&Path) -> Result<R>,
+    R: CliProcessingResult,
+{
+    if !config.input.is_dir() {
+        anyhow::bail!("❌ Not a directory: {}", config.input.display());
+    }
+    if let Err(e) = crate::safety::check_apple_photos_library(&config.input) {
+        anyhow::bail!("{}", e);
+    }
+
+    // Create the output and done dirs up front: a dir that does not exist yet cannot be
+    // canonicalized, and an uncanonical ignore path would not match the watcher's events.
+    for dir in [config.output.as_deref(), done_dir.as_deref()]
+        .into_iter()
+        .flatten()
+    {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create {}", dir.display()))?;
+    }
+
+    // Watcher events carry canonical paths (e.g. /private/var on macOS).
+    let canonical = |p: &Path| p.canonicalize().unwrap_or_else(|_| p.to_path_buf());
+    let root = canonical(&config.input);
+    let options = crate::watch_folder::WatchOptions {
+        recursive: config.recursive,
+        settle,
+        extensions: SUPPORTED_VIDEO_EXTENSIONS,
+        ignore: [config.output.as_deref(), done_dir.as_deref()]
+            .into_iter()
+            .flatten()
+            .map(canonical)
+            .collect(),
+    };
+
+    info!(
+        "👀 Watching {} for new videos (settle {}s, Ctrl+C to stop)",
+        root.display(),
+        settle.as_secs()
+    );
+    crate::watch_folder::watch_directory(&root, &options, |file| {
+        if let Err(e) = process_watched_file(&config, &root, file, done_dir.as_deref(), &converter)
+        {
+            error!("❌ {} failed: {:#}", file.display(), e);
+        }
+        crate::progress_mode::flush_log_file();
+    })
+}
+
+fn process_watched_file<F, R>(
+    config: &CliRunnerConfig,
+    root: &Path,
+    file: &Path,
+    done_dir: Option<&Path>,
+    converter: &F,
+) -> Result<()>
+where
+    F: Fn(&Path) -> Result<R>,
+    R: CliProcessingResult,
+{
+    let fixed = fix_extension_if_mismatch(file)?;
+    if !has_extension(&fixed, SUPPORTED_VIDEO_EXTENSIONS) {
+        info!(
+            "⏭️ {} → SKIP (not a video after content check)",
+            fixed.display()
+        );
+        return Ok(());
+    }
+
+    let result = convert_with_retries(config, &fixed, converter)?;
+    let name = fixed.file_name().unwrap_or_default().to_string_lossy();
+    if result.is_skipped() {
+        info!(
+            "⏭️ {} → SKIP ({})",
+            name,
+            result.skip_reason().unwrap_or("unknown")
+        );
+        return Ok(());
+    }
+    if !result.is_success() {
+        anyhow::bail!("{}", result.message());
+    }
+    info!(
+        "{} → {} ({}) ✅",
+        name,
+        result.output_path().unwrap_or("?"),
+        result.message()
+    );
 
+    // The source is gone when the conversion config deleted it.
+    if let (Some(done_dir), true) = (done_dir, fixed.exists()) {
+        let moved = move_into_dir(&fixed, root, done_dir)?;
+        info!("📦 Moved source to {}", moved.display());
+    }
     Ok(())
 }
 
+/// Move `file` under `dest_root`, keeping its path relative to `root`. Falls back to
+/// copy + remove across filesystems.
+fn move_into_dir(file: &Path, root: &Path, dest_root: &Path) -> Result<PathBuf> {
+    let relative = file
+        .strip_prefix(root)
+        .map(Path::to_path_buf)
+        .unwrap_or_else(|_| PathBuf::from(file.file_name().unwrap_or_default()));
+    let target = dest_root.join(relative);
+    if let Some(parent) = target.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    if std::fs::rename(file, &target).is_err() {
+        std::fs::copy(file, &target)?;
+        std::fs::remove_file(file)?;
+    }
+    Ok(target)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -574,4 +1312,179 @@ mod tests {
         let next = select_hot_start_file_index(&pending, Some("mov"), Some(Path::new("beta")));
         assert_eq!(next, 1);
     }
+
+    #[test]
+    fn parallel_workers_aggregate_the_same_totals_as_a_serial_run() {
+        let files: Vec<PathBuf> = (0..64)
+            .map(|i| PathBuf::from(format!("dir/clip-{:02}.mp4", i)))
+            .collect();
+        let state = Mutex::new(DirectoryRunState::new(files));
+
+        std::thread::scope(|scope| {
+            for _ in 0..4 {
+                scope.spawn(|| loop {
+                    let Some(path) = state.lock().unwrap().take_next(false) else {
+                        break;
+                    };
+                    let outcome = if path.to_string_lossy().ends_with("0.mp4") {
+                        FileOutcome::Skipped(SkipReason::ModernLossy)
+                    } else {
+                        FileOutcome::Succeeded {
+                            path,
+                            input_size: 100,
+                            output_size: 60,
+                            stream_sizes: Some((90, 50)),
+                            benchmark: None,
+                        }
+                    };
+                    state.lock().unwrap().record(outcome, None);
+                });
+            }
+        });
+
+        let state = state.into_inner().unwrap();
+        assert!(state.pending.is_empty());
+        assert_eq!(state.batch_result.total, 64);
+        assert_eq!(state.batch_result.skipped, 7);
+        assert_eq!(
+            state.batch_result.skip_breakdown(),
+            vec![(SkipReason::ModernLossy, 7)]
+        );
+        assert_eq!(state.batch_result.succeeded, 57);
+        assert_eq!(state.total_input_bytes, 5_700);
+        assert_eq!(state.total_output_bytes, 3_420);
+        assert_eq!(state.stream_output_bytes, 2_850);
+    }
+
+    #[test]
+    fn fail_fast_and_pause_stop_handing_out_files() {
+        let files = vec![
+            PathBuf::from("a.mp4"),
+            PathBuf::from("b.mp4"),
+            PathBuf::from("c.mp4"),
+        ];
+        let abort = BatchAbortController::new();
+        let mut state = DirectoryRunState::new(files.clone());
+        let first = state.take_next(false).unwrap();
+        state.record(
+            FileOutcome::Failed {
+                path: first.clone(),
+                error: "encode failed".to_string(),
+                fail_fast_detail: Some("encode failed: exit 1".to_string()),
+            },
+            Some(&abort),
+        );
+        assert_eq!(
+            abort.abort_info(),
+            Some((first, "encode failed: exit 1".to_string()))
+        );
+        assert_eq!(state.take_next(abort.is_aborted()), None);
+
+        let mut state = DirectoryRunState::new(files);
+        assert!(state.take_next(true).is_none());
+        let path = state.take_next(false).unwrap();
+        state.record(
+            FileOutcome::Paused {
+                path,
+                reason: "disk full".to_string(),
+            },
+            None,
+        );
+        assert!(state.batch_result.paused);
+        assert_eq!(state.batch_result.paused_remaining, 3);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn fail_fast_kills_the_encode_running_on_the_other_worker() {
+        let dir = tempfile::tempdir().unwrap();
+        let temp = dir.path().join("slow.tmp.mp4");
+        let abort = BatchAbortController::new();
+        let state = Mutex::new(DirectoryRunState::new(vec![
+            PathBuf::from("slow.mp4"),
+            PathBuf::from("bad.mp4"),
+            PathBuf::from("next.mp4"),
+        ]));
+        let slow = state.lock().unwrap().take_next(false).unwrap();
+        let bad = state.lock().unwrap().take_next(false).unwrap();
+        assert_eq!(slow, PathBuf::from("slow.mp4"));
+
+        std::thread::scope(|scope| {
+            // Same setup as `DirectoryRun::work`: the batch token on the thread, the
+            // encoder child watched against it, the partial output behind a temp guard.
+            let slow_worker = scope.spawn(|| {
+                let _cancel_guard = crate::cancellation::install(Some(abort.token()));
+                let _temp_guard = crate::conversion::TempOutputGuard::new(temp.clone());
+                std::fs::write(&temp, b"partial encode").unwrap();
+                let mut child = std::process::Command::new("sleep").arg("30").spawn().ok()?;
+                let _watcher = crate::cancellation::ChildWatcher::for_current(&child);
+                let started = Instant::now();
+                let status = child.wait().unwrap();
+                Some((status, started.elapsed()))
+            });
+            scope.spawn(|| {
+                let _cancel_guard = crate::cancellation::install(Some(abort.token()));
+                state.lock().unwrap().record(
+                    FileOutcome::Failed {
+                        path: bad.clone(),
+                        error: "encode failed".to_string(),
+                        fail_fast_detail: Some("encode failed: exit 1".to_string()),
+                    },
+                    Some(&abort),
+                );
+            });
+            if let Some((status, elapsed)) = slow_worker.join().unwrap() {
+                assert!(!status.success());
+                assert!(elapsed < Duration::from_secs(10));
+            }
+        });
+
+        assert!(!temp.exists());
+        assert_eq!(
+            abort.abort_info(),
+            Some((bad, "encode failed: exit 1".to_string()))
+        );
+        let mut state = state.into_inner().unwrap();
+        assert_eq!(state.take_next(abort.is_aborted()), None);
+        assert_eq!(state.pending, vec![PathBuf::from("next.mp4")]);
+    }
+
+    #[test]
+    fn watch_moves_source_under_done_dir_with_relative_path() {
+        let root = tempfile::tempdir().unwrap();
+        let done = tempfile::tempdir().unwrap();
+        std::fs::create_dir(root.path().join("cam")).unwrap();
+        let source = root.path().join("cam/clip.mov");
+        std::fs::write(&source, b"source").unwrap();
+
+        let moved = move_into_dir(&source, root.path(), done.path()).unwrap();
+        assert_eq!(moved, done.path().join("cam/clip.mov"));
+        assert!(!source.exists());
+        assert_eq!(std::fs::read(&moved).unwrap(), b"source");
+    }
+
+    #[test]
+    fn in_place_run_leaves_directory_mtimes_unchanged() {
+        let root = tempfile::tempdir().unwrap();
+        let album = root.path().join("2019 Trip");
+        std::fs::create_dir(&album).unwrap();
+        let original = album.join("clip.mov");
+        std::fs::write(&original, b"source").unwrap();
+
+        let old = filetime::FileTime::from_unix_time(1_500_000_000, 0);
+        for dir in [root.path(), album.as_path()] {
+            filetime::set_file_times(dir, old, old).unwrap();
+        }
+
+        // In-place conversion: output written next to the source, original removed.
+        with_directory_timestamps_preserved(root.path(), || {
+            std::fs::write(album.join("clip.mp4"), b"converted").unwrap();
+            std::fs::remove_file(&original).unwrap();
+        });
+
+        for dir in [root.path(), album.as_path()] {
+            let meta = std::fs::metadata(dir).unwrap();
+            assert_eq!(filetime::FileTime::from_last_modification_time(&meta), old);
+        }
+    }
 }
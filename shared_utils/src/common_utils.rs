@@ -427,6 +427,28 @@ pub fn format_command_string(command: &str, args: &[&str]) -> String {
     }
 }
 
+/// Like [`format_command_string`], but every argument a POSIX shell would split or expand is
+/// single-quoted, so the line can be pasted into a terminal as is (`--print-command`).
+pub fn shell_command_string<S: AsRef<str>>(command: &str, args: &[S]) -> String {
+    std::iter::once(command)
+        .chain(args.iter().map(AsRef::as_ref))
+        .map(shell_quote)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn shell_quote(arg: &str) -> std::borrow::Cow<'_, str> {
+    let plain = !arg.is_empty()
+        && arg
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_./:=+,@%".contains(c));
+    if plain {
+        std::borrow::Cow::Borrowed(arg)
+    } else {
+        std::borrow::Cow::Owned(format!("'{}'", arg.replace('\'', "'\\''")))
+    }
+}
+
 pub fn validate_file_integrity(path: &std::path::Path) -> anyhow::Result<()> {
     let metadata = std::fs::metadata(path)?;
     let size = metadata.len();
@@ -591,6 +613,17 @@ mod tests {
         assert_eq!(format_command_string("ls", &[]), "ls");
     }
 
+    #[test]
+    fn test_shell_command_string_quotes_only_when_needed() {
+        assert_eq!(
+            shell_command_string(
+                "ffmpeg",
+                &["-i", "My Clip's.mov", "-vf", "scale=iw:-2", "", "out.mp4"]
+            ),
+            r"ffmpeg -i 'My Clip'\''s.mov' -vf scale=iw:-2 '' out.mp4"
+        );
+    }
+
     #[test]
     fn test_execute_command_with_logging() {
         let mut cmd = Command::new("echo");
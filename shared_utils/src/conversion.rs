@@ -25,13 +25,13 @@
 
 use crate::modern_ui::{colors, symbols};
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::io::{BufRead, BufReader, Write};
 use std::path::{Component, Path, PathBuf};
 use std::sync::{
-    atomic::{AtomicU64, Ordering},
-    LazyLock, Mutex,
+    atomic::{AtomicBool, AtomicU64, Ordering},
+    LazyLock, Mutex, OnceLock,
 };
 use std::time::{SystemTime, UNIX_EPOCH};
 
@@ -86,8 +86,154 @@ pub fn clear_processed_list() {
     processed.clear();
 }
 
+static SKIP_UP_TO_DATE: AtomicBool = AtomicBool::new(false);
+
+/// Slack for filesystems that store mtimes coarsely (FAT keeps 2 s), so a preserved timestamp
+/// that lost its fraction still counts as current.
+const MTIME_SLACK: std::time::Duration = std::time::Duration::from_secs(2);
+
+/// `--skip-up-to-date`: an existing output is kept only while it is at least as new as its
+/// source; outputs of edited sources are converted again without `--force`.
+pub fn set_skip_up_to_date(enabled: bool) {
+    SKIP_UP_TO_DATE.store(enabled, Ordering::Relaxed);
+}
+
+pub fn skip_up_to_date_enabled() -> bool {
+    SKIP_UP_TO_DATE.load(Ordering::Relaxed)
+}
+
+/// Whether `output` is at least as new as `input`. Outputs carry their source's mtime
+/// (metadata preservation), so an older output means the source changed after conversion.
+/// Unreadable mtimes count as current, which keeps the plain "exists" behaviour.
+pub fn output_is_up_to_date(input: &Path, output: &Path) -> bool {
+    let mtime = |p: &Path| fs::metadata(p).and_then(|m| m.modified()).ok();
+    match (mtime(input), mtime(output)) {
+        (Some(source), Some(out)) => out + MTIME_SLACK >= source,
+        _ => true,
+    }
+}
+
+/// Whether an existing `output` means `input` is skipped: always without `force` (unless
+/// `collision` overwrites), and with `--skip-up-to-date` only while the output is still
+/// [up to date](output_is_up_to_date).
+pub fn keeps_existing_output(
+    input: &Path,
+    output: &Path,
+    force: bool,
+    collision: CollisionPolicy,
+) -> bool {
+    if force || !output.exists() || collision == CollisionPolicy::Overwrite {
+        return false;
+    }
+    !skip_up_to_date_enabled() || output_is_up_to_date(input, output)
+}
+
+/// `--on-collision`: what happens when the output path of a file already exists.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CollisionPolicy {
+    /// Keep the existing output and skip the file (with `--skip-up-to-date`, only while it is
+    /// current).
+    #[default]
+    Skip,
+    /// Replace the existing output.
+    Overwrite,
+    /// Write next to it as `name (1).ext`, `name (2).ext`, ...
+    Rename,
+    /// Fail the file.
+    Error,
+}
+
+impl CollisionPolicy {
+    pub const ALL: [CollisionPolicy; 4] = [
+        CollisionPolicy::Skip,
+        CollisionPolicy::Overwrite,
+        CollisionPolicy::Rename,
+        CollisionPolicy::Error,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CollisionPolicy::Skip => "skip",
+            CollisionPolicy::Overwrite => "overwrite",
+            CollisionPolicy::Rename => "rename",
+            CollisionPolicy::Error => "error",
+        }
+    }
+
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let normalized = value.trim().to_ascii_lowercase().replace('_', "-");
+        Self::ALL
+            .into_iter()
+            .find(|p| p.as_str() == normalized)
+            .ok_or_else(|| {
+                format!(
+                    "Invalid collision policy '{}' (expected one of: {})",
+                    value,
+                    Self::ALL.map(|p| p.as_str()).join(", ")
+                )
+            })
+    }
+}
+
+/// Outputs handed out under `--on-collision rename`, with the input each belongs to, so two
+/// files converting at once never pick the same free name and a second lookup for the same
+/// input gets the same answer.
+static RENAMED_OUTPUTS: LazyLock<Mutex<HashMap<PathBuf, PathBuf>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Apply the caller's `--on-collision` policy to a resolved `output` of `input`. `Skip` and
+/// `Overwrite` return it unchanged (they act later, in [`keeps_existing_output`]); `Rename` moves
+/// an existing output to the first free `name (N).ext`; `Error` fails when it exists.
+pub fn resolve_output_collision(
+    input: &Path,
+    output: PathBuf,
+    collision: CollisionPolicy,
+) -> Result<PathBuf, String> {
+    match collision {
+        CollisionPolicy::Skip | CollisionPolicy::Overwrite => Ok(output),
+        CollisionPolicy::Error if output.exists() => Err(format!(
+            "Output already exists: {} (--on-collision error)",
+            output.display()
+        )),
+        CollisionPolicy::Error => Ok(output),
+        CollisionPolicy::Rename => {
+            let mut reserved = RENAMED_OUTPUTS.lock().unwrap_or_else(|e| e.into_inner());
+            let output =
+                first_free_output_name(&output, |candidate| match reserved.get(candidate) {
+                    Some(owner) => owner != input,
+                    None => candidate.exists(),
+                });
+            reserved.insert(output.clone(), input.to_path_buf());
+            Ok(output)
+        }
+    }
+}
+
+/// `output` itself if not `taken`, else `stem (1).ext`, `stem (2).ext`, ...
+fn first_free_output_name(output: &Path, taken: impl Fn(&Path) -> bool) -> PathBuf {
+    if !taken(output) {
+        return output.to_path_buf();
+    }
+    let stem = output
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let ext = output.extension().map(|e| e.to_string_lossy().into_owned());
+    (1u32..)
+        .map(|n| {
+            let name = match &ext {
+                Some(ext) => format!("{} ({}).{}", stem, n, ext),
+                None => format!("{} ({})", stem, n),
+            };
+            output.with_file_name(name)
+        })
+        .find(|candidate| !taken(candidate))
+        .unwrap_or_else(|| output.to_path_buf())
+}
+
 pub use crate::checkpoint::{
-    safe_delete_original, verify_output_integrity, MIN_OUTPUT_SIZE_BEFORE_DELETE_IMAGE,
+    safe_delete_original, safe_delete_original_lossless, verify_output_decodable,
+    verify_output_integrity, MIN_OUTPUT_SIZE_BEFORE_DELETE_IMAGE,
     MIN_OUTPUT_SIZE_BEFORE_DELETE_VIDEO,
 };
 
@@ -334,6 +480,22 @@ pub struct ConvertOptions {
     pub child_threads: usize,
     pub input_format: Option<String>,
     pub quality_label: Option<String>,
+    /// SSIM gate for the animated→video search (`--min-ssim`); `None` uses the CRF-based default.
+    pub min_ssim: Option<f64>,
+    /// `--normalize-orientation`: losslessly rotate JPEGs upright before the JXL transcode.
+    pub normalize_orientation: bool,
+    /// `--auto-grayscale` found no chroma in this image: encode the JXL as single-channel gray.
+    pub grayscale: bool,
+    /// `--min-animation-duration`: animations at least this long go to video and shorter ones
+    /// are kept, replacing the GIF meme-score decision. `None` keeps the meme-score routing.
+    pub min_animation_duration: Option<f32>,
+    /// `--preserve-icc`: attach the source's ICC profile to JXL output even when the input was
+    /// re-encoded for cjxl first, so wide-gamut images are not read back as sRGB.
+    pub preserve_icc: bool,
+    /// `--strip-metadata` / `--strip-timestamps` for this conversion's encoders and metadata step.
+    pub strip: crate::metadata::StripPolicy,
+    /// `--on-collision`: what happens when this conversion's output path already exists.
+    pub on_collision: CollisionPolicy,
 }
 
 impl Default for ConvertOptions {
@@ -355,6 +517,13 @@ impl Default for ConvertOptions {
             child_threads: 0,
             input_format: None,
             quality_label: None,
+            min_ssim: None,
+            normalize_orientation: false,
+            grayscale: false,
+            min_animation_duration: None,
+            preserve_icc: true,
+            strip: crate::metadata::StripPolicy::default(),
+            on_collision: CollisionPolicy::default(),
         }
     }
 }
@@ -379,10 +548,248 @@ impl ConvertOptions {
     }
 }
 
+const OUTPUT_TEMPLATE_PLACEHOLDERS: &[&str] = &["stem", "ext", "codec", "crf", "parent"];
+
+/// Custom output filename from `--output-template`, e.g. `{stem}_hevc.{ext}`.
+///
+/// Placeholders: `{stem}` input stem, `{ext}` output extension, `{codec}` target codec,
+/// `{crf}` planned CRF (empty when it is only known after exploration), `{parent}` name of
+/// the input's parent directory. The rendered name must stay a single safe path component.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutputTemplate {
+    template: String,
+    video_codec: String,
+}
+
+impl OutputTemplate {
+    pub fn parse(template: &str) -> Result<Self, String> {
+        let mut literal = String::new();
+        let mut rest = template;
+        while let Some(open) = rest.find('{') {
+            literal.push_str(&rest[..open]);
+            let after = &rest[open + 1..];
+            let close = after
+                .find('}')
+                .ok_or_else(|| format!("unclosed '{{' in output template '{}'", template))?;
+            let name = &after[..close];
+            if !OUTPUT_TEMPLATE_PLACEHOLDERS.contains(&name) {
+                return Err(format!(
+                    "unknown placeholder '{{{}}}' in output template (expected one of: {})",
+                    name,
+                    OUTPUT_TEMPLATE_PLACEHOLDERS
+                        .iter()
+                        .map(|p| format!("{{{}}}", p))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ));
+            }
+            literal.push('x');
+            rest = &after[close + 1..];
+        }
+        literal.push_str(rest);
+
+        if literal.contains('}') {
+            return Err(format!("unmatched '}}' in output template '{}'", template));
+        }
+        if !template.contains("{stem}") {
+            return Err(format!(
+                "output template '{}' must contain {{stem}} so outputs do not collide",
+                template
+            ));
+        }
+        if !template.contains("{ext}") {
+            return Err(format!(
+                "output template '{}' must contain {{ext}} so outputs keep their format's extension",
+                template
+            ));
+        }
+        crate::path_validator::validate_file_name(&literal).map_err(|e| e.to_string())?;
+
+        Ok(Self {
+            template: template.to_string(),
+            video_codec: String::new(),
+        })
+    }
+
+    /// Codec reported by `{codec}` for video containers (mp4/mov/mkv) written by this tool.
+    pub fn with_video_codec(mut self, codec: &str) -> Self {
+        self.video_codec = codec.to_string();
+        self
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.template
+    }
+
+    /// Best-effort codec name for an output extension when the caller does not know it.
+    pub fn codec_for_extension(&self, extension: &str) -> String {
+        match extension.to_ascii_lowercase().as_str() {
+            "jxl" => "jxl".to_string(),
+            "avif" => "av1".to_string(),
+            "heic" | "heif" => "hevc".to_string(),
+            "mp4" | "mov" | "mkv" if !self.video_codec.is_empty() => self.video_codec.clone(),
+            other => other.to_string(),
+        }
+    }
+
+    pub fn render(
+        &self,
+        input: &Path,
+        extension: &str,
+        codec: &str,
+        crf: Option<f32>,
+    ) -> Result<String, String> {
+        let stem = input
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("output");
+        let parent = input
+            .parent()
+            .and_then(|p| p.file_name())
+            .and_then(|s| s.to_str())
+            .unwrap_or("");
+        let crf = crf.map(|c| c.to_string()).unwrap_or_default();
+
+        let name = self
+            .template
+            .replace("{stem}", stem)
+            .replace("{ext}", extension)
+            .replace("{codec}", codec)
+            .replace("{crf}", &crf)
+            .replace("{parent}", parent);
+
+        crate::path_validator::validate_file_name(&name).map_err(|e| e.to_string())?;
+        Ok(name)
+    }
+}
+
+static OUTPUT_TEMPLATE: OnceLock<OutputTemplate> = OnceLock::new();
+
+/// Process-wide output template; the first call wins.
+pub fn set_output_template(template: OutputTemplate) {
+    let _ = OUTPUT_TEMPLATE.set(template);
+}
+
+pub fn output_template() -> Option<&'static OutputTemplate> {
+    OUTPUT_TEMPLATE.get()
+}
+
+static TEMP_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+/// Process-wide scratch directory (`--temp-dir` / `ConversionConfig::temp_dir`); the first call wins.
+pub fn set_temp_dir(dir: PathBuf) {
+    let _ = TEMP_DIR.set(dir);
+}
+
+/// Where scratch files (decoded frames, intermediate streams, sidecar logs) are created:
+/// the configured temp dir, else the system default. Temp *outputs* stay beside their
+/// destination (`temp_path_for_output`) so the final commit is a same-filesystem rename.
+pub fn scratch_dir() -> PathBuf {
+    TEMP_DIR.get().cloned().unwrap_or_else(std::env::temp_dir)
+}
+
+/// `--temp-dir` must be an existing directory we can create files in.
+pub fn validate_temp_dir(dir: &Path) -> Result<(), String> {
+    if !dir.is_dir() {
+        return Err(format!("{} is not a directory", dir.display()));
+    }
+    tempfile::Builder::new()
+        .prefix(".mfb_write_test")
+        .tempfile_in(dir)
+        .map(drop)
+        .map_err(|e| format!("{} is not writable: {}", dir.display(), e))
+}
+
+/// Output filename from the active `--output-template`, or `None` for the default naming.
+pub fn templated_output_name(
+    input: &Path,
+    extension: &str,
+    codec: Option<&str>,
+    crf: Option<f32>,
+) -> Result<Option<String>, String> {
+    let Some(template) = output_template() else {
+        return Ok(None);
+    };
+    let codec = codec
+        .map(str::to_string)
+        .unwrap_or_else(|| template.codec_for_extension(extension));
+    template.render(input, extension, &codec, crf).map(Some)
+}
+
+static FLATTEN_OUTPUT: AtomicBool = AtomicBool::new(false);
+
+/// `--flatten-output`: put every output directly in the output dir instead of recreating the
+/// source tree under it.
+pub fn set_flatten_output(enabled: bool) {
+    FLATTEN_OUTPUT.store(enabled, Ordering::Relaxed);
+}
+
+pub fn flatten_output_enabled() -> bool {
+    FLATTEN_OUTPUT.load(Ordering::Relaxed)
+}
+
+/// Folder inside `output_dir` for the output of `input`: its folder relative to `base_dir`,
+/// or `output_dir` itself under `--flatten-output`.
+pub fn output_subdir(input: &Path, base_dir: &Path, output_dir: &Path) -> PathBuf {
+    if flatten_output_enabled() {
+        return output_dir.to_path_buf();
+    }
+    let rel_path = input
+        .strip_prefix(base_dir)
+        .unwrap_or(input)
+        .parent()
+        .unwrap_or(Path::new(""));
+    output_dir.join(rel_path)
+}
+
+/// Suffix that keeps a flattened source apart from same-named sources in other folders: the
+/// first 8 hex digits of the BLAKE3 hash of its folder relative to `base_dir`. `None` for
+/// sources directly in `base_dir`.
+fn flatten_name_suffix(input: &Path, base_dir: &Path) -> Option<String> {
+    let rel_dir = input.strip_prefix(base_dir).ok()?.parent()?;
+    if rel_dir.as_os_str().is_empty() {
+        return None;
+    }
+    // '/'-joined so a tree maps to the same names on every platform.
+    let key = rel_dir
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/");
+    Some(blake3::hash(key.as_bytes()).to_hex()[..8].to_string())
+}
+
+/// `--flatten-output`: `output` (already in the flat output dir) renamed so sources from
+/// different folders under `base_dir` cannot collide. Sources directly in `base_dir` keep their
+/// name; deeper ones get `-<hash>` of their relative folder before the extension. The name
+/// depends only on the source path, so reruns and processing order always agree on it.
+pub fn flatten_output_path(input: &Path, base_dir: Option<&Path>, output: PathBuf) -> PathBuf {
+    match base_dir {
+        Some(base) if flatten_output_enabled() => with_flatten_suffix(input, base, output),
+        _ => output,
+    }
+}
+
+fn with_flatten_suffix(input: &Path, base_dir: &Path, output: PathBuf) -> PathBuf {
+    let Some(suffix) = flatten_name_suffix(input, base_dir) else {
+        return output;
+    };
+    let stem = output
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let name = match output.extension() {
+        Some(ext) => format!("{}-{}.{}", stem, suffix, ext.to_string_lossy()),
+        None => format!("{}-{}", stem, suffix),
+    };
+    output.with_file_name(name)
+}
+
 pub fn determine_output_path(
     input: &Path,
     extension: &str,
     output_dir: &Option<PathBuf>,
+    collision: CollisionPolicy,
 ) -> Result<PathBuf, String> {
     let stem = input
         .file_stem()
@@ -390,14 +797,18 @@ pub fn determine_output_path(
         .unwrap_or("output");
 
     let up_ext = extension.to_uppercase();
+    let templated = templated_output_name(input, &up_ext, None, None)?;
     let output = match output_dir {
         Some(dir) => {
             fs::create_dir_all(dir).map_err(|e| {
                 format!("Failed to create output directory {}: {}", dir.display(), e)
             })?;
-            dir.join(format!("{}.{}", stem, up_ext))
+            dir.join(templated.unwrap_or_else(|| format!("{}.{}", stem, up_ext)))
         }
-        None => input.with_extension(up_ext),
+        None => match templated {
+            Some(name) => input.with_file_name(name),
+            None => input.with_extension(up_ext),
+        },
     };
 
     let input_canonical = input.canonicalize().unwrap_or_else(|_| input.to_path_buf());
@@ -425,6 +836,7 @@ pub fn determine_output_path(
         })?;
     }
 
+    let output = resolve_output_collision(input, output, collision)?;
     validate_output_path(&output, None)?;
 
     Ok(output)
@@ -435,6 +847,7 @@ pub fn determine_output_path_with_base(
     base_dir: &Path,
     extension: &str,
     output_dir: &Option<PathBuf>,
+    collision: CollisionPolicy,
 ) -> Result<PathBuf, String> {
     let stem = input
         .file_stem()
@@ -442,15 +855,10 @@ pub fn determine_output_path_with_base(
         .unwrap_or("output");
 
     let up_ext = extension.to_uppercase();
+    let templated = templated_output_name(input, &up_ext, None, None)?;
     let output = match output_dir {
         Some(dir) => {
-            let rel_path = input
-                .strip_prefix(base_dir)
-                .unwrap_or(input)
-                .parent()
-                .unwrap_or(Path::new(""));
-
-            let out_subdir = dir.join(rel_path);
+            let out_subdir = output_subdir(input, base_dir, dir);
             fs::create_dir_all(&out_subdir).map_err(|e| {
                 format!(
                     "Failed to create output directory {}: {}",
@@ -459,9 +867,16 @@ pub fn determine_output_path_with_base(
                 )
             })?;
 
-            out_subdir.join(format!("{}.{}", stem, up_ext))
+            flatten_output_path(
+                input,
+                Some(base_dir),
+                out_subdir.join(templated.unwrap_or_else(|| format!("{}.{}", stem, up_ext))),
+            )
         }
-        None => input.with_extension(up_ext),
+        None => match templated {
+            Some(name) => input.with_file_name(name),
+            None => input.with_extension(up_ext),
+        },
     };
 
     let input_canonical = input.canonicalize().unwrap_or_else(|_| input.to_path_buf());
@@ -489,6 +904,7 @@ pub fn determine_output_path_with_base(
         })?;
     }
 
+    let output = resolve_output_collision(input, output, collision)?;
     validate_output_path(&output, Some(base_dir))?;
 
     Ok(output)
@@ -532,7 +948,7 @@ pub fn pre_conversion_check(
         return Some(ConversionResult::skipped_duplicate(input));
     }
 
-    if output.exists() && !options.force {
+    if keeps_existing_output(input, output, options.force, options.on_collision) {
         return Some(ConversionResult::skipped_exists(input, output));
     }
 
@@ -546,12 +962,63 @@ pub fn finalize_conversion(
     format_name: &str,
     extra_info: Option<&str>,
     options: &ConvertOptions,
+) -> std::io::Result<ConversionResult> {
+    finalize_conversion_inner(
+        input,
+        output,
+        input_size,
+        format_name,
+        extra_info,
+        options,
+        false,
+    )
+}
+
+/// `finalize_conversion` for lossless targets: the original is only deleted once the
+/// output decodes back to the same pixels (`safe_delete_original_lossless`).
+pub fn finalize_lossless_conversion(
+    input: &Path,
+    output: &Path,
+    input_size: u64,
+    format_name: &str,
+    extra_info: Option<&str>,
+    options: &ConvertOptions,
+) -> std::io::Result<ConversionResult> {
+    finalize_conversion_inner(
+        input,
+        output,
+        input_size,
+        format_name,
+        extra_info,
+        options,
+        true,
+    )
+}
+
+fn finalize_conversion_inner(
+    input: &Path,
+    output: &Path,
+    input_size: u64,
+    format_name: &str,
+    extra_info: Option<&str>,
+    options: &ConvertOptions,
+    lossless: bool,
 ) -> std::io::Result<ConversionResult> {
     let output_size = std::fs::metadata(output)?.len();
 
     // Metadata already preserved by commit_temp_to_output_with_metadata
     // (includes EXIF, XMP, xattrs, permissions, and timestamps)
 
+    if crate::metadata::provenance_enabled() {
+        let source = options
+            .input_format
+            .clone()
+            .unwrap_or_else(|| crate::common_utils::get_extension_lowercase(input));
+        let mut provenance = crate::metadata::Provenance::new(&source.to_lowercase());
+        provenance.settings = extra_info.map(str::to_string);
+        crate::metadata::write_provenance(input, output, &provenance, options.strip);
+    }
+
     mark_as_processed(input);
 
     if format_name.eq_ignore_ascii_case("JXL") {
@@ -559,7 +1026,11 @@ pub fn finalize_conversion(
     }
 
     if options.should_delete_original() {
-        safe_delete_original(input, output, MIN_OUTPUT_SIZE_BEFORE_DELETE_IMAGE)?;
+        if lossless {
+            safe_delete_original_lossless(input, output, MIN_OUTPUT_SIZE_BEFORE_DELETE_IMAGE)?;
+        } else {
+            safe_delete_original(input, output, MIN_OUTPUT_SIZE_BEFORE_DELETE_IMAGE)?;
+        }
     }
 
     Ok(ConversionResult::success(
@@ -577,15 +1048,20 @@ pub fn post_conversion_actions(
     input: &Path,
     output: &Path,
     options: &ConvertOptions,
+    lossless: bool,
 ) -> std::io::Result<()> {
-    if let Err(e) = crate::preserve_metadata(input, output) {
+    if let Err(e) = crate::preserve_metadata(input, output, options.strip) {
         eprintln!("⚠️ Failed to preserve metadata: {}", e);
     }
 
     mark_as_processed(input);
 
     if options.should_delete_original() {
-        safe_delete_original(input, output, MIN_OUTPUT_SIZE_BEFORE_DELETE_IMAGE)?;
+        if lossless {
+            safe_delete_original_lossless(input, output, MIN_OUTPUT_SIZE_BEFORE_DELETE_IMAGE)?;
+        } else {
+            safe_delete_original(input, output, MIN_OUTPUT_SIZE_BEFORE_DELETE_IMAGE)?;
+        }
     }
 
     Ok(())
@@ -658,12 +1134,15 @@ pub fn commit_temp_to_output(_temp: &Path, _output: &Path, _force: bool) -> std:
 }
 
 /// Commits a temp file with complete metadata preservation from the original file.
-/// Preserves: timestamps (atime, mtime, btime), xattrs, permissions, EXIF data, XMP sidecars.
+/// Preserves: timestamps (atime, mtime, btime), xattrs, permissions, EXIF data, XMP sidecars,
+/// minus whatever `strip` drops.
 pub fn commit_temp_to_output_with_metadata(
     temp: &Path,
     output: &Path,
     force: bool,
     original: Option<&Path>,
+    strip: crate::metadata::StripPolicy,
+    collision: CollisionPolicy,
 ) -> std::io::Result<bool> {
     validate_output_path(output, None).map_err(std::io::Error::other)?;
 
@@ -676,7 +1155,11 @@ pub fn commit_temp_to_output_with_metadata(
             ));
         }
     }
-    if !force && output.exists() {
+    let keep_existing = match original {
+        Some(src) => keeps_existing_output(src, output, force, collision),
+        None => !force && output.exists() && collision != CollisionPolicy::Overwrite,
+    };
+    if keep_existing {
         if let Err(e) = fs::remove_file(temp) {
             if e.kind() != std::io::ErrorKind::NotFound {
                 eprintln!(
@@ -692,12 +1175,13 @@ pub fn commit_temp_to_output_with_metadata(
 
     // Preserve complete metadata from original file if provided
     if let Some(src) = original {
+        let _phase = crate::phase_profile::phase(crate::phase_profile::ProfilePhase::Metadata);
         // Step 1: Preserve metadata (EXIF, XMP, xattrs, permissions)
         // This may modify the file (e.g., ExifTool writes EXIF/XMP), which changes timestamps
-        if let Err(e) = crate::metadata::preserve_metadata(src, output) {
+        if let Err(e) = crate::metadata::preserve_metadata(src, output, strip) {
             eprintln!("⚠️ Failed to preserve metadata: {}", e);
         }
-        crate::metadata::merge_xmp_sidecar_into_dest(src, output);
+        crate::metadata::merge_xmp_sidecar_into_dest(src, output, strip);
 
         // Step 2: Finder comment branding — only on the committed conversion output
         #[cfg(target_os = "macos")]
@@ -717,7 +1201,9 @@ pub fn commit_temp_to_output_with_metadata(
         // Step 3: Apply timestamps AFTER all file modifications
         // This is critical because ExifTool and other tools reset creation time to current time
         // We must reapply timestamps as the final step to preserve original creation time
-        crate::metadata::apply_file_timestamps(src, output);
+        if !strip.timestamps {
+            crate::metadata::apply_file_timestamps(src, output);
+        }
     }
 
     Ok(true)
@@ -1365,16 +1851,91 @@ mod tests {
     fn test_determine_output_path() {
         let temp = tempdir_in(std::env::current_dir().unwrap()).unwrap();
         let input = temp.path().join("nested/image.png");
-        let output = determine_output_path(&input, "jxl", &None).unwrap();
+        let output = determine_output_path(&input, "jxl", &None, CollisionPolicy::Skip).unwrap();
         assert_eq!(output, temp.path().join("nested/image.JXL"));
     }
 
+    #[test]
+    fn test_flatten_suffix_separates_folders() {
+        let base = Path::new("/photos");
+        let out = |input: &str, name: &str| {
+            with_flatten_suffix(Path::new(input), base, Path::new("/out").join(name))
+        };
+        assert_eq!(
+            out("/photos/IMG_1.jpg", "IMG_1.JXL"),
+            PathBuf::from("/out/IMG_1.JXL")
+        );
+        let a = out("/photos/2023/IMG_1.jpg", "IMG_1.JXL");
+        let b = out("/photos/2024/IMG_1.jpg", "IMG_1.JXL");
+        assert_ne!(a, b);
+        assert_eq!(a, out("/photos/2023/IMG_1.jpg", "IMG_1.JXL"));
+        let name = a.file_name().unwrap().to_string_lossy().into_owned();
+        assert!(name.starts_with("IMG_1-") && name.ends_with(".JXL"));
+        assert_eq!(name.len(), "IMG_1-.JXL".len() + 8);
+        // Only the folder is hashed: same-folder siblings share the suffix.
+        assert_eq!(
+            out("/photos/2023/IMG_2.jpg", "IMG_2.JXL").to_string_lossy(),
+            a.to_string_lossy().replace("IMG_1", "IMG_2")
+        );
+    }
+
+    #[test]
+    fn test_collision_rename_picks_first_free_name() {
+        let taken = ["/out/clip.MP4", "/out/clip (1).MP4"];
+        let is_taken = |p: &Path| taken.iter().any(|t| Path::new(t) == p);
+        assert_eq!(
+            first_free_output_name(Path::new("/out/clip.MP4"), is_taken),
+            PathBuf::from("/out/clip (2).MP4")
+        );
+        assert_eq!(
+            first_free_output_name(Path::new("/out/other.MP4"), is_taken),
+            PathBuf::from("/out/other.MP4")
+        );
+        assert_eq!(
+            CollisionPolicy::parse("Rename"),
+            Ok(CollisionPolicy::Rename)
+        );
+        assert!(CollisionPolicy::parse("replace").is_err());
+    }
+
+    #[test]
+    fn test_collision_policy_is_per_call() {
+        let dir = tempfile::tempdir().unwrap();
+        let input = dir.path().join("clip.mov");
+        let output = dir.path().join("clip.mp4");
+        fs::write(&input, b"source").unwrap();
+        fs::write(&output, b"existing").unwrap();
+
+        assert!(keeps_existing_output(
+            &input,
+            &output,
+            false,
+            CollisionPolicy::Skip
+        ));
+        assert!(!keeps_existing_output(
+            &input,
+            &output,
+            false,
+            CollisionPolicy::Overwrite
+        ));
+        assert!(resolve_output_collision(&input, output.clone(), CollisionPolicy::Error).is_err());
+        assert_eq!(
+            resolve_output_collision(&input, output.clone(), CollisionPolicy::Skip),
+            Ok(output.clone())
+        );
+        assert_eq!(
+            resolve_output_collision(&input, output, CollisionPolicy::Rename),
+            Ok(dir.path().join("clip (1).mp4"))
+        );
+    }
+
     #[test]
     fn test_determine_output_path_with_dir() {
         let temp = tempdir_in(std::env::current_dir().unwrap()).unwrap();
         let input = temp.path().join("nested/image.png");
         let output_dir = Some(temp.path().join("output"));
-        let output = determine_output_path(&input, "avif", &output_dir).unwrap();
+        let output =
+            determine_output_path(&input, "avif", &output_dir, CollisionPolicy::Skip).unwrap();
         assert_eq!(output, temp.path().join("output/image.AVIF"));
     }
 
@@ -1383,13 +1944,53 @@ mod tests {
         let temp = tempdir_in(std::env::current_dir().unwrap()).unwrap();
         let input = temp.path().join("nested/video.mp4");
 
-        let webm = determine_output_path(&input, "webm", &None).unwrap();
+        let webm = determine_output_path(&input, "webm", &None, CollisionPolicy::Skip).unwrap();
         assert_eq!(webm, temp.path().join("nested/video.WEBM"));
 
-        let mkv = determine_output_path(&input, "mkv", &None).unwrap();
+        let mkv = determine_output_path(&input, "mkv", &None, CollisionPolicy::Skip).unwrap();
         assert_eq!(mkv, temp.path().join("nested/video.MKV"));
     }
 
+    #[test]
+    fn test_output_template_render() {
+        let template = OutputTemplate::parse("{parent}-{stem}_{codec}_crf{crf}.{ext}")
+            .unwrap()
+            .with_video_codec("hevc");
+        let input = Path::new("/photos/trip/clip.mov");
+        let codec = template.codec_for_extension("MP4");
+        assert_eq!(codec, "hevc");
+        assert_eq!(
+            template.render(input, "MP4", &codec, Some(20.5)).unwrap(),
+            "trip-clip_hevc_crf20.5.MP4"
+        );
+        assert_eq!(
+            template.render(input, "JXL", "jxl", None).unwrap(),
+            "trip-clip_jxl_crf.JXL"
+        );
+    }
+
+    #[test]
+    fn test_output_template_rejects_unsafe_templates() {
+        for bad in [
+            "../{stem}.{ext}",
+            "out/{stem}.{ext}",
+            "{stem}.{format}",
+            "{stem.{ext}",
+            "{ext}",
+            "{stem}_hevc",
+        ] {
+            assert!(
+                OutputTemplate::parse(bad).is_err(),
+                "template should be rejected: {}",
+                bad
+            );
+        }
+        let template = OutputTemplate::parse("{stem}.{ext}").unwrap();
+        assert!(template
+            .render(Path::new("/a/b.png"), "JXL", "jxl", None)
+            .is_ok());
+    }
+
     #[test]
     fn test_conversion_result_success() {
         let input = Path::new("/test/input.png");
@@ -1591,4 +2192,47 @@ mod tests {
             crate::video_explorer::ExploreMode::PreciseQualityMatchWithCompression,
         );
     }
+
+    #[test]
+    fn test_validate_temp_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(validate_temp_dir(dir.path()).is_ok());
+        // Nothing left behind by the write probe.
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 0);
+
+        let file = dir.path().join("not_a_dir");
+        std::fs::write(&file, b"x").unwrap();
+        assert!(validate_temp_dir(&file).is_err());
+        assert!(validate_temp_dir(&dir.path().join("missing")).is_err());
+    }
+
+    #[test]
+    fn test_output_is_up_to_date() {
+        use std::time::{Duration, SystemTime};
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("a.png");
+        let output = dir.path().join("a.jxl");
+        std::fs::write(&source, b"src").unwrap();
+        std::fs::write(&output, b"out").unwrap();
+        let set_mtime = |p: &Path, t: SystemTime| {
+            std::fs::File::options()
+                .write(true)
+                .open(p)
+                .unwrap()
+                .set_modified(t)
+                .unwrap()
+        };
+        let base = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+
+        // Preserved timestamp, also when the output filesystem dropped the fraction.
+        set_mtime(&source, base + Duration::from_millis(900));
+        set_mtime(&output, base);
+        assert!(output_is_up_to_date(&source, &output));
+
+        // Source edited after it was converted.
+        set_mtime(&source, base + Duration::from_secs(3600));
+        assert!(!output_is_up_to_date(&source, &output));
+
+        assert!(output_is_up_to_date(&dir.path().join("gone.png"), &output));
+    }
 }
@@ -1,5 +1,8 @@
+use crate::quality_matcher::{EncoderType, MatchMode, QualityBias};
+use crate::video_detection::{DetectedCodec, VideoDetectionResult};
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum TargetVideoFormat {
@@ -7,6 +10,8 @@ pub enum TargetVideoFormat {
     Av1Mp4,
     HevcLosslessMkv,
     HevcMp4,
+    /// Source already uses the target codec: change the container to MP4 via stream copy.
+    Remux,
     Skip,
 }
 
@@ -14,7 +19,20 @@ impl TargetVideoFormat {
     pub fn extension(&self) -> &str {
         match self {
             TargetVideoFormat::Ffv1Mkv | TargetVideoFormat::HevcLosslessMkv => "MKV",
-            TargetVideoFormat::Av1Mp4 | TargetVideoFormat::HevcMp4 => "MP4",
+            TargetVideoFormat::Av1Mp4 | TargetVideoFormat::HevcMp4 | TargetVideoFormat::Remux => {
+                "MP4"
+            }
+            TargetVideoFormat::Skip => "",
+        }
+    }
+
+    /// Short codec name used by the `{codec}` output template placeholder.
+    pub fn codec_name(&self) -> &str {
+        match self {
+            TargetVideoFormat::Ffv1Mkv => "ffv1",
+            TargetVideoFormat::Av1Mp4 => "av1",
+            TargetVideoFormat::HevcLosslessMkv | TargetVideoFormat::HevcMp4 => "hevc",
+            TargetVideoFormat::Remux => "copy",
             TargetVideoFormat::Skip => "",
         }
     }
@@ -25,11 +43,69 @@ impl TargetVideoFormat {
             TargetVideoFormat::Av1Mp4 => "AV1 MP4 (High Quality)",
             TargetVideoFormat::HevcLosslessMkv => "HEVC Lossless MKV (Archival)",
             TargetVideoFormat::HevcMp4 => "HEVC MP4 (High Quality)",
+            TargetVideoFormat::Remux => "Remux to MP4 (Stream Copy)",
             TargetVideoFormat::Skip => "Skip",
         }
     }
 }
 
+/// `--container`: output container for lossy encodes and remuxes (lossless targets stay MKV).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VideoContainer {
+    Mp4,
+    Mkv,
+    Webm,
+}
+
+impl VideoContainer {
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "mp4" => Ok(VideoContainer::Mp4),
+            "mkv" | "matroska" => Ok(VideoContainer::Mkv),
+            "webm" => Ok(VideoContainer::Webm),
+            other => Err(format!(
+                "Invalid container '{}' (expected mkv, mp4 or webm)",
+                other
+            )),
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            VideoContainer::Mp4 => "MP4",
+            VideoContainer::Mkv => "MKV",
+            VideoContainer::Webm => "WEBM",
+        }
+    }
+
+    /// Container name understood by `audio_args_for_container` / `subtitle_args_for_container`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            VideoContainer::Mp4 => "mp4",
+            VideoContainer::Mkv => "mkv",
+            VideoContainer::Webm => "webm",
+        }
+    }
+
+    /// WebM only carries VP8/VP9/AV1 video, so HEVC targets cannot use it.
+    pub fn supports(&self, target: TargetVideoFormat) -> bool {
+        match self {
+            VideoContainer::Webm => !matches!(
+                target,
+                TargetVideoFormat::HevcMp4 | TargetVideoFormat::HevcLosslessMkv
+            ),
+            VideoContainer::Mp4 | VideoContainer::Mkv => true,
+        }
+    }
+}
+
+/// Encoder used for `apple_compat` when no hardware HEVC encoder is available.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AppleFallback {
+    /// H.264 MP4 via libx264 (universally playable, fast on CPU).
+    X264,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ConversionStrategy {
     pub target: TargetVideoFormat,
@@ -63,6 +139,79 @@ pub struct ConversionConfig {
     /// `video_compression_ratio < 1.01` as acceptable for require_compression / Apple fallback.
     /// Does not relax compress goal: compress still requires output < input.
     pub allow_size_tolerance: bool,
+    /// Downscale (lanczos, aspect preserved) when either side exceeds this many pixels.
+    /// Sources already within bounds are never scaled. SSIM is judged against the downscaled reference.
+    pub max_dimension: Option<u32>,
+    /// Re-time VFR sources to constant frame rate (`-vsync cfr`). Default keeps VFR timestamps.
+    pub force_cfr: bool,
+    /// Skip the up-front truncation/corruption probe (`probe_integrity`) for speed.
+    pub skip_integrity_check: bool,
+    /// Structured progress events for library embedders (see `progress_sink`). `None` = terminal output only.
+    pub progress_sink: Option<Arc<dyn crate::progress_sink::ProgressSink>>,
+    /// Apple-compat target when no hardware HEVC encoder is detected. `None` = always HEVC.
+    pub apple_fallback: Option<AppleFallback>,
+    /// CRF bias applied to the matched CRF (Conservative = lower CRF / higher quality).
+    pub quality_bias: QualityBias,
+    /// What the matched CRF optimizes for (quality, size or speed).
+    pub match_mode: MatchMode,
+    /// `--crf-bias`: constant added to the matched CRF after `quality_bias` (negative = higher
+    /// quality). The result is clamped to the encoder's CRF range.
+    pub crf_bias: f64,
+    /// `--start`: convert from this position (seconds). Frame-accurate.
+    pub start_time: Option<f64>,
+    /// `--end`: stop at this position (seconds, source timeline).
+    pub end_time: Option<f64>,
+    /// `--quality-report`: write measured SSIM/PSNR and the source codec as `user.mfb.*` xattrs.
+    pub quality_report: bool,
+    /// `--only-codecs`: convert only sources whose video codec is listed. Empty = all codecs.
+    pub only_codecs: Vec<DetectedCodec>,
+    /// `--no-audio`: write video-only output (`-an`). Subtitles and other streams keep their usual rules.
+    pub drop_audio: bool,
+    /// `--audio-codec`/`--audio-bitrate`: re-encode PCM/lossless or oversized audio in lossy
+    /// encodes instead of copying it. `None` = copy (default).
+    pub audio_transcode: Option<crate::media_passthrough::AudioTranscode>,
+    /// `--preset-speed`: x265/x264 preset name / SVT-AV1 preset for lossy encodes.
+    pub preset_speed: crate::video_explorer::EncoderPreset,
+    /// `--sample-ssim N`: gate on the mean SSIM of N evenly spaced frames (None = whole clip).
+    pub sample_ssim: Option<u32>,
+    /// `--ssim-sampling-strategy`: where the `--sample-ssim` frames come from.
+    pub ssim_sampling_strategy: crate::video_explorer::SsimSamplingStrategy,
+    /// `--container`: replaces MP4 for lossy and remux targets.
+    pub container: Option<VideoContainer>,
+    /// `--explore-mode`: run this search directly instead of the flag-combination mapping.
+    pub explore_mode: Option<crate::video_explorer::ExploreMode>,
+    /// `--auto-grayscale`: encode sources without chroma as gray (4:0:0) HEVC. Apple devices
+    /// cannot decode monochrome HEVC, so `apple_compat` keeps them 4:2:0.
+    pub auto_grayscale: bool,
+    /// Abort the conversion from another thread (see `cancellation`). `None` = not cancellable.
+    pub cancel_token: Option<crate::cancellation::CancellationToken>,
+    /// `--timeout`: kill the encode and fail the file once it has run this long. `None` = no limit.
+    pub per_file_timeout: Option<std::time::Duration>,
+    /// `--temp-dir`: scratch files go here instead of the system temp dir (process-wide, see `conversion::set_temp_dir`).
+    pub temp_dir: Option<PathBuf>,
+    /// `--film-grain N` (AV1 only): SVT-AV1 grain synthesis level 0–50 for lossy encodes
+    /// (libaom `-denoise-noise-level` under `--av1-encoder aom`).
+    pub film_grain: Option<u8>,
+    /// `--film-grain-auto` (AV1 only): estimate the level per file instead (overrides `film_grain`).
+    pub film_grain_auto: bool,
+    /// `--benchmark`: time each conversion and attach its throughput to `ConversionOutput::benchmark`.
+    pub benchmark: bool,
+    /// `--denoise`: pre-filter the source before encoding. SSIM is judged against the denoised
+    /// reference, so `match_quality` matches the cleaned-up picture, not the noisy original.
+    pub denoise: Option<crate::video::DenoiseLevel>,
+    /// `--content-type`: bound the CRF search by this content type instead of the detected one.
+    pub content_type: Option<crate::quality_matcher::ContentType>,
+    /// `--max-output-size`: absolute byte cap on the explored output, applied after the quality search.
+    pub max_output_size: Option<u64>,
+    /// `--pix-fmt`: pixel format of every lossy encode instead of the one derived from the source.
+    pub pix_fmt: Option<crate::video_explorer::PixelFormat>,
+    /// `--strip-metadata` / `--strip-timestamps` for this conversion's encodes and metadata step.
+    pub strip: crate::metadata::StripPolicy,
+    /// `--on-collision`: what happens when this conversion's output path already exists.
+    pub on_collision: crate::conversion::CollisionPolicy,
+    /// `--deterministic`: byte-identical output for this conversion. CPU encoders only, and
+    /// no CRF hints carried over from earlier files.
+    pub deterministic: bool,
 }
 
 impl Default for ConversionConfig {
@@ -84,13 +233,224 @@ impl Default for ConversionConfig {
             ultimate_mode: false,
             child_threads: 0,
             allow_size_tolerance: true,
+            max_dimension: None,
+            force_cfr: false,
+            skip_integrity_check: false,
+            progress_sink: None,
+            apple_fallback: None,
+            quality_bias: QualityBias::Balanced,
+            match_mode: MatchMode::Quality,
+            crf_bias: 0.0,
+            start_time: None,
+            end_time: None,
+            quality_report: false,
+            only_codecs: Vec::new(),
+            drop_audio: false,
+            audio_transcode: None,
+            preset_speed: crate::video_explorer::EncoderPreset::Medium,
+            sample_ssim: None,
+            ssim_sampling_strategy: crate::video_explorer::SsimSamplingStrategy::Uniform,
+            container: None,
+            explore_mode: None,
+            auto_grayscale: false,
+            cancel_token: None,
+            per_file_timeout: None,
+            temp_dir: None,
+            film_grain: None,
+            film_grain_auto: false,
+            benchmark: false,
+            denoise: None,
+            content_type: None,
+            max_output_size: None,
+            pix_fmt: None,
+            strip: crate::metadata::StripPolicy::default(),
+            on_collision: crate::conversion::CollisionPolicy::default(),
+            deterministic: false,
         }
     }
 }
 
 impl ConversionConfig {
+    /// A trimmed output never replaces the whole source, so trimming disables deletion.
     pub fn should_delete_original(&self) -> bool {
-        self.delete_original || self.in_place
+        (self.delete_original || self.in_place) && !self.is_trimmed()
+    }
+
+    /// True when `--start` / `--end` select a segment rather than the whole source.
+    pub fn is_trimmed(&self) -> bool {
+        self.start_time.is_some_and(|s| s > 0.0) || self.end_time.is_some()
+    }
+
+    /// False when `--only-codecs` is set and `codec` is not in the list.
+    pub fn accepts_codec(&self, codec: &DetectedCodec) -> bool {
+        self.only_codecs.is_empty() || self.only_codecs.contains(codec)
+    }
+
+    /// `--container` for `target`. Lossless targets always stay MKV, so only lossy encodes
+    /// and remuxes take the override.
+    pub fn container_for(&self, target: TargetVideoFormat) -> Option<VideoContainer> {
+        self.container.filter(|_| {
+            matches!(
+                target,
+                TargetVideoFormat::HevcMp4 | TargetVideoFormat::Av1Mp4 | TargetVideoFormat::Remux
+            )
+        })
+    }
+
+    /// With `--container`, a remux into the container `input` already uses would change
+    /// nothing, so the source is skipped instead.
+    pub fn skip_redundant_remux(
+        &self,
+        strategy: ConversionStrategy,
+        input: &Path,
+    ) -> ConversionStrategy {
+        let Some(container) = self.container_for(strategy.target) else {
+            return strategy;
+        };
+        let same_container = input
+            .extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|e| e.eq_ignore_ascii_case(container.extension()));
+        if strategy.target != TargetVideoFormat::Remux || !same_container {
+            return strategy;
+        }
+        ConversionStrategy {
+            target: TargetVideoFormat::Skip,
+            reason: format!(
+                "Source already uses the target codec in {} (--container)",
+                container.extension()
+            ),
+            preserve_audio: false,
+            ..strategy
+        }
+    }
+
+    /// With `drop_audio`, treat the source as silent so no encode or remux path maps audio.
+    pub fn apply_audio_policy(&self, detection: &mut VideoDetectionResult) {
+        if self.drop_audio {
+            detection.has_audio = false;
+            detection.audio_codec = None;
+        }
+    }
+
+    /// The options the explore path reads for this conversion.
+    pub fn encode_settings(&self) -> crate::video_explorer::EncodeSettings {
+        crate::video_explorer::EncodeSettings {
+            drop_audio: self.drop_audio,
+            preset: self.preset_speed,
+            max_output_size: self.max_output_size,
+            sample_ssim_frames: self.sample_ssim,
+            ssim_sampling_strategy: self.ssim_sampling_strategy,
+            pix_fmt: self.pix_fmt,
+            strip: self.strip,
+            deterministic: self.deterministic,
+        }
+    }
+}
+
+/// Parse `--only-codecs` names (ffprobe codec names or common aliases, e.g. `h264`, `hevc`,
+/// `mpeg2video`) into the codecs `detect_video` reports.
+pub fn parse_codec_list(names: &[String]) -> Vec<DetectedCodec> {
+    let mut codecs = Vec::new();
+    for name in names {
+        let name = name.trim().to_ascii_lowercase();
+        if name.is_empty() {
+            continue;
+        }
+        let codec = DetectedCodec::from_ffprobe(&name);
+        if !codecs.contains(&codec) {
+            codecs.push(codec);
+        }
+    }
+    codecs
+}
+
+/// Typical FFV1 / x265-lossless size relative to raw decoded YUV frames.
+const FFV1_RAW_RATIO: f64 = 0.5;
+const HEVC_LOSSLESS_RAW_RATIO: f64 = 0.45;
+
+/// Estimated output file size in bytes for converting `detection` to `target` at `crf`.
+///
+/// - `Skip` / `Remux`: the input size (stream copy).
+/// - Lossless targets: raw YUV 4:2:0 frame bytes scaled by a typical lossless ratio.
+/// - Lossy targets: `estimate_encoded_video_bytes` from the source video stream, with
+///   `matched_crf` as the quality-equivalent CRF (falls back to `crf` when unknown),
+///   plus the source audio bytes.
+///
+/// Meant for planning (`--dry-run`); expect ±25% on typical footage.
+pub fn estimate_output_bytes(
+    detection: &VideoDetectionResult,
+    target: TargetVideoFormat,
+    crf: f64,
+    matched_crf: Option<f64>,
+) -> u64 {
+    let total_bytes = (detection.bitrate as f64 * detection.duration_secs / 8.0) as u64;
+    let video_bytes = detection
+        .video_bitrate
+        .map(|vbr| (vbr as f64 * detection.duration_secs / 8.0) as u64)
+        .unwrap_or(total_bytes)
+        .min(detection.file_size);
+    let audio_bytes = if detection.has_audio {
+        total_bytes.saturating_sub(video_bytes)
+    } else {
+        0
+    };
+
+    let encoder = match target {
+        TargetVideoFormat::Skip | TargetVideoFormat::Remux => return detection.file_size,
+        TargetVideoFormat::Ffv1Mkv | TargetVideoFormat::HevcLosslessMkv => {
+            let frames = if detection.frame_count > 0 {
+                detection.frame_count as f64
+            } else {
+                detection.fps * detection.duration_secs
+            };
+            let bytes_per_pixel = if detection.bit_depth > 8 { 3.0 } else { 1.5 };
+            let raw = detection.width as f64 * detection.height as f64 * bytes_per_pixel * frames;
+            let ratio = if target == TargetVideoFormat::Ffv1Mkv {
+                FFV1_RAW_RATIO
+            } else {
+                HEVC_LOSSLESS_RAW_RATIO
+            };
+            return (raw * ratio) as u64 + audio_bytes;
+        }
+        TargetVideoFormat::Av1Mp4 => EncoderType::Av1,
+        TargetVideoFormat::HevcMp4 => EncoderType::Hevc,
+    };
+
+    crate::quality_matcher::estimate_encoded_video_bytes(
+        video_bytes,
+        detection.codec.as_str(),
+        encoder,
+        matched_crf.unwrap_or(crf),
+        crf,
+    ) + audio_bytes
+}
+
+/// Named bundles of quality settings (`--preset`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QualityPreset {
+    /// SSIM floor 0.99, conservative CRF bias, ultimate search.
+    Archive,
+    /// The defaults: SSIM floor 0.95, CRF matched to the source.
+    Balanced,
+    /// SSIM floor 0.90, aggressive CRF bias, size-oriented matching.
+    Small,
+}
+
+/// Expand `preset` onto `base`. Only the quality fields (`min_ssim`, `quality_bias`,
+/// `match_mode`, `ultimate_mode`) change; callers re-apply explicit flags afterwards.
+pub fn preset_to_config(preset: QualityPreset, base: ConversionConfig) -> ConversionConfig {
+    let (min_ssim, quality_bias, match_mode, ultimate_mode) = match preset {
+        QualityPreset::Archive => (0.99, QualityBias::Conservative, MatchMode::Quality, true),
+        QualityPreset::Balanced => (0.95, QualityBias::Balanced, MatchMode::Quality, false),
+        QualityPreset::Small => (0.90, QualityBias::Aggressive, MatchMode::Size, false),
+    };
+    ConversionConfig {
+        min_ssim,
+        quality_bias,
+        match_mode,
+        ultimate_mode,
+        ..base
     }
 }
 
@@ -106,6 +466,207 @@ pub struct ConversionOutput {
     pub message: String,
     pub final_crf: f32,
     pub exploration_attempts: u8,
+    /// Set when `ConversionConfig::benchmark` is on and the file was converted.
+    #[serde(default)]
+    pub benchmark: Option<EncodeBenchmark>,
+    /// Why the file was skipped; `None` for conversions and failures.
+    #[serde(default)]
+    pub skip_reason: Option<SkipReason>,
+    /// SSIM of the final output when the CRF search measured one.
+    #[serde(default)]
+    pub ssim: Option<f64>,
+}
+
+/// Why a file was left unconverted, for the per-reason breakdown in the summary report.
+/// The free-text `message` still carries the details (codec name, sizes, ...).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SkipReason {
+    /// Modern lossy source (WebP/AVIF/HEIC lossy, HEVC/AV1/VP9 video): re-encoding only loses quality.
+    ModernLossy,
+    /// Source is already in the best target format (JXL, GIF kept as GIF, same codec and container).
+    AlreadyOptimal,
+    /// Animation too short or a single frame.
+    ShortAnimation,
+    /// The output would be larger than (or as large as) the source.
+    OutputLarger,
+    /// The output missed the quality target.
+    QualityFailed,
+    /// Truncated or unreadable source.
+    Corrupt,
+    /// Already plays natively on Apple devices (`--apple-compat`).
+    AppleNative,
+    /// `.mfb_overrides`, `--only-codecs` and other explicit user filters.
+    UserOverride,
+    /// Live Photo pair kept together (`--live-photos skip`).
+    LivePhoto,
+    /// `--metadata-only-formats`: copied through without re-encoding.
+    MetadataOnly,
+    /// Output already exists or the input was processed in an earlier run.
+    AlreadyProcessed,
+    /// Below the size threshold worth converting.
+    TooSmall,
+    /// A required tool is missing or an intermediate step failed.
+    ToolFailed,
+    /// `--dedup`: same content as a file already converted in this run.
+    DuplicateContent,
+    Other,
+}
+
+impl SkipReason {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::ModernLossy => "modern lossy source",
+            Self::AlreadyOptimal => "already optimal",
+            Self::ShortAnimation => "short animation",
+            Self::OutputLarger => "output not smaller",
+            Self::QualityFailed => "quality target missed",
+            Self::Corrupt => "corrupt source",
+            Self::AppleNative => "already Apple compatible",
+            Self::UserOverride => "user override",
+            Self::LivePhoto => "Live Photo pair",
+            Self::MetadataOnly => "metadata-only format",
+            Self::AlreadyProcessed => "already processed",
+            Self::TooSmall => "below size threshold",
+            Self::ToolFailed => "tool missing or failed",
+            Self::DuplicateContent => "duplicate content",
+            Self::Other => "other",
+        }
+    }
+
+    /// Map the string codes carried by `ConversionResult::skip_reason` (e.g. `"exists"`,
+    /// `"size_increase"`). Unknown codes fall back to `Other`.
+    pub fn from_code(code: &str) -> Self {
+        match code {
+            "duplicate" | "exists" => Self::AlreadyProcessed,
+            "size_increase"
+            | "size_increase_beyond_tolerance"
+            | "size_unchanged"
+            | "apng_larger" => Self::OutputLarger,
+            "quality_failed" | "ssim_failed" => Self::QualityFailed,
+            "static_animated" => Self::ShortAnimation,
+            "already_gif" | "already_webp" | "gif_meme" => Self::AlreadyOptimal,
+            "small_file" => Self::TooSmall,
+            "duplicate_content" => Self::DuplicateContent,
+            "unsupported_source" => Self::Corrupt,
+            code if code.ends_with("_failed")
+                || code.ends_with("_not_found")
+                || code.ends_with("_invalid_output") =>
+            {
+                Self::ToolFailed
+            }
+            _ => Self::Other,
+        }
+    }
+}
+
+impl std::fmt::Display for SkipReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// `--benchmark`: wall time and throughput of one conversion, search encodes included, so
+/// GPU and CPU runs of the same codec can be compared. Totals use the same type.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EncodeBenchmark {
+    /// Codec and device, e.g. `hevc (GPU)`; the run summary groups by it.
+    pub encoder: String,
+    pub wall_secs: f64,
+    pub frames: u64,
+    pub input_bytes: u64,
+    pub output_bytes: u64,
+}
+
+impl EncodeBenchmark {
+    /// Benchmark for a finished conversion; `None` for skipped or failed outputs. Frames are
+    /// counted on the output, so trimmed or re-timed encodes report what was actually encoded.
+    pub fn measure(
+        output: &ConversionOutput,
+        elapsed: std::time::Duration,
+        use_gpu: bool,
+    ) -> Option<Self> {
+        if !output.success || output.output_path.is_empty() || output.output_size == 0 {
+            return None;
+        }
+        let frames = crate::ffprobe::probe_video(Path::new(&output.output_path))
+            .map(|probe| {
+                if probe.frame_count > 0 {
+                    probe.frame_count
+                } else {
+                    (probe.duration * probe.frame_rate).round().max(0.0) as u64
+                }
+            })
+            .unwrap_or(0);
+        let target = output.strategy.target;
+        let encoder = if target == TargetVideoFormat::Remux {
+            target.codec_name().to_string()
+        } else {
+            format!(
+                "{} ({})",
+                target.codec_name(),
+                if use_gpu { "GPU" } else { "CPU" }
+            )
+        };
+        Some(Self {
+            encoder,
+            wall_secs: elapsed.as_secs_f64(),
+            frames,
+            input_bytes: output.input_size,
+            output_bytes: output.output_size,
+        })
+    }
+
+    pub fn fps(&self) -> f64 {
+        if self.wall_secs > 0.0 {
+            self.frames as f64 / self.wall_secs
+        } else {
+            0.0
+        }
+    }
+
+    /// Source megabytes (MiB) consumed per second.
+    pub fn input_mb_per_sec(&self) -> f64 {
+        if self.wall_secs > 0.0 {
+            self.input_bytes as f64 / (1024.0 * 1024.0) / self.wall_secs
+        } else {
+            0.0
+        }
+    }
+
+    /// Output megabytes (MiB) written per second.
+    pub fn output_mb_per_sec(&self) -> f64 {
+        if self.wall_secs > 0.0 {
+            self.output_bytes as f64 / (1024.0 * 1024.0) / self.wall_secs
+        } else {
+            0.0
+        }
+    }
+
+    /// Add another file's figures to this total.
+    pub fn accumulate(&mut self, other: &EncodeBenchmark) {
+        self.wall_secs += other.wall_secs;
+        self.frames += other.frames;
+        self.input_bytes += other.input_bytes;
+        self.output_bytes += other.output_bytes;
+    }
+}
+
+impl std::fmt::Display for EncodeBenchmark {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}: {} frames in {:.1}s → {:.1} fps, {:.2} MB/s in, {:.2} MB/s out ({} → {})",
+            self.encoder,
+            self.frames,
+            self.wall_secs,
+            self.fps(),
+            self.input_mb_per_sec(),
+            self.output_mb_per_sec(),
+            crate::format_bytes(self.input_bytes),
+            crate::format_bytes(self.output_bytes)
+        )
+    }
 }
 
 impl crate::cli_runner::CliProcessingResult for ConversionOutput {
@@ -148,4 +709,217 @@ impl crate::cli_runner::CliProcessingResult for ConversionOutput {
     fn message(&self) -> &str {
         &self.message
     }
+    fn benchmark(&self) -> Option<&EncodeBenchmark> {
+        self.benchmark.as_ref()
+    }
+    fn skip_kind(&self) -> SkipReason {
+        self.skip_reason.unwrap_or(SkipReason::Other)
+    }
+    fn ssim(&self) -> Option<f64> {
+        self.ssim
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_skip_reason_from_code() {
+        assert_eq!(
+            SkipReason::from_code("exists"),
+            SkipReason::AlreadyProcessed
+        );
+        assert_eq!(
+            SkipReason::from_code("size_increase_beyond_tolerance"),
+            SkipReason::OutputLarger
+        );
+        assert_eq!(
+            SkipReason::from_code("djxl_not_found"),
+            SkipReason::ToolFailed
+        );
+        assert_eq!(
+            SkipReason::from_code("hevc_encode_failed"),
+            SkipReason::ToolFailed
+        );
+        assert_eq!(
+            SkipReason::from_code("quality_failed"),
+            SkipReason::QualityFailed
+        );
+        assert_eq!(SkipReason::from_code("something_new"), SkipReason::Other);
+        assert_eq!(
+            serde_json::to_string(&SkipReason::ModernLossy).unwrap(),
+            "\"modern_lossy\""
+        );
+    }
+
+    #[test]
+    fn test_preset_to_config_sets_quality_fields_only() {
+        let base = ConversionConfig {
+            force: true,
+            max_dimension: Some(1920),
+            ..Default::default()
+        };
+
+        let archive = preset_to_config(QualityPreset::Archive, base.clone());
+        assert_eq!(archive.min_ssim, 0.99);
+        assert_eq!(archive.quality_bias, QualityBias::Conservative);
+        assert!(archive.ultimate_mode);
+        assert!(archive.force);
+        assert_eq!(archive.max_dimension, Some(1920));
+
+        let small = preset_to_config(QualityPreset::Small, base.clone());
+        assert!(small.min_ssim < archive.min_ssim);
+        assert_eq!(small.quality_bias, QualityBias::Aggressive);
+        assert_eq!(small.match_mode, MatchMode::Size);
+        assert!(!small.ultimate_mode);
+    }
+
+    #[test]
+    fn test_balanced_preset_matches_defaults() {
+        let defaults = ConversionConfig::default();
+        let balanced = preset_to_config(QualityPreset::Balanced, defaults.clone());
+        assert_eq!(balanced.min_ssim, defaults.min_ssim);
+        assert_eq!(balanced.quality_bias, defaults.quality_bias);
+        assert_eq!(balanced.match_mode, defaults.match_mode);
+        assert_eq!(balanced.ultimate_mode, defaults.ultimate_mode);
+    }
+
+    #[test]
+    fn test_trimmed_conversion_never_deletes_original() {
+        let config = ConversionConfig {
+            delete_original: true,
+            ..Default::default()
+        };
+        assert!(!config.is_trimmed());
+        assert!(config.should_delete_original());
+
+        let trimmed = ConversionConfig {
+            start_time: Some(90.0),
+            ..config
+        };
+        assert!(trimmed.is_trimmed());
+        assert!(!trimmed.should_delete_original());
+    }
+
+    #[test]
+    fn test_only_codecs_filter() {
+        let config = ConversionConfig::default();
+        assert!(config.accepts_codec(&DetectedCodec::VP9));
+
+        let config = ConversionConfig {
+            only_codecs: parse_codec_list(&[
+                "H264".to_string(),
+                " mpeg2video".to_string(),
+                "avc".to_string(),
+            ]),
+            ..Default::default()
+        };
+        assert_eq!(config.only_codecs.len(), 2);
+        assert!(config.accepts_codec(&DetectedCodec::H264));
+        assert!(config.accepts_codec(&DetectedCodec::from_ffprobe("mpeg2video")));
+        assert!(!config.accepts_codec(&DetectedCodec::H265));
+    }
+
+    #[test]
+    fn test_container_override() {
+        assert_eq!(VideoContainer::parse(" MKV"), Ok(VideoContainer::Mkv));
+        assert_eq!(VideoContainer::parse("webm"), Ok(VideoContainer::Webm));
+        assert!(VideoContainer::parse("avi").is_err());
+
+        assert!(!VideoContainer::Webm.supports(TargetVideoFormat::HevcMp4));
+        assert!(VideoContainer::Webm.supports(TargetVideoFormat::Av1Mp4));
+        assert!(VideoContainer::Mkv.supports(TargetVideoFormat::HevcMp4));
+
+        let config = ConversionConfig {
+            container: Some(VideoContainer::Mkv),
+            ..Default::default()
+        };
+        assert_eq!(
+            config.container_for(TargetVideoFormat::HevcMp4),
+            Some(VideoContainer::Mkv)
+        );
+        assert_eq!(
+            config.container_for(TargetVideoFormat::Remux),
+            Some(VideoContainer::Mkv)
+        );
+        assert_eq!(
+            config.container_for(TargetVideoFormat::HevcLosslessMkv),
+            None
+        );
+        assert_eq!(
+            ConversionConfig::default().container_for(TargetVideoFormat::HevcMp4),
+            None
+        );
+
+        let remux = ConversionStrategy {
+            target: TargetVideoFormat::Remux,
+            reason: String::new(),
+            command: String::new(),
+            preserve_audio: true,
+            crf: 0.0,
+            lossless: false,
+        };
+        let skipped = config.skip_redundant_remux(remux.clone(), Path::new("/in/clip.mkv"));
+        assert_eq!(skipped.target, TargetVideoFormat::Skip);
+        let kept = config.skip_redundant_remux(remux, Path::new("/in/clip.avi"));
+        assert_eq!(kept.target, TargetVideoFormat::Remux);
+    }
+
+    #[test]
+    fn test_drop_audio_policy() {
+        let source = VideoDetectionResult {
+            has_audio: true,
+            audio_codec: Some("aac".to_string()),
+            ..Default::default()
+        };
+
+        let mut kept = source.clone();
+        ConversionConfig::default().apply_audio_policy(&mut kept);
+        assert!(kept.has_audio);
+
+        let mut dropped = source;
+        let config = ConversionConfig {
+            drop_audio: true,
+            ..Default::default()
+        };
+        config.apply_audio_policy(&mut dropped);
+        assert!(!dropped.has_audio);
+        assert_eq!(dropped.audio_codec, None);
+    }
+
+    #[test]
+    fn test_estimate_output_bytes() {
+        let detection = VideoDetectionResult {
+            codec: DetectedCodec::H264,
+            width: 1920,
+            height: 1080,
+            frame_count: 300,
+            fps: 30.0,
+            duration_secs: 10.0,
+            bit_depth: 8,
+            bitrate: 8_128_000,
+            video_bitrate: Some(8_000_000),
+            has_audio: true,
+            file_size: 10_200_000,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            estimate_output_bytes(&detection, TargetVideoFormat::Remux, 20.0, None),
+            10_200_000
+        );
+        // 10 MB H.264 video at the matched CRF → 6.5 MB HEVC, plus 160 KB audio.
+        assert_eq!(
+            estimate_output_bytes(&detection, TargetVideoFormat::HevcMp4, 22.0, Some(22.0)),
+            6_660_000
+        );
+        let smaller =
+            estimate_output_bytes(&detection, TargetVideoFormat::HevcMp4, 27.0, Some(22.0));
+        assert_eq!(smaller, 3_410_000);
+        assert!(
+            estimate_output_bytes(&detection, TargetVideoFormat::Ffv1Mkv, 0.0, None)
+                > detection.file_size
+        );
+    }
 }
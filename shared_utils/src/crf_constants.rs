@@ -35,6 +35,9 @@ pub const X264_CRF_MAX: f32 = 51.0;
 
 pub const X264_CRF_DEFAULT: f32 = 23.0;
 
+/// x264 needs a lower CRF than x265 for the same perceived quality (x265 28 ≈ x264 23).
+pub const X264_CRF_OFFSET_FROM_HEVC: f32 = 5.0;
+
 pub const CRF_CACHE_KEY_MULTIPLIER: f32 = 100.0;
 
 pub const CRF_CACHE_MAX_VALID: f32 = 63.99;
@@ -0,0 +1,401 @@
+//! `--dedup`: skip inputs whose content was already converted in this run. Each file gets a
+//! perceptual hash (pHash of a 32×32 grayscale thumbnail; five frames sampled across a video)
+//! plus its dimensions and duration. A file whose hash is within a few bits, luma levels and
+//! frames of an earlier one is copied through instead of converted, so the same clip under
+//! several names, containers or encode qualities is encoded once.
+
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+/// Side of the grayscale thumbnail a frame is reduced to before hashing.
+const THUMB: usize = 32;
+
+/// Low-frequency DCT block kept for the hash.
+const HASH_BLOCK: usize = 8;
+
+/// Where frames are sampled, as fractions of the video's duration.
+const VIDEO_SAMPLE_POINTS: [f64; 5] = [0.1, 0.3, 0.5, 0.7, 0.9];
+
+/// Hashes kept per run; far above any real batch, it only bounds memory on endless runs.
+const SEEN_CAPACITY: usize = 100_000;
+
+/// pHash bits (of 63) two frames may differ in and still be the same picture. Re-encodes of
+/// one source at different qualities stay well below it; unrelated frames differ in about half.
+const MAX_HAMMING_DISTANCE: u32 = 10;
+
+/// Mean-luma levels two matching frames may differ by (encoders shift flat areas slightly).
+const MEAN_LUMA_TOLERANCE: u8 = 4;
+
+/// Tenths of a second two matching durations may differ by: a re-mux or re-encode can gain or
+/// lose a frame or two at either end.
+const DURATION_TOLERANCE_DS: u64 = 2;
+
+/// Perceptual fingerprint of one input. [`ContentHash::matches`] tells whether two inputs are
+/// the same picture at the same size and length, even when container, name or encoding differ.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContentHash {
+    pub width: u32,
+    pub height: u32,
+    /// Tenths of a second; 0 for stills.
+    pub duration_ds: u64,
+    /// pHash and mean luma of each sampled frame. The mean tells apart flat frames, whose
+    /// pHash carries no information.
+    pub frames: Vec<(u64, u8)>,
+}
+
+impl ContentHash {
+    /// Same dimensions, durations within `DURATION_TOLERANCE_DS`, and every sampled frame within
+    /// `MAX_HAMMING_DISTANCE` bits of pHash and `MEAN_LUMA_TOLERANCE` of mean luma.
+    pub fn matches(&self, other: &ContentHash) -> bool {
+        self.width == other.width
+            && self.height == other.height
+            && self.duration_ds.abs_diff(other.duration_ds) <= DURATION_TOLERANCE_DS
+            && self.frames.len() == other.frames.len()
+            && self.frames.iter().zip(&other.frames).all(|(a, b)| {
+                (a.0 ^ b.0).count_ones() <= MAX_HAMMING_DISTANCE
+                    && a.1.abs_diff(b.1) <= MEAN_LUMA_TOLERANCE
+            })
+    }
+}
+
+/// pHash of a `THUMB`×`THUMB` grayscale frame: one bit per low-frequency DCT coefficient
+/// (DC excluded), set when the coefficient is clearly above their median.
+pub fn phash(luma: &[u8]) -> u64 {
+    debug_assert_eq!(luma.len(), THUMB * THUMB);
+    let n = THUMB as f64;
+    // Centered first, so the AC sums do not carry the rounding error of a large DC term.
+    let mean = luma.iter().map(|&p| p as f64).sum::<f64>() / luma.len().max(1) as f64;
+    let mut coefficients = Vec::with_capacity(HASH_BLOCK * HASH_BLOCK);
+    for u in 0..HASH_BLOCK {
+        for v in 0..HASH_BLOCK {
+            let mut sum = 0.0;
+            for (y, row) in luma.chunks_exact(THUMB).enumerate() {
+                let cy = ((2 * y + 1) as f64 * v as f64 * std::f64::consts::PI / (2.0 * n)).cos();
+                for (x, &p) in row.iter().enumerate() {
+                    let cx =
+                        ((2 * x + 1) as f64 * u as f64 * std::f64::consts::PI / (2.0 * n)).cos();
+                    sum += (p as f64 - mean) * cx * cy;
+                }
+            }
+            coefficients.push(sum);
+        }
+    }
+    let mut ac = coefficients[1..].to_vec();
+    ac.sort_by(|a, b| a.total_cmp(b));
+    let median = ac[ac.len() / 2];
+    // Coefficients that are zero in exact arithmetic (common on smooth or flat frames) must not
+    // flip bits on rounding noise.
+    let epsilon = ac.iter().fold(1.0f64, |m, c| m.max(c.abs())) * 1e-9;
+    coefficients[1..]
+        .iter()
+        .enumerate()
+        .filter(|(_, &c)| c > median + epsilon)
+        .fold(0u64, |hash, (i, _)| hash | 1 << i)
+}
+
+fn frame_hash(luma: &[u8]) -> (u64, u8) {
+    let mean = luma.iter().map(|&p| p as u64).sum::<u64>() / luma.len().max(1) as u64;
+    (phash(luma), mean as u8)
+}
+
+/// Hash `path`; `None` when it cannot be decoded, in which case it is converted as usual.
+pub fn content_hash(path: &Path) -> Option<ContentHash> {
+    let is_video = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| crate::SUPPORTED_VIDEO_EXTENSIONS.contains(&e.to_lowercase().as_str()));
+    if is_video {
+        video_content_hash(path)
+    } else {
+        image_content_hash(path)
+    }
+}
+
+fn video_content_hash(path: &Path) -> Option<ContentHash> {
+    let probe = crate::probe_video(path).ok()?;
+    let frames = VIDEO_SAMPLE_POINTS
+        .iter()
+        .map(|at| thumbnail_with_ffmpeg(path, probe.duration * at).map(|t| frame_hash(&t)))
+        .collect::<Option<Vec<_>>>()?;
+    Some(ContentHash {
+        width: probe.width,
+        height: probe.height,
+        duration_ds: (probe.duration * 10.0).round() as u64,
+        frames,
+    })
+}
+
+fn image_content_hash(path: &Path) -> Option<ContentHash> {
+    // HEIC, JXL and other formats the image crate cannot open go through ffmpeg.
+    match crate::image_detection::open_image_with_limits(path) {
+        Ok(img) => {
+            let thumb = img
+                .resize_exact(
+                    THUMB as u32,
+                    THUMB as u32,
+                    image::imageops::FilterType::Triangle,
+                )
+                .to_luma8();
+            Some(ContentHash {
+                width: img.width(),
+                height: img.height(),
+                duration_ds: 0,
+                frames: vec![frame_hash(thumb.as_raw())],
+            })
+        }
+        Err(_) => {
+            let probe = crate::probe_video(path).ok()?;
+            let thumb = thumbnail_with_ffmpeg(path, 0.0)?;
+            Some(ContentHash {
+                width: probe.width,
+                height: probe.height,
+                duration_ds: 0,
+                frames: vec![frame_hash(&thumb)],
+            })
+        }
+    }
+}
+
+/// One frame at `at_secs`, scaled to a `THUMB`×`THUMB` 8-bit grayscale buffer.
+fn thumbnail_with_ffmpeg(path: &Path, at_secs: f64) -> Option<Vec<u8>> {
+    let output = Command::new("ffmpeg")
+        .args([
+            "-v",
+            "error",
+            "-ss",
+            &format!("{:.3}", at_secs.max(0.0)),
+            "-i",
+        ])
+        .arg(crate::safe_path_arg(path).as_ref())
+        .args([
+            "-frames:v",
+            "1",
+            "-vf",
+            &format!("scale={THUMB}:{THUMB}:flags=area,format=gray"),
+            "-f",
+            "rawvideo",
+            "-",
+        ])
+        .output()
+        .ok()?;
+    (output.status.success() && output.stdout.len() == THUMB * THUMB).then_some(output.stdout)
+}
+
+/// Record `hash` for `path`, or return the earlier file whose content it matches. A file seen
+/// again (a `--max-retries` attempt) is not its own duplicate.
+fn claim(
+    seen: &mut VecDeque<(ContentHash, PathBuf)>,
+    hash: ContentHash,
+    path: &Path,
+) -> Option<PathBuf> {
+    if let Some((_, first)) = seen.iter().find(|(earlier, _)| earlier.matches(&hash)) {
+        return (first != path).then(|| first.clone());
+    }
+    if seen.len() >= SEEN_CAPACITY {
+        seen.pop_front();
+    }
+    seen.push_back((hash, path.to_path_buf()));
+    None
+}
+
+/// The contents claimed during one `--dedup` run, shared by its workers. Near-duplicates have
+/// no common key, so lookups scan the claimed hashes with [`ContentHash::matches`].
+#[derive(Debug, Default)]
+pub struct Deduper {
+    seen: Mutex<VecDeque<(ContentHash, PathBuf)>>,
+    duplicates: AtomicUsize,
+    duplicate_bytes: AtomicU64,
+}
+
+impl Deduper {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Before converting `path`: the earlier input of this run with the same content, if any.
+    /// The first file of a content claims it before its conversion starts, so parallel workers
+    /// never convert both copies; a failed conversion gives the claim back through
+    /// [`Deduper::release_claim`].
+    pub fn check_duplicate(&self, path: &Path) -> Option<PathBuf> {
+        let hash = content_hash(path)?;
+        let first = claim(
+            &mut self.seen.lock().unwrap_or_else(|e| e.into_inner()),
+            hash,
+            path,
+        )?;
+        self.duplicates.fetch_add(1, Ordering::Relaxed);
+        self.duplicate_bytes.fetch_add(
+            std::fs::metadata(path).map(|m| m.len()).unwrap_or(0),
+            Ordering::Relaxed,
+        );
+        Some(first)
+    }
+
+    /// After `path` failed to convert: forget the content it claimed, so a later copy of it is
+    /// converted instead of skipped as a duplicate of a file that has no output.
+    pub fn release_claim(&self, path: &Path) {
+        self.seen
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .retain(|(_, claimed_by)| claimed_by != path);
+    }
+
+    /// Totals of the run so far.
+    pub fn report(&self) -> DedupReport {
+        DedupReport {
+            duplicates: self.duplicates.load(Ordering::Relaxed),
+            bytes_saved: self.duplicate_bytes.load(Ordering::Relaxed),
+        }
+    }
+
+    /// [`Deduper::report`] with a one-line result after the run summary.
+    pub fn report_with_log(&self) -> DedupReport {
+        let report = self.report();
+        crate::log_eprintln!(
+            "\n🔁 Dedup: {} duplicate(s) skipped, {} not converted again",
+            report.duplicates,
+            crate::format_bytes(report.bytes_saved)
+        );
+        report
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DedupReport {
+    pub duplicates: usize,
+    /// Input bytes of the skipped duplicates, i.e. what was not converted a second time.
+    pub bytes_saved: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gradient(flip: bool) -> Vec<u8> {
+        (0..THUMB * THUMB)
+            .map(|i| {
+                let (x, y) = (i % THUMB, i / THUMB);
+                let v = (x * 4 + y * 2) as u8;
+                if flip {
+                    255 - v
+                } else {
+                    v
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_phash_is_stable_under_small_changes() {
+        let base = gradient(false);
+        let brighter: Vec<u8> = base.iter().map(|&p| p.saturating_add(3)).collect();
+        assert_eq!(phash(&base), phash(&brighter));
+        assert_ne!(phash(&base), phash(&gradient(true)));
+    }
+
+    #[test]
+    fn test_flat_frames_differ_by_mean() {
+        let dark = frame_hash(&[10; THUMB * THUMB]);
+        let light = frame_hash(&[200; THUMB * THUMB]);
+        assert_eq!(dark.0, light.0);
+        assert_ne!(dark, light);
+    }
+
+    #[test]
+    fn test_claim_returns_first_path() {
+        let mut seen = VecDeque::new();
+        let hash = ContentHash {
+            width: 1920,
+            height: 1080,
+            duration_ds: 125,
+            frames: vec![frame_hash(&gradient(false))],
+        };
+        let first = Path::new("a/clip.mov");
+        assert_eq!(claim(&mut seen, hash.clone(), first), None);
+        assert_eq!(claim(&mut seen, hash.clone(), first), None);
+        assert_eq!(
+            claim(&mut seen, hash.clone(), Path::new("b/copy of clip.mp4")),
+            Some(first.to_path_buf())
+        );
+        let longer = ContentHash {
+            duration_ds: 150,
+            ..hash.clone()
+        };
+        assert_eq!(claim(&mut seen, longer, Path::new("c/longer.mov")), None);
+
+        // Released after a failed conversion, the next copy claims the content itself.
+        seen.retain(|(_, claimed_by)| claimed_by != first);
+        let copy = Path::new("b/copy of clip.mp4");
+        assert_eq!(claim(&mut seen, hash.clone(), copy), None);
+        assert_eq!(claim(&mut seen, hash, first), Some(copy.to_path_buf()));
+    }
+
+    #[test]
+    fn test_matches_tolerates_small_differences() {
+        let (phash, mean) = frame_hash(&gradient(false));
+        let hash = ContentHash {
+            width: 1920,
+            height: 1080,
+            duration_ds: 125,
+            frames: vec![(phash, mean)],
+        };
+        let near = ContentHash {
+            duration_ds: 126,
+            frames: vec![(phash ^ 0b111, mean.saturating_add(2))],
+            ..hash.clone()
+        };
+        assert!(hash.matches(&near));
+
+        let far_bits = ContentHash {
+            frames: vec![(phash ^ 0xffff, mean)],
+            ..hash.clone()
+        };
+        let far_luma = ContentHash {
+            frames: vec![(phash, mean.saturating_add(40))],
+            ..hash.clone()
+        };
+        let other_size = ContentHash {
+            width: 1280,
+            ..hash.clone()
+        };
+        assert!(!hash.matches(&far_bits));
+        assert!(!hash.matches(&far_luma));
+        assert!(!hash.matches(&other_size));
+    }
+
+    #[test]
+    fn test_reencodes_at_different_crf_dedup() {
+        if which::which("ffmpeg").is_err() || which::which("ffprobe").is_err() {
+            return;
+        }
+        let dir = tempfile::tempdir().unwrap();
+        let encode = |source: &str, crf: &str, name: &str| {
+            let path = dir.path().join(name);
+            let status = Command::new("ffmpeg")
+                .args(["-v", "error", "-y", "-f", "lavfi", "-i", source])
+                .args(["-c:v", "libx264", "-crf", crf, "-pix_fmt", "yuv420p"])
+                .arg(&path)
+                .status()
+                .unwrap();
+            assert!(status.success());
+            path
+        };
+        let clip = "testsrc2=size=320x180:rate=25:duration=3";
+        let high = encode(clip, "18", "high.mp4");
+        let low = encode(clip, "36", "low.mp4");
+        let other = encode(
+            "mandelbrot=size=320x180:rate=25,trim=duration=3",
+            "18",
+            "other.mp4",
+        );
+
+        let deduper = Deduper::new();
+        assert_eq!(deduper.check_duplicate(&high), None);
+        assert_eq!(deduper.check_duplicate(&low), Some(high.clone()));
+        assert_eq!(deduper.check_duplicate(&other), None);
+        assert_eq!(deduper.report().duplicates, 1);
+    }
+}
@@ -0,0 +1,264 @@
+//! Environment Diagnostics (`doctor`)
+//!
+//! One checklist before a big run: external tools and their versions, the encoders
+//! compiled into this ffmpeg, the detected GPU and the CPU/thread plan. Missing
+//! pieces come with an install hint; only required ones make the report fail.
+
+use crate::gpu_accel::GpuAccel;
+use crate::thread_manager::{self, WorkloadType};
+use crate::tools::{self, ToolCheck};
+use std::collections::HashSet;
+use std::process::Command;
+
+/// FFmpeg encoders worth knowing about, with a short description.
+const ENCODERS: &[(&str, &str)] = &[
+    ("libx265", "HEVC, CPU"),
+    ("libx264", "H.264, CPU (Apple fallback)"),
+    ("libsvtav1", "AV1, CPU"),
+    ("libaom-av1", "AV1, CPU (--av1-encoder aom)"),
+    ("hevc_videotoolbox", "HEVC, Apple GPU"),
+];
+
+#[derive(Debug, Clone)]
+pub struct DoctorCheck {
+    pub label: String,
+    pub ok: bool,
+    /// Missing optional pieces are shown as warnings and do not fail the report.
+    pub required: bool,
+    pub detail: String,
+    pub hint: Option<&'static str>,
+}
+
+#[derive(Debug, Clone)]
+pub struct DoctorReport {
+    pub sections: Vec<(&'static str, Vec<DoctorCheck>)>,
+}
+
+impl DoctorReport {
+    pub fn missing_required(&self) -> usize {
+        self.sections
+            .iter()
+            .flat_map(|(_, checks)| checks)
+            .filter(|c| c.required && !c.ok)
+            .count()
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.missing_required() == 0
+    }
+
+    pub fn print(&self) {
+        println!("🩺 Environment Check");
+        println!(
+            "━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━"
+        );
+        for (title, checks) in &self.sections {
+            println!("{}", title);
+            for check in checks {
+                let icon = match (check.ok, check.required) {
+                    (true, _) => "✅",
+                    (false, true) => "❌",
+                    (false, false) => "⚠️ ",
+                };
+                println!("   {} {} - {}", icon, check.label, check.detail);
+                if let (false, Some(hint)) = (check.ok, check.hint) {
+                    println!("      💡 Install with: {}", hint);
+                }
+            }
+        }
+        println!(
+            "━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━"
+        );
+        match self.missing_required() {
+            0 => println!("   ✅ Ready to run"),
+            n => println!("   ❌ Not ready: {} required item(s) missing", n),
+        }
+    }
+}
+
+/// Run every check for `workload`. `required_encoders` are the ffmpeg encoders this
+/// tool cannot work without (e.g. `libx265` for the HEVC tools).
+pub fn run_doctor(workload: WorkloadType, required_encoders: &[&str]) -> DoctorReport {
+    let (tools, optional_tools) = match workload {
+        WorkloadType::Image => (
+            tools::check_image_tools(),
+            vec![
+                optional_tool("exiv2", "brew install exiv2"),
+                optional_tool("magick", "brew install imagemagick"),
+            ],
+        ),
+        // The x265 CLI is only the CPU path; ffmpeg's libx265 covers the rest.
+        WorkloadType::Video => (
+            tools::check_video_tools(),
+            vec![optional_tool("x265", "brew install x265")],
+        ),
+    };
+    // dovi_tool only matters for Dolby Vision sources.
+    let mut tool_checks: Vec<DoctorCheck> = tools
+        .into_iter()
+        .map(|tool| {
+            let required = tool.name != "dovi_tool";
+            tool_check(tool, required)
+        })
+        .collect();
+    tool_checks.extend(optional_tools.into_iter().map(|t| tool_check(t, false)));
+
+    let available = ffmpeg_encoders();
+    let encoder_checks = ENCODERS
+        .iter()
+        .map(|(name, what)| {
+            let ok = available.contains(*name);
+            DoctorCheck {
+                label: name.to_string(),
+                ok,
+                required: required_encoders.contains(name),
+                detail: if ok {
+                    what.to_string()
+                } else {
+                    format!("{} - not in this ffmpeg build", what)
+                },
+                hint: if name.contains("videotoolbox") {
+                    None
+                } else {
+                    Some("brew install ffmpeg")
+                },
+            }
+        })
+        .collect();
+
+    DoctorReport {
+        sections: vec![
+            ("🔧 Tools", tool_checks),
+            ("🎬 FFmpeg encoders", encoder_checks),
+            ("🖥️  Hardware", hardware_checks(workload)),
+        ],
+    }
+}
+
+fn optional_tool(name: &'static str, install_hint: &'static str) -> ToolCheck {
+    ToolCheck {
+        name,
+        available: tools::check_tool(name) || tools::check_tool_alt(name),
+        version: tools::get_tool_version(name),
+        install_hint,
+    }
+}
+
+fn tool_check(tool: ToolCheck, required: bool) -> DoctorCheck {
+    DoctorCheck {
+        label: tool.name.to_string(),
+        ok: tool.available,
+        required,
+        detail: if tool.available {
+            tool.version
+                .unwrap_or_else(|| "unknown version".to_string())
+        } else {
+            "NOT FOUND".to_string()
+        },
+        hint: Some(tool.install_hint),
+    }
+}
+
+fn hardware_checks(workload: WorkloadType) -> Vec<DoctorCheck> {
+    let gpu = GpuAccel::detect();
+    let gpu_encoders: Vec<&str> = [&gpu.hevc_encoder, &gpu.av1_encoder, &gpu.h264_encoder]
+        .into_iter()
+        .flatten()
+        .map(|e| e.name)
+        .collect();
+    let gpu_detail = if gpu_encoders.is_empty() {
+        gpu.description()
+    } else {
+        format!("{} ({})", gpu.description(), gpu_encoders.join(", "))
+    };
+
+    let cores = std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1);
+    let plan = thread_manager::get_balanced_thread_config(workload);
+    let mut cpu_detail = format!(
+        "{} cores → {} parallel file(s) × {} thread(s)",
+        cores, plan.parallel_tasks, plan.child_threads
+    );
+    if let Some(hint) = thread_manager::memory_cap_hint() {
+        cpu_detail.push_str(&format!(" ({})", hint));
+    }
+
+    vec![
+        DoctorCheck {
+            label: "GPU".to_string(),
+            ok: gpu.is_available(),
+            required: false,
+            detail: gpu_detail,
+            hint: None,
+        },
+        DoctorCheck {
+            label: "CPU".to_string(),
+            ok: true,
+            required: true,
+            detail: cpu_detail,
+            hint: None,
+        },
+    ]
+}
+
+fn ffmpeg_encoders() -> HashSet<String> {
+    Command::new("ffmpeg")
+        .args(["-hide_banner", "-encoders"])
+        .output()
+        .map(|o| parse_encoder_list(&String::from_utf8_lossy(&o.stdout)))
+        .unwrap_or_default()
+}
+
+/// Encoder names from `ffmpeg -encoders` (lines like ` V....D libx265  libx265 H.265 / HEVC`).
+fn parse_encoder_list(output: &str) -> HashSet<String> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let flags = fields.next()?;
+            let name = fields.next()?;
+            let is_entry = flags.len() == 6
+                && flags.starts_with(['V', 'A', 'S'])
+                && flags[1..]
+                    .chars()
+                    .all(|c| c == '.' || c.is_ascii_uppercase());
+            (is_entry && name != "=").then(|| name.to_string())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_encoder_list() {
+        let output = "Encoders:\n V..... = Video\n A..... = Audio\n ------\n V....D libx265              libx265 H.265 / HEVC (codec hevc)\n V....D libsvtav1            SVT-AV1(Scalable Video Technology for AV1) encoder (codec av1)\n A....D aac                  AAC (Advanced Audio Coding)\n";
+        let encoders = parse_encoder_list(output);
+        assert!(encoders.contains("libx265"));
+        assert!(encoders.contains("libsvtav1"));
+        assert!(encoders.contains("aac"));
+        assert!(!encoders.contains("="));
+        assert!(!encoders.contains("libaom-av1"));
+    }
+
+    #[test]
+    fn test_report_readiness_ignores_optional() {
+        let check = |ok, required| DoctorCheck {
+            label: "x".to_string(),
+            ok,
+            required,
+            detail: String::new(),
+            hint: None,
+        };
+        let report = DoctorReport {
+            sections: vec![("t", vec![check(true, true), check(false, false)])],
+        };
+        assert!(report.is_ready());
+        let report = DoctorReport {
+            sections: vec![("t", vec![check(false, true), check(false, false)])],
+        };
+        assert_eq!(report.missing_required(), 1);
+    }
+}
@@ -0,0 +1,418 @@
+//! Encoder Comparison (`compare-encoders`)
+//!
+//! Encodes the first seconds of one video at a fixed CRF with each candidate codec and
+//! reports output size, SSIM and VMAF side by side, so a library can be measured before
+//! committing to HEVC or AV1. The sample is first cut to a lossless FFV1 reference, which
+//! every candidate is encoded from and scored against.
+//!
+//! Like the GPU coarse search, candidates use the hardware encoder when one is available
+//! (CRF mapped through `CrfMapping`); `use_gpu: false` forces the software encoders.
+
+use anyhow::{bail, Context, Result};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::Instant;
+
+use crate::gpu_accel::{CrfMapping, GpuAccel, GpuEncoder};
+
+/// Sample length used when `--seconds` is not given.
+pub const DEFAULT_COMPARE_SECONDS: f32 = 10.0;
+
+/// Two results whose SSIM differs by less than this count as the same quality.
+const SSIM_TIE: f64 = 0.005;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompareCodec {
+    Hevc,
+    Av1,
+    Vp9,
+}
+
+impl CompareCodec {
+    pub fn label(&self) -> &'static str {
+        match self {
+            CompareCodec::Hevc => "HEVC",
+            CompareCodec::Av1 => "AV1",
+            CompareCodec::Vp9 => "VP9",
+        }
+    }
+
+    pub fn default_crf(&self) -> f32 {
+        match self {
+            CompareCodec::Hevc => crate::crf_constants::HEVC_CRF_DEFAULT,
+            CompareCodec::Av1 => crate::crf_constants::AV1_CRF_DEFAULT,
+            CompareCodec::Vp9 => crate::crf_constants::VP9_CRF_DEFAULT,
+        }
+    }
+
+    fn max_crf(&self) -> f32 {
+        match self {
+            CompareCodec::Hevc => crate::crf_constants::HEVC_CRF_MAX,
+            CompareCodec::Av1 => crate::crf_constants::AV1_CRF_MAX,
+            CompareCodec::Vp9 => crate::crf_constants::VP9_CRF_MAX,
+        }
+    }
+
+    fn extension(&self) -> &'static str {
+        match self {
+            CompareCodec::Vp9 => "webm",
+            _ => "mp4",
+        }
+    }
+
+    fn gpu_encoder<'a>(&self, gpu: &'a GpuAccel) -> Option<(&'a GpuEncoder, CrfMapping)> {
+        match self {
+            CompareCodec::Hevc => gpu
+                .get_hevc_encoder()
+                .map(|e| (e, CrfMapping::hevc(gpu.gpu_type))),
+            CompareCodec::Av1 => gpu
+                .get_av1_encoder()
+                .map(|e| (e, CrfMapping::av1(gpu.gpu_type))),
+            CompareCodec::Vp9 => None,
+        }
+    }
+
+    fn cpu_args(&self, crf: f32, max_threads: usize) -> Vec<String> {
+        let mut args: Vec<String> = match self {
+            CompareCodec::Hevc => vec![
+                "-c:v".into(),
+                crate::VideoEncoder::Hevc.ffmpeg_name().into(),
+                "-crf".into(),
+                format!("{:.1}", crf),
+                "-preset".into(),
+                "medium".into(),
+                "-tag:v".into(),
+                "hvc1".into(),
+            ],
+            CompareCodec::Av1 => vec![
+                "-c:v".into(),
+                "libsvtav1".into(),
+                "-crf".into(),
+                format!("{:.0}", crf),
+                "-preset".into(),
+                "6".into(),
+            ],
+            CompareCodec::Vp9 => vec![
+                "-c:v".into(),
+                "libvpx-vp9".into(),
+                "-crf".into(),
+                format!("{:.0}", crf),
+                "-b:v".into(),
+                "0".into(),
+                "-row-mt".into(),
+                "1".into(),
+            ],
+        };
+        if max_threads > 0 {
+            args.push("-threads".into());
+            args.push(max_threads.to_string());
+        }
+        args
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct EncoderComparisonConfig {
+    pub sample_secs: f32,
+    /// Fixed CRF for every codec; `None` uses each codec's default.
+    pub crf: Option<f32>,
+    pub include_vp9: bool,
+    pub use_gpu: bool,
+    pub max_threads: usize,
+}
+
+impl Default for EncoderComparisonConfig {
+    fn default() -> Self {
+        Self {
+            sample_secs: DEFAULT_COMPARE_SECONDS,
+            crf: None,
+            include_vp9: false,
+            use_gpu: true,
+            max_threads: 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct EncoderSample {
+    pub codec: CompareCodec,
+    pub encoder: String,
+    pub crf: f32,
+    pub size: u64,
+    pub ssim: Option<f64>,
+    pub vmaf: Option<f64>,
+    pub encode_secs: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct EncoderComparison {
+    pub input: PathBuf,
+    pub sample_secs: f32,
+    /// Source bytes for the sampled span, pro-rated from the whole file.
+    pub source_sample_bytes: u64,
+    pub samples: Vec<EncoderSample>,
+    pub failures: Vec<(CompareCodec, String)>,
+}
+
+impl EncoderComparison {
+    /// Smallest output among the samples that reach the best measured SSIM
+    /// (within `SSIM_TIE`). Samples without SSIM are only considered when none has one.
+    pub fn best_tradeoff(&self) -> Option<&EncoderSample> {
+        let best_ssim = self.samples.iter().filter_map(|s| s.ssim).reduce(f64::max);
+        self.samples
+            .iter()
+            .filter(|s| match (best_ssim, s.ssim) {
+                (Some(best), Some(ssim)) => ssim >= best - SSIM_TIE,
+                (Some(_), None) => false,
+                (None, _) => true,
+            })
+            .min_by_key(|s| s.size)
+    }
+
+    pub fn print_table(&self) {
+        println!("\n📊 Encoder Comparison");
+        println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        println!("📁 File: {}", self.input.display());
+        println!(
+            "🎞️  Sample: first {:.1}s (source ≈ {})",
+            self.sample_secs,
+            crate::format_bytes(self.source_sample_bytes)
+        );
+        println!();
+        println!(
+            "{:<6} {:<18} {:>5} {:>11} {:>8} {:>7} {:>6} {:>7}",
+            "Codec", "Encoder", "CRF", "Size", "vs src", "SSIM", "VMAF", "Time"
+        );
+        for s in &self.samples {
+            let vs_source = if self.source_sample_bytes > 0 {
+                format!(
+                    "{:+.1}%",
+                    (s.size as f64 / self.source_sample_bytes as f64 - 1.0) * 100.0
+                )
+            } else {
+                "-".to_string()
+            };
+            println!(
+                "{:<6} {:<18} {:>5.1} {:>11} {:>8} {:>7} {:>6} {:>6.1}s",
+                s.codec.label(),
+                s.encoder,
+                s.crf,
+                crate::format_bytes(s.size),
+                vs_source,
+                s.ssim
+                    .map(|v| format!("{:.4}", v))
+                    .unwrap_or_else(|| "-".to_string()),
+                s.vmaf
+                    .map(|v| format!("{:.1}", v))
+                    .unwrap_or_else(|| "-".to_string()),
+                s.encode_secs
+            );
+        }
+        for (codec, err) in &self.failures {
+            println!("{:<6} ❌ {}", codec.label(), err);
+        }
+        if let Some(best) = self.best_tradeoff() {
+            println!();
+            println!(
+                "💡 Best size/quality on this content: {} ({})",
+                best.codec.label(),
+                best.encoder
+            );
+        }
+    }
+}
+
+/// Encode the first `config.sample_secs` of `input` with HEVC, AV1 and optionally VP9.
+/// A codec that fails to encode is reported in `failures`; the others still run.
+pub fn compare_encoders(
+    input: &Path,
+    config: &EncoderComparisonConfig,
+) -> Result<EncoderComparison> {
+    if config.sample_secs.is_nan() || config.sample_secs <= 0.0 {
+        bail!("Sample length must be positive");
+    }
+    let probe = crate::ffprobe::probe_video(input)
+        .map_err(|e| anyhow::anyhow!("ffprobe failed for {}: {}", input.display(), e))?;
+    let input_size = std::fs::metadata(input)
+        .context("Failed to read input file metadata")?
+        .len();
+    let sample_secs = if probe.duration > 0.0 {
+        config.sample_secs.min(probe.duration as f32)
+    } else {
+        config.sample_secs
+    };
+    let source_sample_bytes = if probe.duration > 0.0 {
+        (input_size as f64 * (sample_secs as f64 / probe.duration).min(1.0)) as u64
+    } else {
+        input_size
+    };
+
+    let work_dir = tempfile::Builder::new()
+        .prefix("mfb_compare_")
+        .tempdir_in(crate::conversion::scratch_dir())
+        .context("Failed to create temp directory")?;
+    let reference = work_dir.path().join("reference.mkv");
+    cut_lossless_reference(input, sample_secs, &reference)?;
+
+    let mut codecs = vec![CompareCodec::Hevc, CompareCodec::Av1];
+    if config.include_vp9 {
+        codecs.push(CompareCodec::Vp9);
+    }
+
+    let gpu = GpuAccel::detect();
+    let mut samples = Vec::new();
+    let mut failures = Vec::new();
+    for codec in codecs {
+        let crf = config
+            .crf
+            .unwrap_or_else(|| codec.default_crf())
+            .clamp(0.0, codec.max_crf());
+        let output = work_dir.path().join(format!(
+            "{}.{}",
+            codec.label().to_lowercase(),
+            codec.extension()
+        ));
+
+        let gpu_choice = if config.use_gpu {
+            codec.gpu_encoder(gpu)
+        } else {
+            None
+        };
+        let (encoder, args) = match gpu_choice {
+            Some((encoder, mapping)) => {
+                let mut args = vec!["-c:v".to_string(), encoder.ffmpeg_name().to_string()];
+                args.extend(encoder.get_crf_args(mapping.cpu_to_gpu(crf)));
+                args.extend(encoder.extra_args().iter().map(|a| a.to_string()));
+                (encoder.ffmpeg_name().to_string(), args)
+            }
+            None => {
+                let args = codec.cpu_args(crf, config.max_threads);
+                (args[1].clone(), args)
+            }
+        };
+
+        crate::log_eprintln!("   🔄 {} ({}) @ CRF {:.1}...", codec.label(), encoder, crf);
+        let start = Instant::now();
+        if let Err(e) = encode_sample(&reference, &args, &output) {
+            failures.push((codec, format!("{:#}", e)));
+            continue;
+        }
+        let encode_secs = start.elapsed().as_secs_f64();
+        let size = std::fs::metadata(&output).map(|m| m.len()).unwrap_or(0);
+        let ssim = crate::video_explorer::calculate_ssim_enhanced(&reference, &output, &[]);
+        let vmaf = crate::video_explorer::calculate_vmaf_y(&reference, &output, 2);
+
+        samples.push(EncoderSample {
+            codec,
+            encoder,
+            crf,
+            size,
+            ssim,
+            vmaf,
+            encode_secs,
+        });
+    }
+
+    Ok(EncoderComparison {
+        input: input.to_path_buf(),
+        sample_secs,
+        source_sample_bytes,
+        samples,
+        failures,
+    })
+}
+
+fn cut_lossless_reference(input: &Path, sample_secs: f32, reference: &Path) -> Result<()> {
+    let output = Command::new("ffmpeg")
+        .arg("-y")
+        .arg("-t")
+        .arg(format!("{:.3}", sample_secs))
+        .arg("-i")
+        .arg(crate::safe_path_arg(input).as_ref())
+        .arg("-map")
+        .arg("0:v:0")
+        .arg("-an")
+        .arg("-c:v")
+        .arg("ffv1")
+        .arg(crate::safe_path_arg(reference).as_ref())
+        .output()
+        .context("Failed to run ffmpeg")?;
+    if !output.status.success() {
+        bail!(
+            "Failed to cut sample: {}",
+            String::from_utf8_lossy(&output.stderr)
+                .lines()
+                .last()
+                .unwrap_or("unknown error")
+        );
+    }
+    Ok(())
+}
+
+fn encode_sample(reference: &Path, codec_args: &[String], output: &Path) -> Result<()> {
+    let result = Command::new("ffmpeg")
+        .arg("-y")
+        .arg("-i")
+        .arg(crate::safe_path_arg(reference).as_ref())
+        .args(codec_args)
+        .arg("-pix_fmt")
+        .arg("yuv420p")
+        .arg(crate::safe_path_arg(output).as_ref())
+        .output()
+        .context("Failed to run ffmpeg")?;
+    if !result.status.success() {
+        bail!(
+            "{}",
+            String::from_utf8_lossy(&result.stderr)
+                .lines()
+                .last()
+                .unwrap_or("encode failed")
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(codec: CompareCodec, size: u64, ssim: Option<f64>) -> EncoderSample {
+        EncoderSample {
+            codec,
+            encoder: String::new(),
+            crf: codec.default_crf(),
+            size,
+            ssim,
+            vmaf: None,
+            encode_secs: 1.0,
+        }
+    }
+
+    fn comparison(samples: Vec<EncoderSample>) -> EncoderComparison {
+        EncoderComparison {
+            input: PathBuf::from("clip.mp4"),
+            sample_secs: 10.0,
+            source_sample_bytes: 10_000_000,
+            samples,
+            failures: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_best_tradeoff_prefers_smaller_at_equal_quality() {
+        let c = comparison(vec![
+            sample(CompareCodec::Hevc, 3_000_000, Some(0.970)),
+            sample(CompareCodec::Av1, 2_000_000, Some(0.968)),
+            sample(CompareCodec::Vp9, 1_500_000, Some(0.940)),
+        ]);
+        assert_eq!(c.best_tradeoff().unwrap().codec, CompareCodec::Av1);
+
+        let c = comparison(vec![
+            sample(CompareCodec::Hevc, 3_000_000, Some(0.970)),
+            sample(CompareCodec::Av1, 1_000_000, None),
+        ]);
+        assert_eq!(c.best_tradeoff().unwrap().codec, CompareCodec::Hevc);
+
+        assert!(comparison(Vec::new()).best_tradeoff().is_none());
+    }
+}
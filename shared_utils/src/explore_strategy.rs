@@ -430,6 +430,7 @@ impl ExploreContext {
 
         let size = self.do_encode(crf)?;
         self.cache_size(crf, size);
+        crate::progress_sink::emit_iteration(crf, None, size);
         Ok(size)
     }
 
@@ -442,6 +443,7 @@ impl ExploreContext {
         cmd.arg("-y")
             .arg("-threads")
             .arg(self.max_threads.to_string())
+            .args(crate::video::source_trim_args(&self.input_path))
             .arg("-i")
             .arg(crate::safe_path_arg(&self.input_path).as_ref())
             .arg("-c:v")
@@ -451,13 +453,18 @@ impl ExploreContext {
             .arg("-preset")
             .arg(self.preset.x26x_name());
 
-        for arg in self.encoder.extra_args(self.max_threads) {
+        for arg in self.encoder.extra_args_with_preset(
+            self.max_threads,
+            self.preset,
+            self.config.settings.deterministic,
+        ) {
             cmd.arg(arg);
         }
 
         for arg in &self.vf_args {
             cmd.arg(arg);
         }
+        cmd.args(crate::video_explorer::frame_rate_mode_encode_args());
 
         cmd.arg(crate::safe_path_arg(&self.output_path).as_ref());
 
@@ -485,6 +492,11 @@ impl ExploreContext {
 
         let result = self.do_calculate_ssim()?;
         self.cache_ssim(crf, result.clone());
+        crate::progress_sink::emit_iteration(
+            crf,
+            Some(result.value),
+            self.get_cached_size(crf).unwrap_or(0),
+        );
         Ok(result)
     }
 
@@ -505,15 +517,19 @@ impl ExploreContext {
     fn do_calculate_ssim(&self) -> Result<SsimResult> {
         use std::process::Command;
 
-        let filter = "[0:v]scale='iw-mod(iw,2)':'ih-mod(ih,2)':flags=bicubic[ref];[ref][1:v]ssim";
+        let filter = format!(
+            "{};[ref][1:v]ssim",
+            crate::video::metric_reference_filter(&self.vf_args)
+        );
 
         let output = Command::new("ffmpeg")
+            .args(crate::video::source_trim_args(&self.input_path))
             .arg("-i")
             .arg(crate::safe_path_arg(&self.input_path).as_ref())
             .arg("-i")
             .arg(crate::safe_path_arg(&self.output_path).as_ref())
             .arg("-lavfi")
-            .arg(filter)
+            .arg(&filter)
             .arg("-f")
             .arg("null")
             .arg("-")
@@ -531,7 +547,7 @@ impl ExploreContext {
         eprintln!("   ⚠️ SSIM calculation failed, trying PSNR fallback...");
 
         if let Some(psnr) = self.calculate_psnr()? {
-            let ssim = crate::ssim_mapping::psnr_to_ssim_estimate(psnr);
+            let ssim = crate::ssim_mapping::calibrated_psnr_to_ssim(psnr);
             eprintln!("   📊 PSNR: {:.1} dB → Estimated SSIM: {:.4}", psnr, ssim);
             return Ok(SsimResult::predicted(ssim, psnr));
         }
@@ -566,15 +582,19 @@ impl ExploreContext {
     fn calculate_psnr(&self) -> Result<Option<f64>> {
         use std::process::Command;
 
-        let filter = "[0:v]scale='iw-mod(iw,2)':'ih-mod(ih,2)':flags=bicubic[ref];[ref][1:v]psnr";
+        let filter = format!(
+            "{};[ref][1:v]psnr",
+            crate::video::metric_reference_filter(&self.vf_args)
+        );
 
         let output = Command::new("ffmpeg")
+            .args(crate::video::source_trim_args(&self.input_path))
             .arg("-i")
             .arg(crate::safe_path_arg(&self.input_path).as_ref())
             .arg("-i")
             .arg(crate::safe_path_arg(&self.output_path).as_ref())
             .arg("-lavfi")
-            .arg(filter)
+            .arg(&filter)
             .arg("-f")
             .arg("null")
             .arg("-")
@@ -32,6 +32,7 @@
 //! let (status, stderr) = process.wait_with_output()?;
 //! ```
 
+use crate::cancellation::ChildWatcher;
 use anyhow::{Context, Result};
 use std::io::{BufRead, BufReader};
 use std::process::{Child, ChildStdout, Command, ExitStatus, Stdio};
@@ -41,6 +42,8 @@ use tracing::{debug, error, info, warn};
 pub struct FfmpegProcess {
     child: Child,
     stderr_thread: Option<JoinHandle<String>>,
+    /// Kills the child if the thread's cancellation token fires while it runs.
+    watcher: Option<ChildWatcher>,
 }
 
 impl FfmpegProcess {
@@ -79,9 +82,12 @@ impl FfmpegProcess {
             buf
         });
 
+        let watcher = ChildWatcher::for_current(&child);
+
         Ok(Self {
             child,
             stderr_thread: Some(stderr_thread),
+            watcher,
         })
     }
 
@@ -110,6 +116,7 @@ impl FfmpegProcess {
             })
         });
         let status = self.child.wait().context("Failed to wait for FFmpeg")?;
+        self.watcher.take();
         if let Some(h) = stdout_drain {
             match h.join() {
                 Ok(Some(err)) => warn!(error = %err, "Failed while draining FFmpeg stdout"),
@@ -297,6 +304,10 @@ pub fn is_recoverable_error(stderr: &str) -> bool {
         "Too many open files",
         "Connection reset",
         "Broken pipe",
+        "Device or resource busy",
+        "Interrupted system call",
+        "Input/output error",
+        "OpenEncodeSessionEx failed",
     ];
     recoverable_patterns
         .iter()
@@ -480,6 +491,12 @@ Conversion failed!
     fn test_is_recoverable_error() {
         assert!(is_recoverable_error("Resource temporarily unavailable"));
         assert!(is_recoverable_error("Cannot allocate memory"));
+        assert!(is_recoverable_error(
+            "[hevc_nvenc] OpenEncodeSessionEx failed: out of memory (10)"
+        ));
+        assert!(is_recoverable_error(
+            "/dev/dri/renderD128: Device or resource busy"
+        ));
         assert!(!is_recoverable_error("Invalid input file"));
     }
 }
@@ -52,6 +52,9 @@ pub struct FFprobeResult {
     pub color_space: Option<String>,
     pub color_transfer: Option<String>,
     pub color_primaries: Option<String>,
+    /// "tv" or "pc", see [`crate::ffprobe_json::normalize_color_range`].
+    #[serde(default)]
+    pub color_range: Option<String>,
     pub bit_depth: u8,
     pub has_audio: bool,
     pub audio_codec: Option<String>,
@@ -87,6 +90,8 @@ pub struct FFprobeResult {
     pub is_variable_frame_rate: bool,
     /// Stream index of the selected video stream (for multi-stream files like animated AVIF)
     pub stream_index: usize,
+    /// Clockwise rotation (90/180/270) needed for upright display; `None` when upright
+    pub rotation: Option<i32>,
     /// Format tags (e.g. encoder, creation_time) from the format section
     pub tags: std::collections::HashMap<String, String>,
 }
@@ -119,12 +124,24 @@ fn detect_vfr_enhanced(
         }
     }
 
-    // Standard VFR detection with 2% threshold
-    let diff_ratio = (r_frame_rate - avg_frame_rate).abs() / r_frame_rate;
-    diff_ratio > 0.02
+    crate::ffprobe_json::frame_rates_indicate_vfr(r_frame_rate, avg_frame_rate)
 }
 
+/// Probe `path` with ffprobe. Answered from the per-file cache while `path` is the input
+/// being converted ([`crate::file_probe_cache`]). For a `--start` / `--end` source, duration,
+/// frame count and size describe the trimmed range ([`crate::video::trim_source`]).
 pub fn probe_video(path: &Path) -> Result<FFprobeResult, FFprobeError> {
+    let mut probe = crate::file_probe_cache::cached_probe(path, || probe_video_uncached(path))?;
+    let share = crate::video::trimmed_share(path);
+    if share < 1.0 {
+        probe.duration *= share;
+        probe.frame_count = (probe.frame_count as f64 * share).round() as u64;
+        probe.size = (probe.size as f64 * share) as u64;
+    }
+    Ok(probe)
+}
+
+fn probe_video_uncached(path: &Path) -> Result<FFprobeResult, FFprobeError> {
     if !is_ffprobe_available() {
         return Err(FFprobeError::ToolNotFound(
             "ffprobe not found. Install with: brew install ffmpeg".to_string(),
@@ -296,6 +313,14 @@ pub fn probe_video(path: &Path) -> Result<FFprobeResult, FFprobeError> {
     let is_variable_frame_rate =
         detect_vfr_enhanced(video_stream, frame_rate, avg_frame_rate, &format_name);
 
+    // For VFR, r_frame_rate is the timebase-derived ceiling, not the real rate: report and
+    // estimate frame counts from avg_frame_rate instead.
+    let frame_rate = if is_variable_frame_rate {
+        avg_frame_rate
+    } else {
+        frame_rate
+    };
+
     let frame_count = video_stream["nb_frames"]
         .as_str()
         .and_then(|s| s.parse::<u64>().ok())
@@ -327,6 +352,12 @@ pub fn probe_video(path: &Path) -> Result<FFprobeResult, FFprobeError> {
         }
     });
 
+    let color_range = crate::ffprobe_json::normalize_color_range(
+        video_stream["color_range"].as_str(),
+        Some(&pix_fmt),
+    )
+    .map(str::to_string);
+
     // Parse HDR side data: Dolby Vision, HDR10+, mastering display, CLL
     // We scan all objects across streams and frames for side_data entries
     let hdr = extract_hdr_side_data(&json);
@@ -353,6 +384,7 @@ pub fn probe_video(path: &Path) -> Result<FFprobeResult, FFprobeError> {
         .as_str()
         .and_then(|s| s.parse::<u64>().ok());
     let refs = video_stream["refs"].as_u64().map(|r| r as u32);
+    let rotation = crate::ffprobe_json::stream_rotation(video_stream);
 
     let audio_stream = streams
         .iter()
@@ -394,6 +426,7 @@ pub fn probe_video(path: &Path) -> Result<FFprobeResult, FFprobeError> {
         color_space,
         color_transfer,
         color_primaries,
+        color_range,
         bit_depth,
         has_audio,
         audio_codec,
@@ -417,6 +450,7 @@ pub fn probe_video(path: &Path) -> Result<FFprobeResult, FFprobeError> {
         subtitle_codec,
         is_variable_frame_rate,
         stream_index,
+        rotation,
         tags,
     })
 }
@@ -632,7 +666,7 @@ pub fn get_duration(path: &Path) -> Option<f64> {
     let stdout = String::from_utf8_lossy(&output.stdout);
     let trimmed = stdout.trim();
     match trimmed.parse::<f64>() {
-        Ok(duration) => Some(duration),
+        Ok(duration) => Some(duration * crate::video::trimmed_share(path)),
         Err(err) => {
             warn!(
                 path = %path.display(),
@@ -645,6 +679,174 @@ pub fn get_duration(path: &Path) -> Option<f64> {
     }
 }
 
+/// Result of [`probe_integrity`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MediaIntegrity {
+    Ok,
+    /// The file ends before its container says it should (incomplete download/copy).
+    Truncated(String),
+    /// ffprobe cannot open or parse the file at all.
+    Unreadable(String),
+}
+
+impl MediaIntegrity {
+    pub fn is_ok(&self) -> bool {
+        matches!(self, MediaIntegrity::Ok)
+    }
+}
+
+/// Seconds before the end decoded by the tail check in [`probe_integrity`].
+const INTEGRITY_TAIL_SECS: f64 = 3.0;
+
+/// Fast pre-conversion integrity check, so truncated downloads are caught before a long encode.
+///
+/// MP4/MOV/M4V: walks the top-level atoms; a missing `moov` or an atom running past EOF is
+/// `Truncated`. Other containers (and MP4 that passes): ffprobe must open the file and decode
+/// at least one frame, of any stream, from the last few seconds. The container duration is that
+/// of the longest stream, so audio running past the video is not mistaken for a cut-off file.
+pub fn probe_integrity(path: &Path) -> MediaIntegrity {
+    let ext = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_ascii_lowercase())
+        .unwrap_or_default();
+    if matches!(ext.as_str(), "mp4" | "mov" | "m4v" | "3gp") {
+        match std::fs::File::open(path) {
+            Ok(mut file) => {
+                let file_len = file.metadata().map(|m| m.len()).unwrap_or(0);
+                if let Some(result) = check_isobmff_atoms(&mut file, file_len) {
+                    if !result.is_ok() {
+                        return result;
+                    }
+                }
+            }
+            Err(e) => return MediaIntegrity::Unreadable(e.to_string()),
+        }
+    }
+
+    let output = match Command::new("ffprobe")
+        .args([
+            "-v",
+            "error",
+            "-show_entries",
+            "format=duration",
+            "-of",
+            "default=noprint_wrappers=1:nokey=1",
+            "--",
+        ])
+        .arg(crate::safe_path_arg(path).as_ref())
+        .output()
+    {
+        Ok(output) => output,
+        Err(e) => return MediaIntegrity::Unreadable(format!("ffprobe not runnable: {}", e)),
+    };
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return MediaIntegrity::Unreadable(
+            stderr
+                .lines()
+                .next()
+                .unwrap_or("ffprobe failed")
+                .to_string(),
+        );
+    }
+    let duration = String::from_utf8_lossy(&output.stdout)
+        .trim()
+        .parse::<f64>()
+        .unwrap_or(0.0);
+    if duration <= INTEGRITY_TAIL_SECS {
+        return MediaIntegrity::Ok;
+    }
+
+    let tail_start = duration - INTEGRITY_TAIL_SECS;
+    let tail = Command::new("ffprobe")
+        .args(["-v", "error", "-read_intervals"])
+        .arg(format!("{:.3}%", tail_start))
+        .args(["-show_entries", "frame=pts_time", "-of", "csv=p=0", "--"])
+        .arg(crate::safe_path_arg(path).as_ref())
+        .output();
+    match tail {
+        Ok(out) if String::from_utf8_lossy(&out.stdout).trim().is_empty() => {
+            MediaIntegrity::Truncated(format!(
+                "no decodable frames after {:.1}s of reported {:.1}s",
+                tail_start, duration
+            ))
+        }
+        // Tail decodes (or the probe could not run): do not block conversion on this check.
+        _ => MediaIntegrity::Ok,
+    }
+}
+
+/// Walk ISO-BMFF top-level boxes. `None` when the data does not look like ISO-BMFF.
+fn check_isobmff_atoms<R: std::io::Read + std::io::Seek>(
+    reader: &mut R,
+    file_len: u64,
+) -> Option<MediaIntegrity> {
+    use std::io::SeekFrom;
+
+    let mut offset = 0u64;
+    let mut has_moov = false;
+    let mut first = true;
+    while offset < file_len {
+        if file_len - offset < 8 {
+            return Some(MediaIntegrity::Truncated(format!(
+                "{} trailing bytes do not form an atom header",
+                file_len - offset
+            )));
+        }
+        reader.seek(SeekFrom::Start(offset)).ok()?;
+        let mut header = [0u8; 8];
+        reader.read_exact(&mut header).ok()?;
+        let box_type = &header[4..8];
+        if !box_type.iter().all(|b| b.is_ascii_graphic() || *b == b' ') {
+            // Not ISO-BMFF at all: let ffprobe decide. Garbage after valid atoms is truncation.
+            return if first {
+                None
+            } else {
+                Some(MediaIntegrity::Truncated(format!(
+                    "invalid atom header at offset {}",
+                    offset
+                )))
+            };
+        }
+        first = false;
+        let type_str = String::from_utf8_lossy(box_type).to_string();
+        let mut size = u32::from_be_bytes([header[0], header[1], header[2], header[3]]) as u64;
+        if size == 1 {
+            let mut large = [0u8; 8];
+            reader.read_exact(&mut large).ok()?;
+            size = u64::from_be_bytes(large);
+        } else if size == 0 {
+            // Box extends to end of file.
+            size = file_len - offset;
+        }
+        if size < 8 {
+            return Some(MediaIntegrity::Truncated(format!(
+                "atom '{}' at offset {} has invalid size {}",
+                type_str, offset, size
+            )));
+        }
+        if offset + size > file_len {
+            return Some(MediaIntegrity::Truncated(format!(
+                "atom '{}' needs {} bytes but only {} remain",
+                type_str,
+                size,
+                file_len - offset
+            )));
+        }
+        if type_str == "moov" {
+            has_moov = true;
+        }
+        offset += size;
+    }
+
+    if has_moov {
+        Some(MediaIntegrity::Ok)
+    } else {
+        Some(MediaIntegrity::Truncated("moov atom not found".to_string()))
+    }
+}
+
 pub fn get_frame_count(path: &Path) -> Option<u64> {
     let output = match Command::new("ffprobe")
         .args([
@@ -685,7 +887,9 @@ pub fn get_frame_count(path: &Path) -> Option<u64> {
     let stdout = String::from_utf8_lossy(&output.stdout);
     let trimmed = stdout.trim();
     match trimmed.parse::<u64>() {
-        Ok(frame_count) => Some(frame_count),
+        Ok(frame_count) => {
+            Some((frame_count as f64 * crate::video::trimmed_share(path)).round() as u64)
+        }
         Err(err) => {
             warn!(
                 path = %path.display(),
@@ -756,6 +960,80 @@ pub fn detect_bit_depth(pix_fmt: &str) -> u8 {
 mod tests {
     use super::*;
 
+    fn atom(kind: &[u8; 4], payload_len: usize) -> Vec<u8> {
+        let mut out = ((payload_len + 8) as u32).to_be_bytes().to_vec();
+        out.extend_from_slice(kind);
+        out.extend(std::iter::repeat_n(0u8, payload_len));
+        out
+    }
+
+    fn check_bytes(bytes: &[u8]) -> Option<MediaIntegrity> {
+        check_isobmff_atoms(&mut std::io::Cursor::new(bytes), bytes.len() as u64)
+    }
+
+    #[test]
+    fn test_isobmff_complete_file_ok() {
+        let mut data = atom(b"ftyp", 16);
+        data.extend(atom(b"mdat", 1000));
+        data.extend(atom(b"moov", 200));
+        assert_eq!(check_bytes(&data), Some(MediaIntegrity::Ok));
+    }
+
+    #[test]
+    fn test_isobmff_truncated_mdat() {
+        let mut data = atom(b"ftyp", 16);
+        data.extend(atom(b"moov", 200));
+        data.extend(atom(b"mdat", 1000));
+        data.truncate(data.len() - 300);
+        assert!(matches!(
+            check_bytes(&data),
+            Some(MediaIntegrity::Truncated(reason)) if reason.contains("mdat")
+        ));
+    }
+
+    #[test]
+    fn test_isobmff_missing_moov() {
+        let mut data = atom(b"ftyp", 16);
+        data.extend(atom(b"mdat", 1000));
+        assert_eq!(
+            check_bytes(&data),
+            Some(MediaIntegrity::Truncated("moov atom not found".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_integrity_audio_longer_than_video_is_ok() {
+        if which::which("ffmpeg").is_err() || which::which("ffprobe").is_err() {
+            return;
+        }
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("long_audio.mkv");
+        let status = Command::new("ffmpeg")
+            .args([
+                "-v",
+                "error",
+                "-f",
+                "lavfi",
+                "-i",
+                "testsrc=size=64x64:rate=10:duration=2",
+            ])
+            .args(["-f", "lavfi", "-i", "sine=frequency=440:duration=8"])
+            .args(["-c:v", "ffv1", "-c:a", "pcm_s16le"])
+            .arg(&path)
+            .status()
+            .unwrap();
+        assert!(status.success());
+        assert_eq!(probe_integrity(&path), MediaIntegrity::Ok);
+    }
+
+    #[test]
+    fn test_isobmff_not_bmff_defers_to_ffprobe() {
+        assert_eq!(
+            check_bytes(&[0x1a, 0x45, 0xdf, 0xa3, 0, 0, 0, 0, 0, 0]),
+            None
+        );
+    }
+
     #[test]
     fn test_parse_frame_rate() {
         let cases: &[(&str, f64, f64)] = &[
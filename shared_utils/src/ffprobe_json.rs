@@ -23,6 +23,15 @@ pub struct FfprobeSideData {
     // CLL fields
     pub max_content: Option<u64>,
     pub max_average: Option<u64>,
+    // Display Matrix: counter-clockwise degrees (e.g. -90 for a portrait phone clip)
+    pub rotation: Option<f64>,
+}
+
+#[derive(Debug, Clone, Deserialize, Default)]
+pub struct FfprobeStreamTags {
+    /// Legacy clockwise rotation tag written by older muxers
+    #[serde(default)]
+    pub rotate: Option<String>,
 }
 
 #[derive(Debug, Clone, Deserialize, Default)]
@@ -34,11 +43,50 @@ pub struct FfprobeStream {
     #[serde(default)]
     pub color_primaries: Option<String>,
     #[serde(default)]
+    pub color_range: Option<String>,
+    #[serde(default)]
     pub pix_fmt: Option<String>,
     #[serde(default)]
     pub bits_per_raw_sample: Option<String>,
     #[serde(default)]
     pub side_data_list: Vec<FfprobeSideData>,
+    #[serde(default)]
+    pub r_frame_rate: Option<String>,
+    #[serde(default)]
+    pub avg_frame_rate: Option<String>,
+    #[serde(default)]
+    pub tags: FfprobeStreamTags,
+}
+
+impl FfprobeStream {
+    /// Clockwise rotation (90, 180 or 270) needed to display the stream upright, from the
+    /// Display Matrix side data or, failing that, the legacy `rotate` tag. `None` when upright.
+    pub fn rotation(&self) -> Option<i32> {
+        let matrix = self
+            .side_data_list
+            .iter()
+            .find(|sd| {
+                sd.side_data_type
+                    .as_deref()
+                    .is_some_and(|t| t.eq_ignore_ascii_case("display matrix"))
+            })
+            .and_then(|sd| sd.rotation)
+            .map(|ccw| -ccw);
+        let tag = || self.tags.rotate.as_deref()?.trim().parse::<f64>().ok();
+        normalize_rotation(matrix.or_else(tag)?)
+    }
+
+    /// Average frame rate when the stream is VFR (`avg_frame_rate` vs `r_frame_rate`
+    /// disagree), `None` for CFR or when either rate is missing.
+    pub fn vfr_avg_frame_rate(&self) -> Option<f64> {
+        let r = crate::ffprobe::parse_frame_rate(self.r_frame_rate.as_deref()?).ok()?;
+        let avg = crate::ffprobe::parse_frame_rate(self.avg_frame_rate.as_deref()?).ok()?;
+        frame_rates_indicate_vfr(r, avg).then_some(avg)
+    }
+
+    pub fn is_vfr(&self) -> bool {
+        self.vfr_avg_frame_rate().is_some()
+    }
 }
 
 #[derive(Debug, Clone, Deserialize, Default)]
@@ -60,6 +108,9 @@ pub struct ColorInfo {
     pub color_space: Option<String>,
     pub color_transfer: Option<String>,
     pub color_primaries: Option<String>,
+    /// "tv" (limited) or "pc" (full), see [`normalize_color_range`].
+    #[serde(default)]
+    pub color_range: Option<String>,
     pub pix_fmt: Option<String>,
     pub bit_depth: Option<u8>,
     /// HDR10 mastering display string (ffmpeg format)
@@ -202,7 +253,80 @@ fn parse_side_data_list(
     }
 }
 
+/// Snap a clockwise angle to 0/90/180/270; `None` for 0.
+fn normalize_rotation(clockwise: f64) -> Option<i32> {
+    let quarter_turns = (clockwise / 90.0).round() as i32;
+    let degrees = (quarter_turns * 90).rem_euclid(360);
+    (degrees != 0).then_some(degrees)
+}
+
+/// [`FfprobeStream::rotation`] for a raw `-show_streams` JSON stream object.
+pub fn stream_rotation(stream: &serde_json::Value) -> Option<i32> {
+    serde_json::from_value::<FfprobeStream>(stream.clone())
+        .ok()?
+        .rotation()
+}
+
+/// Relative difference between `r_frame_rate` and `avg_frame_rate` above which a stream is VFR.
+pub const VFR_RATE_TOLERANCE: f64 = 0.02;
+
+/// `r_frame_rate` is the lowest rate that represents every timestamp; for CFR it equals
+/// `avg_frame_rate`. Phone recordings that drop/duplicate frames report a higher `r_frame_rate`.
+pub fn frame_rates_indicate_vfr(r_frame_rate: f64, avg_frame_rate: f64) -> bool {
+    if r_frame_rate <= 0.0 || avg_frame_rate <= 0.0 {
+        return false;
+    }
+    (r_frame_rate - avg_frame_rate).abs() / r_frame_rate > VFR_RATE_TOLERANCE
+}
+
+/// Average frame rate of the first video stream when it is VFR; `None` for CFR or on probe failure.
+pub fn probe_vfr_avg_frame_rate(input: &Path) -> Option<f64> {
+    let output = Command::new("ffprobe")
+        .args([
+            "-v",
+            "error",
+            "-print_format",
+            "json",
+            "-show_entries",
+            "stream=r_frame_rate,avg_frame_rate",
+            "-select_streams",
+            "v:0",
+            "--",
+        ])
+        .arg(crate::safe_path_arg(input).as_ref())
+        .output()
+        .map_err(|e| warn!(error = %e, input = %input.display(), "FFPROBE ERROR"))
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let parsed: FfprobeOutput = serde_json::from_slice(&output.stdout)
+        .map_err(|e| warn!(error = %e, "FFPROBE JSON PARSE ERROR"))
+        .ok()?;
+    parsed.streams.first()?.vfr_avg_frame_rate()
+}
+
+/// Color range as ffmpeg option values: "pc" for full range, "tv" for limited. ffprobe's own
+/// spellings are accepted, and the deprecated `yuvj*` pixel formats imply full range even when
+/// the stream does not tag it (typical of MJPEG and phone JPEG-derived clips). `None` when
+/// unknown, i.e. the encoder default (limited) applies.
+pub fn normalize_color_range(
+    color_range: Option<&str>,
+    pix_fmt: Option<&str>,
+) -> Option<&'static str> {
+    match color_range.map(str::to_ascii_lowercase).as_deref() {
+        Some("pc" | "jpeg" | "full") => Some("pc"),
+        Some("tv" | "mpeg" | "limited") => Some("tv"),
+        _ if pix_fmt.is_some_and(|f| f.starts_with("yuvj")) => Some("pc"),
+        _ => None,
+    }
+}
+
 pub fn extract_color_info(input: &Path) -> ColorInfo {
+    crate::file_probe_cache::cached_color_info(input, || extract_color_info_uncached(input))
+}
+
+fn extract_color_info_uncached(input: &Path) -> ColorInfo {
     let input_str = input.to_string_lossy();
 
     let output = match Command::new("ffprobe")
@@ -359,6 +483,11 @@ pub fn extract_color_info(input: &Path) -> ColorInfo {
         color_space,
         color_transfer,
         color_primaries,
+        color_range: normalize_color_range(
+            stream.color_range.as_deref(),
+            stream.pix_fmt.as_deref(),
+        )
+        .map(str::to_string),
         pix_fmt: stream.pix_fmt.clone(),
         bit_depth,
         mastering_display,
@@ -381,6 +510,18 @@ mod tests {
         assert_eq!(parsed.streams[0].pix_fmt, Some("yuv420p".to_string()));
     }
 
+    #[test]
+    fn test_color_range_from_tag_or_jpeg_pix_fmt() {
+        let json = r#"{"streams":[{"pix_fmt":"yuvj420p"},{"pix_fmt":"yuv444p","color_range":"pc"},{"pix_fmt":"yuv420p","color_range":"tv"},{"pix_fmt":"yuv420p","color_range":"unknown"}]}"#;
+        let parsed: FfprobeOutput = serde_json::from_str(json).unwrap();
+        let ranges: Vec<_> = parsed
+            .streams
+            .iter()
+            .map(|s| normalize_color_range(s.color_range.as_deref(), s.pix_fmt.as_deref()))
+            .collect();
+        assert_eq!(ranges, [Some("pc"), Some("pc"), Some("tv"), None]);
+    }
+
     #[test]
     fn test_parse_empty_streams() {
         let json = r#"{"streams":[]}"#;
@@ -396,6 +537,53 @@ mod tests {
         assert_eq!(parsed.streams[0].pix_fmt, Some("yuv420p10le".to_string()));
     }
 
+    #[test]
+    fn test_vfr_phone_recording() {
+        // Handcrafted from an iPhone clip: container timebase allows 60 fps, real average ~29.6.
+        let json = r#"{"streams":[{"codec_type":"video","r_frame_rate":"60/1","avg_frame_rate":"53280/1799"}]}"#;
+        let parsed: FfprobeOutput = serde_json::from_str(json).unwrap();
+        let stream = &parsed.streams[0];
+        assert!(stream.is_vfr());
+        let avg = stream.vfr_avg_frame_rate().unwrap();
+        assert!((avg - 29.616).abs() < 0.01, "avg fps {}", avg);
+    }
+
+    #[test]
+    fn test_cfr_not_vfr() {
+        let json = r#"{"streams":[{"r_frame_rate":"30000/1001","avg_frame_rate":"30000/1001"}]}"#;
+        let parsed: FfprobeOutput = serde_json::from_str(json).unwrap();
+        assert!(!parsed.streams[0].is_vfr());
+
+        // Missing or zero rates are never treated as VFR.
+        let json = r#"{"streams":[{"r_frame_rate":"30/1","avg_frame_rate":"0/0"}]}"#;
+        let parsed: FfprobeOutput = serde_json::from_str(json).unwrap();
+        assert!(!parsed.streams[0].is_vfr());
+        assert!(!FfprobeStream::default().is_vfr());
+    }
+
+    #[test]
+    fn test_rotation_portrait_phone_clip() {
+        // Handcrafted from a portrait iPhone clip: stored 1920x1080, Display Matrix -90°.
+        let json = r#"{"streams":[{"codec_type":"video","width":1920,"height":1080,"side_data_list":[{"side_data_type":"Display Matrix","displaymatrix":"...","rotation":-90}]}]}"#;
+        let parsed: FfprobeOutput = serde_json::from_str(json).unwrap();
+        assert_eq!(parsed.streams[0].rotation(), Some(90));
+
+        let legacy = r#"{"streams":[{"tags":{"rotate":"270"}}]}"#;
+        let parsed: FfprobeOutput = serde_json::from_str(legacy).unwrap();
+        assert_eq!(parsed.streams[0].rotation(), Some(270));
+
+        let upside_down = serde_json::json!({"side_data_list":[{"side_data_type":"Display Matrix","rotation":180.0}]});
+        assert_eq!(stream_rotation(&upside_down), Some(180));
+        assert_eq!(FfprobeStream::default().rotation(), None);
+    }
+
+    #[test]
+    fn test_frame_rates_indicate_vfr_tolerance() {
+        assert!(!frame_rates_indicate_vfr(30.0, 29.97));
+        assert!(frame_rates_indicate_vfr(30.0, 25.0));
+        assert!(!frame_rates_indicate_vfr(0.0, 25.0));
+    }
+
     #[test]
     fn test_is_hdr_pq() {
         let ci = ColorInfo {
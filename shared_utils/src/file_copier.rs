@@ -16,7 +16,6 @@
 
 use std::path::{Path, PathBuf};
 use tracing::{debug, error, info, warn};
-use walkdir::WalkDir;
 
 pub const SUPPORTED_IMAGE_EXTENSIONS: &[&str] = &[
     "png", "jpg", "jpeg", "jpe", "jfif", "webp", "gif", "tiff", "tif", "heic", "heif", "avif",
@@ -42,6 +41,7 @@ pub const IMAGE_EXTENSIONS_ANALYZE: &[&str] = &[
     "png", "jpg", "jpeg", "jpe", "jfif", "webp", "gif", "tiff", "tif",
 ];
 
+/// Default sidecar extensions; `--sidecar-extensions` replaces them for a run.
 pub const SIDECAR_EXTENSIONS: &[&str] = &["xmp"];
 
 #[derive(Debug, Clone)]
@@ -95,7 +95,7 @@ fn should_copy_file(path: &Path) -> bool {
         return false;
     }
 
-    if SIDECAR_EXTENSIONS.contains(&ext.as_str()) {
+    if crate::xmp_merger::is_sidecar_extension(&ext) {
         return false;
     }
 
@@ -112,11 +112,7 @@ pub fn copy_unsupported_files(input_dir: &Path, output_dir: &Path, recursive: bo
         "Starting batch file copy operation"
     );
 
-    let walker = if recursive {
-        WalkDir::new(input_dir).follow_links(true)
-    } else {
-        WalkDir::new(input_dir).max_depth(1)
-    };
+    let walker = crate::batch::walk_dir(input_dir, recursive);
 
     let mut total_files = 0usize;
     for entry in walker.into_iter() {
@@ -147,11 +143,7 @@ pub fn copy_unsupported_files(input_dir: &Path, output_dir: &Path, recursive: bo
         None
     };
 
-    let walker = if recursive {
-        WalkDir::new(input_dir).follow_links(true)
-    } else {
-        WalkDir::new(input_dir).max_depth(1)
-    };
+    let walker = crate::batch::walk_dir(input_dir, recursive);
 
     for entry in walker.into_iter() {
         let entry = match entry {
@@ -232,13 +224,14 @@ pub fn copy_unsupported_files(input_dir: &Path, output_dir: &Path, recursive: bo
             Ok(_) => {
                 result.copied += 1;
 
-                crate::copy_metadata(path, &dest);
+                // Copied unchanged: the strip policy only applies to encoded outputs.
+                crate::copy_metadata(path, &dest, crate::StripPolicy::default());
 
                 let ext = path
                     .extension()
                     .and_then(|e| e.to_str())
                     .unwrap_or("unknown");
-                println!("📦 Copied unsupported file (.{}): {}", ext, path.display());
+                crate::report_println!("📦 Copied unsupported file (.{}): {}", ext, path.display());
 
                 debug!(
                     source = %path.display(),
@@ -260,7 +253,10 @@ pub fn copy_unsupported_files(input_dir: &Path, output_dir: &Path, recursive: bo
                             error = %e,
                             "XMP merge failed, trying to copy sidecar"
                         );
-                        println!("⚠️ XMP merge failed ({}), trying to copy sidecar...", e);
+                        crate::report_println!(
+                            "⚠️ XMP merge failed ({}), trying to copy sidecar...",
+                            e
+                        );
                         copy_xmp_sidecar_if_exists(path, &dest);
                     }
                 }
@@ -309,21 +305,31 @@ fn copy_xmp_sidecar_if_exists(source: &Path, dest: &Path) {
     let source_str = source.to_string_lossy();
     let dest_str = dest.to_string_lossy();
 
-    let xmp_patterns = [
-        format!("{}.xmp", source_str),
-        format!("{}.XMP", source_str),
-        source.with_extension("xmp").to_string_lossy().to_string(),
-    ];
-
-    for xmp_source in &xmp_patterns {
+    let xmp_patterns: Vec<(String, String)> = crate::xmp_merger::sidecar_extensions()
+        .into_iter()
+        .flat_map(|ext| {
+            [
+                format!("{}.{}", source_str, ext),
+                format!("{}.{}", source_str, ext.to_uppercase()),
+                source.with_extension(&ext).to_string_lossy().to_string(),
+            ]
+            .map(|pattern| (pattern, ext.clone()))
+        })
+        .collect();
+
+    for (xmp_source, ext) in &xmp_patterns {
         let xmp_path = Path::new(xmp_source);
         if xmp_path.exists() {
-            let xmp_dest = format!("{}.xmp", dest_str);
+            let xmp_dest = format!("{}.{}", dest_str, ext);
 
             match std::fs::copy(xmp_path, &xmp_dest) {
                 Ok(_) => {
-                    crate::copy_metadata(xmp_path, Path::new(&xmp_dest));
-                    println!("   📋 Copied XMP sidecar: {}", xmp_path.display());
+                    crate::copy_metadata(
+                        xmp_path,
+                        Path::new(&xmp_dest),
+                        crate::StripPolicy::default(),
+                    );
+                    crate::report_println!("   📋 Copied XMP sidecar: {}", xmp_path.display());
 
                     debug!(
                         source = %xmp_path.display(),
@@ -380,11 +386,7 @@ pub fn count_files(dir: &Path, recursive: bool) -> FileStats {
         others: 0,
     };
 
-    let walker = if recursive {
-        WalkDir::new(dir).follow_links(true)
-    } else {
-        WalkDir::new(dir).max_depth(1)
-    };
+    let walker = crate::batch::walk_dir(dir, recursive);
 
     for entry in walker.into_iter() {
         let entry = match entry {
@@ -426,7 +428,7 @@ pub fn count_files(dir: &Path, recursive: bool) -> FileStats {
             stats.images += 1;
         } else if SUPPORTED_VIDEO_EXTENSIONS.contains(&ext.as_str()) {
             stats.videos += 1;
-        } else if SIDECAR_EXTENSIONS.contains(&ext.as_str()) {
+        } else if crate::xmp_merger::is_sidecar_extension(&ext) {
             stats.sidecars += 1;
         } else {
             stats.others += 1;
@@ -449,12 +451,26 @@ pub fn verify_output_completeness(
     input_dir: &Path,
     output_dir: &Path,
     recursive: bool,
+) -> VerifyResult {
+    verify_output_completeness_excluding(input_dir, output_dir, recursive, None)
+}
+
+/// Like [`verify_output_completeness`], but `exclude` (e.g. an `--archive` written inside the
+/// output directory) is not counted as an output.
+pub fn verify_output_completeness_excluding(
+    input_dir: &Path,
+    output_dir: &Path,
+    recursive: bool,
+    exclude: Option<&Path>,
 ) -> VerifyResult {
     let input_stats = count_files(input_dir, recursive);
     let output_stats = count_files(output_dir, recursive);
 
     let expected = input_stats.expected_output();
-    let actual = output_stats.total;
+    let excluded = exclude
+        .filter(|p| is_counted_in(p, output_dir, recursive))
+        .is_some();
+    let actual = output_stats.total - usize::from(excluded);
     let diff = expected as i64 - actual as i64;
 
     let (passed, message) = if diff == 0 {
@@ -489,6 +505,24 @@ pub fn verify_output_completeness(
     }
 }
 
+/// Whether [`count_files`] on `dir` includes the file at `path`.
+fn is_counted_in(path: &Path, dir: &Path, recursive: bool) -> bool {
+    let (Ok(path), Ok(dir)) = (path.canonicalize(), dir.canonicalize()) else {
+        return false;
+    };
+    let hidden = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .is_some_and(|n| n.starts_with('.'));
+    path.is_file()
+        && !hidden
+        && if recursive {
+            path.starts_with(&dir)
+        } else {
+            path.parent() == Some(dir.as_path())
+        }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -507,4 +541,21 @@ mod tests {
 
         assert!(!should_copy_file(Path::new(".DS_Store")));
     }
+
+    #[test]
+    fn test_verify_excludes_archive_inside_output() {
+        let input = tempfile::tempdir().unwrap();
+        let output = tempfile::tempdir().unwrap();
+        std::fs::write(input.path().join("a.jpg"), b"a").unwrap();
+        std::fs::write(output.path().join("a.jxl"), b"a").unwrap();
+        let archive = output.path().join("out.tar");
+        std::fs::write(&archive, b"tar").unwrap();
+
+        let plain = verify_output_completeness(input.path(), output.path(), true);
+        assert_eq!(plain.diff, -1);
+        let verify =
+            verify_output_completeness_excluding(input.path(), output.path(), true, Some(&archive));
+        assert!(verify.passed);
+        assert_eq!(verify.diff, 0);
+    }
 }
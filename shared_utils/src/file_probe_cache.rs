@@ -0,0 +1,182 @@
+//! Per-file probe cache: while one input is converted, the ffprobe results for it (stream
+//! probe, color info, animation duration) are computed once and reused by every later step
+//! that asks again — analysis, routing, the GIF meme-score, dedup and the encoder setup.
+//!
+//! Not to be confused with [`crate::analysis_cache::AnalysisCache`], the persistent SQLite
+//! cache shared across runs. This one lives for a single file on the thread converting it, so
+//! parallel conversions never share entries, and only answers for that file: outputs and temp
+//! files are always probed fresh.
+
+use crate::ffprobe::FFprobeResult;
+use crate::ffprobe_json::ColorInfo;
+use std::cell::RefCell;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// Probe results for one input, filled in as they are first asked for.
+#[derive(Debug, Clone, Default)]
+pub struct FileProbeCache {
+    path: PathBuf,
+    /// Size and mtime when the entries were taken; a change (an in-place rewrite) drops them.
+    fingerprint: Option<(u64, SystemTime)>,
+    probe: Option<FFprobeResult>,
+    color_info: Option<ColorInfo>,
+    animation_duration: Option<Option<f32>>,
+}
+
+impl FileProbeCache {
+    pub fn new(path: &Path) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            fingerprint: fingerprint(path),
+            ..Self::default()
+        }
+    }
+
+    /// Entries are only valid for `path`, and only while the file is unchanged.
+    fn entries_for(&mut self, path: &Path) -> Option<&mut Self> {
+        if self.path != path {
+            return None;
+        }
+        let current = fingerprint(path);
+        if current != self.fingerprint {
+            *self = Self {
+                path: self.path.clone(),
+                fingerprint: current,
+                ..Self::default()
+            };
+        }
+        Some(self)
+    }
+}
+
+fn fingerprint(path: &Path) -> Option<(u64, SystemTime)> {
+    let meta = std::fs::metadata(path).ok()?;
+    Some((meta.len(), meta.modified().ok()?))
+}
+
+thread_local! {
+    static CURRENT: RefCell<Option<FileProbeCache>> = const { RefCell::new(None) };
+}
+
+/// Restores the previously installed cache on drop.
+pub struct FileProbeCacheGuard {
+    previous: Option<FileProbeCache>,
+}
+
+impl Drop for FileProbeCacheGuard {
+    fn drop(&mut self) {
+        let previous = self.previous.take();
+        CURRENT.with(|c| *c.borrow_mut() = previous);
+    }
+}
+
+/// Install `cache` for the current thread until the returned guard is dropped. Converters
+/// open one per input, right after the extension fix-up settled its final path.
+pub fn install(cache: FileProbeCache) -> FileProbeCacheGuard {
+    let previous = CURRENT.with(|c| c.borrow_mut().replace(cache));
+    FileProbeCacheGuard { previous }
+}
+
+/// The value stored in `slot` while the installed cache is for `path`.
+fn lookup<T: Clone>(path: &Path, slot: fn(&mut FileProbeCache) -> &mut Option<T>) -> Option<T> {
+    CURRENT.with(|c| {
+        c.borrow_mut()
+            .as_mut()
+            .and_then(|c| c.entries_for(path))
+            .and_then(|c| slot(c).clone())
+    })
+}
+
+/// Keep `value` in `slot`; a no-op without an installed cache for `path`.
+fn store<T: Clone>(path: &Path, slot: fn(&mut FileProbeCache) -> &mut Option<T>, value: &T) {
+    CURRENT.with(|c| {
+        if let Some(c) = c.borrow_mut().as_mut().and_then(|c| c.entries_for(path)) {
+            *slot(c) = Some(value.clone());
+        }
+    });
+}
+
+/// [`crate::probe_video`] through the cache. Failures are not kept, so a retry probes again.
+pub(crate) fn cached_probe(
+    path: &Path,
+    probe: impl FnOnce() -> Result<FFprobeResult, crate::ffprobe::FFprobeError>,
+) -> Result<FFprobeResult, crate::ffprobe::FFprobeError> {
+    if let Some(result) = lookup(path, |c| &mut c.probe) {
+        return Ok(result);
+    }
+    // The borrow is not held while probing: the probe may consult the cache itself.
+    let result = probe()?;
+    store(path, |c| &mut c.probe, &result);
+    Ok(result)
+}
+
+pub(crate) fn cached_color_info(path: &Path, extract: impl FnOnce() -> ColorInfo) -> ColorInfo {
+    if let Some(info) = lookup(path, |c| &mut c.color_info) {
+        return info;
+    }
+    let info = extract();
+    store(path, |c| &mut c.color_info, &info);
+    info
+}
+
+/// Animation duration. A `None` answer is kept too: every method was already tried.
+pub(crate) fn cached_animation_duration(
+    path: &Path,
+    duration: impl FnOnce() -> Option<f32>,
+) -> Option<f32> {
+    if let Some(duration) = lookup(path, |c| &mut c.animation_duration) {
+        return duration;
+    }
+    let duration = duration();
+    store(path, |c| &mut c.animation_duration, &duration);
+    duration
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    #[test]
+    fn test_probes_run_once_per_file() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let input = dir.path().join("anim.gif");
+        let other = dir.path().join("anim.mp4");
+        std::fs::write(&input, b"GIF89a").unwrap();
+        std::fs::write(&other, b"output").unwrap();
+
+        let runs = Cell::new(0);
+        let duration = || {
+            runs.set(runs.get() + 1);
+            None
+        };
+
+        // No cache installed: every call probes.
+        cached_animation_duration(&input, duration);
+        cached_animation_duration(&input, duration);
+        assert_eq!(runs.get(), 2);
+
+        {
+            let _guard = install(FileProbeCache::new(&input));
+            runs.set(0);
+            assert_eq!(cached_animation_duration(&input, duration), None);
+            assert_eq!(cached_animation_duration(&input, duration), None);
+            assert_eq!(runs.get(), 1);
+
+            // Other paths (outputs, temp files) are never answered from the cache.
+            cached_animation_duration(&other, duration);
+            cached_animation_duration(&other, duration);
+            assert_eq!(runs.get(), 3);
+
+            // A rewritten input is probed again.
+            std::fs::write(&input, b"GIF89a rewritten").unwrap();
+            cached_animation_duration(&input, duration);
+            assert_eq!(runs.get(), 4);
+        }
+
+        // The guard took the cache with it.
+        cached_animation_duration(&input, duration);
+        assert_eq!(runs.get(), 5);
+    }
+}
@@ -89,6 +89,90 @@ pub fn validate_flags_result_with_ultimate(
     }
 }
 
+/// Parse `--min-ssim`: a value in (0, 1].
+pub fn parse_min_ssim(value: &str) -> Result<f64, String> {
+    let ssim: f64 = value
+        .trim()
+        .parse()
+        .map_err(|_| format!("Invalid SSIM '{}' (expected a number in (0, 1])", value))?;
+    if ssim > 0.0 && ssim <= 1.0 {
+        Ok(ssim)
+    } else {
+        Err(format!("SSIM {} out of range (must be in (0, 1])", ssim))
+    }
+}
+
+/// Parse `--entropy-skip-threshold`: bits of luma entropy in (0, 8].
+pub fn parse_entropy_threshold(value: &str) -> Result<f64, String> {
+    let bits: f64 = value
+        .trim()
+        .parse()
+        .map_err(|_| format!("Invalid entropy '{}' (expected a number in (0, 8])", value))?;
+    if bits > 0.0 && bits <= 8.0 {
+        Ok(bits)
+    } else {
+        Err(format!("Entropy {} out of range (must be in (0, 8])", bits))
+    }
+}
+
+/// Parse `--min-animation-duration`: a positive number of seconds.
+pub fn parse_min_animation_duration(value: &str) -> Result<f32, String> {
+    let secs: f32 = value
+        .trim()
+        .parse()
+        .map_err(|_| format!("Invalid duration '{}' (expected seconds > 0)", value))?;
+    if secs.is_finite() && secs > 0.0 {
+        Ok(secs)
+    } else {
+        Err(format!("Duration {} out of range (must be > 0)", secs))
+    }
+}
+
+/// Parse `--crf-bias`: a CRF offset in [-10, 10] added to the matched CRF.
+pub fn parse_crf_bias(value: &str) -> Result<f64, String> {
+    let bias: f64 = value.trim().parse().map_err(|_| {
+        format!(
+            "Invalid CRF bias '{}' (expected a number in [-10, 10])",
+            value
+        )
+    })?;
+    if (-10.0..=10.0).contains(&bias) {
+        Ok(bias)
+    } else {
+        Err(format!(
+            "CRF bias {} out of range (must be in [-10, 10])",
+            bias
+        ))
+    }
+}
+
+/// Parse a byte size with an optional binary suffix (`500K`, `25M`, `1.5G`), as taken by
+/// `--max-output-size` and `--max-memory`.
+pub fn parse_byte_size(value: &str) -> Result<u64, String> {
+    let trimmed = value.trim();
+    let upper = trimmed.to_ascii_uppercase();
+    let digits = upper.trim_end_matches('B');
+    let (number, scale) = match digits.chars().last() {
+        Some('K') => (&digits[..digits.len() - 1], 1u64 << 10),
+        Some('M') => (&digits[..digits.len() - 1], 1u64 << 20),
+        Some('G') => (&digits[..digits.len() - 1], 1u64 << 30),
+        _ => (digits, 1),
+    };
+    let amount: f64 = number
+        .trim()
+        .parse()
+        .map_err(|_| format!("Invalid size '{}' (expected e.g. 500K, 25M, 1.5G)", value))?;
+    let bytes = amount * scale as f64;
+    if bytes.is_finite() && bytes >= 1.0 && bytes < u64::MAX as f64 {
+        Ok(bytes as u64)
+    } else {
+        Err(format!(
+            "Size '{}' out of range (must be at least 1 byte)",
+            trimmed
+        ))
+    }
+}
+
 pub fn print_flag_help() {
     eprintln!("📋 Flag (simplified): Only the recommended combination is supported.");
     eprintln!("   Default: explore + match-quality + compress (all on).");
@@ -168,4 +252,54 @@ mod tests {
         assert!(FlagMode::UltimateExplore.is_ultimate());
         assert!(!FlagMode::PreciseQualityWithCompress.is_ultimate());
     }
+
+    #[test]
+    fn test_parse_min_ssim_range() {
+        assert_eq!(parse_min_ssim("0.98"), Ok(0.98));
+        assert_eq!(parse_min_ssim(" 1 "), Ok(1.0));
+        assert!(parse_min_ssim("0").is_err());
+        assert!(parse_min_ssim("1.01").is_err());
+        assert!(parse_min_ssim("-0.5").is_err());
+        assert!(parse_min_ssim("high").is_err());
+    }
+
+    #[test]
+    fn test_parse_entropy_threshold_range() {
+        assert_eq!(parse_entropy_threshold("7.5"), Ok(7.5));
+        assert_eq!(parse_entropy_threshold(" 8 "), Ok(8.0));
+        assert!(parse_entropy_threshold("0").is_err());
+        assert!(parse_entropy_threshold("8.1").is_err());
+        assert!(parse_entropy_threshold("noise").is_err());
+    }
+
+    #[test]
+    fn test_parse_min_animation_duration() {
+        assert_eq!(parse_min_animation_duration("2"), Ok(2.0));
+        assert_eq!(parse_min_animation_duration(" 0.5 "), Ok(0.5));
+        assert!(parse_min_animation_duration("0").is_err());
+        assert!(parse_min_animation_duration("-3").is_err());
+        assert!(parse_min_animation_duration("inf").is_err());
+        assert!(parse_min_animation_duration("long").is_err());
+    }
+
+    #[test]
+    fn test_parse_crf_bias() {
+        assert_eq!(parse_crf_bias("-2.0"), Ok(-2.0));
+        assert_eq!(parse_crf_bias(" 1.5 "), Ok(1.5));
+        assert!(parse_crf_bias("11").is_err());
+        assert!(parse_crf_bias("NaN").is_err());
+        assert!(parse_crf_bias("lower").is_err());
+    }
+
+    #[test]
+    fn test_parse_byte_size_suffixes() {
+        assert_eq!(parse_byte_size("25M"), Ok(25 * 1024 * 1024));
+        assert_eq!(parse_byte_size("500k"), Ok(500 * 1024));
+        assert_eq!(parse_byte_size(" 1.5GB "), Ok(3 * 512 * 1024 * 1024));
+        assert_eq!(parse_byte_size("4096"), Ok(4096));
+        assert!(parse_byte_size("0").is_err());
+        assert!(parse_byte_size("-2M").is_err());
+        assert!(parse_byte_size("M").is_err());
+        assert!(parse_byte_size("big").is_err());
+    }
 }
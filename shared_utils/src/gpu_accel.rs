@@ -433,6 +433,17 @@ impl GpuAccel {
         }
     }
 
+    /// [`Self::detect`], or no accelerator for a `--deterministic` conversion: hardware
+    /// encoders give no reproducibility guarantee.
+    pub fn detect_for(deterministic: bool) -> &'static GpuAccel {
+        static DISABLED: OnceLock<GpuAccel> = OnceLock::new();
+        if deterministic {
+            DISABLED.get_or_init(GpuAccel::default)
+        } else {
+            Self::detect()
+        }
+    }
+
     fn detect_internal() -> GpuAccel {
         let encoders = get_available_encoders();
 
@@ -915,6 +926,7 @@ pub fn calculate_smart_sample(
         .arg("-hide_banner")
         .arg("-t")
         .arg("10")
+        .args(crate::video::source_trim_args(input))
         .arg("-i")
         .arg(crate::safe_path_arg(input).as_ref())
         .arg("-vf")
@@ -1067,7 +1079,39 @@ pub fn estimate_cpu_search_range(
 }
 
 pub fn estimate_cpu_search_center(gpu_boundary: f32, gpu_type: GpuType, codec: &str) -> f32 {
-    estimate_cpu_search_center_dynamic(gpu_boundary, gpu_type, codec, None)
+    estimate_cpu_search_center_with_confidence(gpu_boundary, gpu_type, codec, None)
+}
+
+/// Like `estimate_cpu_search_center`, but pulls the center toward the quality matcher's
+/// predicted CRF in proportion to its confidence (`prediction = (crf, confidence)`, see
+/// `quality_matcher::predict_crf_with_confidence`). At most half the weight goes to the
+/// prediction so the GPU measurement always dominates.
+pub fn estimate_cpu_search_center_with_confidence(
+    gpu_boundary: f32,
+    gpu_type: GpuType,
+    codec: &str,
+    prediction: Option<(f64, f32)>,
+) -> f32 {
+    let gpu_center = estimate_cpu_search_center_dynamic(gpu_boundary, gpu_type, codec, None);
+    blend_with_prediction(gpu_center, prediction)
+}
+
+/// Pull an already mapped CPU `center` toward the predicted CRF, weighted by half its confidence.
+pub fn blend_with_prediction(center: f32, prediction: Option<(f64, f32)>) -> f32 {
+    match prediction {
+        Some((predicted_crf, confidence)) if predicted_crf.is_finite() => {
+            let weight = confidence.clamp(0.0, 1.0) * 0.5;
+            center * (1.0 - weight) + predicted_crf as f32 * weight
+        }
+        _ => center,
+    }
+}
+
+/// Half-width of a CPU search window around the center: 3.0 CRF, shrinking linearly to 1.5 as
+/// the prediction's confidence rises to 1.
+pub fn confidence_search_margin(prediction: Option<(f64, f32)>) -> f32 {
+    let confidence = prediction.map(|(_, c)| c.clamp(0.0, 1.0)).unwrap_or(0.0);
+    3.0 - 1.5 * confidence
 }
 
 pub fn gpu_boundary_to_cpu_range(
@@ -1077,10 +1121,32 @@ pub fn gpu_boundary_to_cpu_range(
     min_crf: f32,
     max_crf: f32,
 ) -> (f32, f32) {
-    let cpu_center = estimate_cpu_search_center(gpu_boundary, gpu_type, codec);
+    gpu_boundary_to_cpu_range_with_confidence(gpu_boundary, gpu_type, codec, min_crf, max_crf, None)
+}
 
-    let cpu_low = gpu_boundary.max(min_crf);
-    let cpu_high = (cpu_center + 3.0).min(max_crf);
+/// CPU search bounds derived from the GPU boundary, narrowed by a confident CRF prediction.
+///
+/// Without a prediction the range is `[gpu_boundary, center + 3.0]`. With one, the margin above
+/// the center shrinks linearly from 3.0 (confidence 0) to 1.5 (confidence 1), and the lower bound
+/// is raised to `center - margin` when that is above the GPU boundary.
+pub fn gpu_boundary_to_cpu_range_with_confidence(
+    gpu_boundary: f32,
+    gpu_type: GpuType,
+    codec: &str,
+    min_crf: f32,
+    max_crf: f32,
+    prediction: Option<(f64, f32)>,
+) -> (f32, f32) {
+    let cpu_center =
+        estimate_cpu_search_center_with_confidence(gpu_boundary, gpu_type, codec, prediction);
+    let margin = confidence_search_margin(prediction);
+
+    let cpu_low = if prediction.is_some() {
+        gpu_boundary.max(cpu_center - margin).max(min_crf)
+    } else {
+        gpu_boundary.max(min_crf)
+    };
+    let cpu_high = (cpu_center + margin).min(max_crf).max(cpu_low);
 
     (cpu_low, cpu_high)
 }
@@ -1095,6 +1161,11 @@ pub struct GpuCoarseResult {
     pub gpu_boundary_crf: f32,
     pub gpu_best_size: Option<u64>,
     pub gpu_best_ssim: Option<f64>,
+    /// `gpu_best_ssim` (and `quality_ceiling_ssim`) was inferred from PSNR because the SSIM
+    /// measurement failed; it is an estimate, not a measurement.
+    pub gpu_ssim_estimated: bool,
+    /// PSNR of the final validation encode at the boundary CRF.
+    pub gpu_best_psnr: Option<f64>,
     pub gpu_type: GpuType,
     pub codec: String,
     pub iterations: u32,
@@ -1228,6 +1299,7 @@ impl Default for GpuCoarseConfig {
 
 fn calculate_psnr_fast(input: &str, output: &str) -> Result<f64, String> {
     let psnr_output = Command::new("ffmpeg")
+        .args(crate::video::source_trim_args(std::path::Path::new(input)))
         .arg("-i")
         .arg(crate::safe_path_arg(std::path::Path::new(input)).as_ref())
         .arg("-i")
@@ -1346,10 +1418,15 @@ struct PsnrSsimMapper {
 }
 
 impl PsnrSsimMapper {
+    /// Seeded with the user calibration (`--psnr-ssim-calibration`) when one is installed;
+    /// points measured during this search are added on top.
     fn new() -> Self {
+        let calibration_points: Vec<(f64, f64)> = crate::ssim_mapping::global_calibration()
+            .map(|m| m.get_points().iter().map(|p| (p.psnr, p.ssim)).collect())
+            .unwrap_or_default();
         Self {
-            calibration_points: Vec::new(),
-            calibrated: false,
+            calibrated: calibration_points.len() >= 2,
+            calibration_points,
         }
     }
 
@@ -1535,6 +1612,8 @@ fn gpu_coarse_search_with_log_impl(
             gpu_boundary_crf: config.initial_crf,
             gpu_best_size: None,
             gpu_best_ssim: None,
+            gpu_ssim_estimated: false,
+            gpu_best_psnr: None,
             gpu_type: GpuType::None,
             codec: encoder.to_string(),
             iterations: 0,
@@ -1569,6 +1648,8 @@ fn gpu_coarse_search_with_log_impl(
                 gpu_boundary_crf: config.initial_crf,
                 gpu_best_size: None,
                 gpu_best_ssim: None,
+                gpu_ssim_estimated: false,
+                gpu_best_psnr: None,
                 gpu_type: gpu.gpu_type,
                 codec: encoder.to_string(),
                 iterations: 0,
@@ -1610,7 +1691,13 @@ fn gpu_coarse_search_with_log_impl(
 
         duration_output
             .ok()
-            .and_then(|o| String::from_utf8_lossy(&o.stdout).trim().parse().ok())
+            .and_then(|o| {
+                String::from_utf8_lossy(&o.stdout)
+                    .trim()
+                    .parse::<f32>()
+                    .ok()
+            })
+            .map(|d| d * crate::video::trimmed_share(input) as f32)
             .unwrap_or(GPU_SAMPLE_DURATION)
     };
 
@@ -1635,6 +1722,8 @@ fn gpu_coarse_search_with_log_impl(
             gpu_boundary_crf: config.initial_crf,
             gpu_best_size: None,
             gpu_best_ssim: None,
+            gpu_ssim_estimated: false,
+            gpu_best_psnr: None,
             gpu_type: gpu.gpu_type,
             codec: encoder.to_string(),
             iterations: 0,
@@ -1731,6 +1820,7 @@ fn gpu_coarse_search_with_log_impl(
         cmd.arg("-y")
             .arg("-t")
             .arg(format!("{}", warmup_duration))
+            .args(crate::video::source_trim_args(input))
             .arg("-i")
             .arg(crate::safe_path_arg(input).as_ref())
             .arg("-c:v")
@@ -1787,6 +1877,8 @@ fn gpu_coarse_search_with_log_impl(
             gpu_boundary_crf: config.max_crf,
             gpu_best_size: warmup_result.ok(),
             gpu_best_ssim: None,
+            gpu_ssim_estimated: false,
+            gpu_best_psnr: None,
             gpu_type: gpu.gpu_type,
             codec: encoder.to_string(),
             iterations: 1,
@@ -1836,6 +1928,7 @@ fn gpu_coarse_search_with_log_impl(
             cmd.arg("-t").arg(format!("{}", actual_sample_duration));
         }
 
+        cmd.args(crate::video::source_trim_args(input));
         cmd.arg("-i")
             .arg(crate::safe_path_arg(input).as_ref())
             .arg("-c:v")
@@ -2051,6 +2144,7 @@ fn gpu_coarse_search_with_log_impl(
                     cmd.arg("-y")
                         .arg("-t")
                         .arg(format!("{}", sample_dur))
+                        .args(crate::video::source_trim_args(&input_path))
                         .arg("-i")
                         .arg(crate::safe_path_arg(&input_path).as_ref())
                         .arg("-c:v")
@@ -2682,6 +2776,7 @@ fn gpu_coarse_search_with_log_impl(
         .map(|(crf, psnr)| (Some(crf), if psnr > 0.0 { Some(psnr) } else { None }))
         .unwrap_or((None, None));
 
+    let mut gpu_ssim_estimated = false;
     let (gpu_ssim, gpu_psnr) = if found {
         log_msg!(
             "   📍 Final quality validation at CRF {:.1}",
@@ -2690,6 +2785,7 @@ fn gpu_coarse_search_with_log_impl(
         match encode_gpu(last_tested_crf) {
             Ok(_) => {
                 let ssim_output = Command::new("ffmpeg")
+                    .args(crate::video::source_trim_args(input))
                     .arg("-i")
                     .arg(crate::safe_path_arg(input).as_ref())
                     .arg("-i")
@@ -2753,6 +2849,23 @@ fn gpu_coarse_search_with_log_impl(
                     );
                 }
 
+                // SSIM measurement failed: hand the CPU phase a calibrated estimate instead.
+                let ssim = if ssim.is_none() {
+                    let estimate = psnr
+                        .and_then(|p| psnr_ssim_mapper.predict_ssim_from_psnr(p))
+                        .map(|s| s.clamp(0.0, 0.9999));
+                    if let Some(estimate) = estimate {
+                        log_msg!(
+                            "      📊 GPU SSIM estimated from PSNR (calibrated): {:.6}",
+                            estimate
+                        );
+                        gpu_ssim_estimated = true;
+                    }
+                    estimate
+                } else {
+                    ssim
+                };
+
                 (ssim, psnr)
             }
             Err(_) => (None, None),
@@ -2791,7 +2904,16 @@ fn gpu_coarse_search_with_log_impl(
             } else {
                 "🟠 Below expected"
             };
-            log_msg!("   📊 GPU Best SSIM: {:.6} {}", ssim, quality_hint);
+            log_msg!(
+                "   📊 GPU Best SSIM: {:.6}{} {}",
+                ssim,
+                if gpu_ssim_estimated {
+                    " (estimated from PSNR)"
+                } else {
+                    ""
+                },
+                quality_hint
+            );
         }
         if let Some(psnr) = gpu_psnr {
             log_msg!("   📊 GPU Best PSNR: {:.2}dB", psnr);
@@ -2838,6 +2960,8 @@ fn gpu_coarse_search_with_log_impl(
         gpu_boundary_crf,
         gpu_best_size: best_size,
         gpu_best_ssim: gpu_ssim,
+        gpu_ssim_estimated,
+        gpu_best_psnr: gpu_psnr,
         gpu_type: gpu.gpu_type,
         codec: encoder.to_string(),
         iterations,
@@ -2919,6 +3043,56 @@ mod tests {
         assert!((low - 12.0).abs() < 0.1, "low should be GPU boundary");
     }
 
+    #[test]
+    fn test_gpu_boundary_to_cpu_range_with_confidence_narrows() {
+        let (base_low, base_high) =
+            gpu_boundary_to_cpu_range(10.0, GpuType::Apple, "hevc", 8.0, 28.0);
+        let (low, high) = gpu_boundary_to_cpu_range_with_confidence(
+            10.0,
+            GpuType::Apple,
+            "hevc",
+            8.0,
+            28.0,
+            Some((15.0, 1.0)),
+        );
+        assert!(
+            low >= base_low,
+            "low={} should not drop below {}",
+            low,
+            base_low
+        );
+        assert!(high - low < base_high - base_low, "range should narrow");
+
+        let (low0, high0) = gpu_boundary_to_cpu_range_with_confidence(
+            10.0,
+            GpuType::Apple,
+            "hevc",
+            8.0,
+            28.0,
+            Some((15.0, 0.0)),
+        );
+        assert!((high0 - base_high).abs() < 0.01);
+        assert!(low0 >= base_low);
+    }
+
+    #[test]
+    fn test_estimate_cpu_search_center_with_confidence_blends() {
+        let center = estimate_cpu_search_center_with_confidence(
+            10.0,
+            GpuType::Apple,
+            "hevc",
+            Some((21.0, 1.0)),
+        );
+        assert!(
+            (center - 18.0).abs() < 0.1,
+            "Expected ~18.0, got {}",
+            center
+        );
+
+        let center = estimate_cpu_search_center_with_confidence(10.0, GpuType::Apple, "hevc", None);
+        assert!((center - 15.0).abs() < 0.1);
+    }
+
     #[test]
     fn test_videotoolbox_crf_mapping_crf_0() {
         let encoder = GpuEncoder {
@@ -51,7 +51,7 @@ pub fn decode_hdr_image_to_png16(
     // Create temporary PNG file
     let temp_png = tempfile::Builder::new()
         .suffix(".png")
-        .tempfile()
+        .tempfile_in(crate::conversion::scratch_dir())
         .map_err(ImgQualityError::IoError)?;
     let temp_path = temp_png.path().to_path_buf();
 
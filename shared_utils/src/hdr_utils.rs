@@ -92,9 +92,21 @@ pub fn color_info_to_ffmpeg_args(info: &ColorInfo) -> Vec<String> {
         args.push(primaries.clone());
     }
 
+    args.extend(color_range_args(info.color_range.as_deref()));
+
     args
 }
 
+/// `-color_range` for an encode, carrying the source's range ("pc" or "tv") through. Without
+/// it ffmpeg converts a full-range (e.g. `yuvj420p`) source to limited range or, worse, keeps
+/// the full-range samples but tags them limited, which plays back washed out.
+pub fn color_range_args(color_range: Option<&str>) -> Vec<String> {
+    match color_range {
+        Some(range @ ("pc" | "tv")) => vec!["-color_range".to_string(), range.to_string()],
+        _ => Vec::new(),
+    }
+}
+
 /// Generate x265 HDR parameters for video encoding.
 /// Returns a string suitable for x265 --hdr or --hdr10 options.
 pub fn color_info_to_x265_hdr_params(info: &ColorInfo) -> Option<String> {
@@ -328,6 +340,16 @@ mod tests {
                 "bt2020"
             ]
         );
+
+        let full_range = ColorInfo {
+            color_range: Some("pc".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            color_info_to_ffmpeg_args(&full_range),
+            ["-color_range", "pc"]
+        );
+        assert!(color_range_args(Some("unknown")).is_empty());
     }
 
     #[test]
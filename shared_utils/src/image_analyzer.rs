@@ -40,6 +40,219 @@ pub struct ImageFeatures {
     pub compression_ratio: f64,
 }
 
+/// Default `--entropy-skip-threshold`: luma histogram entropy in bits (8.0 = flat histogram).
+pub const DEFAULT_ENTROPY_SKIP_THRESHOLD: f64 = 7.5;
+
+/// File size as a fraction of the raw pixel data above which a PNG counts as incompressible.
+pub const INCOMPRESSIBLE_SIZE_RATIO: f64 = 0.85;
+
+impl ImageFeatures {
+    /// Near-flat histogram and a lossless coder that already failed to shrink the pixels:
+    /// lossless JXL is unlikely to do better. Unmeasured features (all zero) never match.
+    pub fn is_near_incompressible(&self, entropy_threshold: f64) -> bool {
+        self.entropy >= entropy_threshold && self.compression_ratio >= INCOMPRESSIBLE_SIZE_RATIO
+    }
+}
+
+/// Gamut described by an embedded ICC profile. Pixels of a wide-gamut image are clipped or
+/// desaturated when the profile is lost and they are read as sRGB.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ColorGamut {
+    Srgb,
+    DisplayP3,
+    AdobeRgb,
+    Rec2020,
+    ProPhoto,
+    Other,
+}
+
+impl ColorGamut {
+    pub fn is_wide(self) -> bool {
+        matches!(
+            self,
+            ColorGamut::DisplayP3
+                | ColorGamut::AdobeRgb
+                | ColorGamut::Rec2020
+                | ColorGamut::ProPhoto
+        )
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ColorGamut::Srgb => "sRGB",
+            ColorGamut::DisplayP3 => "Display P3",
+            ColorGamut::AdobeRgb => "Adobe RGB",
+            ColorGamut::Rec2020 => "Rec.2020",
+            ColorGamut::ProPhoto => "ProPhoto RGB",
+            ColorGamut::Other => "other",
+        }
+    }
+
+    /// From a profile description such as "Display P3" or "Adobe RGB (1998)".
+    pub fn from_description(description: &str) -> Option<Self> {
+        let d = description.to_ascii_lowercase();
+        if d.contains("p3") {
+            Some(ColorGamut::DisplayP3)
+        } else if d.contains("adobe rgb") || d.contains("adobergb") {
+            Some(ColorGamut::AdobeRgb)
+        } else if d.contains("2020") || d.contains("2100") {
+            Some(ColorGamut::Rec2020)
+        } else if d.contains("prophoto") || d.contains("romm") {
+            Some(ColorGamut::ProPhoto)
+        } else if d.contains("srgb") || d.contains("61966") {
+            Some(ColorGamut::Srgb)
+        } else {
+            None
+        }
+    }
+
+    /// From the X of the D50-adapted red colorant (`rXYZ`), for profiles whose description
+    /// names no known space.
+    fn from_red_colorant(x: f64) -> Self {
+        const RED_X: [(f64, ColorGamut); 5] = [
+            (0.436, ColorGamut::Srgb),
+            (0.515, ColorGamut::DisplayP3),
+            (0.610, ColorGamut::AdobeRgb),
+            (0.674, ColorGamut::Rec2020),
+            (0.797, ColorGamut::ProPhoto),
+        ];
+        RED_X
+            .iter()
+            .find(|(red_x, _)| (x - red_x).abs() < 0.015)
+            .map_or(ColorGamut::Other, |(_, gamut)| *gamut)
+    }
+}
+
+/// Embedded ICC profile of an image.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IccProfileInfo {
+    /// Profile description (`desc` tag); empty when the profile has none.
+    pub description: String,
+    pub gamut: ColorGamut,
+    pub size_bytes: usize,
+}
+
+impl IccProfileInfo {
+    /// Parse an ICC profile; `None` unless it has a valid header (`acsp` signature).
+    pub fn from_bytes(icc: &[u8]) -> Option<Self> {
+        if icc.len() < 132 || &icc[36..40] != b"acsp" {
+            return None;
+        }
+        let description = icc_description(icc).unwrap_or_default();
+        let gamut = ColorGamut::from_description(&description)
+            .or_else(|| icc_red_colorant_x(icc).map(ColorGamut::from_red_colorant))
+            .unwrap_or(ColorGamut::Other);
+        Some(Self {
+            description,
+            gamut,
+            size_bytes: icc.len(),
+        })
+    }
+}
+
+fn icc_u32(icc: &[u8], at: usize) -> Option<u32> {
+    icc.get(at..at + 4)
+        .map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+/// Data of the tag `signature` from the profile's tag table.
+fn icc_tag<'a>(icc: &'a [u8], signature: &[u8; 4]) -> Option<&'a [u8]> {
+    let count = icc_u32(icc, 128)? as usize;
+    (0..count.min(256)).find_map(|i| {
+        let entry = 132 + i * 12;
+        if icc.get(entry..entry + 4)? != signature {
+            return None;
+        }
+        let offset = icc_u32(icc, entry + 4)? as usize;
+        let size = icc_u32(icc, entry + 8)? as usize;
+        icc.get(offset..offset.checked_add(size)?)
+    })
+}
+
+/// `desc` tag text: ICC v2 `desc` (ASCII) or v4 `mluc` (first record, UTF-16BE).
+fn icc_description(icc: &[u8]) -> Option<String> {
+    let tag = icc_tag(icc, b"desc")?;
+    let text = match tag.get(0..4)? {
+        b"desc" => {
+            let len = icc_u32(tag, 8)? as usize;
+            String::from_utf8_lossy(tag.get(12..12 + len)?).into_owned()
+        }
+        b"mluc" => {
+            let len = icc_u32(tag, 20)? as usize;
+            let offset = icc_u32(tag, 24)? as usize;
+            let units: Vec<u16> = tag
+                .get(offset..offset + len)?
+                .chunks_exact(2)
+                .map(|c| u16::from_be_bytes([c[0], c[1]]))
+                .collect();
+            String::from_utf16_lossy(&units)
+        }
+        _ => return None,
+    };
+    Some(text.trim_end_matches('\0').trim().to_string())
+}
+
+fn icc_red_colorant_x(icc: &[u8]) -> Option<f64> {
+    let tag = icc_tag(icc, b"rXYZ")?;
+    if tag.get(0..4)? != b"XYZ " {
+        return None;
+    }
+    Some(icc_u32(tag, 8)? as i32 as f64 / 65536.0)
+}
+
+/// Embedded ICC profile bytes as the image decoder reads them (PNG `iCCP`, JPEG `APP2`, WebP
+/// `ICCP`, TIFF). Only headers are read; no pixels are decoded.
+pub fn read_icc_profile(path: &Path) -> Option<Vec<u8>> {
+    use image::ImageDecoder;
+    let mut decoder = image::ImageReader::open(path)
+        .ok()?
+        .with_guessed_format()
+        .ok()?
+        .into_decoder()
+        .ok()?;
+    decoder
+        .icc_profile()
+        .ok()
+        .flatten()
+        .filter(|icc| !icc.is_empty())
+}
+
+/// Pixel layout of `path` as the image decoder reports it. Only headers are read.
+pub fn read_color_type(path: &Path) -> Option<image::ColorType> {
+    use image::ImageDecoder;
+    let decoder = image::ImageReader::open(path)
+        .ok()?
+        .with_guessed_format()
+        .ok()?
+        .into_decoder()
+        .ok()?;
+    Some(decoder.color_type())
+}
+
+/// Bits per channel of `path` (8, 16, or 32 for float); sources stored in 16-bit containers
+/// (12-bit medical/scientific TIFFs) report 16. Only headers are read.
+pub fn read_bit_depth(path: &Path) -> Option<u8> {
+    let color = read_color_type(path)?;
+    Some((color.bits_per_pixel() / u16::from(color.channel_count())) as u8)
+}
+
+/// Bits per sample of a JXL file, from `jxlinfo`; `None` when jxlinfo is missing or fails.
+pub fn read_jxl_bit_depth(path: &Path) -> Option<u8> {
+    let output = std::process::Command::new("jxlinfo")
+        .arg(crate::safe_path_arg(path).as_ref())
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    parse_jxlinfo_bit_depth(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Embedded ICC profile of `path` and the gamut it describes; `None` when there is none.
+pub fn detect_icc_profile(path: &Path) -> Option<IccProfileInfo> {
+    read_icc_profile(path).and_then(|icc| IccProfileInfo::from_bytes(&icc))
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ImageAnalysis {
     pub cache_version: u16,
@@ -83,6 +296,10 @@ pub struct ImageAnalysis {
 
     /// ⚠️ Optional: Store error message if deep analysis failed but we fell back to basic info
     pub analysis_error: Option<String>,
+
+    /// Embedded ICC profile, when the format carries one the decoder can read.
+    #[serde(default)]
+    pub icc_profile: Option<IccProfileInfo>,
 }
 
 impl Default for ImageAnalysis {
@@ -112,6 +329,7 @@ impl Default for ImageAnalysis {
             history: ProcessHistory::default(),
             perception: VisualPerception::default(),
             analysis_error: None,
+            icc_profile: None,
         }
     }
 }
@@ -402,6 +620,7 @@ fn analyze_image_internal(path: &Path) -> Result<ImageAnalysis> {
         history: crate::common_utils::get_current_history(),
         perception: Default::default(),
         analysis_error: None,
+        icc_profile: detect_icc_profile(path),
     })
 }
 
@@ -555,6 +774,7 @@ fn analyze_heic_image(path: &Path, file_size: u64) -> Result<ImageAnalysis> {
         history: crate::common_utils::get_current_history(),
         perception: Default::default(),
         analysis_error,
+        icc_profile: None,
     })
 }
 
@@ -640,6 +860,7 @@ fn analyze_jpeg_fast_path(path: &Path, file_size: u64) -> Result<ImageAnalysis>
         history: crate::common_utils::get_current_history(),
         perception: Default::default(),
         analysis_error: None,
+        icc_profile: detect_icc_profile(path),
     })
 }
 
@@ -1032,7 +1253,15 @@ pub fn get_animation_duration_for_path(path: &Path) -> Option<f32> {
     get_animation_duration(path)
 }
 
+/// Cached per file ([`crate::file_probe_cache`]): analysis and the retry in the converters
+/// would otherwise run the whole chain below twice.
 fn get_animation_duration(path: &Path) -> Option<f32> {
+    crate::file_probe_cache::cached_animation_duration(path, || {
+        get_animation_duration_uncached(path)
+    })
+}
+
+fn get_animation_duration_uncached(path: &Path) -> Option<f32> {
     // Special handling for JXL: FFmpeg's jpegxl_anim decoder is incomplete
     // Convert to temporary APNG first, then probe duration
     if path
@@ -1097,7 +1326,7 @@ fn try_jxl_via_apng(path: &Path) -> Option<f32> {
     // Create temporary APNG file
     let temp_apng = tempfile::Builder::new()
         .suffix(".apng")
-        .tempfile()
+        .tempfile_in(crate::conversion::scratch_dir())
         .map_err(|e| {
             log_eprintln!(
                 "⚠️  Failed to create temporary APNG for {}: {}",
@@ -1622,6 +1851,7 @@ fn analyze_jxl_image(path: &Path, file_size: u64) -> Result<ImageAnalysis> {
         history: crate::common_utils::get_current_history(),
         perception: Default::default(),
         analysis_error: None,
+        icc_profile: None,
     })
 }
 
@@ -1731,6 +1961,7 @@ fn analyze_avif_image(path: &Path, file_size: u64) -> Result<ImageAnalysis> {
         history: crate::common_utils::get_current_history(),
         perception: Default::default(),
         analysis_error: None,
+        icc_profile: detect_icc_profile(path),
     })
 }
 
@@ -1761,16 +1992,21 @@ fn parse_jxlinfo_output(output: &str) -> (u32, u32, bool, u8) {
             has_alpha = true;
         }
 
-        if line.contains("16-bit") {
-            color_depth = 16;
-        } else if line.contains("32-bit") {
-            color_depth = 32;
+        if let Some(depth) = parse_jxlinfo_bit_depth(line) {
+            color_depth = depth;
         }
     }
 
     (width, height, has_alpha, color_depth)
 }
 
+/// The "N-bit" figure of jxlinfo output ("JPEG XL image, 640x480, lossless, 12-bit Grayscale").
+fn parse_jxlinfo_bit_depth(output: &str) -> Option<u8> {
+    output
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .find_map(|word| word.strip_suffix("-bit")?.parse().ok())
+}
+
 fn extract_metadata(path: &Path) -> Result<HashMap<String, String>> {
     let mut metadata = HashMap::new();
 
@@ -1811,6 +2047,36 @@ fn extract_hdr_info(path: &Path) -> Option<ColorInfo> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_parse_jxlinfo_bit_depth() {
+        let info = "JPEG XL image, 640x480, lossless, 12-bit Grayscale\nColor space: Grayscale";
+        assert_eq!(parse_jxlinfo_bit_depth(info), Some(12));
+        assert_eq!(parse_jxlinfo_output(info), (640, 480, false, 12));
+        assert_eq!(
+            parse_jxlinfo_bit_depth("JPEG XL image, 64x64, (possibly) lossless, 16-bit RGB+Alpha"),
+            Some(16)
+        );
+        assert_eq!(parse_jxlinfo_bit_depth("Color space: RGB"), None);
+    }
+
+    #[test]
+    fn test_near_incompressible_needs_entropy_and_ratio() {
+        let noise = ImageFeatures {
+            entropy: 7.9,
+            compression_ratio: 0.97,
+        };
+        assert!(noise.is_near_incompressible(DEFAULT_ENTROPY_SKIP_THRESHOLD));
+        assert!(!noise.is_near_incompressible(7.95));
+
+        // Busy histogram, but PNG still compresses it well.
+        let photo = ImageFeatures {
+            entropy: 7.8,
+            compression_ratio: 0.45,
+        };
+        assert!(!photo.is_near_incompressible(DEFAULT_ENTROPY_SKIP_THRESHOLD));
+        assert!(!ImageFeatures::default().is_near_incompressible(DEFAULT_ENTROPY_SKIP_THRESHOLD));
+    }
+
     #[test]
     fn test_psnr_estimation() {
         let psnr_high = estimate_psnr_from_quality(95);
@@ -1844,4 +2110,27 @@ mod tests {
         assert!(psnr_max.is_finite());
         assert!(psnr_min.is_finite());
     }
+
+    #[test]
+    fn test_color_gamut_from_profile_description_and_primaries() {
+        assert_eq!(
+            ColorGamut::from_description("Display P3"),
+            Some(ColorGamut::DisplayP3)
+        );
+        assert_eq!(
+            ColorGamut::from_description("Adobe RGB (1998)"),
+            Some(ColorGamut::AdobeRgb)
+        );
+        assert_eq!(
+            ColorGamut::from_description("sRGB IEC61966-2.1"),
+            Some(ColorGamut::Srgb)
+        );
+        assert_eq!(ColorGamut::from_description("RGB_D65_DCI_Rel_SRG"), None);
+        assert_eq!(ColorGamut::from_red_colorant(0.5151), ColorGamut::DisplayP3);
+        assert_eq!(ColorGamut::from_red_colorant(0.4361), ColorGamut::Srgb);
+        assert_eq!(ColorGamut::from_red_colorant(0.3), ColorGamut::Other);
+        assert!(ColorGamut::AdobeRgb.is_wide());
+        assert!(!ColorGamut::Srgb.is_wide());
+        assert!(IccProfileInfo::from_bytes(&[0u8; 200]).is_none());
+    }
 }
@@ -667,7 +667,10 @@ fn is_jxl_animated_via_ffprobe(path: &Path) -> bool {
     }
 
     // Create temporary APNG file
-    let temp_apng = match tempfile::Builder::new().suffix(".apng").tempfile() {
+    let temp_apng = match tempfile::Builder::new()
+        .suffix(".apng")
+        .tempfile_in(crate::conversion::scratch_dir())
+    {
         Ok(f) => f,
         Err(_) => return false,
     };
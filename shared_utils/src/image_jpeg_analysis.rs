@@ -9,6 +9,8 @@
 
 use serde::{Deserialize, Serialize};
 
+use crate::video_quality_detector::ChromaSubsampling;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct JpegQualityAnalysis {
     pub estimated_quality: u8,
@@ -21,6 +23,56 @@ pub struct JpegQualityAnalysis {
     pub quality_description: String,
     pub is_high_quality_original: bool,
     pub encoder_hint: Option<String>,
+    /// Chroma layout from the SOF header; `Unknown` for grayscale and CMYK.
+    #[serde(default)]
+    pub chroma_subsampling: ChromaSubsampling,
+    /// EXIF Orientation (1–8) from the APP1 segment; `None` when absent or out of range.
+    #[serde(default)]
+    pub exif_orientation: Option<u8>,
+}
+
+/// How JPEG inputs are turned into JXL.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum JpegStrategy {
+    /// Always bit-exact lossless transcode (reversible back to the original JPEG).
+    Lossless,
+    /// Always lossy JXL at a distance matched to the source quality.
+    Matched,
+    /// Lossless for 4:4:4 / high-quality sources, matched for low-quality 4:2:0.
+    #[default]
+    Auto,
+}
+
+/// In `auto` mode, 4:2:0 JPEGs below this estimated quality take the matched route:
+/// their chroma is already halved and the DCT blocks are coarse, so a lossless
+/// transcode keeps artifacts that cost more bits than a matched re-encode.
+pub const JPEG_MATCHED_QUALITY_CEILING: u8 = 85;
+
+pub fn parse_jpeg_strategy(value: &str) -> Result<JpegStrategy, String> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "lossless" => Ok(JpegStrategy::Lossless),
+        "matched" => Ok(JpegStrategy::Matched),
+        "auto" => Ok(JpegStrategy::Auto),
+        other => Err(format!(
+            "Invalid JPEG strategy '{}' (expected lossless, matched or auto)",
+            other
+        )),
+    }
+}
+
+impl JpegStrategy {
+    /// Whether a JPEG with this analysis should use the lossless transcode.
+    /// Without an analysis `auto` stays lossless, which is always safe.
+    pub fn prefers_lossless(&self, analysis: Option<&JpegQualityAnalysis>) -> bool {
+        match self {
+            JpegStrategy::Lossless => true,
+            JpegStrategy::Matched => false,
+            JpegStrategy::Auto => analysis.is_none_or(|a| {
+                a.chroma_subsampling != ChromaSubsampling::Yuv420
+                    || a.estimated_quality >= JPEG_MATCHED_QUALITY_CEILING
+            }),
+        }
+    }
 }
 
 const IJG_LUMINANCE_BASE: [[u16; 8]; 8] = [
@@ -266,6 +318,186 @@ const MARKER_SOI: u8 = 0xD8;
 const MARKER_DQT: u8 = 0xDB;
 const MARKER_SOS: u8 = 0xDA;
 const MARKER_EOI: u8 = 0xD9;
+const MARKER_APP1: u8 = 0xE1;
+const EXIF_TAG_ORIENTATION: u16 = 0x0112;
+
+/// SOF0–SOF15, excluding DHT (C4), JPG (C8) and DAC (CC) which share the range.
+fn is_sof_marker(marker: u8) -> bool {
+    (0xC0..=0xCF).contains(&marker) && !matches!(marker, 0xC4 | 0xC8 | 0xCC)
+}
+
+/// Reads component sampling factors from the first SOF segment.
+///
+/// Chroma is classified by how many luma samples share one chroma sample: 1 → 4:4:4,
+/// 2 → 4:2:2 (or 4:4:0), 4 with vertical subsampling → 4:2:0. Horizontal-only 4:1:1
+/// counts as 4:2:2, like `ChromaSubsampling::from_pix_fmt`. Grayscale and CMYK are `Unknown`.
+pub fn extract_chroma_subsampling(data: &[u8]) -> ChromaSubsampling {
+    if data.len() < 2 || data[0] != 0xFF || data[1] != MARKER_SOI {
+        return ChromaSubsampling::Unknown;
+    }
+    let mut pos = 2;
+
+    while pos + 1 < data.len() {
+        if data[pos] != 0xFF {
+            pos += 1;
+            continue;
+        }
+        while pos < data.len() && data[pos] == 0xFF {
+            pos += 1;
+        }
+        if pos >= data.len() {
+            break;
+        }
+
+        let marker = data[pos];
+        pos += 1;
+
+        if marker == MARKER_SOI || marker == MARKER_EOI || (0xD0..=0xD7).contains(&marker) {
+            continue;
+        }
+        if marker == MARKER_SOS || pos + 2 > data.len() {
+            break;
+        }
+        let length = ((data[pos] as usize) << 8) | (data[pos + 1] as usize);
+
+        if is_sof_marker(marker) {
+            // length(2) precision(1) height(2) width(2) count(1), then id/HV/Tq per component
+            let components = match data.get(pos + 7) {
+                Some(&n) => n as usize,
+                None => return ChromaSubsampling::Unknown,
+            };
+            if components != 3 || pos + 8 + components * 3 > data.len() {
+                return ChromaSubsampling::Unknown;
+            }
+            let factors = |i: usize| {
+                let hv = data[pos + 8 + i * 3 + 1];
+                ((hv >> 4) as u32, (hv & 0x0F) as u32)
+            };
+            let (luma_h, luma_v) = factors(0);
+            let (cb_h, cb_v) = factors(1);
+            let (cr_h, cr_v) = factors(2);
+            if cb_h * cb_v == 0 || (cb_h, cb_v) != (cr_h, cr_v) {
+                return ChromaSubsampling::Unknown;
+            }
+            return match (luma_h * luma_v) / (cb_h * cb_v) {
+                1 => ChromaSubsampling::Yuv444,
+                2 => ChromaSubsampling::Yuv422,
+                n if n >= 4 && luma_v > cb_v => ChromaSubsampling::Yuv420,
+                n if n >= 4 => ChromaSubsampling::Yuv422,
+                _ => ChromaSubsampling::Unknown,
+            };
+        }
+
+        pos += length;
+    }
+
+    ChromaSubsampling::Unknown
+}
+
+/// Absolute offset of the IFD0 Orientation value in the first Exif APP1 segment, and
+/// whether the TIFF block is little-endian.
+fn find_exif_orientation(data: &[u8]) -> Option<(usize, bool)> {
+    if data.len() < 2 || data[0] != 0xFF || data[1] != MARKER_SOI {
+        return None;
+    }
+    let mut pos = 2;
+
+    while pos + 4 <= data.len() {
+        if data[pos] != 0xFF {
+            return None;
+        }
+        let marker = data[pos + 1];
+        if marker == 0xFF {
+            pos += 1;
+            continue;
+        }
+        if marker == MARKER_SOS || marker == MARKER_EOI {
+            return None;
+        }
+        let length = ((data[pos + 2] as usize) << 8) | (data[pos + 3] as usize);
+        let segment = data.get(pos + 4..pos + 2 + length)?;
+        if marker == MARKER_APP1 && segment.starts_with(b"Exif\0\0") {
+            let tiff_start = pos + 4 + 6;
+            let tiff = &segment[6..];
+            let little_endian = match tiff.get(0..4)? {
+                b"II*\0" => true,
+                b"MM\0*" => false,
+                _ => return None,
+            };
+            let u16_at = |off: usize| -> Option<u16> {
+                let b = tiff.get(off..off + 2)?;
+                Some(if little_endian {
+                    u16::from_le_bytes([b[0], b[1]])
+                } else {
+                    u16::from_be_bytes([b[0], b[1]])
+                })
+            };
+            let u32_at = |off: usize| -> Option<u32> {
+                let b = tiff.get(off..off + 4)?;
+                Some(if little_endian {
+                    u32::from_le_bytes([b[0], b[1], b[2], b[3]])
+                } else {
+                    u32::from_be_bytes([b[0], b[1], b[2], b[3]])
+                })
+            };
+            let ifd0 = u32_at(4)? as usize;
+            let entries = u16_at(ifd0)? as usize;
+            for i in 0..entries {
+                let entry = ifd0 + 2 + i * 12;
+                // tag(2) type(2) count(4) value(4); Orientation is a single SHORT.
+                if u16_at(entry)? == EXIF_TAG_ORIENTATION && u16_at(entry + 2)? == 3 {
+                    tiff.get(entry + 8..entry + 10)?;
+                    return Some((tiff_start + entry + 8, little_endian));
+                }
+            }
+            return None;
+        }
+        pos += 2 + length;
+    }
+
+    None
+}
+
+/// EXIF Orientation (1–8) of a JPEG, read from IFD0 of the Exif APP1 segment.
+pub fn extract_exif_orientation(data: &[u8]) -> Option<u8> {
+    let (offset, little_endian) = find_exif_orientation(data)?;
+    let bytes = [data[offset], data[offset + 1]];
+    let value = if little_endian {
+        u16::from_le_bytes(bytes)
+    } else {
+        u16::from_be_bytes(bytes)
+    };
+    (1..=8).contains(&value).then_some(value as u8)
+}
+
+/// Rewrite the Orientation tag in place to 1 (normal). Returns false when there is no tag.
+pub fn reset_exif_orientation(data: &mut [u8]) -> bool {
+    let Some((offset, little_endian)) = find_exif_orientation(data) else {
+        return false;
+    };
+    let bytes = if little_endian {
+        1u16.to_le_bytes()
+    } else {
+        1u16.to_be_bytes()
+    };
+    data[offset..offset + 2].copy_from_slice(&bytes);
+    true
+}
+
+/// `jpegtran` transform that turns pixels stored with EXIF `orientation` upright.
+/// `None` for orientation 1 (nothing to do) and invalid values.
+pub fn jpegtran_orientation_args(orientation: u8) -> Option<&'static [&'static str]> {
+    match orientation {
+        2 => Some(&["-flip", "horizontal"]),
+        3 => Some(&["-rotate", "180"]),
+        4 => Some(&["-flip", "vertical"]),
+        5 => Some(&["-transpose"]),
+        6 => Some(&["-rotate", "90"]),
+        7 => Some(&["-transverse"]),
+        8 => Some(&["-rotate", "270"]),
+        _ => None,
+    }
+}
 
 pub fn extract_quantization_tables(data: &[u8]) -> Result<Vec<[[u16; 8]; 8]>, String> {
     let mut tables = Vec::new();
@@ -427,6 +659,8 @@ pub fn analyze_jpeg_quality(data: &[u8]) -> Result<JpegQualityAnalysis, String>
         quality_description,
         is_high_quality_original,
         encoder_hint,
+        chroma_subsampling: extract_chroma_subsampling(data),
+        exif_orientation: extract_exif_orientation(data),
     })
 }
 
@@ -518,4 +752,134 @@ mod tests {
             assert_eq!(sse, 0.0);
         }
     }
+
+    fn sof0_with_sampling(sampling: &[u8]) -> Vec<u8> {
+        let mut data = vec![0xFF, MARKER_SOI, 0xFF, 0xC0];
+        let length = 8 + sampling.len() * 3;
+        data.extend_from_slice(&[(length >> 8) as u8, length as u8, 8, 0, 16, 0, 16]);
+        data.push(sampling.len() as u8);
+        for (i, hv) in sampling.iter().enumerate() {
+            data.extend_from_slice(&[i as u8 + 1, *hv, 0]);
+        }
+        data.extend_from_slice(&[0xFF, MARKER_SOS, 0, 2, 0xFF, MARKER_EOI]);
+        data
+    }
+
+    #[test]
+    fn test_chroma_subsampling_from_sof() {
+        let cases = [
+            (vec![0x11, 0x11, 0x11], ChromaSubsampling::Yuv444),
+            (vec![0x21, 0x11, 0x11], ChromaSubsampling::Yuv422),
+            (vec![0x22, 0x11, 0x11], ChromaSubsampling::Yuv420),
+            (vec![0x41, 0x11, 0x11], ChromaSubsampling::Yuv422),
+            (vec![0x11], ChromaSubsampling::Unknown),
+            (vec![0x11, 0x11, 0x11, 0x11], ChromaSubsampling::Unknown),
+        ];
+        for (sampling, expected) in cases {
+            assert_eq!(
+                extract_chroma_subsampling(&sof0_with_sampling(&sampling)),
+                expected,
+                "sampling {:02x?}",
+                sampling
+            );
+        }
+        assert_eq!(
+            extract_chroma_subsampling(b"not a jpeg"),
+            ChromaSubsampling::Unknown
+        );
+    }
+
+    #[test]
+    fn test_jpeg_strategy_routing() {
+        assert_eq!(parse_jpeg_strategy("Matched"), Ok(JpegStrategy::Matched));
+        assert!(parse_jpeg_strategy("lossy").is_err());
+
+        let mut analysis = analyze_jpeg_quality(&{
+            let mut data = sof0_with_sampling(&[0x22, 0x11, 0x11]);
+            let qt = generate_standard_qt(70, &IJG_LUMINANCE_BASE);
+            let mut dqt = vec![0xFF, MARKER_DQT, 0, 67, 0];
+            for &idx in ZIGZAG_ORDER.iter() {
+                dqt.push(qt[idx / 8][idx % 8] as u8);
+            }
+            data.splice(2..2, dqt);
+            data
+        })
+        .unwrap();
+        assert_eq!(analysis.chroma_subsampling, ChromaSubsampling::Yuv420);
+        assert_eq!(analysis.estimated_quality, 70);
+
+        assert!(!JpegStrategy::Auto.prefers_lossless(Some(&analysis)));
+        assert!(JpegStrategy::Lossless.prefers_lossless(Some(&analysis)));
+        assert!(JpegStrategy::Auto.prefers_lossless(None));
+
+        analysis.chroma_subsampling = ChromaSubsampling::Yuv444;
+        assert!(JpegStrategy::Auto.prefers_lossless(Some(&analysis)));
+        assert!(!JpegStrategy::Matched.prefers_lossless(Some(&analysis)));
+    }
+
+    /// SOI + Exif APP1 whose IFD0 holds an unrelated tag followed by Orientation.
+    fn jpeg_with_orientation(orientation: u16, little_endian: bool) -> Vec<u8> {
+        let (u16b, u32b): (fn(u16) -> [u8; 2], fn(u32) -> [u8; 4]) = if little_endian {
+            (u16::to_le_bytes, u32::to_le_bytes)
+        } else {
+            (u16::to_be_bytes, u32::to_be_bytes)
+        };
+        let mut tiff = if little_endian {
+            b"II*\0".to_vec()
+        } else {
+            b"MM\0*".to_vec()
+        };
+        tiff.extend_from_slice(&u32b(8));
+        tiff.extend_from_slice(&u16b(2));
+        // ImageWidth (LONG), then Orientation (SHORT, left-aligned in the value field)
+        let mut orientation_value = [0u8; 4];
+        orientation_value[..2].copy_from_slice(&u16b(orientation));
+        for (tag, ty, value) in [(0x0100u16, 4u16, u32b(640)), (0x0112, 3, orientation_value)] {
+            tiff.extend_from_slice(&u16b(tag));
+            tiff.extend_from_slice(&u16b(ty));
+            tiff.extend_from_slice(&u32b(1));
+            tiff.extend_from_slice(&value);
+        }
+        tiff.extend_from_slice(&u32b(0));
+
+        let length = 2 + 6 + tiff.len();
+        let mut data = vec![0xFF, MARKER_SOI, 0xFF, MARKER_APP1];
+        data.extend_from_slice(&[(length >> 8) as u8, length as u8]);
+        data.extend_from_slice(b"Exif\0\0");
+        data.extend_from_slice(&tiff);
+        data.extend_from_slice(&sof0_with_sampling(&[0x22, 0x11, 0x11])[2..]);
+        data
+    }
+
+    #[test]
+    fn test_exif_orientation_all_values() {
+        for little_endian in [true, false] {
+            for orientation in 1..=8u8 {
+                let mut data = jpeg_with_orientation(orientation as u16, little_endian);
+                assert_eq!(
+                    extract_exif_orientation(&data),
+                    Some(orientation),
+                    "orientation {} (LE {})",
+                    orientation,
+                    little_endian
+                );
+                assert_eq!(
+                    jpegtran_orientation_args(orientation).is_none(),
+                    orientation == 1
+                );
+                assert!(reset_exif_orientation(&mut data));
+                assert_eq!(extract_exif_orientation(&data), Some(1));
+                // Nothing else moves: chroma layout is still readable after the rewrite.
+                assert_eq!(extract_chroma_subsampling(&data), ChromaSubsampling::Yuv420);
+            }
+        }
+
+        assert_eq!(
+            extract_exif_orientation(&jpeg_with_orientation(9, true)),
+            None
+        );
+        let mut plain = sof0_with_sampling(&[0x11, 0x11, 0x11]);
+        assert_eq!(extract_exif_orientation(&plain), None);
+        assert!(!reset_exif_orientation(&mut plain));
+    }
 }
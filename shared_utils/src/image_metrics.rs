@@ -4,6 +4,8 @@
 //! Uses standard algorithms:
 //! - PSNR: Peak Signal-to-Noise Ratio with parallel MSE calculation
 //! - SSIM: Structural Similarity Index with 11x11 Gaussian window (Wang et al. 2004)
+//! - MS-SSIM: 5-scale SSIM (Wang et al. 2003), downscaled first for very large images
+//! - Dark-weighted SSIM (`--perceptual-ssim`): SSIM map averaged with extra weight on low-luma windows
 
 use image::{DynamicImage, GenericImageView, GrayImage};
 use rayon::prelude::*;
@@ -17,6 +19,11 @@ const C2: f64 = (K2 * L) * (K2 * L);
 
 const WINDOW_SIZE: usize = 11;
 
+/// Reference-window mean luma (0-255) at or below which a window gets the full dark boost.
+pub const DARK_LUMA_KNEE: f64 = 64.0;
+/// Extra weight given to a black window (weight 1 + boost); mid-tones and highlights keep weight 1.
+pub const DARK_WEIGHT_BOOST: f64 = 3.0;
+
 fn get_gaussian_window() -> [[f64; WINDOW_SIZE]; WINDOW_SIZE] {
     let sigma = 1.5;
     let mut window = [[0.0f64; WINDOW_SIZE]; WINDOW_SIZE];
@@ -84,9 +91,6 @@ pub fn calculate_ssim(original: &DynamicImage, converted: &DynamicImage) -> Opti
         return None;
     }
 
-    let orig_gray = original.to_luma8();
-    let conv_gray = converted.to_luma8();
-
     let width = w1 as usize;
     let height = h1 as usize;
 
@@ -94,25 +98,72 @@ pub fn calculate_ssim(original: &DynamicImage, converted: &DynamicImage) -> Opti
         return calculate_ssim_simple(original, converted);
     }
 
-    let window = get_gaussian_window();
+    weighted_ssim_map_mean(&original.to_luma8(), &converted.to_luma8(), |_| 1.0)
+}
 
-    let valid_width = width - WINDOW_SIZE + 1;
-    let valid_height = height - WINDOW_SIZE + 1;
+/// SSIM with low-luma windows weighted up, for content (dark film scenes) where banding in
+/// the shadows is what viewers notice but plain SSIM averages it away.
+///
+/// With `SSIM_i` the usual SSIM of window `i` and `μ_i` the Gaussian-weighted mean luma of
+/// the *reference* window (so the weights do not depend on the distortion):
+///
+/// ```text
+/// w(μ)  = 1 + β · clamp(1 − μ / κ, 0, 1)        β = DARK_WEIGHT_BOOST, κ = DARK_LUMA_KNEE
+/// SSIM_w = Σ w(μ_i) · SSIM_i / Σ w(μ_i)
+/// ```
+///
+/// Windows brighter than κ keep weight 1, so on bright content this equals `calculate_ssim`;
+/// a black window counts `1 + β` times. Images smaller than one window fall back to the
+/// single-window SSIM, where weighting has no effect.
+pub fn calculate_ssim_dark_weighted(
+    original: &DynamicImage,
+    converted: &DynamicImage,
+) -> Option<f64> {
+    let (w1, h1) = original.dimensions();
+    if (w1, h1) != converted.dimensions() {
+        return None;
+    }
+    if (w1 as usize) < WINDOW_SIZE || (h1 as usize) < WINDOW_SIZE {
+        return calculate_ssim_simple(original, converted);
+    }
 
-    let positions: Vec<(usize, usize)> = (0..valid_height)
-        .flat_map(|y| (0..valid_width).map(move |x| (x, y)))
-        .collect();
+    weighted_ssim_map_mean(&original.to_luma8(), &converted.to_luma8(), dark_weight)
+}
 
-    let ssim_sum: f64 = positions
-        .par_iter()
-        .map(|&(x, y)| calculate_window_ssim(&orig_gray, &conv_gray, x, y, &window))
-        .sum();
+fn dark_weight(mean_luma: f64) -> f64 {
+    1.0 + DARK_WEIGHT_BOOST * (1.0 - mean_luma / DARK_LUMA_KNEE).clamp(0.0, 1.0)
+}
+
+/// `Σ weight(μ_i) · SSIM_i / Σ weight(μ_i)` over every valid window position.
+fn weighted_ssim_map_mean<W>(orig_gray: &GrayImage, conv_gray: &GrayImage, weight: W) -> Option<f64>
+where
+    W: Fn(f64) -> f64 + Sync,
+{
+    let window = get_gaussian_window();
 
-    if positions.is_empty() {
+    let valid_width = orig_gray.width() as usize - WINDOW_SIZE + 1;
+    let valid_height = orig_gray.height() as usize - WINDOW_SIZE + 1;
+
+    // Rows in parallel, each summed serially, then reduced in row order: the result is
+    // bit-identical regardless of how rayon splits the work.
+    let row_sums: Vec<(f64, f64)> = (0..valid_height)
+        .into_par_iter()
+        .map(|y| {
+            (0..valid_width).fold((0.0, 0.0), |(sum, total_weight), x| {
+                let (ssim, mean) = calculate_window_ssim(orig_gray, conv_gray, x, y, &window);
+                let w = weight(mean);
+                (sum + w * ssim, total_weight + w)
+            })
+        })
+        .collect();
+
+    let (sum, total_weight) = row_sums
+        .iter()
+        .fold((0.0, 0.0), |(s, t), (rs, rt)| (s + rs, t + rt));
+    if total_weight == 0.0 {
         return None;
     }
-    let count = positions.len() as f64;
-    Some(ssim_sum / count)
+    Some(sum / total_weight)
 }
 
 fn calculate_window_ssim(
@@ -121,7 +172,7 @@ fn calculate_window_ssim(
     x: usize,
     y: usize,
     window: &[[f64; WINDOW_SIZE]; WINDOW_SIZE],
-) -> f64 {
+) -> (f64, f64) {
     // Single read of the window to avoid repeated get_pixel (cache-friendly).
     let mut buf_x = [[0.0f64; WINDOW_SIZE]; WINDOW_SIZE];
     let mut buf_y = [[0.0f64; WINDOW_SIZE]; WINDOW_SIZE];
@@ -159,7 +210,7 @@ fn calculate_window_ssim(
     let numerator = (2.0 * mean_x * mean_y + C1) * (2.0 * cov_xy + C2);
     let denominator = (mean_x * mean_x + mean_y * mean_y + C1) * (var_x + var_y + C2);
 
-    numerator / denominator
+    (numerator / denominator, mean_x)
 }
 
 fn calculate_ssim_simple(original: &DynamicImage, converted: &DynamicImage) -> Option<f64> {
@@ -246,6 +297,136 @@ pub fn calculate_ms_ssim(original: &DynamicImage, converted: &DynamicImage) -> O
     Some(ms_ssim.powf(1.0 / used_weight_sum))
 }
 
+/// Pixel budget for [`calculate_ms_ssim_bounded`]; larger images are downscaled to about this
+/// size first. The coarse MS-SSIM scales carry most of the weight, so the score barely moves.
+pub const MS_SSIM_MAX_PIXELS: u64 = 4_000_000;
+
+/// Dimensions an image of `width`×`height` is scored at by [`calculate_ms_ssim_bounded`].
+pub fn ms_ssim_working_size(width: u32, height: u32) -> (u32, u32) {
+    let pixels = width as u64 * height as u64;
+    if pixels <= MS_SSIM_MAX_PIXELS {
+        return (width, height);
+    }
+    let scale = (MS_SSIM_MAX_PIXELS as f64 / pixels as f64).sqrt();
+    (
+        ((width as f64 * scale) as u32).max(1),
+        ((height as f64 * scale) as u32).max(1),
+    )
+}
+
+/// [`calculate_ms_ssim`] with both images first downscaled to [`ms_ssim_working_size`], so
+/// verifying a 50 MP photo stays fast. Returns `None` when the dimensions differ.
+pub fn calculate_ms_ssim_bounded(original: &DynamicImage, converted: &DynamicImage) -> Option<f64> {
+    let (w, h) = original.dimensions();
+    if converted.dimensions() != (w, h) {
+        return None;
+    }
+    let (tw, th) = ms_ssim_working_size(w, h);
+    if (tw, th) == (w, h) {
+        return calculate_ms_ssim(original, converted);
+    }
+    let filter = image::imageops::FilterType::Triangle;
+    calculate_ms_ssim(
+        &original.resize_exact(tw, th, filter),
+        &converted.resize_exact(tw, th, filter),
+    )
+}
+
+/// Mean MS-SSIM over a frame sequence, honouring the MS-SSIM sampling rate
+/// (every Nth frame, matching the `select='not(mod(n,N))'` filter used for video).
+///
+/// Sampled frames are scored in parallel; scores are collected in frame order and
+/// averaged serially, so the result is identical to a serial pass.
+/// Returns `None` for `SamplingStrategy::Skip` or when no frame could be scored.
+pub fn calculate_ms_ssim_frames(
+    original: &[DynamicImage],
+    converted: &[DynamicImage],
+    strategy: crate::msssim_sampling::SamplingStrategy,
+) -> Option<f64> {
+    let rate = strategy.sampling_rate()? as usize;
+    let frame_count = original.len().min(converted.len());
+    let sampled: Vec<usize> = (0..frame_count).step_by(rate.max(1)).collect();
+
+    let scores: Vec<Option<f64>> = sampled
+        .par_iter()
+        .map(|&i| calculate_ms_ssim(&original[i], &converted[i]))
+        .collect();
+
+    mean_of_scores(&scores)
+}
+
+/// Mean MS-SSIM of two animations, decoding only the frames `strategy` samples. Both files
+/// are decoded side by side and the sampled frames scored in parallel, in a stable order.
+/// Returns the score and the original's frame count; `None` for `SamplingStrategy::Skip`
+/// (before either file is opened) and wherever [`load_animation_frames`] gives up.
+pub fn calculate_animation_ms_ssim(
+    original: &std::path::Path,
+    converted: &std::path::Path,
+    strategy: crate::msssim_sampling::SamplingStrategy,
+) -> Option<(f64, usize)> {
+    strategy.sampling_rate()?;
+    let (original, converted) = rayon::join(
+        || load_animation_frames(original, strategy),
+        || load_animation_frames(converted, strategy),
+    );
+    let ((original, total), (converted, _)) = (original?, converted?);
+
+    let frame_count = original.len().min(converted.len());
+    let scores: Vec<Option<f64>> = (0..frame_count)
+        .into_par_iter()
+        .map(|i| calculate_ms_ssim(&original[i], &converted[i]))
+        .collect();
+
+    mean_of_scores(&scores).map(|score| (score, total))
+}
+
+/// Every Nth frame of an animated GIF, WebP or APNG for `strategy`'s sampling rate, plus the
+/// animation's total frame count. Frames are composited in order, but only sampled ones are
+/// converted and kept. `None` for `SamplingStrategy::Skip`, other formats, still images and
+/// decode failures.
+pub fn load_animation_frames(
+    path: &std::path::Path,
+    strategy: crate::msssim_sampling::SamplingStrategy,
+) -> Option<(Vec<DynamicImage>, usize)> {
+    use image::AnimationDecoder;
+
+    let rate = strategy.sampling_rate()? as usize;
+    let ext = path.extension()?.to_str()?.to_ascii_lowercase();
+    let reader = std::io::BufReader::new(std::fs::File::open(path).ok()?);
+    let frames = match ext.as_str() {
+        "gif" => image::codecs::gif::GifDecoder::new(reader)
+            .ok()?
+            .into_frames(),
+        "webp" => image::codecs::webp::WebPDecoder::new(reader)
+            .ok()?
+            .into_frames(),
+        "png" | "apng" => image::codecs::png::PngDecoder::new(reader)
+            .ok()?
+            .apng()
+            .ok()?
+            .into_frames(),
+        _ => return None,
+    };
+    let mut sampled = Vec::new();
+    let mut total = 0;
+    for frame in frames {
+        let frame = frame.ok()?;
+        if total % rate.max(1) == 0 {
+            sampled.push(DynamicImage::ImageRgba8(frame.into_buffer()));
+        }
+        total += 1;
+    }
+    (total > 1).then_some((sampled, total))
+}
+
+fn mean_of_scores(scores: &[Option<f64>]) -> Option<f64> {
+    let valid: Vec<f64> = scores.iter().flatten().copied().collect();
+    if valid.is_empty() {
+        return None;
+    }
+    Some(valid.iter().sum::<f64>() / valid.len() as f64)
+}
+
 pub fn psnr_quality_description(psnr: f64) -> &'static str {
     if psnr.is_infinite() {
         "Identical (lossless)"
@@ -278,6 +459,24 @@ pub fn ssim_quality_description(ssim: f64) -> &'static str {
     }
 }
 
+/// MS-SSIM runs higher than single-scale SSIM for the same visible loss, so the bands are
+/// tighter than [`ssim_quality_description`].
+pub fn ms_ssim_quality_description(ms_ssim: f64) -> &'static str {
+    if ms_ssim >= 0.999 {
+        "Identical"
+    } else if ms_ssim >= 0.99 {
+        "Excellent - virtually lossless"
+    } else if ms_ssim >= 0.97 {
+        "Very good - minimal visible difference"
+    } else if ms_ssim >= 0.95 {
+        "Good - acceptable quality"
+    } else if ms_ssim >= 0.90 {
+        "Fair - noticeable degradation"
+    } else {
+        "Poor - significant quality loss"
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -369,6 +568,145 @@ mod tests {
         assert!(result.unwrap() >= 0.99 && result.unwrap() <= 1.01);
     }
 
+    #[test]
+    fn test_ms_ssim_bounded_downscales_large_images() {
+        assert_eq!(ms_ssim_working_size(1920, 1080), (1920, 1080));
+        let (w, h) = ms_ssim_working_size(8000, 6000);
+        assert!(w as u64 * h as u64 <= MS_SSIM_MAX_PIXELS);
+        assert!((w as f64 / h as f64 - 8000.0 / 6000.0).abs() < 0.01);
+
+        let img = DynamicImage::ImageRgb8(RgbImage::from_fn(64, 64, |x, y| {
+            image::Rgb([(x * 3 % 256) as u8, (y * 5 % 256) as u8, 90])
+        }));
+        let score = calculate_ms_ssim_bounded(&img, &img).unwrap();
+        assert!(score >= 0.99);
+        assert_eq!(ms_ssim_quality_description(score), "Identical");
+    }
+
+    fn synthetic_sequence(frames: u32, shift: u32) -> Vec<DynamicImage> {
+        (0..frames)
+            .map(|f| {
+                DynamicImage::ImageRgb8(RgbImage::from_fn(48, 48, move |x, y| {
+                    let v = (x * 5 + y * 3 + f * 7 + shift) % 256;
+                    image::Rgb([v as u8, (255 - v) as u8, ((x ^ y) % 256) as u8])
+                }))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_ms_ssim_frames_parallel_matches_serial() {
+        use crate::msssim_sampling::SamplingStrategy;
+
+        let original = synthetic_sequence(300, 0);
+        let converted = synthetic_sequence(300, 2);
+
+        let serial_scores: Vec<Option<f64>> = original
+            .iter()
+            .zip(converted.iter())
+            .map(|(o, c)| calculate_ms_ssim(o, c))
+            .collect();
+        let serial = mean_of_scores(&serial_scores).unwrap();
+        let parallel =
+            calculate_ms_ssim_frames(&original, &converted, SamplingStrategy::Full).unwrap();
+
+        assert_eq!(serial, parallel, "parallel result must be bit-identical");
+        assert!(
+            serial > 0.0 && serial < 1.0,
+            "shifted frames differ: {}",
+            serial
+        );
+        // Identical sequences score 1 on every frame.
+        let same = calculate_ms_ssim_frames(&original, &original, SamplingStrategy::Full).unwrap();
+        assert!((same - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_load_animation_frames_gif() {
+        use crate::msssim_sampling::SamplingStrategy;
+        use image::codecs::gif::GifEncoder;
+        use image::Frame;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("anim.gif");
+        let frames = synthetic_sequence(4, 0);
+        {
+            let mut encoder = GifEncoder::new(std::fs::File::create(&path).unwrap());
+            encoder
+                .encode_frames(frames.iter().map(|f| Frame::new(f.to_rgba8())))
+                .unwrap();
+        }
+
+        let (decoded, total) = load_animation_frames(&path, SamplingStrategy::Full).unwrap();
+        assert_eq!((decoded.len(), total), (4, 4));
+        assert_eq!(decoded[0].dimensions(), (48, 48));
+        let score = calculate_ms_ssim_frames(&decoded, &decoded, SamplingStrategy::Full);
+        assert!((score.unwrap() - 1.0).abs() < 1e-9);
+
+        let still = dir.path().join("still.txt");
+        std::fs::write(&still, b"x").unwrap();
+        assert!(load_animation_frames(&still, SamplingStrategy::Full).is_none());
+    }
+
+    #[test]
+    fn test_animation_ms_ssim_decodes_only_sampled_frames() {
+        use crate::msssim_sampling::SamplingStrategy;
+        use image::codecs::gif::GifEncoder;
+        use image::Frame;
+
+        let dir = tempfile::tempdir().unwrap();
+        let write_gif = |name: &str, shift: u32| {
+            let path = dir.path().join(name);
+            let mut encoder = GifEncoder::new(std::fs::File::create(&path).unwrap());
+            encoder
+                .encode_frames(
+                    synthetic_sequence(9, shift)
+                        .iter()
+                        .map(|f| Frame::new(f.to_rgba8())),
+                )
+                .unwrap();
+            path
+        };
+        let original = write_gif("original.gif", 0);
+        let converted = write_gif("converted.gif", 2);
+
+        let (sampled, total) =
+            load_animation_frames(&original, SamplingStrategy::OneThird).unwrap();
+        assert_eq!((sampled.len(), total), (3, 9));
+
+        // Same frames, same order: the path-based score equals scoring the sampled frames.
+        let (converted_sampled, _) =
+            load_animation_frames(&converted, SamplingStrategy::OneThird).unwrap();
+        let expected =
+            calculate_ms_ssim_frames(&sampled, &converted_sampled, SamplingStrategy::Full).unwrap();
+        assert_eq!(
+            calculate_animation_ms_ssim(&original, &converted, SamplingStrategy::OneThird),
+            Some((expected, 9))
+        );
+
+        // Skip is decided before any file is opened.
+        let missing = dir.path().join("missing.gif");
+        assert!(calculate_animation_ms_ssim(&missing, &missing, SamplingStrategy::Skip).is_none());
+    }
+
+    #[test]
+    fn test_ms_ssim_frames_respects_sampling() {
+        use crate::msssim_sampling::SamplingStrategy;
+
+        let original = synthetic_sequence(30, 0);
+        let converted = synthetic_sequence(30, 2);
+        let sampled: Vec<Option<f64>> = (0..30)
+            .step_by(3)
+            .map(|i| calculate_ms_ssim(&original[i], &converted[i]))
+            .collect();
+
+        assert_eq!(
+            calculate_ms_ssim_frames(&original, &converted, SamplingStrategy::OneThird),
+            mean_of_scores(&sampled)
+        );
+        assert!(calculate_ms_ssim_frames(&original, &converted, SamplingStrategy::Skip).is_none());
+    }
+
     #[test]
     fn test_ms_ssim_small_image_returns_none() {
         // No scale has size >= 11; used_weight_sum == 0 -> None.
@@ -376,4 +714,46 @@ mod tests {
         let result = calculate_ms_ssim(&img, &img);
         assert!(result.is_none());
     }
+
+    #[test]
+    fn test_dark_weighted_ssim_penalizes_shadow_banding() {
+        // Left half: smooth dark gradient (luma 4-36); right half: bright gradient (160-224).
+        let luma = |x: u32, y: u32| -> u8 {
+            if x < 64 {
+                (4 + x / 2 + y % 4) as u8
+            } else {
+                (160 + x - 64 + y % 4) as u8
+            }
+        };
+        let gray = |f: &dyn Fn(u32, u32) -> u8| {
+            DynamicImage::ImageLuma8(GrayImage::from_fn(128, 64, |x, y| image::Luma([f(x, y)])))
+        };
+        let original = gray(&luma);
+        // Banding injected into the shadows only: dark values snapped to steps of 8.
+        let banded = gray(&|x, y| {
+            let v = luma(x, y);
+            if x < 64 {
+                v / 8 * 8
+            } else {
+                v
+            }
+        });
+
+        let plain = calculate_ssim(&original, &banded).unwrap();
+        let weighted = calculate_ssim_dark_weighted(&original, &banded).unwrap();
+        assert!(
+            weighted < plain - 0.01,
+            "weighted {weighted} vs plain {plain}"
+        );
+
+        // No distortion: both are exactly 1.
+        assert!((calculate_ssim_dark_weighted(&original, &original).unwrap() - 1.0).abs() < 1e-9);
+
+        // Bright-only content: every weight is 1, so the variants agree.
+        let bright = gray(&|x, y| (160 + x / 2 + y % 4) as u8);
+        let bright_banded = gray(&|x, y| (160 + x / 2 + y % 4) as u8 / 8 * 8);
+        let a = calculate_ssim(&bright, &bright_banded).unwrap();
+        let b = calculate_ssim_dark_weighted(&bright, &bright_banded).unwrap();
+        assert!((a - b).abs() < 1e-12);
+    }
 }
@@ -26,6 +26,9 @@ pub struct ImageQualityAnalysis {
     pub has_alpha: bool,
     pub is_animated: bool,
     pub frame_count: u32,
+    /// No pixel carries chroma: a black-and-white image stored as RGB.
+    #[serde(default)]
+    pub is_grayscale: bool,
 
     pub complexity: f64,
     pub edge_density: f64,
@@ -156,6 +159,7 @@ pub fn analyze_image_quality(
     let sharpness = calculate_sharpness(rgba_data, width, height);
     let contrast = calculate_contrast(rgba_data, width, height);
     let has_alpha = detect_alpha_usage(rgba_data);
+    let is_grayscale = detect_grayscale(rgba_data);
 
     let complexity =
         calculate_overall_complexity(edge_density, color_diversity, texture_variance, noise_level);
@@ -186,6 +190,7 @@ pub fn analyze_image_quality(
         has_alpha,
         is_animated,
         frame_count,
+        is_grayscale,
         complexity,
         edge_density,
         color_diversity,
@@ -475,6 +480,18 @@ fn detect_alpha_usage(rgba: &[u8]) -> bool {
     false
 }
 
+/// Largest RGB channel spread (code values) a pixel may have and still count as neutral;
+/// absorbs the chroma noise JPEG-sourced scans carry.
+const GRAYSCALE_CHANNEL_TOLERANCE: u8 = 4;
+
+/// True when every visible pixel is neutral, i.e. the chroma planes would be empty.
+fn detect_grayscale(rgba: &[u8]) -> bool {
+    rgba.chunks_exact(4).all(|px| {
+        let (r, g, b, a) = (px[0], px[1], px[2], px[3]);
+        a == 0 || r.max(g).max(b) - r.min(g).min(b) <= GRAYSCALE_CHANNEL_TOLERANCE
+    })
+}
+
 fn calculate_overall_complexity(
     edge_density: f64,
     color_diversity: f64,
@@ -700,5 +717,26 @@ pub fn log_media_info_for_image_quality(analysis: &ImageQualityAnalysis, input_p
         ),
     );
     write_to_log_at_level(Level::DEBUG, &format!("  color_diversity={:.4} texture_variance={:.4} noise={:.4} sharpness={:.4} contrast={:.4} confidence={:.4}", analysis.color_diversity, analysis.texture_variance, analysis.noise_level, analysis.sharpness, analysis.contrast, analysis.confidence));
+    write_to_log_at_level(
+        Level::DEBUG,
+        &format!("  grayscale={}", analysis.is_grayscale),
+    );
     write_to_log_at_level(Level::DEBUG, "");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_grayscale_tolerates_noise_but_not_color() {
+        let gray = [10, 12, 11, 255, 200, 203, 199, 255];
+        assert!(detect_grayscale(&gray));
+        // A fully transparent colored pixel is never seen.
+        let hidden = [10, 12, 11, 255, 255, 0, 0, 0];
+        assert!(detect_grayscale(&hidden));
+        // One faint sepia pixel is enough to keep the color container.
+        let sepia = [10, 12, 11, 255, 112, 100, 86, 255];
+        assert!(!detect_grayscale(&sepia));
+    }
+}
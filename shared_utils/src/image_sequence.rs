@@ -0,0 +1,382 @@
+//! Image Sequence Module
+//!
+//! Detects numbered frame sequences (`frame_0001.png` …) and assembles them into a
+//! lossless intermediate so they can go through the normal video CRF exploration.
+//! 图像序列模块：识别编号帧序列并合成为无损中间文件。
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+const SEQUENCE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "tif", "tiff", "bmp", "webp"];
+const MAX_REPORTED_GAPS: usize = 10;
+
+/// A gap-free run of numbered frames sharing one prefix, suffix and digit width.
+#[derive(Debug, Clone)]
+pub struct ImageSequence {
+    pub dir: PathBuf,
+    pub prefix: String,
+    pub suffix: String,
+    /// Zero-padded digit width; `0` when frame numbers are not padded.
+    pub width: usize,
+    pub start: u64,
+    pub frames: Vec<PathBuf>,
+}
+
+impl ImageSequence {
+    pub fn frame_count(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn total_bytes(&self) -> u64 {
+        self.frames
+            .iter()
+            .filter_map(|f| fs::metadata(f).ok())
+            .map(|m| m.len())
+            .sum()
+    }
+
+    /// printf-style pattern for ffmpeg's image2 demuxer, e.g. `dir/frame_%04d.png`.
+    pub fn ffmpeg_pattern(&self) -> PathBuf {
+        let digits = if self.width > 0 {
+            format!("%0{}d", self.width)
+        } else {
+            "%d".to_string()
+        };
+        self.dir.join(format!(
+            "{}{}{}",
+            self.prefix.replace('%', "%%"),
+            digits,
+            self.suffix.replace('%', "%%")
+        ))
+    }
+
+    pub fn ffmpeg_input_args(&self, fps: f64) -> Vec<String> {
+        vec![
+            "-framerate".to_string(),
+            format!("{}", fps),
+            "-start_number".to_string(),
+            self.start.to_string(),
+            "-f".to_string(),
+            "image2".to_string(),
+            "-i".to_string(),
+            crate::safe_path_arg(&self.ffmpeg_pattern()).into_owned(),
+        ]
+    }
+
+    /// Output stem: the frame prefix without trailing separators, else the folder name.
+    pub fn name(&self) -> String {
+        let trimmed = self.prefix.trim_end_matches(['_', '-', '.', ' ']);
+        if !trimmed.is_empty() {
+            return trimmed.to_string();
+        }
+        self.dir
+            .file_name()
+            .and_then(|n| n.to_str())
+            .filter(|n| !n.is_empty())
+            .unwrap_or("sequence")
+            .to_string()
+    }
+}
+
+/// Parse `--fps`: a positive frame rate up to 240.
+pub fn parse_fps(value: &str) -> Result<f64, String> {
+    let fps: f64 = value
+        .trim()
+        .parse()
+        .map_err(|_| format!("Invalid frame rate '{}'", value))?;
+    if fps > 0.0 && fps <= 240.0 {
+        Ok(fps)
+    } else {
+        Err(format!(
+            "Frame rate {} out of range (must be in (0, 240])",
+            fps
+        ))
+    }
+}
+
+/// Frame numbers missing between the first and last entry of a sorted, deduplicated list.
+pub fn missing_frames(numbers: &[u64]) -> Vec<u64> {
+    numbers.windows(2).flat_map(|w| (w[0] + 1)..w[1]).collect()
+}
+
+/// Splits a sequence spec into (dir, prefix, suffix, fixed width).
+/// Accepts a printf pattern (`frame_%04d.png`), a glob with one `*` (`frame_*.png`)
+/// or a directory (the largest numbered group inside it is used).
+fn split_spec(spec: &Path) -> Result<(PathBuf, Option<(String, String, Option<usize>)>), String> {
+    if spec.is_dir() {
+        return Ok((spec.to_path_buf(), None));
+    }
+
+    let dir = spec
+        .parent()
+        .filter(|p| !p.as_os_str().is_empty())
+        .unwrap_or(Path::new("."))
+        .to_path_buf();
+    let file = spec
+        .file_name()
+        .and_then(|n| n.to_str())
+        .ok_or_else(|| format!("Invalid sequence pattern: {}", spec.display()))?;
+
+    if let Some(pos) = file.find('%') {
+        let rest = &file[pos + 1..];
+        let digits_end = rest
+            .find('d')
+            .ok_or_else(|| format!("Unsupported printf pattern '{}' (use %d or %0Nd)", file))?;
+        let spec_digits = &rest[..digits_end];
+        let width = if spec_digits.is_empty() {
+            None
+        } else if spec_digits.starts_with('0') && spec_digits.chars().all(|c| c.is_ascii_digit()) {
+            Some(
+                spec_digits[1..]
+                    .parse::<usize>()
+                    .map_err(|_| format!("Invalid printf width in '{}'", file))?,
+            )
+        } else {
+            return Err(format!(
+                "Unsupported printf pattern '{}' (use %d or %0Nd)",
+                file
+            ));
+        };
+        let prefix = file[..pos].to_string();
+        let suffix = rest[digits_end + 1..].to_string();
+        return Ok((dir, Some((prefix, suffix, width))));
+    }
+
+    match file.matches('*').count() {
+        1 => {
+            let (prefix, suffix) = file.split_once('*').unwrap_or((file, ""));
+            Ok((dir, Some((prefix.to_string(), suffix.to_string(), None))))
+        }
+        0 => Err(format!(
+            "'{}' is not a sequence pattern (use a directory, frame_%04d.png or frame_*.png)",
+            spec.display()
+        )),
+        _ => Err(format!(
+            "Sequence pattern '{}' must contain exactly one '*'",
+            file
+        )),
+    }
+}
+
+/// Splits `frame_0012.png` into ("frame_", "0012", ".png") on the last digit run of the stem.
+fn split_numbered(name: &str) -> Option<(&str, &str, &str)> {
+    let dot = name.rfind('.')?;
+    let ext = name[dot + 1..].to_ascii_lowercase();
+    if !SEQUENCE_EXTENSIONS.contains(&ext.as_str()) {
+        return None;
+    }
+    let stem = &name[..dot];
+    let digits_start = stem
+        .char_indices()
+        .rev()
+        .take_while(|(_, c)| c.is_ascii_digit())
+        .last()
+        .map(|(i, _)| i)?;
+    Some((&name[..digits_start], &stem[digits_start..], &name[dot..]))
+}
+
+/// Detects the frame sequence described by `spec` and rejects gaps and mixed padding.
+pub fn detect_image_sequence(spec: &Path) -> Result<ImageSequence, String> {
+    let (dir, parts) = split_spec(spec)?;
+    let names: Vec<String> = fs::read_dir(&dir)
+        .map_err(|e| format!("Cannot read sequence directory {}: {}", dir.display(), e))?
+        .filter_map(|e| e.ok())
+        .filter(|e| e.path().is_file())
+        .filter_map(|e| e.file_name().to_str().map(str::to_string))
+        .collect();
+
+    let (prefix, suffix, fixed_width) = match parts {
+        Some(parts) => parts,
+        None => {
+            let mut groups: std::collections::HashMap<(String, String), usize> =
+                std::collections::HashMap::new();
+            for name in &names {
+                if let Some((prefix, _, suffix)) = split_numbered(name) {
+                    *groups
+                        .entry((prefix.to_string(), suffix.to_string()))
+                        .or_default() += 1;
+                }
+            }
+            let ((prefix, suffix), _) = groups
+                .into_iter()
+                .max_by(|a, b| a.1.cmp(&b.1).then_with(|| b.0.cmp(&a.0)))
+                .ok_or_else(|| format!("No numbered image frames found in {}", dir.display()))?;
+            (prefix, suffix, None)
+        }
+    };
+
+    let mut numbered: Vec<(u64, usize, String)> = names
+        .iter()
+        .filter_map(|name| {
+            let digits = name.strip_prefix(&prefix)?.strip_suffix(&suffix)?;
+            if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit()) {
+                return None;
+            }
+            let n = digits.parse::<u64>().ok()?;
+            Some((n, digits.len(), name.clone()))
+        })
+        .filter(|(_, len, _)| fixed_width.is_none_or(|w| w == 0 || *len == w))
+        .collect();
+    numbered.sort();
+
+    if numbered.len() < 2 {
+        return Err(format!(
+            "Image sequence '{}' needs at least 2 frames, found {}",
+            spec.display(),
+            numbered.len()
+        ));
+    }
+
+    let first_len = numbered[0].1;
+    let padded = numbered.iter().all(|(_, len, _)| *len == first_len);
+    let width = match fixed_width {
+        Some(w) => w,
+        None if padded
+            && numbered
+                .iter()
+                .any(|(_, _, name)| name[prefix.len()..].starts_with('0')) =>
+        {
+            first_len
+        }
+        None if numbered
+            .iter()
+            .all(|(n, len, _)| n.to_string().len() == *len) =>
+        {
+            0
+        }
+        None => {
+            return Err(format!(
+                "Image sequence '{}' mixes zero-padded and unpadded frame numbers",
+                spec.display()
+            ))
+        }
+    };
+
+    let numbers: Vec<u64> = numbered.iter().map(|(n, _, _)| *n).collect();
+    if numbers.windows(2).any(|w| w[0] == w[1]) {
+        return Err(format!(
+            "Image sequence '{}' has duplicate frame numbers",
+            spec.display()
+        ));
+    }
+    let gaps = missing_frames(&numbers);
+    if !gaps.is_empty() {
+        let shown: Vec<String> = gaps
+            .iter()
+            .take(MAX_REPORTED_GAPS)
+            .map(|n| n.to_string())
+            .collect();
+        return Err(format!(
+            "Image sequence '{}' has gaps: {} missing frame(s) between {} and {} (missing: {}{})",
+            spec.display(),
+            gaps.len(),
+            numbers[0],
+            numbers[numbers.len() - 1],
+            shown.join(", "),
+            if gaps.len() > MAX_REPORTED_GAPS {
+                ", …"
+            } else {
+                ""
+            }
+        ));
+    }
+
+    Ok(ImageSequence {
+        start: numbers[0],
+        frames: numbered
+            .into_iter()
+            .map(|(_, _, name)| dir.join(name))
+            .collect(),
+        dir,
+        prefix,
+        suffix,
+        width,
+    })
+}
+
+/// Encodes the sequence losslessly (FFV1 MKV) at `fps` so the explorer can compare
+/// every decoded output frame against the exact source frames.
+pub fn assemble_lossless_intermediate(
+    sequence: &ImageSequence,
+    fps: f64,
+    dest: &Path,
+) -> Result<(), String> {
+    let output = Command::new("ffmpeg")
+        .arg("-y")
+        .arg("-nostdin")
+        .args(sequence.ffmpeg_input_args(fps))
+        .args(["-c:v", "ffv1", "-level", "3", "-an"])
+        .arg(crate::safe_path_arg(dest).as_ref())
+        .output()
+        .map_err(|e| format!("Failed to run ffmpeg: {}", e))?;
+
+    if !output.status.success() || !dest.exists() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!(
+            "ffmpeg failed to assemble image sequence: {}",
+            stderr.lines().last().unwrap_or("unknown error")
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn touch_frames(dir: &Path, names: &[&str]) {
+        for name in names {
+            fs::write(dir.join(name), b"frame").unwrap();
+        }
+    }
+
+    #[test]
+    fn test_missing_frames() {
+        assert!(missing_frames(&[1, 2, 3]).is_empty());
+        assert_eq!(missing_frames(&[1, 2, 5, 7]), vec![3, 4, 6]);
+    }
+
+    #[test]
+    fn test_detect_padded_sequence_from_glob_and_printf() {
+        let temp = tempfile::tempdir().unwrap();
+        touch_frames(
+            temp.path(),
+            &[
+                "frame_0001.png",
+                "frame_0002.png",
+                "frame_0003.png",
+                "notes.txt",
+            ],
+        );
+
+        let seq = detect_image_sequence(&temp.path().join("frame_*.png")).unwrap();
+        assert_eq!(seq.frame_count(), 3);
+        assert_eq!(seq.start, 1);
+        assert_eq!(seq.name(), "frame");
+        assert_eq!(seq.ffmpeg_pattern(), temp.path().join("frame_%04d.png"));
+
+        let seq = detect_image_sequence(&temp.path().join("frame_%04d.png")).unwrap();
+        assert_eq!(seq.width, 4);
+
+        let seq = detect_image_sequence(temp.path()).unwrap();
+        assert_eq!(seq.frame_count(), 3);
+    }
+
+    #[test]
+    fn test_detect_sequence_rejects_gaps() {
+        let temp = tempfile::tempdir().unwrap();
+        touch_frames(temp.path(), &["f1.png", "f2.png", "f4.png", "f7.png"]);
+
+        let err = detect_image_sequence(&temp.path().join("f*.png")).unwrap_err();
+        assert!(err.contains("gaps"), "{}", err);
+        assert!(err.contains("missing: 3, 5, 6"), "{}", err);
+    }
+
+    #[test]
+    fn test_parse_fps() {
+        assert_eq!(parse_fps("23.976").unwrap(), 23.976);
+        assert!(parse_fps("0").is_err());
+        assert!(parse_fps("fast").is_err());
+    }
+}
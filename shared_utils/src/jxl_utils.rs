@@ -9,34 +9,120 @@
 use std::path::Path;
 use std::process::Command;
 
-/// Extract ICC Profile from source image and return temp file path
+/// Extract ICC Profile from source image and return temp file path.
+/// Uses ExifTool when installed, otherwise (or when it finds nothing) the image decoder's copy.
 pub fn extract_icc_profile(src: &Path) -> Option<tempfile::NamedTempFile> {
-    if which::which("exiftool").is_err() {
-        return None;
-    }
+    let from_exiftool = if which::which("exiftool").is_ok() {
+        Command::new("exiftool")
+            .arg("-icc_profile")
+            .arg("-b")
+            .arg(crate::safe_path_arg(src).as_ref())
+            .output()
+            .ok()
+            .filter(|output| output.status.success() && !output.stdout.is_empty())
+            .map(|output| output.stdout)
+    } else {
+        None
+    };
+    let icc = from_exiftool.or_else(|| crate::image_analyzer::read_icc_profile(src))?;
 
-    let temp_icc = tempfile::Builder::new().suffix(".icc").tempfile().ok()?;
-    let output = Command::new("exiftool")
-        .arg("-icc_profile")
-        .arg("-b")
-        .arg(crate::safe_path_arg(src).as_ref())
-        .output()
+    let temp_icc = tempfile::Builder::new()
+        .suffix(".icc")
+        .tempfile_in(crate::conversion::scratch_dir())
         .ok()?;
+    std::fs::write(temp_icc.path(), &icc).ok()?;
+    Some(temp_icc)
+}
 
-    if output.status.success() && !output.stdout.is_empty() {
-        std::fs::write(temp_icc.path(), &output.stdout).ok()?;
-        Some(temp_icc)
-    } else {
-        None
+/// cjxl arguments attaching an extracted ICC profile (`-x icc_pathname=…`), if there is one.
+pub fn icc_cjxl_args(icc_file: Option<&Path>) -> Vec<String> {
+    icc_file
+        .map(|icc_path| {
+            vec![
+                "-x".to_string(),
+                format!("icc_pathname={}", icc_path.display()),
+            ]
+        })
+        .unwrap_or_default()
+}
+
+/// `-- <input> <output>`: the trailing cjxl arguments, with both paths made safe for argv.
+pub fn cjxl_io_args(input: &Path, output: &Path) -> [String; 3] {
+    [
+        "--".to_string(),
+        crate::safe_path_arg(input).as_ref().to_string(),
+        crate::safe_path_arg(output).as_ref().to_string(),
+    ]
+}
+
+/// `--print-command`: `#` notes for the run-time steps a cjxl preview line leaves out because
+/// they write temporary files. `decoded` names the decode cjxl reads instead of the source,
+/// `grayscale` the single-channel copy of `--auto-grayscale`, `icc` the source ICC profile the
+/// run extracts, when there is one, and passes as `-x icc_pathname=…`.
+pub fn cjxl_preview_notes(decoded: Option<&str>, grayscale: bool, icc: bool) -> Vec<String> {
+    let mut notes = Vec::new();
+    if let Some(decoded) = decoded {
+        notes.push(format!(
+            "# cjxl reads {} of the source, written to a temp file at run time",
+            decoded
+        ));
+    }
+    if grayscale {
+        notes.push(
+            "# cjxl reads a single-channel copy of the source (--auto-grayscale)".to_string(),
+        );
     }
+    if icc {
+        notes.push(
+            "# a source ICC profile, if present, is extracted and added as -x icc_pathname=…"
+                .to_string(),
+        );
+    }
+    notes
 }
 
-/// Add ICC Profile argument to cjxl command if available
-pub fn add_icc_to_cjxl(cmd: &mut Command, icc_file: Option<&Path>) {
-    if let Some(icc_path) = icc_file {
-        cmd.arg("-x")
-            .arg(format!("icc_pathname={}", icc_path.display()));
+/// Source ICC profile to attach to the cjxl encode of `src`, so it survives preprocessing that
+/// re-encodes the input (`--preserve-icc`). `None` for grayscale encodes, which an RGB profile
+/// cannot describe. A wide-gamut source that ends up without its profile would be read as
+/// sRGB and clipped, so that case is warned about.
+pub fn icc_for_cjxl(
+    src: &Path,
+    preserve: bool,
+    grayscale: bool,
+    verbose: bool,
+) -> Option<tempfile::NamedTempFile> {
+    if grayscale {
+        return None;
     }
+    let icc = if preserve {
+        extract_icc_profile(src)
+    } else {
+        None
+    };
+    let info = match &icc {
+        Some(temp) => std::fs::read(temp.path())
+            .ok()
+            .and_then(|bytes| crate::image_analyzer::IccProfileInfo::from_bytes(&bytes)),
+        None => crate::image_analyzer::detect_icc_profile(src),
+    };
+    if let Some(info) = info.filter(|info| info.gamut.is_wide()) {
+        if icc.is_some() {
+            if verbose {
+                eprintln!(
+                    "   🎨 {} ICC profile preserved ({} bytes)",
+                    info.gamut.as_str(),
+                    info.size_bytes
+                );
+            }
+        } else {
+            crate::progress_mode::emit_stderr(&format!(
+                "   ⚠️  {} source encoded without its ICC profile{}: colors may be clipped to sRGB",
+                info.gamut.as_str(),
+                if preserve { "" } else { " (--no-preserve-icc)" }
+            ));
+        }
+    }
+    icc
 }
 
 /// Verify that a JXL file is valid by checking its signature and optionally running jxlinfo.
@@ -80,7 +166,9 @@ pub fn convert_to_temp_png(
 ) -> std::io::Result<(std::path::PathBuf, Option<tempfile::NamedTempFile>)> {
     use console::style;
 
-    let temp_png_file = tempfile::Builder::new().suffix(".png").tempfile()?;
+    let temp_png_file = tempfile::Builder::new()
+        .suffix(".png")
+        .tempfile_in(crate::conversion::scratch_dir())?;
     let temp_png = temp_png_file.path().to_path_buf();
 
     let mut cmd = Command::new(tool);
@@ -114,6 +202,71 @@ pub fn convert_to_temp_png(
     }
 }
 
+/// `--auto-grayscale`: decode `input` into a single-channel PNG (gray, or gray + alpha) so cjxl
+/// writes a grayscale JXL; high-bit-depth sources stay 16-bit. `None` when the image crate
+/// cannot decode it, in which case the caller keeps the color input.
+pub fn convert_to_gray_png(input: &Path) -> Option<(std::path::PathBuf, tempfile::NamedTempFile)> {
+    use image::{ColorType, DynamicImage};
+
+    let img = image::open(input).ok()?;
+    let high_depth = !matches!(
+        img.color(),
+        ColorType::L8 | ColorType::La8 | ColorType::Rgb8 | ColorType::Rgba8
+    );
+    let gray = match (high_depth, img.color().has_alpha()) {
+        (true, true) => DynamicImage::ImageLumaA16(img.to_luma_alpha16()),
+        (true, false) => DynamicImage::ImageLuma16(img.to_luma16()),
+        (false, true) => DynamicImage::ImageLumaA8(img.to_luma_alpha8()),
+        (false, false) => DynamicImage::ImageLuma8(img.to_luma8()),
+    };
+
+    let temp_png_file = tempfile::Builder::new()
+        .suffix(".png")
+        .tempfile_in(crate::conversion::scratch_dir())
+        .ok()?;
+    let temp_png = temp_png_file.path().to_path_buf();
+    gray.save_with_format(&temp_png, image::ImageFormat::Png)
+        .ok()?;
+    Some((temp_png, temp_png_file))
+}
+
+/// cjxl `--override_bitdepth` for a high-bit-depth source (12/16-bit, or float, which the
+/// PNG hand-off stores as 16-bit), so cjxl keeps every bit instead of inferring the depth;
+/// `None` for 8-bit or unknown sources.
+pub fn cjxl_bit_depth_arg(source_depth: Option<u8>) -> Option<String> {
+    source_depth
+        .filter(|d| *d > 8)
+        .map(|d| format!("--override_bitdepth={}", d.min(16)))
+}
+
+/// FFmpeg `-pix_fmt` for a PNG hand-off that keeps a high-bit-depth source at 16 bits (FFmpeg
+/// may otherwise pick an 8-bit format); `None` for 8-bit sources.
+pub fn png16_pix_fmt(color: image::ColorType) -> Option<&'static str> {
+    use image::ColorType;
+    match color {
+        ColorType::L8 | ColorType::La8 | ColorType::Rgb8 | ColorType::Rgba8 => None,
+        ColorType::L16 => Some("gray16be"),
+        ColorType::La16 => Some("ya16be"),
+        c if c.has_alpha() => Some("rgba64be"),
+        _ => Some("rgb48be"),
+    }
+}
+
+/// Lossless JXL output must keep the source's bit depth: `Err` when jxlinfo reports fewer bits
+/// per sample than a high-bit-depth source has. Passes when either depth is unknown.
+pub fn verify_jxl_bit_depth(source_depth: Option<u8>, output: &Path) -> Result<(), String> {
+    let Some(source) = source_depth.filter(|d| *d > 8) else {
+        return Ok(());
+    };
+    match crate::image_analyzer::read_jxl_bit_depth(output) {
+        Some(actual) if actual < source.min(16) => Err(format!(
+            "JXL output is {}-bit but the source is {}-bit; refusing to downconvert",
+            actual, source
+        )),
+        _ => Ok(()),
+    }
+}
+
 /// True when cjxl failed due to grayscale PNG + ICC profile (libpng: "RGB color space not permitted on grayscale").
 /// Only then do we retry with -strip to avoid metadata loss in the general case.
 /// Enhanced to catch more variants of the error message.
@@ -171,6 +324,7 @@ fn run_imagemagick_cjxl_pipeline(
     depth: u8,
     normalize_icc: bool,
     apple_compat: bool,
+    policy: crate::metadata::StripPolicy,
 ) -> std::result::Result<(), (bool, bool, String)> {
     use std::process::Stdio;
 
@@ -240,6 +394,7 @@ fn run_imagemagick_cjxl_pipeline(
     if apple_compat {
         cjxl_cmd.arg("--compress_boxes=0");
     }
+    cjxl_cmd.args(policy.cjxl_args());
     let mut cjxl_proc = cjxl_cmd
         .stdin(magick_stdout)
         .stderr(Stdio::piped())
@@ -363,6 +518,7 @@ pub fn try_imagemagick_fallback(
     distance: f32,
     max_threads: usize,
     apple_compat: bool,
+    strip: crate::metadata::StripPolicy,
 ) -> std::result::Result<(), std::io::Error> {
     use console::style;
 
@@ -380,6 +536,7 @@ pub fn try_imagemagick_fallback(
         16,
         false,
         apple_compat,
+        strip,
     ) {
         Ok(()) => {
             crate::progress_mode::emit_stderr(&format!(
@@ -419,6 +576,7 @@ pub fn try_imagemagick_fallback(
                     16,
                     false,
                     apple_compat,
+                    strip,
                 ) {
                     Ok(()) => {
                         crate::progress_mode::emit_stderr(&format!(
@@ -461,6 +619,7 @@ pub fn try_imagemagick_fallback(
                                     8,
                                     false,
                                     apple_compat,
+                                    strip,
                                 ) {
                                     Ok(()) => {
                                         crate::progress_mode::emit_stderr(&format!(
@@ -491,6 +650,7 @@ pub fn try_imagemagick_fallback(
                                     16,
                                     true,
                                     apple_compat,
+                                    strip,
                                 ) {
                                     Ok(()) => {
                                         crate::progress_mode::emit_stderr(&format!(
@@ -530,6 +690,7 @@ pub fn try_imagemagick_fallback(
                         8,
                         false,
                         apple_compat,
+                        strip,
                     ) {
                         Ok(()) => {
                             crate::progress_mode::emit_stderr(&format!(
@@ -560,6 +721,7 @@ pub fn try_imagemagick_fallback(
                         16,
                         true,
                         apple_compat,
+                        strip,
                     ) {
                         Ok(()) => {
                             crate::progress_mode::emit_stderr(&format!(
@@ -594,6 +756,7 @@ pub fn try_imagemagick_fallback(
                     16,
                     false,
                     apple_compat,
+                    strip,
                 ) {
                     Ok(()) => {
                         crate::progress_mode::emit_stderr(&format!(
@@ -623,6 +786,56 @@ pub fn try_imagemagick_fallback(
     ))
 }
 
+/// Losslessly rotate/flip a JPEG whose EXIF Orientation is not 1 (`jpegtran -perfect`) and
+/// reset the tag, so the pixels themselves are upright.
+/// Returns (temp_path, guard), or None when the JPEG is already upright, jpegtran is missing,
+/// or the transform would not be lossless (partial edge MCUs); the tag is then left as is.
+pub fn normalize_jpeg_orientation_to_temp(
+    path: &Path,
+) -> std::io::Result<Option<(std::path::PathBuf, tempfile::NamedTempFile)>> {
+    use crate::image_jpeg_analysis::{
+        extract_exif_orientation, jpegtran_orientation_args, reset_exif_orientation,
+    };
+
+    let data = std::fs::read(path)?;
+    let Some(transform) = extract_exif_orientation(&data).and_then(jpegtran_orientation_args)
+    else {
+        return Ok(None);
+    };
+    if which::which("jpegtran").is_err() {
+        crate::progress_mode::emit_stderr(
+            "   ⚠️  jpegtran not found; keeping EXIF orientation instead of rotating",
+        );
+        return Ok(None);
+    }
+
+    let temp = tempfile::Builder::new()
+        .suffix(".jpg")
+        .tempfile_in(crate::conversion::scratch_dir())?;
+    let output = Command::new("jpegtran")
+        .args(["-copy", "all", "-perfect"])
+        .args(transform)
+        .arg("-outfile")
+        .arg(temp.path())
+        .arg(crate::safe_path_arg(path).as_ref())
+        .output()?;
+    if !output.status.success() {
+        crate::progress_mode::emit_stderr(&format!(
+            "   ⚠️  Orientation not normalized (lossless {} impossible); keeping EXIF orientation",
+            transform.join(" ")
+        ));
+        return Ok(None);
+    }
+
+    let mut rotated = std::fs::read(temp.path())?;
+    if !reset_exif_orientation(&mut rotated) {
+        return Ok(None);
+    }
+    std::fs::write(temp.path(), &rotated)?;
+    let temp_path = temp.path().to_path_buf();
+    Ok(Some((temp_path, temp)))
+}
+
 /// Losslessly strip trailing data after JPEG EOI (0xFF 0xD9) so cjxl can use bitstream reconstruction.
 /// Returns (temp_path, guard) if tail was stripped, or None if no tail or strip failed.
 pub fn strip_jpeg_tail_to_temp(
@@ -645,7 +858,9 @@ pub fn strip_jpeg_tail_to_temp(
     if end == data.len() {
         return Ok(None);
     }
-    let temp = tempfile::Builder::new().suffix(".jpg").tempfile()?;
+    let temp = tempfile::Builder::new()
+        .suffix(".jpg")
+        .tempfile_in(crate::conversion::scratch_dir())?;
     std::fs::write(temp.path(), &data[..end])?;
     let temp_path = temp.path().to_path_buf();
     Ok(Some((temp_path, temp)))
@@ -21,6 +21,8 @@ pub mod codecs;
 pub mod conversion;
 pub mod crf_constants;
 pub mod date_analysis;
+pub mod doctor;
+pub mod encoder_comparison;
 pub mod error_handler;
 pub mod explore_strategy;
 pub mod ffmpeg_process;
@@ -29,6 +31,7 @@ pub mod flag_validator;
 pub mod float_compare;
 pub mod gpu_accel;
 pub mod image_quality_detector;
+pub mod image_sequence;
 pub mod lru_cache;
 pub mod metadata;
 pub mod modern_ui;
@@ -49,6 +52,7 @@ pub mod video_explorer;
 // #[cfg(test)]
 // mod image_detection_tests;
 pub mod video_quality_detector;
+pub mod watch_folder;
 pub mod xmp_merger;
 
 pub mod path_safety;
@@ -66,6 +70,11 @@ pub mod system_memory;
 pub mod types;
 
 pub mod progress_mode;
+pub mod progress_sink;
+pub use progress_sink::{JsonEventSink, ProgressSink};
+
+pub mod cancellation;
+pub use cancellation::{CancellationToken, Cancelled};
 
 pub mod ctrlc_guard;
 
@@ -104,8 +113,39 @@ pub mod conversion_types;
 pub mod video_detection;
 
 pub mod media_passthrough;
-pub use media_passthrough::{audio_args_for_container, subtitle_args_for_container};
+pub use media_passthrough::{
+    audio_args_for_container, audio_args_for_encode, audio_transcode, audio_transcode_args,
+    container_of, is_bloated_audio, is_mp4_remux_candidate, mp4_remux_args, parse_audio_bitrate,
+    parse_audio_codec, remux_args, set_audio_transcode, subtitle_args_for_container, AudioCodec,
+    AudioTranscode, DEFAULT_AUDIO_BITRATE_KBPS,
+};
+
+pub mod output_archive;
+pub use output_archive::{
+    archive_output, finish_output_archive_with_log, open_output_archive, set_output_archive,
+    OutputArchive,
+};
+
+pub mod verify_after;
+pub use verify_after::{
+    finish_verify_after, finish_verify_after_with_log, record_verify_output, set_verify_after,
+    verify_after_enabled, verify_output_readable, VerifyAfterReport,
+};
+
+pub mod dedup;
+pub use dedup::{content_hash, ContentHash, DedupReport, Deduper};
+
+pub mod file_probe_cache;
+pub use file_probe_cache::FileProbeCache;
+
+pub mod phase_profile;
+pub use phase_profile::{
+    begin_file_profile, finish_file_profile, finish_file_profile_with_log, profile_enabled,
+    set_profile, PhaseTimings, ProfilePhase,
+};
 
+pub mod animated_apng;
+pub mod animated_webp;
 pub mod gif_meme_score;
 pub mod image_analyzer;
 pub mod image_detection;
@@ -116,27 +156,31 @@ pub mod image_metrics;
 pub mod image_recommender;
 pub mod img_errors;
 pub mod live_photo;
+pub mod routing_overrides;
 pub use gif_meme_score::{
     gif_meta_from_probe, gif_meta_from_probe_with_path, scan_gif_headers, should_keep_as_gif,
     GifMeta, MemeScore,
 };
+pub use image_jpeg_analysis::{parse_jpeg_strategy, JpegStrategy};
 
 pub use batch::*;
 pub use codecs::*;
 pub use conversion::*;
+pub use conversion_types::SkipReason;
 pub use date_analysis::{
     analyze_directory, print_analysis, DateAnalysisConfig, DateAnalysisResult, DateSource,
     FileDateInfo,
 };
 pub use ffprobe::{
     detect_bit_depth, get_duration, get_frame_count, is_ffprobe_available, parse_frame_rate,
-    probe_video, FFprobeError, FFprobeResult,
+    probe_integrity, probe_video, FFprobeError, FFprobeResult, MediaIntegrity,
 };
 pub use metadata::{
-    apply_saved_timestamps_to_dst, copy_metadata, preserve_directory_metadata,
-    preserve_directory_metadata_with_log, preserve_metadata, preserve_pro,
-    restore_directory_timestamps, restore_timestamps_from_source_to_output,
-    save_directory_timestamps,
+    apply_saved_timestamps_to_dst, copy_metadata, copy_metadata_with_report,
+    preserve_directory_metadata, preserve_directory_metadata_with_log, preserve_metadata,
+    preserve_pro, provenance_enabled, reset_orientation_tag, restore_directory_timestamps,
+    restore_timestamps_from_source_to_output, save_directory_timestamps, set_provenance,
+    write_provenance, write_quality_xattrs, Provenance, StripPolicy,
 };
 pub use progress::{
     create_compact_progress_bar, create_detailed_progress_bar, create_multi_progress,
@@ -145,11 +189,14 @@ pub use progress::{
     ExploreProgress, FixedBottomProgress, GlobalProgressManager, ProgressStats, SmartProgressBar,
 };
 pub use quality_matcher::{
-    calculate_av1_crf, calculate_av1_crf_with_options, calculate_hevc_crf,
-    calculate_hevc_crf_with_options, calculate_jxl_distance, calculate_jxl_distance_with_options,
-    from_image_analysis, from_video_detection, is_apple_incompatible_video_codec,
-    log_quality_analysis, parse_source_codec, should_keep_apple_fallback_hevc_output,
-    should_keep_best_effort_output_on_failure, should_skip_image_format, should_skip_video_codec,
+    calculate_av1_crf, calculate_av1_crf_with_options, calculate_h264_crf,
+    calculate_h264_crf_with_options, calculate_hevc_crf, calculate_hevc_crf_with_options,
+    calculate_jxl_distance, calculate_jxl_distance_with_options, crf_doubling_step,
+    estimate_encoded_video_bytes, from_image_analysis, from_video_detection,
+    is_apple_incompatible_video_codec, log_quality_analysis, parse_source_codec,
+    predict_crf_with_confidence, reconvert_enabled, set_reconvert,
+    should_keep_apple_fallback_hevc_output, should_keep_best_effort_output_on_failure,
+    should_skip_image_format, should_skip_same_codec_reencode, should_skip_video_codec,
     should_skip_video_codec_apple_compat, AnalysisDetails, ContentType, EncoderType, MatchMode,
     MatchedQuality, QualityAnalysis, QualityBias, SkipDecision, SourceCodec, VideoAnalysisBuilder,
 };
@@ -164,33 +211,53 @@ pub use image_quality_detector::{
 };
 
 pub use video_quality_detector::{
-    analyze_video_quality, analyze_video_quality_from_detection, log_media_info_for_quality,
+    analyze_video_quality, analyze_video_quality_from_detection, detect_grayscale_video,
+    estimate_film_grain, log_media_info_for_quality,
     to_quality_analysis as video_to_quality_analysis, ChromaSubsampling, CompressionLevel,
     VideoCodecType, VideoContentType, VideoQualityAnalysis,
 };
 
 pub use video_explorer::{
     calculate_metadata_margin, can_compress_with_metadata, compression_target_size,
-    detect_metadata_size, explore_av1, explore_av1_compress_only,
+    detect_metadata_size, encode_command_preview, explore_av1, explore_av1_compress_only,
     explore_av1_compress_with_quality, explore_av1_quality_match, explore_av1_size_only,
     explore_compress_only, explore_compress_with_quality, explore_hevc, explore_hevc_compress_only,
     explore_hevc_compress_with_quality, explore_hevc_quality_match, explore_hevc_size_only,
     explore_precise_quality_match, explore_precise_quality_match_with_compression,
-    explore_quality_match, explore_size_only, precision, precision::SearchPhase,
-    precision::ThreePhaseSearch, pure_video_size, verify_compression_precise,
-    verify_compression_simple, CompressionVerifyStrategy, EncoderPreset, ExploreConfig,
-    ExploreMode, ExploreResult, IterationMetrics, QualityThresholds, SsimSource,
-    TransparencyReport, VideoEncoder, VideoExplorer, METADATA_MARGIN_MAX, METADATA_MARGIN_MIN,
-    METADATA_MARGIN_PERCENT, SMALL_FILE_THRESHOLD,
+    explore_quality_match, explore_size_only, explore_with_mode, precision,
+    precision::set_global_ultimate_budget, precision::BudgetCap, precision::SearchPhase,
+    precision::ThreePhaseSearch, precision::UltimateBudget, pure_video_size,
+    verify_compression_precise, verify_compression_simple, CompressionVerifyStrategy,
+    EncodeSettings, EncoderPreset, ExploreConfig, ExploreMode, ExploreResult, GpuHandoff,
+    IterationMetrics, QualityThresholds, SsimSource, TransparencyReport, VideoEncoder,
+    VideoExplorer, METADATA_MARGIN_MAX, METADATA_MARGIN_MIN, METADATA_MARGIN_PERCENT,
+    SMALL_FILE_THRESHOLD,
 };
 
 pub use video_explorer::{
-    explore_compress_only_gpu, explore_compress_with_quality_gpu,
+    crf_prediction, deterministic_args, deterministic_x265_cli_args, deterministic_x265_params,
+    encode_crf_prediction, encode_film_grain, encode_frame_rate_mode, encode_grayscale,
+    enforce_max_output_size, explore_compress_only_gpu, explore_compress_with_quality_gpu,
     explore_precise_quality_match_gpu, explore_precise_quality_match_with_compression_gpu,
-    explore_quality_match_gpu, explore_size_only_gpu,
+    explore_quality_match_gpu, explore_size_only_gpu, film_grain_level, force_10bit_enabled,
+    gpu_cpu_fallback_enabled, gray_pix_fmt, grayscale_encode_enabled, hevc_pix_fmt,
+    hevc_pix_fmt_for, hevc_profile_args, set_force_10bit, set_gpu_cpu_fallback, CrfPredictionGuard,
+    FrameRateModeGuard, SsimSamplingStrategy, FILM_GRAIN_MAX,
 };
 
-pub use checkpoint::{safe_delete_original, verify_output_integrity, CheckpointManager};
+pub use video_explorer::PixelFormat;
+
+pub use video_explorer::{av1_backend, libaom_args, set_av1_backend, Av1Backend};
+
+pub use video_explorer::{
+    calculate_content_thresholds, content_crf_cap, current_search_content_type,
+    search_content_type, SearchContentGuard,
+};
+
+pub use checkpoint::{
+    safe_delete_original, safe_delete_original_lossless, verify_lossless_output,
+    verify_output_decodable, verify_output_integrity, CheckpointManager,
+};
 
 pub use quality_verifier_enhanced::{
     verify_after_encode, verify_output_file, EnhancedVerifyResult, VerifyOptions,
@@ -198,18 +265,25 @@ pub use quality_verifier_enhanced::{
 };
 
 pub use xmp_merger::{
-    merge_xmp_for_copied_file, MergeResult, MergeSummary, XmpFile, XmpMerger, XmpMergerConfig,
+    find_sidecars, find_sidecars_with, is_sidecar_extension, merge_xmp_for_copied_file,
+    parse_sidecar_extensions, parse_sidecar_mode, parse_xmp_merge_policy, set_sidecar_extensions,
+    set_sidecar_mode, set_xmp_merge_policy, sidecar_extensions, sidecar_mode, xmp_merge_policy,
+    MergeResult, MergeSummary, SidecarMode, XmpConflict, XmpFile, XmpMergePolicy, XmpMerger,
+    XmpMergerConfig,
 };
 
 pub use flag_validator::{
-    print_flag_help, validate_flags, validate_flags_result, validate_flags_result_with_ultimate,
-    validate_flags_with_ultimate, FlagMode, FlagValidation,
+    parse_byte_size, parse_crf_bias, parse_entropy_threshold, parse_min_animation_duration,
+    parse_min_ssim, print_flag_help, validate_flags, validate_flags_result,
+    validate_flags_result_with_ultimate, validate_flags_with_ultimate, FlagMode, FlagValidation,
 };
 
 pub use gpu_accel::{
-    estimate_cpu_search_center, get_cpu_search_range_from_gpu, gpu_boundary_to_cpu_range,
-    gpu_coarse_search, gpu_coarse_search_with_log, CrfMapping, GpuAccel, GpuCoarseConfig,
-    GpuCoarseResult, GpuEncoder, GpuType,
+    blend_with_prediction, confidence_search_margin, estimate_cpu_search_center,
+    estimate_cpu_search_center_with_confidence, get_cpu_search_range_from_gpu,
+    gpu_boundary_to_cpu_range, gpu_boundary_to_cpu_range_with_confidence, gpu_coarse_search,
+    gpu_coarse_search_with_log, CrfMapping, GpuAccel, GpuCoarseConfig, GpuCoarseResult, GpuEncoder,
+    GpuType,
 };
 
 pub use video_explorer::{
@@ -256,7 +330,15 @@ pub use float_compare::{
     SSIM_EPSILON as FLOAT_SSIM_EPSILON,
 };
 
-pub use path_validator::{validate_path, validate_paths, PathValidationError};
+pub use doctor::{run_doctor, DoctorCheck, DoctorReport};
+pub use encoder_comparison::{
+    compare_encoders, CompareCodec, EncoderComparison, EncoderComparisonConfig, EncoderSample,
+    DEFAULT_COMPARE_SECONDS,
+};
+pub use image_sequence::{
+    assemble_lossless_intermediate, detect_image_sequence, parse_fps, ImageSequence,
+};
+pub use path_validator::{validate_file_name, validate_path, validate_paths, PathValidationError};
 
 pub use crf_constants::{
     AV1_CRF_DEFAULT, AV1_CRF_MAX, AV1_CRF_MIN, AV1_CRF_PRACTICAL_MAX, AV1_CRF_VISUALLY_LOSSLESS,
@@ -264,6 +346,7 @@ pub use crf_constants::{
     EMERGENCY_MAX_ITERATIONS as CRF_EMERGENCY_MAX_ITERATIONS, HEVC_CRF_DEFAULT, HEVC_CRF_MAX,
     HEVC_CRF_MIN, HEVC_CRF_PRACTICAL_MAX, HEVC_CRF_VISUALLY_LOSSLESS, NORMAL_MAX_ITERATIONS,
     VP9_CRF_DEFAULT, VP9_CRF_MAX, VP9_CRF_MIN, X264_CRF_DEFAULT, X264_CRF_MAX, X264_CRF_MIN,
+    X264_CRF_OFFSET_FROM_HEVC,
 };
 
 pub use ffprobe_json::{extract_color_info as ffprobe_extract_color_info, ColorInfo};
@@ -271,7 +354,7 @@ pub use ffprobe_json::{extract_color_info as ffprobe_extract_color_info, ColorIn
 pub use hdr_decode::{decode_hdr_image_to_png16, needs_hdr_decode};
 
 pub use hdr_utils::{
-    color_info_to_cicp, color_info_to_ffmpeg_args, color_info_to_x265_hdr_params,
+    color_info_to_cicp, color_info_to_ffmpeg_args, color_info_to_x265_hdr_params, color_range_args,
     dv_x265_profile_string, extract_dv_rpu, extract_hevc_bitstream, get_hdr_pix_fmt,
     is_dovi_tool_available, should_use_hdr_decode,
 };
@@ -294,15 +377,20 @@ pub use types::{
 pub use app_error::AppError;
 
 pub use file_copier::{
-    copy_unsupported_files, count_files as count_all_files, verify_output_completeness, CopyResult,
-    FileStats, VerifyResult, IMAGE_EXTENSIONS_ANALYZE, IMAGE_EXTENSIONS_FOR_CONVERT,
-    SIDECAR_EXTENSIONS, SUPPORTED_IMAGE_EXTENSIONS, SUPPORTED_VIDEO_EXTENSIONS,
+    copy_unsupported_files, count_files as count_all_files, verify_output_completeness,
+    verify_output_completeness_excluding, CopyResult, FileStats, VerifyResult,
+    IMAGE_EXTENSIONS_ANALYZE, IMAGE_EXTENSIONS_FOR_CONVERT, SIDECAR_EXTENSIONS,
+    SUPPORTED_IMAGE_EXTENSIONS, SUPPORTED_VIDEO_EXTENSIONS,
 };
 pub use smart_file_copier::{
-    copy_on_skip_or_fail, fix_extension_if_mismatch, smart_copy_with_structure,
+    copy_on_skip_or_fail, copy_skipped_enabled, copy_through, fix_extension_if_mismatch,
+    set_copy_skipped, smart_copy_with_structure,
 };
 
-pub use live_photo::is_live_photo;
+pub use live_photo::{
+    is_live_photo, live_photo_mode, parse_live_photo_mode, set_live_photo_mode, LivePhotoMode,
+};
+pub use routing_overrides::{OverrideTarget, RoutingAction, RoutingOverrides};
 
 pub use file_sorter::{
     sort_by_name, sort_by_size_ascending, sort_by_size_descending, FileInfo, FileSorter,
@@ -339,7 +427,7 @@ pub use common_utils::{
     execute_command_with_logging, extract_digits, extract_suggested_extension,
     format_command_string, get_command_version, get_extension_lowercase, has_extension,
     is_command_available, is_hidden_file, normalize_path_string, parse_float_or_default,
-    truncate_string,
+    shell_command_string, truncate_string,
 };
 
 pub use thread_manager::{
@@ -1,8 +1,54 @@
 //! Live Photo Detection Module
 //!
-//! Detects Apple Live Photos by checking for companion MOV files
+//! Detects Apple Live Photos by checking for companion MOV files, and keeps
+//! the `com.apple.quicktime.content.identifier` linkage when both halves are converted.
+
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// What to do with both halves of a Live Photo pair (`--live-photos`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LivePhotoMode {
+    /// Leave the still and the motion untouched (copied through in adjacent-output mode).
+    #[default]
+    Skip,
+    /// Convert the still and the motion like any other file, carrying the content
+    /// identifier into both outputs so Photos still pairs them on import.
+    Convert,
+}
+
+/// Parse `--live-photos`: `skip` or `convert`.
+pub fn parse_live_photo_mode(value: &str) -> Result<LivePhotoMode, String> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "skip" => Ok(LivePhotoMode::Skip),
+        "convert" => Ok(LivePhotoMode::Convert),
+        other => Err(format!(
+            "Invalid Live Photo mode '{}' (expected skip or convert)",
+            other
+        )),
+    }
+}
 
-use std::path::Path;
+static LIVE_PHOTO_MODE: AtomicU8 = AtomicU8::new(0);
+
+/// Process-wide Live Photo mode. The image and video tools must agree on it so a pair is
+/// never split between a skipped half and a converted half.
+pub fn set_live_photo_mode(mode: LivePhotoMode) {
+    let raw = match mode {
+        LivePhotoMode::Skip => 0,
+        LivePhotoMode::Convert => 1,
+    };
+    LIVE_PHOTO_MODE.store(raw, Ordering::Relaxed);
+}
+
+pub fn live_photo_mode() -> LivePhotoMode {
+    match LIVE_PHOTO_MODE.load(Ordering::Relaxed) {
+        1 => LivePhotoMode::Convert,
+        _ => LivePhotoMode::Skip,
+    }
+}
 
 /// Check if a file is part of a Live Photo pair
 ///
@@ -11,52 +57,90 @@ use std::path::Path;
 /// - A companion MOV video file (e.g., IMG_1234.MOV)
 ///
 /// This function checks if the given file has a companion file with the same
-/// stem but different extension (.mov/.MOV for images, .heic/.HEIC for videos)
+/// stem but different extension (.mov/.MOV for images, .heic/.HEIC for videos).
+/// When both halves carry a content identifier, they must match.
 pub fn is_live_photo(path: &Path) -> bool {
-    let Some(ext) = path.extension().and_then(|e| e.to_str()) else {
+    let Some(companion) = live_photo_companion(path) else {
         return false;
     };
+    match (content_identifier(path), content_identifier(&companion)) {
+        (Some(a), Some(b)) => a == b,
+        _ => true,
+    }
+}
 
-    let ext_lower = ext.to_lowercase();
-    let stem = path.file_stem().and_then(|s| s.to_str());
-    let parent = path.parent();
+/// The other half of a Live Photo pair by file name alone (same stem, same directory).
+pub fn live_photo_companion(path: &Path) -> Option<PathBuf> {
+    let ext = path.extension().and_then(|e| e.to_str())?;
 
-    let Some(stem) = stem else {
-        return false;
-    };
-    let Some(parent) = parent else {
-        return false;
+    let ext_lower = ext.to_lowercase();
+    let stem = path.file_stem().and_then(|s| s.to_str())?;
+    let parent = path.parent()?;
+
+    let companion_exts: &[&str] = match ext_lower.as_str() {
+        // HEIC/HEIF still: look for the companion .mov or .MOV
+        "heic" | "heif" | "hif" => &["mov", "MOV"],
+        // MOV motion: look for the companion HEIC/HEIF still
+        "mov" => &["heic", "HEIC", "heif", "HEIF"],
+        _ => return None,
     };
+    companion_exts
+        .iter()
+        .map(|ext| parent.join(format!("{}.{}", stem, ext)))
+        .find(|p| p.exists())
+}
 
-    // Check if this is a HEIC/HEIF file with a companion MOV
-    if matches!(ext_lower.as_str(), "heic" | "heif" | "hif") {
-        // Look for companion .mov or .MOV file
-        let mov_path = parent.join(format!("{}.mov", stem));
-        let mov_upper_path = parent.join(format!("{}.MOV", stem));
+/// Apple's pairing key: MakerNotes `ContentIdentifier` in the still, the
+/// `com.apple.quicktime.content.identifier` Keys tag in the motion. `None` without ExifTool.
+pub fn content_identifier(path: &Path) -> Option<String> {
+    let output = Command::new("exiftool")
+        .arg("-s3")
+        .arg("-ContentIdentifier")
+        .arg(crate::safe_path_arg(path).as_ref())
+        .output()
+        .ok()?;
+    let id = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (!id.is_empty()).then_some(id)
+}
 
-        if mov_path.exists() || mov_upper_path.exists() {
-            return true;
-        }
+/// After a Live Photo half was converted, make sure its output still carries the pair's
+/// content identifier. The general ExifTool copy usually keeps it; when it did not (JXL has
+/// no Apple MakerNotes until they are copied as a block, MP4 needs the Keys tag), write it.
+pub fn relink_content_identifier(src: &Path, dst: &Path) -> io::Result<()> {
+    if live_photo_companion(src).is_none() {
+        return Ok(());
     }
-
-    // Check if this is a MOV file with a companion HEIC/HEIF
-    if ext_lower == "mov" {
-        // Look for companion HEIC/HEIF files
-        let heic_path = parent.join(format!("{}.heic", stem));
-        let heic_upper_path = parent.join(format!("{}.HEIC", stem));
-        let heif_path = parent.join(format!("{}.heif", stem));
-        let heif_upper_path = parent.join(format!("{}.HEIF", stem));
-
-        if heic_path.exists()
-            || heic_upper_path.exists()
-            || heif_path.exists()
-            || heif_upper_path.exists()
-        {
-            return true;
-        }
+    let Some(id) = content_identifier(src) else {
+        return Ok(());
+    };
+    if content_identifier(dst).as_deref() == Some(id.as_str()) {
+        return Ok(());
     }
 
-    false
+    let mut cmd = Command::new("exiftool");
+    if crate::common_utils::get_extension_lowercase(src) == "mov" {
+        cmd.arg(format!("-Keys:ContentIdentifier={}", id));
+    } else {
+        cmd.arg("-tagsfromfile")
+            .arg(crate::safe_path_arg(src).as_ref())
+            .arg("-MakerNotes");
+    }
+    cmd.arg("-overwrite_original")
+        .arg("-P")
+        .arg("-q")
+        .arg("-m")
+        .arg(crate::safe_path_arg(dst).as_ref());
+    cmd.output()?;
+
+    if content_identifier(dst).as_deref() == Some(id.as_str()) {
+        Ok(())
+    } else {
+        Err(io::Error::other(format!(
+            "{} has no ContentIdentifier {}; Photos may not pair it with its companion",
+            dst.display(),
+            id
+        )))
+    }
 }
 
 #[cfg(test)]
@@ -107,4 +191,26 @@ mod tests {
         assert!(is_live_photo(&heic_lower));
         assert!(is_live_photo(&mov_upper));
     }
+
+    #[test]
+    fn test_companion_and_mode_parsing() {
+        let temp_dir = TempDir::new().unwrap();
+        let base_path = temp_dir.path();
+
+        let heic = base_path.join("IMG_0002.HEIC");
+        let mov = base_path.join("IMG_0002.mov");
+        File::create(&heic).unwrap();
+        File::create(&mov).unwrap();
+
+        assert_eq!(live_photo_companion(&heic), Some(mov.clone()));
+        assert_eq!(live_photo_companion(&mov), Some(heic));
+        assert_eq!(live_photo_companion(&base_path.join("IMG_0002.jpg")), None);
+
+        assert_eq!(parse_live_photo_mode("skip"), Ok(LivePhotoMode::Skip));
+        assert_eq!(
+            parse_live_photo_mode(" Convert "),
+            Ok(LivePhotoMode::Convert)
+        );
+        assert!(parse_live_photo_mode("split").is_err());
+    }
 }
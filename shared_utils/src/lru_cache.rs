@@ -131,6 +131,11 @@ impl<K: Hash + Eq + Clone, V: Clone> LruCache<K, V> {
     pub fn clear(&mut self) {
         self.entries.clear();
     }
+
+    /// Drop every entry for which `keep` returns false.
+    pub fn retain(&mut self, mut keep: impl FnMut(&K, &V) -> bool) {
+        self.entries.retain(|k, entry| keep(k, &entry.value));
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
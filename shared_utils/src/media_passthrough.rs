@@ -2,11 +2,19 @@
 //!
 //! These functions determine whether audio/subtitle streams can be copied
 //! directly (`-c:a copy`, `-c:s copy`) or must be transcoded for the target
-//! container format (MP4/MOV vs MKV).
+//! container format (MP4/MOV, MKV or WebM).
+//!
+//! Also hosts the remux-only path: a source already in the target codec but in
+//! another container is stream-copied into MP4 instead of being re-encoded.
+
+use crate::video_detection::{DetectedCodec, VideoDetectionResult};
+use std::path::Path;
+use std::sync::RwLock;
 
 /// Determine FFmpeg audio arguments for the target container.
 ///
 /// - MKV: always `-c:a copy` (supports every codec).
+/// - WebM: `-c:a copy` for opus/vorbis; anything else is transcoded to Opus 160 kbps.
 /// - MP4/MOV: `-c:a copy` unless the codec is incompatible (opus, vorbis).
 ///   Incompatible codecs are transcoded to AAC 256 kbps.
 /// - No audio (`None` codec): returns `-an`.
@@ -22,9 +30,21 @@ pub fn audio_args_for_container(audio_codec: Option<&str>, container: &str) -> V
         return vec!["-c:a".to_string(), "copy".to_string()];
     }
 
-    // MP4/MOV: check for incompatible codecs
-    let incompatible = codec.contains("opus") || codec.contains("vorbis");
-    if incompatible {
+    let is_webm_audio = codec.contains("opus") || codec.contains("vorbis");
+    if container.eq_ignore_ascii_case("webm") {
+        if is_webm_audio {
+            return vec!["-c:a".to_string(), "copy".to_string()];
+        }
+        return vec![
+            "-c:a".to_string(),
+            "libopus".to_string(),
+            "-b:a".to_string(),
+            "160k".to_string(),
+        ];
+    }
+
+    // MP4/MOV: opus/vorbis are incompatible
+    if is_webm_audio {
         vec![
             "-c:a".to_string(),
             "aac".to_string(),
@@ -36,10 +56,140 @@ pub fn audio_args_for_container(audio_codec: Option<&str>, container: &str) -> V
     }
 }
 
+/// `--audio-codec` target for re-encoding bloated audio.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AudioCodec {
+    Opus,
+    Aac,
+}
+
+impl AudioCodec {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Opus => "opus",
+            Self::Aac => "aac",
+        }
+    }
+
+    pub fn ffmpeg_encoder(self) -> &'static str {
+        match self {
+            Self::Opus => "libopus",
+            Self::Aac => "aac",
+        }
+    }
+}
+
+/// `--audio-codec` + `--audio-bitrate`: re-encode uncompressed/lossless or oversized audio
+/// instead of copying it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AudioTranscode {
+    pub codec: AudioCodec,
+    pub bitrate_kbps: u32,
+}
+
+/// Default `--audio-bitrate` when only `--audio-codec` is given.
+pub const DEFAULT_AUDIO_BITRATE_KBPS: u32 = 128;
+
+/// Parse `--audio-codec`: `opus` or `aac`.
+pub fn parse_audio_codec(value: &str) -> Result<AudioCodec, String> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "opus" => Ok(AudioCodec::Opus),
+        "aac" => Ok(AudioCodec::Aac),
+        other => Err(format!(
+            "Invalid audio codec '{}' (expected opus or aac)",
+            other
+        )),
+    }
+}
+
+/// Parse `--audio-bitrate`: kbps, with an optional `k` suffix (`128k`, `96`). 16–512.
+pub fn parse_audio_bitrate(value: &str) -> Result<u32, String> {
+    let trimmed = value.trim();
+    let digits = trimmed.strip_suffix(['k', 'K']).unwrap_or(trimmed);
+    let kbps: u32 = digits
+        .parse()
+        .map_err(|_| format!("Invalid audio bitrate '{}' (expected e.g. 128k)", trimmed))?;
+    if !(16..=512).contains(&kbps) {
+        return Err(format!("Audio bitrate {}k out of range (16k–512k)", kbps));
+    }
+    Ok(kbps)
+}
+
+static AUDIO_TRANSCODE: RwLock<Option<AudioTranscode>> = RwLock::new(None);
+
+pub fn set_audio_transcode(transcode: Option<AudioTranscode>) {
+    if let Ok(mut current) = AUDIO_TRANSCODE.write() {
+        *current = transcode;
+    }
+}
+
+pub fn audio_transcode() -> Option<AudioTranscode> {
+    AUDIO_TRANSCODE.read().ok().and_then(|current| *current)
+}
+
+/// True when the source audio is worth re-encoding at `target_kbps`: uncompressed PCM,
+/// a lossless codec, or a lossy stream more than twice the target bitrate.
+pub fn is_bloated_audio(codec: &str, bit_rate: Option<u64>, target_kbps: u32) -> bool {
+    let codec = codec.to_ascii_lowercase();
+    let uncompressed_or_lossless = codec.starts_with("pcm_")
+        || matches!(
+            codec.as_str(),
+            "flac" | "alac" | "wavpack" | "truehd" | "mlp" | "ape" | "tta"
+        );
+    uncompressed_or_lossless || bit_rate.is_some_and(|b| b > u64::from(target_kbps) * 2_000)
+}
+
+/// Audio args for a lossy encode: the `--audio-codec` re-encode when it is enabled and the
+/// source is bloated (see [`is_bloated_audio`]), otherwise [`audio_args_for_container`].
+/// WebM keeps its own Opus rule when AAC was requested; MOV cannot carry Opus, so AAC is
+/// used there at the same bitrate.
+pub fn audio_args_for_encode(
+    audio_codec: Option<&str>,
+    audio_bit_rate: Option<u64>,
+    container: &str,
+) -> Vec<String> {
+    audio_transcode_args(audio_codec, audio_bit_rate, container)
+        .unwrap_or_else(|| audio_args_for_container(audio_codec, container))
+}
+
+/// Just the `--audio-codec` re-encode args; `None` when disabled or not worthwhile.
+pub fn audio_transcode_args(
+    audio_codec: Option<&str>,
+    audio_bit_rate: Option<u64>,
+    container: &str,
+) -> Option<Vec<String>> {
+    transcode_audio_args(audio_transcode(), audio_codec, audio_bit_rate, container)
+}
+
+fn transcode_audio_args(
+    transcode: Option<AudioTranscode>,
+    audio_codec: Option<&str>,
+    audio_bit_rate: Option<u64>,
+    container: &str,
+) -> Option<Vec<String>> {
+    let transcode = transcode?;
+    let source = audio_codec.filter(|c| !c.is_empty())?;
+    if !is_bloated_audio(source, audio_bit_rate, transcode.bitrate_kbps) {
+        return None;
+    }
+    let codec = match transcode.codec {
+        AudioCodec::Aac if container.eq_ignore_ascii_case("webm") => return None,
+        AudioCodec::Opus if container.eq_ignore_ascii_case("mov") => AudioCodec::Aac,
+        codec => codec,
+    };
+    Some(vec![
+        "-c:a".to_string(),
+        codec.ffmpeg_encoder().to_string(),
+        "-b:a".to_string(),
+        format!("{}k", transcode.bitrate_kbps),
+    ])
+}
+
 /// Determine FFmpeg subtitle arguments for the target container.
 ///
 /// - No subtitles: returns empty vec (nothing to map).
 /// - MKV: `-c:s copy` (supports all subtitle formats).
+/// - WebM: text-based subs → `-c:s webvtt`; image-based subs → skip.
 /// - MP4/MOV: text-based subs → `-c:s mov_text`; image-based subs → skip
 ///   (MP4 doesn't support bitmap subtitle tracks like dvd_subtitle / hdmv_pgs_subtitle).
 pub fn subtitle_args_for_container(
@@ -56,7 +206,7 @@ pub fn subtitle_args_for_container(
         return vec!["-c:s".to_string(), "copy".to_string()];
     }
 
-    // MP4/MOV: only text-based subtitles are supported (as mov_text).
+    // MP4/MOV and WebM: only text-based subtitles are supported (as mov_text / webvtt).
     let codec_lower = subtitle_codec.map(|s| s.to_lowercase()).unwrap_or_default();
     let is_text_based = matches!(
         codec_lower.as_str(),
@@ -64,17 +214,265 @@ pub fn subtitle_args_for_container(
     );
 
     if is_text_based {
-        vec!["-c:s".to_string(), "mov_text".to_string()]
+        let text_codec = if container.eq_ignore_ascii_case("webm") {
+            "webvtt"
+        } else {
+            "mov_text"
+        };
+        vec!["-c:s".to_string(), text_codec.to_string()]
     } else {
-        // Image-based subtitles (dvd_subtitle, hdmv_pgs_subtitle, etc.) cannot go into MP4.
+        // Image-based subtitles (dvd_subtitle, hdmv_pgs_subtitle, etc.) cannot go into MP4/WebM.
         // Drop them silently rather than failing the encode.
         vec!["-sn".to_string()]
     }
 }
 
+/// True when the video stream is already `target` (HEVC or AV1, both MP4-compatible) but sits
+/// in a container other than MP4/MOV, so only the container has to change.
+pub fn is_mp4_remux_candidate(detection: &VideoDetectionResult, target: &DetectedCodec) -> bool {
+    if &detection.codec != target || !matches!(target, DetectedCodec::H265 | DetectedCodec::AV1) {
+        return false;
+    }
+    // ffprobe format_name, e.g. "matroska,webm" or "mov,mp4,m4a,3gp,3g2,mj2".
+    let format = detection.format.to_lowercase();
+    !format.is_empty() && !format.contains("mp4") && !format.contains("mov")
+}
+
+/// Container name for `audio_args_for_container` / `subtitle_args_for_container`, taken from
+/// the output extension (anything other than MKV/WebM follows the MP4 rules).
+pub fn container_of(output: &Path) -> &'static str {
+    let ext = output
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_ascii_lowercase();
+    match ext.as_str() {
+        "mkv" => "mkv",
+        "webm" => "webm",
+        _ => "mp4",
+    }
+}
+
+/// FFmpeg arguments that stream-copy the source into an MP4 at `output`.
+/// Every video/audio/subtitle track is kept; the video bitstream is copied unchanged, and
+/// audio/subtitles follow the MP4 rules of `audio_args_for_container` / `subtitle_args_for_container`.
+pub fn mp4_remux_args(
+    detection: &VideoDetectionResult,
+    output: &Path,
+    video_tag: Option<&str>,
+    strip: crate::metadata::StripPolicy,
+) -> Vec<String> {
+    remux_args(detection, output, video_tag, "mp4", strip)
+}
+
+/// Like `mp4_remux_args`, for any `container` (`--container`). The video tag and
+/// `+faststart` only apply to MP4.
+pub fn remux_args(
+    detection: &VideoDetectionResult,
+    output: &Path,
+    video_tag: Option<&str>,
+    container: &str,
+    strip: crate::metadata::StripPolicy,
+) -> Vec<String> {
+    let is_mp4 = container.eq_ignore_ascii_case("mp4");
+    let mut args = vec![
+        "-y".to_string(),
+        "-i".to_string(),
+        crate::safe_path_arg(Path::new(&detection.file_path))
+            .as_ref()
+            .to_string(),
+        "-map".to_string(),
+        "0:v:0".to_string(),
+        "-map".to_string(),
+        "0:a?".to_string(),
+        "-map".to_string(),
+        "0:s?".to_string(),
+        "-c:v".to_string(),
+        "copy".to_string(),
+    ];
+    if let Some(tag) = video_tag.filter(|_| is_mp4) {
+        args.extend(["-tag:v".to_string(), tag.to_string()]);
+    }
+    if detection.has_audio {
+        args.extend(audio_args_for_container(
+            detection.audio_codec.as_deref(),
+            container,
+        ));
+    } else {
+        args.push("-an".to_string());
+    }
+    args.extend(subtitle_args_for_container(
+        detection.has_subtitles,
+        detection.subtitle_codec.as_deref(),
+        container,
+    ));
+    args.extend(crate::video::rotation_metadata_args(detection.rotation));
+    let strip = strip.ffmpeg_args();
+    if strip.is_empty() {
+        args.extend(["-map_metadata".to_string(), "0".to_string()]);
+    } else {
+        args.extend(strip);
+    }
+    if is_mp4 {
+        args.extend(["-movflags".to_string(), "+faststart".to_string()]);
+    }
+    args.push(crate::safe_path_arg(output).as_ref().to_string());
+    args
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::metadata::StripPolicy;
+
+    #[test]
+    fn test_audio_transcode_only_for_bloated_sources() {
+        let opus = Some(AudioTranscode {
+            codec: AudioCodec::Opus,
+            bitrate_kbps: 128,
+        });
+        assert_eq!(
+            transcode_audio_args(opus, Some("pcm_s16le"), None, "mp4"),
+            Some(vec![
+                "-c:a".to_string(),
+                "libopus".to_string(),
+                "-b:a".to_string(),
+                "128k".to_string()
+            ])
+        );
+        // Already-compressed audio near the target is left alone.
+        assert_eq!(
+            transcode_audio_args(opus, Some("aac"), Some(192_000), "mp4"),
+            None
+        );
+        assert!(transcode_audio_args(opus, Some("aac"), Some(320_000), "mp4").is_some());
+        assert_eq!(
+            transcode_audio_args(None, Some("pcm_s16le"), None, "mp4"),
+            None
+        );
+        assert_eq!(transcode_audio_args(opus, None, None, "mp4"), None);
+
+        let mov = transcode_audio_args(opus, Some("flac"), None, "mov").unwrap();
+        assert_eq!(mov[1], "aac");
+        let aac = Some(AudioTranscode {
+            codec: AudioCodec::Aac,
+            bitrate_kbps: 96,
+        });
+        assert_eq!(
+            transcode_audio_args(aac, Some("pcm_s24le"), None, "webm"),
+            None
+        );
+
+        assert_eq!(parse_audio_bitrate("128k"), Ok(128));
+        assert_eq!(parse_audio_bitrate("96"), Ok(96));
+        assert!(parse_audio_bitrate("2000k").is_err());
+        assert_eq!(parse_audio_codec("AAC"), Ok(AudioCodec::Aac));
+    }
+
+    fn detection(codec: DetectedCodec, format: &str) -> VideoDetectionResult {
+        VideoDetectionResult {
+            file_path: "/test/video.mkv".to_string(),
+            format: format.to_string(),
+            codec,
+            has_audio: true,
+            audio_codec: Some("aac".to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_remux_candidate_requires_matching_codec_outside_mp4() {
+        let hevc_mkv = detection(DetectedCodec::H265, "matroska,webm");
+        assert!(is_mp4_remux_candidate(&hevc_mkv, &DetectedCodec::H265));
+        assert!(!is_mp4_remux_candidate(&hevc_mkv, &DetectedCodec::AV1));
+
+        let hevc_mp4 = detection(DetectedCodec::H265, "mov,mp4,m4a,3gp,3g2,mj2");
+        assert!(!is_mp4_remux_candidate(&hevc_mp4, &DetectedCodec::H265));
+
+        let av1_webm = detection(DetectedCodec::AV1, "matroska,webm");
+        assert!(is_mp4_remux_candidate(&av1_webm, &DetectedCodec::AV1));
+
+        let vp9_webm = detection(DetectedCodec::VP9, "matroska,webm");
+        assert!(!is_mp4_remux_candidate(&vp9_webm, &DetectedCodec::VP9));
+    }
+
+    #[test]
+    fn test_mp4_remux_args_copy_video() {
+        let args = mp4_remux_args(
+            &detection(DetectedCodec::H265, "matroska,webm"),
+            Path::new("/out/video.mp4"),
+            Some("hvc1"),
+            StripPolicy::default(),
+        );
+        let joined = args.join(" ");
+        assert!(joined.contains("-c:v copy -tag:v hvc1"));
+        assert!(joined.contains("-c:a copy"));
+        assert_eq!(args.last().map(String::as_str), Some("/out/video.mp4"));
+
+        assert!(!joined.contains("rotate="));
+
+        let portrait = VideoDetectionResult {
+            rotation: Some(90),
+            ..detection(DetectedCodec::H265, "matroska,webm")
+        };
+        let joined = mp4_remux_args(
+            &portrait,
+            Path::new("/out/video.mp4"),
+            Some("hvc1"),
+            StripPolicy::default(),
+        )
+        .join(" ");
+        assert!(joined.contains("-metadata:s:v:0 rotate=90"));
+    }
+
+    #[test]
+    fn test_remux_args_follow_container() {
+        let source = detection(DetectedCodec::AV1, "matroska,webm");
+        let joined = remux_args(
+            &source,
+            Path::new("/out/video.mkv"),
+            Some("av01"),
+            "mkv",
+            StripPolicy::default(),
+        )
+        .join(" ");
+        assert!(!joined.contains("-tag:v"));
+        assert!(!joined.contains("+faststart"));
+
+        let joined = remux_args(
+            &source,
+            Path::new("/out/video.webm"),
+            Some("av01"),
+            "webm",
+            StripPolicy::default(),
+        )
+        .join(" ");
+        assert!(joined.contains("-c:a libopus"));
+
+        assert_eq!(container_of(Path::new("/out/a.MKV")), "mkv");
+        assert_eq!(container_of(Path::new("/out/a.webm")), "webm");
+        assert_eq!(container_of(Path::new("/out/a.mov")), "mp4");
+    }
+
+    #[test]
+    fn test_audio_webm_requires_opus_or_vorbis() {
+        assert_eq!(
+            audio_args_for_container(Some("opus"), "webm"),
+            vec!["-c:a", "copy"]
+        );
+        assert_eq!(
+            audio_args_for_container(Some("aac"), "webm"),
+            vec!["-c:a", "libopus", "-b:a", "160k"]
+        );
+        assert_eq!(
+            subtitle_args_for_container(true, Some("subrip"), "webm"),
+            vec!["-c:s", "webvtt"]
+        );
+        assert_eq!(
+            subtitle_args_for_container(true, Some("hdmv_pgs_subtitle"), "webm"),
+            vec!["-sn"]
+        );
+    }
 
     #[test]
     fn test_audio_mkv_always_copy() {
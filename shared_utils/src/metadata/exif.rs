@@ -17,11 +17,11 @@ use std::sync::OnceLock;
 
 static EXIFTOOL_AVAILABLE: OnceLock<bool> = OnceLock::new();
 
-fn is_exiftool_available() -> bool {
+pub(super) fn is_exiftool_available() -> bool {
     *EXIFTOOL_AVAILABLE.get_or_init(|| which::which("exiftool").is_ok())
 }
 
-fn is_video_file(path: &Path) -> bool {
+pub(super) fn is_video_file(path: &Path) -> bool {
     path.extension()
         .and_then(|e| e.to_str())
         .map(|e| crate::SUPPORTED_VIDEO_EXTENSIONS.contains(&e.to_lowercase().as_str()))
@@ -164,7 +164,7 @@ fn preserve_internal_metadata_fallback(
 /// Extract a meaningful error message from an ExifTool output.
 /// ExifTool with `-q` writes errors to stdout (not stderr); stderr is often empty on failure.
 /// Returns Some(msg) only when there is a real actionable error (not just warnings or empty output).
-fn exiftool_error_message(output: &std::process::Output) -> Option<String> {
+pub(super) fn exiftool_error_message(output: &std::process::Output) -> Option<String> {
     let stderr = String::from_utf8_lossy(&output.stderr);
     let stdout = String::from_utf8_lossy(&output.stdout);
 
@@ -331,9 +331,34 @@ fn preserve_internal_metadata_core(src: &Path, dst: &Path) -> io::Result<()> {
         fix_quicktime_dates(src, dst)?;
     }
 
+    if let Err(e) = crate::live_photo::relink_content_identifier(src, dst) {
+        eprintln!("⚠️ [metadata] Live Photo pairing: {}", e);
+    }
+
     Ok(())
 }
 
+/// Set EXIF Orientation to 1 on `path`. Used after the pixels were rotated upright, since the
+/// metadata step copies the original tag back from the source.
+pub fn reset_orientation_tag(path: &Path) -> io::Result<()> {
+    if !is_exiftool_available() {
+        return Ok(());
+    }
+    let output = Command::new("exiftool")
+        .arg("-n")
+        .arg("-Orientation=1")
+        .arg("-overwrite_original")
+        .arg("-P")
+        .arg("-q")
+        .arg("-m")
+        .arg(crate::safe_path_arg(path).as_ref())
+        .output()?;
+    match exiftool_error_message(&output) {
+        Some(msg) => Err(io::Error::other(msg)),
+        None => Ok(()),
+    }
+}
+
 fn fix_quicktime_dates(src: &Path, dst: &Path) -> io::Result<()> {
     // Always sync all QuickTime date fields from source — don't skip if dst already has a date,
     // because the date may have been reset to encode time rather than original capture time.
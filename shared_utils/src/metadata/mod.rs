@@ -4,6 +4,7 @@
 //! 时间戳统一入口：单文件经 `apply_file_timestamps(src, dst)`，目录树经
 //! `save_directory_timestamps` → `apply_saved_timestamps_to_dst` / `restore_directory_timestamps`，
 //! 避免多处重复实现。exiftool 会改写文件，故时间戳一律在写操作之后设置。
+//! `--strip-metadata`（`StripPolicy`）反向：编码器不带元数据，各保留层跳过，仅按需保留时间戳。
 
 use std::io;
 use std::path::Path;
@@ -14,12 +15,53 @@ mod linux;
 #[cfg(target_os = "macos")]
 mod macos;
 mod network;
+mod provenance;
 #[cfg(target_os = "windows")]
 mod windows;
 
-pub use exif::preserve_internal_metadata;
+pub use exif::{preserve_internal_metadata, reset_orientation_tag};
 #[cfg(target_os = "macos")]
 pub use macos::append_mfb_branding;
+pub use provenance::{provenance_enabled, set_provenance, write_provenance, Provenance};
+
+/// `--strip-metadata` / `--strip-timestamps`: publish outputs without EXIF/GPS/XMP.
+/// ICC profiles and HDR signalling are colour data, not metadata, and are always kept.
+/// Carried per conversion (`ConversionConfig::strip`, `ConvertOptions::strip`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StripPolicy {
+    /// Encoders drop embedded metadata; `preserve_pro` and the XMP sidecar merge are skipped.
+    pub metadata: bool,
+    /// Outputs also keep their own (conversion-time) file timestamps.
+    pub timestamps: bool,
+}
+
+impl StripPolicy {
+    /// Appended to every ffmpeg output: no global, stream or chapter metadata from the input.
+    pub fn ffmpeg_args(self) -> Vec<String> {
+        if self.metadata {
+            vec!["-map_metadata".to_string(), "-1".to_string()]
+        } else {
+            Vec::new()
+        }
+    }
+
+    /// cjxl copies EXIF/XMP/JUMBF boxes from the input unless told otherwise.
+    pub fn cjxl_args(self) -> &'static [&'static str] {
+        if self.metadata {
+            &["-x", "strip=exif", "-x", "strip=xmp", "-x", "strip=jumbf"]
+        } else {
+            &[]
+        }
+    }
+
+    pub fn avifenc_args(self) -> &'static [&'static str] {
+        if self.metadata {
+            &["--ignore-exif", "--ignore-xmp"]
+        } else {
+            &[]
+        }
+    }
+}
 
 pub fn apply_file_timestamps(src: &Path, dst: &Path) {
     use tracing::debug;
@@ -132,7 +174,21 @@ pub fn apply_file_timestamps(src: &Path, dst: &Path) {
     }
 }
 
-pub fn preserve_pro(src: &Path, dst: &Path) -> io::Result<()> {
+/// Every preservation layer unless `strip.metadata`, then the timestamps unless `strip.timestamps`.
+pub fn preserve_pro(src: &Path, dst: &Path, strip: StripPolicy) -> io::Result<()> {
+    if !strip.metadata {
+        preserve_pro_inner(src, dst);
+    }
+    if !strip.timestamps {
+        apply_file_timestamps(src, dst);
+    }
+    Ok(())
+}
+
+/// Runs every preservation layer except timestamps, which callers apply last (ExifTool rewrites
+/// the file); returns whether the ExifTool (EXIF/IPTC/XMP) layer succeeded, since that is the
+/// only layer whose failure cannot be verified afterwards.
+fn preserve_pro_inner(src: &Path, dst: &Path) -> bool {
     #[cfg(target_os = "macos")]
     {
         // copyfile: copies ACL + STAT + xattr in one syscall
@@ -142,9 +198,13 @@ pub fn preserve_pro(src: &Path, dst: &Path) -> io::Result<()> {
             copy_xattrs_manual(src, dst);
         }
         // ExifTool: EXIF/IPTC/XMP internal tags
-        if let Err(e) = exif::preserve_internal_metadata(src, dst) {
-            eprintln!("⚠️ [metadata] Internal metadata failed: {}", e);
-        }
+        let exif_ok = match exif::preserve_internal_metadata(src, dst) {
+            Ok(()) => true,
+            Err(e) => {
+                eprintln!("⚠️ [metadata] Internal metadata failed: {}", e);
+                false
+            }
+        };
         // Network xattrs (WhereFroms, UserTags) — copy + verify
         if let Err(e) = network::preserve_network_metadata(src, dst) {
             eprintln!("⚠️ [metadata] Network metadata preservation failed: {}", e);
@@ -161,17 +221,19 @@ pub fn preserve_pro(src: &Path, dst: &Path) -> io::Result<()> {
                 );
             }
         }
-        // Timestamps last (ExifTool rewrites file, so must come after)
-        apply_file_timestamps(src, dst);
-        Ok(())
+        exif_ok
     }
 
     #[cfg(not(target_os = "macos"))]
     {
         // ExifTool: EXIF/IPTC/XMP internal tags
-        if let Err(e) = exif::preserve_internal_metadata(src, dst) {
-            eprintln!("⚠️ [metadata] Internal metadata failed: {}", e);
-        }
+        let exif_ok = match exif::preserve_internal_metadata(src, dst) {
+            Ok(()) => true,
+            Err(e) => {
+                eprintln!("⚠️ [metadata] Internal metadata failed: {}", e);
+                false
+            }
+        };
         // Network xattrs — copy + verify
         if let Err(e) = network::preserve_network_metadata(src, dst) {
             eprintln!("⚠️ [metadata] Network metadata preservation failed: {}", e);
@@ -199,27 +261,150 @@ pub fn preserve_pro(src: &Path, dst: &Path) -> io::Result<()> {
                 );
             }
         }
-        // Timestamps last
-        apply_file_timestamps(src, dst);
-        Ok(())
+        exif_ok
     }
 }
 
-pub fn preserve_metadata(src: &Path, dst: &Path) -> io::Result<()> {
-    preserve_pro(src, dst)
+pub fn preserve_metadata(src: &Path, dst: &Path, strip: StripPolicy) -> io::Result<()> {
+    preserve_pro(src, dst, strip)
 }
 
 /// Merge source's XMP sidecar into destination (for conversion output). Idempotent if no sidecar.
-pub fn merge_xmp_sidecar_into_dest(src: &Path, dst: &Path) {
-    merge_xmp_sidecar(src, dst);
+pub fn merge_xmp_sidecar_into_dest(src: &Path, dst: &Path, strip: StripPolicy) {
+    if !strip.metadata {
+        merge_xmp_sidecar(src, dst);
+    }
 }
 
-pub fn copy_metadata(src: &Path, dst: &Path) {
-    if let Err(e) = preserve_metadata(src, dst) {
-        eprintln!("⚠️ Failed to preserve metadata: {}", e);
+pub fn copy_metadata(src: &Path, dst: &Path, strip: StripPolicy) {
+    if !strip.metadata {
+        preserve_pro_inner(src, dst);
+        merge_xmp_sidecar(src, dst);
     }
-    merge_xmp_sidecar(src, dst);
+    if !strip.timestamps {
+        apply_file_timestamps(src, dst);
+    }
+}
+
+/// Per-category outcome of `copy_metadata_with_report`.
+/// `None` means the category does not apply (no XMP sidecar, no ACL tooling on this platform).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MetadataCopyReport {
+    pub exif: bool,
+    pub xattr: bool,
+    pub timestamps: bool,
+    pub acl: Option<bool>,
+    pub xmp: Option<bool>,
+}
+
+impl MetadataCopyReport {
+    pub fn all_ok(&self) -> bool {
+        self.exif
+            && self.xattr
+            && self.timestamps
+            && self.acl != Some(false)
+            && self.xmp != Some(false)
+    }
+
+    /// One line per category, e.g. `✅ EXIF`, `❌ xattr`, `➖ XMP (not applicable)`.
+    pub fn lines(&self) -> Vec<String> {
+        fn mark(name: &str, ok: Option<bool>) -> String {
+            match ok {
+                Some(true) => format!("✅ {}", name),
+                Some(false) => format!("❌ {}", name),
+                None => format!("➖ {} (not applicable)", name),
+            }
+        }
+        vec![
+            mark("EXIF", Some(self.exif)),
+            mark("xattr", Some(self.xattr)),
+            mark("Timestamps", Some(self.timestamps)),
+            mark("ACL", self.acl),
+            mark("XMP sidecar", self.xmp),
+        ]
+    }
+}
+
+/// Same as `copy_metadata`, then verifies what actually landed on `dst`.
+/// Used by the `copy-metadata` subcommand to re-apply metadata without re-encoding.
+pub fn copy_metadata_with_report(src: &Path, dst: &Path) -> MetadataCopyReport {
+    let exif = preserve_pro_inner(src, dst);
+    let xmp = merge_xmp_sidecar(src, dst);
     apply_file_timestamps(src, dst);
+
+    MetadataCopyReport {
+        exif,
+        xattr: xattrs_match(src, dst),
+        timestamps: mtimes_match(src, dst),
+        acl: acls_match(src, dst),
+        xmp,
+    }
+}
+
+fn xattrs_match(src: &Path, dst: &Path) -> bool {
+    let Ok(src_names) = xattr::list(src) else {
+        // Filesystem without xattr support: nothing to copy.
+        return true;
+    };
+    let dst_names: Vec<std::ffi::OsString> = match xattr::list(dst) {
+        Ok(iter) => iter.collect(),
+        Err(_) => return false,
+    };
+    src_names
+        .into_iter()
+        .all(|name| dst_names.iter().any(|d| *d == name))
+}
+
+fn mtimes_match(src: &Path, dst: &Path) -> bool {
+    let (Ok(src_meta), Ok(dst_meta)) = (std::fs::metadata(src), std::fs::metadata(dst)) else {
+        return false;
+    };
+    let src_mtime = filetime::FileTime::from_last_modification_time(&src_meta);
+    let dst_mtime = filetime::FileTime::from_last_modification_time(&dst_meta);
+    (src_mtime.unix_seconds() - dst_mtime.unix_seconds()).abs() <= 1
+}
+
+/// ACL entries as text (path header stripped), or `None` when no ACL tooling is available.
+fn acl_listing(path: &Path) -> Option<String> {
+    #[cfg(target_os = "linux")]
+    {
+        which::which("getfacl").ok()?;
+        let out = std::process::Command::new("getfacl")
+            .arg("--omit-header")
+            .arg("--absolute-names")
+            .arg(path)
+            .output()
+            .ok()?;
+        out.status
+            .success()
+            .then(|| String::from_utf8_lossy(&out.stdout).into_owned())
+    }
+    #[cfg(target_os = "macos")]
+    {
+        // `ls -led`: first line is the file itself, following lines are ACL entries.
+        let out = std::process::Command::new("ls")
+            .arg("-led")
+            .arg(path)
+            .output()
+            .ok()?;
+        out.status.success().then(|| {
+            String::from_utf8_lossy(&out.stdout)
+                .lines()
+                .skip(1)
+                .collect::<Vec<_>>()
+                .join("\n")
+        })
+    }
+    #[cfg(not(any(target_os = "linux", target_os = "macos")))]
+    {
+        let _ = path;
+        None
+    }
+}
+
+fn acls_match(src: &Path, dst: &Path) -> Option<bool> {
+    let src_acl = acl_listing(src)?;
+    Some(acl_listing(dst).is_some_and(|dst_acl| dst_acl == src_acl))
 }
 
 pub fn preserve_directory_metadata(src_dir: &Path, dst_dir: &Path) -> io::Result<()> {
@@ -469,17 +654,12 @@ fn collect_dir_timestamps(
         (filetime::FileTime, filetime::FileTime),
     >,
 ) -> io::Result<()> {
-    let entries = std::fs::read_dir(dir)?;
-    for entry in entries {
-        let entry = entry?;
-        let path = entry.path();
-        if path.is_dir() {
-            let meta = std::fs::metadata(&path)?;
-            let atime = filetime::FileTime::from_last_access_time(&meta);
-            let mtime = filetime::FileTime::from_last_modification_time(&meta);
-            map.insert(path.clone(), (atime, mtime));
-            collect_dir_timestamps(&path, map)?;
-        }
+    for entry in walk_subdirs(dir) {
+        let path = entry?;
+        let meta = std::fs::metadata(&path)?;
+        let atime = filetime::FileTime::from_last_access_time(&meta);
+        let mtime = filetime::FileTime::from_last_modification_time(&meta);
+        map.insert(path, (atime, mtime));
     }
     Ok(())
 }
@@ -488,19 +668,25 @@ fn collect_dir_metadata(
     dir: &Path,
     map: &mut std::collections::HashMap<std::path::PathBuf, std::fs::Metadata>,
 ) -> io::Result<()> {
-    let entries = std::fs::read_dir(dir)?;
-    for entry in entries {
-        let entry = entry?;
-        let path = entry.path();
-        if path.is_dir() {
-            let meta = std::fs::metadata(&path)?;
-            map.insert(path.clone(), meta);
-            collect_dir_metadata(&path, map)?;
-        }
+    for entry in walk_subdirs(dir) {
+        let path = entry?;
+        let meta = std::fs::metadata(&path)?;
+        map.insert(path, meta);
     }
     Ok(())
 }
 
+/// Every directory below `dir` (not `dir` itself) under the `--follow-symlinks` policy; links
+/// back to an ancestor are dropped instead of recursing forever.
+fn walk_subdirs(dir: &Path) -> impl Iterator<Item = io::Result<std::path::PathBuf>> {
+    crate::batch::walk_dir(dir, true).filter_map(|entry| match entry {
+        Ok(entry) if entry.depth() > 0 && entry.file_type().is_dir() => Some(Ok(entry.into_path())),
+        Ok(_) => None,
+        Err(err) if err.loop_ancestor().is_some() => None,
+        Err(err) => Some(Err(err.into())),
+    })
+}
+
 fn copy_dir_xattrs(src: &Path, dst: &Path) {
     match xattr::list(src) {
         Ok(iter) => {
@@ -541,6 +727,49 @@ fn copy_dir_xattrs(src: &Path, dst: &Path) {
     }
 }
 
+/// xattr names and values written by [`write_quality_xattrs`]; metrics that were not measured are left out.
+fn quality_xattr_entries(
+    ssim: Option<f64>,
+    psnr: Option<f64>,
+    source_codec: &str,
+) -> Vec<(&'static str, String)> {
+    let mut entries = Vec::new();
+    if let Some(ssim) = ssim {
+        entries.push(("user.mfb.ssim", format!("{:.6}", ssim)));
+    }
+    if let Some(psnr) = psnr {
+        let value = if psnr.is_finite() {
+            format!("{:.2}", psnr)
+        } else {
+            "inf".to_string()
+        };
+        entries.push(("user.mfb.psnr", value));
+    }
+    if !source_codec.is_empty() {
+        entries.push(("user.mfb.source_codec", source_codec.to_string()));
+    }
+    entries
+}
+
+/// `--quality-report`: record measured quality on the output as `user.mfb.ssim`, `user.mfb.psnr`
+/// and `user.mfb.source_codec` xattrs, so an archive can be audited (`getfattr -d -m user.mfb`)
+/// without re-measuring. Filesystems without xattr support warn once per run; never fatal.
+pub fn write_quality_xattrs(dst: &Path, ssim: Option<f64>, psnr: Option<f64>, source_codec: &str) {
+    for (name, value) in quality_xattr_entries(ssim, psnr, source_codec) {
+        if let Err(e) = xattr::set(dst, name, value.as_bytes()) {
+            static WARNED: std::sync::OnceLock<()> = std::sync::OnceLock::new();
+            WARNED.get_or_init(|| {
+                eprintln!(
+                    "⚠️ [metadata] Could not write quality xattrs to {}: {} (quality report disabled for this filesystem; further warnings suppressed)",
+                    dst.display(),
+                    e
+                );
+            });
+            return;
+        }
+    }
+}
+
 /// Fallback: try exiv2 to merge XMP into the destination (exiv2 -i expects sidecar named <stem>.xmp beside image).
 /// Returns true if exiv2 merge succeeded. No fake success; only when exiv2 actually succeeds do we return true.
 fn try_merge_xmp_exiv2(xmp_path: &Path, dst: &Path) -> bool {
@@ -593,27 +822,62 @@ fn try_merge_xmp_exiv2(xmp_path: &Path, dst: &Path) -> bool {
     ok
 }
 
-fn merge_xmp_sidecar(src: &Path, dst: &Path) {
+/// Returns `None` when `src` has no sidecar, otherwise whether the merge succeeded.
+fn merge_xmp_sidecar(src: &Path, dst: &Path) -> Option<bool> {
     let xmp_path = find_xmp_sidecar(src);
 
     if let Some(xmp) = xmp_path {
         if crate::progress_mode::is_verbose_mode() {
-            eprintln!("📋 Found XMP sidecar: {}", xmp.display());
+            eprintln!(
+                "📋 Found {} sidecar: {}",
+                xmp.extension()
+                    .unwrap_or_default()
+                    .to_string_lossy()
+                    .to_uppercase(),
+                xmp.display()
+            );
         }
 
+        let sidecar_mode = crate::xmp_merger::sidecar_mode();
         let config = crate::xmp_merger::XmpMergerConfig {
             delete_xmp_after_merge: false,
             overwrite_original: true,
             preserve_timestamps: true,
             verbose: false,
+            sidecar_mode,
+            merge_policy: crate::xmp_merger::xmp_merge_policy(),
+            sidecar_extensions: crate::xmp_merger::sidecar_extensions(),
         };
 
         let merger = crate::xmp_merger::XmpMerger::new(config);
 
+        let copied = if sidecar_mode.copies() {
+            match merger.copy_sidecar_beside(&xmp, dst) {
+                Ok(_) => true,
+                Err(e) => {
+                    crate::progress_mode::emit_stderr(&format!("   ⚠️  {}", e));
+                    false
+                }
+            }
+        } else {
+            true
+        };
+        if !sidecar_mode.embeds() {
+            return Some(copied);
+        }
+
+        // `photo.jpg.xmp` and `photo.xmp` side by side are merged together by policy.
+        let mut sidecars = crate::xmp_merger::find_sidecars(src);
+        if !sidecars.contains(&xmp) {
+            sidecars = vec![xmp.clone()];
+        }
+
         crate::progress_mode::xmp_merge_attempt();
-        match merger.merge_xmp(&xmp, dst) {
-            Ok(()) => {
+        match merger.merge_sidecars(&sidecars, dst) {
+            Ok(conflicts) => {
                 crate::progress_mode::xmp_merge_success();
+                crate::xmp_merger::report_conflicts(&conflicts);
+                Some(copied)
             }
             Err(e) => {
                 let err_str = e.to_string();
@@ -626,7 +890,11 @@ fn merge_xmp_sidecar(src: &Path, dst: &Path) {
                 } else {
                     crate::progress_mode::xmp_merge_failure(&err_str);
                 }
-                let fallback_ok = try_merge_xmp_exiv2(&xmp, dst);
+                // exiv2 only reads XMP; other sidecar types have no fallback.
+                let is_xmp = xmp
+                    .extension()
+                    .is_some_and(|e| e.eq_ignore_ascii_case("xmp"));
+                let fallback_ok = is_xmp && try_merge_xmp_exiv2(&xmp, dst);
                 if fallback_ok {
                     crate::progress_mode::xmp_merge_success();
                     if crate::progress_mode::has_log_file() {
@@ -641,22 +909,34 @@ fn merge_xmp_sidecar(src: &Path, dst: &Path) {
                         "   → Fallback: exiv2 merge failed or exiv2 not available; no fake success.",
                     );
                 }
+                Some(fallback_ok && copied)
             }
         }
+    } else {
+        None
     }
 }
 
+/// First sidecar of `src` with a configured extension (`--sidecar-extensions`), trying each
+/// type in order: `<name>.<ext>.<sidecar>`, `<stem>.<sidecar>`, then a root-stem scan of the
+/// directory.
 fn find_xmp_sidecar(src: &Path) -> Option<std::path::PathBuf> {
+    let extensions = crate::xmp_merger::sidecar_extensions();
+
     if let Some(ext) = src.extension() {
-        let xmp_full = src.with_extension(format!("{}.xmp", ext.to_str()?));
-        if xmp_full.exists() {
-            return Some(xmp_full);
+        for sidecar in &extensions {
+            let full = src.with_extension(format!("{}.{}", ext.to_str()?, sidecar));
+            if full.exists() {
+                return Some(full);
+            }
         }
     }
 
-    let xmp_stem = src.with_extension("xmp");
-    if xmp_stem.exists() {
-        return Some(xmp_stem);
+    for sidecar in &extensions {
+        let stem = src.with_extension(sidecar);
+        if stem.exists() {
+            return Some(stem);
+        }
     }
 
     if let Some(parent) = src.parent() {
@@ -673,16 +953,17 @@ fn find_xmp_sidecar(src: &Path) -> Option<std::path::PathBuf> {
                                 tracing::warn!(
                                     dir = %parent.display(),
                                     error = %err,
-                                    "Failed to inspect sibling file while searching for XMP sidecar"
+                                    "Failed to inspect sibling file while searching for sidecar"
                                 );
                                 continue;
                             }
                         };
                         let path = entry.path();
 
-                        if !path
-                            .extension()
-                            .is_some_and(|e| e.to_string_lossy().eq_ignore_ascii_case("xmp"))
+                        if path == src
+                            || !path.extension().is_some_and(|e| {
+                                extensions.contains(&e.to_string_lossy().to_lowercase())
+                            })
                         {
                             continue;
                         }
@@ -709,7 +990,7 @@ fn find_xmp_sidecar(src: &Path) -> Option<std::path::PathBuf> {
                     tracing::warn!(
                         dir = %parent.display(),
                         error = %err,
-                        "Failed to read parent directory while searching for XMP sidecar"
+                        "Failed to read parent directory while searching for sidecar"
                     );
                 }
             }
@@ -758,3 +1039,124 @@ fn copy_xattrs_manual(src: &Path, dst: &Path) {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quality_xattr_entries() {
+        let entries = quality_xattr_entries(Some(0.98765), Some(42.123), "h264");
+        assert_eq!(
+            entries,
+            vec![
+                ("user.mfb.ssim", "0.987650".to_string()),
+                ("user.mfb.psnr", "42.12".to_string()),
+                ("user.mfb.source_codec", "h264".to_string()),
+            ]
+        );
+
+        let lossless = quality_xattr_entries(None, Some(f64::INFINITY), "ffv1");
+        assert_eq!(lossless[0], ("user.mfb.psnr", "inf".to_string()));
+        assert_eq!(lossless.len(), 2);
+    }
+
+    #[test]
+    fn test_metadata_copy_report_all_ok_ignores_not_applicable() {
+        let report = MetadataCopyReport {
+            exif: true,
+            xattr: true,
+            timestamps: true,
+            acl: None,
+            xmp: None,
+        };
+        assert!(report.all_ok());
+        assert!(report.lines()[4].contains("not applicable"));
+
+        let failed = MetadataCopyReport {
+            xmp: Some(false),
+            ..report.clone()
+        };
+        assert!(!failed.all_ok());
+        assert!(failed.lines()[4].starts_with("❌"));
+    }
+
+    #[test]
+    fn test_copy_metadata_with_report_restores_timestamps() {
+        let dir = tempfile::tempdir().unwrap();
+        let src = dir.path().join("src.bin");
+        let dst = dir.path().join("dst.bin");
+        std::fs::write(&src, b"source").unwrap();
+        std::fs::write(&dst, b"dest").unwrap();
+        let old = filetime::FileTime::from_unix_time(1_500_000_000, 0);
+        filetime::set_file_mtime(&src, old).unwrap();
+
+        let report = copy_metadata_with_report(&src, &dst);
+        assert!(report.timestamps);
+        assert_eq!(report.xmp, None);
+    }
+
+    #[test]
+    fn test_strip_policy_encoder_args() {
+        let keep = StripPolicy::default();
+        assert!(keep.ffmpeg_args().is_empty());
+        assert!(keep.cjxl_args().is_empty());
+        assert!(keep.avifenc_args().is_empty());
+
+        let strip = StripPolicy {
+            metadata: true,
+            timestamps: false,
+        };
+        assert_eq!(strip.ffmpeg_args(), ["-map_metadata", "-1"]);
+        assert!(strip.cjxl_args().contains(&"strip=exif"));
+        assert!(strip.avifenc_args().contains(&"--ignore-exif"));
+    }
+
+    #[test]
+    fn test_strip_metadata_leaves_no_gps_on_output() {
+        if !exif::is_exiftool_available() {
+            eprintln!("ExifTool not available, skipping test");
+            return;
+        }
+        let dir = tempfile::tempdir().unwrap();
+        let src = dir.path().join("src.jpg");
+        let dst = dir.path().join("dst.jpg");
+        image::RgbImage::from_pixel(16, 16, image::Rgb([90, 120, 150]))
+            .save(&src)
+            .unwrap();
+        std::fs::copy(&src, &dst).unwrap();
+        let tagged = std::process::Command::new("exiftool")
+            .args([
+                "-overwrite_original",
+                "-GPSLatitude=48.8584",
+                "-GPSLatitudeRef=N",
+                "-GPSLongitude=2.2945",
+                "-GPSLongitudeRef=E",
+            ])
+            .arg(&src)
+            .status()
+            .unwrap();
+        assert!(tagged.success());
+
+        let has_gps = |path: &Path| {
+            let out = std::process::Command::new("exiftool")
+                .args(["-s3", "-GPSLatitude"])
+                .arg(path)
+                .output()
+                .unwrap();
+            !String::from_utf8_lossy(&out.stdout).trim().is_empty()
+        };
+        assert!(has_gps(&src));
+
+        let strip = StripPolicy {
+            metadata: true,
+            timestamps: false,
+        };
+        copy_metadata(&src, &dst, strip);
+        assert!(!has_gps(&dst));
+
+        // Control: the default policy does carry GPS over.
+        copy_metadata(&src, &dst, StripPolicy::default());
+        assert!(has_gps(&dst));
+    }
+}
@@ -0,0 +1,167 @@
+//! `--provenance`: a compact JSON note embedded in each output recording what produced it
+//! (tool, version, source codec, CRF, SSIM, date), so the settings can be read back from the
+//! file itself: `ffprobe -show_entries format_tags=comment` for video, `exiftool -UserComment`
+//! for images.
+
+use serde::{Deserialize, Serialize};
+use std::io;
+use std::path::Path;
+use std::process::Command;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static PROVENANCE: AtomicBool = AtomicBool::new(false);
+
+/// Process-wide `--provenance` switch read by the video and image commit paths.
+pub fn set_provenance(enabled: bool) {
+    PROVENANCE.store(enabled, Ordering::Relaxed);
+}
+
+pub fn provenance_enabled() -> bool {
+    PROVENANCE.load(Ordering::Relaxed)
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Provenance {
+    pub tool: String,
+    pub version: String,
+    pub source_codec: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub crf: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ssim: Option<f64>,
+    /// Encoder settings for outputs without a CRF (e.g. the JXL distance of an image).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub settings: Option<String>,
+    /// RFC 3339, local time with offset.
+    pub date: String,
+}
+
+impl Provenance {
+    /// A note for an output made now by this build from a `source_codec` source.
+    pub fn new(source_codec: &str) -> Self {
+        Self {
+            tool: "modern-format-boost".to_string(),
+            version: crate::version::PROGRAM_VERSION.to_string(),
+            source_codec: source_codec.to_string(),
+            crf: None,
+            ssim: None,
+            settings: None,
+            date: chrono::Local::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, false),
+        }
+    }
+
+    /// Single-line JSON, the form embedded in the file.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+
+    /// Read a note back from a comment written by [`write_provenance`]; `None` for any other comment.
+    pub fn parse(comment: &str) -> Option<Self> {
+        serde_json::from_str::<Self>(comment.trim())
+            .ok()
+            .filter(|p| p.tool == "modern-format-boost")
+    }
+}
+
+/// Embed `provenance` in `dst`: the container `comment` tag for video (rewritten by a stream-copy
+/// remux), EXIF and XMP `UserComment` for images. The file keeps its inode, so xattrs and
+/// permissions survive; timestamps are re-applied from `src` afterwards unless `strip.timestamps`.
+/// Failures only warn.
+pub fn write_provenance(
+    src: &Path,
+    dst: &Path,
+    provenance: &Provenance,
+    strip: super::StripPolicy,
+) {
+    let json = provenance.to_json();
+    let result = if super::exif::is_video_file(dst) {
+        write_video_comment(dst, &json)
+    } else {
+        write_image_user_comment(dst, &json)
+    };
+    if let Err(e) = result {
+        eprintln!(
+            "⚠️ [metadata] Could not embed provenance in {}: {}",
+            dst.display(),
+            e
+        );
+    }
+    if !strip.timestamps {
+        super::apply_file_timestamps(src, dst);
+    }
+}
+
+fn write_video_comment(dst: &Path, comment: &str) -> io::Result<()> {
+    let remuxed = crate::conversion::temp_path_for_output(dst);
+    let _guard = crate::conversion::TempOutputGuard::new(remuxed.clone());
+    let output = Command::new("ffmpeg")
+        .arg("-y")
+        .arg("-v")
+        .arg("error")
+        .arg("-i")
+        .arg(crate::safe_path_arg(dst).as_ref())
+        .arg("-map")
+        .arg("0")
+        .arg("-c")
+        .arg("copy")
+        .arg("-map_metadata")
+        .arg("0")
+        .arg("-metadata")
+        .arg(format!("comment={}", comment))
+        .arg(crate::safe_path_arg(&remuxed).as_ref())
+        .output()?;
+    if !output.status.success() {
+        return Err(io::Error::other(format!(
+            "ffmpeg remux failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    // Copy the bytes back rather than renaming, so the output keeps its inode and xattrs.
+    let permissions = std::fs::metadata(dst)?.permissions();
+    std::fs::copy(&remuxed, dst)?;
+    std::fs::set_permissions(dst, permissions)
+}
+
+fn write_image_user_comment(dst: &Path, comment: &str) -> io::Result<()> {
+    if !super::exif::is_exiftool_available() {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            "exiftool not found",
+        ));
+    }
+    let output = Command::new("exiftool")
+        .arg(format!("-EXIF:UserComment={}", comment))
+        .arg(format!("-XMP-exif:UserComment={}", comment))
+        .arg("-overwrite_original_in_place")
+        .arg("-q")
+        .arg("-m")
+        .arg(crate::safe_path_arg(dst).as_ref())
+        .output()?;
+    match super::exif::exiftool_error_message(&output) {
+        Some(msg) => Err(io::Error::other(msg)),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_provenance_round_trip() {
+        let mut provenance = Provenance::new("h264");
+        provenance.crf = Some(23.5);
+        provenance.ssim = Some(0.9712);
+        let json = provenance.to_json();
+        assert!(!json.contains('\n'));
+        assert!(!json.contains("settings"));
+        assert!(json.starts_with(r#"{"tool":"modern-format-boost","version":""#));
+        assert_eq!(Provenance::parse(&json), Some(provenance));
+
+        assert_eq!(Provenance::parse("Shot on a phone"), None);
+        assert_eq!(
+            Provenance::parse(r#"{"tool":"other","version":"1","source_codec":"x","date":"d"}"#),
+            None
+        );
+    }
+}
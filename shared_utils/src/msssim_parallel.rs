@@ -177,12 +177,14 @@ impl ParallelMsssimCalculator {
     ) -> Result<f64, AppError> {
         let original_path_str = original_path.to_string_lossy();
         let converted_path_str = converted_path.to_string_lossy();
-        let mut args = vec![
+        let trim_args = crate::video::source_trim_args(original_path);
+        let mut args: Vec<&str> = trim_args.iter().map(String::as_str).collect();
+        args.extend([
             "-i",
             original_path_str.as_ref(),
             "-i",
             converted_path_str.as_ref(),
-        ];
+        ]);
 
         let filter_str;
         if let Some(filter) = config.strategy.ffmpeg_filter() {
@@ -213,12 +215,13 @@ impl ParallelMsssimCalculator {
                     channel
                 );
 
-                let mut ssim_args = vec![
+                let mut ssim_args: Vec<&str> = trim_args.iter().map(String::as_str).collect();
+                ssim_args.extend([
                     "-i",
                     original_path_str.as_ref(),
                     "-i",
                     converted_path_str.as_ref(),
-                ];
+                ]);
 
                 let ssim_filter_str;
                 if let Some(filter) = config.strategy.ffmpeg_filter() {
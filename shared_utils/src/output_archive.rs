@@ -0,0 +1,477 @@
+//! Output Archive Module
+//!
+//! `--archive PATH`: append every converted output to one tar file instead of leaving loose
+//! files behind. `.tar` is written as is, `.tar.gz`/`.tgz` through flate2 and `.tar.zst`/
+//! `.tzst` through the `zstd` binary. Entries keep the output's mtime, mode and owner ids.
+
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::fs::File;
+use std::io::{self, BufWriter, Read, Write};
+use std::path::{Component, Path, PathBuf};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::Mutex;
+use std::time::UNIX_EPOCH;
+use tracing::warn;
+
+const BLOCK: usize = 512;
+
+enum Sink {
+    Plain(BufWriter<File>),
+    Gzip(GzEncoder<BufWriter<File>>),
+    Zstd(BufWriter<ChildStdin>, Child),
+}
+
+impl Sink {
+    fn create(path: &Path) -> io::Result<Self> {
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_ascii_lowercase())
+            .unwrap_or_default();
+        if name.ends_with(".tar.zst") || name.ends_with(".tzst") {
+            let mut child = Command::new("zstd")
+                .args(["-q", "-f", "-T0", "-", "-o"])
+                .arg(path)
+                .stdin(Stdio::piped())
+                .spawn()
+                .map_err(|e| {
+                    io::Error::new(
+                        e.kind(),
+                        format!("cannot start zstd ({e}); install zstd or use .tar/.tar.gz"),
+                    )
+                })?;
+            let stdin = child
+                .stdin
+                .take()
+                .ok_or_else(|| io::Error::new(io::ErrorKind::BrokenPipe, "zstd stdin closed"))?;
+            return Ok(Sink::Zstd(BufWriter::new(stdin), child));
+        }
+        let file = BufWriter::new(File::options().write(true).create_new(true).open(path)?);
+        if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+            Ok(Sink::Gzip(GzEncoder::new(file, Compression::default())))
+        } else {
+            Ok(Sink::Plain(file))
+        }
+    }
+
+    fn writer(&mut self) -> &mut dyn Write {
+        match self {
+            Sink::Plain(w) => w,
+            Sink::Gzip(w) => w,
+            Sink::Zstd(w, _) => w,
+        }
+    }
+
+    fn finish(self) -> io::Result<()> {
+        match self {
+            Sink::Plain(mut w) => w.flush(),
+            Sink::Gzip(w) => w.finish()?.flush(),
+            Sink::Zstd(mut w, mut child) => {
+                w.flush()?;
+                drop(w);
+                let status = child.wait()?;
+                if status.success() {
+                    Ok(())
+                } else {
+                    Err(io::Error::new(
+                        io::ErrorKind::Other,
+                        format!("zstd exited with {status}"),
+                    ))
+                }
+            }
+        }
+    }
+}
+
+/// Owner, mode and mtime stored in a tar header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct EntryMeta {
+    mode: u32,
+    uid: u64,
+    gid: u64,
+    mtime: u64,
+}
+
+impl EntryMeta {
+    fn of(meta: &std::fs::Metadata) -> Self {
+        let mtime = meta
+            .modified()
+            .ok()
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map_or(0, |d| d.as_secs());
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::MetadataExt;
+            Self {
+                mode: meta.mode() & 0o7777,
+                uid: meta.uid() as u64,
+                gid: meta.gid() as u64,
+                mtime,
+            }
+        }
+        #[cfg(not(unix))]
+        {
+            let mode = if meta.permissions().readonly() {
+                0o444
+            } else {
+                0o644
+            };
+            Self {
+                mode,
+                uid: 0,
+                gid: 0,
+                mtime,
+            }
+        }
+    }
+}
+
+fn write_octal(field: &mut [u8], value: u64) {
+    let digits = field.len() - 1;
+    let text = format!("{:0width$o}", value, width = digits);
+    field[..digits].copy_from_slice(&text.as_bytes()[text.len() - digits..]);
+    field[digits] = 0;
+}
+
+/// Octal when `value` fits the field's digits; otherwise (files over 8 GiB, uid/gid past
+/// 0o7777777) the GNU base-256 encoding instead of a truncated number.
+fn write_number(field: &mut [u8], value: u64) {
+    let digits = field.len() - 1;
+    if value < 1 << (3 * digits) {
+        write_octal(field, value);
+    } else {
+        // GNU base-256: high bit set, big-endian value in the remaining bytes.
+        field.fill(0);
+        let bytes = value.to_be_bytes();
+        let n = bytes.len().min(digits);
+        let len = field.len();
+        field[len - n..].copy_from_slice(&bytes[bytes.len() - n..]);
+        field[0] |= 0x80;
+    }
+}
+
+/// Split `name` into the ustar `prefix` (≤155 bytes) and `name` (≤100 bytes) fields at a `/`.
+fn split_ustar_name(name: &str) -> Option<(&str, &str)> {
+    if name.len() <= 100 {
+        return Some(("", name));
+    }
+    name.char_indices()
+        .filter(|&(i, c)| c == '/' && i <= 155)
+        .map(|(i, _)| (&name[..i], &name[i + 1..]))
+        .find(|(_, rest)| !rest.is_empty() && rest.len() <= 100)
+}
+
+fn header(name: &[u8], prefix: &[u8], size: u64, meta: EntryMeta, typeflag: u8) -> [u8; BLOCK] {
+    let mut h = [0u8; BLOCK];
+    h[..name.len().min(100)].copy_from_slice(&name[..name.len().min(100)]);
+    write_number(&mut h[100..108], meta.mode as u64);
+    write_number(&mut h[108..116], meta.uid);
+    write_number(&mut h[116..124], meta.gid);
+    write_number(&mut h[124..136], size);
+    write_number(&mut h[136..148], meta.mtime);
+    h[156] = typeflag;
+    h[257..263].copy_from_slice(b"ustar\0");
+    h[263..265].copy_from_slice(b"00");
+    h[345..345 + prefix.len()].copy_from_slice(prefix);
+    h[148..156].fill(b' ');
+    let checksum: u64 = h.iter().map(|&b| b as u64).sum();
+    write_octal(&mut h[148..155], checksum);
+    h[155] = b' ';
+    h
+}
+
+fn write_padding(out: &mut dyn Write, len: u64) -> io::Result<()> {
+    let rem = (len % BLOCK as u64) as usize;
+    if rem != 0 {
+        out.write_all(&[0u8; BLOCK][..BLOCK - rem])?;
+    }
+    Ok(())
+}
+
+/// Write one regular-file entry; names that do not fit ustar get a GNU `././@LongLink` record.
+fn write_entry(
+    out: &mut dyn Write,
+    name: &str,
+    meta: EntryMeta,
+    size: u64,
+    data: impl Read,
+) -> io::Result<()> {
+    let block = match split_ustar_name(name) {
+        Some((prefix, short)) => header(short.as_bytes(), prefix.as_bytes(), size, meta, b'0'),
+        None => {
+            let long = name.as_bytes();
+            let len = long.len() as u64 + 1;
+            out.write_all(&header(b"././@LongLink", b"", len, meta, b'L'))?;
+            out.write_all(long)?;
+            out.write_all(&[0])?;
+            write_padding(out, len)?;
+            header(long, b"", size, meta, b'0')
+        }
+    };
+    out.write_all(&block)?;
+    let copied = io::copy(&mut data.take(size), out)?;
+    if copied != size {
+        // Keep the archive readable: fill the entry up to the size its header announced.
+        io::copy(&mut io::repeat(0).take(size - copied), out)?;
+        write_padding(out, size)?;
+        return Err(io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            format!("{name} shrank while archiving ({copied} of {size} bytes)"),
+        ));
+    }
+    write_padding(out, size)
+}
+
+/// Entry name: `path` relative to `root` with `/` separators, or just the file name when it
+/// lies outside `root`.
+fn entry_name(root: &Path, path: &Path) -> String {
+    let relative = path
+        .strip_prefix(root)
+        .unwrap_or_else(|_| path.file_name().map(Path::new).unwrap_or(path));
+    relative
+        .components()
+        .filter_map(|c| match c {
+            Component::Normal(part) => Some(part.to_string_lossy()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+struct ArchiveState {
+    sink: Sink,
+    entries: usize,
+    bytes: u64,
+}
+
+/// An open `--archive`. Appends are serialized by the internal mutex, so parallel workers can
+/// share it.
+pub struct OutputArchive {
+    path: PathBuf,
+    root: PathBuf,
+    delete_outputs: bool,
+    state: Mutex<Option<ArchiveState>>,
+}
+
+impl OutputArchive {
+    /// Create the archive at `path`, which must not exist yet: a resumed run would otherwise
+    /// truncate outputs an earlier run already archived (and possibly deleted). Entry names are
+    /// relative to `root` (its parent when `root` is a file); with `delete_outputs` each output
+    /// is removed once archived.
+    pub fn create(path: &Path, root: &Path, delete_outputs: bool) -> io::Result<Self> {
+        if path.exists() {
+            return Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                format!(
+                    "{} already exists; choose a new archive name",
+                    path.display()
+                ),
+            ));
+        }
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        let root = if root.is_file() {
+            root.parent().unwrap_or(root)
+        } else {
+            root
+        };
+        Ok(Self {
+            path: path.to_path_buf(),
+            root: root.canonicalize().unwrap_or_else(|_| root.to_path_buf()),
+            delete_outputs,
+            state: Mutex::new(Some(ArchiveState {
+                sink: Sink::create(path)?,
+                entries: 0,
+                bytes: 0,
+            })),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Append `output` (with its mtime, mode and owner) and, with `delete_outputs`, remove it.
+    pub fn append(&self, output: &Path) -> io::Result<()> {
+        let output = output.canonicalize()?;
+        let mut file = File::open(&output)?;
+        let meta = file.metadata()?;
+        let name = entry_name(&self.root, &output);
+        {
+            let mut guard = self.state.lock().unwrap_or_else(|e| e.into_inner());
+            let state = guard
+                .as_mut()
+                .ok_or_else(|| io::Error::new(io::ErrorKind::Other, "archive already finished"))?;
+            write_entry(
+                state.sink.writer(),
+                &name,
+                EntryMeta::of(&meta),
+                meta.len(),
+                &mut file,
+            )?;
+            state.entries += 1;
+            state.bytes += meta.len();
+        }
+        if self.delete_outputs {
+            drop(file);
+            std::fs::remove_file(&output).map_err(|e| {
+                io::Error::new(
+                    e.kind(),
+                    format!("archived, but the loose output stays: {e}"),
+                )
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Write the end-of-archive blocks and flush the compressor. Returns the entry count and
+    /// the archived bytes (before compression).
+    pub fn finish(&self) -> io::Result<(usize, u64)> {
+        let mut guard = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let Some(mut state) = guard.take() else {
+            return Ok((0, 0));
+        };
+        state.sink.writer().write_all(&[0u8; 2 * BLOCK])?;
+        state.sink.finish()?;
+        Ok((state.entries, state.bytes))
+    }
+}
+
+static OUTPUT_ARCHIVE: Mutex<Option<OutputArchive>> = Mutex::new(None);
+
+/// Install the process-wide `--archive` that [`archive_output`] appends to.
+pub fn set_output_archive(archive: OutputArchive) {
+    *OUTPUT_ARCHIVE.lock().unwrap_or_else(|e| e.into_inner()) = Some(archive);
+}
+
+/// Path of the open `--archive` and whether it removes outputs once archived.
+pub fn open_output_archive() -> Option<(PathBuf, bool)> {
+    OUTPUT_ARCHIVE
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .as_ref()
+        .map(|a| (a.path.clone(), a.delete_outputs))
+}
+
+/// After a successful conversion: append `output` to the `--archive`, if one is open. A failed
+/// append is logged and leaves the loose output in place.
+pub fn archive_output(output: &Path) {
+    let guard = OUTPUT_ARCHIVE.lock().unwrap_or_else(|e| e.into_inner());
+    let Some(archive) = guard.as_ref() else {
+        return;
+    };
+    if let Err(e) = archive.append(output) {
+        warn!(
+            output = %output.display(),
+            archive = %archive.path().display(),
+            error = %e,
+            "Failed to archive output"
+        );
+    }
+}
+
+/// Close the `--archive` at the end of a run and print how much went into it.
+pub fn finish_output_archive_with_log() {
+    let Some(archive) = OUTPUT_ARCHIVE
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .take()
+    else {
+        return;
+    };
+    match archive.finish() {
+        Ok((entries, bytes)) => crate::report_println!(
+            "\n🗄️  Archive: {} output(s), {} → {}",
+            entries,
+            crate::format_bytes(bytes),
+            archive.path().display()
+        ),
+        Err(e) => eprintln!(
+            "⚠️ Failed to finish archive {}: {}",
+            archive.path().display(),
+            e
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_split_ustar_name() {
+        assert_eq!(split_ustar_name("a/b.jxl"), Some(("", "a/b.jxl")));
+        let long = format!("{}/{}", "d".repeat(120), "f.mp4");
+        assert_eq!(split_ustar_name(&long), Some((&long[..120], "f.mp4")));
+        assert_eq!(split_ustar_name(&"x".repeat(300)), None);
+    }
+
+    #[test]
+    fn test_archive_writes_readable_tar() {
+        let dir = TempDir::new().unwrap();
+        let out = dir.path().join("out");
+        std::fs::create_dir_all(out.join("album")).unwrap();
+        let file = out.join("album/a.jxl");
+        std::fs::write(&file, b"hello").unwrap();
+        let long_dir = out.join("x".repeat(160));
+        std::fs::create_dir_all(&long_dir).unwrap();
+        std::fs::write(long_dir.join("b.mp4"), b"video").unwrap();
+
+        let tar = dir.path().join("set.tar");
+        let archive = OutputArchive::create(&tar, &out, true).unwrap();
+        archive.append(&file).unwrap();
+        archive.append(&long_dir.join("b.mp4")).unwrap();
+        assert_eq!(archive.finish().unwrap(), (2, 10));
+        assert!(!file.exists(), "loose output removed once archived");
+
+        let data = std::fs::read(&tar).unwrap();
+        assert_eq!(data.len() % BLOCK, 0);
+        assert_eq!(&data[..11], b"album/a.jxl");
+        assert_eq!(&data[257..262], b"ustar");
+        let stored: u64 = data[148..154]
+            .iter()
+            .fold(0, |acc, &b| acc * 8 + (b - b'0') as u64);
+        let mut blank = data[..BLOCK].to_vec();
+        blank[148..156].fill(b' ');
+        assert_eq!(stored, blank.iter().map(|&b| b as u64).sum::<u64>());
+        assert_eq!(&data[BLOCK..BLOCK + 5], b"hello");
+        // Second entry: a 160-character directory does not fit `prefix`, so a LongLink comes first.
+        assert_eq!(data[2 * BLOCK + 156], b'L');
+        assert!(data[2 * BLOCK..].windows(5).any(|w| w == b"video"));
+        assert!(data[data.len() - 2 * BLOCK..].iter().all(|&b| b == 0));
+    }
+
+    #[test]
+    fn test_base256_size() {
+        let mut field = [0u8; 12];
+        write_number(&mut field, 0o77777777777);
+        assert_eq!(&field, b"77777777777\0");
+        write_number(&mut field, 0o77777777777 + 1);
+        assert_eq!(field[0], 0x80);
+        assert_eq!(
+            u64::from_be_bytes(field[4..].try_into().unwrap()),
+            0o77777777777 + 1
+        );
+    }
+
+    #[test]
+    fn test_large_uid_gid_use_base256() {
+        let uid = 0o7777777 + 1;
+        let meta = EntryMeta {
+            mode: 0o644,
+            uid,
+            gid: 0o7777777,
+            mtime: 0,
+        };
+        let h = header(b"a.jxl", b"", 5, meta, b'0');
+        assert_eq!(&h[100..108], b"0000644\0");
+        assert_eq!(h[108], 0x80);
+        let stored = h[109..116]
+            .iter()
+            .fold(0u64, |acc, &b| (acc << 8) | b as u64);
+        assert_eq!(stored, uid);
+        assert_eq!(&h[116..124], b"7777777\0");
+    }
+}
@@ -14,6 +14,7 @@ pub enum PathValidationError {
     EmptyPath,
     NullByte(String),
     InputOutputConflict { path: String },
+    UnsafeFileName { name: String, reason: &'static str },
 }
 
 impl fmt::Display for PathValidationError {
@@ -43,6 +44,13 @@ impl fmt::Display for PathValidationError {
                     path
                 )
             }
+            PathValidationError::UnsafeFileName { name, reason } => {
+                write!(
+                    f,
+                    "❌ PATH SECURITY ERROR: Unsafe file name '{}': {}",
+                    name, reason
+                )
+            }
         }
     }
 }
@@ -122,6 +130,30 @@ pub fn validate_paths(paths: &[&Path]) -> Result<(), PathValidationError> {
     Ok(())
 }
 
+/// Validates a bare file name (e.g. one rendered from `--output-template`):
+/// it must be a single path component that cannot escape its directory.
+pub fn validate_file_name(name: &str) -> Result<(), PathValidationError> {
+    validate_path(Path::new(name))?;
+
+    let reason = if name.contains('/') || name.contains('\\') {
+        Some("path separators are not allowed")
+    } else if name == "." || name == ".." {
+        Some("relative directory names are not allowed")
+    } else if name.trim().is_empty() {
+        Some("name is blank")
+    } else {
+        None
+    };
+
+    match reason {
+        Some(reason) => Err(PathValidationError::UnsafeFileName {
+            name: name.to_string(),
+            reason,
+        }),
+        None => Ok(()),
+    }
+}
+
 pub fn check_input_output_conflict(input: &Path, output: &Path) -> Result<(), PathValidationError> {
     let input_canonical = input.canonicalize().unwrap_or_else(|_| input.to_path_buf());
 
@@ -174,6 +206,21 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_validate_file_name() {
+        assert!(validate_file_name("clip_hevc.MP4").is_ok());
+        assert!(validate_file_name("视频 (1).mp4").is_ok());
+        assert!(validate_file_name("Wait....JXL").is_ok());
+
+        for unsafe_name in ["../escape.mp4", "a/b.mp4", "a\\b.mp4", ".", "..", " ", ""] {
+            assert!(
+                validate_file_name(unsafe_name).is_err(),
+                "file name should be rejected: {:?}",
+                unsafe_name
+            );
+        }
+    }
+
     #[test]
     fn test_shell_metacharacters_are_allowed_in_parameterized_paths() {
         let shellish_paths = [
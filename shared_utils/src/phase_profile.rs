@@ -0,0 +1,265 @@
+//! Phase Profile Module
+//!
+//! `--profile`: wall-clock time of each conversion phase (probing, GPU coarse search, CPU
+//! search, final encode, SSIM validation, metadata), collected per file on the thread that
+//! converts it. Phases nest: while an inner phase runs the outer one is paused, so the
+//! breakdown adds up to the file's wall time and "other" holds whatever no phase claimed.
+
+use serde::Serialize;
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProfilePhase {
+    Probe,
+    GpuCoarse,
+    CpuSearch,
+    FinalEncode,
+    SsimValidation,
+    Metadata,
+}
+
+impl ProfilePhase {
+    pub const ALL: [ProfilePhase; 6] = [
+        ProfilePhase::Probe,
+        ProfilePhase::GpuCoarse,
+        ProfilePhase::CpuSearch,
+        ProfilePhase::FinalEncode,
+        ProfilePhase::SsimValidation,
+        ProfilePhase::Metadata,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ProfilePhase::Probe => "probe",
+            ProfilePhase::GpuCoarse => "GPU coarse search",
+            ProfilePhase::CpuSearch => "CPU search",
+            ProfilePhase::FinalEncode => "final encode",
+            ProfilePhase::SsimValidation => "SSIM validation",
+            ProfilePhase::Metadata => "metadata",
+        }
+    }
+}
+
+/// Time spent per phase for one file, in first-seen order.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct PhaseTimings {
+    pub phases: Vec<(ProfilePhase, Duration)>,
+    /// Wall time of the whole file; `None` when the timings were not taken by
+    /// [`finish_file_profile`].
+    pub total: Option<Duration>,
+}
+
+impl PhaseTimings {
+    pub fn record(&mut self, phase: ProfilePhase, elapsed: Duration) {
+        match self.phases.iter_mut().find(|(p, _)| *p == phase) {
+            Some((_, total)) => *total += elapsed,
+            None => self.phases.push((phase, elapsed)),
+        }
+    }
+
+    pub fn get(&self, phase: ProfilePhase) -> Duration {
+        self.phases
+            .iter()
+            .find(|(p, _)| *p == phase)
+            .map_or(Duration::ZERO, |(_, d)| *d)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.phases.is_empty()
+    }
+
+    /// Wall time no phase accounted for (detection glue, remux bookkeeping, I/O).
+    pub fn unaccounted(&self) -> Duration {
+        let phases: Duration = self.phases.iter().map(|(_, d)| *d).sum();
+        self.total.unwrap_or(phases).saturating_sub(phases)
+    }
+
+    /// One line per phase in pipeline order, with its share of the file's wall time.
+    pub fn lines(&self) -> Vec<String> {
+        let total = self
+            .total
+            .unwrap_or_else(|| self.phases.iter().map(|(_, d)| *d).sum());
+        let share = |d: Duration| {
+            if total.is_zero() {
+                0.0
+            } else {
+                d.as_secs_f64() / total.as_secs_f64() * 100.0
+            }
+        };
+        let mut lines: Vec<String> = ProfilePhase::ALL
+            .iter()
+            .filter(|p| self.phases.iter().any(|(q, _)| q == *p))
+            .map(|p| {
+                let d = self.get(*p);
+                format!(
+                    "{:<18} {:>8.2}s {:>5.1}%",
+                    p.label(),
+                    d.as_secs_f64(),
+                    share(d)
+                )
+            })
+            .collect();
+        let other = self.unaccounted();
+        if !other.is_zero() {
+            lines.push(format!(
+                "{:<18} {:>8.2}s {:>5.1}%",
+                "other",
+                other.as_secs_f64(),
+                share(other)
+            ));
+        }
+        lines
+    }
+}
+
+static PROFILE: AtomicBool = AtomicBool::new(false);
+
+/// `--profile`: collect phase timings for every conversion and print them per file.
+pub fn set_profile(enabled: bool) {
+    PROFILE.store(enabled, Ordering::Relaxed);
+}
+
+pub fn profile_enabled() -> bool {
+    PROFILE.load(Ordering::Relaxed)
+}
+
+#[derive(Default)]
+struct FileProfile {
+    started: Option<Instant>,
+    timings: PhaseTimings,
+    /// Open phases, innermost last, each with the instant it last resumed.
+    stack: Vec<(ProfilePhase, Instant)>,
+}
+
+thread_local! {
+    static FILE_PROFILE: RefCell<FileProfile> = RefCell::new(FileProfile::default());
+}
+
+/// Times one phase until dropped; a no-op unless `--profile` is on.
+pub struct PhaseGuard {
+    active: bool,
+}
+
+impl Drop for PhaseGuard {
+    fn drop(&mut self) {
+        if !self.active {
+            return;
+        }
+        let now = Instant::now();
+        FILE_PROFILE.with(|p| {
+            let mut p = p.borrow_mut();
+            if let Some((phase, since)) = p.stack.pop() {
+                p.timings.record(phase, now - since);
+            }
+            if let Some((_, since)) = p.stack.last_mut() {
+                *since = now;
+            }
+        });
+    }
+}
+
+/// Start timing `phase` on this thread; the enclosing phase pauses until the guard drops.
+pub fn phase(phase: ProfilePhase) -> PhaseGuard {
+    if !profile_enabled() {
+        return PhaseGuard { active: false };
+    }
+    let now = Instant::now();
+    FILE_PROFILE.with(|p| {
+        let mut p = p.borrow_mut();
+        if let Some((outer, since)) = p.stack.last().copied() {
+            p.timings.record(outer, now - since);
+        }
+        p.stack.push((phase, now));
+    });
+    PhaseGuard { active: true }
+}
+
+/// Reset this thread's timings at the start of a file.
+pub fn begin_file_profile() {
+    if profile_enabled() {
+        FILE_PROFILE.with(|p| {
+            *p.borrow_mut() = FileProfile {
+                started: Some(Instant::now()),
+                ..FileProfile::default()
+            }
+        });
+    }
+}
+
+/// Take this thread's timings at the end of a file; `None` unless `--profile` is on.
+pub fn finish_file_profile() -> Option<PhaseTimings> {
+    if !profile_enabled() {
+        return None;
+    }
+    FILE_PROFILE.with(|p| {
+        let p = std::mem::take(&mut *p.borrow_mut());
+        let mut timings = p.timings;
+        timings.total = p.started.map(|s| s.elapsed());
+        Some(timings)
+    })
+}
+
+/// End-of-file hook for converters: print the breakdown for `label` when `--profile` is on.
+pub fn finish_file_profile_with_log(label: &str) {
+    let Some(timings) = finish_file_profile() else {
+        return;
+    };
+    crate::log_eprintln!(
+        "⏱️  Profile {} ({:.2}s):",
+        label,
+        timings.total.unwrap_or_default().as_secs_f64()
+    );
+    for line in timings.lines() {
+        crate::log_eprintln!("   {}", line);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nested_phases_are_exclusive() {
+        struct ProfileReset;
+        impl Drop for ProfileReset {
+            fn drop(&mut self) {
+                set_profile(false);
+            }
+        }
+        set_profile(true);
+        let _reset = ProfileReset;
+        begin_file_profile();
+        {
+            let _search = phase(ProfilePhase::CpuSearch);
+            std::thread::sleep(Duration::from_millis(20));
+            {
+                let _ssim = phase(ProfilePhase::SsimValidation);
+                std::thread::sleep(Duration::from_millis(30));
+            }
+            std::thread::sleep(Duration::from_millis(20));
+        }
+        let timings = finish_file_profile().unwrap();
+        let search = timings.get(ProfilePhase::CpuSearch);
+        let ssim = timings.get(ProfilePhase::SsimValidation);
+        assert!(ssim >= Duration::from_millis(30));
+        // The SSIM time is not counted again under the search that contains it.
+        assert!(search >= Duration::from_millis(40));
+        assert!(search < Duration::from_millis(40) + ssim);
+        assert!(timings.total.unwrap() >= search + ssim);
+        assert_eq!(timings.lines()[0].split_whitespace().next(), Some("CPU"));
+    }
+
+    #[test]
+    fn test_unaccounted_time() {
+        let mut timings = PhaseTimings::default();
+        timings.record(ProfilePhase::Probe, Duration::from_secs(1));
+        timings.record(ProfilePhase::Probe, Duration::from_secs(1));
+        timings.total = Some(Duration::from_secs(5));
+        assert_eq!(timings.get(ProfilePhase::Probe), Duration::from_secs(2));
+        assert_eq!(timings.unaccounted(), Duration::from_secs(3));
+        assert_eq!(timings.lines().len(), 2);
+    }
+}
@@ -25,6 +25,8 @@ static ACTIVE_PROGRESS_LINE: Mutex<Option<String>> = Mutex::new(None);
 pub struct CoarseProgressBar {
     total: u64,
     current: AtomicU64,
+    total_bytes: u64,
+    done_bytes: AtomicU64,
     start_time: Instant,
     prefix: String,
     last_render: Arc<Mutex<Instant>>,
@@ -264,6 +266,26 @@ fn build_coarse_progress_line(
     )
 }
 
+/// Completed fraction of a batch in [0, 1]. When input sizes are known the fraction is
+/// weighted by bytes, so one 4K video counts for more than a folder of thumbnails;
+/// otherwise it falls back to the file count.
+fn batch_fraction(current: u64, total: u64, done_bytes: u64, total_bytes: u64) -> f64 {
+    let fraction = if total_bytes > 0 {
+        done_bytes as f64 / total_bytes as f64
+    } else {
+        current as f64 / total.max(1) as f64
+    };
+    fraction.clamp(0.0, 1.0)
+}
+
+/// Remaining seconds, extrapolated from the time spent on the completed fraction.
+fn remaining_secs(elapsed: Duration, fraction: f64) -> Option<u64> {
+    if fraction <= 0.0 || fraction >= 1.0 {
+        return None;
+    }
+    Some((elapsed.as_secs_f64() * (1.0 - fraction) / fraction) as u64)
+}
+
 fn build_finished_progress_line(
     prefix: &str,
     total: u64,
@@ -318,6 +340,8 @@ impl CoarseProgressBar {
         Self {
             total,
             current: AtomicU64::new(0),
+            total_bytes: 0,
+            done_bytes: AtomicU64::new(0),
             start_time: Instant::now(),
             prefix: prefix.to_string(),
             last_render: Arc::new(Mutex::new(Instant::now())),
@@ -327,6 +351,17 @@ impl CoarseProgressBar {
         }
     }
 
+    /// Weight percent and ETA by input size; pair with `add_bytes` as files finish.
+    pub fn with_total_bytes(mut self, total_bytes: u64) -> Self {
+        self.total_bytes = total_bytes;
+        self
+    }
+
+    /// Count a finished file's input size. Rendering happens on the next `set`.
+    pub fn add_bytes(&self, bytes: u64) {
+        self.done_bytes.fetch_add(bytes, Ordering::Relaxed);
+    }
+
     pub fn set(&self, current: u64) {
         self.current.store(current, Ordering::Relaxed);
 
@@ -381,7 +416,13 @@ impl CoarseProgressBar {
 
         let current = self.current.load(Ordering::Relaxed);
         let total = self.total.max(1);
-        let percent = (current as f64 / total as f64 * 100.0).min(100.0);
+        let fraction = batch_fraction(
+            current,
+            total,
+            self.done_bytes.load(Ordering::Relaxed),
+            self.total_bytes,
+        );
+        let percent = fraction * 100.0;
         let elapsed = self.start_time.elapsed();
         let message = self
             .message
@@ -390,12 +431,9 @@ impl CoarseProgressBar {
             .unwrap_or_default();
         let stats = crate::progress_mode::get_current_stats_string();
 
-        let eta_str = if current > 0 && current < total {
-            let avg_time = elapsed.as_secs_f64() / current as f64;
-            let remaining_secs = ((total - current) as f64 * avg_time) as u64;
-            format_eta_simple(remaining_secs)
-        } else {
-            "---".to_string()
+        let eta_str = match remaining_secs(elapsed, fraction) {
+            Some(secs) if current < total => format_eta_simple(secs),
+            _ => "---".to_string(),
         };
         let terminal_width = terminal_columns().max(48);
         let line = build_coarse_progress_line(
@@ -1445,6 +1483,20 @@ mod tests {
     use super::*;
     use console::measure_text_width;
 
+    #[test]
+    fn test_batch_fraction_weights_by_bytes() {
+        // Two thumbnails done out of two thumbnails and one large video.
+        let by_count = batch_fraction(2, 3, 0, 0);
+        let by_bytes = batch_fraction(2, 3, 20_000, 1_020_000);
+        assert!((by_count - 2.0 / 3.0).abs() < 1e-9);
+        assert!(by_bytes < 0.02);
+        assert_eq!(batch_fraction(5, 3, 0, 0), 1.0);
+
+        assert_eq!(remaining_secs(Duration::from_secs(10), 0.25), Some(30));
+        assert_eq!(remaining_secs(Duration::from_secs(10), 0.0), None);
+        assert_eq!(remaining_secs(Duration::from_secs(10), 1.0), None);
+    }
+
     #[test]
     fn test_format_bytes() {
         assert_eq!(format_bytes(500), "500 B");
@@ -614,6 +614,40 @@ macro_rules! log_eprintln {
     }};
 }
 
+// ── Report stream ────────────────────────────────────────────────────────────
+// Summary tables and end-of-run reports go to stdout by default. `run --events-json` claims
+// stdout for JSON lines, so `JsonEventSink::stdout()` moves the human report to stderr.
+
+static REPORT_TO_STDERR: AtomicBool = AtomicBool::new(false);
+
+pub fn set_report_to_stderr(v: bool) {
+    REPORT_TO_STDERR.store(v, Ordering::Relaxed);
+}
+
+pub fn is_report_to_stderr() -> bool {
+    REPORT_TO_STDERR.load(Ordering::Relaxed)
+}
+
+/// Write one human report line to stdout, or to stderr while stdout carries JSON events.
+pub fn emit_report(line: &str) {
+    if is_report_to_stderr() {
+        eprintln!("{}", line);
+    } else {
+        println!("{}", line);
+    }
+}
+
+/// `println!` for human reports (summary, audits, end-of-run notes); see [`emit_report`].
+#[macro_export]
+macro_rules! report_println {
+    () => {
+        $crate::progress_mode::emit_report("")
+    };
+    ($($arg:tt)*) => {
+        $crate::progress_mode::emit_report(&format!($($arg)*))
+    };
+}
+
 // ── XMP merge + JXL + Images live counter ────────────────────────────────────
 // Tracks XMP sidecar merge, JXL success, and image conversion success/failure; same line.
 
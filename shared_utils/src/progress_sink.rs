@@ -0,0 +1,317 @@
+//! Structured Progress Events for Library Consumers
+//!
+//! `UnifiedProgressBar` renders to the terminal; embedders (e.g. a GUI) instead
+//! set `ConversionConfig::progress_sink` and receive structured events:
+//!
+//! - `on_file_start` / `on_file_done` / `on_file_failed` around each `auto_convert` call
+//! - `on_iteration` for every CRF tried by the explorer (SSIM when measured)
+//!
+//! The explorer does not take the config directly, so the conversion entry point
+//! installs the sink for the current thread (`install`) and the search loop
+//! reports through `emit_iteration`. Without a configured sink, the entry point keeps
+//! the one already installed on the thread: the `run` batch bar, which `cli_runner`
+//! installs on every worker.
+//!
+//! `JsonEventSink` backs `run --events-json`: one JSON line per lifecycle event on stdout.
+
+use crate::cli_runner::CliProcessingResult;
+use crate::conversion_types::ConversionOutput;
+use serde::Serialize;
+use std::cell::RefCell;
+use std::fmt;
+use std::io::Write;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+/// Receiver for conversion progress events. All methods default to no-ops.
+pub trait ProgressSink: Send + Sync {
+    fn on_file_start(&self, _input: &Path) {}
+
+    /// One explore iteration: CRF tried, SSIM if measured for this step, output size in bytes.
+    fn on_iteration(&self, _crf: f32, _ssim: Option<f64>, _size: u64) {}
+
+    fn on_file_done(&self, _output: &ConversionOutput) {}
+
+    /// The conversion returned an error (no `ConversionOutput` was produced).
+    fn on_file_failed(&self, _input: &Path, _error: &str) {}
+}
+
+impl fmt::Debug for dyn ProgressSink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ProgressSink")
+    }
+}
+
+/// Default implementation: drives an existing batch progress bar.
+impl ProgressSink for crate::UnifiedProgressBar {
+    fn on_file_start(&self, input: &Path) {
+        self.set_message(input.file_name().unwrap_or_default().to_string_lossy());
+    }
+
+    fn on_iteration(&self, crf: f32, ssim: Option<f64>, _size: u64) {
+        let ssim_str = ssim
+            .map(|s| format!("SSIM {:.4}", s))
+            .unwrap_or_else(|| "N/A".to_string());
+        self.set_message(format!("CRF {:.1} | {}", crf, ssim_str));
+    }
+
+    fn on_file_done(&self, _output: &ConversionOutput) {
+        self.inc();
+    }
+
+    fn on_file_failed(&self, _input: &Path, _error: &str) {
+        self.inc();
+    }
+}
+
+/// The `run` batch bar: shows the current file and the CRF being tried. The runner counts
+/// finished files itself, so done/failed are not forwarded.
+impl ProgressSink for crate::CoarseProgressBar {
+    fn on_file_start(&self, input: &Path) {
+        self.set_message(&input.file_name().unwrap_or_default().to_string_lossy());
+    }
+
+    fn on_iteration(&self, crf: f32, ssim: Option<f64>, _size: u64) {
+        let ssim_str = ssim
+            .map(|s| format!("SSIM {:.4}", s))
+            .unwrap_or_else(|| "N/A".to_string());
+        self.set_message(&format!("CRF {:.1} | {}", crf, ssim_str));
+    }
+}
+
+/// One `--events-json` line. Skip, success and failed outputs carry the full
+/// `ConversionOutput` fields next to `event`.
+#[derive(Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+enum LifecycleEvent<'a> {
+    Start { input: String },
+    Skip(&'a ConversionOutput),
+    Success(&'a ConversionOutput),
+    Fail(Failure<'a>),
+}
+
+#[derive(Serialize)]
+#[serde(untagged)]
+enum Failure<'a> {
+    Output(&'a ConversionOutput),
+    Error { input: String, error: &'a str },
+}
+
+/// Writes lifecycle events as JSON lines. The writer sits behind a mutex and every line is
+/// written and flushed under the lock, so parallel conversions never interleave partial JSON.
+pub struct JsonEventSink {
+    out: Mutex<Box<dyn Write + Send>>,
+}
+
+impl JsonEventSink {
+    pub fn new(out: Box<dyn Write + Send>) -> Self {
+        Self {
+            out: Mutex::new(out),
+        }
+    }
+
+    /// Events on stdout. Human reports move to stderr so stdout stays pure JSON lines.
+    pub fn stdout() -> Self {
+        crate::progress_mode::set_report_to_stderr(true);
+        Self::new(Box::new(std::io::stdout()))
+    }
+
+    fn emit(&self, event: &LifecycleEvent<'_>) {
+        let Ok(line) = serde_json::to_string(event) else {
+            return;
+        };
+        let mut out = self.out.lock().unwrap_or_else(|e| e.into_inner());
+        // A closed pipe (e.g. `| head`) must not abort the batch.
+        let _ = writeln!(out, "{}", line).and_then(|_| out.flush());
+    }
+}
+
+impl ProgressSink for JsonEventSink {
+    fn on_file_start(&self, input: &Path) {
+        self.emit(&LifecycleEvent::Start {
+            input: input.display().to_string(),
+        });
+    }
+
+    fn on_file_done(&self, output: &ConversionOutput) {
+        let event = if output.is_skipped() {
+            LifecycleEvent::Skip(output)
+        } else if output.is_success() {
+            LifecycleEvent::Success(output)
+        } else {
+            LifecycleEvent::Fail(Failure::Output(output))
+        };
+        self.emit(&event);
+    }
+
+    fn on_file_failed(&self, input: &Path, error: &str) {
+        self.emit(&LifecycleEvent::Fail(Failure::Error {
+            input: input.display().to_string(),
+            error,
+        }));
+    }
+}
+
+thread_local! {
+    static CURRENT_SINK: RefCell<Option<Arc<dyn ProgressSink>>> = const { RefCell::new(None) };
+}
+
+/// Restores the previously installed sink on drop.
+pub struct ProgressSinkGuard {
+    previous: Option<Arc<dyn ProgressSink>>,
+}
+
+impl Drop for ProgressSinkGuard {
+    fn drop(&mut self) {
+        let previous = self.previous.take();
+        CURRENT_SINK.with(|s| *s.borrow_mut() = previous);
+    }
+}
+
+/// Install `sink` for the current thread until the returned guard is dropped.
+pub fn install(sink: Option<Arc<dyn ProgressSink>>) -> ProgressSinkGuard {
+    let previous = CURRENT_SINK.with(|s| std::mem::replace(&mut *s.borrow_mut(), sink));
+    ProgressSinkGuard { previous }
+}
+
+/// The sink installed on this thread, if any.
+pub fn current() -> Option<Arc<dyn ProgressSink>> {
+    CURRENT_SINK.with(|s| s.borrow().clone())
+}
+
+/// Report one explore iteration to the sink installed on this thread, if any.
+pub fn emit_iteration(crf: f32, ssim: Option<f64>, size: u64) {
+    if let Some(sink) = current() {
+        sink.on_iteration(crf, ssim, size);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct Recorder {
+        iterations: Mutex<Vec<(f32, Option<f64>, u64)>>,
+    }
+
+    impl ProgressSink for Recorder {
+        fn on_iteration(&self, crf: f32, ssim: Option<f64>, size: u64) {
+            self.iterations.lock().unwrap().push((crf, ssim, size));
+        }
+    }
+
+    /// Shared buffer so the test can read what the sink wrote.
+    #[derive(Clone, Default)]
+    struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuf {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn output(path: &str, output_path: &str, output_size: u64, success: bool) -> ConversionOutput {
+        ConversionOutput {
+            input_path: path.to_string(),
+            output_path: output_path.to_string(),
+            strategy: crate::conversion_types::ConversionStrategy {
+                target: crate::conversion_types::TargetVideoFormat::HevcMp4,
+                reason: String::new(),
+                command: String::new(),
+                preserve_audio: true,
+                crf: 20.0,
+                lossless: false,
+            },
+            input_size: 1000,
+            output_size,
+            size_ratio: output_size as f64 / 1000.0,
+            success,
+            message: "done".to_string(),
+            final_crf: 20.0,
+            exploration_attempts: 3,
+            benchmark: None,
+            skip_reason: None,
+            ssim: None,
+        }
+    }
+
+    #[test]
+    fn test_json_event_lines_do_not_interleave() {
+        let buf = SharedBuf::default();
+        let sink = Arc::new(JsonEventSink::new(Box::new(buf.clone())));
+        let handles: Vec<_> = (0..8)
+            .map(|i| {
+                let sink = sink.clone();
+                std::thread::spawn(move || {
+                    let input = format!("/in/{}.mp4", i);
+                    sink.on_file_start(Path::new(&input));
+                    match i % 3 {
+                        0 => sink.on_file_done(&output(&input, "/out/x.mp4", 500, true)),
+                        1 => sink.on_file_done(&output(&input, "", 0, true)),
+                        _ => sink.on_file_failed(Path::new(&input), "ffmpeg failed"),
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        let text = String::from_utf8(buf.0.lock().unwrap().clone()).unwrap();
+        let events: Vec<serde_json::Value> = text
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(events.len(), 16);
+        let count = |name: &str| events.iter().filter(|e| e["event"] == name).count();
+        assert_eq!(count("start"), 8);
+        assert_eq!(count("success"), 3);
+        assert_eq!(count("skip"), 3);
+        assert_eq!(count("fail"), 2);
+        let success = events.iter().find(|e| e["event"] == "success").unwrap();
+        assert_eq!(success["output_size"], 500);
+        assert_eq!(success["final_crf"], 20.0);
+    }
+
+    #[test]
+    fn test_emit_without_sink_is_noop() {
+        emit_iteration(20.0, None, 1);
+    }
+
+    #[test]
+    fn test_install_scopes_sink_to_guard() {
+        let recorder = Arc::new(Recorder::default());
+        {
+            let _guard = install(Some(recorder.clone()));
+            emit_iteration(23.5, Some(0.985), 1000);
+            emit_iteration(22.0, None, 1200);
+        }
+        emit_iteration(21.0, None, 1400);
+
+        let seen = recorder.iterations.lock().unwrap();
+        assert_eq!(seen.len(), 2);
+        assert_eq!(seen[0], (23.5, Some(0.985), 1000));
+        assert_eq!(seen[1], (22.0, None, 1200));
+    }
+
+    #[test]
+    fn test_nested_install_restores_previous() {
+        let outer = Arc::new(Recorder::default());
+        let inner = Arc::new(Recorder::default());
+        let _outer_guard = install(Some(outer.clone()));
+        {
+            let _inner_guard = install(Some(inner.clone()));
+            emit_iteration(30.0, None, 1);
+        }
+        emit_iteration(31.0, None, 2);
+
+        assert_eq!(inner.iterations.lock().unwrap().len(), 1);
+        assert_eq!(outer.iterations.lock().unwrap()[0].0, 31.0);
+    }
+}
@@ -34,6 +34,7 @@
 //! - **Conservative on uncertainty**: When in doubt, prefer higher quality (lower CRF)
 
 use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum EncoderType {
@@ -237,6 +238,48 @@ pub enum ContentType {
 }
 
 impl ContentType {
+    pub const ALL: [ContentType; 6] = [
+        ContentType::LiveAction,
+        ContentType::Animation,
+        ContentType::ScreenRecording,
+        ContentType::Gaming,
+        ContentType::FilmGrain,
+        ContentType::Unknown,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ContentType::LiveAction => "live-action",
+            ContentType::Animation => "animation",
+            ContentType::ScreenRecording => "screen",
+            ContentType::Gaming => "gaming",
+            ContentType::FilmGrain => "film-grain",
+            ContentType::Unknown => "unknown",
+        }
+    }
+
+    /// `--content-type` value; `film` is accepted for live action.
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let value = value.trim().to_ascii_lowercase().replace('_', "-");
+        match value.as_str() {
+            "film" => return Ok(ContentType::LiveAction),
+            "screen-recording" => return Ok(ContentType::ScreenRecording),
+            _ => {}
+        }
+        Self::ALL
+            .iter()
+            .find(|c| c.as_str() == value)
+            .copied()
+            .ok_or_else(|| {
+                let names: Vec<&str> = Self::ALL.iter().map(|c| c.as_str()).collect();
+                format!(
+                    "Invalid content type '{}' (expected one of: {})",
+                    value,
+                    names.join(", ")
+                )
+            })
+    }
+
     pub fn crf_adjustment(&self) -> i8 {
         match self {
             ContentType::Animation => 4,
@@ -325,6 +368,9 @@ pub struct AnalysisDetails {
     pub confidence: f64,
     pub match_mode: MatchMode,
     pub quality_bias: QualityBias,
+    /// `--crf-bias` offset added to the matched CRF before rounding and clamping.
+    #[serde(default)]
+    pub crf_bias: f64,
 }
 
 fn default_one() -> f64 {
@@ -352,6 +398,7 @@ impl Default for AnalysisDetails {
             confidence: 0.0,
             match_mode: MatchMode::Quality,
             quality_bias: QualityBias::Balanced,
+            crf_bias: 0.0,
         }
     }
 }
@@ -369,15 +416,18 @@ const HEVC_CRF_CLAMP_MIN: f32 = 0.0;
 const HEVC_CRF_CLAMP_MAX: f32 = 51.0;
 
 pub fn calculate_av1_crf(analysis: &QualityAnalysis) -> Result<MatchedQuality, String> {
-    calculate_av1_crf_with_options(analysis, MatchMode::Quality, QualityBias::Balanced)
+    calculate_av1_crf_with_options(analysis, MatchMode::Quality, QualityBias::Balanced, 0.0)
 }
 
+/// `crf_bias` (`--crf-bias`) shifts the matched CRF by a constant after the `bias` step:
+/// negative for higher quality, positive for smaller output.
 pub fn calculate_av1_crf_with_options(
     analysis: &QualityAnalysis,
     mode: MatchMode,
     bias: QualityBias,
+    crf_bias: f64,
 ) -> Result<MatchedQuality, String> {
-    let (mut effective_bpp, details) =
+    let (mut effective_bpp, mut details) =
         calculate_effective_bpp_with_options(analysis, EncoderType::Av1, mode, bias)?;
 
     if effective_bpp <= 0.0 {
@@ -417,7 +467,8 @@ pub fn calculate_av1_crf_with_options(
         QualityBias::Conservative => crf_with_content - 2.0,
         QualityBias::Balanced => crf_with_content,
         QualityBias::Aggressive => crf_with_content + 2.0,
-    };
+    } + crf_bias;
+    details.crf_bias = crf_bias;
 
     let crf_rounded = (crf_with_bias * 2.0).round() / 2.0;
     // Last line of defense: guarantee CRF in valid range regardless of extreme BPP or content/bias.
@@ -432,15 +483,17 @@ pub fn calculate_av1_crf_with_options(
 }
 
 pub fn calculate_hevc_crf(analysis: &QualityAnalysis) -> Result<MatchedQuality, String> {
-    calculate_hevc_crf_with_options(analysis, MatchMode::Quality, QualityBias::Balanced)
+    calculate_hevc_crf_with_options(analysis, MatchMode::Quality, QualityBias::Balanced, 0.0)
 }
 
+/// See [`calculate_av1_crf_with_options`] for `crf_bias`.
 pub fn calculate_hevc_crf_with_options(
     analysis: &QualityAnalysis,
     mode: MatchMode,
     bias: QualityBias,
+    crf_bias: f64,
 ) -> Result<MatchedQuality, String> {
-    let (mut effective_bpp, details) =
+    let (mut effective_bpp, mut details) =
         calculate_effective_bpp_with_options(analysis, EncoderType::Hevc, mode, bias)?;
 
     if effective_bpp <= 0.0 {
@@ -479,7 +532,8 @@ pub fn calculate_hevc_crf_with_options(
         QualityBias::Conservative => crf_with_content - 2.0,
         QualityBias::Balanced => crf_with_content,
         QualityBias::Aggressive => crf_with_content + 2.0,
-    };
+    } + crf_bias;
+    details.crf_bias = crf_bias;
 
     let crf_rounded = (crf_with_bias * 2.0).round() / 2.0;
     let crf = (crf_rounded as f32).clamp(HEVC_CRF_CLAMP_MIN, HEVC_CRF_CLAMP_MAX);
@@ -492,6 +546,25 @@ pub fn calculate_hevc_crf_with_options(
     })
 }
 
+pub fn calculate_h264_crf(analysis: &QualityAnalysis) -> Result<MatchedQuality, String> {
+    calculate_h264_crf_with_options(analysis, MatchMode::Quality, QualityBias::Balanced, 0.0)
+}
+
+/// x264 CRF for the Apple-compat H.264 fallback: the HEVC match shifted by
+/// `X264_CRF_OFFSET_FROM_HEVC`, clamped to the x264 range.
+pub fn calculate_h264_crf_with_options(
+    analysis: &QualityAnalysis,
+    mode: MatchMode,
+    bias: QualityBias,
+    crf_bias: f64,
+) -> Result<MatchedQuality, String> {
+    use crate::crf_constants::{X264_CRF_MAX, X264_CRF_MIN, X264_CRF_OFFSET_FROM_HEVC};
+
+    let hevc = calculate_hevc_crf_with_options(analysis, mode, bias, crf_bias)?;
+    let crf = (hevc.crf - X264_CRF_OFFSET_FROM_HEVC).clamp(X264_CRF_MIN, X264_CRF_MAX);
+    Ok(MatchedQuality { crf, ..hevc })
+}
+
 pub fn calculate_jxl_distance(analysis: &QualityAnalysis) -> Result<MatchedQuality, String> {
     calculate_jxl_distance_with_options(analysis, MatchMode::Quality, QualityBias::Balanced)
 }
@@ -565,6 +638,83 @@ pub fn calculate_jxl_distance_with_options(
     })
 }
 
+/// Predict a CRF for `encoder` (HEVC or AV1) together with a 0–1 confidence in that prediction.
+///
+/// Confidence is the product of three terms:
+/// - **Metadata completeness** (`AnalysisDetails::confidence`): how many of the analysis inputs
+///   (dimensions, bitrate, fps, duration, GOP, pix_fmt, ...) were actually available.
+/// - **Codec mapping reliability**: mainstream delivery codecs (H.264/HEVC/VP9/AV1/MPEG-2/MPEG-4)
+///   have well-calibrated efficiency factors; intermediate, legacy and unknown codecs do not.
+/// - **BPP range**: the CRF formula is linear in `log2(bpp)` only inside the calibrated region;
+///   outside of it the result is clamped and therefore less trustworthy.
+///
+/// Callers can pass the pair to `gpu_boundary_to_cpu_range_with_confidence` to narrow the search.
+pub fn predict_crf_with_confidence(
+    analysis: &QualityAnalysis,
+    encoder: EncoderType,
+) -> Result<(f64, f32), String> {
+    let result = match encoder {
+        EncoderType::Hevc => calculate_hevc_crf(analysis)?,
+        EncoderType::Av1 => calculate_av1_crf(analysis)?,
+        EncoderType::Jxl => {
+            return Err("❌ CRF confidence is only defined for HEVC/AV1 encoders".to_string());
+        }
+    };
+
+    let codec_reliability = codec_mapping_reliability(parse_source_codec(&analysis.source_codec));
+
+    let (bpp_low, bpp_high) = match encoder {
+        EncoderType::Av1 => (0.03, 2.0),
+        _ => (0.02, 2.0),
+    };
+    let bpp_reliability = if (bpp_low..=bpp_high).contains(&result.effective_bpp) {
+        1.0
+    } else {
+        0.7
+    };
+
+    let confidence =
+        (result.analysis_details.confidence * codec_reliability * bpp_reliability).clamp(0.0, 1.0);
+
+    Ok((result.crf as f64, confidence as f32))
+}
+
+/// How well the efficiency factor of `codec` is backed by measured data points (0–1).
+fn codec_mapping_reliability(codec: SourceCodec) -> f64 {
+    match codec {
+        SourceCodec::H264
+        | SourceCodec::H265
+        | SourceCodec::Vp9
+        | SourceCodec::Av1
+        | SourceCodec::Mpeg4
+        | SourceCodec::Mpeg2 => 1.0,
+        SourceCodec::ProRes | SourceCodec::DnxHD | SourceCodec::Mjpeg | SourceCodec::Vp8 => 0.85,
+        SourceCodec::Ffv1
+        | SourceCodec::UtVideo
+        | SourceCodec::HuffYuv
+        | SourceCodec::RawVideo
+        | SourceCodec::Lagarith
+        | SourceCodec::MagicYuv => 0.8,
+        SourceCodec::Mpeg1
+        | SourceCodec::Wmv
+        | SourceCodec::Theora
+        | SourceCodec::RealVideo
+        | SourceCodec::FlashVideo
+        | SourceCodec::Vvc
+        | SourceCodec::Av2 => 0.7,
+        SourceCodec::Gif | SourceCodec::Apng | SourceCodec::WebpAnimated => 0.65,
+        SourceCodec::Jpeg
+        | SourceCodec::JpegXl
+        | SourceCodec::Png
+        | SourceCodec::WebpStatic
+        | SourceCodec::Avif
+        | SourceCodec::Heic
+        | SourceCodec::Bmp
+        | SourceCodec::Tiff => 0.6,
+        SourceCodec::Unknown => 0.5,
+    }
+}
+
 fn calculate_effective_bpp_with_options(
     analysis: &QualityAnalysis,
     target_encoder: EncoderType,
@@ -666,6 +816,7 @@ fn calculate_effective_bpp_with_options(
         confidence,
         match_mode: mode,
         quality_bias: bias,
+        crf_bias: 0.0,
     };
 
     Ok((effective_bpp, details))
@@ -1121,6 +1272,41 @@ pub fn parse_source_codec(codec_str: &str) -> SourceCodec {
     SourceCodec::Unknown
 }
 
+/// CRF change that halves (or doubles) the output bitrate, i.e. the slope of the
+/// `log2(bpp)` term in `calculate_hevc_crf` (5) and `calculate_av1_crf` (6).
+pub fn crf_doubling_step(encoder: EncoderType) -> f64 {
+    match encoder {
+        EncoderType::Hevc => 5.0,
+        EncoderType::Av1 | EncoderType::Jxl => 6.0,
+    }
+}
+
+/// Heuristic size of a lossy re-encode of `source_video_bytes` (a `source_codec` stream).
+///
+/// At `matched_crf` the output keeps the source's quality, so it is the source size scaled
+/// by the codecs' relative `efficiency_factor`. Each `crf_doubling_step` above the matched CRF
+/// halves that, each step below doubles it. The result never exceeds the source size, which
+/// matches what the pipeline keeps. Typical error is within ±25% on camera/web footage; noisy,
+/// grainy or synthetic content and intra/lossless sources can miss by more.
+pub fn estimate_encoded_video_bytes(
+    source_video_bytes: u64,
+    source_codec: &str,
+    encoder: EncoderType,
+    matched_crf: f64,
+    crf: f64,
+) -> u64 {
+    let source_eff = parse_source_codec(source_codec).efficiency_factor();
+    let target_eff = match encoder {
+        EncoderType::Hevc => SourceCodec::H265.efficiency_factor(),
+        EncoderType::Av1 => SourceCodec::Av1.efficiency_factor(),
+        EncoderType::Jxl => SourceCodec::JpegXl.efficiency_factor(),
+    };
+    let codec_ratio = (target_eff / source_eff.max(0.01)).min(1.0);
+    let crf_ratio = 2f64.powf((matched_crf - crf) / crf_doubling_step(encoder));
+    let estimate = source_video_bytes as f64 * codec_ratio * crf_ratio;
+    (estimate.round() as u64).min(source_video_bytes)
+}
+
 pub fn log_quality_analysis(
     analysis: &QualityAnalysis,
     result: &MatchedQuality,
@@ -1143,6 +1329,9 @@ pub fn log_quality_analysis(
         "      Mode: {:?} | Bias: {:?}",
         d.match_mode, d.quality_bias
     );
+    if d.crf_bias != 0.0 {
+        eprintln!("      CRF bias: {:+.1} (--crf-bias)", d.crf_bias);
+    }
     eprintln!("      Confidence: {:.0}%", d.confidence * 100.0);
     eprintln!();
 
@@ -1376,6 +1565,8 @@ pub struct SkipDecision {
     pub should_skip: bool,
     pub reason: String,
     pub codec: SourceCodec,
+    /// Summary category; `Some` exactly when `should_skip`.
+    pub kind: Option<crate::conversion_types::SkipReason>,
 }
 
 pub fn should_skip_video_codec(codec_str: &str) -> SkipDecision {
@@ -1413,6 +1604,43 @@ pub fn should_skip_video_codec(codec_str: &str) -> SkipDecision {
         should_skip,
         reason,
         codec,
+        kind: should_skip.then_some(crate::conversion_types::SkipReason::ModernLossy),
+    }
+}
+
+static RECONVERT: AtomicBool = AtomicBool::new(false);
+
+/// Process-wide `--reconvert` switch: re-encode sources that are already in the target codec.
+pub fn set_reconvert(enabled: bool) {
+    RECONVERT.store(enabled, Ordering::Relaxed);
+}
+
+pub fn reconvert_enabled() -> bool {
+    RECONVERT.load(Ordering::Relaxed)
+}
+
+/// Generation-loss guard for video targets, the counterpart of the modern-lossy image skip: a
+/// modern source ([`should_skip_video_codec`]) already in the `target` codec (e.g. our own
+/// output fed back in) is skipped, since re-encoding it only loses quality. Off under
+/// `--reconvert`; `codec` is set either way, so callers can tell an override from other codecs.
+pub fn should_skip_same_codec_reencode(codec_str: &str, target: SourceCodec) -> SkipDecision {
+    let modern = should_skip_video_codec(codec_str);
+    let should_skip = modern.should_skip && modern.codec == target && !reconvert_enabled();
+
+    let reason = if should_skip {
+        format!(
+            "Source is already {} (the target codec) - skipping to avoid generation loss (use --reconvert to re-encode)",
+            codec_str
+        )
+    } else {
+        String::new()
+    };
+
+    SkipDecision {
+        should_skip,
+        reason,
+        codec: modern.codec,
+        kind: should_skip.then_some(crate::conversion_types::SkipReason::ModernLossy),
     }
 }
 
@@ -1431,6 +1659,7 @@ pub fn should_skip_video_codec_apple_compat(codec_str: &str) -> SkipDecision {
         should_skip,
         reason,
         codec,
+        kind: should_skip.then_some(crate::conversion_types::SkipReason::AppleNative),
     }
 }
 
@@ -1509,10 +1738,17 @@ pub fn should_skip_image_format(format_str: &str, is_lossless: bool) -> SkipDeci
         String::new()
     };
 
+    let kind = if is_jxl {
+        Some(crate::conversion_types::SkipReason::AlreadyOptimal)
+    } else {
+        should_skip.then_some(crate::conversion_types::SkipReason::ModernLossy)
+    };
+
     SkipDecision {
         should_skip,
         reason,
         codec,
+        kind,
     }
 }
 
@@ -1561,6 +1797,19 @@ pub fn from_image_analysis(
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_content_type_parse() {
+        for content in ContentType::ALL {
+            assert_eq!(ContentType::parse(content.as_str()), Ok(content));
+        }
+        assert_eq!(ContentType::parse("Film"), Ok(ContentType::LiveAction));
+        assert_eq!(
+            ContentType::parse("screen_recording"),
+            Ok(ContentType::ScreenRecording)
+        );
+        assert!(ContentType::parse("cartoon").is_err());
+    }
+
     #[test]
     fn test_av1_crf_calculation() {
         let analysis = QualityAnalysis {
@@ -1605,6 +1854,31 @@ mod tests {
         assert!(result.crf <= 35.0);
     }
 
+    #[test]
+    fn test_h264_crf_offset_from_hevc() {
+        let analysis = QualityAnalysis {
+            bpp: 0.3,
+            source_codec: "h264".to_string(),
+            width: 1920,
+            height: 1080,
+            has_b_frames: true,
+            bit_depth: 8,
+            has_alpha: false,
+            duration_secs: Some(30.0),
+            fps: Some(30.0),
+            file_size: 50_000_000,
+            estimated_quality: None,
+            ..Default::default()
+        };
+
+        let hevc = calculate_hevc_crf(&analysis).unwrap();
+        let h264 = calculate_h264_crf(&analysis).unwrap();
+        let expected = (hevc.crf - crate::crf_constants::X264_CRF_OFFSET_FROM_HEVC)
+            .clamp(crate::X264_CRF_MIN, crate::X264_CRF_MAX);
+        assert!((h264.crf - expected).abs() < 0.01);
+        assert!(h264.crf <= hevc.crf);
+    }
+
     #[test]
     fn test_jxl_distance_with_quality() {
         let analysis = QualityAnalysis {
@@ -1664,19 +1938,58 @@ mod tests {
             &analysis,
             MatchMode::Quality,
             QualityBias::Conservative,
+            0.0,
+        )
+        .unwrap();
+        let balanced = calculate_av1_crf_with_options(
+            &analysis,
+            MatchMode::Quality,
+            QualityBias::Balanced,
+            0.0,
+        )
+        .unwrap();
+        let aggressive = calculate_av1_crf_with_options(
+            &analysis,
+            MatchMode::Quality,
+            QualityBias::Aggressive,
+            0.0,
         )
         .unwrap();
-        let balanced =
-            calculate_av1_crf_with_options(&analysis, MatchMode::Quality, QualityBias::Balanced)
-                .unwrap();
-        let aggressive =
-            calculate_av1_crf_with_options(&analysis, MatchMode::Quality, QualityBias::Aggressive)
-                .unwrap();
 
         assert!(conservative.crf <= balanced.crf);
         assert!(aggressive.crf >= balanced.crf);
     }
 
+    #[test]
+    fn test_crf_bias_offsets_and_clamps() {
+        let analysis = QualityAnalysis {
+            bpp: 0.3,
+            source_codec: "h264".to_string(),
+            width: 1920,
+            height: 1080,
+            file_size: 100_000_000,
+            fps: Some(30.0),
+            duration_secs: Some(60.0),
+            ..Default::default()
+        };
+        let hevc = |crf_bias| {
+            calculate_hevc_crf_with_options(
+                &analysis,
+                MatchMode::Quality,
+                QualityBias::Balanced,
+                crf_bias,
+            )
+            .unwrap()
+        };
+
+        let auto = hevc(0.0);
+        let biased = hevc(-2.0);
+        assert_eq!(biased.crf, auto.crf - 2.0);
+        assert_eq!(biased.analysis_details.crf_bias, -2.0);
+        assert_eq!(hevc(100.0).crf, HEVC_CRF_CLAMP_MAX);
+        assert_eq!(hevc(-100.0).crf, HEVC_CRF_CLAMP_MIN);
+    }
+
     #[test]
     fn test_parse_source_codec() {
         assert_eq!(parse_source_codec("h264"), SourceCodec::H264);
@@ -1702,6 +2015,20 @@ mod tests {
         assert_eq!(parse_source_codec("unknown_codec"), SourceCodec::Unknown);
     }
 
+    #[test]
+    fn test_estimate_encoded_video_bytes() {
+        let matched =
+            estimate_encoded_video_bytes(1_000_000, "h264", EncoderType::Hevc, 23.0, 23.0);
+        assert_eq!(matched, 650_000);
+
+        let higher_crf =
+            estimate_encoded_video_bytes(1_000_000, "h264", EncoderType::Hevc, 23.0, 28.0);
+        assert_eq!(higher_crf, 325_000);
+
+        let av1 = estimate_encoded_video_bytes(1_000_000, "h264", EncoderType::Av1, 30.0, 18.0);
+        assert_eq!(av1, 1_000_000, "never estimated above the source size");
+    }
+
     #[test]
     fn test_codec_properties() {
         assert!(SourceCodec::H265.is_modern());
@@ -1810,6 +2137,48 @@ mod tests {
         assert!(!should_skip_image_format("heif", true).should_skip); // lossless HEIF → JXL
     }
 
+    #[test]
+    fn test_skip_decision_kind() {
+        use crate::conversion_types::SkipReason;
+        assert_eq!(
+            should_skip_image_format("webp", false).kind,
+            Some(SkipReason::ModernLossy)
+        );
+        assert_eq!(
+            should_skip_image_format("jxl", true).kind,
+            Some(SkipReason::AlreadyOptimal)
+        );
+        assert_eq!(should_skip_image_format("png", true).kind, None);
+        assert_eq!(
+            should_skip_video_codec("av1").kind,
+            Some(SkipReason::ModernLossy)
+        );
+        assert_eq!(
+            should_skip_video_codec_apple_compat("hevc").kind,
+            Some(SkipReason::AppleNative)
+        );
+        assert_eq!(should_skip_video_codec_apple_compat("av1").kind, None);
+    }
+
+    #[test]
+    fn test_should_skip_same_codec_reencode() {
+        let hevc = should_skip_same_codec_reencode("H.265", SourceCodec::H265);
+        assert!(hevc.should_skip);
+        assert!(hevc.reason.contains("H.265"));
+        assert!(hevc.reason.contains("--reconvert"));
+        assert!(should_skip_same_codec_reencode("av1", SourceCodec::Av1).should_skip);
+
+        assert!(!should_skip_same_codec_reencode("av1", SourceCodec::H265).should_skip);
+        assert!(!should_skip_same_codec_reencode("h264", SourceCodec::H265).should_skip);
+
+        set_reconvert(true);
+        let forced = should_skip_same_codec_reencode("hevc", SourceCodec::H265);
+        set_reconvert(false);
+        assert!(!forced.should_skip);
+        assert_eq!(forced.codec, SourceCodec::H265);
+        assert_eq!(forced.kind, None);
+    }
+
     #[test]
     fn test_precision_1080p_h264_8mbps() {
         let analysis = VideoAnalysisBuilder::new()
@@ -2253,11 +2622,15 @@ mod tests {
             .pix_fmt("yuv420p")
             .build();
 
-        let quality =
-            calculate_av1_crf_with_options(&analysis, MatchMode::Quality, QualityBias::Balanced)
-                .unwrap();
+        let quality = calculate_av1_crf_with_options(
+            &analysis,
+            MatchMode::Quality,
+            QualityBias::Balanced,
+            0.0,
+        )
+        .unwrap();
         let size =
-            calculate_av1_crf_with_options(&analysis, MatchMode::Size, QualityBias::Balanced)
+            calculate_av1_crf_with_options(&analysis, MatchMode::Size, QualityBias::Balanced, 0.0)
                 .unwrap();
 
         assert!(
@@ -2683,14 +3056,23 @@ mod tests {
             &analysis,
             MatchMode::Quality,
             QualityBias::Conservative,
+            0.0,
+        )
+        .unwrap();
+        let balanced = calculate_av1_crf_with_options(
+            &analysis,
+            MatchMode::Quality,
+            QualityBias::Balanced,
+            0.0,
+        )
+        .unwrap();
+        let aggressive = calculate_av1_crf_with_options(
+            &analysis,
+            MatchMode::Quality,
+            QualityBias::Aggressive,
+            0.0,
         )
         .unwrap();
-        let balanced =
-            calculate_av1_crf_with_options(&analysis, MatchMode::Quality, QualityBias::Balanced)
-                .unwrap();
-        let aggressive =
-            calculate_av1_crf_with_options(&analysis, MatchMode::Quality, QualityBias::Aggressive)
-                .unwrap();
 
         assert!(
             conservative.crf < balanced.crf,
@@ -2714,6 +3096,57 @@ mod tests {
             "Aggressive should be exactly 2 more than Balanced"
         );
     }
+
+    #[test]
+    fn test_predict_crf_with_confidence_matches_hevc_crf() {
+        let analysis = VideoAnalysisBuilder::new()
+            .basic("h264", 1920, 1080, 30.0, 60.0)
+            .file_size(60_000_000)
+            .video_bitrate(8_000_000)
+            .gop(60, 2)
+            .pix_fmt("yuv420p")
+            .bit_depth(8)
+            .build();
+
+        let (crf, confidence) = predict_crf_with_confidence(&analysis, EncoderType::Hevc).unwrap();
+        let expected = calculate_hevc_crf(&analysis).unwrap();
+        assert_eq!(crf, expected.crf as f64);
+        assert!(
+            confidence > 0.8 && confidence <= 1.0,
+            "well-described H.264 source should be high confidence, got {}",
+            confidence
+        );
+    }
+
+    #[test]
+    fn test_predict_crf_confidence_lower_for_unknown_codec() {
+        let known = VideoAnalysisBuilder::new()
+            .basic("h264", 1920, 1080, 30.0, 60.0)
+            .video_bitrate(8_000_000)
+            .build();
+        let unknown = VideoAnalysisBuilder::new()
+            .basic("mystery_codec", 1920, 1080, 30.0, 60.0)
+            .video_bitrate(8_000_000)
+            .build();
+
+        let (_, known_conf) = predict_crf_with_confidence(&known, EncoderType::Av1).unwrap();
+        let (_, unknown_conf) = predict_crf_with_confidence(&unknown, EncoderType::Av1).unwrap();
+        assert!(
+            unknown_conf < known_conf,
+            "unknown codec ({}) should be less confident than h264 ({})",
+            unknown_conf,
+            known_conf
+        );
+    }
+
+    #[test]
+    fn test_predict_crf_with_confidence_rejects_jxl() {
+        let analysis = VideoAnalysisBuilder::new()
+            .basic("h264", 1920, 1080, 30.0, 60.0)
+            .video_bitrate(8_000_000)
+            .build();
+        assert!(predict_crf_with_confidence(&analysis, EncoderType::Jxl).is_err());
+    }
 }
 
 #[test]
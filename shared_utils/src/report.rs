@@ -5,6 +5,8 @@
 
 use crate::batch::BatchResult;
 use crate::progress::{format_bytes, format_duration};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
 use std::time::Duration;
 
 pub fn print_summary_report(
@@ -16,18 +18,15 @@ pub fn print_summary_report(
 ) {
     use crate::modern_ui::colors::*;
 
-    let reduction = if input_bytes > 0 {
-        (1.0 - output_bytes as f64 / input_bytes as f64) * 100.0
-    } else {
-        0.0
-    };
+    let reduction = reduction_pct(input_bytes, output_bytes);
 
-    println!();
-    println!(
+    crate::report_println!();
+    crate::report_println!(
         "{}╭────────────────────────────────────────────────────────────────────────────╮{}",
-        MFB_BLUE, RESET
+        MFB_BLUE,
+        RESET
     );
-    println!(
+    crate::report_println!(
         "{}│{}  {}📊 {} Summary Report{}{}                                        {}│{}",
         MFB_BLUE,
         RESET,
@@ -38,47 +37,98 @@ pub fn print_summary_report(
         MFB_BLUE,
         RESET
     );
-    println!(
+    crate::report_println!(
         "{}├────────────────────────────────────────────────────────────────────────────┤{}",
-        MFB_BLUE, RESET
+        MFB_BLUE,
+        RESET
     );
-    println!(
+    crate::report_println!(
         "{}│{}  📁 Files Processed:    {:>10}                                         {}│{}",
-        MFB_BLUE, RESET, result.total, MFB_BLUE, RESET
+        MFB_BLUE,
+        RESET,
+        result.total,
+        MFB_BLUE,
+        RESET
     );
-    println!(
+    crate::report_println!(
         "{}│{}  {}✅ Succeeded:           {:>10}{}                                         {}│{}",
-        MFB_BLUE, RESET, BRIGHT_GREEN, result.succeeded, RESET, MFB_BLUE, RESET
+        MFB_BLUE,
+        RESET,
+        BRIGHT_GREEN,
+        result.succeeded,
+        RESET,
+        MFB_BLUE,
+        RESET
     );
-    println!(
+    crate::report_println!(
         "{}│{}  {}❌ Failed:              {:>10}{}                                         {}│{}",
-        MFB_BLUE, RESET, BRIGHT_RED, result.failed, RESET, MFB_BLUE, RESET
+        MFB_BLUE,
+        RESET,
+        BRIGHT_RED,
+        result.failed,
+        RESET,
+        MFB_BLUE,
+        RESET
     );
-    println!(
+    crate::report_println!(
         "{}│{}  {}⏭️  Skipped:             {:>10}{}                                         {}│{}",
-        MFB_BLUE, RESET, BRIGHT_YELLOW, result.skipped, RESET, MFB_BLUE, RESET
+        MFB_BLUE,
+        RESET,
+        BRIGHT_YELLOW,
+        result.skipped,
+        RESET,
+        MFB_BLUE,
+        RESET
     );
+    for (reason, count) in result.skip_breakdown() {
+        let line = format!("       ↳ {:<24}{:>10}", reason.as_str(), count);
+        crate::report_println!(
+            "{}│{}{}{:<76}{}{}│{}",
+            MFB_BLUE,
+            RESET,
+            DIM,
+            line,
+            RESET,
+            MFB_BLUE,
+            RESET
+        );
+    }
     if result.paused {
-        println!(
+        crate::report_println!(
             "{}│{}  {}⏸️  Paused:              {:>10}{}                                         {}│{}",
             MFB_BLUE, RESET, BRIGHT_YELLOW, "YES", RESET, MFB_BLUE, RESET
         );
     }
+    if let Some((verified, reverted)) = result.verify_after {
+        crate::report_println!(
+            "{}│{}  🔎 Verified After:     {:>10}                                         {}│{}",
+            MFB_BLUE,
+            RESET,
+            verified,
+            MFB_BLUE,
+            RESET
+        );
+        crate::report_println!(
+            "{}│{}  {}↩️  Reverted:            {:>10}{}                                         {}│{}",
+            MFB_BLUE, RESET, BRIGHT_RED, reverted, RESET, MFB_BLUE, RESET
+        );
+    }
 
     let rate_color = if result.success_rate() > 90.0 {
         BRIGHT_GREEN
     } else {
         BRIGHT_YELLOW
     };
-    println!(
+    crate::report_println!(
         "{}│{}  {}📈 Success Rate:{}        {}{:>9.1}%{}                                         {}│{}",
         MFB_BLUE, RESET, BRIGHT_CYAN, RESET, rate_color, result.success_rate(), RESET, MFB_BLUE, RESET
     );
-    println!(
+    crate::report_println!(
         "{}├────────────────────────────────────────────────────────────────────────────┤{}",
-        MFB_BLUE, RESET
+        MFB_BLUE,
+        RESET
     );
-    println!(
+    crate::report_println!(
         "{}│{}  💾 Input Size:         {}{:>10}{}                                         {}│{}",
         MFB_BLUE,
         RESET,
@@ -94,7 +144,7 @@ pub fn print_summary_report(
     } else {
         BRIGHT_YELLOW
     };
-    println!(
+    crate::report_println!(
         "{}│{}  💾 Output Size:        {}{:>10}{}                                         {}│{}",
         MFB_BLUE,
         RESET,
@@ -104,15 +154,22 @@ pub fn print_summary_report(
         MFB_BLUE,
         RESET
     );
-    println!(
+    crate::report_println!(
         "{}│{}  📉 Size Reduction:     {}{:>9.1}%{}                                         {}│{}",
-        MFB_BLUE, RESET, out_color, reduction, RESET, MFB_BLUE, RESET
+        MFB_BLUE,
+        RESET,
+        out_color,
+        reduction,
+        RESET,
+        MFB_BLUE,
+        RESET
     );
-    println!(
+    crate::report_println!(
         "{}├────────────────────────────────────────────────────────────────────────────┤{}",
-        MFB_BLUE, RESET
+        MFB_BLUE,
+        RESET
     );
-    println!(
+    crate::report_println!(
         "{}│{}  ⏱️  Total Time:         {}{:>10}{}                                         {}│{}",
         MFB_BLUE,
         RESET,
@@ -124,46 +181,354 @@ pub fn print_summary_report(
     );
     if result.total > 0 {
         let avg_time = duration.as_secs_f64() / result.total as f64;
-        println!(
+        crate::report_println!(
             "{}│{}  ⏱️  Avg Time/File:      {}{:>9.2}s{}                                         {}│{}",
             MFB_BLUE, RESET, DIM, avg_time, RESET, MFB_BLUE, RESET
         );
     } else {
-        println!("{}│{}                                                                            {}│{}", MFB_BLUE, RESET, MFB_BLUE, RESET);
+        crate::report_println!("{}│{}                                                                            {}│{}", MFB_BLUE, RESET, MFB_BLUE, RESET);
     }
-    println!(
+    crate::report_println!(
         "{}╰────────────────────────────────────────────────────────────────────────────╯{}",
-        MFB_BLUE, RESET
+        MFB_BLUE,
+        RESET
     );
 
     if !result.errors.is_empty() {
-        println!();
-        println!("{}❌ Errors encountered:{}", BRIGHT_RED, RESET);
-        println!(
+        crate::report_println!();
+        crate::report_println!("{}❌ Errors encountered:{}", BRIGHT_RED, RESET);
+        crate::report_println!(
             "{}━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━{}",
-            BRIGHT_RED, RESET
+            BRIGHT_RED,
+            RESET
         );
         for (path, error) in &result.errors {
-            println!("   {}{} → {}{}", DIM, path.display(), RESET, error);
+            crate::report_println!("   {}{} → {}{}", DIM, path.display(), RESET, error);
         }
     }
 
     if let Some(pause) = &result.pause_info {
-        println!();
-        println!("{}⏸️ Batch Paused:{}", BRIGHT_YELLOW, RESET);
-        println!("   {}File:{} {}", DIM, RESET, pause.path.display());
-        println!("   {}Reason:{} {}", DIM, RESET, pause.reason);
-        println!(
+        crate::report_println!();
+        crate::report_println!("{}⏸️ Batch Paused:{}", BRIGHT_YELLOW, RESET);
+        crate::report_println!("   {}File:{} {}", DIM, RESET, pause.path.display());
+        crate::report_println!("   {}Reason:{} {}", DIM, RESET, pause.reason);
+        crate::report_println!(
             "   {}Pending:{} {} files remain for retry. Free space and rerun with `--resume`.",
-            DIM, RESET, result.paused_remaining
+            DIM,
+            RESET,
+            result.paused_remaining
+        );
+    }
+}
+
+/// Percentage size reduction from `input_bytes` to `output_bytes` (negative = growth).
+pub fn reduction_pct(input_bytes: u64, output_bytes: u64) -> f64 {
+    if input_bytes > 0 {
+        (1.0 - output_bytes as f64 / input_bytes as f64) * 100.0
+    } else {
+        0.0
+    }
+}
+
+/// Verbose companion to [`print_summary_report`]: whole-file vs pure video-stream reduction.
+/// Container overhead differs by format (see `stream_size`), so the stream figure shows the
+/// codec gain independent of remuxing (e.g. MKV → MP4).
+pub fn print_stream_ratio_summary(
+    file_input_bytes: u64,
+    file_output_bytes: u64,
+    stream_input_bytes: u64,
+    stream_output_bytes: u64,
+) {
+    use crate::modern_ui::colors::*;
+
+    crate::report_println!("{}📐 Compression Ratio{}", BOLD, RESET);
+    crate::report_println!(
+        "   Whole file:   {:>7.1}%  ({} → {})",
+        reduction_pct(file_input_bytes, file_output_bytes),
+        format_bytes(file_input_bytes),
+        format_bytes(file_output_bytes)
+    );
+    if stream_input_bytes > 0 && stream_output_bytes > 0 {
+        crate::report_println!(
+            "   Video stream: {:>7.1}%  ({} → {})",
+            reduction_pct(stream_input_bytes, stream_output_bytes),
+            format_bytes(stream_input_bytes),
+            format_bytes(stream_output_bytes)
+        );
+    } else {
+        crate::report_println!(
+            "   Video stream: {}n/a (stream sizes unavailable){}",
+            DIM,
+            RESET
+        );
+    }
+}
+
+/// A converted file whose output is not smaller than its source.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GrownOutput {
+    pub input: PathBuf,
+    pub output: Option<PathBuf>,
+    pub input_bytes: u64,
+    pub output_bytes: u64,
+}
+
+impl GrownOutput {
+    pub fn growth_pct(&self) -> f64 {
+        -reduction_pct(self.input_bytes, self.output_bytes)
+    }
+
+    pub fn wasted_bytes(&self) -> u64 {
+        self.output_bytes.saturating_sub(self.input_bytes)
+    }
+}
+
+/// `--only-larger-than-source`: collects successful conversions that did not shrink (kept only
+/// because of `--allow-size-tolerance`) so the run can end with a list worth reverting.
+#[derive(Debug, Default)]
+pub struct GrowthAudit {
+    entries: Mutex<Vec<GrownOutput>>,
+}
+
+impl GrowthAudit {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records the file when `output_bytes >= input_bytes`; smaller outputs are ignored.
+    pub fn record(&self, input: &Path, output: Option<&Path>, input_bytes: u64, output_bytes: u64) {
+        if input_bytes == 0 || output_bytes < input_bytes {
+            return;
+        }
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        entries.push(GrownOutput {
+            input: input.to_path_buf(),
+            output: output.map(Path::to_path_buf),
+            input_bytes,
+            output_bytes,
+        });
+    }
+
+    /// Recorded files, largest relative growth first.
+    pub fn entries(&self) -> Vec<GrownOutput> {
+        let mut entries = self
+            .entries
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone();
+        entries.sort_by(|a, b| b.growth_pct().total_cmp(&a.growth_pct()));
+        entries
+    }
+
+    pub fn wasted_bytes(&self) -> u64 {
+        let entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        entries.iter().map(GrownOutput::wasted_bytes).sum()
+    }
+}
+
+/// Report section for [`GrowthAudit`]: every output ≥ its source, with the growth and the
+/// total bytes spent on tolerance-allowed growth.
+pub fn print_growth_audit(audit: &GrowthAudit) {
+    use crate::modern_ui::colors::*;
+
+    let entries = audit.entries();
+    crate::report_println!();
+    if entries.is_empty() {
+        crate::report_println!(
+            "{}📏 Larger Than Source:{} none, every output shrank",
+            BOLD,
+            RESET
         );
+        return;
+    }
+    crate::report_println!(
+        "{}📏 Larger Than Source:{} {} file(s), {} wasted",
+        BOLD,
+        RESET,
+        entries.len(),
+        format_bytes(audit.wasted_bytes())
+    );
+    for entry in &entries {
+        crate::report_println!(
+            "   {}{:+7.1}%{}  {} → {}  {}",
+            BRIGHT_YELLOW,
+            entry.growth_pct(),
+            RESET,
+            format_bytes(entry.input_bytes),
+            format_bytes(entry.output_bytes),
+            entry.output.as_deref().unwrap_or(&entry.input).display()
+        );
+    }
+}
+
+/// `--sort-report-by`: order of the per-file table at the end of a run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ReportSortKey {
+    /// Input path, A→Z.
+    #[default]
+    Name,
+    /// Smallest reduction first, so conversions that barely helped come up top.
+    SizeReduction,
+    /// Largest source first.
+    OriginalSize,
+    /// Lowest SSIM first; files without a measurement last.
+    Ssim,
+}
+
+impl ReportSortKey {
+    pub const ALL: [ReportSortKey; 4] = [
+        ReportSortKey::SizeReduction,
+        ReportSortKey::OriginalSize,
+        ReportSortKey::Ssim,
+        ReportSortKey::Name,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReportSortKey::Name => "name",
+            ReportSortKey::SizeReduction => "size-reduction",
+            ReportSortKey::OriginalSize => "original-size",
+            ReportSortKey::Ssim => "ssim",
+        }
+    }
+
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let normalized = value.trim().to_ascii_lowercase().replace('_', "-");
+        Self::ALL
+            .into_iter()
+            .find(|k| k.as_str() == normalized)
+            .ok_or_else(|| {
+                format!(
+                    "Invalid report sort key '{}' (expected one of: {})",
+                    value,
+                    Self::ALL.map(|k| k.as_str()).join(", ")
+                )
+            })
+    }
+}
+
+/// One converted file in the per-file table.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileReportRow {
+    pub input: PathBuf,
+    pub input_bytes: u64,
+    pub output_bytes: u64,
+    pub ssim: Option<f64>,
+}
+
+impl FileReportRow {
+    pub fn reduction_pct(&self) -> f64 {
+        reduction_pct(self.input_bytes, self.output_bytes)
+    }
+}
+
+/// Collects every successful conversion of a run for [`print_file_report`].
+#[derive(Debug, Default)]
+pub struct FileReport {
+    rows: Mutex<Vec<FileReportRow>>,
+}
+
+impl FileReport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&self, input: &Path, input_bytes: u64, output_bytes: u64, ssim: Option<f64>) {
+        let mut rows = self.rows.lock().unwrap_or_else(|e| e.into_inner());
+        rows.push(FileReportRow {
+            input: input.to_path_buf(),
+            input_bytes,
+            output_bytes,
+            ssim,
+        });
+    }
+
+    /// Recorded files in `key` order; ties keep name order.
+    pub fn rows(&self, key: ReportSortKey) -> Vec<FileReportRow> {
+        let mut rows = self.rows.lock().unwrap_or_else(|e| e.into_inner()).clone();
+        rows.sort_by(|a, b| a.input.cmp(&b.input));
+        match key {
+            ReportSortKey::Name => {}
+            ReportSortKey::SizeReduction => {
+                rows.sort_by(|a, b| a.reduction_pct().total_cmp(&b.reduction_pct()))
+            }
+            ReportSortKey::OriginalSize => rows.sort_by(|a, b| b.input_bytes.cmp(&a.input_bytes)),
+            ReportSortKey::Ssim => rows.sort_by(|a, b| match (a.ssim, b.ssim) {
+                (Some(a), Some(b)) => a.total_cmp(&b),
+                (Some(_), None) => std::cmp::Ordering::Less,
+                (None, Some(_)) => std::cmp::Ordering::Greater,
+                (None, None) => std::cmp::Ordering::Equal,
+            }),
+        }
+        rows
+    }
+}
+
+/// Per-file table for [`FileReport`], shown in verbose mode or with `--sort-report-by`.
+pub fn print_file_report(report: &FileReport, key: ReportSortKey) {
+    use crate::modern_ui::colors::*;
+
+    let rows = report.rows(key);
+    if rows.is_empty() {
+        return;
+    }
+    crate::report_println!();
+    crate::report_println!(
+        "{}📋 Per-File Results:{} {} file(s), by {}",
+        BOLD,
+        RESET,
+        rows.len(),
+        key.as_str()
+    );
+    crate::report_println!(
+        "   {}{:>8}  {:>10}   {:>10}  {:>7}  path{}",
+        DIM,
+        "saved",
+        "original",
+        "output",
+        "ssim",
+        RESET
+    );
+    for row in &rows {
+        let reduction = row.reduction_pct();
+        let color = if reduction > 0.0 {
+            BRIGHT_GREEN
+        } else {
+            BRIGHT_YELLOW
+        };
+        crate::report_println!(
+            "   {}{:>7.1}%{}  {:>10} → {:>10}  {:>7}  {}",
+            color,
+            reduction,
+            RESET,
+            format_bytes(row.input_bytes),
+            format_bytes(row.output_bytes),
+            row.ssim
+                .map_or_else(|| "-".to_string(), |s| format!("{:.4}", s)),
+            row.input.display()
+        );
+    }
+}
+
+/// `--benchmark` report section: throughput per encoder, so GPU and CPU runs of one codec
+/// can be compared. Wall time is summed per file, so parallel `--jobs` runs show per-worker rates.
+pub fn print_benchmark_summary(result: &BatchResult) {
+    use crate::modern_ui::colors::*;
+
+    crate::report_println!();
+    crate::report_println!("{}⚡ Throughput{}", BOLD, RESET);
+    for (total, files) in result.benchmark_totals() {
+        crate::report_println!("   {}  {}({} file(s)){}", total, DIM, files, RESET);
     }
 }
 
 pub fn print_simple_summary(result: &BatchResult) {
-    println!(
+    crate::report_println!(
         "\n✅ Complete: {} succeeded, {} failed, {} skipped (total: {})",
-        result.succeeded, result.failed, result.skipped, result.total
+        result.succeeded,
+        result.failed,
+        result.skipped,
+        result.total
     );
 }
 
@@ -175,24 +540,117 @@ pub fn print_health_report(passed: usize, failed: usize, warnings: usize) {
         100.0
     };
 
-    println!();
-    println!("╔══════════════════════════════════════════════╗");
-    println!("║        🏥 Media Health Report                ║");
-    println!("╠══════════════════════════════════════════════╣");
-    println!("║  ✅ Passed:                        {:>6}  ║", passed);
-    println!("║  ❌ Failed:                        {:>6}  ║", failed);
-    println!("║  ⚠️  Warnings:                     {:>6}  ║", warnings);
-    println!(
+    crate::report_println!();
+    crate::report_println!("╔══════════════════════════════════════════════╗");
+    crate::report_println!("║        🏥 Media Health Report                ║");
+    crate::report_println!("╠══════════════════════════════════════════════╣");
+    crate::report_println!("║  ✅ Passed:                        {:>6}  ║", passed);
+    crate::report_println!("║  ❌ Failed:                        {:>6}  ║", failed);
+    crate::report_println!("║  ⚠️  Warnings:                     {:>6}  ║", warnings);
+    crate::report_println!(
         "║  📊 Health Rate:                  {:>5.1}%  ║",
         health_rate
     );
-    println!("╚══════════════════════════════════════════════╝");
+    crate::report_println!("╚══════════════════════════════════════════════╝");
+}
+
+/// Header of `analyze --csv`; [`video_analysis_csv_row`] fills one row per file.
+pub const VIDEO_ANALYSIS_CSV_HEADER: &str = "path,format,codec,width,height,duration,bitrate,\
+bit_depth,quality_score,archival_candidate,compression,target,reason,compression_level,\
+content_type";
+
+/// `value` as one CSV field: quoted, with inner quotes doubled, when it holds a comma, quote
+/// or line break.
+pub fn csv_field(value: &str) -> std::borrow::Cow<'_, str> {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\"")).into()
+    } else {
+        value.into()
+    }
+}
+
+/// The `analyze --csv` row for one video: the summary and `--json` fields, flattened. The
+/// `--deep` columns (`compression_level`, `content_type`) stay empty without an analysis.
+pub fn video_analysis_csv_row(
+    path: &Path,
+    detection: &crate::video_detection::VideoDetectionResult,
+    strategy: &crate::conversion_types::ConversionStrategy,
+    analysis: Option<&crate::video_quality_detector::VideoQualityAnalysis>,
+) -> String {
+    let fields = [
+        path.display().to_string(),
+        detection.format.clone(),
+        detection.codec.as_str().to_string(),
+        detection.width.to_string(),
+        detection.height.to_string(),
+        format!("{:.3}", detection.duration_secs),
+        detection.bitrate.to_string(),
+        detection.bit_depth.to_string(),
+        detection.quality_score.to_string(),
+        detection.archival_candidate.to_string(),
+        detection.compression.as_str().to_string(),
+        strategy.target.as_str().to_string(),
+        strategy.reason.clone(),
+        analysis.map_or_else(String::new, |a| a.compression_type.as_str().to_string()),
+        analysis.map_or_else(String::new, |a| a.content_type.as_str().to_string()),
+    ];
+    fields
+        .iter()
+        .map(|field| csv_field(field))
+        .collect::<Vec<_>>()
+        .join(",")
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_video_analysis_csv_row_matches_header_and_quotes_fields() {
+        use crate::conversion_types::{ConversionStrategy, TargetVideoFormat};
+        use crate::video_detection::{DetectedCodec, VideoDetectionResult};
+
+        let detection = VideoDetectionResult {
+            format: "mov,mp4,m4a,3gp,3g2,mj2".to_string(),
+            codec: DetectedCodec::H264,
+            width: 1920,
+            height: 1080,
+            duration_secs: 12.5,
+            bitrate: 8_000_000,
+            bit_depth: 8,
+            quality_score: 72,
+            ..VideoDetectionResult::default()
+        };
+        let strategy = ConversionStrategy {
+            target: TargetVideoFormat::HevcMp4,
+            reason: "H.264 source, \"lossy\" re-encode".to_string(),
+            command: String::new(),
+            preserve_audio: true,
+            crf: 20.0,
+            lossless: false,
+        };
+        let row = video_analysis_csv_row(Path::new("clips/a, b.mp4"), &detection, &strategy, None);
+
+        assert_eq!(
+            row,
+            format!(
+                "\"clips/a, b.mp4\",\"mov,mp4,m4a,3gp,3g2,mj2\",{},1920,1080,12.500,8000000,8,72,\
+                 false,{},{},\"H.264 source, \"\"lossy\"\" re-encode\",,",
+                DetectedCodec::H264.as_str(),
+                detection.compression.as_str(),
+                TargetVideoFormat::HevcMp4.as_str(),
+            )
+        );
+        assert_eq!(
+            VIDEO_ANALYSIS_CSV_HEADER.split(',').count(),
+            15,
+            "{}",
+            VIDEO_ANALYSIS_CSV_HEADER
+        );
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field("two\nlines"), "\"two\nlines\"");
+    }
+
     #[test]
     fn test_print_simple_summary_no_panic() {
         let mut result = BatchResult::new();
@@ -228,6 +686,77 @@ mod tests {
         print_summary_report(&result, duration, 0, 0, "Test");
     }
 
+    #[test]
+    fn test_reduction_pct() {
+        assert!((reduction_pct(1000, 600) - 40.0).abs() < 1e-9);
+        assert!((reduction_pct(1000, 1100) + 10.0).abs() < 1e-9);
+        assert_eq!(reduction_pct(0, 500), 0.0);
+    }
+
+    #[test]
+    fn test_print_stream_ratio_summary_no_panic() {
+        print_stream_ratio_summary(1000, 600, 900, 520);
+        print_stream_ratio_summary(1000, 600, 0, 0);
+    }
+
+    #[test]
+    fn test_growth_audit_keeps_only_outputs_not_smaller_than_source() {
+        let audit = GrowthAudit::new();
+        audit.record(Path::new("a.mp4"), Some(Path::new("out/a.mp4")), 1000, 600);
+        audit.record(Path::new("b.mp4"), Some(Path::new("out/b.mp4")), 1000, 1000);
+        audit.record(Path::new("c.mp4"), Some(Path::new("out/c.mp4")), 1000, 1200);
+        audit.record(Path::new("d.mp4"), None, 0, 10);
+
+        let entries = audit.entries();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].input, PathBuf::from("c.mp4"));
+        assert!((entries[0].growth_pct() - 20.0).abs() < 1e-9);
+        assert_eq!(entries[1].input, PathBuf::from("b.mp4"));
+        assert_eq!(audit.wasted_bytes(), 200);
+        print_growth_audit(&audit);
+        print_growth_audit(&GrowthAudit::new());
+    }
+
+    #[test]
+    fn test_report_sort_key_parse() {
+        assert_eq!(
+            ReportSortKey::parse("Size_Reduction"),
+            Ok(ReportSortKey::SizeReduction)
+        );
+        assert_eq!(ReportSortKey::parse(" ssim "), Ok(ReportSortKey::Ssim));
+        assert!(ReportSortKey::parse("speed")
+            .unwrap_err()
+            .contains("size-reduction, original-size, ssim, name"));
+    }
+
+    #[test]
+    fn test_file_report_sort_orders() {
+        let report = FileReport::new();
+        report.record(Path::new("b.mp4"), 4000, 1000, Some(0.95));
+        report.record(Path::new("a.mp4"), 1000, 990, None);
+        report.record(Path::new("c.mp4"), 2000, 1000, Some(0.99));
+
+        let names = |key| {
+            report
+                .rows(key)
+                .into_iter()
+                .map(|r| r.input.display().to_string())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names(ReportSortKey::Name), ["a.mp4", "b.mp4", "c.mp4"]);
+        assert_eq!(
+            names(ReportSortKey::SizeReduction),
+            ["a.mp4", "c.mp4", "b.mp4"]
+        );
+        assert_eq!(
+            names(ReportSortKey::OriginalSize),
+            ["b.mp4", "c.mp4", "a.mp4"]
+        );
+        assert_eq!(names(ReportSortKey::Ssim), ["b.mp4", "c.mp4", "a.mp4"]);
+        print_file_report(&report, ReportSortKey::SizeReduction);
+        print_file_report(&FileReport::new(), ReportSortKey::Name);
+    }
+
     #[test]
     fn test_print_health_report_no_panic() {
         print_health_report(10, 2, 3);
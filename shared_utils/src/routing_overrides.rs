@@ -0,0 +1,287 @@
+//! Per-file Routing Overrides
+//!
+//! Loads an optional `.mfb_overrides` file from the input directory that maps
+//! glob patterns to forced routing actions.
+//!
+//! File format (one rule per line, `#` starts a comment):
+//!
+//! ```text
+//! # pattern            action
+//! screenshots/*.png    force_skip
+//! *.gif                target=webp
+//! stickers/**          force_convert
+//! ```
+//!
+//! Precedence: an explicit override beats automatic routing
+//! (`should_skip_image_format`, meme-score, modern-lossy skip), which beats
+//! the defaults. When several rules match a file, the last one wins, so
+//! specific rules belong below general ones.
+//!
+//! Patterns containing `/` are matched against the path relative to the input
+//! directory; patterns without `/` are matched against the file name only.
+//! `*` and `?` do not cross `/`; `**` matches any number of path segments.
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// File name looked up in the input directory.
+pub const OVERRIDES_FILE_NAME: &str = ".mfb_overrides";
+
+/// Output target requested by a `target=<codec>` rule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OverrideTarget {
+    Av1,
+    Hevc,
+    Webp,
+}
+
+impl OverrideTarget {
+    fn parse(s: &str) -> Option<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "av1" => Some(Self::Av1),
+            "hevc" | "h265" => Some(Self::Hevc),
+            "webp" => Some(Self::Webp),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for OverrideTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Av1 => write!(f, "av1"),
+            Self::Hevc => write!(f, "hevc"),
+            Self::Webp => write!(f, "webp"),
+        }
+    }
+}
+
+/// Forced action for files matching an override rule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RoutingAction {
+    /// Convert even if automatic routing would skip the file.
+    ForceConvert,
+    /// Skip (copy through) without analysis.
+    ForceSkip,
+    /// Convert to a specific target; implies `ForceConvert`.
+    Target(OverrideTarget),
+}
+
+impl RoutingAction {
+    fn parse(s: &str) -> Option<Self> {
+        if let Some(target) = s.strip_prefix("target=") {
+            return OverrideTarget::parse(target.trim()).map(Self::Target);
+        }
+        match s {
+            "force_convert" => Some(Self::ForceConvert),
+            "force_skip" => Some(Self::ForceSkip),
+            _ => None,
+        }
+    }
+
+    /// True when automatic skip logic must be bypassed.
+    pub fn forces_conversion(&self) -> bool {
+        matches!(self, Self::ForceConvert | Self::Target(_))
+    }
+}
+
+impl fmt::Display for RoutingAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ForceConvert => write!(f, "force_convert"),
+            Self::ForceSkip => write!(f, "force_skip"),
+            Self::Target(t) => write!(f, "target={}", t),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct OverrideRule {
+    pattern: String,
+    action: RoutingAction,
+}
+
+/// Parsed `.mfb_overrides` rules, anchored at the directory they were loaded from.
+#[derive(Debug, Clone)]
+pub struct RoutingOverrides {
+    root: PathBuf,
+    rules: Vec<OverrideRule>,
+}
+
+impl RoutingOverrides {
+    /// Load `<dir>/.mfb_overrides`. Returns `Ok(None)` if the file does not exist.
+    pub fn load(dir: &Path) -> anyhow::Result<Option<Self>> {
+        let path = dir.join(OVERRIDES_FILE_NAME);
+        if !path.is_file() {
+            return Ok(None);
+        }
+        let content = std::fs::read_to_string(&path)
+            .map_err(|e| anyhow::anyhow!("Failed to read {}: {}", path.display(), e))?;
+        Self::parse(dir, &content)
+            .map(Some)
+            .map_err(|e| anyhow::anyhow!("{}: {}", path.display(), e))
+    }
+
+    /// Parse override rules; `root` is the directory relative patterns are anchored to.
+    pub fn parse(root: &Path, content: &str) -> anyhow::Result<Self> {
+        let mut rules = Vec::new();
+        for (idx, raw) in content.lines().enumerate() {
+            let line = raw.split('#').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+            let mut parts = line.split_whitespace();
+            let (Some(pattern), Some(action), None) = (parts.next(), parts.next(), parts.next())
+            else {
+                anyhow::bail!("line {}: expected `<pattern> <action>`", idx + 1);
+            };
+            let Some(action) = RoutingAction::parse(action) else {
+                anyhow::bail!(
+                    "line {}: unknown action '{}' (expected force_convert, force_skip or target=<av1|hevc|webp>)",
+                    idx + 1,
+                    action
+                );
+            };
+            rules.push(OverrideRule {
+                pattern: pattern.trim_start_matches("./").to_string(),
+                action,
+            });
+        }
+        Ok(Self {
+            root: root.to_path_buf(),
+            rules,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.rules.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Action forced for `path`, if any rule matches (last match wins).
+    pub fn lookup(&self, path: &Path) -> Option<RoutingAction> {
+        let rel = path.strip_prefix(&self.root).unwrap_or(path);
+        let rel = rel.to_string_lossy().replace('\\', "/");
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default();
+
+        self.rules
+            .iter()
+            .rev()
+            .find(|rule| {
+                if rule.pattern.contains('/') {
+                    glob_match(&rule.pattern, &rel)
+                } else {
+                    glob_match(&rule.pattern, &name)
+                }
+            })
+            .map(|rule| rule.action)
+    }
+}
+
+/// Minimal glob matcher: `*` and `?` stay within a path segment, `**` spans segments.
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let p: Vec<char> = pattern.chars().collect();
+    let t: Vec<char> = text.chars().collect();
+    glob_match_from(&p, &t)
+}
+
+fn glob_match_from(p: &[char], t: &[char]) -> bool {
+    match p.first() {
+        None => t.is_empty(),
+        Some('*') if p.get(1) == Some(&'*') => {
+            // `**/` also matches zero directories.
+            let rest = if p.get(2) == Some(&'/') {
+                &p[3..]
+            } else {
+                &p[2..]
+            };
+            (0..=t.len()).any(|i| glob_match_from(rest, &t[i..]))
+        }
+        Some('*') => {
+            let rest = &p[1..];
+            let mut i = 0;
+            loop {
+                if glob_match_from(rest, &t[i..]) {
+                    return true;
+                }
+                if i == t.len() || t[i] == '/' {
+                    return false;
+                }
+                i += 1;
+            }
+        }
+        Some('?') => !t.is_empty() && t[0] != '/' && glob_match_from(&p[1..], &t[1..]),
+        Some(&c) => !t.is_empty() && t[0] == c && glob_match_from(&p[1..], &t[1..]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("*.png", "a.png"));
+        assert!(!glob_match("*.png", "dir/a.png"));
+        assert!(glob_match("dir/*.png", "dir/a.png"));
+        assert!(glob_match("dir/**/*.png", "dir/a.png"));
+        assert!(glob_match("dir/**/*.png", "dir/x/y/a.png"));
+        assert!(glob_match("stickers/**", "stickers/x/a.gif"));
+        assert!(glob_match("IMG_????.HEIC", "IMG_0001.HEIC"));
+        assert!(!glob_match("IMG_????.HEIC", "IMG_001.HEIC"));
+    }
+
+    #[test]
+    fn test_parse_and_lookup_last_match_wins() {
+        let root = Path::new("/photos");
+        let overrides = RoutingOverrides::parse(
+            root,
+            "# comment\n\
+             *.webp          force_convert\n\
+             \n\
+             keep/*.webp     force_skip   # trailing comment\n\
+             anim/*.gif      target=av1\n",
+        )
+        .unwrap();
+        assert_eq!(overrides.len(), 3);
+        assert_eq!(
+            overrides.lookup(Path::new("/photos/a.webp")),
+            Some(RoutingAction::ForceConvert)
+        );
+        assert_eq!(
+            overrides.lookup(Path::new("/photos/keep/a.webp")),
+            Some(RoutingAction::ForceSkip)
+        );
+        assert_eq!(
+            overrides.lookup(Path::new("/photos/anim/x.gif")),
+            Some(RoutingAction::Target(OverrideTarget::Av1))
+        );
+        assert_eq!(overrides.lookup(Path::new("/photos/x.gif")), None);
+    }
+
+    #[test]
+    fn test_parse_rejects_bad_lines() {
+        let root = Path::new("/");
+        assert!(RoutingOverrides::parse(root, "*.png").is_err());
+        assert!(RoutingOverrides::parse(root, "*.png explode").is_err());
+        assert!(RoutingOverrides::parse(root, "*.png target=mpeg2").is_err());
+    }
+
+    #[test]
+    fn test_load_missing_file() {
+        let dir = tempfile::TempDir::new().unwrap();
+        assert!(RoutingOverrides::load(dir.path()).unwrap().is_none());
+        std::fs::write(dir.path().join(OVERRIDES_FILE_NAME), "*.jpg force_skip\n").unwrap();
+        let loaded = RoutingOverrides::load(dir.path()).unwrap().unwrap();
+        assert_eq!(
+            loaded.lookup(&dir.path().join("a.jpg")),
+            Some(RoutingAction::ForceSkip)
+        );
+    }
+}
@@ -16,6 +16,7 @@ use anyhow::{Context, Result};
 use std::fs;
 use std::io::Read;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
 
 /// Minimum buffer size for format detection. Video containers need at least 12 bytes (e.g. RIFF+AVI), ftyp uses 8..12.
 const DETECT_BUF_LEN: usize = 32;
@@ -192,8 +193,13 @@ pub fn smart_copy_with_structure(
     verbose: bool,
 ) -> Result<PathBuf> {
     let dest = if let Some(base) = base_dir {
-        let rel_path = source.strip_prefix(base).unwrap_or(source);
-        output_dir.join(rel_path)
+        if crate::conversion::flatten_output_enabled() {
+            let file_name = source.file_name().context("Source file has no filename")?;
+            crate::conversion::flatten_output_path(source, Some(base), output_dir.join(file_name))
+        } else {
+            let rel_path = source.strip_prefix(base).unwrap_or(source);
+            output_dir.join(rel_path)
+        }
     } else {
         let file_name = source.file_name().context("Source file has no filename")?;
         output_dir.join(file_name)
@@ -226,16 +232,47 @@ pub fn smart_copy_with_structure(
 
     let dest = fix_extension_if_mismatch(&dest)?;
 
-    crate::copy_metadata(source, &dest);
+    // Copied unchanged: the strip policy only applies to encoded outputs.
+    crate::copy_metadata(source, &dest, crate::StripPolicy::default());
 
     Ok(dest)
 }
 
+static COPY_SKIPPED: AtomicBool = AtomicBool::new(true);
+
+/// Process-wide `--no-copy-skipped` switch (stored inverted). While off, skipped and failed
+/// files are not copied through, so the output directory holds converted files only instead
+/// of a complete mirror of the input.
+pub fn set_copy_skipped(enabled: bool) {
+    COPY_SKIPPED.store(enabled, Ordering::Relaxed);
+}
+
+pub fn copy_skipped_enabled() -> bool {
+    COPY_SKIPPED.load(Ordering::Relaxed)
+}
+
+/// Copy a skipped or failed `source` into `output_dir`, keeping its place in the tree.
+/// `Ok(None)` without an output directory or under `--no-copy-skipped`.
 pub fn copy_on_skip_or_fail(
     source: &Path,
     output_dir: Option<&Path>,
     base_dir: Option<&Path>,
     verbose: bool,
+) -> Result<Option<PathBuf>> {
+    if !copy_skipped_enabled() {
+        return Ok(None);
+    }
+    copy_through(source, output_dir, base_dir, verbose)
+}
+
+/// `copy_on_skip_or_fail` without the `--no-copy-skipped` switch, for files that are copied
+/// through by design (`--metadata-only-formats`) rather than as skips.
+/// `Ok(None)` without an output directory.
+pub fn copy_through(
+    source: &Path,
+    output_dir: Option<&Path>,
+    base_dir: Option<&Path>,
+    verbose: bool,
 ) -> Result<Option<PathBuf>> {
     if let Some(out_dir) = output_dir {
         match smart_copy_with_structure(source, out_dir, base_dir, verbose) {
@@ -285,6 +322,21 @@ mod tests {
         assert!(result.is_none());
     }
 
+    #[test]
+    fn test_copy_through_copies_into_output_dir() {
+        let temp = TempDir::new().unwrap();
+        let source = temp.path().join("raw.dng");
+        let output = temp.path().join("out");
+        fs::write(&source, "raw").unwrap();
+
+        let dest = copy_through(&source, Some(&output), None, false)
+            .unwrap()
+            .unwrap();
+        assert_eq!(dest, output.join("raw.dng"));
+        assert!(dest.exists());
+        assert!(copy_through(&source, None, None, false).unwrap().is_none());
+    }
+
     /// Content is video (MP4 ftyp+isom) but extension was wrong → corrected to .mp4.
     #[test]
     fn test_fix_extension_video_content_wrong_ext() {
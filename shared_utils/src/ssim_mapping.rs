@@ -3,6 +3,29 @@
 //! v5.74: 用于透明度数据预测，不影响搜索目标
 
 use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::OnceLock;
+
+/// User calibration (`--psnr-ssim-calibration`), fitted once at startup.
+static USER_CALIBRATION: OnceLock<PsnrSsimMapping> = OnceLock::new();
+
+/// Install a user calibration for the process. Returns false if one was already set.
+pub fn set_global_calibration(mapping: PsnrSsimMapping) -> bool {
+    USER_CALIBRATION.set(mapping.fit()).is_ok()
+}
+
+/// The user calibration, if one was installed and has at least two points.
+pub fn global_calibration() -> Option<&'static PsnrSsimMapping> {
+    USER_CALIBRATION.get().filter(|m| m.len() >= 2)
+}
+
+/// PSNR→SSIM via the user calibration when present, else [`psnr_to_ssim_estimate`].
+pub fn calibrated_psnr_to_ssim(psnr_db: f64) -> f64 {
+    global_calibration()
+        .and_then(|m| m.predict_ssim(psnr_db))
+        .map(|ssim| ssim.clamp(0.0, 0.9999))
+        .unwrap_or_else(|| psnr_to_ssim_estimate(psnr_db))
+}
 
 /// Uncalibrated PSNR→SSIM estimate used when no mapping/calibration is available.
 /// Single formula shared by explore_strategy and other fallbacks so quality decisions are consistent.
@@ -34,6 +57,95 @@ impl PsnrSsimMapping {
         Self { points: Vec::new() }
     }
 
+    /// Build from arbitrary points: sorted by PSNR, later duplicates replace earlier ones.
+    /// Non-finite values are dropped.
+    pub fn from_points(points: Vec<MappingPoint>) -> Self {
+        let mut mapping = Self::new();
+        for point in points {
+            if point.psnr.is_finite() && point.ssim.is_finite() {
+                mapping.insert(point.psnr, point.ssim);
+            }
+        }
+        mapping
+    }
+
+    /// Parse calibration CSV: one `psnr,ssim` pair per line. Blank lines, `#` comments and a
+    /// header (two non-numeric fields before the first pair) are skipped; any other malformed
+    /// line is an error.
+    pub fn from_csv_str(csv: &str) -> Result<Self, String> {
+        let mut points = Vec::new();
+        for (idx, raw) in csv.lines().enumerate() {
+            let line = raw.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+            let parsed = match fields.as_slice() {
+                [psnr, ssim] => psnr.parse::<f64>().ok().zip(ssim.parse::<f64>().ok()),
+                _ => None,
+            };
+            match parsed {
+                Some((psnr, ssim)) if (0.0..=1.0).contains(&ssim) && psnr.is_finite() => {
+                    points.push(MappingPoint { psnr, ssim });
+                }
+                None if points.is_empty()
+                    && fields.len() == 2
+                    && fields.iter().all(|f| f.parse::<f64>().is_err()) =>
+                {
+                    continue
+                }
+                _ => {
+                    return Err(format!(
+                        "line {}: expected `psnr,ssim` with SSIM in 0..=1, got `{}`",
+                        idx + 1,
+                        line
+                    ))
+                }
+            }
+        }
+        Ok(Self::from_points(points))
+    }
+
+    /// Read a calibration CSV file (see [`Self::from_csv_str`]).
+    pub fn load_csv(path: &Path) -> std::io::Result<Self> {
+        let csv = std::fs::read_to_string(path)?;
+        Self::from_csv_str(&csv).map_err(|e| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("{}: {}", path.display(), e),
+            )
+        })
+    }
+
+    /// Monotone fit: SSIM never decreases as PSNR rises. Measurement noise that breaks this
+    /// is pooled (pool-adjacent-violators), so the piecewise-linear interpolation used by
+    /// [`Self::predict_ssim`] is monotone too. SSIM is clamped to `0..=1`.
+    pub fn fit(&self) -> Self {
+        // (psnr sum, ssim sum, count) per pooled block
+        let mut blocks: Vec<(f64, f64, usize)> = Vec::with_capacity(self.points.len());
+        for p in &self.points {
+            blocks.push((p.psnr, p.ssim.clamp(0.0, 1.0), 1));
+            while blocks.len() >= 2 {
+                let n = blocks.len();
+                let (prev, last) = (blocks[n - 2], blocks[n - 1]);
+                if prev.1 / prev.2 as f64 <= last.1 / last.2 as f64 {
+                    break;
+                }
+                blocks.pop();
+                blocks[n - 2] = (prev.0 + last.0, prev.1 + last.1, prev.2 + last.2);
+            }
+        }
+        Self {
+            points: blocks
+                .into_iter()
+                .map(|(psnr, ssim, count)| MappingPoint {
+                    psnr: psnr / count as f64,
+                    ssim: ssim / count as f64,
+                })
+                .collect(),
+        }
+    }
+
     pub fn insert(&mut self, psnr: f64, ssim: f64) {
         if let Some(existing) = self
             .points
@@ -213,6 +325,58 @@ mod tests {
         assert!(predicted.is_finite());
         assert!((predicted - 0.92).abs() < 0.001);
     }
+
+    #[test]
+    fn test_from_csv_str_skips_header_and_comments() {
+        let csv = "psnr,ssim\n# measured on x265 slow\n40.0, 0.95\n\n30.0,0.90\n50.0,0.99\n";
+        let mapping = PsnrSsimMapping::from_csv_str(csv).unwrap();
+        assert_eq!(mapping.len(), 3);
+        assert_eq!(mapping.get_points()[0].psnr, 30.0);
+        assert!((mapping.predict_ssim(35.0).unwrap() - 0.925).abs() < 0.001);
+
+        // The header is recognised by content, not position.
+        let commented = "# exported from ffmpeg runs\nPSNR (dB), SSIM\n40.0,0.95\n30.0,0.90\n";
+        assert_eq!(PsnrSsimMapping::from_csv_str(commented).unwrap().len(), 2);
+
+        assert!(PsnrSsimMapping::from_csv_str("30.0,0.9\nabc,0.9\n").is_err());
+        assert!(PsnrSsimMapping::from_csv_str("30.0,0.9\npsnr,ssim\n").is_err());
+        assert!(PsnrSsimMapping::from_csv_str("30.0,1.5\n").is_err());
+    }
+
+    #[test]
+    fn test_fit_is_monotone() {
+        let mapping = PsnrSsimMapping::from_points(vec![
+            MappingPoint {
+                psnr: 30.0,
+                ssim: 0.90,
+            },
+            MappingPoint {
+                psnr: 35.0,
+                ssim: 0.96,
+            },
+            MappingPoint {
+                psnr: 40.0,
+                ssim: 0.94,
+            },
+            MappingPoint {
+                psnr: 50.0,
+                ssim: 0.99,
+            },
+        ]);
+        let fitted = mapping.fit();
+        let points = fitted.get_points();
+        assert_eq!(points.len(), 3);
+        assert!(points.windows(2).all(|w| w[0].ssim <= w[1].ssim));
+        assert!((points[1].psnr - 37.5).abs() < 1e-9);
+        assert!((points[1].ssim - 0.95).abs() < 1e-9);
+
+        let mut last = 0.0;
+        for psnr in 30..=50 {
+            let ssim = fitted.predict_ssim(psnr as f64).unwrap();
+            assert!(ssim >= last);
+            last = ssim;
+        }
+    }
 }
 
 #[cfg(test)]
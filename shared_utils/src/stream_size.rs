@@ -66,6 +66,22 @@ impl StreamSizeInfo {
     pub fn is_overhead_excessive(&self) -> bool {
         self.container_overhead_percent() > 10.0
     }
+
+    /// The share of the streams that falls in `share` of the duration (bitrates unchanged).
+    pub fn scaled(self, share: f64) -> Self {
+        if share >= 1.0 {
+            return self;
+        }
+        let scale = |bytes: u64| (bytes as f64 * share) as u64;
+        Self {
+            video_stream_size: scale(self.video_stream_size),
+            audio_stream_size: scale(self.audio_stream_size),
+            total_file_size: scale(self.total_file_size),
+            container_overhead: scale(self.container_overhead),
+            duration_secs: self.duration_secs * share,
+            ..self
+        }
+    }
 }
 
 #[derive(Debug, Deserialize, Default)]
@@ -123,11 +139,10 @@ pub fn extract_stream_sizes(path: &Path) -> StreamSizeInfo {
         }
     };
 
-    if let Some(info) = try_ffprobe_extraction(path, total_file_size) {
-        return info;
-    }
-
-    estimate_stream_sizes(path, total_file_size)
+    let info = try_ffprobe_extraction(path, total_file_size)
+        .unwrap_or_else(|| estimate_stream_sizes(path, total_file_size));
+    // `--start` / `--end` source: only the trimmed range is encoded, so compare against it.
+    info.scaled(crate::video::trimmed_share(path))
 }
 
 fn try_ffprobe_extraction(path: &Path, total_file_size: u64) -> Option<StreamSizeInfo> {
@@ -5,14 +5,34 @@
 //! - Prevents system overload during multi-instance scenarios
 //! - Reduces parallelism when system memory is low (avoids OOM kills)
 //! - Allows environment-based configuration (MFB_LOW_MEMORY, MFB_MULTI_INSTANCE)
+//! - Honors explicit `--jobs` / `--child-threads` overrides from the CLI
+//! - Holds concurrent encodes within a memory budget (`--max-memory`, default a share of RAM)
 
-use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::OnceLock;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Condvar, Mutex, OnceLock};
 
 use crate::system_memory::{self, MemoryPressure};
 
 static MULTI_INSTANCE_MODE: AtomicBool = AtomicBool::new(false);
 
+/// User-pinned parallel tasks (`--jobs`); 0 = automatic.
+static JOBS_OVERRIDE: AtomicUsize = AtomicUsize::new(0);
+/// User-pinned threads per encoder process (`--child-threads`); 0 = automatic.
+static CHILD_THREADS_OVERRIDE: AtomicUsize = AtomicUsize::new(0);
+/// `--max-memory` budget in MB for concurrent encodes; 0 = [`DEFAULT_MEMORY_BUDGET_FRACTION`] of RAM.
+static MAX_MEMORY_MB: AtomicU64 = AtomicU64::new(0);
+/// Estimated MB held by the encodes currently running, see [`reserve_task_memory`].
+static MEMORY_IN_USE_MB: Mutex<u64> = Mutex::new(0);
+static MEMORY_RELEASED: Condvar = Condvar::new();
+
+/// Share of physical RAM concurrent encodes may claim when `--max-memory` is not given.
+pub const DEFAULT_MEMORY_BUDGET_FRACTION: f64 = 0.6;
+/// Frames one encode keeps in memory: encoder lookahead and references plus the
+/// decoder/filter queue in front of it.
+const ENCODE_BUFFERED_FRAMES: u64 = 64;
+/// Per-encode overhead independent of resolution (ffmpeg, encoder state, metric passes).
+const ENCODE_BASE_MEMORY_MB: u64 = 256;
+
 #[derive(Debug, Clone)]
 pub struct ThreadConfig {
     pub core_percentage: usize,
@@ -129,6 +149,51 @@ fn apply_multi_instance_cap(
     }
 }
 
+/// Apply explicit user overrides on top of the computed allocation.
+/// Overrides bypass the multi-instance and memory caps; when only `jobs` is pinned,
+/// child threads are re-split across the available cores.
+fn apply_user_overrides(
+    available_cores: usize,
+    parallel_tasks: usize,
+    child_threads: usize,
+    jobs: usize,
+    child: usize,
+) -> (usize, usize) {
+    let parallel_tasks = if jobs > 0 { jobs } else { parallel_tasks };
+    let child_threads = if child > 0 {
+        child
+    } else if jobs > 0 {
+        (available_cores / jobs).max(1)
+    } else {
+        child_threads
+    };
+    (parallel_tasks, child_threads)
+}
+
+/// Pin parallel tasks and/or child threads for this process (0 = automatic).
+/// Affects every subsequent `get_balanced_thread_config` call.
+pub fn set_thread_overrides(jobs: usize, child_threads: usize) {
+    JOBS_OVERRIDE.store(jobs, Ordering::Relaxed);
+    CHILD_THREADS_OVERRIDE.store(child_threads, Ordering::Relaxed);
+}
+
+/// Install `--jobs` / `--child-threads` from a `run` command and warn when the
+/// resulting allocation asks for more threads than there are cores.
+pub fn apply_cli_thread_overrides(jobs: usize, child_threads: usize, workload: WorkloadType) {
+    set_thread_overrides(jobs, child_threads);
+    if !has_thread_overrides() {
+        return;
+    }
+    if let Some(hint) = oversubscription_hint(&get_balanced_thread_config(workload)) {
+        crate::log_eprintln!("⚠️  Thread overrides: {}", hint);
+    }
+}
+
+/// True when `--jobs` or `--child-threads` was given.
+pub fn has_thread_overrides() -> bool {
+    JOBS_OVERRIDE.load(Ordering::Relaxed) > 0 || CHILD_THREADS_OVERRIDE.load(Ordering::Relaxed) > 0
+}
+
 pub fn get_balanced_thread_config(workload: WorkloadType) -> ThreadAllocation {
     let total_cores = std::thread::available_parallelism()
         .map(|n| n.get())
@@ -155,6 +220,13 @@ pub fn get_balanced_thread_config(workload: WorkloadType) -> ThreadAllocation {
     let (parallel_tasks, child_threads) =
         apply_multi_instance_cap(workload, parallel_tasks, child_threads);
     let (parallel_tasks, child_threads) = apply_memory_cap(parallel_tasks, child_threads);
+    let (parallel_tasks, child_threads) = apply_user_overrides(
+        available_cores,
+        parallel_tasks,
+        child_threads,
+        JOBS_OVERRIDE.load(Ordering::Relaxed),
+        CHILD_THREADS_OVERRIDE.load(Ordering::Relaxed),
+    );
 
     ThreadAllocation {
         parallel_tasks: parallel_tasks.max(1),
@@ -162,6 +234,69 @@ pub fn get_balanced_thread_config(workload: WorkloadType) -> ThreadAllocation {
     }
 }
 
+/// `--max-memory`: cap the estimated memory of concurrent encodes (`None` = share of RAM).
+pub fn set_max_memory(bytes: Option<u64>) {
+    let mb = bytes.map_or(0, |b| (b / (1024 * 1024)).max(1));
+    MAX_MEMORY_MB.store(mb, Ordering::Relaxed);
+}
+
+/// Memory budget in MB for concurrent encodes; `None` when RAM cannot be detected and no
+/// `--max-memory` was given, in which case nothing is throttled.
+pub fn memory_budget_mb() -> Option<u64> {
+    match MAX_MEMORY_MB.load(Ordering::Relaxed) {
+        0 => system_memory::get_total_memory_mb()
+            .filter(|&total| total > 0)
+            .map(|total| (total as f64 * DEFAULT_MEMORY_BUDGET_FRACTION) as u64),
+        mb => Some(mb),
+    }
+}
+
+/// Estimated peak memory in MB of one encode of a `width`×`height` source at `bit_depth`
+/// (4:2:0 frames, two bytes per sample above 8 bits).
+pub fn estimate_task_memory_mb(width: u32, height: u32, bit_depth: u8) -> u64 {
+    let bytes_per_sample = if bit_depth > 8 { 2 } else { 1 };
+    let frame_bytes = width as u64 * height as u64 * 3 / 2 * bytes_per_sample;
+    ENCODE_BASE_MEMORY_MB + frame_bytes * ENCODE_BUFFERED_FRAMES / (1024 * 1024)
+}
+
+/// A task may start when nothing else runs (an oversized file still gets converted, alone)
+/// or when it fits next to the running ones.
+fn fits_memory_budget(in_use_mb: u64, estimate_mb: u64, budget_mb: u64) -> bool {
+    in_use_mb == 0 || in_use_mb.saturating_add(estimate_mb) <= budget_mb
+}
+
+/// Returns the memory taken by [`reserve_task_memory`] on drop and wakes waiting tasks.
+pub struct MemoryReservation {
+    mb: u64,
+}
+
+impl Drop for MemoryReservation {
+    fn drop(&mut self) {
+        if self.mb == 0 {
+            return;
+        }
+        let mut in_use = MEMORY_IN_USE_MB.lock().unwrap_or_else(|e| e.into_inner());
+        *in_use = in_use.saturating_sub(self.mb);
+        MEMORY_RELEASED.notify_all();
+    }
+}
+
+/// Block until an encode estimated at `estimate_mb` fits in [`memory_budget_mb`] next to the
+/// encodes already running, then hold that share until the reservation is dropped.
+pub fn reserve_task_memory(estimate_mb: u64) -> MemoryReservation {
+    let Some(budget_mb) = memory_budget_mb() else {
+        return MemoryReservation { mb: 0 };
+    };
+    let mut in_use = MEMORY_IN_USE_MB.lock().unwrap_or_else(|e| e.into_inner());
+    while !fits_memory_budget(*in_use, estimate_mb, budget_mb) {
+        in_use = MEMORY_RELEASED
+            .wait(in_use)
+            .unwrap_or_else(|e| e.into_inner());
+    }
+    *in_use += estimate_mb;
+    MemoryReservation { mb: estimate_mb }
+}
+
 pub fn get_optimal_threads() -> usize {
     get_balanced_thread_config(WorkloadType::Image).parallel_tasks
 }
@@ -178,6 +313,25 @@ pub fn memory_cap_hint() -> Option<&'static str> {
     }
 }
 
+/// Warning for logging when the allocation asks for more threads than the machine has
+/// (e.g. "--jobs 8 × --child-threads 4 = 32 threads on 10 cores").
+pub fn oversubscription_hint(alloc: &ThreadAllocation) -> Option<String> {
+    let total_cores = std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(4);
+    oversubscription_hint_for(alloc, total_cores)
+}
+
+fn oversubscription_hint_for(alloc: &ThreadAllocation, total_cores: usize) -> Option<String> {
+    let requested = alloc.parallel_tasks.saturating_mul(alloc.child_threads);
+    (requested > total_cores).then(|| {
+        format!(
+            "{} jobs × {} child threads = {} threads on {} cores: expect contention",
+            alloc.parallel_tasks, alloc.child_threads, requested, total_cores
+        )
+    })
+}
+
 pub fn get_ffmpeg_threads() -> usize {
     calculate_optimal_threads(&ThreadConfig::video_processing())
 }
@@ -232,6 +386,57 @@ pub fn get_rsync_version() -> Option<String> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_user_overrides() {
+        assert_eq!(apply_user_overrides(8, 4, 2, 0, 0), (4, 2));
+        assert_eq!(apply_user_overrides(8, 4, 2, 2, 0), (2, 4));
+        assert_eq!(apply_user_overrides(8, 4, 2, 0, 3), (4, 3));
+        assert_eq!(apply_user_overrides(8, 4, 2, 16, 0), (16, 1));
+        assert_eq!(apply_user_overrides(8, 1, 1, 6, 6), (6, 6));
+    }
+
+    #[test]
+    fn test_memory_budget_admits_fewer_large_encodes() {
+        let uhd_10bit = estimate_task_memory_mb(3840, 2160, 10);
+        let fhd_8bit = estimate_task_memory_mb(1920, 1080, 8);
+        assert!(uhd_10bit > 3 * fhd_8bit);
+        assert!(fhd_8bit > ENCODE_BASE_MEMORY_MB);
+
+        // Admit tasks while they fit an 8 GB budget.
+        let admitted = |estimate: u64| {
+            let mut in_use = 0;
+            let mut tasks = 0;
+            while tasks < 8 && fits_memory_budget(in_use, estimate, 8192) {
+                in_use += estimate;
+                tasks += 1;
+            }
+            tasks
+        };
+        assert!(admitted(fhd_8bit) > admitted(uhd_10bit));
+        assert!(admitted(uhd_10bit) < 8);
+        // A source larger than the whole budget still runs, alone.
+        assert_eq!(admitted(100_000), 1);
+
+        assert!(fits_memory_budget(0, 100_000, 8192));
+        assert!(fits_memory_budget(4096, 4096, 8192));
+        assert!(!fits_memory_budget(4097, 4096, 8192));
+    }
+
+    #[test]
+    fn test_oversubscription_hint() {
+        let fits = ThreadAllocation {
+            parallel_tasks: 4,
+            child_threads: 2,
+        };
+        assert!(oversubscription_hint_for(&fits, 8).is_none());
+        let over = ThreadAllocation {
+            parallel_tasks: 8,
+            child_threads: 4,
+        };
+        let hint = oversubscription_hint_for(&over, 10).unwrap();
+        assert!(hint.contains("32 threads on 10 cores"));
+    }
+
     #[test]
     fn test_default_thread_calculation() {
         let threads = get_optimal_threads();
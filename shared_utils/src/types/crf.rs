@@ -17,6 +17,7 @@ pub use crate::float_compare::CRF_EPSILON;
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct HevcEncoder;
 
+/// SVT-AV1 and libaom share the 0–63 `-crf` scale, so these bounds hold for either `--av1-encoder`.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Av1Encoder;
 
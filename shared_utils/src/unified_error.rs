@@ -13,6 +13,7 @@
 
 use std::fmt;
 use std::path::PathBuf;
+use std::time::Duration;
 
 // Re-export types from modules we're keeping
 pub use crate::error_handler::{
@@ -97,6 +98,13 @@ pub enum UnifiedError {
     Io(std::io::Error),
     NotImplemented(String),
     SkipFile(String),
+    /// Aborted through `ConversionConfig::cancel_token`.
+    Cancelled,
+    /// Killed after exceeding `ConversionConfig::per_file_timeout`.
+    TimedOut {
+        path: PathBuf,
+        timeout: Duration,
+    },
     Other(anyhow::Error),
 }
 
@@ -122,9 +130,10 @@ impl UnifiedError {
             | UnifiedError::InvalidSsim(_)
             | UnifiedError::CompressionFailed { .. }
             | UnifiedError::QualityValidationFailed { .. }
-            | UnifiedError::IterationLimitExceeded(_) => ErrorCategory::Recoverable,
+            | UnifiedError::IterationLimitExceeded(_)
+            | UnifiedError::TimedOut { .. } => ErrorCategory::Recoverable,
 
-            UnifiedError::OutputExists { .. } => ErrorCategory::Optional,
+            UnifiedError::OutputExists { .. } | UnifiedError::Cancelled => ErrorCategory::Optional,
 
             UnifiedError::Other(_) => ErrorCategory::Fatal,
 
@@ -300,6 +309,14 @@ impl UnifiedError {
             UnifiedError::SkipFile(msg) => {
                 format!("⏭️  Skip file: {}", msg)
             }
+            UnifiedError::Cancelled => "⏹️  Conversion cancelled".to_string(),
+            UnifiedError::TimedOut { path, timeout } => {
+                format!(
+                    "⏱️  Timed out after {}s: {}",
+                    timeout.as_secs(),
+                    path.display()
+                )
+            }
             UnifiedError::Other(e) => {
                 format!("❌ Error: {}", e)
             }
@@ -559,6 +576,15 @@ impl fmt::Display for UnifiedError {
             UnifiedError::Io(e) => write!(f, "IO error: {}", e),
             UnifiedError::NotImplemented(msg) => write!(f, "Not implemented: {}", msg),
             UnifiedError::SkipFile(msg) => write!(f, "Skip file: {}", msg),
+            UnifiedError::Cancelled => write!(f, "Conversion cancelled"),
+            UnifiedError::TimedOut { path, timeout } => {
+                write!(
+                    f,
+                    "Timed out after {}s: {}",
+                    timeout.as_secs(),
+                    path.display()
+                )
+            }
             UnifiedError::Other(e) => write!(f, "{}", e),
         }
     }
@@ -585,6 +611,9 @@ impl From<std::io::Error> for UnifiedError {
 
 impl From<anyhow::Error> for UnifiedError {
     fn from(e: anyhow::Error) -> Self {
+        if crate::cancellation::is_cancelled_error(&e) {
+            return UnifiedError::Cancelled;
+        }
         UnifiedError::Other(e)
     }
 }
@@ -726,6 +755,28 @@ mod tests {
         assert!(matches!(err, UnifiedError::Io(_)));
     }
 
+    #[test]
+    fn test_unified_error_from_cancelled() {
+        let err: UnifiedError = anyhow::Error::new(crate::cancellation::Cancelled)
+            .context("explore failed")
+            .into();
+        assert!(matches!(err, UnifiedError::Cancelled));
+        assert_eq!(err.category(), ErrorCategory::Optional);
+
+        let err: UnifiedError = anyhow::anyhow!("ffmpeg exited").into();
+        assert!(matches!(err, UnifiedError::Other(_)));
+    }
+
+    #[test]
+    fn test_unified_error_timed_out_is_recoverable() {
+        let err = UnifiedError::TimedOut {
+            path: PathBuf::from("/in/stuck.mkv"),
+            timeout: Duration::from_secs(600),
+        };
+        assert_eq!(err.category(), ErrorCategory::Recoverable);
+        assert_eq!(err.to_string(), "Timed out after 600s: /in/stuck.mkv");
+    }
+
     #[test]
     fn test_unified_error_convenience_constructors() {
         let err = UnifiedError::tool_not_found("ffmpeg");
@@ -0,0 +1,200 @@
+//! `--verify-after`: once the whole batch has finished, every output it produced is opened
+//! again (decoded, and probed for at least one frame). An output that fails is deleted. While
+//! the switch is on, `--delete-original` is deferred to this pass, so the original of a failed
+//! output is kept instead of deleted.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+static VERIFY_AFTER: AtomicBool = AtomicBool::new(false);
+
+/// Outputs waiting for the pass, keyed by input.
+static PENDING: Mutex<BTreeMap<PathBuf, PendingOutput>> = Mutex::new(BTreeMap::new());
+
+#[derive(Debug, Clone)]
+struct PendingOutput {
+    output: PathBuf,
+    /// `--delete-original` passed its own checks; the delete waits for this pass.
+    delete_original: bool,
+}
+
+/// Process-wide `--verify-after` switch.
+pub fn set_verify_after(enabled: bool) {
+    VERIFY_AFTER.store(enabled, Ordering::Relaxed);
+}
+
+pub fn verify_after_enabled() -> bool {
+    VERIFY_AFTER.load(Ordering::Relaxed)
+}
+
+/// After a successful conversion: queue `output` for the pass. A no-op unless `--verify-after`.
+pub fn record_verify_output(input: &Path, output: &Path) {
+    if !verify_after_enabled() {
+        return;
+    }
+    let mut pending = PENDING.lock().unwrap_or_else(|e| e.into_inner());
+    pending
+        .entry(input.to_path_buf())
+        .or_insert_with(|| PendingOutput {
+            output: output.to_path_buf(),
+            delete_original: false,
+        })
+        .output = output.to_path_buf();
+}
+
+/// Called by the safe-delete helpers instead of removing `input`: the original is deleted by
+/// the pass once `output` has verified.
+pub(crate) fn defer_delete_original(input: &Path, output: &Path) {
+    let mut pending = PENDING.lock().unwrap_or_else(|e| e.into_inner());
+    pending.insert(
+        input.to_path_buf(),
+        PendingOutput {
+            output: output.to_path_buf(),
+            delete_original: true,
+        },
+    );
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VerifyAfterReport {
+    pub verified: usize,
+    /// Inputs whose output failed and was deleted, with the reason.
+    pub reverted: Vec<(PathBuf, String)>,
+}
+
+/// Open `output` again: it must decode ([`crate::verify_output_decodable`]) and, when ffprobe
+/// can count them, have at least one video frame.
+pub fn verify_output_readable(output: &Path) -> Result<(), String> {
+    crate::verify_output_integrity(output, 1)?;
+    crate::verify_output_decodable(output)?;
+    match video_packet_count(output) {
+        Some(0) => Err("Output has zero frames".to_string()),
+        _ => Ok(()),
+    }
+}
+
+/// Demuxed packets of the first video stream; `None` without one or when ffprobe cannot say.
+fn video_packet_count(output: &Path) -> Option<u64> {
+    let probe = Command::new("ffprobe")
+        .args([
+            "-v",
+            "error",
+            "-select_streams",
+            "v:0",
+            "-count_packets",
+            "-show_entries",
+            "stream=nb_read_packets",
+            "-of",
+            "csv=p=0",
+        ])
+        .arg(crate::safe_path_arg(output).as_ref())
+        .output()
+        .ok()?;
+    if !probe.status.success() {
+        return None;
+    }
+    String::from_utf8_lossy(&probe.stdout).trim().parse().ok()
+}
+
+/// Run the pass over everything queued since the last call; `None` when `--verify-after` is
+/// off. Failed outputs are deleted, deferred originals are deleted only for verified outputs.
+pub fn finish_verify_after() -> Option<VerifyAfterReport> {
+    if !verify_after_enabled() {
+        return None;
+    }
+    let pending = std::mem::take(&mut *PENDING.lock().unwrap_or_else(|e| e.into_inner()));
+    Some(verify_pending(pending))
+}
+
+fn verify_pending(pending: BTreeMap<PathBuf, PendingOutput>) -> VerifyAfterReport {
+    let mut report = VerifyAfterReport::default();
+    for (input, entry) in pending {
+        match verify_output_readable(&entry.output) {
+            Ok(()) => {
+                report.verified += 1;
+                if entry.delete_original {
+                    if let Err(e) = fs::remove_file(&input) {
+                        eprintln!(
+                            "⚠️ [verify-after] Could not delete original {}: {}",
+                            input.display(),
+                            e
+                        );
+                    }
+                }
+            }
+            Err(reason) => {
+                eprintln!(
+                    "   ↩️  Verify-after FAILED: {}: {}",
+                    entry.output.display(),
+                    reason
+                );
+                // An in-place output is the only copy left; never delete it.
+                if entry.output.exists() && !same_file(&input, &entry.output) {
+                    if let Err(e) = fs::remove_file(&entry.output) {
+                        eprintln!(
+                            "⚠️ [verify-after] Could not delete {}: {}",
+                            entry.output.display(),
+                            e
+                        );
+                    }
+                }
+                if entry.delete_original {
+                    eprintln!("   🛡️  Original file PROTECTED: {}", input.display());
+                }
+                report.reverted.push((input, reason));
+            }
+        }
+    }
+    report
+}
+
+/// [`finish_verify_after`] with a one-line result, for runs without a summary report.
+pub fn finish_verify_after_with_log() -> Option<VerifyAfterReport> {
+    let report = finish_verify_after()?;
+    crate::report_println!(
+        "\n🔎 Verify-after: {} verified, {} reverted",
+        report.verified,
+        report.reverted.len()
+    );
+    Some(report)
+}
+
+fn same_file(a: &Path, b: &Path) -> bool {
+    match (a.canonicalize(), b.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_failed_output_is_deleted_and_original_kept() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let input = dir.path().join("clip.mov");
+        let output = dir.path().join("clip.mp4");
+        fs::write(&input, b"source").unwrap();
+        fs::write(&output, b"").unwrap();
+
+        let pending = BTreeMap::from([(
+            input.clone(),
+            PendingOutput {
+                output: output.clone(),
+                delete_original: true,
+            },
+        )]);
+        let report = verify_pending(pending);
+
+        assert_eq!(report.verified, 0);
+        assert_eq!(report.reverted.len(), 1);
+        assert_eq!(report.reverted[0].0, input);
+        assert!(!output.exists());
+        assert!(input.exists());
+    }
+}
@@ -3,6 +3,9 @@
 //! Provides common video processing functionality:
 //! - Dimension validation and correction for chroma subsampling
 //! - FFmpeg filter generation
+//! - Optional downscale to a maximum dimension (`--max-dimension`)
+//! - Segment trimming (`--start` / `--end`)
+//! - Display rotation (bake into pixels or carry as stream metadata)
 //! - Video format detection
 
 pub fn ensure_even_dimensions(width: u32, height: u32) -> (u32, u32, bool) {
@@ -86,6 +89,368 @@ pub fn get_ffmpeg_dimension_args(width: u32, height: u32, has_alpha: bool) -> Ve
     vec!["-vf".to_string(), filter_chain]
 }
 
+/// Target size when the longer side exceeds `max_dimension`: aspect ratio preserved and both
+/// sides rounded down to even. `None` when the source already fits (no scaling needed).
+pub fn downscale_dimensions(width: u32, height: u32, max_dimension: u32) -> Option<(u32, u32)> {
+    if max_dimension == 0 || (width <= max_dimension && height <= max_dimension) {
+        return None;
+    }
+    let factor = max_dimension as f64 / width.max(height) as f64;
+    let scaled_width = ((width as f64 * factor).round() as u32).max(2);
+    let scaled_height = ((height as f64 * factor).round() as u32).max(2);
+    let (scaled_width, scaled_height, _) = ensure_even_dimensions(scaled_width, scaled_height);
+    Some((scaled_width, scaled_height))
+}
+
+/// Lanczos scale step used for `--max-dimension` downscaling.
+pub fn downscale_filter(width: u32, height: u32) -> String {
+    format!("scale={}:{}:flags=lanczos", width, height)
+}
+
+/// Like [`build_video_filter_chain`], but downscales with lanczos when either side exceeds
+/// `max_dimension`. The scaled size is already even, so no pad/crop step is added.
+pub fn build_video_filter_chain_with_max_dimension(
+    width: u32,
+    height: u32,
+    has_alpha: bool,
+    max_dimension: Option<u32>,
+) -> String {
+    let Some((scaled_width, scaled_height)) =
+        max_dimension.and_then(|max| downscale_dimensions(width, height, max))
+    else {
+        return build_video_filter_chain(width, height, has_alpha);
+    };
+
+    let mut filters = Vec::new();
+    if has_alpha {
+        filters.push("format=rgba,premultiply=inplace=1,format=rgb24".to_string());
+    }
+    filters.push(downscale_filter(scaled_width, scaled_height));
+    filters.push("format=yuv420p".to_string());
+    filters.join(",")
+}
+
+pub fn get_ffmpeg_dimension_args_with_max_dimension(
+    width: u32,
+    height: u32,
+    has_alpha: bool,
+    max_dimension: Option<u32>,
+) -> Vec<String> {
+    let filter_chain =
+        build_video_filter_chain_with_max_dimension(width, height, has_alpha, max_dimension);
+    vec!["-vf".to_string(), filter_chain]
+}
+
+/// Find the `--max-dimension` scale step in `-vf` args, if the encode is being downscaled.
+pub fn downscale_filter_from_vf_args(vf_args: &[String]) -> Option<&str> {
+    vf_args
+        .iter()
+        .flat_map(|arg| arg.split(','))
+        .find(|step| step.starts_with("scale=") && step.ends_with(":flags=lanczos"))
+}
+
+/// Reference leg (`[ref]`) for SSIM/PSNR/VMAF filter graphs. When the encode is downscaled the
+/// reference is scaled identically, so the quality gate judges the downscaled picture rather
+/// than failing (or penalising) against the full-resolution source. A `--denoise` step is
+/// replayed the same way: the gate compares against the cleaned-up source, not the noisy one.
+pub fn metric_reference_filter(vf_args: &[String]) -> String {
+    format!("[0:v]{}[ref]", metric_reference_scale(vf_args))
+}
+
+/// Denoise + scale steps of [`metric_reference_filter`], for graphs that insert other filters
+/// first.
+pub fn metric_reference_scale(vf_args: &[String]) -> String {
+    let scale = downscale_filter_from_vf_args(vf_args)
+        .unwrap_or("scale='iw-mod(iw,2)':'ih-mod(ih,2)':flags=bicubic");
+    match denoise_filter_from_vf_args(vf_args) {
+        Some(denoise) => format!("{},{}", denoise, scale),
+        None => scale.to_string(),
+    }
+}
+
+/// `--denoise` strength. Light/medium use `hqdn3d` (fast, temporal); strong uses `nlmeans`
+/// (much slower, removes heavy grain but also fine texture).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DenoiseLevel {
+    Light,
+    Medium,
+    Strong,
+}
+
+impl DenoiseLevel {
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "light" => Ok(Self::Light),
+            "medium" => Ok(Self::Medium),
+            "strong" => Ok(Self::Strong),
+            other => Err(format!(
+                "Invalid denoise level '{}' (expected light, medium or strong)",
+                other
+            )),
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Light => "light",
+            Self::Medium => "medium",
+            Self::Strong => "strong",
+        }
+    }
+
+    /// ffmpeg filter step for this level.
+    pub fn filter(self) -> &'static str {
+        match self {
+            Self::Light => "hqdn3d=2:1.5:3:2.25",
+            Self::Medium => "hqdn3d=4:3:6:4.5",
+            Self::Strong => "nlmeans=s=3.5:p=7:r=15",
+        }
+    }
+}
+
+/// clap value parser for `--denoise`.
+pub fn parse_denoise_level(value: &str) -> Result<DenoiseLevel, String> {
+    DenoiseLevel::parse(value)
+}
+
+/// Prepend the `--denoise` step to the `-vf` chain so it runs on source-resolution frames,
+/// before any downscale/pad/format step.
+pub fn with_denoise_filter(vf_args: Vec<String>, denoise: Option<DenoiseLevel>) -> Vec<String> {
+    let Some(level) = denoise else {
+        return vf_args;
+    };
+    let mut args = vf_args;
+    match args.iter().position(|arg| arg == "-vf") {
+        Some(i) if i + 1 < args.len() => {
+            args[i + 1] = format!("{},{}", level.filter(), args[i + 1]);
+        }
+        _ => args.extend(["-vf".to_string(), level.filter().to_string()]),
+    }
+    args
+}
+
+/// Find the `--denoise` step in `-vf` args, if the encode is being denoised.
+pub fn denoise_filter_from_vf_args(vf_args: &[String]) -> Option<&str> {
+    vf_args
+        .iter()
+        .flat_map(|arg| arg.split(','))
+        .find(|step| step.starts_with("hqdn3d=") || step.starts_with("nlmeans="))
+}
+
+/// Filter that bakes a clockwise display rotation into the pixels.
+pub fn transpose_filter(rotation: i32) -> Option<&'static str> {
+    match rotation.rem_euclid(360) {
+        90 => Some("transpose=clock"),
+        180 => Some("hflip,vflip"),
+        270 => Some("transpose=cclock"),
+        _ => None,
+    }
+}
+
+/// Bake `rotation` into an encode whose input is opened with `-noautorotate`: the transpose runs
+/// first, so the rest of the `-vf` chain must be built for display (rotated) dimensions.
+/// Used for containers without a reliable display matrix (MKV).
+pub fn with_rotation_filter(vf_args: Vec<String>, rotation: Option<i32>) -> Vec<String> {
+    let Some(transpose) = rotation.and_then(transpose_filter) else {
+        return vf_args;
+    };
+    match vf_args.iter().position(|arg| arg == "-vf") {
+        Some(i) if i + 1 < vf_args.len() => {
+            let mut args = vf_args;
+            args[i + 1] = format!("{},{}", transpose, args[i + 1]);
+            args
+        }
+        _ => {
+            let mut args = vf_args;
+            args.extend(["-vf".to_string(), transpose.to_string()]);
+            args
+        }
+    }
+}
+
+/// Output args that tag a stream-copied video with its display rotation. Re-encodes need none:
+/// ffmpeg's autorotate already turns the frames upright.
+pub fn rotation_metadata_args(rotation: Option<i32>) -> Vec<String> {
+    match rotation {
+        Some(degrees) => vec!["-metadata:s:v:0".to_string(), format!("rotate={}", degrees)],
+        None => Vec::new(),
+    }
+}
+
+/// Frame-timing args for the encode. VFR sources keep their timestamps (`-vsync vfr`) unless
+/// `force_cfr` asks for constant frame rate (`-vsync cfr`, duplicating/dropping frames).
+pub fn frame_rate_mode_args(is_vfr: bool, force_cfr: bool) -> Vec<String> {
+    match (is_vfr, force_cfr) {
+        (false, _) => Vec::new(),
+        (true, false) => vec!["-vsync".to_string(), "vfr".to_string()],
+        (true, true) => vec!["-vsync".to_string(), "cfr".to_string()],
+    }
+}
+
+/// Parse a `--start` / `--end` timestamp: seconds (`90`, `90.5`), `MM:SS` or `HH:MM:SS[.fff]`.
+pub fn parse_timestamp(value: &str) -> Result<f64, String> {
+    let value = value.trim();
+    let parts: Vec<&str> = value.split(':').collect();
+    if parts.is_empty() || parts.len() > 3 || parts.iter().any(|p| p.is_empty()) {
+        return Err(format!(
+            "Invalid timestamp '{}' (expected SS, MM:SS or HH:MM:SS)",
+            value
+        ));
+    }
+    let mut secs = 0.0;
+    for (i, part) in parts.iter().enumerate() {
+        let n: f64 = part.parse().map_err(|_| {
+            format!(
+                "Invalid timestamp '{}' (expected SS, MM:SS or HH:MM:SS)",
+                value
+            )
+        })?;
+        let is_last = i == parts.len() - 1;
+        if !n.is_finite() || n < 0.0 || (!is_last && n.fract() != 0.0) || (i > 0 && n >= 60.0) {
+            return Err(format!("Invalid timestamp '{}'", value));
+        }
+        secs = secs * 60.0 + n;
+    }
+    Ok(secs)
+}
+
+/// Reject nonsensical `--start` / `--end` ranges: end at or before start, or start at/after the
+/// end of the source. An end past the source duration is allowed (the encode stops at EOF).
+pub fn validate_trim_range(
+    start: Option<f64>,
+    end: Option<f64>,
+    duration_secs: f64,
+) -> Result<(), String> {
+    let start_secs = start.unwrap_or(0.0);
+    if start_secs < 0.0 || end.is_some_and(|e| e <= 0.0) {
+        return Err("Trim timestamps must be positive".to_string());
+    }
+    if let Some(end_secs) = end {
+        if end_secs <= start_secs {
+            return Err(format!(
+                "Invalid trim range: end ({:.3}s) must be after start ({:.3}s)",
+                end_secs, start_secs
+            ));
+        }
+    }
+    if duration_secs > 0.0 && start_secs >= duration_secs {
+        return Err(format!(
+            "Invalid trim range: start ({:.3}s) is beyond the source duration ({:.3}s)",
+            start_secs, duration_secs
+        ));
+    }
+    Ok(())
+}
+
+/// Length of the `--start` / `--end` range, with `end` clamped to the source duration.
+pub fn trimmed_duration(start: Option<f64>, end: Option<f64>, duration_secs: f64) -> f64 {
+    let end_secs = match end {
+        Some(e) if duration_secs <= 0.0 || e < duration_secs => e,
+        _ => duration_secs,
+    };
+    (end_secs - start.unwrap_or(0.0)).max(0.0)
+}
+
+/// `-ss` / `-to` for the `--start` / `--end` range.
+pub fn trim_args(start: Option<f64>, end: Option<f64>) -> Vec<String> {
+    let mut args = Vec::new();
+    if let Some(s) = start.filter(|s| *s > 0.0) {
+        args.push("-ss".to_string());
+        args.push(format!("{:.3}", s));
+    }
+    if let Some(e) = end {
+        args.push("-to".to_string());
+        args.push(format!("{:.3}", e));
+    }
+    args
+}
+
+#[derive(Debug, Clone, Copy)]
+struct TrimWindow {
+    start: Option<f64>,
+    end: Option<f64>,
+    share: f64,
+}
+
+/// `--start` / `--end` windows keyed by (canonical) source path. Every ffmpeg read of a
+/// registered source adds the window as input options, so encodes, quality references and
+/// probes all see the trimmed range without an intermediate file. Keyed by path rather than
+/// thread so metric worker threads and concurrent `--jobs` files agree.
+static TRIMMED_SOURCES: std::sync::LazyLock<
+    std::sync::Mutex<std::collections::HashMap<std::path::PathBuf, TrimWindow>>,
+> = std::sync::LazyLock::new(Default::default);
+
+fn trim_key(path: &std::path::Path) -> std::path::PathBuf {
+    std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+fn trim_window(path: &std::path::Path) -> Option<TrimWindow> {
+    let sources = TRIMMED_SOURCES.lock().unwrap_or_else(|e| e.into_inner());
+    if sources.is_empty() {
+        return None;
+    }
+    sources.get(&trim_key(path)).copied()
+}
+
+/// Removes the window registered by [`trim_source`] when the conversion ends.
+pub struct SourceTrimGuard(std::path::PathBuf);
+
+impl Drop for SourceTrimGuard {
+    fn drop(&mut self) {
+        TRIMMED_SOURCES
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(&self.0);
+    }
+}
+
+/// Encode and measure only `start..end` of `source` until the guard drops.
+pub fn trim_source(
+    source: &std::path::Path,
+    start: Option<f64>,
+    end: Option<f64>,
+    source_duration: f64,
+) -> SourceTrimGuard {
+    let share = if source_duration > 0.0 {
+        (trimmed_duration(start, end, source_duration) / source_duration).clamp(0.0, 1.0)
+    } else {
+        1.0
+    };
+    let key = trim_key(source);
+    TRIMMED_SOURCES
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(key.clone(), TrimWindow { start, end, share });
+    SourceTrimGuard(key)
+}
+
+/// Input options (`-ss` / `-to`, placed before `-i`) for a source registered with
+/// [`trim_source`]; empty for every other path, including encoded outputs. When
+/// transcoding, ffmpeg decodes from the previous keyframe and discards frames up to
+/// `start`, so the cut stays frame-accurate.
+pub fn source_trim_args(input: &std::path::Path) -> Vec<String> {
+    trim_window(input)
+        .map(|w| trim_args(w.start, w.end))
+        .unwrap_or_default()
+}
+
+/// `(start, end)` of `path`'s trim window in source seconds, if one is registered.
+pub fn source_trim_window(path: &std::path::Path) -> Option<(f64, Option<f64>)> {
+    trim_window(path).map(|w| (w.start.unwrap_or(0.0), w.end))
+}
+
+/// Fraction of `path`'s duration inside its trim window; 1.0 when untrimmed.
+pub fn trimmed_share(path: &std::path::Path) -> f64 {
+    trim_window(path).map(|w| w.share).unwrap_or(1.0)
+}
+
+/// File size attributed to the trim window (size × [`trimmed_share`]), so size changes
+/// compare the output with the matching part of the source.
+pub fn trimmed_source_size(path: &std::path::Path) -> std::io::Result<u64> {
+    let len = std::fs::metadata(path)?.len();
+    Ok((len as f64 * trimmed_share(path)) as u64)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -192,4 +557,172 @@ mod tests {
         assert!(!is_yuv420_compatible(1920, 1081));
         assert!(!is_yuv420_compatible(1921, 1081));
     }
+
+    #[test]
+    fn test_downscale_dimensions_within_bounds() {
+        assert_eq!(downscale_dimensions(1920, 1080, 1920), None);
+        assert_eq!(downscale_dimensions(1280, 720, 1920), None);
+        assert_eq!(downscale_dimensions(3840, 2160, 0), None);
+    }
+
+    #[test]
+    fn test_downscale_dimensions_preserves_aspect() {
+        assert_eq!(downscale_dimensions(3840, 2160, 1920), Some((1920, 1080)));
+        // Portrait: the longer side (height) is limited.
+        assert_eq!(downscale_dimensions(2160, 3840, 1920), Some((1080, 1920)));
+        // Odd results are rounded down to even.
+        assert_eq!(downscale_dimensions(4000, 3000, 1001), Some((1000, 750)));
+    }
+
+    #[test]
+    fn test_build_video_filter_chain_with_max_dimension() {
+        assert_eq!(
+            build_video_filter_chain_with_max_dimension(3840, 2160, false, Some(1920)),
+            "scale=1920:1080:flags=lanczos,format=yuv420p"
+        );
+        assert_eq!(
+            build_video_filter_chain_with_max_dimension(1921, 1081, false, Some(1920)),
+            "scale=1920:1080:flags=lanczos,format=yuv420p"
+        );
+        assert_eq!(
+            build_video_filter_chain_with_max_dimension(1280, 720, false, Some(1920)),
+            build_video_filter_chain(1280, 720, false)
+        );
+        assert_eq!(
+            build_video_filter_chain_with_max_dimension(1921, 1081, false, None),
+            "pad=1922:1082:0:0,format=yuv420p"
+        );
+    }
+
+    #[test]
+    fn test_metric_reference_filter_follows_downscale() {
+        let scaled = get_ffmpeg_dimension_args_with_max_dimension(3840, 2160, false, Some(1920));
+        assert_eq!(
+            downscale_filter_from_vf_args(&scaled),
+            Some("scale=1920:1080:flags=lanczos")
+        );
+        assert_eq!(
+            metric_reference_filter(&scaled),
+            "[0:v]scale=1920:1080:flags=lanczos[ref]"
+        );
+
+        let unscaled = get_ffmpeg_dimension_args(3840, 2160, false);
+        assert_eq!(downscale_filter_from_vf_args(&unscaled), None);
+        assert!(metric_reference_filter(&unscaled).contains("iw-mod(iw,2)"));
+    }
+
+    #[test]
+    fn test_denoise_filter_feeds_metric_reference() {
+        assert_eq!(DenoiseLevel::parse("Medium"), Ok(DenoiseLevel::Medium));
+        assert!(DenoiseLevel::parse("extreme").is_err());
+
+        let plain = get_ffmpeg_dimension_args_with_max_dimension(3840, 2160, false, Some(1920));
+        assert_eq!(with_denoise_filter(plain.clone(), None), plain);
+
+        let denoised = with_denoise_filter(plain, Some(DenoiseLevel::Light));
+        assert_eq!(
+            denoised[1],
+            "hqdn3d=2:1.5:3:2.25,scale=1920:1080:flags=lanczos,format=yuv420p"
+        );
+        assert_eq!(
+            denoise_filter_from_vf_args(&denoised),
+            Some("hqdn3d=2:1.5:3:2.25")
+        );
+        assert_eq!(
+            metric_reference_filter(&denoised),
+            "[0:v]hqdn3d=2:1.5:3:2.25,scale=1920:1080:flags=lanczos[ref]"
+        );
+
+        let strong = with_denoise_filter(Vec::new(), Some(DenoiseLevel::Strong));
+        assert_eq!(strong, vec!["-vf", "nlmeans=s=3.5:p=7:r=15"]);
+    }
+
+    #[test]
+    fn test_frame_rate_mode_args() {
+        assert!(frame_rate_mode_args(false, true).is_empty());
+        assert_eq!(frame_rate_mode_args(true, false), vec!["-vsync", "vfr"]);
+        assert_eq!(frame_rate_mode_args(true, true), vec!["-vsync", "cfr"]);
+    }
+
+    #[test]
+    fn test_parse_timestamp() {
+        assert_eq!(parse_timestamp("90").unwrap(), 90.0);
+        assert_eq!(parse_timestamp("01:30").unwrap(), 90.0);
+        assert_eq!(parse_timestamp("00:01:30").unwrap(), 90.0);
+        assert_eq!(parse_timestamp("1:00:00.5").unwrap(), 3600.5);
+        assert!(parse_timestamp("").is_err());
+        assert!(parse_timestamp("1:70").is_err());
+        assert!(parse_timestamp("1.5:00").is_err());
+        assert!(parse_timestamp("-5").is_err());
+        assert!(parse_timestamp("1:2:3:4").is_err());
+    }
+
+    #[test]
+    fn test_validate_trim_range() {
+        assert!(validate_trim_range(Some(90.0), Some(300.0), 600.0).is_ok());
+        assert!(validate_trim_range(None, Some(900.0), 600.0).is_ok());
+        assert!(validate_trim_range(Some(300.0), Some(300.0), 600.0).is_err());
+        assert!(validate_trim_range(Some(300.0), Some(90.0), 600.0).is_err());
+        assert!(validate_trim_range(Some(600.0), None, 600.0).is_err());
+        // Unknown duration: only the ordering can be checked.
+        assert!(validate_trim_range(Some(600.0), None, 0.0).is_ok());
+    }
+
+    #[test]
+    fn test_trimmed_duration_and_args() {
+        assert_eq!(trimmed_duration(Some(90.0), Some(300.0), 600.0), 210.0);
+        assert_eq!(trimmed_duration(Some(90.0), None, 600.0), 510.0);
+        assert_eq!(trimmed_duration(None, Some(900.0), 600.0), 600.0);
+        assert_eq!(
+            trim_args(Some(90.0), Some(300.0)),
+            vec!["-ss", "90.000", "-to", "300.000"]
+        );
+        assert_eq!(trim_args(Some(0.0), None), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_trim_window_applies_only_to_registered_source() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("source.mov");
+        let output = dir.path().join("output.mp4");
+        std::fs::write(&source, b"src").unwrap();
+        std::fs::write(&output, b"out").unwrap();
+
+        {
+            let _trim = trim_source(&source, Some(90.0), Some(300.0), 600.0);
+            assert_eq!(
+                source_trim_args(&source),
+                vec!["-ss", "90.000", "-to", "300.000"]
+            );
+            assert!(source_trim_args(&output).is_empty());
+            assert_eq!(trimmed_share(&source), 0.35);
+            assert_eq!(trimmed_share(&output), 1.0);
+        }
+        assert!(source_trim_args(&source).is_empty());
+    }
+
+    #[test]
+    fn test_rotation_filters_for_portrait_clip() {
+        assert_eq!(transpose_filter(90), Some("transpose=clock"));
+        assert_eq!(transpose_filter(-90), Some("transpose=cclock"));
+        assert_eq!(transpose_filter(0), None);
+
+        // Stored 1920x1080, displayed 1080x1920: transpose runs before the pad/scale steps.
+        let vf = get_ffmpeg_dimension_args_with_max_dimension(1080, 1920, false, Some(960));
+        let rotated = with_rotation_filter(vf, Some(90));
+        assert_eq!(
+            rotated,
+            vec![
+                "-vf",
+                "transpose=clock,scale=540:960:flags=lanczos,format=yuv420p"
+            ]
+        );
+        assert_eq!(with_rotation_filter(Vec::new(), None), Vec::<String>::new());
+
+        assert_eq!(
+            rotation_metadata_args(Some(90)),
+            vec!["-metadata:s:v:0", "rotate=90"]
+        );
+        assert!(rotation_metadata_args(None).is_empty());
+    }
 }
@@ -205,6 +205,9 @@ pub struct VideoDetectionResult {
     pub color_primaries: Option<String>,
     /// color_transfer (TRC) from ffprobe (e.g. "smpte2084", "arib-std-b67", "bt709")
     pub color_transfer: Option<String>,
+    /// "pc" (full) or "tv" (limited); yuvj* sources count as full range
+    #[serde(default)]
+    pub color_range: Option<String>,
     /// HDR10 mastering display metadata in ffmpeg format
     pub mastering_display: Option<String>,
     /// HDR10 content light level: "MaxCLL,MaxFALL"
@@ -225,6 +228,8 @@ pub struct VideoDetectionResult {
     pub audio_channels: Option<u32>,
     /// Variable frame rate (VFR) detected - common in iPhone slow-motion videos
     pub is_variable_frame_rate: bool,
+    /// Clockwise rotation (90/180/270) from the display matrix; `width`/`height` are as stored
+    pub rotation: Option<i32>,
     /// Precise metadata from encoder tags
     pub precision: VideoPrecisionMetadata,
     /// Raw tags from format section
@@ -236,6 +241,15 @@ pub struct VideoDetectionResult {
 }
 
 impl VideoDetectionResult {
+    /// Width and height as displayed. ffmpeg rotates frames on decode (autorotate), so filter
+    /// graphs on re-encodes see these dimensions rather than the stored ones.
+    pub fn display_dimensions(&self) -> (u32, u32) {
+        match self.rotation {
+            Some(90) | Some(270) => (self.height, self.width),
+            _ => (self.width, self.height),
+        }
+    }
+
     /// Returns true when the content is any form of HDR (PQ, HLG, DV, HDR10, HDR10+)
     pub fn is_hdr(&self) -> bool {
         self.is_dolby_vision
@@ -263,6 +277,120 @@ impl VideoDetectionResult {
     }
 }
 
+/// One row of `analyze --compare`: a property of the two files side by side.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DetectionDiffRow {
+    pub field: &'static str,
+    pub left: String,
+    pub right: String,
+}
+
+impl DetectionDiffRow {
+    pub fn differs(&self) -> bool {
+        self.left != self.right
+    }
+}
+
+/// The properties worth comparing between two detected videos (codec, pixel format, bit depth,
+/// color, bitrate, geometry, audio), one row each in display order.
+pub fn compare_detections(
+    left: &VideoDetectionResult,
+    right: &VideoDetectionResult,
+) -> Vec<DetectionDiffRow> {
+    fn opt(value: &Option<String>) -> String {
+        value.clone().unwrap_or_else(|| "-".to_string())
+    }
+    fn resolution(d: &VideoDetectionResult) -> String {
+        let (w, h) = d.display_dimensions();
+        format!("{}x{}", w, h)
+    }
+    fn kbps(bits: u64) -> String {
+        format!("{} kb/s", bits / 1000)
+    }
+    fn audio(d: &VideoDetectionResult) -> String {
+        match (&d.audio_codec, d.audio_channels) {
+            (Some(codec), Some(ch)) => format!("{} ({} ch)", codec, ch),
+            (Some(codec), None) => codec.clone(),
+            (None, _) if d.has_audio => "yes".to_string(),
+            (None, _) => "-".to_string(),
+        }
+    }
+    let fields: [(&'static str, fn(&VideoDetectionResult) -> String); 17] = [
+        ("container", |d| d.format.clone()),
+        ("codec", |d| d.codec.as_str().to_string()),
+        ("profile", |d| opt(&d.profile)),
+        ("pix_fmt", |d| d.pix_fmt.clone()),
+        ("bit depth", |d| d.bit_depth.to_string()),
+        ("color space", |d| d.color_space.as_str().to_string()),
+        ("color primaries", |d| opt(&d.color_primaries)),
+        ("color transfer", |d| opt(&d.color_transfer)),
+        ("color range", |d| opt(&d.color_range)),
+        ("HDR", |d| d.is_hdr().to_string()),
+        ("resolution", resolution),
+        ("fps", |d| {
+            let vfr = if d.is_variable_frame_rate {
+                " (VFR)"
+            } else {
+                ""
+            };
+            format!("{:.3}{}", d.fps, vfr)
+        }),
+        ("duration", |d| format!("{:.2}s", d.duration_secs)),
+        ("bitrate", |d| kbps(d.bitrate)),
+        ("video bitrate", |d| {
+            d.video_bitrate.map_or("-".to_string(), kbps)
+        }),
+        ("file size", |d| crate::format_bytes(d.file_size)),
+        ("audio", audio),
+    ];
+    fields
+        .into_iter()
+        .map(|(field, value)| DetectionDiffRow {
+            field,
+            left: value(left),
+            right: value(right),
+        })
+        .collect()
+}
+
+/// Print [`compare_detections`] as a two-column table; differing rows are marked with `≠`.
+pub fn print_detection_comparison(left_path: &Path, right_path: &Path, rows: &[DetectionDiffRow]) {
+    let name = |p: &Path| {
+        p.file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_else(|| p.display().to_string())
+    };
+    let width = rows
+        .iter()
+        .map(|r| r.left.chars().count())
+        .chain(std::iter::once(name(left_path).chars().count()))
+        .max()
+        .unwrap_or(0);
+    println!("\n🔀 Compare");
+    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    println!(
+        "  {:<16} {:<width$}   {}",
+        "",
+        name(left_path),
+        name(right_path),
+        width = width
+    );
+    for row in rows {
+        let mark = if row.differs() { "≠" } else { " " };
+        println!(
+            "{} {:<16} {:<width$}   {}",
+            mark,
+            row.field,
+            row.left,
+            row.right,
+            width = width
+        );
+    }
+    let differing = rows.iter().filter(|r| r.differs()).count();
+    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    println!("{} of {} properties differ", differing, rows.len());
+}
+
 pub fn determine_compression_type(
     codec: &DetectedCodec,
     bitrate: u64,
@@ -450,6 +578,7 @@ pub fn detect_video(path: &Path) -> Result<VideoDetectionResult, FFprobeError> {
         bits_per_pixel,
         color_primaries: probe.color_primaries,
         color_transfer: probe.color_transfer,
+        color_range: probe.color_range,
         mastering_display: probe.mastering_display,
         max_cll: probe.max_cll,
         is_dolby_vision: probe.is_dolby_vision,
@@ -460,6 +589,7 @@ pub fn detect_video(path: &Path) -> Result<VideoDetectionResult, FFprobeError> {
         subtitle_codec: probe.subtitle_codec,
         audio_channels: probe.audio_channels,
         is_variable_frame_rate: probe.is_variable_frame_rate,
+        rotation: probe.rotation,
         precision,
         tags: probe.tags,
         history: crate::common_utils::get_current_history(),
@@ -526,3 +656,36 @@ fn extract_video_precision(
 
     precision
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compare_detections_marks_differences() {
+        let left = VideoDetectionResult {
+            codec: DetectedCodec::H264,
+            pix_fmt: "yuv420p".to_string(),
+            bit_depth: 8,
+            width: 1920,
+            height: 1080,
+            bitrate: 8_000_000,
+            ..VideoDetectionResult::default()
+        };
+        let right = VideoDetectionResult {
+            codec: DetectedCodec::H265,
+            pix_fmt: "yuv420p10le".to_string(),
+            bit_depth: 10,
+            ..left.clone()
+        };
+        let rows = compare_detections(&left, &right);
+        let differing: Vec<&str> = rows
+            .iter()
+            .filter(|r| r.differs())
+            .map(|r| r.field)
+            .collect();
+        assert_eq!(differing, ["codec", "pix_fmt", "bit depth"]);
+        let bitrate = rows.iter().find(|r| r.field == "bitrate").unwrap();
+        assert_eq!(bitrate.left, "8000 kb/s");
+    }
+}
@@ -12,11 +12,13 @@ use anyhow::{bail, Context, Result};
 use std::fs;
 use std::path::Path;
 use std::process::Command;
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
 
 use crate::explore_strategy::CrfCache;
 
 use crate::crf_constants::EMERGENCY_MAX_ITERATIONS;
 use crate::float_compare::SSIM_EPSILON;
+use crate::quality_matcher::ContentType;
 use crate::types::{FileSize, Ssim};
 
 pub mod error_handling;
@@ -244,6 +246,46 @@ pub enum ExploreMode {
     CompressWithQuality,
 }
 
+impl ExploreMode {
+    pub const ALL: [ExploreMode; 6] = [
+        ExploreMode::SizeOnly,
+        ExploreMode::QualityMatch,
+        ExploreMode::PreciseQualityMatch,
+        ExploreMode::PreciseQualityMatchWithCompression,
+        ExploreMode::CompressOnly,
+        ExploreMode::CompressWithQuality,
+    ];
+
+    /// `--explore-mode` name.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExploreMode::SizeOnly => "size-only",
+            ExploreMode::QualityMatch => "quality-match",
+            ExploreMode::PreciseQualityMatch => "precise-quality-match",
+            ExploreMode::PreciseQualityMatchWithCompression => "precise-quality-match-compress",
+            ExploreMode::CompressOnly => "compress-only",
+            ExploreMode::CompressWithQuality => "compress-with-quality",
+        }
+    }
+
+    /// Parse `--explore-mode` (underscores are accepted in place of dashes).
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let value = value.trim().to_ascii_lowercase().replace('_', "-");
+        Self::ALL
+            .iter()
+            .find(|mode| mode.as_str() == value)
+            .copied()
+            .ok_or_else(|| {
+                let names: Vec<&str> = Self::ALL.iter().map(|m| m.as_str()).collect();
+                format!(
+                    "Invalid explore mode '{}' (expected one of: {})",
+                    value,
+                    names.join(", ")
+                )
+            })
+    }
+}
+
 /// Per-component confidence; overall() is computed from weights.
 /// Explore results currently use a fixed confidence value per mode; this breakdown is not yet filled.
 #[derive(Debug, Clone, Default)]
@@ -337,6 +379,15 @@ pub struct ExploreResult {
     pub psnr_uv_score: Option<(f64, f64)>,
     /// Early insight triggered: quality plateau detected, skipped further exploration.
     pub early_insight_triggered: bool,
+    /// The GPU search missed `min_ssim` and the result comes from a CPU re-run.
+    pub cpu_fallback_used: bool,
+    /// How the GPU coarse search narrowed the CPU search; `None` when no GPU boundary was used.
+    pub gpu_handoff: Option<GpuHandoff>,
+    /// `--max-output-size` pushed CRF past the quality-matched point; `ssim` may be below `actual_min_ssim`.
+    pub size_capped: bool,
+    /// Ultimate mode: the `--ultimate-max-seconds` / `--ultimate-max-iterations` cap that
+    /// stopped the search; the result is the best CRF found so far.
+    pub budget_cap_hit: Option<precision::BudgetCap>,
 }
 
 impl Default for ExploreResult {
@@ -364,6 +415,10 @@ impl Default for ExploreResult {
             cambi_score: None,
             psnr_uv_score: None,
             early_insight_triggered: false,
+            cpu_fallback_used: false,
+            gpu_handoff: None,
+            size_capped: false,
+            budget_cap_hit: None,
         }
     }
 }
@@ -422,6 +477,7 @@ pub struct ExploreConfig {
     pub max_iterations: u32,
     pub ultimate_mode: bool,
     pub use_pure_media_comparison: bool,
+    pub settings: EncodeSettings,
 }
 
 impl Default for ExploreConfig {
@@ -436,6 +492,7 @@ impl Default for ExploreConfig {
             max_iterations: EXPLORE_DEFAULT_MAX_ITERATIONS,
             ultimate_mode: false,
             use_pure_media_comparison: true,
+            settings: EncodeSettings::default(),
         }
     }
 }
@@ -550,37 +607,152 @@ pub enum VideoEncoder {
     H264,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+/// Encoder speed, fastest first. Maps to x265/x264 `-preset` names and SVT-AV1 preset numbers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
 pub enum EncoderPreset {
     Ultrafast,
+    Superfast,
+    Veryfast,
+    Faster,
     Fast,
     #[default]
     Medium,
     Slow,
     Slower,
     Veryslow,
+    Placebo,
 }
 
 impl EncoderPreset {
+    pub const ALL: [EncoderPreset; 10] = [
+        EncoderPreset::Ultrafast,
+        EncoderPreset::Superfast,
+        EncoderPreset::Veryfast,
+        EncoderPreset::Faster,
+        EncoderPreset::Fast,
+        EncoderPreset::Medium,
+        EncoderPreset::Slow,
+        EncoderPreset::Slower,
+        EncoderPreset::Veryslow,
+        EncoderPreset::Placebo,
+    ];
+
     pub fn x26x_name(&self) -> &'static str {
         match self {
             EncoderPreset::Ultrafast => "ultrafast",
+            EncoderPreset::Superfast => "superfast",
+            EncoderPreset::Veryfast => "veryfast",
+            EncoderPreset::Faster => "faster",
             EncoderPreset::Fast => "fast",
             EncoderPreset::Medium => "medium",
             EncoderPreset::Slow => "slow",
             EncoderPreset::Slower => "slower",
             EncoderPreset::Veryslow => "veryslow",
+            EncoderPreset::Placebo => "placebo",
         }
     }
 
     pub fn svtav1_preset(&self) -> u8 {
         match self {
             EncoderPreset::Ultrafast => 12,
+            EncoderPreset::Superfast => 11,
+            EncoderPreset::Veryfast => 10,
+            EncoderPreset::Faster => 9,
             EncoderPreset::Fast => 8,
             EncoderPreset::Medium => 6,
             EncoderPreset::Slow => 4,
             EncoderPreset::Slower => 2,
             EncoderPreset::Veryslow => 0,
+            EncoderPreset::Placebo => 0,
+        }
+    }
+
+    /// libaom `-cpu-used` (0 slowest – 8 fastest in good-quality mode).
+    pub fn aom_cpu_used(&self) -> u8 {
+        match self {
+            EncoderPreset::Ultrafast => 8,
+            EncoderPreset::Superfast => 7,
+            EncoderPreset::Veryfast => 6,
+            EncoderPreset::Faster => 5,
+            EncoderPreset::Fast => 5,
+            EncoderPreset::Medium => 4,
+            EncoderPreset::Slow => 3,
+            EncoderPreset::Slower => 2,
+            EncoderPreset::Veryslow => 1,
+            EncoderPreset::Placebo => 0,
+        }
+    }
+
+    /// Slower than `medium`; combined with `--ultimate` every search step pays the cost.
+    pub fn is_slower_than_default(&self) -> bool {
+        *self > EncoderPreset::Medium
+    }
+
+    /// Parse `--preset-speed`: an x265 preset name, or an SVT-AV1 preset number (0–13)
+    /// mapped to the nearest name (ties go to the slower preset).
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let value = value.trim().to_ascii_lowercase();
+        if let Some(preset) = Self::ALL.iter().find(|p| p.x26x_name() == value) {
+            return Ok(*preset);
+        }
+        match value.parse::<u8>() {
+            Ok(n) if n <= 13 => Ok(Self::ALL
+                .iter()
+                .rev()
+                .min_by_key(|p| p.svtav1_preset().abs_diff(n))
+                .copied()
+                .unwrap_or_default()),
+            _ => Err(format!(
+                "Invalid preset speed '{}' (expected ultrafast..placebo or an SVT-AV1 preset 0-13)",
+                value
+            )),
+        }
+    }
+}
+
+/// CPU encoder behind [`VideoEncoder::Av1`]. Both take `-crf` 0–63, so CRF values carry over
+/// unchanged; only the speed and grain options differ.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Av1Backend {
+    #[default]
+    Svt,
+    Aom,
+}
+
+impl Av1Backend {
+    pub const ALL: [Av1Backend; 2] = [Av1Backend::Svt, Av1Backend::Aom];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Av1Backend::Svt => "svt",
+            Av1Backend::Aom => "aom",
+        }
+    }
+
+    pub fn ffmpeg_name(&self) -> &'static str {
+        match self {
+            Av1Backend::Svt => "libsvtav1",
+            Av1Backend::Aom => "libaom-av1",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Av1Backend::Svt => "SVT-AV1",
+            Av1Backend::Aom => "libaom",
+        }
+    }
+
+    /// Parse `--av1-encoder`: `svt` or `aom`, or the ffmpeg encoder name.
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value.trim().to_ascii_lowercase().replace('_', "-").as_str() {
+            "svt" | "svt-av1" | "svtav1" | "libsvtav1" => Ok(Av1Backend::Svt),
+            "aom" | "libaom" | "libaom-av1" => Ok(Av1Backend::Aom),
+            other => Err(format!(
+                "Invalid AV1 encoder '{}' (expected one of: {})",
+                other,
+                Self::ALL.map(|b| b.as_str()).join(", ")
+            )),
         }
     }
 }
@@ -598,7 +770,7 @@ impl VideoEncoder {
                     "hevc_videotoolbox"
                 }
             }
-            VideoEncoder::Av1 => "libsvtav1",
+            VideoEncoder::Av1 => av1_backend().ffmpeg_name(),
             VideoEncoder::H264 => {
                 if Self::is_encoder_available("libx264") {
                     "libx264"
@@ -617,10 +789,14 @@ impl VideoEncoder {
 
         static LIBX265_AVAILABLE: std::sync::OnceLock<bool> = std::sync::OnceLock::new();
         static LIBX264_AVAILABLE: std::sync::OnceLock<bool> = std::sync::OnceLock::new();
+        static LIBSVTAV1_AVAILABLE: std::sync::OnceLock<bool> = std::sync::OnceLock::new();
+        static LIBAOM_AVAILABLE: std::sync::OnceLock<bool> = std::sync::OnceLock::new();
 
         let cache = match encoder {
             "libx265" => &LIBX265_AVAILABLE,
             "libx264" => &LIBX264_AVAILABLE,
+            "libsvtav1" => &LIBSVTAV1_AVAILABLE,
+            "libaom-av1" => &LIBAOM_AVAILABLE,
             _ => return true,
         };
 
@@ -645,11 +821,12 @@ impl VideoEncoder {
         }
     }
 
-    pub fn extra_args(&self, max_threads: usize) -> Vec<String> {
-        self.extra_args_with_preset(max_threads, EncoderPreset::default())
-    }
-
-    pub fn extra_args_with_preset(&self, max_threads: usize, preset: EncoderPreset) -> Vec<String> {
+    pub fn extra_args_with_preset(
+        &self,
+        max_threads: usize,
+        preset: EncoderPreset,
+        deterministic: bool,
+    ) -> Vec<String> {
         match self {
             VideoEncoder::Hevc => vec![
                 "-preset".to_string(),
@@ -657,16 +834,24 @@ impl VideoEncoder {
                 "-tag:v".to_string(),
                 "hvc1".to_string(),
                 "-x265-params".to_string(),
-                format!("log-level=error:pools={}", max_threads),
-            ],
-            VideoEncoder::Av1 => vec![
-                "-svtav1-params".to_string(),
                 format!(
-                    "tune=0:film-grain=0:preset={}:lp={}",
-                    preset.svtav1_preset(),
-                    max_threads
+                    "log-level=error:pools={}{}",
+                    max_threads,
+                    deterministic_x265_params(deterministic)
                 ),
             ],
+            VideoEncoder::Av1 => match av1_backend() {
+                Av1Backend::Svt => vec![
+                    "-svtav1-params".to_string(),
+                    format!(
+                        "tune=0:film-grain={}:preset={}:lp={}",
+                        film_grain_level(),
+                        preset.svtav1_preset(),
+                        max_threads
+                    ),
+                ],
+                Av1Backend::Aom => libaom_args(false, preset, max_threads),
+            },
             VideoEncoder::H264 => vec![
                 "-preset".to_string(),
                 preset.x26x_name().to_string(),
@@ -740,6 +925,11 @@ pub struct TransparencyReport {
     pub final_crf: Option<f32>,
     pub final_ssim: Option<f64>,
     pub final_psnr: Option<f64>,
+    /// Set when an ultimate-mode budget cap stopped the search early.
+    pub budget_cap_hit: Option<precision::BudgetCap>,
+    /// Set when the GPU result missed `min_ssim` and the search was re-run on CPU.
+    pub cpu_fallback_used: bool,
+    pub gpu_handoff: Option<GpuHandoff>,
 }
 
 impl TransparencyReport {
@@ -751,9 +941,22 @@ impl TransparencyReport {
             final_crf: None,
             final_ssim: None,
             final_psnr: None,
+            budget_cap_hit: None,
+            cpu_fallback_used: false,
+            gpu_handoff: None,
         }
     }
 
+    /// Copy the outcome of a finished search into the report.
+    pub fn record_result(&mut self, result: &ExploreResult) {
+        self.final_crf = Some(result.optimal_crf);
+        self.final_ssim = result.ssim;
+        self.final_psnr = result.psnr;
+        self.budget_cap_hit = result.budget_cap_hit;
+        self.cpu_fallback_used = result.cpu_fallback_used;
+        self.gpu_handoff = result.gpu_handoff.clone();
+    }
+
     pub fn add_iteration(&mut self, metrics: IterationMetrics) {
         metrics.print_line();
         self.iterations.push(metrics);
@@ -790,9 +993,521 @@ impl TransparencyReport {
         if let Some(psnr) = self.final_psnr {
             crate::log_eprintln!("   • Final PSNR: {:.1} dB", psnr);
         }
+        if let Some(cap) = self.budget_cap_hit {
+            crate::log_eprintln!("   • Stopped early: {} reached (best-so-far result)", cap);
+        }
+        if self.cpu_fallback_used {
+            crate::log_eprintln!("   • GPU missed the SSIM target: final encode re-run on CPU");
+        }
+        if let Some(handoff) = &self.gpu_handoff {
+            crate::log_eprintln!(
+                "   • GPU coarse CRF {:.1} → CPU range [{:.1}, {:.1}] (~{} CPU iterations saved)",
+                handoff.gpu_crf,
+                handoff.cpu_min_crf,
+                handoff.cpu_max_crf,
+                handoff.cpu_iterations_saved
+            );
+        }
+    }
+}
+
+/// Bisection steps needed to pin a CRF to 0.1 within `[min_crf, max_crf]`.
+pub fn estimated_crf_search_iterations(min_crf: f32, max_crf: f32) -> u32 {
+    let width = max_crf - min_crf;
+    if width <= 0.1 {
+        return 0;
+    }
+    (width / 0.1).log2().ceil() as u32
+}
+
+/// GPU coarse search → CPU fine-tune handoff: what the GPU found and how it narrowed the CPU
+/// search. Printed in verbose mode and carried by [`ExploreResult`] / [`TransparencyReport`].
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct GpuHandoff {
+    pub gpu_crf: f32,
+    pub gpu_ssim: Option<f64>,
+    /// `gpu_ssim` was inferred from PSNR, not measured.
+    pub gpu_ssim_estimated: bool,
+    pub gpu_psnr: Option<f64>,
+    pub gpu_iterations: u32,
+    pub cpu_min_crf: f32,
+    pub cpu_max_crf: f32,
+    pub cpu_start_crf: f32,
+    /// CRF the CPU fine-tune settled on; filled in once the CPU search finishes.
+    pub final_cpu_crf: Option<f32>,
+    /// Estimated from [`estimated_crf_search_iterations`]: full encoder range minus the
+    /// GPU-narrowed range.
+    pub cpu_iterations_saved: u32,
+}
+
+impl GpuHandoff {
+    pub fn new(
+        gpu: &crate::gpu_accel::GpuCoarseResult,
+        cpu_range: (f32, f32, f32),
+        full_range: (f32, f32),
+    ) -> Self {
+        let (cpu_min_crf, cpu_max_crf, cpu_start_crf) = cpu_range;
+        let full = estimated_crf_search_iterations(full_range.0, full_range.1);
+        let narrowed = estimated_crf_search_iterations(cpu_min_crf, cpu_max_crf);
+        Self {
+            gpu_crf: gpu.gpu_boundary_crf,
+            gpu_ssim: gpu.gpu_best_ssim,
+            gpu_ssim_estimated: gpu.gpu_ssim_estimated,
+            gpu_psnr: gpu.gpu_best_psnr,
+            gpu_iterations: gpu.iterations,
+            cpu_min_crf,
+            cpu_max_crf,
+            cpu_start_crf,
+            final_cpu_crf: None,
+            cpu_iterations_saved: full.saturating_sub(narrowed),
+        }
+    }
+
+    pub fn print(&self) {
+        let fmt = |v: Option<f64>, digits: usize| {
+            v.map(|v| format!("{:.*}", digits, v))
+                .unwrap_or_else(|| "----".to_string())
+        };
+        crate::verbose_eprintln!("🔀 GPU → CPU handoff:");
+        crate::verbose_eprintln!(
+            "   GPU coarse: CRF {:.1}, SSIM {}{}, PSNR {} dB ({} GPU iterations)",
+            self.gpu_crf,
+            fmt(self.gpu_ssim, 4),
+            if self.gpu_ssim_estimated {
+                " (estimated from PSNR)"
+            } else {
+                ""
+            },
+            fmt(self.gpu_psnr, 2),
+            self.gpu_iterations
+        );
+        crate::verbose_eprintln!(
+            "   CPU search range: [{:.1}, {:.1}] (start {:.1})",
+            self.cpu_min_crf,
+            self.cpu_max_crf,
+            self.cpu_start_crf
+        );
+        if let Some(crf) = self.final_cpu_crf {
+            crate::verbose_eprintln!("   Final CPU CRF: {:.1}", crf);
+        }
+        crate::verbose_eprintln!(
+            "   CPU iterations saved: ~{} (vs. the full CRF range)",
+            self.cpu_iterations_saved
+        );
+    }
+}
+
+// On by default; `--no-gpu-cpu-fallback` turns it off for the whole process.
+static GPU_CPU_FALLBACK: AtomicBool = AtomicBool::new(true);
+
+/// Enable or disable the CPU re-run when a GPU search cannot reach `min_ssim`.
+pub fn set_gpu_cpu_fallback(enabled: bool) {
+    GPU_CPU_FALLBACK.store(enabled, Ordering::Relaxed);
+}
+
+pub fn gpu_cpu_fallback_enabled() -> bool {
+    GPU_CPU_FALLBACK.load(Ordering::Relaxed)
+}
+
+/// Fixed `creation_time` written by `--deterministic` encodes.
+pub const DETERMINISTIC_CREATION_TIME: &str = "2000-01-01T00:00:00.000000Z";
+
+/// Bit-exact muxing without source metadata and with a fixed timestamp; appended to every
+/// encode under `--deterministic` (`enabled`), empty otherwise.
+pub fn deterministic_args(enabled: bool) -> Vec<String> {
+    if !enabled {
+        return Vec::new();
+    }
+    [
+        "-map_metadata",
+        "-1",
+        "-fflags",
+        "+bitexact",
+        "-flags:v",
+        "+bitexact",
+        "-flags:a",
+        "+bitexact",
+        "-metadata",
+        &format!("creation_time={}", DETERMINISTIC_CREATION_TIME),
+    ]
+    .iter()
+    .map(|s| s.to_string())
+    .collect()
+}
+
+/// x265 parameters pinned under `--deterministic`: frame threading and lookahead slices
+/// depend on thread timing, so both are fixed.
+pub fn deterministic_x265_params(enabled: bool) -> &'static str {
+    if enabled {
+        ":frame-threads=1:lookahead-slices=0"
+    } else {
+        ""
     }
 }
 
+/// [`deterministic_x265_params`] as x265 CLI options, for the y4m → x265 encode path.
+pub fn deterministic_x265_cli_args(enabled: bool) -> Vec<String> {
+    if !enabled {
+        return Vec::new();
+    }
+    ["--frame-threads", "1", "--lookahead-slices", "0"]
+        .iter()
+        .map(|s| s.to_string())
+        .collect()
+}
+
+static FORCE_10BIT: AtomicBool = AtomicBool::new(false);
+
+/// `--force-10bit`: encode HEVC as 10-bit (main10) even from 8-bit sources.
+pub fn set_force_10bit(enabled: bool) {
+    FORCE_10BIT.store(enabled, Ordering::Relaxed);
+}
+
+pub fn force_10bit_enabled() -> bool {
+    FORCE_10BIT.load(Ordering::Relaxed)
+}
+
+/// HEVC pixel format: a ≥10-bit source stays 10-bit, an 8-bit source stays 8-bit unless
+/// `force_10bit` upconverts it (less banding in gradients, slightly larger files).
+pub fn hevc_pix_fmt_for(source_bit_depth: u8, force_10bit: bool) -> &'static str {
+    if source_bit_depth >= 10 || force_10bit {
+        "yuv420p10le"
+    } else {
+        "yuv420p"
+    }
+}
+
+/// Single-channel counterpart of a 4:2:0 `pix_fmt`, keeping its bit depth.
+pub fn gray_pix_fmt(pix_fmt: &str) -> &'static str {
+    if pix_fmt.contains("10") {
+        "gray10le"
+    } else {
+        "gray"
+    }
+}
+
+/// Output pixel format accepted by `--pix-fmt`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PixelFormat {
+    Yuv420p,
+    Yuv420p10le,
+    Yuv422p,
+    Yuv422p10le,
+    Yuv444p,
+    Yuv444p10le,
+}
+
+impl PixelFormat {
+    pub const ALL: [PixelFormat; 6] = [
+        PixelFormat::Yuv420p,
+        PixelFormat::Yuv420p10le,
+        PixelFormat::Yuv422p,
+        PixelFormat::Yuv422p10le,
+        PixelFormat::Yuv444p,
+        PixelFormat::Yuv444p10le,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PixelFormat::Yuv420p => "yuv420p",
+            PixelFormat::Yuv420p10le => "yuv420p10le",
+            PixelFormat::Yuv422p => "yuv422p",
+            PixelFormat::Yuv422p10le => "yuv422p10le",
+            PixelFormat::Yuv444p => "yuv444p",
+            PixelFormat::Yuv444p10le => "yuv444p10le",
+        }
+    }
+
+    pub fn is_420(&self) -> bool {
+        matches!(self, PixelFormat::Yuv420p | PixelFormat::Yuv420p10le)
+    }
+
+    /// Refuse formats `encoder` (an ffmpeg encoder name) cannot write: SVT-AV1 is 4:2:0 only.
+    pub fn check_encoder(&self, encoder: &str) -> Result<(), String> {
+        if encoder == "libsvtav1" && !self.is_420() {
+            return Err(format!(
+                "SVT-AV1 only encodes 4:2:0; use --av1-encoder aom for {}",
+                self.as_str()
+            ));
+        }
+        Ok(())
+    }
+
+    /// Parse `--pix-fmt`. The `yuvj*` names are refused: they only mean "full range", which is
+    /// carried over from the source by itself.
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let value = value.trim().to_ascii_lowercase();
+        if let Some(plain) = value.strip_prefix("yuvj") {
+            return Err(format!(
+                "'{}' is a deprecated full-range alias; use yuv{} (the source's color range is kept)",
+                value, plain
+            ));
+        }
+        Self::ALL
+            .iter()
+            .find(|f| f.as_str() == value || f.as_str().strip_suffix("le") == Some(&value))
+            .copied()
+            .ok_or_else(|| {
+                format!(
+                    "Invalid pixel format '{}' (expected one of: {})",
+                    value,
+                    Self::ALL.map(|f| f.as_str()).join(", ")
+                )
+            })
+    }
+}
+
+/// [`hevc_pix_fmt_for`] with the process-wide `--force-10bit` setting, as gray while
+/// [`encode_grayscale`] is active on this thread. An explicit `--pix-fmt` (`pix_fmt_override`)
+/// wins over both.
+pub fn hevc_pix_fmt(source_bit_depth: u8, pix_fmt_override: Option<PixelFormat>) -> &'static str {
+    if let Some(pix_fmt) = pix_fmt_override {
+        return pix_fmt.as_str();
+    }
+    let pix_fmt = hevc_pix_fmt_for(source_bit_depth, force_10bit_enabled());
+    if grayscale_encode_enabled() {
+        gray_pix_fmt(pix_fmt)
+    } else {
+        pix_fmt
+    }
+}
+
+thread_local! {
+    static GRAYSCALE_ENCODE: std::cell::Cell<bool> = const { std::cell::Cell::new(false) };
+}
+
+/// Restores the previous [`encode_grayscale`] setting on drop.
+pub struct GrayscaleEncodeGuard {
+    previous: bool,
+}
+
+impl Drop for GrayscaleEncodeGuard {
+    fn drop(&mut self) {
+        GRAYSCALE_ENCODE.with(|g| g.set(self.previous));
+    }
+}
+
+/// `--auto-grayscale`: HEVC encodes on this thread use a gray (4:0:0) pixel format until the
+/// returned guard is dropped. Installed per file once the source was found to carry no chroma.
+pub fn encode_grayscale(enabled: bool) -> GrayscaleEncodeGuard {
+    let previous = GRAYSCALE_ENCODE.with(|g| g.replace(enabled));
+    GrayscaleEncodeGuard { previous }
+}
+
+pub fn grayscale_encode_enabled() -> bool {
+    GRAYSCALE_ENCODE.with(|g| g.get())
+}
+
+thread_local! {
+    static CRF_PREDICTION: std::cell::Cell<Option<(f64, f32)>> = const { std::cell::Cell::new(None) };
+}
+
+/// Restores the previous [`encode_crf_prediction`] on drop.
+pub struct CrfPredictionGuard {
+    previous: Option<(f64, f32)>,
+}
+
+impl Drop for CrfPredictionGuard {
+    fn drop(&mut self) {
+        CRF_PREDICTION.with(|p| p.set(self.previous));
+    }
+}
+
+/// The quality matcher's `(crf, confidence)` for the file being converted on this thread (see
+/// `predict_crf_with_confidence`). The GPU → CPU handoff pulls its start toward the prediction
+/// and narrows the fine-tuned window when confidence is high. Cleared when the guard drops.
+pub fn encode_crf_prediction(prediction: Option<(f64, f32)>) -> CrfPredictionGuard {
+    let previous = CRF_PREDICTION.with(|p| p.replace(prediction));
+    CrfPredictionGuard { previous }
+}
+
+pub fn crf_prediction() -> Option<(f64, f32)> {
+    CRF_PREDICTION.with(|p| p.get())
+}
+
+thread_local! {
+    static FRAME_RATE_MODE: std::cell::RefCell<Vec<String>> =
+        const { std::cell::RefCell::new(Vec::new()) };
+}
+
+/// Restores the previous [`encode_frame_rate_mode`] args on drop.
+pub struct FrameRateModeGuard {
+    previous: Vec<String>,
+}
+
+impl Drop for FrameRateModeGuard {
+    fn drop(&mut self) {
+        let previous = std::mem::take(&mut self.previous);
+        FRAME_RATE_MODE.with(|m| *m.borrow_mut() = previous);
+    }
+}
+
+/// VFR handling (`-vsync vfr`/`--force-cfr`): encodes on this thread append `args` (see
+/// [`crate::frame_rate_mode_args`]) until the returned guard is dropped. Kept apart from
+/// `vf_args` so filter consumers (metric reference, downscale lookup) only see filters.
+pub fn encode_frame_rate_mode(args: Vec<String>) -> FrameRateModeGuard {
+    let previous = FRAME_RATE_MODE.with(|m| m.replace(args));
+    FrameRateModeGuard { previous }
+}
+
+pub fn frame_rate_mode_encode_args() -> Vec<String> {
+    FRAME_RATE_MODE.with(|m| m.borrow().clone())
+}
+
+/// Highest SVT-AV1 `film-grain` level.
+pub const FILM_GRAIN_MAX: u8 = 50;
+
+thread_local! {
+    static FILM_GRAIN: std::cell::Cell<u8> = const { std::cell::Cell::new(0) };
+}
+
+/// Restores the previous [`encode_film_grain`] level on drop.
+pub struct FilmGrainGuard {
+    previous: u8,
+}
+
+impl Drop for FilmGrainGuard {
+    fn drop(&mut self) {
+        FILM_GRAIN.with(|g| g.set(self.previous));
+    }
+}
+
+/// `--film-grain`: SVT-AV1 encodes on this thread denoise the source and signal grain at
+/// `level` (0 = off) for the decoder to re-synthesize, until the returned guard is dropped.
+/// The synthesized grain is not the source's grain, so SSIM against the source reads lower.
+pub fn encode_film_grain(level: u8) -> FilmGrainGuard {
+    let previous = FILM_GRAIN.with(|g| g.replace(level.min(FILM_GRAIN_MAX)));
+    FilmGrainGuard { previous }
+}
+
+pub fn film_grain_level() -> u8 {
+    FILM_GRAIN.with(|g| g.get())
+}
+
+/// libx265 profile matching `pix_fmt`; ffmpeg does not always pick main10 on its own.
+/// Gray and 4:2:2/4:4:4 have no main/main10 profile, so x265 picks the matching one itself.
+pub fn hevc_profile_args(pix_fmt: &str) -> Vec<String> {
+    if pix_fmt.starts_with("gray") || pix_fmt.starts_with("yuv422") || pix_fmt.starts_with("yuv444")
+    {
+        return Vec::new();
+    }
+    let profile = if pix_fmt.contains("p10") {
+        "main10"
+    } else {
+        "main"
+    };
+    vec!["-profile:v".to_string(), profile.to_string()]
+}
+
+/// Per-conversion encode options, built from the caller's `ConversionConfig` and passed down
+/// the explore path, so conversions running side by side never see each other's settings.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct EncodeSettings {
+    /// `--no-audio`: the full-length explore encode writes video-only output (`-an`).
+    pub drop_audio: bool,
+    /// `--preset-speed`: encoder speed for every CPU encode of the explore search and final output.
+    pub preset: EncoderPreset,
+    /// `--max-output-size`: absolute byte cap on the explored output, applied after the quality search.
+    pub max_output_size: Option<u64>,
+    /// `--sample-ssim N`: the final SSIM gate compares N evenly spaced frames instead of the whole clip.
+    pub sample_ssim_frames: Option<u32>,
+    /// `--ssim-sampling-strategy`: which frames the `--sample-ssim` gate compares.
+    pub ssim_sampling_strategy: SsimSamplingStrategy,
+    /// `--pix-fmt`: pixel format of every encode, instead of the one derived from the source's
+    /// bit depth. `None` keeps the automatic choice.
+    pub pix_fmt: Option<PixelFormat>,
+    /// `--strip-metadata`: encodes drop the input's container metadata.
+    pub strip: crate::metadata::StripPolicy,
+    /// `--deterministic`: byte-identical output across runs. The explorer stays on the CPU
+    /// encoders, pins x265 threading and muxes bit-exact.
+    pub deterministic: bool,
+}
+
+static AV1_BACKEND: AtomicU8 = AtomicU8::new(Av1Backend::Svt as u8);
+static AV1_FALLBACK_LOGGED: AtomicBool = AtomicBool::new(false);
+
+/// `--av1-encoder`: preferred CPU encoder for every AV1 encode.
+pub fn set_av1_backend(backend: Av1Backend) {
+    AV1_BACKEND.store(backend as u8, Ordering::Relaxed);
+}
+
+/// The AV1 encoder actually used: the `--av1-encoder` preference when this ffmpeg has it,
+/// otherwise the other one (logged once). With neither present the preference is kept and
+/// ffmpeg reports the missing encoder.
+pub fn av1_backend() -> Av1Backend {
+    let raw = AV1_BACKEND.load(Ordering::Relaxed) as usize;
+    let preferred = Av1Backend::ALL.get(raw).copied().unwrap_or_default();
+    if VideoEncoder::is_encoder_available(preferred.ffmpeg_name()) {
+        return preferred;
+    }
+    let Some(fallback) = Av1Backend::ALL
+        .into_iter()
+        .find(|b| *b != preferred && VideoEncoder::is_encoder_available(b.ffmpeg_name()))
+    else {
+        return preferred;
+    };
+    if !AV1_FALLBACK_LOGGED.swap(true, Ordering::Relaxed) {
+        crate::log_eprintln!(
+            "⚠️  {} not available, falling back to {}",
+            preferred.ffmpeg_name(),
+            fallback.ffmpeg_name()
+        );
+    }
+    fallback
+}
+
+/// libaom-av1 options for one encode, after `-c:v libaom-av1 -crf N`: `-b:v 0` puts it in
+/// constant-quality mode, `preset` maps to `-cpu-used`, and the thread's [`film_grain_level`]
+/// to `-denoise-noise-level`. `lossless` replaces the grain option with `lossless=1`.
+pub fn libaom_args(lossless: bool, preset: EncoderPreset, max_threads: usize) -> Vec<String> {
+    let mut args = vec![
+        "-b:v".to_string(),
+        "0".to_string(),
+        "-cpu-used".to_string(),
+        preset.aom_cpu_used().to_string(),
+        "-row-mt".to_string(),
+        "1".to_string(),
+        "-threads".to_string(),
+        max_threads.to_string(),
+    ];
+    if lossless {
+        args.extend(["-aom-params".to_string(), "lossless=1".to_string()]);
+    } else if film_grain_level() > 0 {
+        args.extend([
+            "-denoise-noise-level".to_string(),
+            film_grain_level().to_string(),
+        ]);
+    }
+    args
+}
+
+/// Apply `--max-output-size` to a result produced outside `VideoExplorer` (the GPU coarse path).
+/// The re-encodes run on CPU so the capped output matches what the fine-tune would have written.
+pub fn enforce_max_output_size(
+    input: &Path,
+    output: &Path,
+    encoder: VideoEncoder,
+    vf_args: Vec<String>,
+    result: ExploreResult,
+    max_threads: usize,
+    settings: EncodeSettings,
+) -> Result<ExploreResult> {
+    let Some(ceiling) = settings.max_output_size else {
+        return Ok(result);
+    };
+    let written = fs::metadata(output).map(|m| m.len()).unwrap_or(0);
+    if written <= ceiling {
+        return Ok(result);
+    }
+    let _phase = crate::phase_profile::phase(crate::ProfilePhase::FinalEncode);
+    let config = ExploreConfig {
+        settings,
+        ..Default::default()
+    };
+    VideoExplorer::new_with_gpu(input, output, encoder, vf_args, config, false, max_threads)?
+        .fit_size_ceiling(result, ceiling)
+}
+
 pub struct VideoExplorer {
     config: ExploreConfig,
     encoder: VideoEncoder,
@@ -820,14 +1535,15 @@ impl VideoExplorer {
         crate::path_validator::validate_path(input).map_err(|e| anyhow::anyhow!("{}", e))?;
         crate::path_validator::validate_path(output).map_err(|e| anyhow::anyhow!("{}", e))?;
 
-        let input_size = fs::metadata(input)
-            .context("Failed to read input file metadata")?
-            .len();
+        let input_size = crate::video::trimmed_source_size(input)
+            .context("Failed to read input file metadata")?;
 
+        let deterministic = config.settings.deterministic;
         let use_gpu = match use_gpu {
-            Some(b) => b,
+            // Hardware encoders give no reproducibility guarantee.
+            Some(b) => b && !deterministic,
             None => {
-                let gpu = crate::gpu_accel::GpuAccel::detect();
+                let gpu = crate::gpu_accel::GpuAccel::detect_for(deterministic);
                 gpu.is_available()
                     && match encoder {
                         VideoEncoder::Hevc => gpu.get_hevc_encoder().is_some(),
@@ -837,6 +1553,13 @@ impl VideoExplorer {
             }
         };
 
+        if deterministic && encoder.ffmpeg_name().ends_with("_videotoolbox") {
+            crate::log_eprintln!(
+                "⚠️  --deterministic: {} output is not reproducible",
+                encoder.ffmpeg_name()
+            );
+        }
+
         let input_video_stream_size = if config.use_pure_media_comparison {
             let stream_info = crate::stream_size::extract_stream_sizes(input);
             stream_info.video_stream_size
@@ -866,6 +1589,7 @@ impl VideoExplorer {
         config: ExploreConfig,
         max_threads: usize,
     ) -> Result<Self> {
+        let preset = config.settings.preset;
         Self::build(
             input,
             output,
@@ -873,7 +1597,7 @@ impl VideoExplorer {
             vf_args,
             config,
             None,
-            EncoderPreset::default(),
+            preset,
             max_threads,
         )
     }
@@ -887,6 +1611,7 @@ impl VideoExplorer {
         use_gpu: bool,
         max_threads: usize,
     ) -> Result<Self> {
+        let preset = config.settings.preset;
         Self::build(
             input,
             output,
@@ -894,7 +1619,7 @@ impl VideoExplorer {
             vf_args,
             config,
             Some(use_gpu),
-            EncoderPreset::default(),
+            preset,
             max_threads,
         )
     }
@@ -921,6 +1646,12 @@ impl VideoExplorer {
     }
 
     pub fn explore(&self) -> Result<ExploreResult> {
+        let result = self.explore_by_mode()?;
+        let result = self.with_cpu_fallback(result, Self::explore_by_mode)?;
+        self.with_size_ceiling(result)
+    }
+
+    fn explore_by_mode(&self) -> Result<ExploreResult> {
         match self.config.mode {
             ExploreMode::SizeOnly => self.explore_size_only(),
             ExploreMode::QualityMatch => self.explore_quality_match(),
@@ -934,6 +1665,171 @@ impl VideoExplorer {
     }
 
     pub fn explore_with_strategy(&self) -> Result<ExploreResult> {
+        let result = self.explore_strategy_once()?;
+        let result = self.with_cpu_fallback(result, Self::explore_strategy_once)?;
+        self.with_size_ceiling(result)
+    }
+
+    /// GPU encoders (VideoToolbox HEVC in particular) plateau around SSIM 0.95, so a
+    /// match-quality target can be out of reach on GPU. When the measured SSIM of a GPU
+    /// result is below `min_ssim`, re-run the same search on CPU before giving up.
+    fn with_cpu_fallback(
+        &self,
+        gpu_result: ExploreResult,
+        search: fn(&Self) -> Result<ExploreResult>,
+    ) -> Result<ExploreResult> {
+        let min_ssim = self.config.quality_thresholds.min_ssim;
+        let missed_target = self.config.quality_thresholds.validate_ssim
+            && gpu_result.ssim.is_some()
+            && !gpu_result.ssim_meets(min_ssim);
+        if !self.use_gpu || !missed_target || !gpu_cpu_fallback_enabled() {
+            return Ok(gpu_result);
+        }
+
+        let note = format!(
+            "GPU best SSIM {:.4} < {:.4} at CRF {:.1}: retrying on CPU",
+            gpu_result.ssim.unwrap_or(0.0),
+            min_ssim,
+            gpu_result.optimal_crf
+        );
+        crate::log_eprintln!("   🔁 {}", note);
+
+        let cpu = Self {
+            config: self.config.clone(),
+            encoder: self.encoder,
+            input_path: self.input_path.clone(),
+            output_path: self.output_path.clone(),
+            input_size: self.input_size,
+            vf_args: self.vf_args.clone(),
+            use_gpu: false,
+            max_threads: self.max_threads,
+            preset: self.preset,
+            input_video_stream_size: self.input_video_stream_size,
+        };
+        let mut result = search(&cpu)?;
+        result.cpu_fallback_used = true;
+        result.iterations += gpu_result.iterations;
+        result.log.insert(0, note);
+        if !result.quality_passed {
+            crate::log_eprintln!(
+                "   ⚠️  CPU fallback also missed the SSIM target ({})",
+                result
+                    .ssim
+                    .map(|s| format!("{:.4}", s))
+                    .unwrap_or_else(|| "N/A".to_string())
+            );
+        }
+        Ok(result)
+    }
+
+    fn with_size_ceiling(&self, result: ExploreResult) -> Result<ExploreResult> {
+        match self.config.settings.max_output_size {
+            Some(ceiling) if result.output_size > ceiling => self.fit_size_ceiling(result, ceiling),
+            _ => Ok(result),
+        }
+    }
+
+    /// `--max-output-size` is an absolute cap rather than a compression target: when the
+    /// quality-matched encode is still too large, bisect CRF upward to the lowest value that
+    /// fits and report the SSIM that costs, even below the usual floor. If the encoder's
+    /// highest CRF still overshoots, the output is removed and the file is rejected.
+    fn fit_size_ceiling(&self, mut result: ExploreResult, ceiling: u64) -> Result<ExploreResult> {
+        use crate::crf_constants::{AV1_CRF_MAX, HEVC_CRF_MAX, X264_CRF_MAX};
+        use crate::progress::format_bytes;
+
+        let codec_max = match self.encoder {
+            VideoEncoder::Hevc => HEVC_CRF_MAX,
+            VideoEncoder::Av1 => AV1_CRF_MAX,
+            VideoEncoder::H264 => X264_CRF_MAX,
+        };
+        let matched_crf = result.optimal_crf;
+        let matched_size = fs::metadata(&self.output_path)
+            .map(|m| m.len())
+            .unwrap_or(result.output_size);
+        crate::log_eprintln!(
+            "   📏 {} exceeds --max-output-size {}: raising CRF from {:.1}",
+            format_bytes(matched_size),
+            format_bytes(ceiling),
+            matched_crf
+        );
+
+        let mut iterations = 1u32;
+        let mut fit_size = self.encode(codec_max)?;
+        if fit_size > ceiling {
+            let _ = fs::remove_file(&self.output_path);
+            bail!(
+                "--max-output-size {}: even CRF {:.0} produces {}",
+                format_bytes(ceiling),
+                codec_max,
+                format_bytes(fit_size)
+            );
+        }
+
+        let (mut low, mut fit_crf, mut last_crf) = (matched_crf, codec_max, codec_max);
+        while fit_crf - low > 0.5 && iterations < BINARY_SEARCH_MAX_ITERATIONS {
+            let mid = ((low + fit_crf) / 2.0 * 2.0).round() / 2.0;
+            if mid <= low || mid >= fit_crf {
+                break;
+            }
+            let size = self.encode(mid)?;
+            iterations += 1;
+            last_crf = mid;
+            if size <= ceiling {
+                fit_crf = mid;
+                fit_size = size;
+            } else {
+                low = mid;
+            }
+        }
+        if (last_crf - fit_crf).abs() > f32::EPSILON {
+            fit_size = self.encode(fit_crf)?;
+            iterations += 1;
+        }
+
+        let ssim = self.calculate_ssim()?;
+        let floor = result.actual_min_ssim;
+        let ssim_text = ssim
+            .map(|s| format!("{:.4}", s))
+            .unwrap_or_else(|| "N/A".to_string());
+        let note = format!(
+            "--max-output-size {}: CRF {:.1} → {:.1} ({} → {}), SSIM {}",
+            format_bytes(ceiling),
+            matched_crf,
+            fit_crf,
+            format_bytes(matched_size),
+            format_bytes(fit_size),
+            ssim_text
+        );
+        if ssim.is_some_and(|s| crate::float_compare::ssim_meets_threshold(s, floor)) {
+            crate::log_eprintln!("   📏 {}", note);
+        } else {
+            crate::log_eprintln!(
+                "   ⚠️  {} is below the {:.4} floor: quality sacrificed to fit the size cap",
+                note,
+                floor
+            );
+        }
+
+        result.optimal_crf = fit_crf;
+        result.output_size = fit_size;
+        result.size_change_pct = self.calc_change_pct(fit_size);
+        result.ssim = ssim;
+        result.psnr = None;
+        result.ms_ssim = None;
+        result.ms_ssim_passed = None;
+        result.ms_ssim_score = None;
+        result.vmaf_y_score = None;
+        result.cambi_score = None;
+        result.psnr_uv_score = None;
+        result.output_video_stream_size = fit_size.saturating_sub(result.container_overhead);
+        result.iterations += iterations;
+        result.quality_passed = result.enhanced_verify_fail_reason.is_none();
+        result.size_capped = true;
+        result.log.push(note);
+        Ok(result)
+    }
+
+    fn explore_strategy_once(&self) -> Result<ExploreResult> {
         use crate::explore_strategy::{create_strategy, ExploreContext};
 
         let mut ctx = ExploreContext::new(
@@ -2138,11 +3034,13 @@ impl VideoExplorer {
     }
 
     fn encode(&self, crf: f32) -> Result<u64> {
+        crate::cancellation::check()?;
         if !self.use_gpu && self.encoder == VideoEncoder::Hevc {
             return self.encode_with_x265_cli(crf);
         }
 
         let result = self.encode_with_ffmpeg(crf);
+        crate::cancellation::check()?;
 
         if result.is_err() && self.use_gpu && self.encoder == VideoEncoder::Hevc {
             crate::log_eprintln!("      ⚠️  GPU encoding failed, falling back to CPU (x265 CLI)");
@@ -2152,22 +3050,56 @@ impl VideoExplorer {
         result
     }
 
-    fn encode_with_x265_cli(&self, crf: f32) -> Result<u64> {
-        use crate::x265_encoder::{encode_with_x265, X265Config};
+    /// Source audio codec and bitrate, probed only when `--audio-codec` may re-encode it.
+    fn source_audio(&self) -> (Option<String>, Option<u64>) {
+        if crate::audio_transcode().is_none() {
+            return (None, None);
+        }
+        match crate::ffprobe::probe_video(&self.input_path) {
+            Ok(probe) => (probe.audio_codec, probe.audio_bit_rate),
+            Err(_) => (None, None),
+        }
+    }
 
+    /// `--print-command`: the commands [`Self::encode`] runs for `crf`, one shell line each,
+    /// without running them. Describes the first attempt; a GPU failure falls back to x265.
+    pub fn encode_command_preview(&self, crf: f32) -> Vec<String> {
+        if !self.use_gpu && self.encoder == VideoEncoder::Hevc {
+            return crate::x265_encoder::x265_command_preview(
+                &self.input_path,
+                &self.output_path,
+                &self.x265_config(crf),
+                &self.vf_args,
+            );
+        }
+        let (args, _) = self.ffmpeg_encode_args(crf);
+        vec![crate::shell_command_string("ffmpeg", &args)]
+    }
+
+    fn encode_with_x265_cli(&self, crf: f32) -> Result<u64> {
         crate::log_eprintln!("      🖥️  CPU Encoding with x265 CLI (CRF {:.1})", crf);
 
+        crate::x265_encoder::encode_with_x265(
+            &self.input_path,
+            &self.output_path,
+            &self.x265_config(crf),
+            &self.vf_args,
+        )
+        .context("x265 CLI encoding failed")
+    }
+
+    fn x265_config(&self, crf: f32) -> crate::x265_encoder::X265Config {
         // Probe HDR metadata so we can preserve bit depth, colour primaries, TRC,
         // mastering display and CLL through the x265 encode.
         let color_info = crate::ffprobe_json::extract_color_info(&self.input_path);
 
-        let pix_fmt = if color_info.bit_depth.unwrap_or(8) >= 10 {
-            "yuv420p10le".to_string()
-        } else {
-            "yuv420p".to_string()
-        };
+        let pix_fmt = hevc_pix_fmt(
+            color_info.bit_depth.unwrap_or(8),
+            self.config.settings.pix_fmt,
+        )
+        .to_string();
 
-        let config = X265Config {
+        crate::x265_encoder::X265Config {
             crf,
             preset: self.preset.x26x_name().to_string(),
             threads: self.max_threads,
@@ -2177,29 +3109,21 @@ impl VideoExplorer {
             color_primaries: color_info.color_primaries,
             color_trc: color_info.color_transfer,
             colorspace: color_info.color_space,
+            color_range: color_info.color_range,
             mastering_display: color_info.mastering_display,
             max_cll: color_info.max_cll,
-            audio_codec: None,
+            audio_codec: self.source_audio().0,
             has_subtitles: false,
             subtitle_codec: None,
-        };
-
-        encode_with_x265(&self.input_path, &self.output_path, &config, &self.vf_args)
-            .context("x265 CLI encoding failed")
+            strip: self.config.settings.strip,
+            deterministic: self.config.settings.deterministic,
+        }
     }
 
-    fn encode_with_ffmpeg(&self, crf: f32) -> Result<u64> {
-        use std::io::{BufRead, BufReader, Write};
-        use std::process::Stdio;
-
-        use crate::universal_heartbeat::{HeartbeatConfig, HeartbeatGuard};
-        let _heartbeat = HeartbeatGuard::new(
-            HeartbeatConfig::medium("Video Encoding").with_info(format!("CRF {:.1}", crf)),
-        );
-
-        let mut cmd = Command::new("ffmpeg");
-        cmd.arg("-y");
-
+    /// The ffmpeg arguments `encode` runs for `crf` on the ffmpeg path (GPU, or CPU encoders other
+    /// than x265), and the label of the encoder they select. Progress reporting is added by
+    /// [`Self::encode_with_ffmpeg`].
+    fn ffmpeg_encode_args(&self, crf: f32) -> (Vec<String>, String) {
         let gpu = crate::gpu_accel::GpuAccel::detect();
         let (encoder_name, crf_args, extra_args, accel_type) = if self.use_gpu {
             match self.encoder {
@@ -2264,44 +3188,73 @@ impl VideoExplorer {
             )
         };
 
-        cmd.arg("-threads")
-            .arg(self.max_threads.to_string())
-            .arg("-i")
-            .arg(crate::safe_path_arg(&self.input_path).as_ref())
-            .arg("-c:v")
-            .arg(encoder_name);
+        let mut args = vec![
+            "-y".to_string(),
+            "-threads".to_string(),
+            self.max_threads.to_string(),
+        ];
+        args.extend(crate::video::source_trim_args(&self.input_path));
+        args.extend([
+            "-i".to_string(),
+            crate::safe_path_arg(&self.input_path).into_owned(),
+            "-c:v".to_string(),
+            encoder_name.to_string(),
+        ]);
+        args.extend(crf_args);
+        args.extend(extra_args.iter().map(|a| a.to_string()));
 
-        for arg in &crf_args {
-            cmd.arg(arg);
+        if !self.use_gpu {
+            args.extend(self.encoder.extra_args_with_preset(
+                self.max_threads,
+                self.preset,
+                self.config.settings.deterministic,
+            ));
         }
 
-        for arg in extra_args {
-            cmd.arg(arg);
+        args.extend(self.vf_args.iter().cloned());
+        args.extend(frame_rate_mode_encode_args());
+
+        if let Some(pix_fmt) = self.config.settings.pix_fmt {
+            args.extend(["-pix_fmt".to_string(), pix_fmt.as_str().to_string()]);
+        }
+        let color_range = crate::ffprobe::probe_video(&self.input_path)
+            .ok()
+            .and_then(|probe| probe.color_range);
+        args.extend(crate::color_range_args(color_range.as_deref()));
+
+        let (audio_codec, audio_bit_rate) = self.source_audio();
+        if let Some(audio_args) = crate::audio_transcode_args(
+            audio_codec.as_deref(),
+            audio_bit_rate,
+            crate::container_of(&self.output_path),
+        ) {
+            args.extend(audio_args);
         }
 
-        cmd.arg("-progress")
-            .arg("pipe:1")
-            .arg("-stats_period")
-            .arg("0.5");
+        args.extend(self.config.settings.strip.ffmpeg_args());
+        args.extend(deterministic_args(self.config.settings.deterministic));
+        args.push(crate::safe_path_arg(&self.output_path).into_owned());
+        (args, accel_type)
+    }
 
-        if !self.use_gpu {
-            for arg in self
-                .encoder
-                .extra_args_with_preset(self.max_threads, self.preset)
-            {
-                cmd.arg(arg);
-            }
-        }
+    fn encode_with_ffmpeg(&self, crf: f32) -> Result<u64> {
+        use std::io::{BufRead, BufReader, Write};
+        use std::process::Stdio;
 
-        for arg in &self.vf_args {
-            cmd.arg(arg);
-        }
+        use crate::universal_heartbeat::{HeartbeatConfig, HeartbeatGuard};
+        let _heartbeat = HeartbeatGuard::new(
+            HeartbeatConfig::medium("Video Encoding").with_info(format!("CRF {:.1}", crf)),
+        );
 
-        cmd.arg(crate::safe_path_arg(&self.output_path).as_ref());
+        let (args, accel_type) = self.ffmpeg_encode_args(crf);
+        let mut cmd = Command::new("ffmpeg");
+        cmd.args(["-progress", "pipe:1", "-stats_period", "0.5"])
+            .args(&args);
 
         cmd.stdout(Stdio::piped()).stderr(Stdio::piped());
 
         let mut child = cmd.spawn().context("Failed to spawn ffmpeg")?;
+        let watcher = crate::cancellation::ChildWatcher::for_current(&child);
 
         let duration_secs = self.get_input_duration().unwrap_or(0.0);
 
@@ -2396,6 +3349,8 @@ impl VideoExplorer {
             .unwrap_or_default();
 
         let status = child.wait().context("Failed to wait for ffmpeg")?;
+        drop(watcher);
+        crate::cancellation::check()?;
 
         crate::log_eprintln!(
             "\r      ✅ {} Encoding complete                                    ",
@@ -2451,7 +3406,8 @@ impl VideoExplorer {
             .ok()?;
 
         let stdout = String::from_utf8_lossy(&output.stdout);
-        stdout.trim().parse::<f64>().ok()
+        let duration = stdout.trim().parse::<f64>().ok()?;
+        Some(duration * crate::video::trimmed_share(&self.input_path))
     }
 
     fn calc_change_pct(&self, output_size: u64) -> f64 {
@@ -2539,16 +3495,19 @@ impl VideoExplorer {
         use std::io::Write;
         let _ = std::io::stderr().flush();
 
-        let filter = "[0:v]scale='iw-mod(iw,2)':'ih-mod(ih,2)':flags=bicubic[ref];\
-                      [ref][1:v]ssim;[ref][1:v]psnr";
+        let filter = format!(
+            "{};[ref][1:v]ssim;[ref][1:v]psnr",
+            crate::video::metric_reference_filter(&self.vf_args)
+        );
 
         let output = Command::new("ffmpeg")
+            .args(crate::video::source_trim_args(&self.input_path))
             .arg("-i")
             .arg(crate::safe_path_arg(self.input_path.as_path()).as_ref())
             .arg("-i")
             .arg(crate::safe_path_arg(self.output_path.as_path()).as_ref())
             .arg("-lavfi")
-            .arg(filter)
+            .arg(&filter)
             .arg("-f")
             .arg("null")
             .arg("-")
@@ -2622,11 +3581,31 @@ impl VideoExplorer {
         use std::io::Write;
         let _ = std::io::stderr().flush();
 
-        let filters = [
-            "[0:v]scale='iw-mod(iw,2)':'ih-mod(ih,2)':flags=bicubic[ref];[ref][1:v]ssim",
-            "[0:v]scale=trunc(iw/2)*2:trunc(ih/2)*2,format=yuv420p[ref];[1:v]scale=trunc(iw/2)*2:trunc(ih/2)*2,format=yuv420p[cmp];[ref][cmp]ssim",
-            "ssim",
-        ];
+        // --sample-ssim: gate on the mean of N sampled frames; the full comparison below is
+        // the fallback when sampling fails.
+        if let Some(frames) = self.config.settings.sample_ssim_frames {
+            let strategy = self.config.settings.ssim_sampling_strategy;
+            if let Some(ssim) = calculate_ssim_sampled(
+                &self.input_path,
+                &self.output_path,
+                &self.vf_args,
+                frames,
+                strategy,
+            ) {
+                crate::log_eprintln!(
+                    "\r      📊 SSIM: {:.6} (mean of {} {} sampled frames, estimate)          ",
+                    ssim,
+                    frames,
+                    strategy.as_str()
+                );
+                return Ok(Some(ssim));
+            }
+        }
+
+        let filters: Vec<String> = ssim_reference_filters(&self.vf_args)
+            .into_iter()
+            .map(|(_, filter)| filter)
+            .collect();
 
         for (idx, filter) in filters.iter().enumerate() {
             let result = self.try_ssim_with_filter(filter);
@@ -2670,6 +3649,7 @@ impl VideoExplorer {
 
     fn try_ssim_with_filter(&self, filter: &str) -> Result<Option<f64>> {
         let output = Command::new("ffmpeg")
+            .args(crate::video::source_trim_args(&self.input_path))
             .arg("-i")
             .arg(crate::safe_path_arg(self.input_path.as_path()).as_ref())
             .arg("-i")
@@ -2710,9 +3690,13 @@ impl VideoExplorer {
         use crate::universal_heartbeat::{HeartbeatConfig, HeartbeatGuard};
         let _heartbeat = HeartbeatGuard::new(HeartbeatConfig::fast("PSNR Calculation"));
 
-        let filter = "[0:v]scale='iw-mod(iw,2)':'ih-mod(ih,2)':flags=bicubic[ref];[ref][1:v]psnr=stats_file=-";
+        let filter = format!(
+            "{};[ref][1:v]psnr=stats_file=-",
+            crate::video::metric_reference_filter(&self.vf_args)
+        );
 
         let output = Command::new("ffmpeg")
+            .args(crate::video::source_trim_args(&self.input_path))
             .arg("-i")
             .arg(crate::safe_path_arg(self.input_path.as_path()).as_ref())
             .arg("-i")
@@ -2781,26 +3765,30 @@ impl VideoExplorer {
                 );
                 format!(
                     "[0:v]select='lt(t\\,{:.1})+between(t\\,{:.1}\\,{:.1})+gte(t\\,{:.1})',\
-                     scale='iw-mod(iw,2)':'ih-mod(ih,2)':flags=bicubic[ref];\
+                     {}[ref];\
                      [1:v]select='lt(t\\,{:.1})+between(t\\,{:.1}\\,{:.1})+gte(t\\,{:.1})'[dist];\
                      [ref][dist]libvmaf",
                     start_end,
                     mid_start,
                     mid_end,
                     tail_start,
+                    crate::video::metric_reference_scale(&self.vf_args),
                     start_end,
                     mid_start,
                     mid_end,
                     tail_start
                 )
             }
-            _ => "[0:v]scale='iw-mod(iw,2)':'ih-mod(ih,2)':flags=bicubic[ref];[ref][1:v]libvmaf"
-                .to_string(),
+            _ => format!(
+                "{};[ref][1:v]libvmaf",
+                crate::video::metric_reference_filter(&self.vf_args)
+            ),
         };
 
         let use_sampling = duration.map(|d| d > 60.0).unwrap_or(false);
 
         let output = Command::new("ffmpeg")
+            .args(crate::video::source_trim_args(&self.input_path))
             .arg("-i")
             .arg(crate::safe_path_arg(self.input_path.as_path()).as_ref())
             .arg("-i")
@@ -2891,6 +3879,26 @@ impl VideoExplorer {
     }
 }
 
+/// `--print-command`: the final-encode commands for `input` at `crf` on the CPU encoder, as
+/// [`VideoExplorer::encode_command_preview`] renders them.
+pub fn encode_command_preview(
+    input: &Path,
+    output: &Path,
+    encoder: VideoEncoder,
+    vf_args: Vec<String>,
+    crf: f32,
+    max_threads: usize,
+    settings: EncodeSettings,
+) -> Result<Vec<String>> {
+    let config = ExploreConfig {
+        settings,
+        ..Default::default()
+    };
+    let explorer =
+        VideoExplorer::new_with_gpu(input, output, encoder, vf_args, config, false, max_threads)?;
+    Ok(explorer.encode_command_preview(crf))
+}
+
 pub fn explore_size_only(
     input: &Path,
     output: &Path,
@@ -2971,6 +3979,39 @@ pub fn explore_compress_with_quality(
     VideoExplorer::new(input, output, encoder, vf_args, config, max_threads)?.explore()
 }
 
+/// `--explore-mode`: run exactly the search `mode` names, bypassing the flag-combination
+/// mapping. The CRF ceiling comes from `calculate_smart_thresholds`; `min_ssim` is the floor
+/// for the precise modes.
+pub fn explore_with_mode(
+    mode: ExploreMode,
+    input: &Path,
+    output: &Path,
+    encoder: VideoEncoder,
+    vf_args: Vec<String>,
+    initial_crf: f32,
+    min_ssim: f64,
+    max_threads: usize,
+    settings: EncodeSettings,
+) -> Result<ExploreResult> {
+    let (max_crf, _) = calculate_smart_thresholds(initial_crf, encoder);
+    let config = match mode {
+        ExploreMode::SizeOnly => ExploreConfig::size_only(initial_crf, max_crf),
+        ExploreMode::QualityMatch => ExploreConfig::quality_match(initial_crf),
+        ExploreMode::PreciseQualityMatch => {
+            ExploreConfig::precise_quality_match(initial_crf, max_crf, min_ssim)
+        }
+        ExploreMode::PreciseQualityMatchWithCompression => {
+            ExploreConfig::precise_quality_match_with_compression(initial_crf, max_crf, min_ssim)
+        }
+        ExploreMode::CompressOnly => ExploreConfig::compress_only(initial_crf, max_crf),
+        ExploreMode::CompressWithQuality => {
+            ExploreConfig::compress_with_quality(initial_crf, max_crf)
+        }
+    };
+    let config = ExploreConfig { settings, ..config };
+    VideoExplorer::new(input, output, encoder, vf_args, config, max_threads)?.explore()
+}
+
 pub fn explore_precise_quality_match_with_compression_gpu(
     input: &Path,
     output: &Path,
@@ -3111,12 +4152,70 @@ pub fn explore_quality_match_gpu(
     .explore()
 }
 
-pub fn calculate_smart_thresholds(initial_crf: f32, encoder: VideoEncoder) -> (f32, f64) {
-    let (crf_scale, max_crf_cap) = match encoder {
-        VideoEncoder::Hevc => (51.0_f32, 40.0_f32),
-        VideoEncoder::Av1 => (63.0_f32, 50.0_f32),
-        VideoEncoder::H264 => (51.0_f32, 35.0_f32),
+fn encoder_crf_scale_and_cap(encoder: VideoEncoder) -> (f32, f32) {
+    match encoder {
+        VideoEncoder::Hevc => (51.0, 40.0),
+        VideoEncoder::Av1 => (63.0, 50.0),
+        VideoEncoder::H264 => (51.0, 35.0),
+    }
+}
+
+/// Highest CRF the search may reach for `content`. Screen captures show blocking on text and
+/// flat UI early, so they stop well below the generic cap; live action hides artifacts in
+/// motion and texture and may go above it. Offsets are in HEVC steps, scaled to the encoder.
+pub fn content_crf_cap(content: ContentType, encoder: VideoEncoder) -> f32 {
+    let (crf_scale, max_crf_cap) = encoder_crf_scale_and_cap(encoder);
+    let hevc_offset = match content {
+        ContentType::ScreenRecording => -10.0,
+        ContentType::Animation => -4.0,
+        ContentType::Gaming => -2.0,
+        ContentType::FilmGrain | ContentType::Unknown => 0.0,
+        ContentType::LiveAction => 2.0,
     };
+    max_crf_cap + hevc_offset * crf_scale / 51.0
+}
+
+thread_local! {
+    static SEARCH_CONTENT: std::cell::Cell<ContentType> =
+        const { std::cell::Cell::new(ContentType::Unknown) };
+}
+
+/// Restores the previous [`search_content_type`] on drop.
+pub struct SearchContentGuard {
+    previous: ContentType,
+}
+
+impl Drop for SearchContentGuard {
+    fn drop(&mut self) {
+        SEARCH_CONTENT.with(|c| c.set(self.previous));
+    }
+}
+
+/// CRF searches on this thread use the [`content_crf_cap`] of `content` until the returned
+/// guard is dropped. Installed per file from detection or `--content-type`.
+pub fn search_content_type(content: ContentType) -> SearchContentGuard {
+    let previous = SEARCH_CONTENT.with(|c| c.replace(content));
+    SearchContentGuard { previous }
+}
+
+pub fn current_search_content_type() -> ContentType {
+    SEARCH_CONTENT.with(|c| c.get())
+}
+
+/// [`calculate_content_thresholds`] for the content type installed on this thread.
+pub fn calculate_smart_thresholds(initial_crf: f32, encoder: VideoEncoder) -> (f32, f64) {
+    calculate_content_thresholds(initial_crf, encoder, current_search_content_type())
+}
+
+/// `(max_crf, min_ssim)` for a search anchored at `initial_crf`; the CRF ceiling is also
+/// bounded by [`content_crf_cap`].
+pub fn calculate_content_thresholds(
+    initial_crf: f32,
+    encoder: VideoEncoder,
+    content: ContentType,
+) -> (f32, f64) {
+    let (crf_scale, _) = encoder_crf_scale_and_cap(encoder);
+    let max_crf_cap = content_crf_cap(content, encoder);
 
     let normalized_crf = initial_crf / crf_scale;
     let quality_level = (normalized_crf * normalized_crf).clamp(0.0, 1.0) as f64;
@@ -3348,6 +4447,252 @@ mod tests {
     use super::precision::*;
     use super::*;
 
+    #[test]
+    fn test_encoder_preset_parse() {
+        for preset in EncoderPreset::ALL {
+            assert_eq!(EncoderPreset::parse(preset.x26x_name()), Ok(preset));
+        }
+        assert_eq!(EncoderPreset::parse(" Slow "), Ok(EncoderPreset::Slow));
+        // SVT-AV1 numbers map to the nearest name; ties go slower.
+        assert_eq!(EncoderPreset::parse("6"), Ok(EncoderPreset::Medium));
+        assert_eq!(EncoderPreset::parse("5"), Ok(EncoderPreset::Slow));
+        assert_eq!(EncoderPreset::parse("13"), Ok(EncoderPreset::Ultrafast));
+        assert!(EncoderPreset::parse("14").is_err());
+        assert!(EncoderPreset::parse("turbo").is_err());
+
+        assert!(EncoderPreset::Veryslow.is_slower_than_default());
+        assert!(!EncoderPreset::Medium.is_slower_than_default());
+        assert!(!EncoderPreset::Fast.is_slower_than_default());
+    }
+
+    #[test]
+    fn test_av1_backend_parse() {
+        for backend in Av1Backend::ALL {
+            assert_eq!(Av1Backend::parse(backend.as_str()), Ok(backend));
+            assert_eq!(Av1Backend::parse(backend.ffmpeg_name()), Ok(backend));
+        }
+        assert_eq!(Av1Backend::parse("SVT_AV1"), Ok(Av1Backend::Svt));
+        assert!(Av1Backend::parse("rav1e").is_err());
+
+        let args = libaom_args(true, EncoderPreset::Medium, 8);
+        assert_eq!(args[..2], ["-b:v", "0"]);
+        assert!(args.ends_with(&["-aom-params".to_string(), "lossless=1".to_string()]));
+        assert!(EncoderPreset::Placebo.aom_cpu_used() < EncoderPreset::Ultrafast.aom_cpu_used());
+    }
+
+    #[test]
+    fn test_sampled_frame_select() {
+        // 100s in 4 samples: first frame at or after 12.5s, then one every 25s.
+        assert_eq!(
+            sampled_frame_select(100.0, 4),
+            "select='gte(t\\,12.500)*(isnan(prev_selected_t)+gte(t-prev_selected_t\\,25.000))'"
+        );
+        assert!(sampled_frame_select(10.0, 0).contains("gte(t\\,5.000)"));
+    }
+
+    #[test]
+    fn test_ssim_sampling_strategy_placement() {
+        for strategy in SsimSamplingStrategy::ALL {
+            assert_eq!(SsimSamplingStrategy::parse(strategy.as_str()), Ok(strategy));
+        }
+        assert_eq!(
+            SsimSamplingStrategy::parse("Scene_Change"),
+            Ok(SsimSamplingStrategy::SceneChange)
+        );
+        assert!(SsimSamplingStrategy::parse("random").is_err());
+
+        let log = "[Parsed_metadata_2 @ 0x1] frame:0    pts:0       pts_time:0\n\
+                   [Parsed_metadata_2 @ 0x1] lavfi.scene_score=0.010000\n\
+                   [Parsed_metadata_2 @ 0x1] frame:1    pts:1001    pts_time:1.5\n\
+                   [Parsed_metadata_2 @ 0x1] lavfi.scene_score=0.900000\n";
+        assert_eq!(
+            parse_metadata_scores(log, "lavfi.scene_score"),
+            vec![(0.0, 0.01), (1.5, 0.9)]
+        );
+
+        // Highest scores first, but the runner-up next to the cut at 10s is skipped for 30s.
+        let scores = [(10.0, 0.9), (10.04, 0.8), (30.0, 0.5), (50.0, 0.1)];
+        assert_eq!(peak_sample_times(&scores, 2, 5.0), vec![10.0, 30.0]);
+        assert_eq!(
+            spread_sample_times(&[0.0, 2.0, 4.0, 6.0, 8.0, 10.0, 12.0, 14.0], 2),
+            vec![4.0, 12.0]
+        );
+        assert_eq!(spread_sample_times(&[0.0, 2.0], 5), vec![0.0, 2.0]);
+        assert_eq!(
+            timestamp_frame_select(&[1.5, 30.0]),
+            "select='lt(abs(t-1.5000)\\,0.004)+lt(abs(t-30.0000)\\,0.004)'"
+        );
+    }
+
+    #[test]
+    fn test_explore_mode_parse() {
+        for mode in ExploreMode::ALL {
+            assert_eq!(ExploreMode::parse(mode.as_str()), Ok(mode));
+        }
+        assert_eq!(ExploreMode::parse("Size_Only"), Ok(ExploreMode::SizeOnly));
+        assert!(ExploreMode::parse("fastest").is_err());
+    }
+
+    #[test]
+    fn test_deterministic_encode_is_byte_identical() {
+        use sha2::{Digest, Sha256};
+
+        // The CPU HEVC path pipes ffmpeg into the x265 CLI; nothing to check without both.
+        let has_tools = ["ffmpeg", "x265"]
+            .iter()
+            .all(|tool| Command::new(tool).arg("-version").output().is_ok());
+        if !has_tools {
+            return;
+        }
+
+        let dir = tempfile::tempdir().unwrap();
+        let input = dir.path().join("in.mkv");
+        let status = Command::new("ffmpeg")
+            .args(["-v", "error", "-y", "-f", "lavfi"])
+            .args(["-i", "testsrc2=size=320x180:rate=25:duration=2"])
+            .args(["-c:v", "ffv1"])
+            .arg(&input)
+            .status()
+            .unwrap();
+        assert!(status.success());
+
+        let encode = |name: &str| {
+            let output = dir.path().join(name);
+            let explorer = VideoExplorer::new_with_gpu(
+                &input,
+                &output,
+                VideoEncoder::Hevc,
+                Vec::new(),
+                ExploreConfig {
+                    settings: EncodeSettings {
+                        deterministic: true,
+                        ..EncodeSettings::default()
+                    },
+                    ..ExploreConfig::default()
+                },
+                false,
+                4,
+            )
+            .unwrap();
+            explorer.encode(28.0).unwrap();
+            Sha256::digest(std::fs::read(&output).unwrap())
+        };
+        assert_eq!(encode("first.mp4"), encode("second.mp4"));
+    }
+
+    #[test]
+    fn test_grayscale_encode_is_scoped_to_guard() {
+        assert_eq!(gray_pix_fmt("yuv420p"), "gray");
+        assert_eq!(gray_pix_fmt("yuv420p10le"), "gray10le");
+        {
+            let _guard = encode_grayscale(true);
+            assert!(hevc_pix_fmt(8, None).starts_with("gray"));
+            assert!(hevc_profile_args(hevc_pix_fmt(8, None)).is_empty());
+            // Other threads keep encoding in color.
+            assert!(!std::thread::spawn(grayscale_encode_enabled).join().unwrap());
+        }
+        assert!(!grayscale_encode_enabled());
+    }
+
+    #[test]
+    fn test_ssim_all_filters_follow_downscale() {
+        let unscaled = ssim_all_filters(&[]);
+        assert_eq!(unscaled[0], "[0:v][1:v]ssim");
+        assert!(unscaled[1].contains("iw-mod(iw,2)"));
+
+        let scaled = ssim_all_filters(&[
+            "-vf".to_string(),
+            "scale=1920:1080:flags=lanczos,format=yuv420p".to_string(),
+        ]);
+        assert_eq!(scaled.len(), 2);
+        assert!(scaled
+            .iter()
+            .all(|f| f.contains("scale=1920:1080:flags=lanczos[ref]")));
+    }
+
+    #[test]
+    fn test_ssim_gate_filters_replay_denoise() {
+        let denoised = crate::with_denoise_filter(
+            vec!["-vf".to_string(), "format=yuv420p".to_string()],
+            Some(crate::DenoiseLevel::Medium),
+        );
+        let single = ssim_reference_filters(&denoised);
+        assert!(single.iter().all(|(name, _)| *name != "simple"));
+        assert!(single
+            .iter()
+            .all(|(_, filter)| filter.starts_with("[0:v]hqdn3d=4:3:6:4.5,")));
+        assert!(ssim_all_filters(&denoised)
+            .iter()
+            .all(|filter| filter.contains("hqdn3d=4:3:6:4.5")));
+    }
+
+    #[test]
+    fn test_frame_rate_mode_stays_out_of_vf_args() {
+        let dir = tempfile::tempdir().unwrap();
+        let input = dir.path().join("in.mp4");
+        std::fs::write(&input, b"not a video").unwrap();
+        let explorer = VideoExplorer::new_with_gpu(
+            &input,
+            &dir.path().join("out.mp4"),
+            VideoEncoder::Hevc,
+            vec![
+                "-vf".to_string(),
+                "scale=1280:720:flags=lanczos".to_string(),
+            ],
+            ExploreConfig::default(),
+            false,
+            1,
+        )
+        .unwrap();
+        let _guard = encode_frame_rate_mode(crate::frame_rate_mode_args(true, true));
+        let preview = explorer.encode_command_preview(23.0).join(" ");
+        assert!(preview.contains("-vsync cfr"));
+        assert_eq!(
+            crate::metric_reference_scale(&explorer.vf_args),
+            "scale=1280:720:flags=lanczos"
+        );
+        drop(_guard);
+        assert!(frame_rate_mode_encode_args().is_empty());
+    }
+
+    #[test]
+    fn test_hevc_bit_depth_preserved_or_forced() {
+        assert_eq!(hevc_pix_fmt_for(8, false), "yuv420p");
+        assert_eq!(hevc_pix_fmt_for(10, false), "yuv420p10le");
+        assert_eq!(hevc_pix_fmt_for(12, false), "yuv420p10le");
+        assert_eq!(hevc_pix_fmt_for(8, true), "yuv420p10le");
+
+        let has_x265 = Command::new("ffmpeg")
+            .args(["-hide_banner", "-encoders"])
+            .output()
+            .map(|out| String::from_utf8_lossy(&out.stdout).contains("libx265"))
+            .unwrap_or(false);
+        if !has_x265 {
+            return;
+        }
+
+        // 8-bit source: kept 8-bit by default, main10 when forced.
+        let dir = tempfile::tempdir().unwrap();
+        for (force, expected_depth, expected_profile) in [(false, 8, "Main"), (true, 10, "Main 10")]
+        {
+            let output = dir.path().join(format!("force_{}.mp4", force));
+            let pix_fmt = hevc_pix_fmt_for(8, force);
+            let status = Command::new("ffmpeg")
+                .args(["-v", "error", "-y", "-f", "lavfi"])
+                .args(["-i", "testsrc2=size=320x180:rate=25:duration=1"])
+                .args(["-c:v", "libx265", "-crf", "28"])
+                .args(["-pix_fmt", pix_fmt])
+                .args(hevc_profile_args(pix_fmt))
+                .arg(&output)
+                .status()
+                .unwrap();
+            assert!(status.success());
+            let probe = crate::ffprobe::probe_video(&output).unwrap();
+            assert_eq!(probe.bit_depth, expected_depth);
+            assert_eq!(probe.profile.as_deref(), Some(expected_profile));
+        }
+    }
+
     #[test]
     #[ignore]
     fn test_precision_crf_search_range_hevc() {
@@ -3832,6 +5177,53 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_screen_content_lands_below_film() {
+        // Same source, same SSIM curve: the highest CRF that still meets the target, searched
+        // up to each content type's ceiling.
+        let ssim_at = |crf: f32| 1.0 - 0.002 * (crf as f64 - 10.0);
+        let land = |content: ContentType| {
+            let (max_crf, min_ssim) =
+                calculate_content_thresholds(28.0, VideoEncoder::Hevc, content);
+            (0..=max_crf as u32)
+                .map(|c| c as f32)
+                .filter(|&c| ssim_at(c) >= min_ssim)
+                .fold(ABSOLUTE_MIN_CRF, f32::max)
+        };
+        let screen = land(ContentType::ScreenRecording);
+        let film = land(ContentType::LiveAction);
+        assert!(
+            screen < film,
+            "screen {} should land below film {}",
+            screen,
+            film
+        );
+        assert_eq!(screen, 30.0);
+
+        for encoder in [VideoEncoder::Hevc, VideoEncoder::Av1, VideoEncoder::H264] {
+            let caps: Vec<f32> = [
+                ContentType::ScreenRecording,
+                ContentType::Animation,
+                ContentType::Gaming,
+                ContentType::Unknown,
+                ContentType::LiveAction,
+            ]
+            .iter()
+            .map(|&c| content_crf_cap(c, encoder))
+            .collect();
+            assert!(caps.windows(2).all(|w| w[0] < w[1]), "{:?}", caps);
+        }
+        assert_eq!(
+            calculate_smart_thresholds(28.0, VideoEncoder::Av1),
+            calculate_content_thresholds(28.0, VideoEncoder::Av1, ContentType::Unknown)
+        );
+        {
+            let _guard = search_content_type(ContentType::ScreenRecording);
+            assert_eq!(calculate_smart_thresholds(28.0, VideoEncoder::Hevc).0, 30.0);
+        }
+        assert_eq!(current_search_content_type(), ContentType::Unknown);
+    }
+
     #[test]
     #[ignore]
     fn test_smart_thresholds_continuity() {
@@ -4473,6 +5865,55 @@ mod tests {
             LONG_VIDEO_REQUIRED_ZERO_GAINS
         );
     }
+
+    #[test]
+    fn test_gpu_handoff_counts_saved_cpu_iterations() {
+        assert_eq!(estimated_crf_search_iterations(0.0, 51.0), 9);
+        assert_eq!(estimated_crf_search_iterations(20.0, 26.0), 6);
+        assert_eq!(estimated_crf_search_iterations(23.0, 23.0), 0);
+
+        let gpu = crate::gpu_accel::GpuCoarseResult {
+            gpu_boundary_crf: 21.0,
+            gpu_best_size: Some(1_000),
+            gpu_best_ssim: Some(0.97),
+            gpu_ssim_estimated: true,
+            gpu_best_psnr: Some(41.5),
+            gpu_type: crate::gpu_accel::GpuType::Apple,
+            codec: "hevc".to_string(),
+            iterations: 7,
+            found_boundary: true,
+            fine_tuned: true,
+            log: Vec::new(),
+            sample_input_size: 2_000,
+            quality_ceiling_crf: None,
+            quality_ceiling_ssim: None,
+        };
+        let handoff = GpuHandoff::new(&gpu, (20.0, 26.0, 23.0), (0.0, 51.0));
+        assert_eq!(handoff.gpu_crf, 21.0);
+        assert_eq!(handoff.gpu_psnr, Some(41.5));
+        assert_eq!(handoff.cpu_iterations_saved, 3);
+        assert_eq!(handoff.final_cpu_crf, None);
+        let json = serde_json::to_string(&handoff).unwrap();
+        assert!(json.contains("\"cpu_min_crf\":20.0"));
+        assert!(json.contains("\"gpu_ssim_estimated\":true"));
+    }
+
+    #[test]
+    fn test_transparency_report_records_search_outcome() {
+        let result = ExploreResult {
+            optimal_crf: 24.5,
+            ssim: Some(0.981),
+            budget_cap_hit: Some(BudgetCap::Iterations(40)),
+            cpu_fallback_used: true,
+            ..ExploreResult::default()
+        };
+        let mut report = TransparencyReport::new(1_000);
+        report.record_result(&result);
+        assert_eq!(report.final_crf, Some(24.5));
+        assert_eq!(report.final_ssim, Some(0.981));
+        assert_eq!(report.budget_cap_hit, Some(BudgetCap::Iterations(40)));
+        assert!(report.cpu_fallback_used);
+    }
 }
 
 #[cfg(test)]
@@ -125,6 +125,7 @@ pub fn quick_calibrate(
     gpu_encoder: &str,
     sample_duration: f32,
     ultimate_mode: bool,
+    preset: super::EncoderPreset,
 ) -> Result<DynamicCrfMapper> {
     use std::fs;
     use std::process::Command;
@@ -155,11 +156,11 @@ pub fn quick_calibrate(
 
         let temp_gpu_file = tempfile::Builder::new()
             .suffix(".mp4")
-            .tempfile()
+            .tempfile_in(crate::conversion::scratch_dir())
             .context("Failed to create temp file")?;
         let temp_cpu_file = tempfile::Builder::new()
             .suffix(".mp4")
-            .tempfile()
+            .tempfile_in(crate::conversion::scratch_dir())
             .context("Failed to create temp file")?;
         let temp_gpu = temp_gpu_file.path().to_path_buf();
         let temp_cpu = temp_cpu_file.path().to_path_buf();
@@ -168,6 +169,7 @@ pub fn quick_calibrate(
             .arg("-y")
             .arg("-t")
             .arg(format!("{}", sample_duration.min(cpu_sample_cap)))
+            .args(crate::video::source_trim_args(input))
             .arg("-i")
             .arg(crate::safe_path_arg(input).as_ref())
             .arg("-c:v")
@@ -212,6 +214,7 @@ pub fn quick_calibrate(
                 .arg("-y")
                 .arg("-t")
                 .arg(format!("{}", sample_duration.min(cpu_sample_cap)))
+                .args(crate::video::source_trim_args(input))
                 .arg("-i")
                 .arg(crate::safe_path_arg(input).as_ref())
                 .arg("-an")
@@ -221,7 +224,8 @@ pub fn quick_calibrate(
                 .arg("libx265")
                 .arg("-crf")
                 .arg(format!("{:.0}", anchor_crf));
-            for arg in encoder.extra_args(max_threads) {
+            // Calibration only runs on the GPU path, which `--deterministic` never takes.
+            for arg in encoder.extra_args_with_preset(max_threads, preset, false) {
                 cpu_cmd.arg(arg);
             }
             cpu_cmd.arg(crate::safe_path_arg(temp_cpu.as_path()).as_ref());
@@ -262,7 +266,7 @@ pub fn quick_calibrate(
 
             let config = X265Config {
                 crf: *anchor_crf,
-                preset: "medium".to_string(),
+                preset: preset.x26x_name().to_string(),
                 threads: max_threads,
                 container: "mp4".to_string(),
                 preserve_audio: true,
@@ -271,13 +275,14 @@ pub fn quick_calibrate(
 
             let temp_input_file = tempfile::Builder::new()
                 .suffix(".y4m")
-                .tempfile()
+                .tempfile_in(crate::conversion::scratch_dir())
                 .context("Failed to create temp file")?;
             let temp_input = temp_input_file.path().to_path_buf();
             let extract_result = Command::new("ffmpeg")
                 .arg("-y")
                 .arg("-t")
                 .arg(format!("{}", sample_duration.min(cpu_sample_cap)))
+                .args(crate::video::source_trim_args(input))
                 .arg("-i")
                 .arg(crate::safe_path_arg(input).as_ref())
                 .arg("-an")
@@ -352,6 +357,7 @@ pub fn quick_calibrate(
                 .arg("-y")
                 .arg("-t")
                 .arg(format!("{}", sample_duration.min(cpu_sample_cap)))
+                .args(crate::video::source_trim_args(input))
                 .arg("-i")
                 .arg(crate::safe_path_arg(input).as_ref())
                 .arg("-c:v")
@@ -359,7 +365,8 @@ pub fn quick_calibrate(
                 .arg("-crf")
                 .arg(format!("{:.0}", anchor_crf));
 
-            for arg in encoder.extra_args(max_threads) {
+            // Calibration only runs on the GPU path, which `--deterministic` never takes.
+            for arg in encoder.extra_args_with_preset(max_threads, preset, false) {
                 cpu_cmd.arg(arg);
             }
 
@@ -53,12 +53,24 @@ fn build_color_args_from_probe(probe: &crate::ffprobe::FFprobeResult) -> Vec<Str
             args.push(cll.clone());
         }
     }
+    args.extend(crate::color_range_args(probe.color_range.as_deref()));
     args
 }
 
 /// Return the correct pixel format for encoding: yuv420p10le for 10-bit HDR content,
 /// yuv420p for 8-bit SDR. Preserving the bit depth is essential for HDR accuracy.
-fn pick_pix_fmt(probe: &crate::ffprobe::FFprobeResult) -> &'static str {
+/// HEVC additionally honours `--force-10bit`; `--pix-fmt` (`pix_fmt_override`) overrides either.
+fn pick_pix_fmt(
+    probe: &crate::ffprobe::FFprobeResult,
+    encoder: VideoEncoder,
+    pix_fmt_override: Option<PixelFormat>,
+) -> &'static str {
+    if encoder == VideoEncoder::Hevc {
+        return hevc_pix_fmt(probe.bit_depth, pix_fmt_override);
+    }
+    if let Some(pix_fmt) = pix_fmt_override {
+        return pix_fmt.as_str();
+    }
     if probe.bit_depth >= 10 {
         "yuv420p10le"
     } else {
@@ -114,21 +126,54 @@ pub fn explore_with_gpu_coarse_search(
     force_ms_ssim_long: bool,
     allow_size_tolerance: bool,
     max_threads: usize,
+    settings: EncodeSettings,
+) -> Result<ExploreResult> {
+    let result = gpu_coarse_then_cpu_fine(
+        input,
+        output,
+        encoder,
+        vf_args.clone(),
+        initial_crf,
+        max_crf,
+        min_ssim,
+        ultimate_mode,
+        force_ms_ssim_long,
+        allow_size_tolerance,
+        max_threads,
+        settings,
+    )?;
+    enforce_max_output_size(input, output, encoder, vf_args, result, max_threads, settings)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn gpu_coarse_then_cpu_fine(
+    input: &Path,
+    output: &Path,
+    encoder: VideoEncoder,
+    vf_args: Vec<String>,
+    initial_crf: f32,
+    max_crf: f32,
+    min_ssim: f64,
+    ultimate_mode: bool,
+    force_ms_ssim_long: bool,
+    allow_size_tolerance: bool,
+    max_threads: usize,
+    settings: EncodeSettings,
 ) -> Result<ExploreResult> {
     use crate::gpu_accel::{CrfMapping, GpuAccel, GpuCoarseConfig};
 
+    let probe_phase = crate::phase_profile::phase(crate::ProfilePhase::Probe);
     let precheck_info = precheck::run_precheck(input)?;
     let _compressibility = precheck_info.compressibility;
     crate::log_eprintln!();
 
-    let input_size = fs::metadata(input)
-        .context("Failed to read input file metadata")?
-        .len();
+    let input_size =
+        crate::video::trimmed_source_size(input).context("Failed to read input file metadata")?;
 
     let mut best_vmaf_tracked: Option<f64> = None;
     let mut best_psnr_uv_tracked: Option<(f64, f64)> = None;
 
-    let gpu = GpuAccel::detect();
+    let gpu = GpuAccel::detect_for(settings.deterministic);
     gpu.print_detection_info();
     let encoder_name = match encoder {
         VideoEncoder::Hevc => "hevc",
@@ -170,6 +215,7 @@ pub fn explore_with_gpu_coarse_search(
         .as_ref()
         .map(|p| p.duration as f32)
         .unwrap_or(crate::gpu_accel::GPU_SAMPLE_DURATION);
+    drop(probe_phase);
 
     // [New Logic] Bitrate-based GPU Start Condition
     // Low bitrate videos (animation/PPT < 5Mbps) don't benefit from GPU pre-scan
@@ -181,10 +227,12 @@ pub fn explore_with_gpu_coarse_search(
     let is_high_complexity = bitrate_bps > 5_000_000.0; // > 5 Mbps
 
     let mut gpu_executed = false;
+    let mut gpu_handoff: Option<GpuHandoff> = None;
     let (cpu_min_crf, cpu_max_crf, cpu_center_crf) = if gpu.is_available()
         && has_gpu_encoder
         && is_high_complexity
     {
+        let _phase = crate::phase_profile::phase(crate::ProfilePhase::GpuCoarse);
         gpu_executed = true;
         crate::verbose_eprintln!();
         crate::verbose_eprintln!("Phase 1: GPU Coarse Search");
@@ -275,6 +323,7 @@ pub fn explore_with_gpu_coarse_search(
                         gpu_encoder_name,
                         sample_dur,
                         ultimate_mode,
+                        settings.preset,
                     )
                     .unwrap_or_else(|_| dynamic_mapping::DynamicCrfMapper::new(input_size));
 
@@ -333,7 +382,18 @@ pub fn explore_with_gpu_coarse_search(
                     );
                     crate::verbose_eprintln!();
 
-                    let cpu_start = dynamic_cpu_crf;
+                    let prediction = crate::video_explorer::crf_prediction();
+                    let cpu_start =
+                        crate::gpu_accel::blend_with_prediction(dynamic_cpu_crf, prediction);
+                    if let Some((predicted_crf, confidence)) = prediction {
+                        crate::verbose_eprintln!(
+                            "Matched CRF {:.1} (confidence {:.0}%) → CPU start {:.1}",
+                            predicted_crf,
+                            confidence * 100.0,
+                            cpu_start
+                        );
+                    }
+                    let narrow_margin = crate::gpu_accel::confidence_search_margin(prediction);
 
                     crate::verbose_eprintln!(
                         "   ✅ GPU found boundary: CRF {:.1} (fine-tuned: {})",
@@ -344,14 +404,20 @@ pub fn explore_with_gpu_coarse_search(
                         crate::verbose_eprintln!("   GPU best size: {} bytes", size);
                     }
 
+                    let ssim_estimate_note = if gpu_result.gpu_ssim_estimated {
+                        " (estimated from PSNR)"
+                    } else {
+                        ""
+                    };
                     if let (Some(ceiling_crf), Some(ceiling_ssim)) = (
                         gpu_result.quality_ceiling_crf,
                         gpu_result.quality_ceiling_ssim,
                     ) {
                         crate::verbose_eprintln!(
-                            "   GPU Quality Ceiling: CRF {:.1}, SSIM {:.4}",
+                            "   GPU Quality Ceiling: CRF {:.1}, SSIM {:.4}{}",
                             ceiling_crf,
-                            ceiling_ssim
+                            ceiling_ssim,
+                            ssim_estimate_note
                         );
                         crate::verbose_eprintln!(
                             "      (GPU SSIM ceiling, CPU can break through to 0.99+)"
@@ -366,7 +432,12 @@ pub fn explore_with_gpu_coarse_search(
                         } else {
                             "Below expected"
                         };
-                        crate::verbose_eprintln!("   GPU best SSIM: {:.6} {}", ssim, quality_hint);
+                        crate::verbose_eprintln!(
+                            "   GPU best SSIM: {:.6}{} {}",
+                            ssim,
+                            ssim_estimate_note,
+                            quality_hint
+                        );
 
                         if ssim < 0.90 {
                             crate::verbose_eprintln!(
@@ -375,11 +446,12 @@ pub fn explore_with_gpu_coarse_search(
                             (ABSOLUTE_MIN_CRF, (cpu_start + 8.0).min(max_crf))
                         } else if gpu_result.fine_tuned {
                             crate::verbose_eprintln!(
-                                "   GPU fine-tuned → CPU narrow search ±3 CRF"
+                                "   GPU fine-tuned → CPU narrow search ±{:.1} CRF",
+                                narrow_margin
                             );
                             (
-                                (cpu_start - 3.0).max(ABSOLUTE_MIN_CRF),
-                                (cpu_start + 3.0).min(max_crf),
+                                (cpu_start - narrow_margin).max(ABSOLUTE_MIN_CRF),
+                                (cpu_start + narrow_margin).min(max_crf),
                             )
                         } else {
                             crate::verbose_eprintln!(
@@ -391,10 +463,13 @@ pub fn explore_with_gpu_coarse_search(
                             )
                         }
                     } else if gpu_result.fine_tuned {
-                        crate::verbose_eprintln!("   GPU fine-tuned → CPU narrow search ±3 CRF");
+                        crate::verbose_eprintln!(
+                            "   GPU fine-tuned → CPU narrow search ±{:.1} CRF",
+                            narrow_margin
+                        );
                         (
-                            (cpu_start - 3.0).max(ABSOLUTE_MIN_CRF),
-                            (cpu_start + 3.0).min(max_crf),
+                            (cpu_start - narrow_margin).max(ABSOLUTE_MIN_CRF),
+                            (cpu_start + narrow_margin).min(max_crf),
                         )
                     } else {
                         (
@@ -409,6 +484,11 @@ pub fn explore_with_gpu_coarse_search(
                         cpu_max,
                         cpu_start
                     );
+                    gpu_handoff = Some(GpuHandoff::new(
+                        &gpu_result,
+                        (cpu_min, cpu_max, cpu_start),
+                        (ABSOLUTE_MIN_CRF, max_crf),
+                    ));
                     (cpu_min, cpu_max, cpu_start)
                 } else {
                     crate::verbose_eprintln!(
@@ -462,11 +542,12 @@ pub fn explore_with_gpu_coarse_search(
         crate::verbose_eprintln!("      Search will start from boundary instead of optimal point");
     }
 
+    let search_phase = crate::phase_profile::phase(crate::ProfilePhase::CpuSearch);
     let mut result = cpu_fine_tune_from_gpu_boundary(
         input,
         output,
         encoder,
-        vf_args,
+        vf_args.clone(),
         clamped_cpu_center_crf,
         cpu_min_crf,
         cpu_max_crf,
@@ -479,10 +560,64 @@ pub fn explore_with_gpu_coarse_search(
         &mut best_vmaf_tracked,
         &mut best_psnr_uv_tracked,
         gpu_executed,
+        settings,
     )?;
+    drop(search_phase);
+
+    // The GPU boundary narrowed the CPU range; when the result still misses `min_ssim`,
+    // search the full range on CPU before giving up (same rule as `VideoExplorer`).
+    let missed_target = result.ssim.is_some() && !result.ssim_meets(min_ssim);
+    if gpu_handoff.is_some() && missed_target && gpu_cpu_fallback_enabled() {
+        crate::log_eprintln!(
+            "   🔁 GPU-guided best SSIM {:.4} < {:.4} at CRF {:.1}: retrying on CPU over [{:.1}, {:.1}]",
+            result.ssim.unwrap_or(0.0),
+            min_ssim,
+            result.optimal_crf,
+            ABSOLUTE_MIN_CRF,
+            max_crf
+        );
+        let _phase = crate::phase_profile::phase(crate::ProfilePhase::CpuSearch);
+        let gpu_guided_iterations = result.iterations;
+        result = cpu_fine_tune_from_gpu_boundary(
+            input,
+            output,
+            encoder,
+            vf_args.clone(),
+            initial_crf.clamp(ABSOLUTE_MIN_CRF, max_crf),
+            ABSOLUTE_MIN_CRF,
+            max_crf,
+            min_ssim,
+            ultimate_mode,
+            allow_size_tolerance,
+            max_threads,
+            duration,
+            probe_result.as_ref(),
+            &mut best_vmaf_tracked,
+            &mut best_psnr_uv_tracked,
+            false,
+            settings,
+        )?;
+        result.iterations += gpu_guided_iterations;
+        result.cpu_fallback_used = true;
+        if !result.quality_passed {
+            crate::log_eprintln!(
+                "   ⚠️  CPU fallback also missed the SSIM target ({})",
+                result
+                    .ssim
+                    .map(|s| format!("{:.4}", s))
+                    .unwrap_or_else(|| "N/A".to_string())
+            );
+        }
+    }
 
     result.log.clear();
 
+    if let Some(mut handoff) = gpu_handoff {
+        handoff.final_cpu_crf = Some(result.optimal_crf);
+        handoff.print();
+        result.gpu_handoff = Some(handoff);
+    }
+
     // Skip quality verification if early insight triggered
     if result.early_insight_triggered {
         crate::log_eprintln!();
@@ -533,6 +668,7 @@ pub fn explore_with_gpu_coarse_search(
 
     crate::verbose_eprintln!();
     crate::verbose_eprintln!("Phase 3: Quality Verification");
+    let ssim_phase = crate::phase_profile::phase(crate::ProfilePhase::SsimValidation);
 
     let mut quality_verification_skipped_for_format = false;
 
@@ -564,7 +700,7 @@ pub fn explore_with_gpu_coarse_search(
                 "   GIF input: using SSIM-All verification (ffmpeg ssim filter, GIF-compatible)"
             );
 
-            if let Some((y, u, v, all)) = calculate_ssim_all(input, output) {
+            if let Some((y, u, v, all)) = calculate_ssim_all(input, output, &vf_args) {
                 crate::log_eprintln!("   SSIM Y/U/V/All: {:.4}/{:.4}/{:.4}/{:.4}", y, u, v, all);
                 let gif_threshold = result.actual_min_ssim.max(0.92);
                 if all < gif_threshold {
@@ -746,7 +882,7 @@ pub fn explore_with_gpu_coarse_search(
 
                 let max_duration_min = ms_ssim_duration_threshold_secs / 60.0;
                 let ms_ssim_yuv_result = calculate_ms_ssim_yuv(input, output, max_duration_min);
-                let ssim_all_result = calculate_ssim_all(input, output);
+                let ssim_all_result = calculate_ssim_all(input, output, &vf_args);
 
                 crate::log_eprintln!("   ═══════════════════════════════════════════════════");
                 crate::log_eprintln!("   Quality Metrics:");
@@ -892,7 +1028,7 @@ pub fn explore_with_gpu_coarse_search(
             );
             crate::log_eprintln!("   Using SSIM-All verification only.");
 
-            if let Some((y, u, v, all)) = calculate_ssim_all(input, output) {
+            if let Some((y, u, v, all)) = calculate_ssim_all(input, output, &vf_args) {
                 crate::log_eprintln!("   SSIM Y/U/V/All: {:.4}/{:.4}/{:.4}/{:.4}", y, u, v, all);
 
                 let long_threshold = result.actual_min_ssim.max(0.92);
@@ -928,7 +1064,7 @@ pub fn explore_with_gpu_coarse_search(
         crate::log_eprintln!("   ⚠️  Could not determine video duration");
         crate::log_eprintln!("   Using SSIM All verification (includes chroma)...");
 
-        if let Some((y, u, v, all)) = calculate_ssim_all(input, output) {
+        if let Some((y, u, v, all)) = calculate_ssim_all(input, output, &vf_args) {
             crate::log_eprintln!("   SSIM Y/U/V/All: {:.4}/{:.4}/{:.4}/{:.4}", y, u, v, all);
 
             let no_duration_threshold = result.actual_min_ssim.max(0.92);
@@ -960,8 +1096,9 @@ pub fn explore_with_gpu_coarse_search(
             result.ms_ssim_score = None;
         }
     }
+    drop(ssim_phase);
 
-    let input_size = fs::metadata(input).ok().map(|m| m.len());
+    let input_size = crate::video::trimmed_source_size(input).ok();
     let output_size_actual = fs::metadata(output)
         .ok()
         .map(|m| m.len())
@@ -1047,13 +1184,21 @@ fn cpu_fine_tune_from_gpu_boundary(
     best_vmaf_tracked: &mut Option<f64>,
     best_psnr_uv_tracked: &mut Option<(f64, f64)>,
     gpu_executed: bool,
+    settings: EncodeSettings,
 ) -> Result<ExploreResult> {
-    let log = Vec::new();
+    let mut log = Vec::new();
+    // Ultimate mode only: stop at the first --ultimate-max-seconds / --ultimate-max-iterations cap.
+    let ultimate_budget = if ultimate_mode {
+        super::precision::UltimateBudget::global()
+    } else {
+        super::precision::UltimateBudget::default()
+    };
+    let search_start = std::time::Instant::now();
+    let mut budget_cap_hit: Option<super::precision::BudgetCap> = None;
     let mut early_insight_triggered = false;
 
-    let input_size = fs::metadata(input)
-        .context("Failed to read input file metadata")?
-        .len();
+    let input_size =
+        crate::video::trimmed_source_size(input).context("Failed to read input file metadata")?;
 
     // Image containers (AVIF, HEIC, GIF, WebP, …) have no audio streams.
     // Mapping all streams (-map 0) causes FFmpeg libx265 to fail with
@@ -1086,11 +1231,15 @@ fn cpu_fine_tune_from_gpu_boundary(
     #[derive(Debug, Clone)]
     enum AudioTranscodeStrategy {
         Copy,
+        /// `--audio-codec`: bloated source audio re-encoded to the requested codec/bitrate.
+        Requested(Vec<String>),
         Alac,
         AacHigh,
         AacMedium,
+        Opus,
     }
 
+    let drop_audio = settings.drop_audio;
     let audio_strategy = {
         let output_ext = output
             .extension()
@@ -1098,14 +1247,44 @@ fn cpu_fine_tune_from_gpu_boundary(
             .unwrap_or("")
             .to_lowercase();
         let is_mov_mp4 = output_ext == "mov" || output_ext == "mp4" || output_ext == "m4v";
+        let audio_codec = probe_info
+            .and_then(|info| info.audio_codec.as_ref())
+            .map(|s| s.to_lowercase())
+            .unwrap_or_default();
+        let requested = if drop_audio {
+            None
+        } else {
+            crate::audio_transcode_args(
+                Some(&audio_codec),
+                probe_info.and_then(|info| info.audio_bit_rate),
+                &output_ext,
+            )
+        };
 
-        if !is_mov_mp4 {
+        if let Some(args) = requested {
+            crate::log_eprintln!(
+                "   🎵 Re-encoding {} audio ({})",
+                audio_codec,
+                args[1..].join(" ")
+            );
+            AudioTranscodeStrategy::Requested(args)
+        } else if output_ext == "webm" && !drop_audio {
+            // WebM only takes Opus/Vorbis audio.
+            if audio_codec.is_empty()
+                || audio_codec.contains("opus")
+                || audio_codec.contains("vorbis")
+            {
+                AudioTranscodeStrategy::Copy
+            } else {
+                crate::log_eprintln!(
+                    "   🎵 Audio codec '{}' incompatible with WEBM, using Opus 160k",
+                    audio_codec
+                );
+                AudioTranscodeStrategy::Opus
+            }
+        } else if !is_mov_mp4 || drop_audio {
             AudioTranscodeStrategy::Copy
         } else {
-            let audio_codec = probe_info
-                .and_then(|info| info.audio_codec.as_ref())
-                .map(|s| s.to_lowercase())
-                .unwrap_or_default();
             let audio_bitrate = probe_info.and_then(|info| info.audio_bit_rate).unwrap_or(0);
 
             let incompatible = audio_codec.contains("opus")
@@ -1148,10 +1327,13 @@ fn cpu_fine_tune_from_gpu_boundary(
         use std::io::{BufRead, BufReader, Write};
         use std::process::Stdio;
 
+        crate::cancellation::check()?;
+
         let mut cmd = std::process::Command::new("ffmpeg");
         cmd.arg("-y");
         cmd.arg("-progress").arg("pipe:1");
 
+        cmd.args(crate::video::source_trim_args(input));
         cmd.arg("-i").arg(crate::safe_path_arg(input).as_ref());
 
         // Map streams: for image containers (AVIF/HEIC/GIF/WebP), only map video
@@ -1168,14 +1350,19 @@ fn cpu_fine_tune_from_gpu_boundary(
             .arg("-crf")
             .arg(format!("{:.2}", crf));
 
-        for arg in encoder.extra_args(max_threads) {
+        for arg in
+            encoder.extra_args_with_preset(max_threads, settings.preset, settings.deterministic)
+        {
             cmd.arg(arg);
         }
 
         // Preserve pixel format (critical for 10-bit HDR content)
         if let Some(probe) = probe_info {
-            let pix_fmt = pick_pix_fmt(probe);
+            let pix_fmt = pick_pix_fmt(probe, encoder, settings.pix_fmt);
             cmd.arg("-pix_fmt").arg(pix_fmt);
+            if encoder == VideoEncoder::Hevc {
+                cmd.args(hevc_profile_args(pix_fmt));
+            }
 
             // Forward all HDR colour metadata (primaries, TRC, colorspace, mastering display, CLL)
             for arg in build_color_args_from_probe(probe) {
@@ -1188,14 +1375,18 @@ fn cpu_fine_tune_from_gpu_boundary(
                 cmd.arg(arg);
             }
         }
+        cmd.args(frame_rate_mode_encode_args());
 
-        if input_is_image {
+        if input_is_image || drop_audio {
             cmd.arg("-an");
         } else {
             match &audio_strategy {
                 AudioTranscodeStrategy::Copy => {
                     cmd.arg("-c:a").arg("copy");
                 }
+                AudioTranscodeStrategy::Requested(args) => {
+                    cmd.args(args);
+                }
                 AudioTranscodeStrategy::Alac => {
                     cmd.arg("-c:a").arg("alac");
                 }
@@ -1205,22 +1396,19 @@ fn cpu_fine_tune_from_gpu_boundary(
                 AudioTranscodeStrategy::AacMedium => {
                     cmd.arg("-c:a").arg("aac").arg("-b:a").arg("192k");
                 }
+                AudioTranscodeStrategy::Opus => {
+                    cmd.arg("-c:a").arg("libopus").arg("-b:a").arg("160k");
+                }
             }
         }
 
         // Subtitle passthrough
         if let Some(probe) = probe_info {
             if probe.has_subtitles {
-                let out_ext = output
-                    .extension()
-                    .and_then(|e| e.to_str())
-                    .unwrap_or("")
-                    .to_lowercase();
-                let container = if out_ext == "mkv" { "mkv" } else { "mp4" };
                 let sub_args = crate::subtitle_args_for_container(
                     true,
                     probe.subtitle_codec.as_deref(),
-                    container,
+                    crate::container_of(output),
                 );
                 for arg in sub_args {
                     cmd.arg(arg);
@@ -1228,12 +1416,16 @@ fn cpu_fine_tune_from_gpu_boundary(
             }
         }
 
+        cmd.args(settings.strip.ffmpeg_args());
+        cmd.args(crate::video_explorer::deterministic_args(
+            settings.deterministic,
+        ));
         cmd.arg(crate::safe_path_arg(output).as_ref());
 
         cmd.stdout(Stdio::piped());
         let stderr_temp_val = tempfile::Builder::new()
             .suffix(".log")
-            .tempfile()
+            .tempfile_in(crate::conversion::scratch_dir())
             .context("Failed to create stderr temp file")?;
 
         let stderr_file = stderr_temp_val.path().to_path_buf();
@@ -1248,6 +1440,7 @@ fn cpu_fine_tune_from_gpu_boundary(
         }
 
         let mut child = cmd.spawn().context("Failed to spawn ffmpeg")?;
+        let watcher = crate::cancellation::ChildWatcher::for_current(&child);
 
         if let Some(stdout) = child.stdout.take() {
             let reader = BufReader::new(stdout);
@@ -1294,9 +1487,11 @@ fn cpu_fine_tune_from_gpu_boundary(
         }
 
         let status = child.wait().context("Failed to wait for ffmpeg")?;
+        drop(watcher);
         eprint!(
             "\r                                                                              \r"
         );
+        crate::cancellation::check()?;
 
         if !status.success() {
             let error_detail = if stderr_file.exists() {
@@ -1427,15 +1622,13 @@ fn cpu_fine_tune_from_gpu_boundary(
     );
     crate::verbose_eprintln!();
 
+    // Every fallback replays --max-dimension/--denoise on the reference, so a denoised encode is
+    // never judged against the noisy source.
+    let reference_ssim_filters = ssim_reference_filters(&vf_args);
     let calculate_ssim_quick = || -> Option<f64> {
-        let filters = [
-            "[0:v]scale='iw-mod(iw,2)':'ih-mod(ih,2)':flags=bicubic[ref];[ref][1:v]ssim",
-            "[0:v]scale=trunc(iw/2)*2:trunc(ih/2)*2,format=yuv420p[ref];[1:v]scale=trunc(iw/2)*2:trunc(ih/2)*2,format=yuv420p[cmp];[ref][cmp]ssim",
-            "ssim",
-        ];
-
-        for filter in &filters {
+        for (_, filter) in &reference_ssim_filters {
             let ssim_output = std::process::Command::new("ffmpeg")
+                .args(crate::video::source_trim_args(input))
                 .arg("-i")
                 .arg(crate::safe_path_arg(input).as_ref())
                 .arg("-i")
@@ -1496,6 +1689,7 @@ fn cpu_fine_tune_from_gpu_boundary(
         0.0
     };
     let gpu_ssim = calculate_ssim_quick();
+    crate::progress_sink::emit_iteration(gpu_boundary_crf, gpu_ssim, gpu_size);
 
     let is_gpu_effectively_compressed = gpu_size < input_size;
 
@@ -1670,6 +1864,17 @@ fn cpu_fine_tune_from_gpu_boundary(
         let search_floor = if ultimate_mode { 0.0 } else { min_crf };
 
         while iterations < max_iterations_for_video && test_crf >= search_floor {
+            if let Some(cap) = ultimate_budget.exceeded(search_start, iterations) {
+                crate::log_eprintln!(
+                    "   {} [CPU] ULTIMATE BUDGET:{} {} reached, stopping at best CRF {:.1}",
+                    BRIGHT_YELLOW,
+                    RESET,
+                    cap,
+                    last_good_crf
+                );
+                budget_cap_hit = Some(cap);
+                break;
+            }
             if test_crf < search_floor {
                 if current_step > MIN_STEP + 0.01 {
                     crate::verbose_eprintln!(
@@ -1701,6 +1906,7 @@ fn cpu_fine_tune_from_gpu_boundary(
                 0.0
             };
             let current_ssim_opt = calculate_ssim_quick();
+            crate::progress_sink::emit_iteration(test_crf, current_ssim_opt, size);
 
             let is_effectively_compressed = size < input_size;
 
@@ -1996,7 +2202,7 @@ fn cpu_fine_tune_from_gpu_boundary(
             }
         }
 
-        if domain_wall_hit || quality_wall_hit {
+        if domain_wall_hit || quality_wall_hit || budget_cap_hit.is_some() {
             if best_crf.is_none_or(|c| c > last_good_crf) {
                 best_crf = Some(last_good_crf);
                 best_size = Some(last_good_size);
@@ -2069,8 +2275,19 @@ fn cpu_fine_tune_from_gpu_boundary(
         };
 
         while test_crf <= max_crf && iterations < max_iterations_for_video {
+            if let Some(cap) = ultimate_budget.exceeded(search_start, iterations) {
+                crate::log_eprintln!(
+                    "   {} [CPU] ULTIMATE BUDGET:{} {} reached during upward search",
+                    BRIGHT_YELLOW,
+                    RESET,
+                    cap
+                );
+                budget_cap_hit = Some(cap);
+                break;
+            }
             let size = encode_cached(test_crf, &mut size_cache)?;
             iterations += 1;
+            crate::progress_sink::emit_iteration(test_crf, None, size);
             let total_size_pct = if input_size > 0 {
                 (size as f64 / input_size as f64 - 1.0) * 100.0
             } else {
@@ -2232,6 +2449,7 @@ fn cpu_fine_tune_from_gpu_boundary(
                 };
 
                 let current_ssim_opt = calculate_ssim_quick();
+                crate::progress_sink::emit_iteration(test_crf, current_ssim_opt, size);
 
                 // Ultimate metrics for insight mechanism
                 let mut vmaf_improved = false;
@@ -2566,6 +2784,7 @@ fn cpu_fine_tune_from_gpu_boundary(
 
                     let size = encode_cached(test_crf, &mut size_cache)?;
                     iterations += 1;
+                    crate::progress_sink::emit_iteration(test_crf, None, size);
 
                     let is_effectively_compressed = size < input_size;
                     let total_size_pct = if input_size > 0 {
@@ -2712,8 +2931,12 @@ fn cpu_fine_tune_from_gpu_boundary(
                     crate::format_bytes(last_output_video),
                     stream_size_change_pct(last_output_video, input_video_stream_size)
                 );
-                let size = encode_cached(max_crf, &mut size_cache)?;
+                let size = {
+                    let _phase = crate::phase_profile::phase(crate::ProfilePhase::FinalEncode);
+                    encode_cached(max_crf, &mut size_cache)?
+                };
                 iterations += 1;
+                crate::progress_sink::emit_iteration(max_crf, None, size);
                 (max_crf, size)
             }
         }
@@ -2726,7 +2949,19 @@ fn cpu_fine_tune_from_gpu_boundary(
         final_full_size as f64 / 1024.0 / 1024.0
     );
 
-    let ssim = calculate_ssim_enhanced(input, output);
+    let ssim_phase = crate::phase_profile::phase(crate::ProfilePhase::SsimValidation);
+    let sampled_ssim = settings.sample_ssim_frames.and_then(|frames| {
+        let strategy = settings.ssim_sampling_strategy;
+        let ssim = calculate_ssim_sampled(input, output, &vf_args, frames, strategy)?;
+        crate::verbose_eprintln!(
+            "SSIM gate: mean of {} {} sampled frames (estimate)",
+            frames,
+            strategy.as_str()
+        );
+        Some(ssim)
+    });
+    let ssim = sampled_ssim.or_else(|| calculate_ssim_enhanced(input, output, &vf_args));
+    drop(ssim_phase);
 
     if let Some(s) = ssim {
         let quality_hint = if s >= 0.99 {
@@ -2879,6 +3114,16 @@ fn cpu_fine_tune_from_gpu_boundary(
 
     confidence_detail.print_report();
 
+    if let Some(cap) = budget_cap_hit {
+        log.push(format!(
+            "   ⏱️  Ultimate budget: {} reached after {} iterations ({:.0}s) — best-so-far CRF {:.1}",
+            cap,
+            iterations,
+            search_start.elapsed().as_secs_f64(),
+            final_crf
+        ));
+    }
+
     cpu_progress.finish_iteration(final_crf, final_full_size, ssim);
 
     Ok(ExploreResult {
@@ -2904,6 +3149,10 @@ fn cpu_fine_tune_from_gpu_boundary(
         cambi_score: None,
         psnr_uv_score: None,
         early_insight_triggered,
+        cpu_fallback_used: false,
+        gpu_handoff: None,
+        size_capped: false,
+        budget_cap_hit,
     })
 }
 
@@ -2926,9 +3175,12 @@ pub fn explore_hevc_with_gpu_coarse(
         allow_size_tolerance,
         min_ssim,
         max_threads,
+        EncodeSettings::default(),
     )
 }
 
+/// `min_ssim` is the caller's SSIM floor (`--min-ssim`, `--preset`); `None` derives it from
+/// the baseline CRF.
 pub fn explore_hevc_with_gpu_coarse_ultimate_warm_start(
     input: &Path,
     output: &Path,
@@ -2937,9 +3189,12 @@ pub fn explore_hevc_with_gpu_coarse_ultimate_warm_start(
     warm_start_crf: Option<f32>,
     ultimate_mode: bool,
     allow_size_tolerance: bool,
+    min_ssim: Option<f64>,
     max_threads: usize,
+    settings: EncodeSettings,
 ) -> Result<ExploreResult> {
-    let (_, min_ssim) = calculate_smart_thresholds(baseline_crf, VideoEncoder::Hevc);
+    let min_ssim =
+        min_ssim.unwrap_or_else(|| calculate_smart_thresholds(baseline_crf, VideoEncoder::Hevc).1);
     explore_hevc_with_gpu_coarse_full_warm_start(
         input,
         output,
@@ -2951,6 +3206,7 @@ pub fn explore_hevc_with_gpu_coarse_ultimate_warm_start(
         allow_size_tolerance,
         min_ssim,
         max_threads,
+        settings,
     )
 }
 
@@ -2975,9 +3231,11 @@ pub fn explore_hevc_with_gpu_coarse_ultimate(
         allow_size_tolerance,
         min_ssim,
         max_threads,
+        EncodeSettings::default(),
     )
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn explore_hevc_with_gpu_coarse_full_warm_start(
     input: &Path,
     output: &Path,
@@ -2989,6 +3247,7 @@ pub fn explore_hevc_with_gpu_coarse_full_warm_start(
     allow_size_tolerance: bool,
     min_ssim: f64,
     max_threads: usize,
+    settings: EncodeSettings,
 ) -> Result<ExploreResult> {
     let (max_crf, _) = calculate_smart_thresholds(baseline_crf, VideoEncoder::Hevc);
     let search_anchor_crf = warm_start_crf.unwrap_or(baseline_crf).clamp(ABSOLUTE_MIN_CRF, max_crf);
@@ -3004,6 +3263,7 @@ pub fn explore_hevc_with_gpu_coarse_full_warm_start(
         force_ms_ssim_long,
         allow_size_tolerance,
         max_threads,
+        settings,
     )
 }
 
@@ -3029,9 +3289,12 @@ pub fn explore_hevc_with_gpu_coarse_full(
         allow_size_tolerance,
         min_ssim,
         max_threads,
+        EncodeSettings::default(),
     )
 }
 
+/// `min_ssim` is the caller's SSIM floor (`--min-ssim`, `--preset`); `None` derives it from
+/// the baseline CRF.
 pub fn explore_av1_with_gpu_coarse_ultimate_warm_start(
     input: &Path,
     output: &Path,
@@ -3040,9 +3303,12 @@ pub fn explore_av1_with_gpu_coarse_ultimate_warm_start(
     warm_start_crf: Option<f32>,
     ultimate_mode: bool,
     allow_size_tolerance: bool,
+    min_ssim: Option<f64>,
     max_threads: usize,
+    settings: EncodeSettings,
 ) -> Result<ExploreResult> {
-    let (_, min_ssim) = calculate_smart_thresholds(baseline_crf, VideoEncoder::Av1);
+    let min_ssim =
+        min_ssim.unwrap_or_else(|| calculate_smart_thresholds(baseline_crf, VideoEncoder::Av1).1);
     explore_av1_with_gpu_coarse_full_warm_start(
         input,
         output,
@@ -3054,6 +3320,7 @@ pub fn explore_av1_with_gpu_coarse_ultimate_warm_start(
         allow_size_tolerance,
         min_ssim,
         max_threads,
+        settings,
     )
 }
 
@@ -3078,6 +3345,7 @@ pub fn explore_av1_with_gpu_coarse(
         false,
         allow_size_tolerance,
         max_threads,
+        EncodeSettings::default(),
     )
 }
 
@@ -3102,9 +3370,11 @@ pub fn explore_av1_with_gpu_coarse_ultimate(
         allow_size_tolerance,
         min_ssim,
         max_threads,
+        EncodeSettings::default(),
     )
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn explore_av1_with_gpu_coarse_full_warm_start(
     input: &Path,
     output: &Path,
@@ -3116,6 +3386,7 @@ pub fn explore_av1_with_gpu_coarse_full_warm_start(
     allow_size_tolerance: bool,
     min_ssim: f64,
     max_threads: usize,
+    settings: EncodeSettings,
 ) -> Result<ExploreResult> {
     let (max_crf, _) = calculate_smart_thresholds(baseline_crf, VideoEncoder::Av1);
     let search_anchor_crf = warm_start_crf.unwrap_or(baseline_crf).clamp(ABSOLUTE_MIN_CRF, max_crf);
@@ -3131,6 +3402,7 @@ pub fn explore_av1_with_gpu_coarse_full_warm_start(
         force_ms_ssim_long,
         allow_size_tolerance,
         max_threads,
+        settings,
     )
 }
 
@@ -3156,5 +3428,6 @@ pub fn explore_av1_with_gpu_coarse_full(
         allow_size_tolerance,
         min_ssim,
         max_threads,
+        EncodeSettings::default(),
     )
 }
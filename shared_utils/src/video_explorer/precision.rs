@@ -1,6 +1,8 @@
 //! CRF precision constants and quality grade helpers
 
 use crate::crf_constants::{CRF_CACHE_KEY_MULTIPLIER, CRF_CACHE_MAX_VALID};
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use std::time::Instant;
 
 pub const CRF_PRECISION: f32 = 0.25;
 
@@ -81,6 +83,74 @@ impl SearchPhase {
     }
 }
 
+// 0 = no cap. Set once from the CLI (`--ultimate-max-seconds` / `--ultimate-max-iterations`).
+static ULTIMATE_MAX_SECONDS: AtomicU64 = AtomicU64::new(0);
+static ULTIMATE_MAX_ITERATIONS: AtomicU32 = AtomicU32::new(0);
+
+/// Per-file caps for the ultimate-mode (Domain Wall) search. `None` = unlimited.
+/// Whichever cap is reached first stops the search with the best-so-far CRF.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct UltimateBudget {
+    pub max_seconds: Option<u64>,
+    pub max_iterations: Option<u32>,
+}
+
+/// The budget cap that stopped a search.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BudgetCap {
+    Time(u64),
+    Iterations(u32),
+}
+
+impl std::fmt::Display for BudgetCap {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BudgetCap::Time(secs) => write!(f, "time cap {}s", secs),
+            BudgetCap::Iterations(n) => write!(f, "iteration cap {}", n),
+        }
+    }
+}
+
+impl UltimateBudget {
+    /// Budget configured for this process (see [`set_global_ultimate_budget`]).
+    pub fn global() -> Self {
+        let secs = ULTIMATE_MAX_SECONDS.load(Ordering::Relaxed);
+        let iters = ULTIMATE_MAX_ITERATIONS.load(Ordering::Relaxed);
+        Self {
+            max_seconds: (secs > 0).then_some(secs),
+            max_iterations: (iters > 0).then_some(iters),
+        }
+    }
+
+    pub fn is_unlimited(&self) -> bool {
+        self.max_seconds.is_none() && self.max_iterations.is_none()
+    }
+
+    /// First cap reached after `iterations` encodes since `started`, if any.
+    pub fn exceeded(&self, started: Instant, iterations: u32) -> Option<BudgetCap> {
+        self.exceeded_at(started.elapsed().as_secs(), iterations)
+    }
+
+    fn exceeded_at(&self, elapsed_secs: u64, iterations: u32) -> Option<BudgetCap> {
+        if let Some(max) = self.max_iterations {
+            if iterations >= max {
+                return Some(BudgetCap::Iterations(max));
+            }
+        }
+        if let Some(max) = self.max_seconds {
+            if elapsed_secs >= max {
+                return Some(BudgetCap::Time(max));
+            }
+        }
+        None
+    }
+}
+
+pub fn set_global_ultimate_budget(budget: UltimateBudget) {
+    ULTIMATE_MAX_SECONDS.store(budget.max_seconds.unwrap_or(0), Ordering::Relaxed);
+    ULTIMATE_MAX_ITERATIONS.store(budget.max_iterations.unwrap_or(0), Ordering::Relaxed);
+}
+
 /// Step sizes per phase; mirrors SearchPhase::step_size() but allows runtime override (e.g. tests). Defaults match SearchPhase.
 #[derive(Debug, Clone)]
 pub struct ThreePhaseSearch {
@@ -225,3 +295,27 @@ pub fn ms_ssim_quality_grade(ms_ssim: f64) -> &'static str {
 pub fn format_ms_ssim(ms_ssim: f64) -> String {
     format!("{:.4}", ms_ssim)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ultimate_budget_unlimited_by_default() {
+        let budget = UltimateBudget::default();
+        assert!(budget.is_unlimited());
+        assert_eq!(budget.exceeded_at(u64::MAX, u32::MAX), None);
+    }
+
+    #[test]
+    fn test_ultimate_budget_first_cap_wins() {
+        let budget = UltimateBudget {
+            max_seconds: Some(600),
+            max_iterations: Some(40),
+        };
+        assert_eq!(budget.exceeded_at(10, 5), None);
+        assert_eq!(budget.exceeded_at(10, 40), Some(BudgetCap::Iterations(40)));
+        assert_eq!(budget.exceeded_at(600, 5), Some(BudgetCap::Time(600)));
+        assert_eq!(BudgetCap::Time(600).to_string(), "time cap 600s");
+    }
+}
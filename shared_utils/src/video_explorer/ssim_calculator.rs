@@ -48,6 +48,40 @@ fn resolve_common_metric_dimensions(input: &Path, output: &Path) -> Option<(u32,
     Some((target_width, target_height))
 }
 
+/// Frame-sampling prefix for metric filter graphs (`""` when every frame is compared).
+/// CFR sources keep every `sample_rate`-th frame by index. On VFR sources frame indices drift
+/// apart between source and output, so frames are picked by timestamp instead — one every
+/// `sample_rate / avg_fps` seconds — and original timestamps are kept for frame sync.
+fn sample_filter_for(sample_rate: usize, vfr_avg_fps: Option<f64>) -> String {
+    if sample_rate <= 1 {
+        return String::new();
+    }
+    match vfr_avg_fps.filter(|fps| *fps > 0.0 && fps.is_finite()) {
+        Some(fps) => format!(
+            "select='isnan(prev_selected_t)+gte(t-prev_selected_t\\,{:.6})',setpts=PTS-STARTPTS,",
+            sample_rate as f64 / fps
+        ),
+        None => format!(
+            "select='not(mod(n\\,{}))',setpts=N/FRAME_RATE/TB,",
+            sample_rate
+        ),
+    }
+}
+
+fn metric_sample_filter(input: &Path, sample_rate: usize) -> String {
+    if sample_rate <= 1 {
+        return String::new();
+    }
+    let vfr_avg_fps = crate::ffprobe_json::probe_vfr_avg_frame_rate(input);
+    if let Some(fps) = vfr_avg_fps {
+        eprintln!(
+            "   ⏱️  VFR source (avg {:.2} fps): sampling metric frames by timestamp",
+            fps
+        );
+    }
+    sample_filter_for(sample_rate, vfr_avg_fps)
+}
+
 /// `max_duration_min`: skip MS-SSIM when video longer than this (e.g. 5.0 normal, 25.0 ultimate).
 pub fn calculate_ms_ssim_yuv(
     input: &Path,
@@ -112,6 +146,9 @@ pub fn calculate_ms_ssim_yuv(
 
     let (target_width, target_height) = resolve_common_metric_dimensions(input, output)?;
 
+    let sample_filter_y = metric_sample_filter(input, sample_rate);
+    let sample_filter_u = sample_filter_y.clone();
+    let sample_filter_v = sample_filter_y.clone();
     let input_y = input.to_path_buf();
     let output_y = output.to_path_buf();
     let input_u = input.to_path_buf();
@@ -126,7 +163,7 @@ pub fn calculate_ms_ssim_yuv(
             &input_y,
             &output_y,
             "y",
-            sample_rate,
+            &sample_filter_y,
             target_width,
             target_height,
         )
@@ -136,7 +173,7 @@ pub fn calculate_ms_ssim_yuv(
             &input_u,
             &output_u,
             "u",
-            sample_rate,
+            &sample_filter_u,
             target_width,
             target_height,
         )
@@ -146,7 +183,7 @@ pub fn calculate_ms_ssim_yuv(
             &input_v,
             &output_v,
             "v",
-            sample_rate,
+            &sample_filter_v,
             target_width,
             target_height,
         )
@@ -193,11 +230,30 @@ pub fn calculate_ms_ssim_yuv(
     ))
 }
 
+/// libvmaf graph for one channel of [`calculate_ms_ssim_yuv`]. The three channels run at once,
+/// so each gets a third of the threads; libvmaf scores the sampled frames on them in parallel
+/// and pools the per-frame scores in frame order.
+fn ms_ssim_channel_filter(
+    channel: &str,
+    sample_filter: &str,
+    target_width: u32,
+    target_height: u32,
+) -> String {
+    format!(
+        "[0:v]{sf}scale={w}:{h}:flags=bicubic,format=yuv420p,extractplanes={ch}[c0];[1:v]{sf}scale={w}:{h}:flags=bicubic,format=yuv420p,extractplanes={ch}[c1];[c0][c1]libvmaf=feature='name=float_ms_ssim':n_threads={nt}:log_fmt=json:log_path=/dev/stdout",
+        sf = sample_filter,
+        w = target_width,
+        h = target_height,
+        ch = channel,
+        nt = (num_cpus_capped() / 3).max(1),
+    )
+}
+
 fn calculate_ms_ssim_channel_sampled(
     input: &Path,
     output: &Path,
     channel: &str,
-    sample_rate: usize,
+    sample_filter: &str,
     target_width: u32,
     target_height: u32,
 ) -> Option<f64> {
@@ -210,24 +266,10 @@ fn calculate_ms_ssim_channel_sampled(
         }
     }
 
-    let sample_filter = if sample_rate > 1 {
-        format!(
-            "select='not(mod(n\\,{}))',setpts=N/FRAME_RATE/TB,",
-            sample_rate
-        )
-    } else {
-        String::new()
-    };
-
-    let filter = format!(
-        "[0:v]{sf}scale={w}:{h}:flags=bicubic,format=yuv420p,extractplanes={ch}[c0];[1:v]{sf}scale={w}:{h}:flags=bicubic,format=yuv420p,extractplanes={ch}[c1];[c0][c1]libvmaf=feature='name=float_ms_ssim':log_fmt=json:log_path=/dev/stdout",
-        sf = sample_filter,
-        w = target_width,
-        h = target_height,
-        ch = channel,
-    );
+    let filter = ms_ssim_channel_filter(channel, sample_filter, target_width, target_height);
 
     let result = Command::new("ffmpeg")
+        .args(crate::video::source_trim_args(input))
         .arg("-i")
         .arg(crate::safe_path_arg(input).as_ref())
         .arg("-i")
@@ -300,6 +342,7 @@ pub fn calculate_ms_ssim(input: &Path, output: &Path) -> Option<f64> {
     let (target_width, target_height) = resolve_common_metric_dimensions(input, output)?;
 
     let result = Command::new("ffmpeg")
+        .args(crate::video::source_trim_args(input))
         .arg("-i")
         .arg(crate::safe_path_arg(input).as_ref())
         .arg("-i")
@@ -417,14 +460,7 @@ fn parse_ms_ssim_from_legacy(stderr: &str) -> Option<f64> {
 /// `sample_rate`: 1 = every frame, 3 = every 3rd frame, etc.
 /// Returns None on failure (ffmpeg/libvmaf unavailable or other error).
 pub fn calculate_vmaf_y(input: &Path, output: &Path, sample_rate: usize) -> Option<f64> {
-    let sample_filter = if sample_rate > 1 {
-        format!(
-            "select='not(mod(n\\,{}))',setpts=N/FRAME_RATE/TB,",
-            sample_rate
-        )
-    } else {
-        String::new()
-    };
+    let sample_filter = metric_sample_filter(input, sample_rate);
 
     let n_threads = num_cpus_capped();
     let (target_width, target_height) = resolve_common_metric_dimensions(input, output)?;
@@ -441,6 +477,7 @@ pub fn calculate_vmaf_y(input: &Path, output: &Path, sample_rate: usize) -> Opti
     let result = Command::new("ffmpeg")
         .arg("-i")
         .arg(crate::safe_path_arg(output).as_ref())
+        .args(crate::video::source_trim_args(input))
         .arg("-i")
         .arg(crate::safe_path_arg(input).as_ref())
         .arg("-filter_complex")
@@ -488,7 +525,10 @@ pub fn calculate_vmaf_y(input: &Path, output: &Path, sample_rate: usize) -> Opti
 pub fn calculate_cambi(output: &Path, sample_rate: usize) -> Option<f64> {
     let n_threads = num_cpus_capped();
 
-    let log_file = tempfile::Builder::new().suffix(".json").tempfile().ok()?;
+    let log_file = tempfile::Builder::new()
+        .suffix(".json")
+        .tempfile_in(crate::conversion::scratch_dir())
+        .ok()?;
     let log_path = log_file.path().to_path_buf();
 
     // libvmaf filter requires TWO inputs (main + reference).
@@ -559,6 +599,8 @@ pub fn calculate_psnr_uv(input: &Path, output: &Path, sample_rate: usize) -> Opt
 
     let (target_width, target_height) = resolve_common_metric_dimensions(input, output)?;
 
+    let sample_filter_u = metric_sample_filter(input, sample_rate);
+    let sample_filter_v = sample_filter_u.clone();
     let input_u = input.to_path_buf();
     let output_u = output.to_path_buf();
     let input_v = input.to_path_buf();
@@ -569,7 +611,7 @@ pub fn calculate_psnr_uv(input: &Path, output: &Path, sample_rate: usize) -> Opt
             &input_u,
             &output_u,
             "u",
-            sample_rate,
+            &sample_filter_u,
             target_width,
             target_height,
         )
@@ -579,7 +621,7 @@ pub fn calculate_psnr_uv(input: &Path, output: &Path, sample_rate: usize) -> Opt
             &input_v,
             &output_v,
             "v",
-            sample_rate,
+            &sample_filter_v,
             target_width,
             target_height,
         )
@@ -607,19 +649,10 @@ fn psnr_single_channel(
     input: &Path,
     output: &Path,
     channel: &str,
-    sample_rate: usize,
+    sample_filter: &str,
     target_width: u32,
     target_height: u32,
 ) -> Option<f64> {
-    let sample_filter = if sample_rate > 1 {
-        format!(
-            "select='not(mod(n\\,{}))',setpts=N/FRAME_RATE/TB,",
-            sample_rate
-        )
-    } else {
-        String::new()
-    };
-
     // Extract the requested plane from both streams, then run psnr on them.
     let filter = format!(
         "[0:v]{sf}scale={w}:{h}:flags=bicubic,format=yuv420p,extractplanes={ch}[ref];[1:v]{sf}scale={w}:{h}:flags=bicubic,format=yuv420p,extractplanes={ch}[dis];[ref][dis]psnr=stats_file=-",
@@ -630,6 +663,7 @@ fn psnr_single_channel(
     );
 
     let result = Command::new("ffmpeg")
+        .args(crate::video::source_trim_args(input))
         .arg("-i")
         .arg(crate::safe_path_arg(input).as_ref())
         .arg("-i")
@@ -929,6 +963,41 @@ mod tests {
 
     // ── num_cpus_capped ───────────────────────────────────────────────────────
 
+    #[test]
+    fn test_sample_filter_cfr_uses_frame_index() {
+        assert_eq!(sample_filter_for(1, None), "");
+        assert_eq!(
+            sample_filter_for(3, None),
+            "select='not(mod(n\\,3))',setpts=N/FRAME_RATE/TB,"
+        );
+    }
+
+    #[test]
+    fn test_sample_filter_vfr_uses_timestamps() {
+        let filter = sample_filter_for(3, Some(30.0));
+        assert!(filter.contains("prev_selected_t"), "{}", filter);
+        assert!(filter.contains("0.100000"), "{}", filter);
+        assert!(filter.ends_with("setpts=PTS-STARTPTS,"));
+        assert!(!filter.contains("mod(n"));
+        // Full-rate comparison never adds a sampling filter, VFR or not.
+        assert_eq!(sample_filter_for(1, Some(30.0)), "");
+    }
+
+    #[test]
+    fn test_ms_ssim_channel_filter_scores_frames_in_parallel() {
+        let sample = sample_filter_for(3, None);
+        let filter = ms_ssim_channel_filter("u", &sample, 1280, 720);
+        let threads = (num_cpus_capped() / 3).max(1);
+        assert!(
+            filter.contains(&format!("n_threads={}", threads)),
+            "{}",
+            filter
+        );
+        assert!(filter.contains("extractplanes=u[c0]"), "{}", filter);
+        // Both inputs are sampled the same way before scaling.
+        assert_eq!(filter.matches(sample.as_str()).count(), 2, "{}", filter);
+    }
+
     #[test]
     fn test_num_cpus_capped_within_bounds() {
         let n = num_cpus_capped();
@@ -60,7 +60,7 @@ pub fn get_video_duration(input: &Path) -> Option<f64> {
     let stdout = String::from_utf8_lossy(&output.stdout);
     let trimmed = stdout.trim();
     match trimmed.parse::<f64>() {
-        Ok(duration) => Some(duration),
+        Ok(duration) => Some(duration * crate::video::trimmed_share(input)),
         Err(err) => {
             warn!(
                 path = %input.display(),
@@ -73,21 +73,45 @@ pub fn get_video_duration(input: &Path) -> Option<f64> {
     }
 }
 
-pub fn calculate_ssim_enhanced(input: &Path, output: &Path) -> Option<f64> {
-    let filters: &[(&str, &str)] = &[
-        ("standard", "[0:v]scale='iw-mod(iw,2)':'ih-mod(ih,2)':flags=bicubic[ref];[ref][1:v]ssim"),
-        ("format_convert", "[0:v]scale=trunc(iw/2)*2:trunc(ih/2)*2,format=yuv420p[ref];[1:v]scale=trunc(iw/2)*2:trunc(ih/2)*2,format=yuv420p[cmp];[ref][cmp]ssim"),
-        ("simple", "ssim"),
+/// lavfi graphs for a single SSIM score, most faithful first. The reference leg replays the
+/// encode's `--max-dimension` downscale and `--denoise` step from `vf_args` (see
+/// [`crate::video::metric_reference_filter`]); a bare `ssim` against the untouched source is only
+/// offered when the encode kept the source as-is.
+pub fn ssim_reference_filters(vf_args: &[String]) -> Vec<(&'static str, String)> {
+    let mut filters = vec![
+        (
+            "standard",
+            format!(
+                "{};[ref][1:v]ssim",
+                crate::video::metric_reference_filter(vf_args)
+            ),
+        ),
+        (
+            "format_convert",
+            format!(
+                "[0:v]{},format=yuv420p[ref];[1:v]scale=trunc(iw/2)*2:trunc(ih/2)*2,format=yuv420p[cmp];[ref][cmp]ssim",
+                crate::video::metric_reference_scale(vf_args)
+            ),
+        ),
     ];
+    if !reference_is_transformed(vf_args) {
+        filters.push(("simple", "ssim".to_string()));
+    }
+    filters
+}
+
+pub fn calculate_ssim_enhanced(input: &Path, output: &Path, vf_args: &[String]) -> Option<f64> {
+    let filters = ssim_reference_filters(vf_args);
 
-    for (name, filter) in filters {
+    for (name, filter) in &filters {
         let result = Command::new("ffmpeg")
+            .args(crate::video::source_trim_args(input))
             .arg("-i")
             .arg(crate::safe_path_arg(input).as_ref())
             .arg("-i")
             .arg(crate::safe_path_arg(output).as_ref())
             .arg("-lavfi")
-            .arg(*filter)
+            .arg(filter)
             .arg("-f")
             .arg("null")
             .arg("-")
@@ -116,9 +140,269 @@ pub fn calculate_ssim_enhanced(input: &Path, output: &Path) -> Option<f64> {
     None
 }
 
+/// `select` filter keeping `frames` frames spread evenly over `duration`: the first frame at or
+/// after `interval / 2`, then one frame per `interval`. Source and output share timestamps, so
+/// the same expression picks matching frames on both sides.
+pub fn sampled_frame_select(duration: f64, frames: u32) -> String {
+    let interval = duration / frames.max(1) as f64;
+    format!(
+        "select='gte(t\\,{:.3})*(isnan(prev_selected_t)+gte(t-prev_selected_t\\,{:.3}))'",
+        interval / 2.0,
+        interval
+    )
+}
+
+/// Where `--sample-ssim` takes its frames from (`--ssim-sampling-strategy`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SsimSamplingStrategy {
+    /// Evenly spaced over the clip.
+    #[default]
+    Uniform,
+    /// Source keyframes, spread over the clip.
+    Keyframes,
+    /// Frames with the highest ffmpeg scene-change score (cuts, flashes).
+    SceneChange,
+    /// Frames that differ most from their predecessor (signalstats `YDIF`).
+    HighMotion,
+}
+
+impl SsimSamplingStrategy {
+    pub const ALL: [SsimSamplingStrategy; 4] = [
+        SsimSamplingStrategy::Uniform,
+        SsimSamplingStrategy::Keyframes,
+        SsimSamplingStrategy::SceneChange,
+        SsimSamplingStrategy::HighMotion,
+    ];
+
+    /// `--ssim-sampling-strategy` name.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SsimSamplingStrategy::Uniform => "uniform",
+            SsimSamplingStrategy::Keyframes => "keyframes",
+            SsimSamplingStrategy::SceneChange => "scene-change",
+            SsimSamplingStrategy::HighMotion => "high-motion",
+        }
+    }
+
+    /// Parse `--ssim-sampling-strategy` (underscores are accepted in place of dashes).
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let value = value.trim().to_ascii_lowercase().replace('_', "-");
+        Self::ALL
+            .iter()
+            .find(|s| s.as_str() == value)
+            .copied()
+            .ok_or_else(|| {
+                let names: Vec<&str> = Self::ALL.iter().map(|s| s.as_str()).collect();
+                format!(
+                    "Invalid SSIM sampling strategy '{}' (expected one of: {})",
+                    value,
+                    names.join(", ")
+                )
+            })
+    }
+}
+
+/// Half-width of the window that matches a source timestamp on both sides; well below one
+/// frame at 100 fps, well above the rounding a container time base adds.
+const SAMPLE_TIME_TOLERANCE: f64 = 0.004;
+
+/// `select` filter keeping the frames at `times` (source timestamps).
+pub fn timestamp_frame_select(times: &[f64]) -> String {
+    let terms: Vec<String> = times
+        .iter()
+        .map(|t| format!("lt(abs(t-{:.4})\\,{})", t, SAMPLE_TIME_TOLERANCE))
+        .collect();
+    format!("select='{}'", terms.join("+"))
+}
+
+/// `frames` timestamps spread evenly over `times` (sorted); all of them when there are fewer.
+pub fn spread_sample_times(times: &[f64], frames: u32) -> Vec<f64> {
+    let frames = frames.max(1) as usize;
+    if times.len() <= frames {
+        return times.to_vec();
+    }
+    (0..frames)
+        .map(|i| times[(2 * i + 1) * times.len() / (2 * frames)])
+        .collect()
+}
+
+/// The `frames` highest-scoring timestamps, at least `min_gap` seconds apart so one long action
+/// scene cannot take every sample. Returned in time order.
+pub fn peak_sample_times(scores: &[(f64, f64)], frames: u32, min_gap: f64) -> Vec<f64> {
+    let mut ranked: Vec<(f64, f64)> = scores
+        .iter()
+        .filter(|(_, s)| s.is_finite())
+        .copied()
+        .collect();
+    ranked.sort_by(|a, b| b.1.total_cmp(&a.1));
+    let mut picked: Vec<f64> = Vec::with_capacity(frames as usize);
+    for (t, _) in ranked {
+        if picked.len() >= frames as usize {
+            break;
+        }
+        if picked.iter().all(|p| (p - t).abs() >= min_gap) {
+            picked.push(t);
+        }
+    }
+    picked.sort_by(f64::total_cmp);
+    picked
+}
+
+/// Per-frame `(pts_time, value)` pairs from the log of ffmpeg's `metadata=print` filter.
+pub fn parse_metadata_scores(log: &str, key: &str) -> Vec<(f64, f64)> {
+    let needle = format!("{}=", key);
+    let mut current_t = None;
+    let mut scores = Vec::new();
+    for line in log.lines() {
+        if let Some(pos) = line.find("pts_time:") {
+            current_t = line[pos + 9..]
+                .split_whitespace()
+                .next()
+                .and_then(|v| v.parse::<f64>().ok());
+        } else if let Some(pos) = line.find(&needle) {
+            let value = line[pos + needle.len()..].trim().parse::<f64>().ok();
+            if let (Some(t), Some(v)) = (current_t.take(), value) {
+                scores.push((t, v));
+            }
+        }
+    }
+    scores
+}
+
+/// Source keyframe timestamps (ffprobe decodes keyframes only, so this is quick).
+fn probe_keyframe_times(input: &Path) -> Vec<f64> {
+    let Ok(out) = Command::new("ffprobe")
+        .args([
+            "-v",
+            "error",
+            "-select_streams",
+            "v:0",
+            "-skip_frame",
+            "nokey",
+        ])
+        .args(["-show_entries", "frame=pts_time", "-of", "csv=p=0"])
+        .arg("--")
+        .arg(crate::safe_path_arg(input).as_ref())
+        .output()
+    else {
+        return Vec::new();
+    };
+    let mut times: Vec<f64> = String::from_utf8_lossy(&out.stdout)
+        .lines()
+        .filter_map(|l| l.trim().trim_end_matches(',').parse::<f64>().ok())
+        .collect();
+    times.sort_by(f64::total_cmp);
+    // ffprobe reports source time; shift keyframes inside the trim window onto its timeline.
+    if let Some((start, end)) = crate::video::source_trim_window(input) {
+        times.retain(|t| *t >= start && end.is_none_or(|e| *t < e));
+        times.iter_mut().for_each(|t| *t -= start);
+    }
+    times
+}
+
+/// Per-frame scores of the source from `filter`, read back through `metadata=print`. The
+/// analysis runs on a 320px-wide copy: it only has to rank frames, not measure them.
+fn probe_frame_scores(input: &Path, filter: &str, key: &str) -> Vec<(f64, f64)> {
+    let Ok(out) = Command::new("ffmpeg")
+        .args(["-hide_banner", "-nostats"])
+        .args(crate::video::source_trim_args(input))
+        .arg("-i")
+        .arg(crate::safe_path_arg(input).as_ref())
+        .args(["-map", "0:v:0", "-an", "-vf"])
+        .arg(format!("scale=320:-2,{filter},metadata=print:key={key}"))
+        .args(["-f", "null", "-"])
+        .output()
+    else {
+        return Vec::new();
+    };
+    parse_metadata_scores(&String::from_utf8_lossy(&out.stderr), key)
+}
+
+/// `select` expression for `frames` samples placed by `strategy`. Falls back to even spacing
+/// when the source analysis finds nothing to rank.
+fn strategy_frame_select(
+    input: &Path,
+    duration: f64,
+    frames: u32,
+    strategy: SsimSamplingStrategy,
+) -> String {
+    let min_gap = duration / frames.max(1) as f64 / 2.0;
+    let times = match strategy {
+        SsimSamplingStrategy::Uniform => Vec::new(),
+        SsimSamplingStrategy::Keyframes => {
+            spread_sample_times(&probe_keyframe_times(input), frames)
+        }
+        SsimSamplingStrategy::SceneChange => peak_sample_times(
+            &probe_frame_scores(input, "select='gte(scene\\,0)'", "lavfi.scene_score"),
+            frames,
+            min_gap,
+        ),
+        SsimSamplingStrategy::HighMotion => peak_sample_times(
+            &probe_frame_scores(input, "signalstats", "lavfi.signalstats.YDIF"),
+            frames,
+            min_gap,
+        ),
+    };
+    if times.is_empty() {
+        if strategy != SsimSamplingStrategy::Uniform {
+            warn!(
+                strategy = strategy.as_str(),
+                "No frames found for SSIM sampling strategy, sampling evenly"
+            );
+        }
+        return sampled_frame_select(duration, frames);
+    }
+    timestamp_frame_select(&times)
+}
+
+/// Mean SSIM over `frames` sampled frames (`--sample-ssim`), placed by `strategy`. This is an
+/// estimate: damage confined to frames between the samples is not seen; `SceneChange` and
+/// `HighMotion` aim the samples at the frames an encoder finds hardest. None when the duration
+/// is unknown or ffmpeg fails; callers fall back to the full comparison.
+pub fn calculate_ssim_sampled(
+    input: &Path,
+    output: &Path,
+    vf_args: &[String],
+    frames: u32,
+    strategy: SsimSamplingStrategy,
+) -> Option<f64> {
+    let duration = get_video_duration(input).filter(|d| *d > 0.0)?;
+    let select = strategy_frame_select(input, duration, frames, strategy);
+    let filter = format!(
+        "[0:v]{select},{reference}[ref];[1:v]{select}[cmp];[ref][cmp]ssim",
+        reference = crate::video::metric_reference_scale(vf_args)
+    );
+
+    let out = Command::new("ffmpeg")
+        .args(crate::video::source_trim_args(input))
+        .arg("-i")
+        .arg(crate::safe_path_arg(input).as_ref())
+        .arg("-i")
+        .arg(crate::safe_path_arg(output).as_ref())
+        .arg("-lavfi")
+        .arg(&filter)
+        .arg("-f")
+        .arg("null")
+        .arg("-")
+        .output()
+        .ok()?;
+    if !out.status.success() {
+        warn!(
+            frames = frames,
+            "Sampled SSIM failed, falling back to full comparison"
+        );
+        return None;
+    }
+    let ssim = parse_ssim_from_output(&String::from_utf8_lossy(&out.stderr))
+        .filter(|s| is_valid_ssim_value(*s))?;
+    info!(frames = frames, strategy = strategy.as_str(), ssim = %ssim, "Sampled SSIM calculated");
+    Some(ssim)
+}
+
 /// Run ffmpeg with the given lavfi filter and parse SSIM Y/U/V/All from stderr.
 fn run_ssim_all_filter(input: &Path, output: &Path, lavfi: &str) -> Option<(f64, f64, f64, f64)> {
     let out = Command::new("ffmpeg")
+        .args(crate::video::source_trim_args(input))
         .arg("-i")
         .arg(crate::safe_path_arg(input).as_ref())
         .arg("-i")
@@ -147,20 +431,43 @@ fn run_ssim_all_filter(input: &Path, output: &Path, lavfi: &str) -> Option<(f64,
     None
 }
 
+/// True when the encode downscales or denoises, so the source must be transformed the same way
+/// before it can serve as the metric reference.
+fn reference_is_transformed(vf_args: &[String]) -> bool {
+    crate::video::downscale_filter_from_vf_args(vf_args).is_some()
+        || crate::video::denoise_filter_from_vf_args(vf_args).is_some()
+}
+
+/// lavfi graphs [`calculate_ssim_all`] tries, in order. The reference leg replays the encode's
+/// `--max-dimension`/`--denoise` steps from `vf_args`.
+pub fn ssim_all_filters(vf_args: &[String]) -> Vec<String> {
+    let reference = crate::video::metric_reference_scale(vf_args);
+    let even_cmp = "[1:v]format=yuv420p,scale='iw-mod(iw,2)':'ih-mod(ih,2)'[cmp];[ref][cmp]ssim";
+    let mut filters = Vec::new();
+    if !reference_is_transformed(vf_args) {
+        filters.push("[0:v][1:v]ssim".to_string());
+    }
+    filters.push(format!("[0:v]format=yuv420p,{reference}[ref];{even_cmp}"));
+    // Match encoder: format=rgba, premultiply (composite on black), then yuv420p.
+    filters.push(format!(
+        "[0:v]format=rgba,premultiply=inplace=1,format=rgb24,format=yuv420p,{reference}[ref];{even_cmp}"
+    ));
+    filters
+}
+
 /// SSIM Y/U/V/All between input and output. Tries in order:
-/// 1. Direct ssim (when formats already match).
+/// 1. Direct ssim (when formats already match and the encode neither downscales nor denoises).
 /// 2. Format normalization (GIF palette / odd-size → yuv420p even).
 /// 3. Alpha flatten: composite input on black (same as encoder) then compare,
 ///    so transparent GIF/WebP/PNG matches HEVC output that has no alpha.
-pub fn calculate_ssim_all(input: &Path, output: &Path) -> Option<(f64, f64, f64, f64)> {
-    const DIRECT: &str = "[0:v][1:v]ssim";
-    const FORMAT_NORM: &str = "[0:v]format=yuv420p,scale='iw-mod(iw,2)':'ih-mod(ih,2)'[ref];[1:v]format=yuv420p,scale='iw-mod(iw,2)':'ih-mod(ih,2)'[cmp];[ref][cmp]ssim";
-    // Match encoder: format=rgba, premultiply (composite on black), then yuv420p.
-    const ALPHA_FLATTEN: &str = "[0:v]format=rgba,premultiply=inplace=1,format=rgb24,format=yuv420p,scale='iw-mod(iw,2)':'ih-mod(ih,2)'[ref];[1:v]format=yuv420p,scale='iw-mod(iw,2)':'ih-mod(ih,2)'[cmp];[ref][cmp]ssim";
-
-    run_ssim_all_filter(input, output, DIRECT)
-        .or_else(|| run_ssim_all_filter(input, output, FORMAT_NORM))
-        .or_else(|| run_ssim_all_filter(input, output, ALPHA_FLATTEN))
+pub fn calculate_ssim_all(
+    input: &Path,
+    output: &Path,
+    vf_args: &[String],
+) -> Option<(f64, f64, f64, f64)> {
+    ssim_all_filters(vf_args)
+        .iter()
+        .find_map(|filter| run_ssim_all_filter(input, output, filter))
 }
 
 fn parse_ssim_from_output(stderr: &str) -> Option<f64> {
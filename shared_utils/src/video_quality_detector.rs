@@ -22,6 +22,7 @@ use crate::quality_matcher::{
 use crate::video_detection::VideoDetectionResult;
 use serde::{Deserialize, Serialize};
 use std::path::Path;
+use std::process::Command;
 use tracing::Level;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -46,6 +47,9 @@ pub struct VideoQualityAnalysis {
 
     pub pix_fmt: String,
     pub chroma: ChromaSubsampling,
+    /// No chroma: a gray `pix_fmt`, or color frames found neutral by [`detect_grayscale_video`].
+    #[serde(default)]
+    pub is_grayscale: bool,
     pub gop_size: Option<u32>,
     /// Actual B-frame count (max_b_frames) from ffprobe.
     pub b_frame_count: u8,
@@ -92,12 +96,13 @@ impl VideoCodecType {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
 pub enum ChromaSubsampling {
     Yuv420,
     Yuv422,
     Yuv444,
     Rgb,
+    #[default]
     Unknown,
 }
 
@@ -143,6 +148,17 @@ pub enum VideoContentType {
 }
 
 impl VideoContentType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            VideoContentType::LiveAction => "Live Action",
+            VideoContentType::Animation => "Animation",
+            VideoContentType::ScreenRecording => "Screen Recording",
+            VideoContentType::Gaming => "Gaming",
+            VideoContentType::FilmGrain => "Film Grain",
+            VideoContentType::Unknown => "Unknown",
+        }
+    }
+
     pub fn to_content_type(&self) -> ContentType {
         match self {
             VideoContentType::LiveAction => ContentType::LiveAction,
@@ -165,6 +181,30 @@ pub enum CompressionLevel {
 }
 
 impl CompressionLevel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CompressionLevel::Lossless => "Lossless",
+            CompressionLevel::VisuallyLossless => "Visually Lossless",
+            CompressionLevel::HighQuality => "High Quality",
+            CompressionLevel::Standard => "Standard",
+            CompressionLevel::LowQuality => "Low Quality",
+        }
+    }
+
+    /// Short hint on whether converting is likely worth it at this compression level.
+    pub fn conversion_hint(&self) -> &'static str {
+        match self {
+            CompressionLevel::Lossless | CompressionLevel::VisuallyLossless => {
+                "lightly compressed: large savings likely"
+            }
+            CompressionLevel::HighQuality => "moderately compressed: good savings likely",
+            CompressionLevel::Standard => "already compressed: modest savings expected",
+            CompressionLevel::LowQuality => {
+                "already heavily compressed: little to gain, risk of generation loss"
+            }
+        }
+    }
+
     pub fn from_bpp(bpp: f64, codec_type: VideoCodecType) -> Self {
         if codec_type == VideoCodecType::Lossless {
             return CompressionLevel::Lossless;
@@ -292,6 +332,7 @@ pub fn analyze_video_quality(
         bit_depth,
         pix_fmt: pix_fmt.to_string(),
         chroma,
+        is_grayscale: pix_fmt.starts_with("gray"),
         gop_size,
         b_frame_count,
         has_b_frames,
@@ -334,6 +375,125 @@ pub fn analyze_video_quality_from_detection(
     )
 }
 
+/// How far (8-bit code values) a frame's 10th/90th chroma percentiles may sit from neutral 128
+/// for [`detect_grayscale_video`]; percentiles ignore isolated noisy pixels.
+const GRAYSCALE_CHROMA_TOLERANCE: f64 = 2.0;
+
+/// Frames [`detect_grayscale_video`] samples, spread evenly over the clip.
+const GRAYSCALE_SAMPLE_FRAMES: u32 = 5;
+
+/// True when a source carries no chroma: a gray `pix_fmt`, or every sampled frame has its U/V
+/// percentiles within [`GRAYSCALE_CHROMA_TOLERANCE`] of neutral. Probe failures count as color.
+pub fn detect_grayscale_video(input: &Path, pix_fmt: &str, duration_secs: f64) -> bool {
+    if pix_fmt.starts_with("gray") {
+        return true;
+    }
+    if duration_secs <= 0.0 {
+        return false;
+    }
+    (0..GRAYSCALE_SAMPLE_FRAMES).all(|i| {
+        let at = duration_secs * (i as f64 + 0.5) / GRAYSCALE_SAMPLE_FRAMES as f64;
+        let output = Command::new("ffmpeg")
+            .arg("-hide_banner")
+            .arg("-nostdin")
+            .arg("-ss")
+            .arg(format!("{:.3}", at))
+            .arg("-i")
+            .arg(crate::safe_path_arg(input).as_ref())
+            .arg("-frames:v")
+            .arg("1")
+            .arg("-vf")
+            .arg("format=yuv444p,signalstats,metadata=mode=print")
+            .arg("-an")
+            .arg("-f")
+            .arg("null")
+            .arg("-")
+            .output();
+        match output {
+            Ok(out) if out.status.success() => {
+                signalstats_chroma_is_neutral(&String::from_utf8_lossy(&out.stderr))
+                    .unwrap_or(false)
+            }
+            _ => false,
+        }
+    })
+}
+
+/// PSNR (dB) between a sample and its `hqdn3d`-denoised copy at or above which the source
+/// counts as clean for [`estimate_film_grain`].
+const FILM_GRAIN_CLEAN_PSNR: f64 = 44.0;
+
+/// Grain levels added per dB the denoised copy falls below [`FILM_GRAIN_CLEAN_PSNR`].
+const FILM_GRAIN_LEVELS_PER_DB: f64 = 2.5;
+
+/// Frames [`estimate_film_grain`] measures, from a stretch in the middle of the clip.
+const FILM_GRAIN_SAMPLE_FRAMES: u32 = 48;
+
+/// `--film-grain-auto`: SVT-AV1 grain level for a source, from a noise-measurement pass.
+/// A short stretch is compared with an `hqdn3d`-denoised copy of itself: clean footage barely
+/// changes, grainy film loses a lot. `None` when the probe fails.
+pub fn estimate_film_grain(input: &Path, duration_secs: f64) -> Option<u8> {
+    let output = Command::new("ffmpeg")
+        .arg("-hide_banner")
+        .arg("-nostdin")
+        .arg("-ss")
+        .arg(format!("{:.3}", (duration_secs * 0.4).max(0.0)))
+        .arg("-i")
+        .arg(crate::safe_path_arg(input).as_ref())
+        .arg("-filter_complex")
+        .arg("[0:v]format=yuv420p,split[a][b];[b]hqdn3d=4:3:6:4.5[d];[a][d]psnr")
+        .arg("-frames:v")
+        .arg(FILM_GRAIN_SAMPLE_FRAMES.to_string())
+        .arg("-an")
+        .arg("-f")
+        .arg("null")
+        .arg("-")
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    parse_psnr_y(&String::from_utf8_lossy(&output.stderr)).map(film_grain_level_for_psnr)
+}
+
+/// Luma PSNR from the `psnr` filter summary (`PSNR y:38.12 u:… average:… min:… max:…`).
+fn parse_psnr_y(stderr: &str) -> Option<f64> {
+    stderr.lines().rev().find_map(|line| {
+        let (_, rest) = line.split_once("PSNR y:")?;
+        rest.split_whitespace().next()?.parse().ok()
+    })
+}
+
+fn film_grain_level_for_psnr(psnr_y: f64) -> u8 {
+    let level = ((FILM_GRAIN_CLEAN_PSNR - psnr_y) * FILM_GRAIN_LEVELS_PER_DB).round();
+    level.clamp(0.0, crate::video_explorer::FILM_GRAIN_MAX as f64) as u8
+}
+
+/// Whether the `signalstats` chroma percentiles printed by `metadata=print` all sit near
+/// neutral. `None` when the output holds no chroma statistics.
+fn signalstats_chroma_is_neutral(stderr: &str) -> Option<bool> {
+    let mut seen = false;
+    for line in stderr.lines() {
+        let Some((_, stat)) = line.split_once("lavfi.signalstats.") else {
+            continue;
+        };
+        let Some((key, value)) = stat.split_once('=') else {
+            continue;
+        };
+        if !matches!(key, "ULOW" | "UHIGH" | "VLOW" | "VHIGH") {
+            continue;
+        }
+        let Ok(value) = value.trim().parse::<f64>() else {
+            continue;
+        };
+        if (value - 128.0).abs() > GRAYSCALE_CHROMA_TOLERANCE {
+            return Some(false);
+        }
+        seen = true;
+    }
+    seen.then_some(true)
+}
+
 fn extract_crf_from_params(params: &str) -> Option<u8> {
     let lower = params.to_lowercase();
 
@@ -393,8 +553,8 @@ pub fn log_media_info_for_quality(analysis: &VideoQualityAnalysis, input_path: &
     write_to_log_at_level(
         Level::DEBUG,
         &format!(
-            "  pix_fmt={} chroma={:?} has_b_frames={}",
-            analysis.pix_fmt, analysis.chroma, analysis.has_b_frames
+            "  pix_fmt={} chroma={:?} grayscale={} has_b_frames={}",
+            analysis.pix_fmt, analysis.chroma, analysis.is_grayscale, analysis.has_b_frames
         ),
     );
     write_to_log_at_level(
@@ -578,6 +738,50 @@ fn calculate_video_confidence(
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_signalstats_chroma_neutral_detection() {
+        let gray = "[Parsed_metadata_2 @ 0x1] frame:0    pts:0       pts_time:0\n\
+                    [Parsed_metadata_2 @ 0x1] lavfi.signalstats.YLOW=16\n\
+                    [Parsed_metadata_2 @ 0x1] lavfi.signalstats.ULOW=127\n\
+                    [Parsed_metadata_2 @ 0x1] lavfi.signalstats.UHIGH=129\n\
+                    [Parsed_metadata_2 @ 0x1] lavfi.signalstats.VLOW=128\n\
+                    [Parsed_metadata_2 @ 0x1] lavfi.signalstats.VHIGH=128\n";
+        assert_eq!(signalstats_chroma_is_neutral(gray), Some(true));
+        let sepia = gray.replace("VHIGH=128", "VHIGH=141");
+        assert_eq!(signalstats_chroma_is_neutral(&sepia), Some(false));
+        assert_eq!(signalstats_chroma_is_neutral("Conversion failed!"), None);
+        assert!(detect_grayscale_video(
+            Path::new("/nonexistent.mp4"),
+            "gray",
+            0.0
+        ));
+    }
+
+    #[test]
+    fn test_film_grain_level_from_denoise_psnr() {
+        let stderr = "frame=   48 fps=0.0 q=-0.0 size=N/A\n\
+                      [Parsed_psnr_4 @ 0x1] PSNR y:36.02 u:42.10 v:41.87 average:37.40 min:35.11 max:37.90\n";
+        assert_eq!(parse_psnr_y(stderr), Some(36.02));
+        assert_eq!(parse_psnr_y("Conversion failed!"), None);
+
+        assert_eq!(film_grain_level_for_psnr(36.02), 20);
+        // Clean or identical footage: no grain; extreme noise is capped.
+        assert_eq!(film_grain_level_for_psnr(47.5), 0);
+        assert_eq!(film_grain_level_for_psnr(f64::INFINITY), 0);
+        assert_eq!(film_grain_level_for_psnr(12.0), 50);
+    }
+
+    #[test]
+    fn test_compression_level_hint_flags_heavy_compression() {
+        let level = CompressionLevel::from_bpp(0.02, VideoCodecType::Legacy);
+        assert_eq!(level, CompressionLevel::LowQuality);
+        assert!(level.conversion_hint().contains("heavily compressed"));
+        assert_eq!(
+            CompressionLevel::from_bpp(0.5, VideoCodecType::Legacy).as_str(),
+            "High Quality"
+        );
+    }
+
     #[test]
     fn test_analyze_h264_1080p() {
         let result = analyze_video_quality(
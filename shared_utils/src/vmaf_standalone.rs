@@ -18,17 +18,17 @@ pub fn calculate_ms_ssim_standalone(reference: &Path, distorted: &Path) -> Resul
     let ref_y4m_file = tempfile::Builder::new()
         .prefix("vmaf_ref_")
         .suffix(".y4m")
-        .tempfile()
+        .tempfile_in(crate::conversion::scratch_dir())
         .context("Failed to create ref temp file")?;
     let dist_y4m_file = tempfile::Builder::new()
         .prefix("vmaf_dist_")
         .suffix(".y4m")
-        .tempfile()
+        .tempfile_in(crate::conversion::scratch_dir())
         .context("Failed to create dist temp file")?;
     let json_file = tempfile::Builder::new()
         .prefix("vmaf_result_")
         .suffix(".json")
-        .tempfile()
+        .tempfile_in(crate::conversion::scratch_dir())
         .context("Failed to create json temp file")?;
 
     convert_to_y4m(reference, ref_y4m_file.path())?;
@@ -58,6 +58,7 @@ pub fn calculate_ms_ssim_standalone(reference: &Path, distorted: &Path) -> Resul
 
 fn convert_to_y4m(input: &Path, output_path: &Path) -> Result<()> {
     let status = Command::new("ffmpeg")
+        .args(crate::video::source_trim_args(input))
         .arg("-i")
         .arg(crate::safe_path_arg(input).as_ref())
         .arg("-pix_fmt")
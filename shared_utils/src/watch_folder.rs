@@ -0,0 +1,188 @@
+//! Watch Mode (`watch DIR`)
+//!
+//! Converts files as they appear in an ingest folder. `notify` reports creations and
+//! writes; a file is only handed on once its size has stayed the same for the settle
+//! window, so a download or copy that is still in progress is never picked up.
+
+use anyhow::{Context, Result};
+use notify::{EventKind, RecursiveMode, Watcher};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::time::{Duration, Instant};
+
+/// Seconds a new file's size must stay unchanged before it is converted.
+pub const DEFAULT_SETTLE_SECS: u64 = 3;
+
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Files reported by the watcher that have not settled yet: last seen size and when it changed.
+#[derive(Debug, Default)]
+pub struct SettleTracker {
+    pending: HashMap<PathBuf, (Option<u64>, Instant)>,
+}
+
+impl SettleTracker {
+    /// Record a create/modify event. Every event restarts the settle window.
+    pub fn touch(&mut self, path: PathBuf, now: Instant) {
+        let entry = self.pending.entry(path).or_insert((None, now));
+        entry.1 = now;
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    /// Remove and return the files whose size has not changed for `settle`.
+    /// Files that disappeared (`size_of` → None) are dropped; empty files keep waiting.
+    pub fn take_settled<S>(&mut self, now: Instant, settle: Duration, size_of: S) -> Vec<PathBuf>
+    where
+        S: Fn(&Path) -> Option<u64>,
+    {
+        let mut ready = Vec::new();
+        self.pending.retain(|path, (last_size, since)| {
+            let Some(size) = size_of(path) else {
+                return false;
+            };
+            if *last_size != Some(size) {
+                *last_size = Some(size);
+                *since = now;
+                return true;
+            }
+            if size > 0 && now.duration_since(*since) >= settle {
+                ready.push(path.clone());
+                return false;
+            }
+            true
+        });
+        ready.sort();
+        ready
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct WatchOptions {
+    pub recursive: bool,
+    pub settle: Duration,
+    pub extensions: &'static [&'static str],
+    /// Events under these directories are ignored (output and done folders inside DIR).
+    pub ignore: Vec<PathBuf>,
+}
+
+fn is_candidate(path: &Path, options: &WatchOptions) -> bool {
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy())
+        .unwrap_or_default();
+    // Hidden files and our own temp outputs (`<stem>.tmp.<id>.<ext>`).
+    if crate::common_utils::is_hidden_file(path) || name.contains(".tmp.") {
+        return false;
+    }
+    if options.ignore.iter().any(|dir| path.starts_with(dir)) {
+        return false;
+    }
+    crate::common_utils::has_extension(path, options.extensions)
+}
+
+/// Block forever, calling `on_ready` for every new file under `dir` once it has settled.
+/// Files already present when the watch starts are left alone.
+pub fn watch_directory<F>(dir: &Path, options: &WatchOptions, mut on_ready: F) -> Result<()>
+where
+    F: FnMut(&Path),
+{
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(tx).context("Failed to start file watcher")?;
+    let mode = if options.recursive {
+        RecursiveMode::Recursive
+    } else {
+        RecursiveMode::NonRecursive
+    };
+    watcher
+        .watch(dir, mode)
+        .with_context(|| format!("Failed to watch {}", dir.display()))?;
+
+    let mut tracker = SettleTracker::default();
+    loop {
+        match rx.recv_timeout(POLL_INTERVAL) {
+            Ok(Ok(event)) => {
+                if matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_)) {
+                    for path in event.paths {
+                        if is_candidate(&path, options) {
+                            tracker.touch(path, Instant::now());
+                        }
+                    }
+                }
+            }
+            Ok(Err(e)) => {
+                crate::log_eprintln!("⚠️ [Watch] {}", e);
+            }
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => {
+                anyhow::bail!("File watcher stopped unexpectedly");
+            }
+        }
+
+        if tracker.is_empty() {
+            continue;
+        }
+        let ready = tracker.take_settled(Instant::now(), options.settle, |path| {
+            std::fs::metadata(path)
+                .ok()
+                .filter(|m| m.is_file())
+                .map(|m| m.len())
+        });
+        for path in ready {
+            crate::ctrlc_guard::wait_if_prompt_active();
+            on_ready(&path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    #[test]
+    fn test_settle_tracker_waits_for_stable_size() {
+        let start = Instant::now();
+        let settle = Duration::from_secs(3);
+        let sizes = RefCell::new(HashMap::from([(PathBuf::from("a.mp4"), 100u64)]));
+        let size_of = |p: &Path| sizes.borrow().get(p).copied();
+
+        let mut tracker = SettleTracker::default();
+        tracker.touch(PathBuf::from("a.mp4"), start);
+        tracker.touch(PathBuf::from("gone.mp4"), start);
+
+        // First poll records the size; the vanished file is dropped.
+        assert!(tracker.take_settled(start, settle, size_of).is_empty());
+        // Still growing after 2s: the window restarts.
+        sizes.borrow_mut().insert(PathBuf::from("a.mp4"), 500);
+        let t = start + Duration::from_secs(2);
+        assert!(tracker.take_settled(t, settle, size_of).is_empty());
+        // Unchanged, but only 2s since the last change.
+        let t = start + Duration::from_secs(4);
+        assert!(tracker.take_settled(t, settle, size_of).is_empty());
+        let t = start + Duration::from_secs(5);
+        assert_eq!(
+            tracker.take_settled(t, settle, size_of),
+            vec![PathBuf::from("a.mp4")]
+        );
+        assert!(tracker.is_empty());
+    }
+
+    #[test]
+    fn test_is_candidate_filters() {
+        let options = WatchOptions {
+            recursive: true,
+            settle: Duration::from_secs(DEFAULT_SETTLE_SECS),
+            extensions: &["mp4", "mov"],
+            ignore: vec![PathBuf::from("/in/out")],
+        };
+        assert!(is_candidate(Path::new("/in/clip.MP4"), &options));
+        assert!(!is_candidate(Path::new("/in/clip.part"), &options));
+        assert!(!is_candidate(Path::new("/in/.clip.mp4"), &options));
+        assert!(!is_candidate(Path::new("/in/clip.tmp.123.mp4"), &options));
+        assert!(!is_candidate(Path::new("/in/out/clip.mp4"), &options));
+    }
+}
@@ -35,6 +35,9 @@ pub struct X265Config {
     pub color_trc: Option<String>,
     /// HDR matrix coefficients (e.g. "bt2020nc")
     pub colorspace: Option<String>,
+    /// Source color range, "pc" (full) or "tv" (limited); kept through the pipe and signalled
+    /// in the bitstream
+    pub color_range: Option<String>,
     /// HDR10 mastering display metadata in ffmpeg format
     pub mastering_display: Option<String>,
     /// HDR10 content light level: "MaxCLL,MaxFALL"
@@ -45,6 +48,10 @@ pub struct X265Config {
     pub has_subtitles: bool,
     /// Codec name of the first subtitle stream
     pub subtitle_codec: Option<String>,
+    /// `--strip-metadata`: the mux step drops the source's container metadata
+    pub strip: crate::metadata::StripPolicy,
+    /// `--deterministic`: pinned x265 threading and a bit-exact mux
+    pub deterministic: bool,
 }
 
 impl Default for X265Config {
@@ -59,11 +66,14 @@ impl Default for X265Config {
             color_primaries: None,
             color_trc: None,
             colorspace: None,
+            color_range: None,
             mastering_display: None,
             max_cll: None,
             audio_codec: None,
             has_subtitles: false,
             subtitle_codec: None,
+            strip: crate::metadata::StripPolicy::default(),
+            deterministic: false,
         }
     }
 }
@@ -86,7 +96,7 @@ pub fn encode_with_x265(
 
     let hevc_temp = tempfile::Builder::new()
         .suffix(".hevc")
-        .tempfile()
+        .tempfile_in(crate::conversion::scratch_dir())
         .context("Failed to create temporary HEVC file")?;
     let hevc_file = hevc_temp.path().to_path_buf();
 
@@ -128,19 +138,7 @@ fn encode_y4m_direct(
     );
 
     let output = Command::new("x265")
-        .arg("--y4m")
-        .arg("--input")
-        .arg(crate::safe_path_arg(input).as_ref())
-        .arg("--output")
-        .arg(crate::safe_path_arg(hevc_output).as_ref())
-        .arg("--crf")
-        .arg(format!("{:.1}", config.crf))
-        .arg("--preset")
-        .arg(&config.preset)
-        .arg("--pools")
-        .arg(config.threads.to_string())
-        .arg("--log-level")
-        .arg("error")
+        .args(x265_direct_args(input, hevc_output, config))
         .output()
         .context("Failed to run x265")?;
 
@@ -171,6 +169,143 @@ fn encode_y4m_direct(
     Ok(true)
 }
 
+fn x265_direct_args(input: &Path, hevc_output: &Path, config: &X265Config) -> Vec<String> {
+    let mut args = vec![
+        "--y4m".to_string(),
+        "--input".to_string(),
+        crate::safe_path_arg(input).into_owned(),
+        "--output".to_string(),
+        crate::safe_path_arg(hevc_output).into_owned(),
+        "--crf".to_string(),
+        format!("{:.1}", config.crf),
+        "--preset".to_string(),
+        config.preset.clone(),
+        "--pools".to_string(),
+        config.threads.to_string(),
+        "--log-level".to_string(),
+        "error".to_string(),
+    ];
+    args.extend(x265_range_args(config));
+    args.extend(crate::video_explorer::deterministic_x265_cli_args(
+        config.deterministic,
+    ));
+    args
+}
+
+/// `--range` matching the source, so a full-range source is neither squeezed nor mislabelled.
+fn x265_range_args(config: &X265Config) -> Vec<String> {
+    let range = match config.color_range.as_deref() {
+        Some("pc") => "full",
+        Some("tv") => "limited",
+        _ => return Vec::new(),
+    };
+    vec!["--range".to_string(), range.to_string()]
+}
+
+/// ffmpeg side of the pipe: decode `input` (through `vf_args`) to y4m on stdout.
+fn y4m_decode_args(input: &Path, config: &X265Config, vf_args: &[String]) -> Vec<String> {
+    let mut args = vec!["-y".to_string()];
+    args.extend(crate::video::source_trim_args(input));
+    args.extend([
+        "-i".to_string(),
+        crate::safe_path_arg(input).into_owned(),
+        "-f".to_string(),
+        "yuv4mpegpipe".to_string(),
+    ]);
+    args.extend(vf_args.iter().cloned());
+    args.extend(crate::video_explorer::frame_rate_mode_encode_args());
+    args.extend(["-pix_fmt".to_string(), config.pix_fmt.clone()]);
+    // Without it the scaler turns full-range samples into limited range on the way to y4m.
+    args.extend(crate::color_range_args(config.color_range.as_deref()));
+    args.push("-".to_string());
+    args
+}
+
+/// x265 side of the pipe: y4m on stdin to a raw HEVC bitstream.
+fn x265_pipe_args(hevc_output: &Path, config: &X265Config) -> Vec<String> {
+    let mut args = vec![
+        "--y4m".to_string(),
+        "--input".to_string(),
+        "-".to_string(),
+        "--output".to_string(),
+        crate::safe_path_arg(hevc_output).into_owned(),
+        "--crf".to_string(),
+        format!("{:.1}", config.crf),
+        "--preset".to_string(),
+        config.preset.clone(),
+        "--pools".to_string(),
+        config.threads.to_string(),
+        "--log-level".to_string(),
+        "error".to_string(),
+    ];
+    args.extend(crate::video_explorer::deterministic_x265_cli_args(
+        config.deterministic,
+    ));
+
+    // 10-bit input (source depth or --force-10bit) must also leave x265 as 10-bit.
+    if config.pix_fmt == "yuv420p10le" {
+        args.extend(["--output-depth", "10", "--profile", "main10"].map(String::from));
+    } else if config.pix_fmt.contains("10") {
+        // 4:0:0 (--auto-grayscale) and 4:2:2/4:4:4 (--pix-fmt): x265 picks the matching
+        // profile from the y4m header.
+        args.extend(["--output-depth", "10"].map(String::from));
+    }
+    args.extend(x265_range_args(config));
+
+    // HDR-specific x265 options: enabled when the source is 10-bit or has explicit HDR metadata.
+    let is_hdr_content = config.pix_fmt.contains("10")
+        || config.mastering_display.is_some()
+        || config.max_cll.is_some()
+        || matches!(
+            config.color_trc.as_deref(),
+            Some("smpte2084") | Some("arib-std-b67")
+        );
+    if is_hdr_content {
+        args.extend(["--hdr10-opt", "--repeat-headers"].map(String::from));
+
+        let tagged = [
+            ("--colorprim", &config.color_primaries),
+            ("--transfer", &config.color_trc),
+            ("--colormatrix", &config.colorspace),
+            ("--master-display", &config.mastering_display),
+            ("--max-cll", &config.max_cll),
+        ];
+        for (flag, value) in tagged {
+            if let Some(value) = value {
+                args.extend([flag.to_string(), value.clone()]);
+            }
+        }
+    }
+    args
+}
+
+/// The commands [`encode_with_x265`] runs, one shell line per step, for `--print-command`. The
+/// intermediate bitstream is shown as `<output>.hevc` (a scratch file in a real run).
+pub fn x265_command_preview(
+    input: &Path,
+    output: &Path,
+    config: &X265Config,
+    vf_args: &[String],
+) -> Vec<String> {
+    let hevc_file = output.with_extension("hevc");
+    let is_y4m = input
+        .extension()
+        .is_some_and(|e| e.eq_ignore_ascii_case("y4m"));
+    let encode = if is_y4m {
+        crate::shell_command_string("x265", &x265_direct_args(input, &hevc_file, config))
+    } else {
+        format!(
+            "{} | {}",
+            crate::shell_command_string("ffmpeg", &y4m_decode_args(input, config, vf_args)),
+            crate::shell_command_string("x265", &x265_pipe_args(&hevc_file, config))
+        )
+    };
+    vec![
+        encode,
+        crate::shell_command_string("ffmpeg", &mux_args(input, &hevc_file, output, config)),
+    ]
+}
+
 fn encode_to_hevc(
     input: &Path,
     hevc_output: &Path,
@@ -191,66 +326,12 @@ fn encode_to_hevc(
 
     let mut ffmpeg_cmd = Command::new("ffmpeg");
     ffmpeg_cmd
-        .arg("-y")
-        .arg("-i")
-        .arg(crate::safe_path_arg(input).as_ref())
-        .arg("-f")
-        .arg("yuv4mpegpipe");
-
-    for arg in vf_args {
-        ffmpeg_cmd.arg(arg);
-    }
-
-    ffmpeg_cmd
-        .arg("-pix_fmt")
-        .arg(&config.pix_fmt)
-        .arg("-")
+        .args(y4m_decode_args(input, config, vf_args))
         .stdout(Stdio::piped())
         .stderr(Stdio::piped());
 
     let mut x265_cmd = Command::new("x265");
-    x265_cmd
-        .arg("--y4m")
-        .arg("--input")
-        .arg("-")
-        .arg("--output")
-        .arg(crate::safe_path_arg(hevc_output).as_ref())
-        .arg("--crf")
-        .arg(format!("{:.1}", config.crf))
-        .arg("--preset")
-        .arg(&config.preset)
-        .arg("--pools")
-        .arg(config.threads.to_string())
-        .arg("--log-level")
-        .arg("error");
-
-    // HDR-specific x265 options: enabled when the source is 10-bit or has explicit HDR metadata.
-    let is_hdr_content = config.pix_fmt.contains("10")
-        || config.mastering_display.is_some()
-        || config.max_cll.is_some()
-        || matches!(
-            config.color_trc.as_deref(),
-            Some("smpte2084") | Some("arib-std-b67")
-        );
-    if is_hdr_content {
-        x265_cmd.arg("--hdr10-opt").arg("--repeat-headers");
-
-        if let Some(ref cp) = config.color_primaries {
-            x265_cmd.arg("--colorprim").arg(cp);
-        }
-        if let Some(ref trc) = config.color_trc {
-            x265_cmd.arg("--transfer").arg(trc);
-        }
-        if let Some(ref cs) = config.colorspace {
-            x265_cmd.arg("--colormatrix").arg(cs);
-        }
-        if let Some(ref md) = config.mastering_display {
-            x265_cmd.arg("--master-display").arg(md);
-        }
-        if let Some(ref cll) = config.max_cll {
-            x265_cmd.arg("--max-cll").arg(cll);
-        }
-    }
+    x265_cmd.args(x265_pipe_args(hevc_output, config));
 
     x265_cmd
         .stdin(Stdio::piped())
@@ -264,6 +345,10 @@ fn encode_to_hevc(
     let mut x265_child = x265_cmd
         .spawn()
         .context("Failed to spawn x265 encode process")?;
+    let watchers = [
+        crate::cancellation::ChildWatcher::for_current(&ffmpeg_child),
+        crate::cancellation::ChildWatcher::for_current(&x265_child),
+    ];
 
     let ffmpeg_stderr_thread = ffmpeg_child.stderr.take().map(|stderr| {
         std::thread::spawn(move || {
@@ -331,6 +416,8 @@ fn encode_to_hevc(
 
         let x265_status = x265_child.wait().context("Failed to wait for x265")?;
         let ffmpeg_status = ffmpeg_child.wait().context("Failed to wait for ffmpeg")?;
+        drop(watchers);
+        crate::cancellation::check()?;
 
         let duration = start_time.elapsed();
 
@@ -422,64 +509,73 @@ fn is_image_container(path: &Path) -> bool {
     )
 }
 
-fn mux_hevc_to_container(
+fn mux_args(
     original_input: &Path,
     hevc_file: &Path,
     output: &Path,
     config: &X265Config,
-) -> Result<()> {
-    let start_time = std::time::Instant::now();
-
+) -> Vec<String> {
     // Image containers (AVIF, HEIC, GIF, WebP, …) cannot carry audio streams.
     // Attempting to demux audio from them causes "Not yet implemented in FFmpeg".
     let input_is_image = is_image_container(original_input);
 
-    let mut cmd = Command::new("ffmpeg");
-    cmd.arg("-y")
-        .arg("-i")
-        .arg(crate::safe_path_arg(hevc_file).as_ref());
+    let mut args = vec![
+        "-y".to_string(),
+        "-i".to_string(),
+        crate::safe_path_arg(hevc_file).into_owned(),
+    ];
 
     if config.preserve_audio && !input_is_image {
-        cmd.arg("-i")
-            .arg(crate::safe_path_arg(original_input).as_ref());
+        args.extend(crate::video::source_trim_args(original_input));
+        args.extend([
+            "-i".to_string(),
+            crate::safe_path_arg(original_input).into_owned(),
+        ]);
         // Map: video from HEVC bitstream (input 0), all audio + subtitle from original (input 1)
-        cmd.arg("-map").arg("0:v:0");
-        cmd.arg("-map").arg("1:a?");
-        cmd.arg("-c:v").arg("copy");
+        args.extend(["-map", "0:v:0", "-map", "1:a?", "-c:v", "copy"].map(String::from));
 
-        // Audio: copy when compatible, transcode only for incompatible codecs
+        // Audio: copy when compatible, transcode for incompatible codecs or `--audio-codec`
         let audio_args =
-            crate::audio_args_for_container(config.audio_codec.as_deref(), &config.container);
-        for arg in &audio_args {
-            // Skip -an since we already have -map 1:a?
-            if arg != "-an" {
-                cmd.arg(arg);
-            }
-        }
+            crate::audio_args_for_encode(config.audio_codec.as_deref(), None, &config.container);
+        // Skip -an since we already have -map 1:a?
+        args.extend(audio_args.into_iter().filter(|arg| arg != "-an"));
 
         // Subtitles: map and copy/transcode as appropriate for container
         if config.has_subtitles {
-            cmd.arg("-map").arg("1:s?");
-            let sub_args = crate::subtitle_args_for_container(
+            args.extend(["-map", "1:s?"].map(String::from));
+            args.extend(crate::subtitle_args_for_container(
                 true,
                 config.subtitle_codec.as_deref(),
                 &config.container,
-            );
-            for arg in sub_args {
-                cmd.arg(arg);
-            }
+            ));
         }
     } else {
         // No audio: either disabled or source is an image format with no audio streams.
-        cmd.arg("-c:v").arg("copy").arg("-an");
+        args.extend(["-c:v", "copy", "-an"].map(String::from));
     }
 
     if config.container == "mp4" || config.container == "mov" {
-        cmd.arg("-tag:v").arg("hvc1");
-        cmd.arg("-movflags").arg("+faststart");
+        args.extend(["-tag:v", "hvc1", "-movflags", "+faststart"].map(String::from));
     }
 
-    cmd.arg(crate::safe_path_arg(output).as_ref())
+    args.extend(config.strip.ffmpeg_args());
+    args.extend(crate::video_explorer::deterministic_args(
+        config.deterministic,
+    ));
+    args.push(crate::safe_path_arg(output).into_owned());
+    args
+}
+
+fn mux_hevc_to_container(
+    original_input: &Path,
+    hevc_file: &Path,
+    output: &Path,
+    config: &X265Config,
+) -> Result<()> {
+    let start_time = std::time::Instant::now();
+
+    let mut cmd = Command::new("ffmpeg");
+    cmd.args(mux_args(original_input, hevc_file, output, config))
         .stdout(Stdio::null())
         .stderr(Stdio::piped());
 
@@ -539,4 +635,52 @@ mod tests {
             println!("⚠️  x265 not found - install with: brew install x265");
         }
     }
+
+    #[test]
+    fn test_x265_command_preview() {
+        let config = X265Config {
+            crf: 21.5,
+            preset: "slow".to_string(),
+            threads: 4,
+            pix_fmt: "yuv420p10le".to_string(),
+            color_trc: Some("smpte2084".to_string()),
+            ..X265Config::default()
+        };
+        let vf_args = vec!["-vf".to_string(), "scale=1920:-2".to_string()];
+        let lines = x265_command_preview(
+            Path::new("/in/My Clip.mov"),
+            Path::new("/out/My Clip.mp4"),
+            &config,
+            &vf_args,
+        );
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with(
+            "ffmpeg -y -i '/in/My Clip.mov' -f yuv4mpegpipe -vf scale=1920:-2 -pix_fmt yuv420p10le - | x265 --y4m --input - --output '/out/My Clip.hevc' --crf 21.5 --preset slow --pools 4"
+        ));
+        assert!(lines[0].contains("--output-depth 10 --profile main10 --hdr10-opt"));
+        assert!(lines[0].ends_with("--transfer smpte2084"));
+        assert!(lines[1].starts_with("ffmpeg -y -i '/out/My Clip.hevc' -i '/in/My Clip.mov'"));
+        assert!(lines[1].ends_with("'/out/My Clip.mp4'"));
+    }
+
+    #[test]
+    fn test_full_range_source_stays_full_range() {
+        let config = X265Config {
+            color_range: Some("pc".to_string()),
+            ..X265Config::default()
+        };
+        let decode = y4m_decode_args(Path::new("/in/clip.mov"), &config, &[]).join(" ");
+        assert!(decode.ends_with("-pix_fmt yuv420p -color_range pc -"));
+        let encode = x265_pipe_args(Path::new("/out/clip.hevc"), &config).join(" ");
+        assert!(encode.ends_with("--range full"));
+
+        let limited = X265Config {
+            color_range: Some("tv".to_string()),
+            pix_fmt: "yuv444p10le".to_string(),
+            ..X265Config::default()
+        };
+        let encode = x265_pipe_args(Path::new("/out/clip.hevc"), &limited).join(" ");
+        assert!(encode.contains("--output-depth 10 --range limited"));
+        assert!(!encode.contains("main10"));
+    }
 }
@@ -1,7 +1,10 @@
 use anyhow::{bail, Context, Result};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
 use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::RwLock;
 use walkdir::WalkDir;
 
 const EXCLUDED_EXTENSIONS: &[&str] = &[
@@ -63,6 +66,314 @@ pub struct MergeResult {
     pub success: bool,
     pub message: String,
     pub match_strategy: Option<String>,
+    /// Lowercase extension of the sidecar that matched (`xmp`, `thm`, …).
+    pub sidecar_type: String,
+    /// Tags that differ between several sidecars of the same media file. Reported on the
+    /// highest-precedence sidecar's result only.
+    pub conflicts: Vec<XmpConflict>,
+}
+
+/// One tag with different values in two or more sidecars of the same media file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct XmpConflict {
+    /// ExifTool tag name with family-1 group, e.g. `XMP-dc:Title`.
+    pub tag: String,
+    /// Each sidecar's value, highest precedence first; the first one is what ends up written.
+    pub values: Vec<(PathBuf, String)>,
+}
+
+impl fmt::Display for XmpConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:", self.tag)?;
+        for (i, (path, value)) in self.values.iter().enumerate() {
+            let name = path.file_name().unwrap_or_default().to_string_lossy();
+            let sep = if i == 0 { " " } else { " vs " };
+            write!(f, "{}\"{}\" ({})", sep, value, name)?;
+        }
+        Ok(())
+    }
+}
+
+/// What to do with a source's XMP sidecar once the output exists (`--xmp-mode`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SidecarMode {
+    /// Merge the sidecar into the output with ExifTool.
+    #[default]
+    Embed,
+    /// Copy the sidecar to `<output-stem>.xmp` next to the output; nothing is embedded.
+    CopyBeside,
+    /// Embed and copy.
+    Both,
+}
+
+impl SidecarMode {
+    pub fn embeds(self) -> bool {
+        matches!(self, SidecarMode::Embed | SidecarMode::Both)
+    }
+
+    pub fn copies(self) -> bool {
+        matches!(self, SidecarMode::CopyBeside | SidecarMode::Both)
+    }
+}
+
+/// Parse `--xmp-mode`: `embed`, `copy` or `both`.
+pub fn parse_sidecar_mode(value: &str) -> std::result::Result<SidecarMode, String> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "embed" => Ok(SidecarMode::Embed),
+        "copy" | "copy-beside" => Ok(SidecarMode::CopyBeside),
+        "both" => Ok(SidecarMode::Both),
+        other => Err(format!(
+            "Invalid XMP mode '{}' (expected embed, copy or both)",
+            other
+        )),
+    }
+}
+
+static SIDECAR_MODE: AtomicU8 = AtomicU8::new(0);
+
+/// Process-wide sidecar mode used by the conversion metadata step.
+pub fn set_sidecar_mode(mode: SidecarMode) {
+    let raw = match mode {
+        SidecarMode::Embed => 0,
+        SidecarMode::CopyBeside => 1,
+        SidecarMode::Both => 2,
+    };
+    SIDECAR_MODE.store(raw, Ordering::Relaxed);
+}
+
+pub fn sidecar_mode() -> SidecarMode {
+    match SIDECAR_MODE.load(Ordering::Relaxed) {
+        1 => SidecarMode::CopyBeside,
+        2 => SidecarMode::Both,
+        _ => SidecarMode::Embed,
+    }
+}
+
+/// How sidecar values combine with tags already in the media file, and which sidecar wins
+/// when several match one file (`--xmp-merge-policy`). Between sidecars the more specific
+/// one (`photo.jpg.xmp` over `photo.xmp`) always takes precedence.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum XmpMergePolicy {
+    /// Tags already in the media file are kept; sidecars only add tags it does not have.
+    PreferExisting,
+    /// Sidecar values overwrite the media file's tags.
+    #[default]
+    PreferSidecar,
+    /// Like `PreferSidecar`, but list tags (keywords, subjects, …) are appended to the
+    /// existing values instead of replacing them.
+    MergeUnion,
+}
+
+impl XmpMergePolicy {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            XmpMergePolicy::PreferExisting => "prefer-existing",
+            XmpMergePolicy::PreferSidecar => "prefer-sidecar",
+            XmpMergePolicy::MergeUnion => "merge-union",
+        }
+    }
+
+    /// ExifTool option that copies a sidecar's tags.
+    fn copy_option(self) -> &'static str {
+        match self {
+            XmpMergePolicy::MergeUnion => "-addtagsfromfile",
+            _ => "-tagsfromfile",
+        }
+    }
+
+    /// Write order for `sidecars` (highest precedence first). Overwriting policies write the
+    /// winner last; create-only `PreferExisting` writes it first.
+    fn write_order(self, sidecars: &[PathBuf]) -> Vec<&PathBuf> {
+        match self {
+            XmpMergePolicy::PreferExisting => sidecars.iter().collect(),
+            _ => sidecars.iter().rev().collect(),
+        }
+    }
+}
+
+/// Parse `--xmp-merge-policy`: `prefer-existing`, `prefer-sidecar` or `merge-union`.
+pub fn parse_xmp_merge_policy(value: &str) -> std::result::Result<XmpMergePolicy, String> {
+    match value.trim().to_ascii_lowercase().replace('_', "-").as_str() {
+        "prefer-existing" | "existing" => Ok(XmpMergePolicy::PreferExisting),
+        "prefer-sidecar" | "sidecar" => Ok(XmpMergePolicy::PreferSidecar),
+        "merge-union" | "union" => Ok(XmpMergePolicy::MergeUnion),
+        other => Err(format!(
+            "Invalid XMP merge policy '{}' (expected prefer-existing, prefer-sidecar or merge-union)",
+            other
+        )),
+    }
+}
+
+static MERGE_POLICY: AtomicU8 = AtomicU8::new(1);
+
+/// Process-wide merge policy used by the conversion metadata step.
+pub fn set_xmp_merge_policy(policy: XmpMergePolicy) {
+    let raw = match policy {
+        XmpMergePolicy::PreferExisting => 0,
+        XmpMergePolicy::PreferSidecar => 1,
+        XmpMergePolicy::MergeUnion => 2,
+    };
+    MERGE_POLICY.store(raw, Ordering::Relaxed);
+}
+
+pub fn xmp_merge_policy() -> XmpMergePolicy {
+    match MERGE_POLICY.load(Ordering::Relaxed) {
+        0 => XmpMergePolicy::PreferExisting,
+        2 => XmpMergePolicy::MergeUnion,
+        _ => XmpMergePolicy::PreferSidecar,
+    }
+}
+
+static SIDECAR_EXTENSIONS: RwLock<Vec<String>> = RwLock::new(Vec::new());
+
+/// Parse `--sidecar-extensions`: a comma-separated list such as `xmp,thm,yml`. Leading dots
+/// are dropped and case is ignored; the order given is the precedence between types.
+pub fn parse_sidecar_extensions(value: &str) -> std::result::Result<Vec<String>, String> {
+    let mut extensions: Vec<String> = Vec::new();
+    for raw in value.split(',') {
+        let ext = raw.trim().trim_start_matches('.').to_ascii_lowercase();
+        if ext.is_empty() {
+            continue;
+        }
+        if !ext.chars().all(|c| c.is_ascii_alphanumeric()) {
+            return Err(format!("Invalid sidecar extension '{}'", raw.trim()));
+        }
+        if !extensions.contains(&ext) {
+            extensions.push(ext);
+        }
+    }
+    if extensions.is_empty() {
+        return Err("Expected at least one sidecar extension (e.g. xmp,thm)".to_string());
+    }
+    Ok(extensions)
+}
+
+/// Process-wide sidecar extensions used by the conversion metadata step and file copier.
+pub fn set_sidecar_extensions(extensions: Vec<String>) {
+    *SIDECAR_EXTENSIONS
+        .write()
+        .unwrap_or_else(|e| e.into_inner()) = extensions;
+}
+
+/// Configured sidecar extensions, lowercase and in precedence order; `xmp` when unset.
+pub fn sidecar_extensions() -> Vec<String> {
+    let configured = SIDECAR_EXTENSIONS.read().unwrap_or_else(|e| e.into_inner());
+    if configured.is_empty() {
+        crate::file_copier::SIDECAR_EXTENSIONS
+            .iter()
+            .map(|ext| ext.to_string())
+            .collect()
+    } else {
+        configured.clone()
+    }
+}
+
+/// Whether `ext` (any case, no dot) is one of the configured sidecar extensions.
+pub fn is_sidecar_extension(ext: &str) -> bool {
+    let ext = ext.to_ascii_lowercase();
+    sidecar_extensions().contains(&ext)
+}
+
+/// Lowercase extension of `sidecar`, reported as `MergeResult::sidecar_type`.
+fn sidecar_type_of(sidecar: &Path) -> String {
+    sidecar
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default()
+}
+
+/// Precedence of `xmp` as a sidecar of `media` (lower wins): `photo.jpg.xmp` names the exact
+/// file, `photo.xmp` only the stem, anything else was matched heuristically. The sidecar's
+/// own extension does not matter, so `photo.jpg.thm` ranks with `photo.jpg.xmp`.
+fn sidecar_precedence(media: &Path, xmp: &Path) -> u8 {
+    let lower =
+        |p: Option<&std::ffi::OsStr>| p.unwrap_or_default().to_string_lossy().to_lowercase();
+    let xmp_stem = lower(xmp.file_stem());
+    if xmp_stem == lower(media.file_name()) {
+        0
+    } else if xmp_stem == lower(media.file_stem()) {
+        1
+    } else {
+        2
+    }
+}
+
+/// Sidecars next to `media` with any of the configured `sidecar_extensions`.
+pub fn find_sidecars(media: &Path) -> Vec<PathBuf> {
+    find_sidecars_with(media, &sidecar_extensions())
+}
+
+/// Sidecars next to `media`, highest precedence first: `<name>.<ext>.<sidecar>` for each of
+/// `extensions` in order, then `<stem>.<sidecar>` (either extension case). The same file
+/// reached through two spellings is listed once.
+pub fn find_sidecars_with(media: &Path, extensions: &[String]) -> Vec<PathBuf> {
+    let stem = media.file_stem().and_then(|s| s.to_str()).unwrap_or("");
+    let ext = media.extension().and_then(|e| e.to_str()).unwrap_or("");
+    let parent = media.parent().unwrap_or(Path::new("."));
+
+    let mut candidates = Vec::new();
+    if !ext.is_empty() {
+        for sidecar in extensions {
+            candidates.push(parent.join(format!("{}.{}.{}", stem, ext, sidecar)));
+            candidates.push(parent.join(format!("{}.{}.{}", stem, ext.to_lowercase(), sidecar)));
+        }
+    }
+    for sidecar in extensions {
+        candidates.push(parent.join(format!("{}.{}", stem, sidecar)));
+        candidates.push(parent.join(format!("{}.{}", stem, sidecar.to_uppercase())));
+    }
+
+    let mut seen = Vec::new();
+    let mut sidecars = Vec::new();
+    for candidate in candidates {
+        if !candidate.is_file() {
+            continue;
+        }
+        let key = candidate
+            .canonicalize()
+            .unwrap_or_else(|_| candidate.clone());
+        if !seen.contains(&key) {
+            seen.push(key);
+            sidecars.push(candidate);
+        }
+    }
+    sidecars
+}
+
+/// Tags present in more than one sidecar with different values. `tag_sets` is in precedence
+/// order; file and tool pseudo-tags are ignored.
+pub fn find_conflicts(
+    tag_sets: &[(PathBuf, serde_json::Map<String, serde_json::Value>)],
+) -> Vec<XmpConflict> {
+    let mut by_tag: BTreeMap<&str, Vec<(PathBuf, String)>> = BTreeMap::new();
+    for (path, tags) in tag_sets {
+        for (tag, value) in tags {
+            if tag == "SourceFile"
+                || ["File:", "System:", "ExifTool:"]
+                    .iter()
+                    .any(|group| tag.starts_with(group))
+            {
+                continue;
+            }
+            let value = match value {
+                serde_json::Value::String(s) => s.clone(),
+                other => other.to_string(),
+            };
+            by_tag
+                .entry(tag.as_str())
+                .or_default()
+                .push((path.clone(), value));
+        }
+    }
+
+    by_tag
+        .into_iter()
+        .filter(|(_, values)| values.iter().any(|(_, v)| *v != values[0].1))
+        .map(|(tag, values)| XmpConflict {
+            tag: tag.to_string(),
+            values,
+        })
+        .collect()
 }
 
 #[derive(Debug, Clone)]
@@ -71,6 +382,10 @@ pub struct XmpMergerConfig {
     pub overwrite_original: bool,
     pub preserve_timestamps: bool,
     pub verbose: bool,
+    pub sidecar_mode: SidecarMode,
+    pub merge_policy: XmpMergePolicy,
+    /// Lowercase sidecar extensions to look for, in precedence order.
+    pub sidecar_extensions: Vec<String>,
 }
 
 impl Default for XmpMergerConfig {
@@ -80,6 +395,12 @@ impl Default for XmpMergerConfig {
             overwrite_original: true,
             preserve_timestamps: true,
             verbose: false,
+            sidecar_mode: SidecarMode::Embed,
+            merge_policy: XmpMergePolicy::PreferSidecar,
+            sidecar_extensions: crate::file_copier::SIDECAR_EXTENSIONS
+                .iter()
+                .map(|ext| ext.to_string())
+                .collect(),
         }
     }
 }
@@ -105,10 +426,22 @@ impl XmpMerger {
         Ok(())
     }
 
+    /// Extension of `path` if it is one of the configured sidecar types.
+    fn sidecar_type(&self, path: &Path) -> Option<String> {
+        let ext = sidecar_type_of(path);
+        self.config.sidecar_extensions.contains(&ext).then_some(ext)
+    }
+
+    /// Candidate media extension: not a document/archive type and not a sidecar type.
+    fn is_media_extension(&self, ext: &str) -> bool {
+        is_potential_media(ext) && !self.config.sidecar_extensions.iter().any(|s| s == ext)
+    }
+
+    /// Every file under `dir` with one of the configured sidecar extensions.
     pub fn find_xmp_files(&self, dir: &Path) -> Result<Vec<PathBuf>> {
         let mut xmp_files = Vec::new();
 
-        for entry in WalkDir::new(dir).follow_links(true) {
+        for entry in crate::batch::walk_dir(dir, true) {
             let entry = match entry {
                 Ok(entry) => entry,
                 Err(err) => {
@@ -120,11 +453,7 @@ impl XmpMerger {
             };
 
             let path = entry.path();
-            if path.is_file()
-                && path
-                    .extension()
-                    .is_some_and(|ext| ext.eq_ignore_ascii_case("xmp"))
-            {
+            if path.is_file() && self.sidecar_type(path).is_some() {
                 xmp_files.push(path.to_path_buf());
             }
         }
@@ -226,10 +555,8 @@ impl XmpMerger {
     }
 
     fn find_direct_match(&self, xmp_path: &Path) -> Option<PathBuf> {
-        let xmp_str = xmp_path.to_string_lossy();
-        if xmp_str.to_lowercase().ends_with(".xmp") {
-            let base = &xmp_str[..xmp_str.len() - 4];
-            let base_path = PathBuf::from(base);
+        if self.sidecar_type(xmp_path).is_some() {
+            let base_path = xmp_path.with_extension("");
             if base_path.exists() && base_path.is_file() {
                 return Some(base_path);
             }
@@ -261,7 +588,7 @@ impl XmpMerger {
             };
 
             if (file_stem_raw == xmp_stem_raw || file_root_stem == xmp_root_stem)
-                && is_potential_media(&ext)
+                && self.is_media_extension(&ext)
             {
                 return Some(path);
             }
@@ -287,7 +614,7 @@ impl XmpMerger {
                 None => continue,
             };
 
-            if file_stem == xmp_stem && is_potential_media(&ext) {
+            if file_stem == xmp_stem && self.is_media_extension(&ext) {
                 return Some(path);
             }
         }
@@ -316,7 +643,7 @@ impl XmpMerger {
                 None => continue,
             };
 
-            if !is_potential_media(&ext) {
+            if !self.is_media_extension(&ext) {
                 continue;
             }
 
@@ -356,7 +683,7 @@ impl XmpMerger {
                 None => continue,
             };
 
-            if !is_potential_media(&ext) {
+            if !self.is_media_extension(&ext) {
                 continue;
             }
 
@@ -414,7 +741,7 @@ impl XmpMerger {
                 None => continue,
             };
 
-            if !is_potential_media(&ext) {
+            if !self.is_media_extension(&ext) {
                 continue;
             }
 
@@ -463,7 +790,7 @@ impl XmpMerger {
                 None => continue,
             };
 
-            if !is_potential_media(&ext) {
+            if !self.is_media_extension(&ext) {
                 continue;
             }
 
@@ -522,7 +849,7 @@ impl XmpMerger {
                 Some(ext) => ext.to_string_lossy().to_lowercase(),
                 None => continue,
             };
-            if !is_potential_media(&ext) {
+            if !self.is_media_extension(&ext) {
                 continue;
             }
 
@@ -643,6 +970,122 @@ impl XmpMerger {
         Ok((None, "no_match".to_string()))
     }
 
+    /// Apply `xmp_path` to `media_path` as configured by `sidecar_mode`.
+    pub fn apply_sidecar(&self, xmp_path: &Path, media_path: &Path) -> Result<()> {
+        if self.config.sidecar_mode.copies() {
+            self.copy_sidecar_beside(xmp_path, media_path)?;
+        }
+        if self.config.sidecar_mode.embeds() {
+            self.merge_xmp(xmp_path, media_path)?;
+        }
+        Ok(())
+    }
+
+    /// Apply several sidecars of one media file (highest precedence first). Only the first is
+    /// copied beside the output; all are embedded according to `merge_policy`.
+    pub fn apply_sidecars(
+        &self,
+        sidecars: &[PathBuf],
+        media_path: &Path,
+    ) -> Result<Vec<XmpConflict>> {
+        let Some(primary) = sidecars.first() else {
+            return Ok(Vec::new());
+        };
+        if self.config.sidecar_mode.copies() {
+            self.copy_sidecar_beside(primary, media_path)?;
+        }
+        if self.config.sidecar_mode.embeds() {
+            return self.merge_sidecars(sidecars, media_path);
+        }
+        Ok(Vec::new())
+    }
+
+    /// Merge every sidecar in `sidecars` (highest precedence first) into `media_path` and
+    /// return the tags they disagree on. With one sidecar this is `merge_xmp`.
+    pub fn merge_sidecars(
+        &self,
+        sidecars: &[PathBuf],
+        media_path: &Path,
+    ) -> Result<Vec<XmpConflict>> {
+        if let [single] = sidecars {
+            self.merge_xmp(single, media_path)?;
+            return Ok(Vec::new());
+        }
+
+        let tag_sets: Vec<_> = sidecars
+            .iter()
+            .filter_map(|xmp| match self.read_sidecar_tags(xmp) {
+                Ok(tags) => Some((xmp.clone(), tags)),
+                Err(e) => {
+                    if self.config.verbose {
+                        eprintln!("  ⚠️ {}", e);
+                    }
+                    None
+                }
+            })
+            .collect();
+        let conflicts = find_conflicts(&tag_sets);
+
+        for xmp in self.config.merge_policy.write_order(sidecars) {
+            self.merge_xmp(xmp, media_path)?;
+        }
+        Ok(conflicts)
+    }
+
+    fn read_sidecar_tags(
+        &self,
+        xmp_path: &Path,
+    ) -> Result<serde_json::Map<String, serde_json::Value>> {
+        let output = Command::new("exiftool")
+            .args(["-j", "-G1", "-s"])
+            .arg(crate::safe_path_arg(xmp_path).as_ref())
+            .output()
+            .context("Failed to run exiftool")?;
+        if !output.status.success() {
+            bail!(
+                "ExifTool could not read sidecar {}: {}",
+                xmp_path.display(),
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        let parsed: Vec<serde_json::Map<String, serde_json::Value>> =
+            serde_json::from_slice(&output.stdout).with_context(|| {
+                format!("Unexpected ExifTool output for {}", xmp_path.display())
+            })?;
+        Ok(parsed.into_iter().next().unwrap_or_default())
+    }
+
+    /// Copy `xmp_path` to `<media-stem>.<sidecar-ext>` next to `media_path`, keeping the
+    /// sidecar's modification time. A no-op when that is the sidecar itself (in-place runs).
+    pub fn copy_sidecar_beside(&self, xmp_path: &Path, media_path: &Path) -> Result<PathBuf> {
+        let target = media_path.with_extension(sidecar_type_of(xmp_path));
+        let same_file = match (xmp_path.canonicalize(), target.canonicalize()) {
+            (Ok(a), Ok(b)) => a == b,
+            _ => false,
+        };
+        if same_file {
+            return Ok(target);
+        }
+
+        std::fs::copy(xmp_path, &target).with_context(|| {
+            format!(
+                "Failed to copy XMP sidecar {} → {}",
+                xmp_path.display(),
+                target.display()
+            )
+        })?;
+        if self.config.preserve_timestamps {
+            if let Ok(meta) = std::fs::metadata(xmp_path) {
+                let mtime = filetime::FileTime::from_last_modification_time(&meta);
+                let _ = filetime::set_file_mtime(&target, mtime);
+            }
+        }
+        if self.config.verbose || crate::progress_mode::is_verbose_mode() {
+            eprintln!("📋 XMP sidecar copied: {}", target.display());
+        }
+        Ok(target)
+    }
+
     pub fn merge_xmp(&self, xmp_path: &Path, media_path: &Path) -> Result<()> {
         match self.merge_xmp_core(xmp_path, media_path) {
             Ok(()) => Ok(()),
@@ -684,7 +1127,13 @@ impl XmpMerger {
             args.push("-icc_profile".to_string());
         }
 
-        args.push("-tagsfromfile".to_string());
+        // The Apple JXL path rebuilds every tag above, so there is nothing "existing" to keep.
+        if self.config.merge_policy == XmpMergePolicy::PreferExisting && !(is_jxl && apple_compat) {
+            // Create tags only; never edit ones the file already has.
+            args.push("-wm".to_string());
+            args.push("cg".to_string());
+        }
+        args.push(self.config.merge_policy.copy_option().to_string());
         args.push(crate::safe_path_arg(xmp_path).as_ref().to_string());
         args.push("-all:all".to_string());
 
@@ -734,16 +1183,14 @@ impl XmpMerger {
         media_path: &Path,
         hint_ext: Option<&str>,
     ) -> Result<()> {
-        let xmp_filename = xmp_path.file_name().and_then(|n| n.to_str()).unwrap_or("");
-
         let detected_ext = if let Some(hint) = hint_ext {
             Some(hint.to_string())
         } else {
             crate::common_utils::detect_real_extension(media_path).map(|s| s.to_string())
         };
 
-        let implied_ext = if xmp_filename.to_lowercase().ends_with(".xmp") {
-            let stem = &xmp_filename[..xmp_filename.len() - 4];
+        let implied_ext = if self.sidecar_type(xmp_path).is_some() {
+            let stem = xmp_path.file_stem().and_then(|s| s.to_str()).unwrap_or("");
             Path::new(stem).extension().and_then(|e| e.to_str())
         } else {
             None
@@ -845,58 +1292,92 @@ impl XmpMerger {
         }
     }
 
-    pub fn process_xmp(&self, xmp_path: &Path) -> MergeResult {
-        let (media_path, strategy) = match self.find_media_file(xmp_path) {
-            Ok((path, strat)) => (path, strat),
-            Err(e) => {
-                return MergeResult {
-                    xmp_path: xmp_path.to_path_buf(),
-                    media_path: None,
-                    success: false,
-                    message: format!("Error finding media: {}", e),
-                    match_strategy: None,
-                };
-            }
+    /// Find the media file for `xmp_path`; `Err` is the unmatched result to report.
+    fn match_xmp(&self, xmp_path: &Path) -> std::result::Result<(PathBuf, String), MergeResult> {
+        let unmatched = |message: String, match_strategy: Option<String>| MergeResult {
+            xmp_path: xmp_path.to_path_buf(),
+            media_path: None,
+            success: false,
+            message,
+            match_strategy,
+            sidecar_type: sidecar_type_of(xmp_path),
+            conflicts: Vec::new(),
         };
+        match self.find_media_file(xmp_path) {
+            Ok((Some(media), strategy)) => Ok((media, strategy)),
+            Ok((None, strategy)) => Err(unmatched(
+                "No matching media file found".to_string(),
+                Some(strategy),
+            )),
+            Err(e) => Err(unmatched(format!("Error finding media: {}", e), None)),
+        }
+    }
 
-        let Some(media) = media_path else {
-            return MergeResult {
-                xmp_path: xmp_path.to_path_buf(),
-                media_path: None,
-                success: false,
-                message: "No matching media file found".to_string(),
-                match_strategy: Some(strategy),
-            };
-        };
+    pub fn process_xmp(&self, xmp_path: &Path) -> MergeResult {
+        match self.match_xmp(xmp_path) {
+            Ok((media, strategy)) => self
+                .merge_group(&media, vec![(xmp_path.to_path_buf(), strategy)])
+                .remove(0),
+            Err(result) => result,
+        }
+    }
 
-        match self.merge_xmp(xmp_path, &media) {
-            Ok(()) => {
-                if self.config.delete_xmp_after_merge {
-                    if let Err(err) = std::fs::remove_file(xmp_path) {
-                        crate::progress_mode::emit_stderr(&format!(
-                            "⚠️ XMP merge succeeded but sidecar delete failed for {}: {}",
-                            xmp_path.display(),
-                            err
-                        ));
+    /// Merge all sidecars matched to `media` (highest precedence first), one result each.
+    fn merge_group(&self, media: &Path, sidecars: Vec<(PathBuf, String)>) -> Vec<MergeResult> {
+        let paths: Vec<PathBuf> = sidecars.iter().map(|(xmp, _)| xmp.clone()).collect();
+        let outcome = self.merge_sidecars(&paths, media);
+
+        sidecars
+            .into_iter()
+            .enumerate()
+            .map(|(i, (xmp_path, strategy))| match &outcome {
+                Ok(conflicts) => {
+                    if self.config.delete_xmp_after_merge {
+                        if let Err(err) = std::fs::remove_file(&xmp_path) {
+                            crate::progress_mode::emit_stderr(&format!(
+                                "⚠️ XMP merge succeeded but sidecar delete failed for {}: {}",
+                                xmp_path.display(),
+                                err
+                            ));
+                        }
                     }
-                }
 
-                MergeResult {
-                    xmp_path: xmp_path.to_path_buf(),
-                    media_path: Some(media),
-                    success: true,
-                    message: "Merged successfully".to_string(),
-                    match_strategy: Some(strategy),
+                    let message = if paths.len() > 1 {
+                        format!(
+                            "Merged successfully ({} of {} sidecars, {}, {} conflicts)",
+                            i + 1,
+                            paths.len(),
+                            self.config.merge_policy.as_str(),
+                            conflicts.len()
+                        )
+                    } else {
+                        "Merged successfully".to_string()
+                    };
+                    MergeResult {
+                        sidecar_type: sidecar_type_of(&xmp_path),
+                        xmp_path,
+                        media_path: Some(media.to_path_buf()),
+                        success: true,
+                        message,
+                        match_strategy: Some(strategy),
+                        conflicts: if i == 0 {
+                            conflicts.clone()
+                        } else {
+                            Vec::new()
+                        },
+                    }
                 }
-            }
-            Err(e) => MergeResult {
-                xmp_path: xmp_path.to_path_buf(),
-                media_path: Some(media),
-                success: false,
-                message: format!("Merge failed: {}", e),
-                match_strategy: Some(strategy),
-            },
-        }
+                Err(e) => MergeResult {
+                    sidecar_type: sidecar_type_of(&xmp_path),
+                    xmp_path,
+                    media_path: Some(media.to_path_buf()),
+                    success: false,
+                    message: format!("Merge failed: {}", e),
+                    match_strategy: Some(strategy),
+                    conflicts: Vec::new(),
+                },
+            })
+            .collect()
     }
 
     pub fn process_directory(&self, dir: &Path) -> Result<Vec<MergeResult>> {
@@ -914,9 +1395,25 @@ impl XmpMerger {
             None
         };
 
+        // Several sidecars can match one media file (`photo.xmp` and `photo.jpg.xmp`); they are
+        // merged together so the policy decides, instead of whichever was visited last.
+        let mut groups: BTreeMap<PathBuf, Vec<(PathBuf, String)>> = BTreeMap::new();
         for xmp_path in xmp_files {
-            let result = self.process_xmp(&xmp_path);
-            results.push(result);
+            match self.match_xmp(&xmp_path) {
+                Ok((media, strategy)) => {
+                    groups.entry(media).or_default().push((xmp_path, strategy))
+                }
+                Err(result) => results.push(result),
+            }
+        }
+
+        for (media, mut sidecars) in groups {
+            sidecars.sort_by(|(a, _), (b, _)| {
+                sidecar_precedence(&media, a)
+                    .cmp(&sidecar_precedence(&media, b))
+                    .then_with(|| a.cmp(b))
+            });
+            results.extend(self.merge_group(&media, sidecars));
         }
 
         Ok(results)
@@ -958,48 +1455,51 @@ impl MergeSummary {
 }
 
 pub fn merge_xmp_for_copied_file(input: &Path, dest: &Path) -> Result<bool> {
-    let stem = input.file_stem().and_then(|s| s.to_str()).unwrap_or("");
-    let ext = input.extension().and_then(|e| e.to_str()).unwrap_or("");
-    let parent = input.parent().unwrap_or(Path::new("."));
-
-    let ext_lower = ext.to_lowercase();
-    let xmp_candidates = [
-        parent.join(format!("{}.xmp", stem)),
-        parent.join(format!("{}.{}.xmp", stem, ext)),
-        parent.join(format!("{}.{}.xmp", stem, ext_lower)),
-        parent.join(format!("{}.XMP", stem)),
-    ];
-
-    for xmp_path in &xmp_candidates {
-        if xmp_path.is_file() {
-            if crate::progress_mode::is_verbose_mode() {
-                eprintln!("📋 Found XMP sidecar: {}", xmp_path.display());
-            }
-
-            let config = XmpMergerConfig {
-                delete_xmp_after_merge: false,
-                overwrite_original: true,
-                preserve_timestamps: true,
-                verbose: false,
-            };
-
-            let merger = XmpMerger::new(config);
+    let sidecars = find_sidecars(input);
+    if sidecars.is_empty() {
+        return Ok(false);
+    }
+    if crate::progress_mode::is_verbose_mode() {
+        for xmp_path in &sidecars {
+            eprintln!(
+                "📋 Found {} sidecar: {}",
+                sidecar_type_of(xmp_path).to_uppercase(),
+                xmp_path.display()
+            );
+        }
+    }
 
-            crate::progress_mode::xmp_merge_attempt();
-            match merger.merge_xmp(xmp_path, dest) {
-                Ok(()) => {
-                    crate::progress_mode::xmp_merge_success();
-                }
-                Err(e) => {
-                    crate::progress_mode::xmp_merge_failure(&e.to_string());
-                    bail!("Failed to merge XMP: {}", e);
-                }
-            }
-            return Ok(true);
+    let config = XmpMergerConfig {
+        delete_xmp_after_merge: false,
+        overwrite_original: true,
+        preserve_timestamps: true,
+        verbose: false,
+        sidecar_mode: sidecar_mode(),
+        merge_policy: xmp_merge_policy(),
+        sidecar_extensions: sidecar_extensions(),
+    };
+
+    let merger = XmpMerger::new(config);
+
+    crate::progress_mode::xmp_merge_attempt();
+    match merger.apply_sidecars(&sidecars, dest) {
+        Ok(conflicts) => {
+            crate::progress_mode::xmp_merge_success();
+            report_conflicts(&conflicts);
+        }
+        Err(e) => {
+            crate::progress_mode::xmp_merge_failure(&e.to_string());
+            bail!("Failed to merge XMP: {}", e);
         }
     }
+    Ok(true)
+}
 
-    Ok(false)
+/// Warn about tags that differ between sidecars; the first value listed has precedence.
+pub fn report_conflicts(conflicts: &[XmpConflict]) {
+    for conflict in conflicts {
+        crate::progress_mode::emit_stderr(&format!("   ⚠️  XMP sidecars disagree: {}", conflict));
+    }
 }
 
 #[cfg(test)]
@@ -1021,6 +1521,170 @@ mod tests {
         assert!(!XmpMerger::is_uuid_filename("123-456-789"));
     }
 
+    #[test]
+    fn test_copy_beside_writes_output_stem_sidecar_without_embedding() {
+        let temp_dir = TempDir::new().unwrap();
+        let xmp = temp_dir.path().join("photo.jpg.xmp");
+        let output = temp_dir.path().join("out").join("photo.JXL");
+        fs::create_dir_all(output.parent().unwrap()).unwrap();
+        fs::write(&xmp, "<x:xmpmeta/>").unwrap();
+        fs::write(&output, "not an image").unwrap();
+
+        let merger = XmpMerger::new(XmpMergerConfig {
+            sidecar_mode: SidecarMode::CopyBeside,
+            ..Default::default()
+        });
+        merger.apply_sidecar(&xmp, &output).unwrap();
+
+        let copied = temp_dir.path().join("out").join("photo.xmp");
+        assert_eq!(fs::read_to_string(copied).unwrap(), "<x:xmpmeta/>");
+        assert_eq!(fs::read_to_string(&output).unwrap(), "not an image");
+    }
+
+    #[test]
+    fn test_parse_sidecar_mode() {
+        assert_eq!(parse_sidecar_mode("copy"), Ok(SidecarMode::CopyBeside));
+        assert_eq!(parse_sidecar_mode("Both"), Ok(SidecarMode::Both));
+        assert_eq!(parse_sidecar_mode("embed"), Ok(SidecarMode::Embed));
+        assert!(parse_sidecar_mode("sidecar").is_err());
+        assert!(SidecarMode::Both.embeds() && SidecarMode::Both.copies());
+        assert!(!SidecarMode::CopyBeside.embeds());
+    }
+
+    #[test]
+    fn test_parse_xmp_merge_policy() {
+        assert_eq!(
+            parse_xmp_merge_policy("prefer-existing"),
+            Ok(XmpMergePolicy::PreferExisting)
+        );
+        assert_eq!(
+            parse_xmp_merge_policy("Merge_Union"),
+            Ok(XmpMergePolicy::MergeUnion)
+        );
+        assert_eq!(
+            parse_xmp_merge_policy("sidecar"),
+            Ok(XmpMergePolicy::PreferSidecar)
+        );
+        assert!(parse_xmp_merge_policy("newest").is_err());
+        assert_eq!(XmpMergePolicy::default(), XmpMergePolicy::PreferSidecar);
+    }
+
+    #[test]
+    fn test_find_sidecars_orders_by_precedence() {
+        let temp_dir = TempDir::new().unwrap();
+        let jpg = temp_dir.path().join("photo.jpg");
+        fs::write(&jpg, "fake jpg").unwrap();
+        assert!(find_sidecars(&jpg).is_empty());
+
+        fs::write(temp_dir.path().join("photo.xmp"), "stem").unwrap();
+        fs::write(temp_dir.path().join("photo.jpg.xmp"), "full").unwrap();
+        let sidecars = find_sidecars(&jpg);
+        let names: Vec<_> = sidecars
+            .iter()
+            .map(|p| fs::read_to_string(p).unwrap())
+            .collect();
+        assert_eq!(names, ["full", "stem"]);
+
+        // Overwriting policies write the winner last; create-only writes it first.
+        let order = XmpMergePolicy::PreferSidecar.write_order(&sidecars);
+        assert_eq!(order.last().unwrap(), &&sidecars[0]);
+        let order = XmpMergePolicy::PreferExisting.write_order(&sidecars);
+        assert_eq!(order.first().unwrap(), &&sidecars[0]);
+    }
+
+    #[test]
+    fn test_parse_sidecar_extensions() {
+        assert_eq!(
+            parse_sidecar_extensions("xmp, .THM,yml,xmp"),
+            Ok(vec![
+                "xmp".to_string(),
+                "thm".to_string(),
+                "yml".to_string()
+            ])
+        );
+        assert!(parse_sidecar_extensions(" , ").is_err());
+        assert!(parse_sidecar_extensions("xmp,a/b").is_err());
+    }
+
+    #[test]
+    fn test_non_xmp_sidecars_match_like_xmp() {
+        let temp_dir = TempDir::new().unwrap();
+        let jpg = temp_dir.path().join("photo.jpg");
+        let thm = temp_dir.path().join("photo.thm");
+        let yml = temp_dir.path().join("photo.jpg.yml");
+        fs::write(&jpg, "fake jpg").unwrap();
+        fs::write(&thm, "thumb").unwrap();
+        fs::write(&yml, "title: harbor").unwrap();
+
+        let extensions = vec!["xmp".to_string(), "thm".to_string(), "yml".to_string()];
+        assert_eq!(
+            find_sidecars_with(&jpg, &extensions),
+            [yml.clone(), thm.clone()]
+        );
+        assert!(find_sidecars_with(&jpg, &["xmp".to_string()]).is_empty());
+
+        let merger = XmpMerger::new(XmpMergerConfig {
+            sidecar_extensions: extensions,
+            ..Default::default()
+        });
+        assert_eq!(merger.find_direct_match(&yml), Some(jpg.clone()));
+        // The other sidecar is never mistaken for the media file.
+        assert_eq!(merger.find_same_name_different_ext(&thm), Some(jpg.clone()));
+        let mut found = merger.find_xmp_files(temp_dir.path()).unwrap();
+        found.sort();
+        assert_eq!(found, [yml.clone(), thm.clone()]);
+        assert_eq!(sidecar_type_of(&thm), "thm");
+    }
+
+    #[test]
+    fn test_find_conflicts_reports_differing_tags() {
+        let tags = |pairs: &[(&str, serde_json::Value)]| {
+            pairs
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.clone()))
+                .collect::<serde_json::Map<_, _>>()
+        };
+        let full = PathBuf::from("photo.jpg.xmp");
+        let stem = PathBuf::from("photo.xmp");
+        let tag_sets = vec![
+            (
+                full.clone(),
+                tags(&[
+                    ("SourceFile", "photo.jpg.xmp".into()),
+                    ("System:FileSize", "1 kB".into()),
+                    ("XMP-dc:Title", "Harbor".into()),
+                    ("XMP-xmp:Rating", 5.into()),
+                ]),
+            ),
+            (
+                stem.clone(),
+                tags(&[
+                    ("SourceFile", "photo.xmp".into()),
+                    ("System:FileSize", "2 kB".into()),
+                    ("XMP-dc:Title", "Harbour at dusk".into()),
+                    ("XMP-xmp:Rating", 5.into()),
+                    ("XMP-dc:Creator", "A. Person".into()),
+                ]),
+            ),
+        ];
+
+        let conflicts = find_conflicts(&tag_sets);
+        assert_eq!(
+            conflicts,
+            vec![XmpConflict {
+                tag: "XMP-dc:Title".to_string(),
+                values: vec![
+                    (full, "Harbor".to_string()),
+                    (stem, "Harbour at dusk".to_string()),
+                ],
+            }]
+        );
+        assert_eq!(
+            conflicts[0].to_string(),
+            "XMP-dc:Title: \"Harbor\" (photo.jpg.xmp) vs \"Harbour at dusk\" (photo.xmp)"
+        );
+    }
+
     #[test]
     fn test_find_xmp_files() {
         let temp_dir = TempDir::new().unwrap();
@@ -33,7 +33,7 @@ fn extract_webp_to_apng(input: &Path, output_apng: &Path, verbose: bool) -> Resu
     // Create temporary directory for frames
     let temp_dir = tempfile::Builder::new()
         .prefix("webp_frames_")
-        .tempdir()
+        .tempdir_in(shared_utils::conversion::scratch_dir())
         .map_err(|e| {
             VidQualityError::ConversionError(format!("Failed to create temp dir: {}", e))
         })?;
@@ -174,11 +174,22 @@ fn get_output_path(
     options: &ConvertOptions,
 ) -> Result<std::path::PathBuf> {
     if let Some(ref base) = options.base_dir {
-        determine_output_path_with_base(input, base, extension, &options.output_dir)
-            .map_err(VidQualityError::ConversionError)
+        determine_output_path_with_base(
+            input,
+            base,
+            extension,
+            &options.output_dir,
+            options.on_collision,
+        )
+        .map_err(VidQualityError::ConversionError)
     } else {
-        shared_utils::conversion::determine_output_path(input, extension, &options.output_dir)
-            .map_err(VidQualityError::ConversionError)
+        shared_utils::conversion::determine_output_path(
+            input,
+            extension,
+            &options.output_dir,
+            options.on_collision,
+        )
+        .map_err(VidQualityError::ConversionError)
     }
 }
 
@@ -213,6 +224,34 @@ fn get_max_threads(options: &ConvertOptions) -> usize {
     }
 }
 
+/// `-c:v` onwards for the `--av1-encoder` in use: CRF 0 at SVT-AV1 preset 6, or libaom at the
+/// matching speed; `lossless` switches either encoder to true lossless instead of CRF 0.
+fn av1_codec_args(lossless: bool, max_threads: usize) -> Vec<String> {
+    let backend = shared_utils::av1_backend();
+    let mut args = vec!["-c:v".to_string(), backend.ffmpeg_name().to_string()];
+    if !lossless {
+        args.extend(["-crf".to_string(), "0".to_string()]);
+    }
+    match backend {
+        shared_utils::Av1Backend::Svt => args.extend([
+            "-preset".to_string(),
+            "6".to_string(),
+            "-svtav1-params".to_string(),
+            format!(
+                "{}tune=0:film-grain=0:lp={}",
+                if lossless { "lossless=1:" } else { "" },
+                max_threads
+            ),
+        ]),
+        shared_utils::Av1Backend::Aom => args.extend(shared_utils::libaom_args(
+            lossless,
+            shared_utils::EncoderPreset::Medium,
+            max_threads,
+        )),
+    }
+    args
+}
+
 pub fn is_high_quality_animated(width: u32, height: u32) -> bool {
     let total_pixels = width as u64 * height as u64;
     width >= 1280 || height >= 720 || total_pixels >= 921600
@@ -352,7 +391,12 @@ pub fn convert_to_av1_mp4(input: &Path, options: &ConvertOptions) -> Result<Conv
     let ext = if options.apple_compat { "mov" } else { "mp4" };
     let output = get_output_path(input, ext, options)?;
 
-    if output.exists() && !options.force {
+    if shared_utils::conversion::keeps_existing_output(
+        input,
+        &output,
+        options.force,
+        options.on_collision,
+    ) {
         return Ok(skipped_output_exists(input, &output, input_size));
     }
 
@@ -389,7 +433,7 @@ pub fn convert_to_av1_mp4(input: &Path, options: &ConvertOptions) -> Result<Conv
             // Create temporary APNG file
             let temp_apng = tempfile::Builder::new()
                 .suffix(".apng")
-                .tempfile()
+                .tempfile_in(shared_utils::conversion::scratch_dir())
                 .map_err(|e| {
                     VidQualityError::ConversionError(format!("Failed to create temp APNG: {}", e))
                 })?;
@@ -453,7 +497,7 @@ pub fn convert_to_av1_mp4(input: &Path, options: &ConvertOptions) -> Result<Conv
             // Create temporary APNG file
             let temp_apng = tempfile::Builder::new()
                 .suffix(".apng")
-                .tempfile()
+                .tempfile_in(shared_utils::conversion::scratch_dir())
                 .map_err(|e| {
                     VidQualityError::ConversionError(format!("Failed to create temp APNG: {}", e))
                 })?;
@@ -487,7 +531,6 @@ pub fn convert_to_av1_mp4(input: &Path, options: &ConvertOptions) -> Result<Conv
     let vf_args = shared_utils::get_ffmpeg_dimension_args(width, height, false);
 
     let max_threads = get_max_threads(options);
-    let svtav1_params = format!("tune=0:film-grain=0:lp={}", max_threads);
 
     // Probe ORIGINAL input to get stream index for multi-stream files (animated AVIF/HEIC)
     // For JXL/WebP, actual_input is APNG (single stream), so we probe the original input
@@ -514,21 +557,15 @@ pub fn convert_to_av1_mp4(input: &Path, options: &ConvertOptions) -> Result<Conv
         .arg("-map")
         .arg(format!("0:{}", effective_stream_idx)) // Select the correct stream
         // NO -r parameter: preserve original frame rate
-        .arg("-c:v")
-        .arg("libsvtav1")
-        .arg("-crf")
-        .arg("0")
-        .arg("-preset")
-        .arg("6")
+        .args(av1_codec_args(false, max_threads))
         .arg("-tag:v")
-        .arg("av01")
-        .arg("-svtav1-params")
-        .arg(&svtav1_params);
+        .arg("av01");
 
     for arg in &vf_args {
         cmd.arg(arg);
     }
 
+    cmd.args(options.strip.ffmpeg_args());
     cmd.arg(shared_utils::safe_path_arg(&temp_output).as_ref());
     let result = cmd.output();
 
@@ -562,13 +599,15 @@ pub fn convert_to_av1_mp4(input: &Path, options: &ConvertOptions) -> Result<Conv
                 &output,
                 options.force,
                 Some(input),
+                options.strip,
+                options.on_collision,
             )? {
                 return Ok(skipped_output_exists(input, &output, input_size));
             }
 
             let reduction = 1.0 - (output_size as f64 / input_size as f64);
 
-            shared_utils::copy_metadata(input, &output);
+            shared_utils::copy_metadata(input, &output, options.strip);
             mark_as_processed(input);
 
             if options.should_delete_original() {
@@ -700,7 +739,12 @@ pub fn convert_to_av1_mp4_matched(
     let ext = if options.apple_compat { "mov" } else { "mp4" };
     let output = get_output_path(input, ext, options)?;
 
-    if output.exists() && !options.force {
+    if shared_utils::conversion::keeps_existing_output(
+        input,
+        &output,
+        options.force,
+        options.on_collision,
+    ) {
         return Ok(skipped_output_exists(input, &output, input_size));
     }
 
@@ -730,7 +774,7 @@ pub fn convert_to_av1_mp4_matched(
             }
             let temp_apng = tempfile::Builder::new()
                 .suffix(".apng")
-                .tempfile()
+                .tempfile_in(shared_utils::conversion::scratch_dir())
                 .map_err(|e| {
                     VidQualityError::ConversionError(format!("Failed to create temp APNG: {}", e))
                 })?;
@@ -791,7 +835,7 @@ pub fn convert_to_av1_mp4_matched(
             // Create temporary APNG file
             let temp_apng = tempfile::Builder::new()
                 .suffix(".apng")
-                .tempfile()
+                .tempfile_in(shared_utils::conversion::scratch_dir())
                 .map_err(|e| {
                     VidQualityError::ConversionError(format!("Failed to create temp APNG: {}", e))
                 })?;
@@ -856,7 +900,7 @@ pub fn convert_to_av1_mp4_matched(
                     // Create temporary APNG file
                     let temp_stream = tempfile::Builder::new()
                         .suffix(".apng")
-                        .tempfile()
+                        .tempfile_in(shared_utils::conversion::scratch_dir())
                         .map_err(|e| {
                             VidQualityError::ConversionError(format!(
                                 "Failed to create temp APNG: {}",
@@ -940,7 +984,19 @@ pub fn convert_to_av1_mp4_matched(
         shared_utils::VideoEncoder::Av1,
     );
 
-    let explore_result = if flag_mode.is_ultimate() {
+    let explore_result = if let Some(min_ssim) = options.min_ssim {
+        shared_utils::explore_av1_with_gpu_coarse_full(
+            &final_input,
+            &temp_output,
+            vf_args,
+            actual_initial_crf,
+            flag_mode.is_ultimate(),
+            false,
+            options.allow_size_tolerance,
+            min_ssim,
+            options.child_threads,
+        )
+    } else if flag_mode.is_ultimate() {
         shared_utils::explore_av1_with_gpu_coarse_ultimate(
             &final_input,
             &temp_output,
@@ -1123,11 +1179,13 @@ pub fn convert_to_av1_mp4_matched(
         &output,
         options.force,
         Some(input),
+        options.strip,
+        options.on_collision,
     )? {
         return Ok(skipped_output_exists(input, &output, input_size));
     }
 
-    shared_utils::copy_metadata(input, &output);
+    shared_utils::copy_metadata(input, &output, options.strip);
     mark_as_processed(input);
 
     if options.should_delete_original() {
@@ -1193,7 +1251,12 @@ pub fn convert_to_av1_mkv_lossless(
     let input_size = fs::metadata(input)?.len();
     let output = get_output_path(input, "mkv", options)?;
 
-    if output.exists() && !options.force {
+    if shared_utils::conversion::keeps_existing_output(
+        input,
+        &output,
+        options.force,
+        options.on_collision,
+    ) {
         return Ok(skipped_output_exists(input, &output, input_size));
     }
 
@@ -1203,19 +1266,13 @@ pub fn convert_to_av1_mkv_lossless(
     let vf_args = shared_utils::get_ffmpeg_dimension_args(width, height, false);
 
     let max_threads = get_max_threads(options);
-    let svtav1_params = format!("lossless=1:tune=0:film-grain=0:lp={}", max_threads);
     let mut cmd = Command::new("ffmpeg");
     cmd.arg("-y")
         .arg("-threads")
         .arg(max_threads.to_string())
         .arg("-i")
         .arg(shared_utils::safe_path_arg(input).as_ref())
-        .arg("-c:v")
-        .arg("libsvtav1")
-        .arg("-svtav1-params")
-        .arg(&svtav1_params)
-        .arg("-preset")
-        .arg("6")
+        .args(av1_codec_args(true, max_threads))
         .arg("-tag:v")
         .arg("av01");
 
@@ -1223,6 +1280,7 @@ pub fn convert_to_av1_mkv_lossless(
         cmd.arg(arg);
     }
 
+    cmd.args(options.strip.ffmpeg_args());
     cmd.arg(shared_utils::safe_path_arg(&temp_output).as_ref());
     let result = cmd.output();
 
@@ -1235,13 +1293,15 @@ pub fn convert_to_av1_mkv_lossless(
                 &output,
                 options.force,
                 Some(input),
+                options.strip,
+                options.on_collision,
             )? {
                 return Ok(skipped_output_exists(input, &output, input_size));
             }
 
             let reduction = 1.0 - (output_size as f64 / input_size as f64);
 
-            shared_utils::copy_metadata(input, &output);
+            shared_utils::copy_metadata(input, &output, options.strip);
             mark_as_processed(input);
 
             if options.should_delete_original() {
@@ -1378,7 +1438,12 @@ pub fn convert_to_gif_apple_compat(
         fs::create_dir_all(parent)?;
     }
 
-    if output.exists() && !options.force {
+    if shared_utils::conversion::keeps_existing_output(
+        input,
+        &output,
+        options.force,
+        options.on_collision,
+    ) {
         return Ok(ConversionResult {
             success: true,
             input_path: input.display().to_string(),
@@ -1425,7 +1490,7 @@ pub fn convert_to_gif_apple_compat(
             // Create temporary APNG file
             let temp_apng = tempfile::Builder::new()
                 .suffix(".apng")
-                .tempfile()
+                .tempfile_in(shared_utils::conversion::scratch_dir())
                 .map_err(|e| {
                     VidQualityError::ConversionError(format!("Failed to create temp APNG: {}", e))
                 })?;
@@ -1489,7 +1554,7 @@ pub fn convert_to_gif_apple_compat(
             // Create temporary APNG file
             let temp_apng = tempfile::Builder::new()
                 .suffix(".apng")
-                .tempfile()
+                .tempfile_in(shared_utils::conversion::scratch_dir())
                 .map_err(|e| {
                     VidQualityError::ConversionError(format!("Failed to create temp APNG: {}", e))
                 })?;
@@ -1686,6 +1751,8 @@ pub fn convert_to_gif_apple_compat(
         &output,
         options.force,
         Some(input),
+        options.strip,
+        options.on_collision,
     )? {
         return Ok(ConversionResult {
             success: true,
@@ -1700,7 +1767,7 @@ pub fn convert_to_gif_apple_compat(
         });
     }
 
-    shared_utils::copy_metadata(input, &output);
+    shared_utils::copy_metadata(input, &output, options.strip);
     mark_as_processed(input);
 
     if options.should_delete_original() {
@@ -10,9 +10,10 @@ use crate::{Result, VidQualityError};
 
 use shared_utils::analysis_cache::AnalysisCache;
 use shared_utils::conversion_types::{
-    ConversionConfig, ConversionOutput, ConversionStrategy, TargetVideoFormat,
+    ConversionConfig, ConversionOutput, ConversionStrategy, SkipReason, TargetVideoFormat,
 };
-use std::path::Path;
+use shared_utils::ffmpeg_process::FfmpegProcess;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 use tracing::{info, warn};
 
@@ -92,6 +93,12 @@ fn build_hdr_ffmpeg_args(detection: &VideoDetectionResult) -> Vec<String> {
         }
     }
 
+    // -color_range: a full-range (JPEG-style) source would otherwise be encoded and tagged as
+    // limited range, washing out blacks and whites.
+    args.extend(shared_utils::color_range_args(
+        detection.color_range.as_deref(),
+    ));
+
     args
 }
 
@@ -99,8 +106,15 @@ fn build_hdr_ffmpeg_args(detection: &VideoDetectionResult) -> Vec<String> {
 /// - If source is 10-bit (yuv420p10le, yuv422p10le, etc.) use yuv420p10le so that
 ///   the HDR signal range / precision is preserved in the output stream.
 /// - Otherwise default to yuv420p (8-bit SDR).
-fn hdr_pix_fmt(detection: &VideoDetectionResult) -> &'static str {
-    if detection.bit_depth >= 10 {
+///
+/// An explicit `--pix-fmt` (`pix_fmt_override`) wins over both.
+fn hdr_pix_fmt(
+    detection: &VideoDetectionResult,
+    pix_fmt_override: Option<shared_utils::PixelFormat>,
+) -> &'static str {
+    if let Some(pix_fmt) = pix_fmt_override {
+        pix_fmt.as_str()
+    } else if detection.bit_depth >= 10 {
         "yuv420p10le"
     } else {
         "yuv420p"
@@ -111,17 +125,79 @@ pub fn determine_strategy(result: &VideoDetectionResult) -> ConversionStrategy {
     determine_strategy_with_apple_compat(result, false)
 }
 
+/// Appended to AV1 strategy reasons under `apple_compat`: the output stays AV1, which only
+/// Apple devices with AV1 hardware decode play natively.
+const APPLE_AV1_NOTE: &str =
+    " [Apple compat: AV1 is not Apple-native (hardware decode only on A17 Pro / M3 and later); use vid_hevc for HEVC output]";
+
+/// Summary category for a `TargetVideoFormat::Skip` strategy, mirroring the checks in
+/// [`determine_strategy_with_apple_compat`].
+fn strategy_skip_reason(result: &VideoDetectionResult, apple_compat: bool) -> SkipReason {
+    // A remux candidate only becomes a skip when `--container` already matches.
+    if shared_utils::is_mp4_remux_candidate(result, &crate::detection_api::DetectedCodec::AV1) {
+        return SkipReason::AlreadyOptimal;
+    }
+    let decision = if apple_compat {
+        shared_utils::should_skip_video_codec_apple_compat(result.codec.as_str())
+    } else {
+        shared_utils::should_skip_video_codec(result.codec.as_str())
+    };
+    decision.kind.unwrap_or(SkipReason::ModernLossy)
+}
+
+/// AV1 counterpart of `vid_hevc::determine_strategy_with_apple_compat`. Skips follow the
+/// Apple-compat rules, but the target stays AV1 (this crate only encodes AV1), so every
+/// non-skip reason carries a note that the output is not Apple-native.
 pub fn determine_strategy_with_apple_compat(
     result: &VideoDetectionResult,
     apple_compat: bool,
 ) -> ConversionStrategy {
+    let apple_note = if apple_compat { APPLE_AV1_NOTE } else { "" };
+
+    let generation_guard = shared_utils::should_skip_same_codec_reencode(
+        result.codec.as_str(),
+        shared_utils::SourceCodec::Av1,
+    );
+    // `--reconvert` on a source already in the target codec: re-encode it, skipping the
+    // remux shortcut and the modern-codec skips below.
+    let reconvert =
+        generation_guard.codec == shared_utils::SourceCodec::Av1 && !generation_guard.should_skip;
+
+    // Already AV1, just not in MP4: change the container instead of re-encoding.
+    if !reconvert
+        && shared_utils::is_mp4_remux_candidate(result, &crate::detection_api::DetectedCodec::AV1)
+    {
+        return ConversionStrategy {
+            target: TargetVideoFormat::Remux,
+            reason: format!(
+                "Source is already AV1 in {} - remuxing to MP4 (stream copy, no re-encode){}",
+                result.format, apple_note
+            ),
+            command: String::new(),
+            preserve_audio: result.has_audio,
+            crf: 0.0,
+            lossless: false,
+        };
+    }
+
+    if generation_guard.should_skip {
+        return ConversionStrategy {
+            target: TargetVideoFormat::Skip,
+            reason: generation_guard.reason,
+            command: String::new(),
+            preserve_audio: false,
+            crf: 0.0,
+            lossless: false,
+        };
+    }
+
     let skip_decision = if apple_compat {
         shared_utils::should_skip_video_codec_apple_compat(result.codec.as_str())
     } else {
         shared_utils::should_skip_video_codec(result.codec.as_str())
     };
 
-    if skip_decision.should_skip {
+    if skip_decision.should_skip && !reconvert {
         return ConversionStrategy {
             target: TargetVideoFormat::Skip,
             reason: skip_decision.reason,
@@ -133,8 +209,12 @@ pub fn determine_strategy_with_apple_compat(
     }
 
     if let crate::detection_api::DetectedCodec::Unknown(ref s) = result.codec {
-        let unknown_skip = shared_utils::should_skip_video_codec(s);
-        if unknown_skip.should_skip {
+        let unknown_skip = if apple_compat {
+            shared_utils::should_skip_video_codec_apple_compat(s)
+        } else {
+            shared_utils::should_skip_video_codec(s)
+        };
+        if unknown_skip.should_skip && !reconvert {
             return ConversionStrategy {
                 target: TargetVideoFormat::Skip,
                 reason: unknown_skip.reason,
@@ -179,7 +259,7 @@ pub fn determine_strategy_with_apple_compat(
 
     ConversionStrategy {
         target,
-        reason,
+        reason: reason + apple_note,
         command: String::new(),
         preserve_audio: result.has_audio,
         crf,
@@ -187,15 +267,45 @@ pub fn determine_strategy_with_apple_compat(
     }
 }
 
+/// Simple wrapper: AV1 lossless MP4, overwriting existing output, metadata preserved.
 pub fn simple_convert(input: &Path, output_dir: Option<&Path>) -> Result<ConversionOutput> {
+    let config = ConversionConfig {
+        output_dir: output_dir.map(Path::to_path_buf),
+        force: true,
+        use_lossless: true,
+        ..Default::default()
+    };
+    simple_convert_with_config(input, &config)
+}
+
+/// Single-pass conversion driven by `config`, without the skip/explore routing of `auto_convert`.
+///
+/// - `use_lossless`: mathematically lossless AV1; otherwise a single AV1 CRF encode
+/// - `match_quality`: CRF matched to the source via `match_mode` / `quality_bias`; otherwise CRF 20
+/// - `output_dir`, `base_dir`, `force`, `max_dimension`, `child_threads` (0 = automatic) and
+///   `delete_original` / `in_place` are honored; metadata is always preserved.
+pub fn simple_convert_with_config(
+    input: &Path,
+    config: &ConversionConfig,
+) -> Result<ConversionOutput> {
     if let Err(e) = shared_utils::conversion::validate_input_file(input) {
         return Err(VidQualityError::ConversionError(e));
     }
 
-    let detection = crate::detection_api::detect_video_with_cache(input, None)?;
+    let mut detection = crate::detection_api::detect_video_with_cache(input, None)?;
+    config.apply_audio_policy(&mut detection);
+    shared_utils::set_audio_transcode(config.audio_transcode);
+    let _trim = if config.is_trimmed() {
+        Some(prepare_trim(&mut detection, config)?)
+    } else {
+        None
+    };
 
-    let output_dir = output_dir
-        .map(|p| p.to_path_buf())
+    let lossless = config.use_lossless;
+
+    let output_dir = config
+        .output_dir
+        .clone()
         .unwrap_or_else(|| input.parent().unwrap_or(Path::new(".")).to_path_buf());
 
     std::fs::create_dir_all(&output_dir)?;
@@ -206,28 +316,103 @@ pub fn simple_convert(input: &Path, output_dir: Option<&Path>) -> Result<Convers
         .unwrap_or("output");
     let input_ext = input.extension().and_then(|e| e.to_str()).unwrap_or("");
 
-    let output_path = if input_ext.eq_ignore_ascii_case("MP4") {
-        output_dir.join(format!("{}_av1.MP4", stem))
+    let target_ext = config
+        .container_for(TargetVideoFormat::Av1Mp4)
+        .map_or("MP4", |c| c.extension());
+    let output_path = if input_ext.eq_ignore_ascii_case(target_ext) {
+        output_dir.join(format!("{}_av1.{}", stem, target_ext))
     } else {
-        output_dir.join(format!("{}.MP4", stem))
+        output_dir.join(format!("{}.{}", stem, target_ext))
+    };
+    let output_path = if config.output_dir.is_some() {
+        shared_utils::conversion::flatten_output_path(
+            input,
+            config.base_dir.as_deref(),
+            output_path,
+        )
+    } else {
+        output_path
     };
-    shared_utils::conversion::validate_output_path(&output_path, None)
+    let output_path =
+        shared_utils::conversion::resolve_output_collision(input, output_path, config.on_collision)
+            .map_err(VidQualityError::ConversionError)?;
+    shared_utils::conversion::validate_output_path(&output_path, config.base_dir.as_deref())
         .map_err(VidQualityError::ConversionError)?;
 
-    info!("🎬 Simple Mode: {} → AV1 MP4 (LOSSLESS)", input.display());
+    if shared_utils::conversion::keeps_existing_output(
+        input,
+        &output_path,
+        config.force,
+        config.on_collision,
+    ) {
+        return Err(VidQualityError::ConversionError(format!(
+            "Output exists: {} (set force to overwrite)",
+            output_path.display()
+        )));
+    }
 
-    let thread_config = shared_utils::thread_manager::get_balanced_thread_config(
-        shared_utils::thread_manager::WorkloadType::Video,
-    );
+    let max_threads = if config.child_threads > 0 {
+        config.child_threads
+    } else {
+        shared_utils::thread_manager::get_balanced_thread_config(
+            shared_utils::thread_manager::WorkloadType::Video,
+        )
+        .child_threads
+    };
+
+    let crf = if lossless {
+        0.0
+    } else if config.match_quality {
+        calculate_matched_crf_with_options(
+            &detection,
+            config.match_mode,
+            config.quality_bias,
+            config.crf_bias,
+        )? as f32
+    } else {
+        shared_utils::crf_constants::AV1_CRF_VISUALLY_LOSSLESS
+    };
+
+    if lossless {
+        info!("🎬 Simple Mode: {} → AV1 MP4 (LOSSLESS)", input.display());
+    } else {
+        info!(
+            "🎬 Simple Mode: {} → AV1 MP4 (CRF {:.0})",
+            input.display(),
+            crf
+        );
+    }
+
+    let _film_grain_guard =
+        (!lossless).then(|| shared_utils::encode_film_grain(film_grain_for(config, &detection)));
     let temp_path = shared_utils::conversion::temp_path_for_output(&output_path);
     let _temp_guard = shared_utils::conversion::TempOutputGuard::new(temp_path.clone());
-    let output_size = execute_av1_lossless(&detection, &temp_path, thread_config.child_threads)?;
+    let output_size = if lossless {
+        execute_av1_lossless(
+            &detection,
+            &temp_path,
+            max_threads,
+            config.max_dimension,
+            config.encode_settings(),
+        )?
+    } else {
+        execute_av1_crf(
+            &detection,
+            &temp_path,
+            crf,
+            max_threads,
+            config.max_dimension,
+            config.encode_settings(),
+        )?
+    };
 
     if !shared_utils::conversion::commit_temp_to_output_with_metadata(
         &temp_path,
         &output_path,
-        true,
+        config.force,
         Some(input),
+        config.strip,
+        config.on_collision,
     )
     .map_err(|e| VidQualityError::ConversionError(e.to_string()))?
     {
@@ -236,30 +421,68 @@ pub fn simple_convert(input: &Path, output_dir: Option<&Path>) -> Result<Convers
         ));
     }
 
-    shared_utils::copy_metadata(input, &output_path);
+    shared_utils::copy_metadata(input, &output_path, config.strip);
+
+    if config.should_delete_original() {
+        let downscaled = config
+            .max_dimension
+            .and_then(|max| {
+                shared_utils::downscale_dimensions(detection.width, detection.height, max)
+            })
+            .is_some();
+        let delete = if lossless && !downscaled {
+            shared_utils::conversion::safe_delete_original_lossless
+        } else {
+            shared_utils::conversion::safe_delete_original
+        };
+        if let Err(e) = delete(
+            input,
+            &output_path,
+            shared_utils::conversion::MIN_OUTPUT_SIZE_BEFORE_DELETE_VIDEO,
+        ) {
+            warn!("   ⚠️  Safe delete failed: {}", e);
+        } else {
+            info!("   🗑️  Original deleted (integrity verified)");
+        }
+    }
 
     let size_ratio = output_size as f64 / detection.file_size as f64;
 
     info!("   ✅ Complete: {:.1}% of original", size_ratio * 100.0);
 
+    let (reason, message) = if lossless {
+        (
+            "Simple mode: Always AV1 Lossless".to_string(),
+            "Simple conversion successful (Lossless)".to_string(),
+        )
+    } else {
+        (
+            "Simple mode: AV1 High Quality".to_string(),
+            format!("Simple conversion successful (AV1 CRF {:.0})", crf),
+        )
+    };
+
     Ok(ConversionOutput {
         input_path: input.display().to_string(),
         output_path: output_path.display().to_string(),
         strategy: ConversionStrategy {
             target: TargetVideoFormat::Av1Mp4,
-            reason: "Simple mode: Always AV1 Lossless".to_string(),
+            reason,
             command: String::new(),
             preserve_audio: detection.has_audio,
-            crf: 0.0,
-            lossless: true,
+            crf,
+            lossless,
         },
         input_size: detection.file_size,
         output_size,
         size_ratio,
         success: true,
-        message: "Simple conversion successful (Lossless)".to_string(),
-        final_crf: 0.0,
+        message,
+        final_crf: crf,
         exploration_attempts: 0,
+        benchmark: None,
+        skip_reason: None,
+        ssim: None,
     })
 }
 
@@ -271,14 +494,260 @@ pub fn auto_convert_with_cache(
     input: &Path,
     config: &ConversionConfig,
     cache: Option<&AnalysisCache>,
+) -> Result<ConversionOutput> {
+    // Explore iterations report through the thread-local sink; file start/done are emitted here.
+    // Without a configured sink the one already on the thread (the batch bar) is kept.
+    let sink = config
+        .progress_sink
+        .clone()
+        .or_else(shared_utils::progress_sink::current);
+    let _sink_guard = shared_utils::progress_sink::install(sink.clone());
+    if let Some(dir) = &config.temp_dir {
+        shared_utils::conversion::set_temp_dir(dir.clone());
+    }
+    // Without a configured token the one already on the thread (the batch fail-fast) is kept.
+    let cancel_token = config
+        .cancel_token
+        .clone()
+        .or_else(shared_utils::cancellation::current);
+    let deadline = config
+        .per_file_timeout
+        .map(|timeout| shared_utils::cancellation::Deadline::start(cancel_token.clone(), timeout));
+    let _cancel_guard = shared_utils::cancellation::install(
+        deadline
+            .as_ref()
+            .map(|d| d.token())
+            .or_else(|| cancel_token.clone()),
+    );
+    if let Some(sink) = sink.as_deref() {
+        sink.on_file_start(input);
+    }
+    let started = std::time::Instant::now();
+    shared_utils::begin_file_profile();
+    let mut result = auto_convert_with_cache_inner(input, config, cache);
+    // Encode failures caused by a killed child are reported as the timeout/cancellation they are.
+    if result.is_err() {
+        if let Some(timeout) = config
+            .per_file_timeout
+            .filter(|_| deadline.as_ref().is_some_and(|d| d.timed_out()))
+        {
+            shared_utils::log_eprintln!(
+                "⏱️  TIMED OUT after {}s, encode killed: {}",
+                timeout.as_secs(),
+                input.display()
+            );
+            result = Err(VidQualityError::TimedOut {
+                path: input.to_path_buf(),
+                timeout,
+            });
+        } else if cancel_token.as_ref().is_some_and(|t| t.is_cancelled()) {
+            result = Err(VidQualityError::Cancelled);
+        }
+    }
+    if config.benchmark {
+        if let Ok(output) = &mut result {
+            output.benchmark = shared_utils::conversion_types::EncodeBenchmark::measure(
+                output,
+                started.elapsed(),
+                config.use_gpu,
+            );
+        }
+    }
+    if let Some(sink) = sink.as_deref() {
+        match &result {
+            Ok(output) => sink.on_file_done(output),
+            Err(e) => sink.on_file_failed(input, &e.to_string()),
+        }
+    }
+    shared_utils::finish_file_profile_with_log(
+        &input.file_name().unwrap_or_default().to_string_lossy(),
+    );
+    result
+}
+
+/// Where the output of `input` goes, before `--on-collision` is applied. Creates nothing.
+fn planned_output_path(
+    input: &Path,
+    strategy: &ConversionStrategy,
+    config: &ConversionConfig,
+) -> Result<PathBuf> {
+    let output_dir = config
+        .output_dir
+        .clone()
+        .unwrap_or_else(|| input.parent().unwrap_or(Path::new(".")).to_path_buf());
+
+    let stem = input
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("output");
+    let target_ext = config
+        .container_for(strategy.target)
+        .map_or(strategy.target.extension(), |c| c.extension());
+    let input_ext = input.extension().and_then(|e| e.to_str()).unwrap_or("");
+
+    let templated = shared_utils::conversion::templated_output_name(
+        input,
+        target_ext,
+        Some(strategy.target.codec_name()),
+        // Lossy encodes pick their CRF during exploration, after the name is fixed.
+        None,
+    )
+    .map_err(VidQualityError::ConversionError)?;
+    let output_path = if let Some(name) = templated {
+        output_dir.join(name)
+    } else if input_ext.eq_ignore_ascii_case(target_ext) {
+        output_dir.join(format!("{}_av1.{}", stem, target_ext))
+    } else {
+        output_dir.join(format!("{}.{}", stem, target_ext))
+    };
+    Ok(if config.output_dir.is_some() {
+        shared_utils::conversion::flatten_output_path(
+            input,
+            config.base_dir.as_deref(),
+            output_path,
+        )
+    } else {
+        output_path
+    })
+}
+
+/// `-vf` args of the CRF encode: display size capped by `--max-dimension`, and `--denoise`.
+/// Frame-timing args (`--force-cfr`) are not filters; see [`explore_frame_rate_mode`].
+fn explore_vf_args(detection: &VideoDetectionResult, config: &ConversionConfig) -> Vec<String> {
+    let (display_width, display_height) = detection.display_dimensions();
+    let vf_args = shared_utils::get_ffmpeg_dimension_args_with_max_dimension(
+        display_width,
+        display_height,
+        false,
+        config.max_dimension,
+    );
+    shared_utils::with_denoise_filter(vf_args, config.denoise)
+}
+
+/// Installs the VFR/`--force-cfr` frame-timing args for the CRF encodes on this thread.
+fn explore_frame_rate_mode(
+    detection: &VideoDetectionResult,
+    config: &ConversionConfig,
+) -> shared_utils::FrameRateModeGuard {
+    shared_utils::encode_frame_rate_mode(shared_utils::frame_rate_mode_args(
+        detection.is_variable_frame_rate,
+        config.force_cfr,
+    ))
+}
+
+/// `--print-command`: the encode command [`auto_convert`] would run for `input`, one shell line,
+/// without converting anything. The CRF shown is where the search starts; the run may settle on
+/// another. Skipped files get a `#` comment saying why.
+pub fn encode_command_preview(input: &Path, config: &ConversionConfig) -> Result<Vec<String>> {
+    let mut detection = crate::detection_api::detect_video(input)?;
+    config.apply_audio_policy(&mut detection);
+    shared_utils::set_audio_transcode(config.audio_transcode);
+
+    let strategy = config.skip_redundant_remux(
+        determine_strategy_with_apple_compat(&detection, config.apple_compat),
+        input,
+    );
+    if !config.accepts_codec(&detection.codec) {
+        return Ok(vec![format!(
+            "# skipped: codec {} not in --only-codecs",
+            detection.codec.as_str()
+        )]);
+    }
+    if strategy.target == TargetVideoFormat::Skip {
+        return Ok(vec![format!("# skipped: {}", strategy.reason)]);
+    }
+
+    let output_path = planned_output_path(input, &strategy, config)?;
+    let settings = config.encode_settings();
+    let ffmpeg = |args: Vec<String>| vec![shared_utils::shell_command_string("ffmpeg", &args)];
+    match strategy.target {
+        TargetVideoFormat::Remux => {
+            return Ok(ffmpeg(remux_command_args(
+                &detection,
+                &output_path,
+                config.strip,
+            )));
+        }
+        TargetVideoFormat::Ffv1Mkv => {
+            return Ok(ffmpeg(ffv1_args(
+                &detection,
+                &output_path,
+                config.child_threads,
+                config.max_dimension,
+                settings,
+            )));
+        }
+        _ if strategy.lossless || config.use_lossless => {
+            return Ok(ffmpeg(av1_encode_args(
+                &detection,
+                &output_path,
+                None,
+                config.child_threads,
+                config.max_dimension,
+                settings,
+            )));
+        }
+        _ => {}
+    }
+
+    let _film_grain_guard = shared_utils::encode_film_grain(film_grain_for(config, &detection));
+    let _frame_rate_guard = explore_frame_rate_mode(&detection, config);
+    let crf = calculate_matched_crf_with_options(
+        &detection,
+        config.match_mode,
+        config.quality_bias,
+        config.crf_bias,
+    )? as f32;
+    shared_utils::encode_command_preview(
+        input,
+        &output_path,
+        shared_utils::VideoEncoder::Av1,
+        explore_vf_args(&detection, config),
+        crf,
+        config.child_threads,
+        settings,
+    )
+    .map_err(|e| VidQualityError::ConversionError(e.to_string()))
+}
+
+/// Result of a file whose output appeared while it was being encoded: the commit declined to
+/// replace it, so the original is kept and nothing is written.
+fn concurrent_output_skip(
+    input: &Path,
+    detection: &VideoDetectionResult,
+    strategy: &ConversionStrategy,
+) -> ConversionOutput {
+    ConversionOutput {
+        input_path: input.display().to_string(),
+        output_path: String::new(),
+        strategy: strategy.clone(),
+        input_size: detection.file_size,
+        output_size: 0,
+        size_ratio: 1.0,
+        success: true,
+        message: "Skipped: output was created concurrently".to_string(),
+        final_crf: 0.0,
+        exploration_attempts: 0,
+        benchmark: None,
+        skip_reason: Some(SkipReason::AlreadyProcessed),
+        ssim: None,
+    }
+}
+
+fn auto_convert_with_cache_inner(
+    input: &Path,
+    config: &ConversionConfig,
+    cache: Option<&AnalysisCache>,
 ) -> Result<ConversionOutput> {
     // Pause if the user is being prompted to exit via Ctrl+C
     shared_utils::ctrlc_guard::wait_if_prompt_active();
 
-    // Skip Live Photos in Apple compat mode
-    if config.apple_compat && shared_utils::is_live_photo(input) {
+    // --live-photos skip (default): leave the MOV half alone, as the image tools do the still.
+    if shared_utils::live_photo_mode() == shared_utils::LivePhotoMode::Skip
+        && shared_utils::is_live_photo(input)
+    {
         info!("🎬 Auto Mode: {} → SKIP (Live Photo)", input.display());
-        info!("   Reason: Live Photo detected in Apple compat mode");
+        info!("   Reason: Live Photo pair, skipped together with its still (--live-photos convert to convert both)");
 
         let file_size = std::fs::metadata(input).map(|m| m.len()).unwrap_or(0);
 
@@ -295,7 +764,7 @@ pub fn auto_convert_with_cache(
             output_path: "".to_string(),
             strategy: ConversionStrategy {
                 target: TargetVideoFormat::Skip,
-                reason: "Live Photo detected in Apple compat mode".to_string(),
+                reason: "Live Photo pair (--live-photos skip)".to_string(),
                 command: "".to_string(),
                 preserve_audio: false,
                 crf: 0.0,
@@ -305,9 +774,12 @@ pub fn auto_convert_with_cache(
             output_size: 0,
             size_ratio: 0.0,
             success: true,
-            message: "Skipped Live Photo in Apple compat mode".to_string(),
+            message: "Skipped Live Photo motion half".to_string(),
             final_crf: 0.0,
             exploration_attempts: 0,
+            benchmark: None,
+            skip_reason: Some(SkipReason::LivePhoto),
+            ssim: None,
         });
     }
 
@@ -315,7 +787,59 @@ pub fn auto_convert_with_cache(
         return Err(VidQualityError::ConversionError(e));
     }
 
+    let probe_phase = shared_utils::phase_profile::phase(shared_utils::ProfilePhase::Probe);
+    if !config.skip_integrity_check {
+        match shared_utils::probe_integrity(input) {
+            shared_utils::MediaIntegrity::Ok => {}
+            shared_utils::MediaIntegrity::Truncated(reason) => {
+                warn!("   ⚠️  Truncated/corrupt source, skipping: {}", reason);
+                shared_utils::progress_mode::video_skipped(&format!(
+                    "Truncated source ({})",
+                    reason
+                ));
+
+                let file_size = std::fs::metadata(input).map(|m| m.len()).unwrap_or(0);
+
+                shared_utils::copy_on_skip_or_fail(
+                    input,
+                    config.output_dir.as_deref(),
+                    config.base_dir.as_deref(),
+                    false,
+                )
+                .map_err(|e| VidQualityError::GeneralError(e.to_string()))?;
+
+                return Ok(ConversionOutput {
+                    input_path: input.display().to_string(),
+                    output_path: "".to_string(),
+                    strategy: ConversionStrategy {
+                        target: TargetVideoFormat::Skip,
+                        reason: format!("Truncated source: {}", reason),
+                        command: "".to_string(),
+                        preserve_audio: false,
+                        crf: 0.0,
+                        lossless: false,
+                    },
+                    input_size: file_size,
+                    output_size: 0,
+                    size_ratio: 0.0,
+                    success: true,
+                    message: format!("Skipped truncated/corrupt source: {}", reason),
+                    final_crf: 0.0,
+                    exploration_attempts: 0,
+                    benchmark: None,
+                    skip_reason: Some(SkipReason::Corrupt),
+                    ssim: None,
+                });
+            }
+            // Detection below reports the real error for unreadable files.
+            shared_utils::MediaIntegrity::Unreadable(reason) => {
+                warn!("   ⚠️  Integrity probe could not read source: {}", reason);
+            }
+        }
+    }
+
     let detection = crate::detection_api::detect_video_with_cache(input, cache)?;
+    drop(probe_phase);
 
     // Warn about dynamic HDR metadata that will be stripped during re-encode
     if detection.is_dolby_vision {
@@ -327,11 +851,59 @@ pub fn auto_convert_with_cache(
     }
 
     let mut detection = detection;
+    config.apply_audio_policy(&mut detection);
+    shared_utils::set_audio_transcode(config.audio_transcode);
     let mut explore_result_opt: Option<shared_utils::ExploreResult> = None;
 
-    let strategy = determine_strategy_with_apple_compat(&detection, config.apple_compat);
+    let strategy = config.skip_redundant_remux(
+        determine_strategy_with_apple_compat(&detection, config.apple_compat),
+        input,
+    );
+
+    if !config.accepts_codec(&detection.codec) {
+        let reason = format!(
+            "Skipped: codec {} not in --only-codecs",
+            detection.codec.as_str()
+        );
+        info!(
+            "   ⏭️  {}: codec {} not in --only-codecs, copying through",
+            input.display(),
+            detection.codec.as_str()
+        );
+        shared_utils::progress_mode::video_skipped(&reason);
+
+        shared_utils::copy_on_skip_or_fail(
+            input,
+            config.output_dir.as_deref(),
+            config.base_dir.as_deref(),
+            false,
+        )
+        .map_err(|e| VidQualityError::GeneralError(e.to_string()))?;
+
+        return Ok(ConversionOutput {
+            input_path: input.display().to_string(),
+            output_path: "".to_string(),
+            strategy,
+            input_size: detection.file_size,
+            output_size: 0,
+            size_ratio: 0.0,
+            success: true,
+            message: reason,
+            final_crf: 0.0,
+            exploration_attempts: 0,
+            benchmark: None,
+            skip_reason: Some(SkipReason::UserOverride),
+            ssim: None,
+        });
+    }
 
     if strategy.target == TargetVideoFormat::Skip {
+        if config.is_trimmed() {
+            warn!(
+                "   ⚠️  --start/--end ignored: source is skipped ({})",
+                strategy.reason
+            );
+        }
         info!("🎬 Auto Mode: {} → SKIP", input.display());
         info!("   Reason: {}", strategy.reason);
 
@@ -356,37 +928,57 @@ pub fn auto_convert_with_cache(
             message: "Skipped modern codec to avoid generation loss".to_string(),
             final_crf: 0.0,
             exploration_attempts: 0,
+            benchmark: None,
+            skip_reason: Some(strategy_skip_reason(&detection, config.apple_compat)),
+            ssim: None,
         });
     }
 
-    let output_dir = config
-        .output_dir
-        .clone()
-        .unwrap_or_else(|| input.parent().unwrap_or(Path::new(".")).to_path_buf());
-
-    std::fs::create_dir_all(&output_dir)?;
+    // --start/--end: every read of the source is limited to the range, so the SSIM reference,
+    // the MS-SSIM long-video skip and sampling frame counts all see the trimmed duration.
+    let _trim = if config.is_trimmed() {
+        Some(prepare_trim(&mut detection, config)?)
+    } else {
+        None
+    };
+    let strategy = if config.is_trimmed() && strategy.target == TargetVideoFormat::Remux {
+        ConversionStrategy {
+            target: TargetVideoFormat::Av1Mp4,
+            reason: "Trimmed segment - stream copy cannot cut frame-accurately, re-encoding"
+                .to_string(),
+            crf: shared_utils::crf_constants::AV1_CRF_VISUALLY_LOSSLESS,
+            ..strategy
+        }
+    } else {
+        strategy
+    };
 
-    let stem = input
-        .file_stem()
-        .and_then(|s| s.to_str())
-        .unwrap_or("output");
-    let target_ext = strategy.target.extension();
+    let target_ext = config
+        .container_for(strategy.target)
+        .map_or(strategy.target.extension(), |c| c.extension());
     let input_ext = input.extension().and_then(|e| e.to_str()).unwrap_or("");
     // GIF as source has no Apple compatibility issue; do not show "APPLE COMPAT FALLBACK" for GIF→video.
     let source_is_gif = input_ext.eq_ignore_ascii_case("gif");
 
-    let output_path = if input_ext.eq_ignore_ascii_case(target_ext) {
-        output_dir.join(format!("{}_av1.{}", stem, target_ext))
-    } else {
-        output_dir.join(format!("{}.{}", stem, target_ext))
-    };
+    let output_path = planned_output_path(input, &strategy, config)?;
+    if let Some(parent) = output_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let output_path =
+        shared_utils::conversion::resolve_output_collision(input, output_path, config.on_collision)
+            .map_err(VidQualityError::ConversionError)?;
     shared_utils::conversion::validate_output_path(&output_path, config.base_dir.as_deref())
         .map_err(VidQualityError::ConversionError)?;
 
     shared_utils::path_validator::check_input_output_conflict(input, &output_path)
         .map_err(|e| VidQualityError::ConversionError(e.to_string()))?;
 
-    if output_path.exists() && !config.force {
+    if shared_utils::conversion::keeps_existing_output(
+        input,
+        &output_path,
+        config.force,
+        config.on_collision,
+    ) {
         info!("⏭️ Output exists, skipping: {}", output_path.display());
         return Ok(ConversionOutput {
             input_path: input.display().to_string(),
@@ -399,6 +991,9 @@ pub fn auto_convert_with_cache(
             message: format!("Skipped: output exists ({})", output_path.display()),
             final_crf: 0.0,
             exploration_attempts: 0,
+            benchmark: None,
+            skip_reason: Some(SkipReason::AlreadyProcessed),
+            ssim: None,
         });
     }
 
@@ -411,9 +1006,32 @@ pub fn auto_convert_with_cache(
     );
     info!("   Reason: {}", strategy.reason);
 
+    let downscale = config
+        .max_dimension
+        .and_then(|max| shared_utils::downscale_dimensions(detection.width, detection.height, max));
+    if let Some((w, h)) = downscale {
+        info!(
+            "   📐 Downscaling {}x{} → {}x{} (max dimension {})",
+            detection.width,
+            detection.height,
+            w,
+            h,
+            config.max_dimension.unwrap_or_default()
+        );
+    }
+
+    // Only the lossy explore path runs the `-vf` chain through the SSIM judge, so that is the
+    // only place `--denoise` is applied.
+    let mut denoised = None;
     let (output_size, final_crf, attempts) = match strategy.target {
         TargetVideoFormat::Ffv1Mkv => {
-            let size = execute_ffv1_conversion(&detection, &temp_path, config.child_threads)?;
+            let size = execute_ffv1_conversion(
+                &detection,
+                &temp_path,
+                config.child_threads,
+                config.max_dimension,
+                config.encode_settings(),
+            )?;
             (size, 0.0, 0)
         }
         TargetVideoFormat::Av1Mp4 => {
@@ -423,39 +1041,81 @@ pub fn auto_convert_with_cache(
                 } else {
                     info!("   🚀 Using AV1 Mathematical Lossless Mode");
                 }
-                let size = execute_av1_lossless(&detection, &temp_path, config.child_threads)?;
+                let size = execute_av1_lossless(
+                    &detection,
+                    &temp_path,
+                    config.child_threads,
+                    config.max_dimension,
+                    config.encode_settings(),
+                )?;
                 (size, 0.0, 0)
             } else {
-                let vf_args = shared_utils::get_ffmpeg_dimension_args(
-                    detection.width,
-                    detection.height,
-                    false,
-                );
+                if detection.is_variable_frame_rate && config.force_cfr {
+                    warn!(
+                        "   ⚠️  VFR source re-timed to constant frame rate (--force-cfr): frames may be duplicated or dropped"
+                    );
+                }
+                let vf_args = explore_vf_args(&detection, config);
+                let _frame_rate_guard = explore_frame_rate_mode(&detection, config);
+                if let Some(level) = config.denoise {
+                    info!(
+                        "   🧽 Denoising ({}): SSIM is judged against the denoised reference",
+                        level.as_str()
+                    );
+                    denoised = Some(level);
+                }
                 let input_path = Path::new(&detection.file_path);
+                let _film_grain_guard =
+                    shared_utils::encode_film_grain(film_grain_for(config, &detection));
 
                 // Log media info to log file only (for SSIM/quality context); not shown on terminal.
-                if let Ok(quality_analysis) =
+                let detected_content =
                     shared_utils::analyze_video_quality_from_detection(&detection)
-                {
-                    shared_utils::log_media_info_for_quality(&quality_analysis, input_path);
+                        .map(|quality_analysis| {
+                            shared_utils::log_media_info_for_quality(&quality_analysis, input_path);
+                            quality_analysis.content_type.to_content_type()
+                        })
+                        .unwrap_or_default();
+                let content = config.content_type.unwrap_or(detected_content);
+                if content != shared_utils::ContentType::Unknown {
+                    info!(
+                        "   🎞️  Content: {} (CRF search capped at {:.0})",
+                        content.as_str(),
+                        shared_utils::content_crf_cap(content, shared_utils::VideoEncoder::Av1)
+                    );
                 }
-
-                let flag_mode = shared_utils::validate_flags_result_with_ultimate(
-                    config.explore_smaller,
-                    config.match_quality,
-                    config.require_compression,
-                    config.ultimate_mode,
-                )
-                .map_err(VidQualityError::ConversionError)?;
+                let _content_guard = shared_utils::search_content_type(content);
+
+                // --explore-mode names the search directly; the flag combination is only
+                // validated without it.
+                let (ultimate, mode_label) = match config.explore_mode {
+                    Some(mode) => (false, shared_utils::create_strategy(mode).description()),
+                    None => {
+                        let flag_mode = shared_utils::validate_flags_result_with_ultimate(
+                            config.explore_smaller,
+                            config.match_quality,
+                            config.require_compression,
+                            config.ultimate_mode,
+                        )
+                        .map_err(VidQualityError::ConversionError)?;
+                        (flag_mode.is_ultimate(), flag_mode.description_en())
+                    }
+                };
 
                 let use_gpu = config.use_gpu;
                 if !use_gpu {
-                    info!("   🖥️  CPU Mode: Using libaom for maximum SSIM (≥0.98)");
+                    info!(
+                        "   🖥️  CPU Mode: Using {} for maximum SSIM (≥0.98)",
+                        shared_utils::av1_backend().ffmpeg_name()
+                    );
                 }
 
-                let ultimate = flag_mode.is_ultimate();
-
-                let predicted_crf = calculate_matched_crf(&detection)? as f32;
+                let predicted_crf = calculate_matched_crf_with_options(
+                    &detection,
+                    config.match_mode,
+                    config.quality_bias,
+                    config.crf_bias,
+                )? as f32;
                 let warm_start_crf = if let Some(hint) = detection.precision.last_best_crf {
                     info!("   💡 Using cached CRF hint: {:.1} (warm start only)", hint);
                     Some(hint)
@@ -467,6 +1127,8 @@ pub fn auto_convert_with_cache(
                     Some(hint)
                 } else if let Some(hint) =
                     shared_utils::crf_constants::get_global_last_hit_crf_av1()
+                        // One file's result must not steer the next file's search.
+                        .filter(|_| !config.deterministic)
                 {
                     info!("   💡 Using global last hit CRF: {:.1} (warm start only)", hint);
                     Some(hint)
@@ -474,14 +1136,32 @@ pub fn auto_convert_with_cache(
                     None
                 };
                 let search_crf = warm_start_crf.unwrap_or(predicted_crf);
+                let crf_confidence = matched_crf_confidence(&detection);
                 info!(
-                    "   {} {}: base CRF {:.1} → search anchor {:.1}",
+                    "   {} {}: base CRF {:.1} (confidence {:.0}%) → search anchor {:.1}",
                     if ultimate { "🔥" } else { "🔬" },
-                    flag_mode.description_en(),
+                    mode_label,
                     predicted_crf,
+                    crf_confidence * 100.0,
                     search_crf
                 );
-                let explore_result = if ultimate {
+                let _crf_prediction = shared_utils::encode_crf_prediction(Some((
+                    predicted_crf as f64,
+                    crf_confidence,
+                )));
+                let explore_result = if let Some(mode) = config.explore_mode {
+                    shared_utils::explore_with_mode(
+                        mode,
+                        input_path,
+                        &temp_path,
+                        shared_utils::VideoEncoder::Av1,
+                        vf_args,
+                        search_crf,
+                        config.min_ssim,
+                        config.child_threads,
+                        config.encode_settings(),
+                    )
+                } else if ultimate {
                     shared_utils::explore_av1_with_gpu_coarse_ultimate_warm_start(
                         input_path,
                         &temp_path,
@@ -490,7 +1170,9 @@ pub fn auto_convert_with_cache(
                         warm_start_crf,
                         ultimate,
                         config.allow_size_tolerance,
+                        Some(config.min_ssim),
                         config.child_threads,
+                        config.encode_settings(),
                     )
                 } else {
                     shared_utils::explore_av1_with_gpu_coarse_full_warm_start(
@@ -504,6 +1186,7 @@ pub fn auto_convert_with_cache(
                         config.allow_size_tolerance,
                         config.min_ssim,
                         config.child_threads,
+                        config.encode_settings(),
                     )
                 }
                 .map_err(|e| VidQualityError::ConversionError(e.to_string()))?;
@@ -666,13 +1349,19 @@ pub fn auto_convert_with_cache(
                             explore_result.optimal_crf,
                             explore_result.iterations
                         );
-                        shared_utils::conversion::commit_temp_to_output_with_metadata(
+                        if !shared_utils::conversion::commit_temp_to_output_with_metadata(
                             &temp_path,
                             &output_path,
                             config.force,
                             Some(input),
+                            config.strip,
+                            config.on_collision,
                         )
-                        .map_err(|e| VidQualityError::ConversionError(e.to_string()))?;
+                        .map_err(|e| VidQualityError::ConversionError(e.to_string()))?
+                        {
+                            info!("⏭️ Output was created concurrently, skipping overwrite");
+                            return Ok(concurrent_output_skip(input, &detection, &strategy));
+                        }
                         return Ok(ConversionOutput {
                             input_path: input.display().to_string(),
                             output_path: output_path.display().to_string(),
@@ -695,6 +1384,9 @@ pub fn auto_convert_with_cache(
                             ),
                             final_crf: explore_result.optimal_crf,
                             exploration_attempts: explore_result.iterations as u8,
+                            benchmark: None,
+                            skip_reason: None,
+                            ssim: None,
                         });
                     }
 
@@ -733,6 +1425,9 @@ pub fn auto_convert_with_cache(
                         message: fail_message,
                         final_crf: explore_result.optimal_crf,
                         exploration_attempts: explore_result.iterations as u8,
+                        benchmark: None,
+                        skip_reason: None,
+                        ssim: None,
                     });
                 }
 
@@ -784,6 +1479,9 @@ pub fn auto_convert_with_cache(
                             ),
                             final_crf: explore_result.optimal_crf,
                             exploration_attempts: explore_result.iterations as u8,
+                            benchmark: None,
+                            skip_reason: None,
+                            ssim: None,
                         });
                     }
 
@@ -821,6 +1519,9 @@ pub fn auto_convert_with_cache(
                         ),
                         final_crf: explore_result.optimal_crf,
                         exploration_attempts: explore_result.iterations as u8,
+                        benchmark: None,
+                        skip_reason: None,
+                        ssim: None,
                     });
                 }
 
@@ -831,6 +1532,19 @@ pub fn auto_convert_with_cache(
                 )
             }
         }
+        TargetVideoFormat::Remux => {
+            if downscale.is_some() {
+                warn!(
+                    "   ⚠️  --max-dimension not applied: remux copies the video stream unchanged"
+                );
+            }
+            info!(
+                "   📦 Remux only: copying streams into {} (no re-encode, quality check skipped)",
+                target_ext
+            );
+            let size = execute_remux(&detection, &temp_path, config.strip)?;
+            (size, 0.0, 0)
+        }
         TargetVideoFormat::Skip => unreachable!(),
         _ => unreachable!("AV1 tool should not return HEVC target"),
     };
@@ -844,10 +1558,11 @@ pub fn auto_convert_with_cache(
     let cache_best_effort_hint =
         best_effort_status_for_cache(strategy.target, &explore_result_opt, final_crf);
 
-    if cache_exact_hint && final_crf > 0.0 {
+    if cache_exact_hint && final_crf > 0.0 && !config.deterministic {
         shared_utils::crf_constants::update_global_last_hit_crf_av1(final_crf);
     }
-    if let Some(cache) = cache {
+    // Hints from a trimmed segment do not describe the whole source.
+    if let Some(cache) = cache.filter(|_| !config.is_trimmed()) {
         if cache_exact_hint || cache_best_effort_hint {
             if cache_exact_hint {
                 detection.precision.last_best_crf = Some(final_crf);
@@ -871,37 +1586,15 @@ pub fn auto_convert_with_cache(
         }
     }
 
-    if !shared_utils::conversion::commit_temp_to_output_with_metadata(
-        &temp_path,
-        &output_path,
-        config.force,
-        Some(input),
-    )
-    .map_err(|e| VidQualityError::ConversionError(e.to_string()))?
-    {
-        info!("⏭️ Output was created concurrently, skipping overwrite");
-        return Ok(ConversionOutput {
-            input_path: input.display().to_string(),
-            output_path: String::new(),
-            strategy: strategy.clone(),
-            input_size: detection.file_size,
-            output_size: 0,
-            size_ratio: 1.0,
-            success: true,
-            message: "Skipped: output was created concurrently".to_string(),
-            final_crf: 0.0,
-            exploration_attempts: 0,
-        });
-    }
-
-    shared_utils::copy_metadata(input, &output_path);
+    // Validate the temp file (with metadata applied) so a rejected output never reaches output_path.
+    shared_utils::copy_metadata(input, &temp_path, config.strip);
 
-    let actual_output_size = std::fs::metadata(&output_path)
+    let actual_output_size = std::fs::metadata(&temp_path)
         .map(|m| m.len())
         .unwrap_or(output_size);
 
     let input_stream_info = shared_utils::extract_stream_sizes(input);
-    let output_stream_info = shared_utils::extract_stream_sizes(&output_path);
+    let output_stream_info = shared_utils::extract_stream_sizes(&temp_path);
     let verify_result = shared_utils::verify_pure_media_compression(
         &input_stream_info,
         &output_stream_info,
@@ -935,7 +1628,11 @@ pub fn auto_convert_with_cache(
         total_file_compressed
     };
 
-    if config.require_compression && !total_within_tolerance {
+    // A remux is bit-identical, so its size only reflects the container; don't reject it.
+    if config.require_compression
+        && !total_within_tolerance
+        && strategy.target != TargetVideoFormat::Remux
+    {
         warn!("   ⚠️  COMPRESSION FAILED (total file comparison):");
         warn!(
             "   ⚠️  Total file: {} → {} ({:+.1}%)",
@@ -969,6 +1666,19 @@ pub fn auto_convert_with_cache(
                 "   Keeping best-effort output: last attempt CRF {:.1} ({} iterations), file is AV1 and importable",
                 final_crf, attempts
             );
+            if !shared_utils::conversion::commit_temp_to_output_with_metadata(
+                &temp_path,
+                &output_path,
+                config.force,
+                Some(input),
+                config.strip,
+                config.on_collision,
+            )
+            .map_err(|e| VidQualityError::ConversionError(e.to_string()))?
+            {
+                info!("⏭️ Output was created concurrently, skipping overwrite");
+                return Ok(concurrent_output_skip(input, &detection, &strategy));
+            }
             return Ok(ConversionOutput {
                 input_path: input.display().to_string(),
                 output_path: output_path.display().to_string(),
@@ -990,11 +1700,14 @@ pub fn auto_convert_with_cache(
                 ),
                 final_crf,
                 exploration_attempts: attempts,
+                benchmark: None,
+                skip_reason: None,
+                ssim: None,
             });
         }
 
-        if output_path.exists() {
-            cleanup_output_file(&output_path, "compression failure cleanup");
+        if temp_path.exists() {
+            cleanup_output_file(&temp_path, "compression failure cleanup");
             info!("   🗑️  Output deleted (cannot compress by total file size)");
         }
         shared_utils::copy_on_skip_or_fail(
@@ -1033,6 +1746,9 @@ pub fn auto_convert_with_cache(
             ),
             final_crf,
             exploration_attempts: attempts,
+            benchmark: None,
+            skip_reason: None,
+            ssim: None,
         });
     }
 
@@ -1048,10 +1764,46 @@ pub fn auto_convert_with_cache(
         );
     }
 
+    if !shared_utils::conversion::commit_temp_to_output_with_metadata(
+        &temp_path,
+        &output_path,
+        config.force,
+        Some(input),
+        config.strip,
+        config.on_collision,
+    )
+    .map_err(|e| VidQualityError::ConversionError(e.to_string()))?
+    {
+        info!("⏭️ Output was created concurrently, skipping overwrite");
+        return Ok(concurrent_output_skip(input, &detection, &strategy));
+    }
+
+    if shared_utils::provenance_enabled() {
+        let mut provenance = shared_utils::Provenance::new(detection.codec.as_str());
+        if !(strategy.lossless || config.use_lossless) {
+            provenance.crf = Some(final_crf);
+        }
+        provenance.ssim = explore_result_opt.as_ref().and_then(|r| r.ssim);
+        shared_utils::write_provenance(input, &output_path, &provenance, config.strip);
+    }
+
+    if config.quality_report {
+        let (ssim, psnr) = explore_result_opt
+            .as_ref()
+            .map_or((None, None), |r| (r.ssim, r.psnr));
+        shared_utils::write_quality_xattrs(&output_path, ssim, psnr, detection.codec.as_str());
+    }
+
     let size_ratio = actual_output_size as f64 / detection.file_size as f64;
 
     if config.should_delete_original() {
-        if let Err(e) = shared_utils::conversion::safe_delete_original(
+        // Lossless targets must also prove PSNR = ∞; a downscaled output can't, so it gets the decode check only.
+        let delete = if (strategy.lossless || config.use_lossless) && downscale.is_none() {
+            shared_utils::conversion::safe_delete_original_lossless
+        } else {
+            shared_utils::conversion::safe_delete_original
+        };
+        if let Err(e) = delete(
             input,
             &output_path,
             shared_utils::conversion::MIN_OUTPUT_SIZE_BEFORE_DELETE_VIDEO,
@@ -1079,16 +1831,71 @@ pub fn auto_convert_with_cache(
         output_size: actual_output_size,
         size_ratio,
         success: true,
-        message: if attempts > 0 {
-            format!("Explored {} CRF values, final CRF: {}", attempts, final_crf)
-        } else {
-            "Conversion successful".to_string()
+        message: {
+            let base = if attempts > 0 {
+                format!("Explored {} CRF values, final CRF: {}", attempts, final_crf)
+            } else {
+                "Conversion successful".to_string()
+            };
+            let base = match downscale {
+                Some((w, h)) => format!(
+                    "{} (scaled {}x{} → {}x{})",
+                    base, detection.width, detection.height, w, h
+                ),
+                None => base,
+            };
+            match denoised {
+                Some(level) => format!("{} (denoised: {})", base, level.as_str()),
+                None => base,
+            }
         },
         final_crf,
         exploration_attempts: attempts,
+        benchmark: None,
+        skip_reason: None,
+        ssim: explore_result_opt.as_ref().and_then(|r| r.ssim),
     })
 }
 
+/// `--start` / `--end`: register the range so every ffmpeg read of the source gets input-side
+/// `-ss` / `-to` (cleared when the guard drops). Duration, frame count and size are scaled to the
+/// range; codec, bitrate and HDR fields keep describing the source, so strategy and CRF matching
+/// are unchanged.
+fn prepare_trim(
+    detection: &mut VideoDetectionResult,
+    config: &ConversionConfig,
+) -> Result<shared_utils::SourceTrimGuard> {
+    shared_utils::validate_trim_range(config.start_time, config.end_time, detection.duration_secs)
+        .map_err(VidQualityError::ConversionError)?;
+
+    let source_duration = detection.duration_secs;
+    let guard = shared_utils::trim_source(
+        Path::new(&detection.file_path),
+        config.start_time,
+        config.end_time,
+        source_duration,
+    );
+    let share = shared_utils::trimmed_share(Path::new(&detection.file_path));
+    if source_duration > 0.0 {
+        detection.duration_secs =
+            shared_utils::trimmed_duration(config.start_time, config.end_time, source_duration);
+    }
+    detection.frame_count = (detection.frame_count as f64 * share).round() as u64;
+    // Compare the output against the matching share of the source, not the whole file.
+    detection.file_size = (detection.file_size as f64 * share) as u64;
+    info!(
+        "   ✂️  Trimmed to {:.1}s of {:.1}s ({} frames)",
+        detection.duration_secs, source_duration, detection.frame_count
+    );
+    if detection.is_dolby_vision {
+        warn!("   ⚠️  Dolby Vision RPU is not carried into trimmed output (HDR10 base layer only)");
+        detection.is_dolby_vision = false;
+    }
+    detection.has_subtitles = false;
+    detection.subtitle_codec = None;
+    Ok(guard)
+}
+
 fn success_status_for_cache(
     target: TargetVideoFormat,
     explore_result: &Option<shared_utils::ExploreResult>,
@@ -1113,8 +1920,8 @@ fn best_effort_status_for_cache(
             .unwrap_or(false)
 }
 
-pub fn calculate_matched_crf(detection: &VideoDetectionResult) -> Result<u8> {
-    let analysis = shared_utils::from_video_detection(
+fn build_quality_analysis(detection: &VideoDetectionResult) -> shared_utils::QualityAnalysis {
+    shared_utils::from_video_detection(
         &detection.file_path,
         detection.codec.as_str(),
         detection.width,
@@ -1125,9 +1932,63 @@ pub fn calculate_matched_crf(detection: &VideoDetectionResult) -> Result<u8> {
         detection.has_b_frames,
         detection.bit_depth,
         detection.file_size,
-    );
+    )
+}
+
+/// Estimated output size in bytes for converting `detection` to `target` at `crf`, without
+/// encoding anything.
+///
+/// Lossy targets scale the source video stream by codec efficiency and by the distance
+/// between `crf` and the source's matched CRF (see `shared_utils::estimate_encoded_video_bytes`);
+/// lossless targets use raw frame bytes. Expect ±25% on typical footage.
+pub fn estimate_output_size(
+    detection: &VideoDetectionResult,
+    target: TargetVideoFormat,
+    crf: f64,
+) -> u64 {
+    let analysis = build_quality_analysis(detection);
+    let matched = match target {
+        TargetVideoFormat::HevcMp4 => shared_utils::calculate_hevc_crf(&analysis),
+        _ => shared_utils::calculate_av1_crf(&analysis),
+    };
+    shared_utils::conversion_types::estimate_output_bytes(
+        detection,
+        target,
+        crf,
+        matched.ok().map(|m| m.crf as f64),
+    )
+}
+
+/// 0–1 trust in the matched CRF (see `shared_utils::predict_crf_with_confidence`); 0 when the
+/// quality analysis fails.
+fn matched_crf_confidence(detection: &VideoDetectionResult) -> f32 {
+    shared_utils::predict_crf_with_confidence(
+        &build_quality_analysis(detection),
+        shared_utils::EncoderType::Av1,
+    )
+    .map(|(_, confidence)| confidence)
+    .unwrap_or(0.0)
+}
+
+pub fn calculate_matched_crf(detection: &VideoDetectionResult) -> Result<u8> {
+    calculate_matched_crf_with_options(
+        detection,
+        shared_utils::MatchMode::Quality,
+        shared_utils::QualityBias::Balanced,
+        0.0,
+    )
+}
+
+/// Matched CRF with an explicit match mode and bias (e.g. from `--preset`).
+pub fn calculate_matched_crf_with_options(
+    detection: &VideoDetectionResult,
+    mode: shared_utils::MatchMode,
+    bias: shared_utils::QualityBias,
+    crf_bias: f64,
+) -> Result<u8> {
+    let analysis = build_quality_analysis(detection);
 
-    match shared_utils::calculate_av1_crf(&analysis) {
+    match shared_utils::calculate_av1_crf_with_options(&analysis, mode, bias, crf_bias) {
         Ok(result) => {
             shared_utils::log_quality_analysis(&analysis, &result, shared_utils::EncoderType::Av1);
             Ok(result.crf.round() as u8)
@@ -1143,8 +2004,65 @@ fn execute_ffv1_conversion(
     detection: &VideoDetectionResult,
     output: &Path,
     max_threads: usize,
+    max_dimension: Option<u32>,
+    settings: shared_utils::EncodeSettings,
 ) -> Result<u64> {
-    let vf_args = shared_utils::get_ffmpeg_dimension_args(detection.width, detection.height, false);
+    let _phase = shared_utils::phase_profile::phase(shared_utils::ProfilePhase::FinalEncode);
+    let args = ffv1_args(detection, output, max_threads, max_dimension, settings);
+
+    let process = FfmpegProcess::spawn(Command::new("ffmpeg").args(&args))?;
+    let (status, stderr) = process.wait_with_output()?;
+
+    if !status.success() {
+        cleanup_output_file(output, "failed FFV1 output");
+        return Err(VidQualityError::FFmpegError {
+            message: "FFmpeg command failed".to_string(),
+            stderr,
+            exit_code: status.code(),
+            command: None,
+            file_path: None,
+        });
+    }
+
+    let size = std::fs::metadata(output).map_err(|e| {
+        VidQualityError::ConversionError(format!("Failed to read FFV1 output: {}", e))
+    })?;
+    let size = size.len();
+    if size == 0 {
+        cleanup_output_file(output, "empty FFV1 output");
+        return Err(VidQualityError::ConversionError(
+            "FFV1 output file is empty (encoding may have failed)".to_string(),
+        ));
+    }
+    if shared_utils::conversion::get_input_dimensions(output).is_err() {
+        cleanup_output_file(output, "invalid FFV1 output");
+        return Err(VidQualityError::ConversionError(
+            "FFV1 output file is not readable (invalid or corrupted)".to_string(),
+        ));
+    }
+
+    Ok(size)
+}
+
+/// ffmpeg arguments of [`execute_ffv1_conversion`].
+fn ffv1_args(
+    detection: &VideoDetectionResult,
+    output: &Path,
+    max_threads: usize,
+    max_dimension: Option<u32>,
+    settings: shared_utils::EncodeSettings,
+) -> Vec<String> {
+    let (display_width, display_height) = detection.display_dimensions();
+    // MKV has no dependable display matrix: bake the rotation into the pixels.
+    let vf_args = shared_utils::with_rotation_filter(
+        shared_utils::get_ffmpeg_dimension_args_with_max_dimension(
+            display_width,
+            display_height,
+            false,
+            max_dimension,
+        ),
+        detection.rotation,
+    );
     let input_arg = shared_utils::safe_path_arg(Path::new(&detection.file_path))
         .as_ref()
         .to_string();
@@ -1154,6 +2072,12 @@ fn execute_ffv1_conversion(
         "-y".to_string(),
         "-threads".to_string(),
         max_threads.to_string(),
+        "-noautorotate".to_string(),
+    ];
+    args.extend(shared_utils::source_trim_args(Path::new(
+        &detection.file_path,
+    )));
+    args.extend([
         "-i".to_string(),
         input_arg,
         "-c:v".to_string(),
@@ -1170,7 +2094,7 @@ fn execute_ffv1_conversion(
         max_threads.to_string(),
         "-slicecrc".to_string(),
         "1".to_string(),
-    ];
+    ]);
 
     for arg in &vf_args {
         args.push(arg.clone());
@@ -1182,73 +2106,187 @@ fn execute_ffv1_conversion(
         args.push("-an".to_string());
     }
 
+    args.extend(settings.strip.ffmpeg_args());
+    args.extend(shared_utils::deterministic_args(settings.deterministic));
     args.push(output_arg);
+    args
+}
+
+fn execute_av1_lossless(
+    detection: &VideoDetectionResult,
+    output: &Path,
+    max_threads: usize,
+    max_dimension: Option<u32>,
+    settings: shared_utils::EncodeSettings,
+) -> Result<u64> {
+    warn!("⚠️  Mathematical lossless AV1 encoding (SVT-AV1) - this will be SLOW!");
+    execute_av1_encode(
+        detection,
+        output,
+        None,
+        max_threads,
+        max_dimension,
+        settings,
+    )
+}
 
-    let result = Command::new("ffmpeg").args(&args).output()?;
+/// `--film-grain` / `--film-grain-auto`: grain synthesis level for this file's lossy encodes.
+fn film_grain_for(config: &ConversionConfig, detection: &VideoDetectionResult) -> u8 {
+    let level = if config.film_grain_auto {
+        match shared_utils::estimate_film_grain(
+            Path::new(&detection.file_path),
+            detection.duration_secs,
+        ) {
+            Some(level) => level,
+            None => {
+                warn!("   ⚠️  Film grain estimate failed, encoding without grain synthesis");
+                0
+            }
+        }
+    } else {
+        config.film_grain.unwrap_or(0)
+    };
+    if level > 0 {
+        info!(
+            "   🎞️  Film grain synthesis: level {} (SSIM reads lower than the visual quality)",
+            level
+        );
+    }
+    level
+}
 
-    if !result.status.success() {
-        cleanup_output_file(output, "failed FFV1 output");
+/// Single-pass SVT-AV1 CRF encode to MP4 (no quality exploration).
+fn execute_av1_crf(
+    detection: &VideoDetectionResult,
+    output: &Path,
+    crf: f32,
+    max_threads: usize,
+    max_dimension: Option<u32>,
+    settings: shared_utils::EncodeSettings,
+) -> Result<u64> {
+    execute_av1_encode(
+        detection,
+        output,
+        Some(crf),
+        max_threads,
+        max_dimension,
+        settings,
+    )
+}
+
+/// `crf: None` = mathematically lossless (FLAC audio); `Some(crf)` = lossy CRF with MP4-compatible audio.
+fn execute_av1_encode(
+    detection: &VideoDetectionResult,
+    output: &Path,
+    crf: Option<f32>,
+    max_threads: usize,
+    max_dimension: Option<u32>,
+    settings: shared_utils::EncodeSettings,
+) -> Result<u64> {
+    let _phase = shared_utils::phase_profile::phase(shared_utils::ProfilePhase::FinalEncode);
+    let args = av1_encode_args(detection, output, crf, max_threads, max_dimension, settings);
+
+    let process = FfmpegProcess::spawn(Command::new("ffmpeg").args(&args))?;
+    let (status, stderr) = process.wait_with_output()?;
+
+    if !status.success() {
+        cleanup_output_file(output, "failed AV1 output");
         return Err(VidQualityError::FFmpegError {
             message: "FFmpeg command failed".to_string(),
-            stderr: String::from_utf8_lossy(&result.stderr).to_string(),
-            exit_code: result.status.code(),
+            stderr,
+            exit_code: status.code(),
             command: None,
             file_path: None,
         });
     }
 
     let size = std::fs::metadata(output).map_err(|e| {
-        VidQualityError::ConversionError(format!("Failed to read FFV1 output: {}", e))
+        VidQualityError::ConversionError(format!("Failed to read AV1 output: {}", e))
     })?;
     let size = size.len();
     if size == 0 {
-        cleanup_output_file(output, "empty FFV1 output");
+        cleanup_output_file(output, "empty AV1 output");
         return Err(VidQualityError::ConversionError(
-            "FFV1 output file is empty (encoding may have failed)".to_string(),
+            "AV1 output file is empty (encoding may have failed)".to_string(),
         ));
     }
     if shared_utils::conversion::get_input_dimensions(output).is_err() {
-        cleanup_output_file(output, "invalid FFV1 output");
+        cleanup_output_file(output, "invalid AV1 output");
         return Err(VidQualityError::ConversionError(
-            "FFV1 output file is not readable (invalid or corrupted)".to_string(),
+            "AV1 output file is not readable (invalid or corrupted)".to_string(),
         ));
     }
 
     Ok(size)
 }
 
-fn execute_av1_lossless(
+/// ffmpeg arguments of [`execute_av1_encode`].
+fn av1_encode_args(
     detection: &VideoDetectionResult,
     output: &Path,
+    crf: Option<f32>,
     max_threads: usize,
-) -> Result<u64> {
-    warn!("⚠️  Mathematical lossless AV1 encoding (SVT-AV1) - this will be SLOW!");
-
-    let svt_params = format!("lossless=1:lp={}", max_threads);
+    max_dimension: Option<u32>,
+    settings: shared_utils::EncodeSettings,
+) -> Vec<String> {
+    let (svt_params, crf_arg, preset) = match crf {
+        None => (format!("lossless=1:lp={}", max_threads), "0".to_string(), 4),
+        Some(crf) => (
+            match shared_utils::film_grain_level() {
+                0 => format!("lp={}", max_threads),
+                level => format!("film-grain={}:lp={}", level, max_threads),
+            },
+            format!("{:.0}", crf),
+            settings.preset.svtav1_preset(),
+        ),
+    };
 
-    let vf_args = shared_utils::get_ffmpeg_dimension_args(detection.width, detection.height, false);
+    let (display_width, display_height) = detection.display_dimensions();
+    let vf_args = shared_utils::get_ffmpeg_dimension_args_with_max_dimension(
+        display_width,
+        display_height,
+        false,
+        max_dimension,
+    );
     let input_arg = shared_utils::safe_path_arg(Path::new(&detection.file_path))
         .as_ref()
         .to_string();
     let output_arg = shared_utils::safe_path_arg(output).as_ref().to_string();
 
+    let backend = shared_utils::av1_backend();
     let mut args = vec![
         "-y".to_string(),
         "-threads".to_string(),
         max_threads.to_string(),
+    ];
+    args.extend(shared_utils::source_trim_args(Path::new(
+        &detection.file_path,
+    )));
+    args.extend([
         "-i".to_string(),
         input_arg,
         "-c:v".to_string(),
-        "libsvtav1".to_string(),
+        backend.ffmpeg_name().to_string(),
         "-crf".to_string(),
-        "0".to_string(),
-        "-preset".to_string(),
-        "4".to_string(),
-        "-svtav1-params".to_string(),
-        svt_params,
+        crf_arg,
+    ]);
+    match backend {
+        shared_utils::Av1Backend::Svt => args.extend([
+            "-preset".to_string(),
+            preset.to_string(),
+            "-svtav1-params".to_string(),
+            svt_params,
+        ]),
+        shared_utils::Av1Backend::Aom => args.extend(shared_utils::libaom_args(
+            crf.is_none(),
+            settings.preset,
+            max_threads,
+        )),
+    }
+    args.extend([
         "-pix_fmt".to_string(),
-        hdr_pix_fmt(detection).to_string(),
-    ];
+        hdr_pix_fmt(detection, settings.pix_fmt).to_string(),
+    ]);
 
     args.extend(build_hdr_ffmpeg_args(detection));
 
@@ -1256,45 +2294,62 @@ fn execute_av1_lossless(
         args.push(arg.clone());
     }
 
-    if detection.has_audio {
+    if !detection.has_audio {
+        args.push("-an".to_string());
+    } else if crf.is_none() && shared_utils::container_of(output) != "webm" {
         args.extend(vec!["-c:a".to_string(), "flac".to_string()]);
     } else {
-        args.push("-an".to_string());
+        args.extend(shared_utils::audio_args_for_encode(
+            detection.audio_codec.as_deref(),
+            None,
+            shared_utils::container_of(output),
+        ));
     }
 
+    args.extend(settings.strip.ffmpeg_args());
+    args.extend(shared_utils::deterministic_args(settings.deterministic));
     args.push(output_arg);
+    args
+}
 
-    let result = Command::new("ffmpeg").args(&args).output()?;
+/// Stream-copy the source into the container of `output` (MP4 unless `--container` says
+/// otherwise); only the container changes, the video bitstream is untouched.
+fn execute_remux(
+    detection: &VideoDetectionResult,
+    output: &Path,
+    strip: shared_utils::StripPolicy,
+) -> Result<u64> {
+    let _phase = shared_utils::phase_profile::phase(shared_utils::ProfilePhase::FinalEncode);
+    let args = remux_command_args(detection, output, strip);
+    let process = FfmpegProcess::spawn(Command::new("ffmpeg").args(&args))?;
+    let (status, stderr) = process.wait_with_output()?;
 
-    if !result.status.success() {
-        cleanup_output_file(output, "failed AV1 output");
+    if !status.success() {
         return Err(VidQualityError::FFmpegError {
-            message: "FFmpeg command failed".to_string(),
-            stderr: String::from_utf8_lossy(&result.stderr).to_string(),
-            exit_code: result.status.code(),
+            message: "FFmpeg remux failed".to_string(),
+            stderr,
+            exit_code: status.code(),
             command: None,
             file_path: None,
         });
     }
 
-    let size = std::fs::metadata(output).map_err(|e| {
-        VidQualityError::ConversionError(format!("Failed to read AV1 output: {}", e))
-    })?;
-    let size = size.len();
-    if size == 0 {
-        cleanup_output_file(output, "empty AV1 output");
-        return Err(VidQualityError::ConversionError(
-            "AV1 output file is empty (encoding may have failed)".to_string(),
-        ));
-    }
-    if shared_utils::conversion::get_input_dimensions(output).is_err() {
-        cleanup_output_file(output, "invalid AV1 output");
-        return Err(VidQualityError::ConversionError(
-            "AV1 output file is not readable (invalid or corrupted)".to_string(),
-        ));
-    }
+    Ok(std::fs::metadata(output)?.len())
+}
 
-    Ok(size)
+/// ffmpeg arguments of [`execute_remux`].
+fn remux_command_args(
+    detection: &VideoDetectionResult,
+    output: &Path,
+    strip: shared_utils::StripPolicy,
+) -> Vec<String> {
+    shared_utils::remux_args(
+        detection,
+        output,
+        None,
+        shared_utils::container_of(output),
+        strip,
+    )
 }
 
 pub fn smart_convert(input: &Path, config: &ConversionConfig) -> Result<ConversionOutput> {
@@ -1310,4 +2365,51 @@ mod tests {
         assert_eq!(TargetVideoFormat::Ffv1Mkv.extension(), "MKV");
         assert_eq!(TargetVideoFormat::Av1Mp4.extension(), "MP4");
     }
+
+    #[test]
+    fn test_strategy_apple_compat_flags_av1() {
+        let vp9 = VideoDetectionResult {
+            file_path: "/test/video.webm".to_string(),
+            format: "webm".to_string(),
+            codec: crate::detection_api::DetectedCodec::VP9,
+            compression: CompressionType::Standard,
+            file_size: 50_000_000,
+            ..Default::default()
+        };
+
+        let normal = determine_strategy(&vp9);
+        assert_eq!(normal.target, TargetVideoFormat::Skip);
+
+        let apple = determine_strategy_with_apple_compat(&vp9, true);
+        assert_eq!(apple.target, TargetVideoFormat::Av1Mp4);
+        assert!(apple.reason.ends_with(APPLE_AV1_NOTE));
+
+        let h264 = VideoDetectionResult {
+            codec: crate::detection_api::DetectedCodec::H264,
+            ..vp9
+        };
+        assert!(!determine_strategy(&h264).reason.contains("Apple"));
+        assert!(determine_strategy_with_apple_compat(&h264, true)
+            .reason
+            .contains("not Apple-native"));
+    }
+
+    #[test]
+    fn test_strategy_av1_source_skipped_in_apple_compat() {
+        // Apple compat converts other modern codecs, but never AV1 to AV1 again.
+        let av1 = VideoDetectionResult {
+            file_path: "/test/video.mp4".to_string(),
+            format: "mov,mp4,m4a,3gp,3g2,mj2".to_string(),
+            codec: crate::detection_api::DetectedCodec::AV1,
+            compression: CompressionType::Standard,
+            file_size: 50_000_000,
+            ..Default::default()
+        };
+        for apple_compat in [false, true] {
+            let strategy = determine_strategy_with_apple_compat(&av1, apple_compat);
+            assert_eq!(strategy.target, TargetVideoFormat::Skip);
+            assert!(strategy.reason.contains("already AV1"));
+            assert!(strategy.reason.contains("--reconvert"));
+        }
+    }
 }
@@ -13,24 +13,50 @@
 //! let output_dir = Some(Path::new("output/"));
 //! simple_convert(input, output_dir)?;
 //! ```
+//!
+//! ## Configured Mode
+//! ```rust,ignore
+//! use vid_av1::{simple_convert_with_config, ConversionConfig};
+//!
+//! let config = ConversionConfig { use_lossless: true, force: true, ..Default::default() };
+//! let output = simple_convert_with_config(input, &config)?;
+//! println!("{} → {:.1}%", output.output_path, output.size_ratio * 100.0);
+//! ```
+//!
+//! ## Cancellation
+//! ```rust,ignore
+//! use vid_av1::{auto_convert, CancellationToken, ConversionConfig, VidQualityError};
+//!
+//! let token = CancellationToken::new();
+//! let config = ConversionConfig { cancel_token: Some(token.clone()), ..Default::default() };
+//! // token.cancel() from another thread kills the running encoder.
+//! match auto_convert(input, &config) {
+//!     Err(VidQualityError::Cancelled) => println!("cancelled"),
+//!     other => { other?; }
+//! }
+//! ```
 
 pub mod animated_image;
 pub mod codecs;
 pub mod conversion_api;
 pub mod detection_api;
 pub mod ffprobe;
+pub mod image_sequence;
 
 pub use conversion_api::{
     auto_convert, auto_convert_with_cache, determine_strategy,
-    determine_strategy_with_apple_compat, simple_convert,
+    determine_strategy_with_apple_compat, estimate_output_size, simple_convert,
+    simple_convert_with_config,
 };
 pub use detection_api::{
-    detect_video, detect_video_with_cache, ColorSpace, CompressionType, DetectedCodec,
-    VideoDetectionResult,
+    compare_detections, detect_video, detect_video_with_cache, print_detection_comparison,
+    ColorSpace, CompressionType, DetectedCodec, DetectionDiffRow, VideoDetectionResult,
 };
 pub use ffprobe::{probe_video, FFprobeResult};
 pub use shared_utils::conversion_types::{
-    ConversionConfig, ConversionOutput, ConversionStrategy, TargetVideoFormat,
+    preset_to_config, ConversionConfig, ConversionOutput, ConversionStrategy, QualityPreset,
+    TargetVideoFormat,
 };
 
+pub use shared_utils::cancellation::CancellationToken;
 pub use shared_utils::unified_error::{Result, VidQualityError};
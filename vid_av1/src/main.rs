@@ -4,8 +4,8 @@ use tracing::info;
 
 use shared_utils::analysis_cache::AnalysisCache;
 use vid_av1::{
-    auto_convert_with_cache, detect_video_with_cache, determine_strategy, ConversionConfig,
-    VidQualityError,
+    auto_convert_with_cache, compare_detections, detect_video_with_cache, determine_strategy,
+    print_detection_comparison, ConversionConfig, VidQualityError,
 };
 
 #[derive(Parser)]
@@ -16,6 +16,27 @@ struct Cli {
     command: Commands,
 }
 
+/// Quality preset for `run --preset`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+enum PresetArg {
+    /// SSIM ≥ 0.99, conservative CRF, ultimate search
+    Archive,
+    /// Default SSIM floor and CRF matching
+    Balanced,
+    /// Lower SSIM floor, aggressive CRF, size-oriented
+    Small,
+}
+
+impl From<PresetArg> for vid_av1::QualityPreset {
+    fn from(arg: PresetArg) -> Self {
+        match arg {
+            PresetArg::Archive => vid_av1::QualityPreset::Archive,
+            PresetArg::Balanced => vid_av1::QualityPreset::Balanced,
+            PresetArg::Small => vid_av1::QualityPreset::Small,
+        }
+    }
+}
+
 #[derive(Subcommand)]
 enum Commands {
     #[command(name = "run")]
@@ -71,17 +92,353 @@ enum Commands {
         #[arg(short, long)]
         verbose: bool,
 
+        /// Resume from last run: skip files already in progress file (default).
+        /// Entries are keyed by absolute path, size and mtime, so a changed source is re-encoded.
         #[arg(long, default_value_t = true)]
         resume: bool,
 
+        /// Start fresh: ignore previous progress file, process all files.
         #[arg(long)]
         no_resume: bool,
+
+        #[arg(long, conflicts_with = "keep_going")]
+        fail_fast: bool,
+
+        #[arg(long)]
+        keep_going: bool,
+
+        /// Downscale (lanczos, aspect preserved) when width or height exceeds this, e.g. 1920
+        #[arg(long, value_name = "PIXELS", value_parser = clap::value_parser!(u32).range(2..))]
+        max_dimension: Option<u32>,
+
+        /// Re-time variable-frame-rate sources to constant frame rate (-vsync cfr)
+        #[arg(long)]
+        force_cfr: bool,
+        /// Denoise before encoding (light|medium|strong); SSIM is judged against the denoised source
+        #[arg(long, value_name = "LEVEL", value_parser = shared_utils::parse_denoise_level)]
+        denoise: Option<shared_utils::DenoiseLevel>,
+
+        /// Ultimate mode: stop the per-file search after this many seconds (best-so-far result)
+        #[arg(long, value_name = "SECS")]
+        ultimate_max_seconds: Option<u64>,
+
+        /// Ultimate mode: stop the per-file search after this many encode iterations
+        #[arg(long, value_name = "N")]
+        ultimate_max_iterations: Option<u32>,
+
+        /// Skip the up-front truncated/corrupt file check (faster when files are known good)
+        #[arg(long)]
+        skip_integrity_check: bool,
+
+        /// Parallel files to process (0 = automatic)
+        #[arg(long, value_name = "N", default_value_t = 0)]
+        jobs: usize,
+
+        /// Threads per encoder process (0 = automatic)
+        #[arg(long, value_name = "N", default_value_t = 0)]
+        child_threads: usize,
+
+        /// Quality preset; explicit flags (--min-ssim, --ultimate) still override its fields
+        #[arg(long, value_enum)]
+        preset: Option<PresetArg>,
+
+        /// Minimum SSIM the explore search must reach (default 0.95)
+        #[arg(long, value_name = "SSIM", value_parser = shared_utils::parse_min_ssim)]
+        min_ssim: Option<f64>,
+
+        /// Shift the matched CRF by this much before the search: negative = higher quality, positive = smaller (-10 to 10)
+        #[arg(long, value_name = "CRF", default_value_t = 0.0, allow_hyphen_values = true, value_parser = shared_utils::parse_crf_bias)]
+        crf_bias: f64,
+
+        /// Convert from this position: seconds, MM:SS or HH:MM:SS (frame-accurate)
+        #[arg(long, value_name = "TIME", value_parser = shared_utils::parse_timestamp)]
+        start: Option<f64>,
+
+        /// Stop at this position in the source: seconds, MM:SS or HH:MM:SS
+        #[arg(long, value_name = "TIME", value_parser = shared_utils::parse_timestamp)]
+        end: Option<f64>,
+
+        /// PSNR→SSIM calibration CSV (`psnr,ssim` per line) for the GPU→CPU search handoff
+        #[arg(long, value_name = "CSV")]
+        psnr_ssim_calibration: Option<PathBuf>,
+
+        /// Record measured SSIM/PSNR and source codec on each output as user.mfb.* xattrs
+        #[arg(long)]
+        quality_report: bool,
+
+        /// Only process files with these extensions in directory runs (comma-separated, e.g. mov,mkv)
+        #[arg(long, value_name = "EXT", value_delimiter = ',')]
+        include_ext: Vec<String>,
+
+        /// Skip files with these extensions in directory runs (comma-separated, e.g. gif)
+        #[arg(long, value_name = "EXT", value_delimiter = ',')]
+        exclude_ext: Vec<String>,
+
+        /// Re-run the search on CPU when the GPU result misses the SSIM target (default)
+        #[arg(long, default_value_t = true)]
+        gpu_cpu_fallback: bool,
+
+        /// Keep the GPU result even when it misses the SSIM target
+        #[arg(long)]
+        no_gpu_cpu_fallback: bool,
+
+        /// Only convert sources with these video codecs (ffprobe names, e.g. h264,mpeg2video); others are copied through
+        #[arg(long, value_name = "CODECS", value_delimiter = ',')]
+        only_codecs: Vec<String>,
+
+        /// Print the estimated output size of each file and the total without converting
+        #[arg(long)]
+        dry_run: bool,
+        /// Print the ffmpeg command of each file's final encode, at the CRF the search starts from, without converting (combines with --dry-run)
+        #[arg(long)]
+        print_command: bool,
+
+        /// XMP sidecars: embed into the output, copy beside it as <output-stem>.xmp, or both
+        #[arg(long, value_name = "MODE", default_value = "embed", value_parser = shared_utils::parse_sidecar_mode)]
+        xmp_mode: shared_utils::SidecarMode,
+        /// When a file has several sidecars or existing tags: prefer-sidecar, prefer-existing or merge-union (lists appended)
+        #[arg(long, value_name = "POLICY", default_value = "prefer-sidecar", value_parser = shared_utils::parse_xmp_merge_policy)]
+        xmp_merge_policy: shared_utils::XmpMergePolicy,
+        /// Sidecar extensions to merge, in precedence order, e.g. xmp,thm,yml (default: xmp)
+        #[arg(
+            long,
+            value_name = "EXTS",
+            value_delimiter = ',',
+            default_value = "xmp"
+        )]
+        sidecar_extensions: Vec<String>,
+        /// Live Photo pairs (HEIC + MOV with the same name): skip both halves, or convert both and keep them paired
+        #[arg(long, value_name = "MODE", default_value = "skip", value_parser = shared_utils::parse_live_photo_mode)]
+        live_photos: shared_utils::LivePhotoMode,
+
+        /// Output filename template, e.g. "{stem}_av1.{ext}" (placeholders: {stem} {ext} {codec} {crf} {parent})
+        #[arg(long, value_name = "TEMPLATE", value_parser = shared_utils::OutputTemplate::parse)]
+        output_template: Option<shared_utils::OutputTemplate>,
+
+        /// Drop all audio tracks (-an); subtitles and other streams are unaffected
+        #[arg(long)]
+        no_audio: bool,
+        /// Re-encode PCM/lossless or oversized audio to this codec (opus|aac) instead of copying it
+        #[arg(long, value_name = "CODEC", value_parser = shared_utils::parse_audio_codec, conflicts_with = "no_audio")]
+        audio_codec: Option<shared_utils::AudioCodec>,
+        /// Target bitrate for --audio-codec, e.g. 96k (default 128k)
+        #[arg(long, value_name = "KBPS", value_parser = shared_utils::parse_audio_bitrate, requires = "audio_codec")]
+        audio_bitrate: Option<u32>,
+        /// Retry a file up to N times when it fails with a recoverable error (busy GPU/device, EAGAIN)
+        #[arg(long, value_name = "N", default_value_t = 1)]
+        max_retries: u32,
+        /// Never re-encode these extensions; copy them to --output with their XMP sidecar merged (comma-separated, e.g. mov,mp4)
+        #[arg(long, value_name = "EXT", value_delimiter = ',')]
+        metadata_only_formats: Vec<String>,
+        /// Encoder speed preset for lossy encodes: ultrafast … placebo, or an SVT-AV1 number 0-13
+        #[arg(long, value_name = "PRESET", default_value = "medium", value_parser = shared_utils::EncoderPreset::parse)]
+        preset_speed: shared_utils::EncoderPreset,
+        /// Gate on the mean SSIM of N evenly spaced frames instead of the whole clip (faster on long videos, an estimate)
+        #[arg(long, value_name = "N", value_parser = clap::value_parser!(u32).range(2..))]
+        sample_ssim: Option<u32>,
+        /// Where --sample-ssim takes its frames: uniform, keyframes, scene-change or high-motion (the last two aim at the hardest frames)
+        #[arg(long, value_name = "STRATEGY", default_value = "uniform", value_parser = shared_utils::SsimSamplingStrategy::parse, requires = "sample_ssim")]
+        ssim_sampling_strategy: shared_utils::SsimSamplingStrategy,
+        /// Hard cap on each output (e.g. 25M): raise CRF past the quality match until it fits, rejecting files that cannot
+        #[arg(long, value_name = "SIZE", value_parser = shared_utils::parse_byte_size)]
+        max_output_size: Option<u64>,
+        /// Container for lossy encodes and remuxes: mp4 (default), mkv or webm
+        #[arg(long, value_name = "CONTAINER", value_parser = shared_utils::conversion_types::VideoContainer::parse)]
+        container: Option<shared_utils::conversion_types::VideoContainer>,
+        /// Byte-identical output across runs: CPU encoders only, one frame thread, no CRF hints, bit-exact muxing
+        #[arg(long)]
+        deterministic: bool,
+        /// Run one CRF search directly instead of mapping --explore/--match-quality/--compress (see README)
+        #[arg(long, value_name = "MODE", value_parser = shared_utils::ExploreMode::parse)]
+        explore_mode: Option<shared_utils::ExploreMode>,
+        /// Print one JSON line per file event (start, skip, success, fail) to stdout as it happens
+        #[arg(long)]
+        events_json: bool,
+        /// Process only the first N pending files in queue order (files finished by --resume do not count)
+        #[arg(long, value_name = "N", value_parser = clap::value_parser!(u32).range(1..))]
+        limit: Option<u32>,
+        /// Kill an encode that runs longer than SECS and count the file as failed (the run continues)
+        #[arg(long, value_name = "SECS", value_parser = clap::value_parser!(u64).range(1..))]
+        timeout: Option<u64>,
+        /// Create scratch files (decoded frames, intermediate streams) here instead of the system temp dir
+        #[arg(long, value_name = "PATH")]
+        temp_dir: Option<PathBuf>,
+        /// Record per-file wall time, frames and MB/s and add a throughput table per encoder to the summary
+        #[arg(long)]
+        benchmark: bool,
+        /// Drop EXIF/GPS/XMP from outputs instead of preserving them (file timestamps are still kept)
+        #[arg(long)]
+        strip_metadata: bool,
+        /// With --strip-metadata: also leave output file timestamps at conversion time
+        #[arg(long, requires = "strip_metadata")]
+        strip_timestamps: bool,
+        /// SVT-AV1 film grain synthesis level for lossy encodes (0-50): grainy film keeps its look at a lower bitrate
+        #[arg(long, value_name = "N", value_parser = clap::value_parser!(u8).range(0..=50), conflicts_with = "film_grain_auto")]
+        film_grain: Option<u8>,
+        /// Estimate the film grain level per file from a noise-measurement pass
+        #[arg(long)]
+        film_grain_auto: bool,
+        /// After the summary, list every output not smaller than its source (kept under --allow-size-tolerance) with the total bytes wasted
+        #[arg(long)]
+        only_larger_than_source: bool,
+        /// Order of the per-file table printed after the summary: size-reduction (least saved first), original-size (largest first), ssim (lowest first) or name; the table is also shown, by name, in verbose mode
+        #[arg(long, value_name = "KEY", value_parser = shared_utils::ReportSortKey::parse)]
+        sort_report_by: Option<shared_utils::ReportSortKey>,
+        /// Descend into symlinked directories and include symlinked files; each canonical path is visited once, so loops and symlink farms are not double-processed
+        #[arg(long)]
+        follow_symlinks: bool,
+        /// Ignore symlinks while collecting files (default)
+        #[arg(long)]
+        no_follow_symlinks: bool,
+        /// Append every converted output to this tar (.tar, .tar.gz/.tgz, or .tar.zst via the zstd tool); the file must not exist yet
+        #[arg(long, value_name = "PATH")]
+        archive: Option<PathBuf>,
+        /// With --archive: delete each loose output once it is in the archive
+        #[arg(long, requires = "archive")]
+        archive_delete_outputs: bool,
+        /// Skip a file only while its existing output is at least as new as it (outputs keep the source mtime); edited sources are converted again, overwriting the stale output
+        #[arg(long, conflicts_with = "force")]
+        skip_up_to_date: bool,
+        /// Print a per-file breakdown of where time went: probe, GPU coarse search, CPU search, final encode, SSIM validation, metadata
+        #[arg(long)]
+        profile: bool,
+        /// Bound the CRF search for this content instead of the detected type: screen, animation, gaming, film-grain, live-action (alias film)
+        #[arg(long, value_name = "TYPE", value_parser = shared_utils::ContentType::parse)]
+        content_type: Option<shared_utils::ContentType>,
+        /// Embed a JSON note (tool, version, source codec, CRF, SSIM, date) in each output: the container comment for video, EXIF/XMP UserComment for images
+        #[arg(long)]
+        provenance: bool,
+        /// Memory budget for parallel encodes (e.g. 8G): each file waits until its estimated use (from resolution and bit depth) fits next to the running ones; default 60% of RAM
+        #[arg(long, value_name = "SIZE", value_parser = shared_utils::parse_byte_size)]
+        max_memory: Option<u64>,
+        /// Put every output directly in --output instead of recreating the source folders; sources from subfolders get a hash of their folder appended (name-a1b2c3d4.jxl) so same-named files cannot collide
+        #[arg(long, requires = "output")]
+        flatten_output: bool,
+        /// AV1 encoder: svt (SVT-AV1, default) or aom (libaom, slower); falls back to the other when this ffmpeg lacks it
+        #[arg(long, value_name = "ENCODER", default_value = "svt", value_parser = shared_utils::Av1Backend::parse)]
+        av1_encoder: shared_utils::Av1Backend,
+        /// After the batch, re-open every output (decode, at least one frame); delete any that fail and keep their originals (--delete-original waits for this check)
+        #[arg(long, conflicts_with = "archive")]
+        verify_after: bool,
+        /// What to do when an output already exists: skip (default; with --skip-up-to-date only while current), overwrite, rename (write "name (1).ext" next to it) or error; --force still overwrites
+        #[arg(long, value_name = "POLICY", default_value = "skip", value_parser = shared_utils::CollisionPolicy::parse, conflicts_with = "force")]
+        on_collision: shared_utils::CollisionPolicy,
+        /// Re-encode sources that are already AV1; by default they are skipped (or only remuxed) so running on earlier outputs does not lose quality each generation
+        #[arg(long)]
+        reconvert: bool,
+        /// Leave skipped and failed files out of --output instead of copying them through, so it holds converted files only rather than a complete mirror of the input (matters for sync tools that mirror the output dir)
+        #[arg(long)]
+        no_copy_skipped: bool,
+        /// Skip files whose content matches one already converted in this run (perceptual hash of the image, or of frames sampled across a video), copying them through instead; the summary reports how many were skipped
+        #[arg(long)]
+        dedup: bool,
+        /// Pixel format for every encode (yuv420p, yuv420p10le, yuv422p, yuv422p10le, yuv444p, yuv444p10le) instead of following the source's bit depth; the source's color range (tv/pc) is kept either way
+        #[arg(long, value_name = "FORMAT", value_parser = shared_utils::PixelFormat::parse)]
+        pix_fmt: Option<shared_utils::PixelFormat>,
     },
 
+    #[command(alias = "analyze")]
     Strategy {
         #[arg(value_name = "INPUT")]
         input: PathBuf,
+        /// Also run the quality analysis (compression level, content type)
+        #[arg(long)]
+        deep: bool,
+        /// Print machine-readable JSON instead of the summary
+        #[arg(long)]
+        json: bool,
+        /// Print CSV for spreadsheets instead: a header row, then one row per file. INPUT may be a directory, which is scanned recursively
+        #[arg(long, conflicts_with_all = ["json", "compare"])]
+        csv: bool,
+        /// Instead of a strategy, show INPUT and PATH side by side and mark the properties that differ (codec, pix_fmt, bit depth, color, bitrate, ...)
+        #[arg(long, value_name = "PATH", conflicts_with = "deep")]
+        compare: Option<PathBuf>,
+    },
+
+    /// Re-apply metadata (EXIF, xattr, timestamps, ACL, XMP sidecar) from SOURCE onto DEST without re-encoding
+    CopyMetadata {
+        #[arg(value_name = "SOURCE")]
+        source: PathBuf,
+        #[arg(value_name = "DEST")]
+        dest: PathBuf,
     },
+
+    /// Encode numbered frames (a directory, frame_%04d.png or "frame_*.png") into one AV1 clip
+    ImageSequence {
+        #[arg(value_name = "PATTERN")]
+        pattern: PathBuf,
+        /// Frame rate of the sequence
+        #[arg(long, default_value = "30", value_parser = shared_utils::parse_fps)]
+        fps: f64,
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        #[arg(short, long)]
+        force: bool,
+        #[arg(long)]
+        apple_compat: bool,
+        #[arg(long, default_value_t = false)]
+        ultimate: bool,
+        /// Minimum SSIM the explore search must reach
+        #[arg(long, value_name = "SSIM", value_parser = shared_utils::parse_min_ssim)]
+        min_ssim: Option<f64>,
+        #[arg(short, long)]
+        verbose: bool,
+    },
+
+    /// Encode the first seconds of INPUT with HEVC and AV1 (optionally VP9) at a fixed CRF and compare size and quality
+    CompareEncoders {
+        #[arg(value_name = "INPUT")]
+        input: PathBuf,
+        /// Sample length taken from the start of the video
+        #[arg(long, value_name = "SECS", default_value_t = shared_utils::DEFAULT_COMPARE_SECONDS)]
+        seconds: f32,
+        /// CRF used for every codec (default: each codec's own default CRF)
+        #[arg(long, value_name = "CRF")]
+        crf: Option<f32>,
+        /// Also encode with VP9 (libvpx-vp9)
+        #[arg(long)]
+        vp9: bool,
+        /// Use the software encoders even when a GPU encoder is available
+        #[arg(long)]
+        cpu: bool,
+    },
+
+    /// Convert new videos as they appear in DIR, once each file has stopped growing
+    Watch {
+        #[arg(value_name = "DIR")]
+        dir: PathBuf,
+        #[arg(short, long)]
+        output: PathBuf,
+        /// Move each successfully converted source here, keeping its path under DIR
+        #[arg(long, value_name = "DIR", conflicts_with = "delete_original")]
+        done_dir: Option<PathBuf>,
+        #[arg(long)]
+        delete_original: bool,
+        /// Seconds a new file's size must stay unchanged before it is converted
+        #[arg(long, value_name = "SECS", default_value_t = shared_utils::watch_folder::DEFAULT_SETTLE_SECS)]
+        settle_secs: u64,
+        #[arg(short, long, default_value_t = true)]
+        recursive: bool,
+        #[arg(short, long)]
+        force: bool,
+        #[arg(long, default_value_t = true)]
+        apple_compat: bool,
+        #[arg(long)]
+        no_apple_compat: bool,
+        #[arg(long, default_value_t = false)]
+        ultimate: bool,
+        /// Minimum SSIM the explore search must reach
+        #[arg(long, value_name = "SSIM", value_parser = shared_utils::parse_min_ssim)]
+        min_ssim: Option<f64>,
+        /// Retry a file up to N times when it fails with a recoverable error (busy GPU/device, EAGAIN)
+        #[arg(long, value_name = "N", default_value_t = 1)]
+        max_retries: u32,
+        #[arg(short, long)]
+        verbose: bool,
+    },
+
+    /// Check tools, ffmpeg encoders, GPU and CPU before a run (exits 1 if something required is missing)
+    Doctor,
 }
 
 fn main() -> anyhow::Result<()> {
@@ -116,21 +473,137 @@ fn main() -> anyhow::Result<()> {
             verbose,
             resume,
             no_resume,
+            fail_fast,
+            keep_going,
+            max_dimension,
+            force_cfr,
+            denoise,
+            ultimate_max_seconds,
+            ultimate_max_iterations,
+            skip_integrity_check,
+            jobs,
+            child_threads,
+            preset,
+            min_ssim,
+            crf_bias,
+            start,
+            end,
+            psnr_ssim_calibration,
+            quality_report,
+            include_ext,
+            exclude_ext,
+            gpu_cpu_fallback,
+            no_gpu_cpu_fallback,
+            only_codecs,
+            dry_run,
+            print_command,
+            xmp_mode,
+            xmp_merge_policy,
+            sidecar_extensions,
+            live_photos,
+            output_template,
+            no_audio,
+            audio_codec,
+            audio_bitrate,
+            max_retries,
+            metadata_only_formats,
+            preset_speed,
+            sample_ssim,
+            ssim_sampling_strategy,
+            max_output_size,
+            container,
+            deterministic,
+            explore_mode,
+            events_json,
+            limit,
+            timeout,
+            temp_dir,
+            benchmark,
+            strip_metadata,
+            strip_timestamps,
+            film_grain,
+            film_grain_auto,
+            only_larger_than_source,
+            sort_report_by,
+            follow_symlinks,
+            no_follow_symlinks,
+            archive,
+            archive_delete_outputs,
+            skip_up_to_date,
+            profile,
+            content_type,
+            provenance,
+            max_memory,
+            flatten_output,
+            av1_encoder,
+            verify_after,
+            on_collision,
+            reconvert,
+            no_copy_skipped,
+            dedup,
+            pix_fmt,
         } => {
+            if let Some(dir) = &temp_dir {
+                if let Err(e) = shared_utils::conversion::validate_temp_dir(dir) {
+                    eprintln!("Invalid --temp-dir: {}", e);
+                    std::process::exit(1);
+                }
+                shared_utils::conversion::set_temp_dir(dir.clone());
+            }
+            shared_utils::set_sidecar_mode(xmp_mode);
+            shared_utils::set_xmp_merge_policy(xmp_merge_policy);
+            shared_utils::set_live_photo_mode(live_photos);
+            match shared_utils::parse_sidecar_extensions(&sidecar_extensions.join(",")) {
+                Ok(extensions) => shared_utils::set_sidecar_extensions(extensions),
+                Err(e) => {
+                    eprintln!("Invalid --sidecar-extensions: {}", e);
+                    std::process::exit(1);
+                }
+            }
+            shared_utils::set_follow_symlinks(follow_symlinks && !no_follow_symlinks);
+            shared_utils::set_skip_up_to_date(skip_up_to_date);
+            shared_utils::set_profile(profile);
+            shared_utils::set_provenance(provenance);
+            shared_utils::thread_manager::set_max_memory(max_memory);
+            shared_utils::conversion::set_flatten_output(flatten_output);
+            shared_utils::set_av1_backend(av1_encoder);
+            shared_utils::set_verify_after(verify_after);
+            shared_utils::set_reconvert(reconvert);
+            shared_utils::set_copy_skipped(!no_copy_skipped);
+            if let Some(p) = pix_fmt {
+                if let Err(e) = p.check_encoder(av1_encoder.ffmpeg_name()) {
+                    eprintln!("Invalid --pix-fmt: {}", e);
+                    std::process::exit(1);
+                }
+            }
+            if let Some(template) = output_template {
+                shared_utils::set_output_template(template.with_video_codec("av1"));
+            }
+            shared_utils::set_gpu_cpu_fallback(gpu_cpu_fallback && !no_gpu_cpu_fallback);
+            shared_utils::set_global_ultimate_budget(shared_utils::UltimateBudget {
+                max_seconds: ultimate_max_seconds,
+                max_iterations: ultimate_max_iterations,
+            });
+            shared_utils::thread_manager::apply_cli_thread_overrides(
+                jobs,
+                child_threads,
+                shared_utils::thread_manager::WorkloadType::Video,
+            );
+            if let Some(path) = psnr_ssim_calibration.as_deref() {
+                match shared_utils::PsnrSsimMapping::load_csv(path) {
+                    Ok(mapping) => {
+                        shared_utils::ssim_mapping::set_global_calibration(mapping);
+                    }
+                    Err(e) => {
+                        eprintln!("Invalid --psnr-ssim-calibration: {}", e);
+                        std::process::exit(1);
+                    }
+                }
+            }
             let apple_compat = apple_compat && !no_apple_compat;
             let allow_size_tolerance = allow_size_tolerance && !no_allow_size_tolerance;
             let resume = resume && !no_resume;
 
-            if let Err(e) = shared_utils::validate_flags_result_with_ultimate(
-                explore,
-                match_quality,
-                compress,
-                ultimate,
-            ) {
-                eprintln!("{}", e);
-                std::process::exit(1);
-            }
-
             let base_dir =
                 shared_utils::cli_runner::resolve_video_run_base_dir(&input, recursive, base_dir);
 
@@ -150,12 +623,94 @@ fn main() -> anyhow::Result<()> {
                 min_ssim: 0.95,
                 require_compression: compress,
                 apple_compat,
-                use_gpu: true,
+                use_gpu: !deterministic,
                 force_ms_ssim_long,
                 ultimate_mode: ultimate,
                 child_threads: thread_config.child_threads,
                 allow_size_tolerance,
+                max_dimension,
+                force_cfr,
+                denoise,
+                skip_integrity_check,
+                // Human progress already goes to stderr, so stdout carries only the events.
+                // Without --events-json the batch progress bar of `run` is the sink.
+                progress_sink: if events_json {
+                    Some(std::sync::Arc::new(shared_utils::JsonEventSink::stdout()))
+                } else {
+                    None
+                },
+                apple_fallback: None,
+                quality_bias: shared_utils::QualityBias::Balanced,
+                match_mode: shared_utils::MatchMode::Quality,
+                crf_bias,
+                start_time: start,
+                end_time: end,
+                quality_report,
+                only_codecs: shared_utils::conversion_types::parse_codec_list(&only_codecs),
+                drop_audio: no_audio,
+                audio_transcode: audio_codec.map(|codec| shared_utils::AudioTranscode {
+                    codec,
+                    bitrate_kbps: audio_bitrate.unwrap_or(shared_utils::DEFAULT_AUDIO_BITRATE_KBPS),
+                }),
+                preset_speed,
+                sample_ssim,
+                ssim_sampling_strategy,
+                container,
+                explore_mode,
+                // SVT-AV1 has no 4:0:0 mode.
+                auto_grayscale: false,
+                cancel_token: None,
+                per_file_timeout: timeout.map(std::time::Duration::from_secs),
+                temp_dir,
+                benchmark,
+                film_grain,
+                film_grain_auto,
+                content_type,
+                max_output_size,
+                pix_fmt,
+                strip: shared_utils::StripPolicy {
+                    metadata: strip_metadata,
+                    timestamps: strip_timestamps,
+                },
+                on_collision,
+                deterministic,
+            };
+
+            let mut config = match preset {
+                Some(preset) => vid_av1::preset_to_config(preset.into(), config),
+                None => config,
             };
+            // Explicit flags win over the preset.
+            if ultimate {
+                config.ultimate_mode = true;
+            }
+            if let Some(min_ssim) = min_ssim {
+                config.min_ssim = min_ssim;
+            } else if film_grain_auto || film_grain.is_some_and(|n| n > 0) {
+                eprintln!(
+                    "ℹ️  Film grain synthesis changes pixels, so SSIM reads lower than the visual quality; consider a relaxed --min-ssim (e.g. 0.92)"
+                );
+            }
+            let ultimate = config.ultimate_mode;
+
+            // Ordering is checked up front; the source duration is checked per file.
+            if let Err(e) = shared_utils::validate_trim_range(start, end, 0.0) {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            }
+
+            // An explicit --explore-mode replaces the flag combination, so it is not validated.
+            if explore_mode.is_none() {
+                if let Err(e) = shared_utils::validate_flags_result_with_ultimate(
+                    explore,
+                    match_quality,
+                    compress,
+                    ultimate,
+                ) {
+                    eprintln!("{}", e);
+                    std::process::exit(1);
+                }
+            }
 
             shared_utils::progress_mode::set_verbose_mode(verbose);
             // Run 时自动创建并写入 ./logs/vid_av1_run_<timestamp>.log
@@ -182,6 +737,18 @@ fn main() -> anyhow::Result<()> {
             if recursive {
                 info!("   📂 Recursive: ENABLED");
             }
+            // Routing already passes HEVC/AV1/VP9 sources through, so only archival runs get
+            // the default list (already-optimal JXL/HEIC picked up alongside the videos).
+            let metadata_only = shared_utils::MetadataOnlyFormats::resolve(
+                &metadata_only_formats,
+                preset == Some(PresetArg::Archive),
+            );
+            if !metadata_only.is_empty() {
+                info!(
+                    "   📋 Metadata-only: {} (copied through, XMP sidecar merged)",
+                    metadata_only.describe()
+                );
+            }
             if apple_compat {
                 info!("   🍎 Apple Compatibility: ENABLED (⚠️ Note: AV1 not natively supported on older Apple devices)");
                 std::env::set_var("MODERN_FORMAT_BOOST_APPLE_COMPAT", "1");
@@ -189,10 +756,106 @@ fn main() -> anyhow::Result<()> {
             if ultimate {
                 info!("   🔍 Ultimate Explore: ENABLED (search until SSIM saturates)");
             }
+            let av1_backend = shared_utils::av1_backend();
+            info!(
+                "   🎞️  AV1 encoder: {} ({})",
+                av1_backend.label(),
+                av1_backend.ffmpeg_name()
+            );
+            if preset_speed != shared_utils::EncoderPreset::Medium {
+                match av1_backend {
+                    shared_utils::Av1Backend::Svt => info!(
+                        "   ⏱️  Encoder preset: {} (SVT-AV1 preset {})",
+                        preset_speed.x26x_name(),
+                        preset_speed.svtav1_preset()
+                    ),
+                    shared_utils::Av1Backend::Aom => info!(
+                        "   ⏱️  Encoder preset: {} (libaom cpu-used {})",
+                        preset_speed.x26x_name(),
+                        preset_speed.aom_cpu_used()
+                    ),
+                }
+            }
+            if ultimate && preset_speed.is_slower_than_default() {
+                shared_utils::log_eprintln!(
+                    "⚠️  --preset-speed {} with --ultimate: every explore iteration uses the slow preset, expect very long runs",
+                    preset_speed.x26x_name()
+                );
+            }
             if force_ms_ssim_long {
                 info!("   ⚠️  Force MS-SSIM for long videos: ENABLED");
             }
+            if let Some(container) = container {
+                info!(
+                    "   📦 Container: {} (lossy and remux outputs; lossless stays MKV)",
+                    container.extension()
+                );
+            }
+            if let Some(bytes) = max_output_size {
+                info!(
+                    "   📏 Max output size: {} (CRF raised past the quality match if needed)",
+                    shared_utils::format_bytes(bytes)
+                );
+            }
+            if let Some(frames) = sample_ssim {
+                if ssim_sampling_strategy == shared_utils::SsimSamplingStrategy::Uniform {
+                    info!("   🎯 Sampled SSIM gate: {} evenly spaced frames", frames);
+                } else {
+                    info!(
+                        "   🎯 Sampled SSIM gate: {} frames ({})",
+                        frames,
+                        ssim_sampling_strategy.as_str()
+                    );
+                }
+                shared_utils::log_eprintln!(
+                    "⚠️  --sample-ssim: SSIM is estimated from {} frames; localized artifacts between samples can go unnoticed",
+                    frames
+                );
+            }
+            if let Some(max) = max_dimension {
+                info!("   📐 Max dimension: {}px (larger sources downscaled)", max);
+            }
+            if crf_bias != 0.0 {
+                info!(
+                    "   🎚️  CRF bias: {:+.1} on the matched CRF (clamped to the encoder range)",
+                    crf_bias
+                );
+            }
+            if let Some(content) = content_type {
+                info!(
+                    "   🎞️  Content type: {} (CRF search capped at {:.0} instead of the detected type's)",
+                    content.as_str(),
+                    shared_utils::content_crf_cap(content, shared_utils::VideoEncoder::Av1)
+                );
+            }
+            if let Some(level) = denoise {
+                info!(
+                    "   🧽 Denoise: {} (smaller output, but quality is matched to the cleaned-up source)",
+                    level.as_str()
+                );
+            }
+            if let Some(codec) = audio_codec {
+                info!(
+                    "   🎵 Audio: PCM/lossless or oversized tracks re-encoded to {} {}k",
+                    codec.as_str(),
+                    audio_bitrate.unwrap_or(shared_utils::DEFAULT_AUDIO_BITRATE_KBPS)
+                );
+            }
+            if let Some(secs) = timeout {
+                info!("   ⏱️  Per-file timeout: {}s", secs);
+            }
+            if let Some(mode) = explore_mode {
+                info!(
+                    "   🧭 Explore mode: {} (--explore/--match-quality/--compress/--ultimate ignored)",
+                    mode.as_str()
+                );
+            }
+            if deterministic {
+                info!("   🔒 Deterministic: CPU encoders, one frame thread, bit-exact muxing");
+            }
+            // Cached CRF hints would make the search depend on earlier runs.
             let cache = match AnalysisCache::default_local() {
+                Ok(_) if deterministic => None,
                 Ok(cache) => Some(cache),
                 Err(e) => {
                     shared_utils::log_eprintln!(
@@ -208,46 +871,350 @@ fn main() -> anyhow::Result<()> {
 
             info!("");
 
-            shared_utils::cli_runner::run_auto_command(
-                shared_utils::cli_runner::CliRunnerConfig {
-                    input: input.clone(),
-                    output: output.clone(),
-                    recursive,
-                    label: "AV1 Video".to_string(),
-                    base_dir: if output.is_some() {
-                        Some(input.clone())
-                    } else {
-                        None
-                    },
-                    resume,
+            let runner_config = shared_utils::cli_runner::CliRunnerConfig {
+                input: input.clone(),
+                output: output.clone(),
+                recursive,
+                label: "AV1 Video".to_string(),
+                base_dir: if output.is_some() {
+                    Some(input.clone())
+                } else {
+                    None
                 },
+                resume,
+                failure_policy: shared_utils::FailurePolicy::from_flags(fail_fast, keep_going),
+                retry_policy: shared_utils::RetryPolicy::new(max_retries),
+                extension_filter: shared_utils::ExtensionFilter::new(
+                    &include_ext,
+                    &exclude_ext,
+                    shared_utils::SUPPORTED_VIDEO_EXTENSIONS,
+                ),
+                metadata_only,
+                limit: limit.map(|n| n as usize),
+                only_larger_than_source,
+                sort_report_by,
+                dedup,
+            };
+
+            if dry_run {
+                shared_utils::cli_runner::run_estimate_command(&runner_config, |file| {
+                    let mut detection = detect_video_with_cache(file, cache.as_ref())?;
+                    config.apply_audio_policy(&mut detection);
+                    let strategy = vid_av1::determine_strategy_with_apple_compat(
+                        &detection,
+                        config.apple_compat,
+                    );
+                    let crf = if config.match_quality && !strategy.lossless {
+                        vid_av1::conversion_api::calculate_matched_crf_with_options(
+                            &detection,
+                            config.match_mode,
+                            config.quality_bias,
+                            config.crf_bias,
+                        )? as f64
+                    } else {
+                        strategy.crf as f64
+                    };
+                    let estimate = vid_av1::estimate_output_size(&detection, strategy.target, crf);
+                    Ok((
+                        detection.file_size,
+                        estimate,
+                        strategy.target.as_str().to_string(),
+                    ))
+                })?;
+            }
+            if print_command {
+                shared_utils::cli_runner::run_print_command(&runner_config, |file| {
+                    vid_av1::conversion_api::encode_command_preview(file, &config)
+                        .map_err(|e: VidQualityError| anyhow::anyhow!(e))
+                })?;
+            }
+            if dry_run || print_command {
+                shared_utils::progress_mode::flush_log_file();
+                return Ok(());
+            }
+
+            if let Some(ref path) = archive {
+                match shared_utils::OutputArchive::create(
+                    path,
+                    runner_config
+                        .output
+                        .as_deref()
+                        .unwrap_or(&runner_config.input),
+                    archive_delete_outputs,
+                ) {
+                    Ok(archive) => shared_utils::set_output_archive(archive),
+                    Err(e) => {
+                        eprintln!("❌ Cannot create --archive: {}", e);
+                        std::process::exit(1);
+                    }
+                }
+            }
+            shared_utils::cli_runner::run_auto_command(runner_config, |file| {
+                auto_convert_with_cache(file, &config, cache.as_ref())
+                    .map_err(|e: VidQualityError| anyhow::anyhow!(e))
+            })?;
+            shared_utils::progress_mode::xmp_merge_finalize();
+            shared_utils::progress_mode::flush_log_file();
+        }
+
+        Commands::CopyMetadata { source, dest } => {
+            shared_utils::cli_runner::run_copy_metadata_command(&source, &dest)?;
+        }
+
+        Commands::ImageSequence {
+            pattern,
+            fps,
+            output,
+            force,
+            apple_compat,
+            ultimate,
+            min_ssim,
+            verbose,
+        } => {
+            let options = shared_utils::ConvertOptions {
+                force,
+                output_dir: output,
+                explore: true,
+                match_quality: true,
+                compress: true,
+                apple_compat,
+                ultimate,
+                verbose,
+                min_ssim,
+                ..Default::default()
+            };
+            let result = vid_av1::image_sequence::convert_image_sequence(&pattern, fps, &options)?;
+            if result.success {
+                println!("✅ {}", result.message);
+                if let Some(ref path) = result.output_path {
+                    println!("📁 Output: {}", path);
+                }
+            } else {
+                println!("⏭️  {}", result.message);
+            }
+        }
+
+        Commands::CompareEncoders {
+            input,
+            seconds,
+            crf,
+            vp9,
+            cpu,
+        } => {
+            let config = shared_utils::EncoderComparisonConfig {
+                sample_secs: seconds,
+                crf,
+                include_vp9: vp9,
+                use_gpu: !cpu,
+                ..Default::default()
+            };
+            shared_utils::compare_encoders(&input, &config)?.print_table();
+        }
+
+        Commands::Watch {
+            dir,
+            output,
+            done_dir,
+            delete_original,
+            settle_secs,
+            recursive,
+            force,
+            apple_compat,
+            no_apple_compat,
+            ultimate,
+            min_ssim,
+            max_retries,
+            verbose,
+        } => {
+            let apple_compat = apple_compat && !no_apple_compat;
+            // Watcher events carry canonical paths; output paths are mirrored relative to DIR.
+            let dir = dir.canonicalize().unwrap_or(dir);
+            let config = ConversionConfig {
+                output_dir: Some(output.clone()),
+                base_dir: Some(dir.clone()),
+                force,
+                delete_original,
+                explore_smaller: true,
+                match_quality: true,
+                apple_compat,
+                require_compression: true,
+                ultimate_mode: ultimate,
+                min_ssim: min_ssim.unwrap_or(0.95),
+                child_threads: shared_utils::thread_manager::get_balanced_thread_config(
+                    shared_utils::thread_manager::WorkloadType::Video,
+                )
+                .child_threads,
+                ..Default::default()
+            };
+
+            shared_utils::progress_mode::set_verbose_mode(verbose);
+            if let Err(e) = shared_utils::progress_mode::set_default_run_log_file("vid_av1") {
+                shared_utils::log_eprintln!(
+                    "⚠️  {}: {}",
+                    "\x1b[33mCould not open run log file\x1b[0m",
+                    e
+                );
+            }
+            if apple_compat {
+                std::env::set_var("MODERN_FORMAT_BOOST_APPLE_COMPAT", "1");
+            }
+            let cache = AnalysisCache::default_local().ok();
+
+            let runner_config = shared_utils::cli_runner::CliRunnerConfig {
+                input: dir.clone(),
+                output: Some(output),
+                recursive,
+                label: "AV1 Video".to_string(),
+                base_dir: Some(dir),
+                resume: false,
+                failure_policy: shared_utils::FailurePolicy::default(),
+                retry_policy: shared_utils::RetryPolicy::new(max_retries),
+                extension_filter: shared_utils::ExtensionFilter::default(),
+                metadata_only: shared_utils::MetadataOnlyFormats::default(),
+                limit: None,
+                only_larger_than_source: false,
+                sort_report_by: None,
+                dedup: false,
+            };
+            shared_utils::cli_runner::run_watch_command(
+                runner_config,
+                std::time::Duration::from_secs(settle_secs),
+                done_dir,
                 |file| {
                     auto_convert_with_cache(file, &config, cache.as_ref())
                         .map_err(|e: VidQualityError| anyhow::anyhow!(e))
                 },
             )?;
-            shared_utils::progress_mode::xmp_merge_finalize();
-            shared_utils::progress_mode::flush_log_file();
         }
 
-        Commands::Strategy { input } => {
+        Commands::Doctor => {
+            let report = shared_utils::run_doctor(
+                shared_utils::thread_manager::WorkloadType::Video,
+                &[shared_utils::av1_backend().ffmpeg_name()],
+            );
+            report.print();
+            if !report.is_ready() {
+                std::process::exit(1);
+            }
+        }
+
+        Commands::Strategy {
+            input,
+            deep,
+            json,
+            csv,
+            compare,
+        } => {
+            if csv {
+                return print_analysis_csv(&input, deep);
+            }
             let detection = detect_video_with_cache(&input, None)?;
+            if let Some(other) = compare {
+                let rows = compare_detections(&detection, &detect_video_with_cache(&other, None)?);
+                if json {
+                    let report = serde_json::json!({
+                        "left": input.display().to_string(),
+                        "right": other.display().to_string(),
+                        "fields": rows,
+                    });
+                    println!("{}", serde_json::to_string_pretty(&report)?);
+                } else {
+                    print_detection_comparison(&input, &other, &rows);
+                }
+                return Ok(());
+            }
             let strategy = determine_strategy(&detection);
 
-            println!("\n🎯 Recommended Strategy (AV1 Auto Mode)");
-            println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
-            println!("📁 File: {}", input.display());
-            println!(
-                "🎬 Codec: {} ({})",
-                detection.codec.as_str(),
-                detection.compression.as_str()
-            );
-            println!();
-            println!("💡 Target: {}", strategy.target.as_str());
-            println!("📝 Reason: {}", strategy.reason);
-            println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+            let analysis = if deep {
+                Some(
+                    shared_utils::analyze_video_quality_from_detection(&detection)
+                        .map_err(|e| anyhow::anyhow!(e))?,
+                )
+            } else {
+                None
+            };
+
+            if json {
+                let report = serde_json::json!({
+                    "file": input.display().to_string(),
+                    "codec": detection.codec.as_str(),
+                    "compression": detection.compression.as_str(),
+                    "strategy": strategy,
+                    "analysis": analysis,
+                });
+                println!("{}", serde_json::to_string_pretty(&report)?);
+            } else {
+                println!("\n🎯 Recommended Strategy (AV1 Auto Mode)");
+                println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+                println!("📁 File: {}", input.display());
+                println!(
+                    "🎬 Codec: {} ({})",
+                    detection.codec.as_str(),
+                    detection.compression.as_str()
+                );
+                println!();
+                println!("💡 Target: {}", strategy.target.as_str());
+                println!("📝 Reason: {}", strategy.reason);
+                if let Some(ref analysis) = analysis {
+                    println!(
+                        "📊 Compression level: {} ({})",
+                        analysis.compression_type.as_str(),
+                        analysis.compression_type.conversion_hint()
+                    );
+                    println!(
+                        "🎞️  Content type: {} (CRF search capped at {:.0})",
+                        analysis.content_type.as_str(),
+                        shared_utils::content_crf_cap(
+                            analysis.content_type.to_content_type(),
+                            shared_utils::VideoEncoder::Av1
+                        )
+                    );
+                }
+                println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+            }
         }
     }
 
     Ok(())
 }
+
+/// `analyze --csv`: one row for INPUT, or for every video under it. In a directory scan a
+/// file that cannot be analyzed is reported on stderr and left out.
+fn print_analysis_csv(input: &std::path::Path, deep: bool) -> anyhow::Result<()> {
+    let row = |path: &std::path::Path| -> anyhow::Result<String> {
+        let detection = detect_video_with_cache(path, None)?;
+        let analysis = if deep {
+            Some(
+                shared_utils::analyze_video_quality_from_detection(&detection)
+                    .map_err(|e| anyhow::anyhow!(e))?,
+            )
+        } else {
+            None
+        };
+        Ok(shared_utils::video_analysis_csv_row(
+            path,
+            &detection,
+            &determine_strategy(&detection),
+            analysis.as_ref(),
+        ))
+    };
+
+    if !input.is_dir() {
+        let row = row(input)?;
+        println!("{}", shared_utils::VIDEO_ANALYSIS_CSV_HEADER);
+        println!("{}", row);
+        return Ok(());
+    }
+
+    let mut files =
+        shared_utils::collect_files(input, shared_utils::SUPPORTED_VIDEO_EXTENSIONS, true);
+    files.sort();
+    println!("{}", shared_utils::VIDEO_ANALYSIS_CSV_HEADER);
+    for path in files {
+        match row(&path) {
+            Ok(row) => println!("{}", row),
+            Err(e) => eprintln!("⚠️  {}: {}", path.display(), e),
+        }
+    }
+    Ok(())
+}
@@ -33,7 +33,7 @@ fn extract_webp_to_apng(input: &Path, output_apng: &Path, verbose: bool) -> Resu
     // Create temporary directory for frames
     let temp_dir = tempfile::Builder::new()
         .prefix("webp_frames_")
-        .tempdir()
+        .tempdir_in(shared_utils::conversion::scratch_dir())
         .map_err(|e| {
             VidQualityError::ConversionError(format!("Failed to create temp dir: {}", e))
         })?;
@@ -174,11 +174,22 @@ fn get_output_path(
     options: &ConvertOptions,
 ) -> Result<std::path::PathBuf> {
     if let Some(ref base) = options.base_dir {
-        determine_output_path_with_base(input, base, extension, &options.output_dir)
-            .map_err(VidQualityError::ConversionError)
+        determine_output_path_with_base(
+            input,
+            base,
+            extension,
+            &options.output_dir,
+            options.on_collision,
+        )
+        .map_err(VidQualityError::ConversionError)
     } else {
-        shared_utils::conversion::determine_output_path(input, extension, &options.output_dir)
-            .map_err(VidQualityError::ConversionError)
+        shared_utils::conversion::determine_output_path(
+            input,
+            extension,
+            &options.output_dir,
+            options.on_collision,
+        )
+        .map_err(VidQualityError::ConversionError)
     }
 }
 
@@ -359,7 +370,12 @@ pub fn convert_to_hevc_mp4(input: &Path, options: &ConvertOptions) -> Result<Con
     let ext = if options.apple_compat { "MOV" } else { "MP4" };
     let output = get_output_path(input, ext, options)?;
 
-    if output.exists() && !options.force {
+    if shared_utils::conversion::keeps_existing_output(
+        input,
+        &output,
+        options.force,
+        options.on_collision,
+    ) {
         return Ok(skipped_output_exists(input, &output, input_size));
     }
 
@@ -396,7 +412,7 @@ pub fn convert_to_hevc_mp4(input: &Path, options: &ConvertOptions) -> Result<Con
             // Create temporary APNG file
             let temp_apng = tempfile::Builder::new()
                 .suffix(".apng")
-                .tempfile()
+                .tempfile_in(shared_utils::conversion::scratch_dir())
                 .map_err(|e| {
                     VidQualityError::ConversionError(format!("Failed to create temp APNG: {}", e))
                 })?;
@@ -460,7 +476,7 @@ pub fn convert_to_hevc_mp4(input: &Path, options: &ConvertOptions) -> Result<Con
             // Create temporary APNG file
             let temp_apng = tempfile::Builder::new()
                 .suffix(".apng")
-                .tempfile()
+                .tempfile_in(shared_utils::conversion::scratch_dir())
                 .map_err(|e| {
                     VidQualityError::ConversionError(format!("Failed to create temp APNG: {}", e))
                 })?;
@@ -536,6 +552,7 @@ pub fn convert_to_hevc_mp4(input: &Path, options: &ConvertOptions) -> Result<Con
         cmd.arg(arg);
     }
 
+    cmd.args(options.strip.ffmpeg_args());
     cmd.arg(shared_utils::safe_path_arg(&temp_output).as_ref());
     let result = cmd.output();
 
@@ -569,13 +586,15 @@ pub fn convert_to_hevc_mp4(input: &Path, options: &ConvertOptions) -> Result<Con
                 &output,
                 options.force,
                 Some(input),
+                options.strip,
+                options.on_collision,
             )? {
                 return Ok(skipped_output_exists(input, &output, input_size));
             }
 
             let reduction = 1.0 - (output_size as f64 / input_size as f64);
 
-            shared_utils::copy_metadata(input, &output);
+            shared_utils::copy_metadata(input, &output, options.strip);
             mark_as_processed(input);
 
             if options.should_delete_original() {
@@ -708,7 +727,12 @@ pub fn convert_to_hevc_mp4_matched(
     let ext = if options.apple_compat { "MOV" } else { "MP4" };
     let output = get_output_path(input, ext, options)?;
 
-    if output.exists() && !options.force {
+    if shared_utils::conversion::keeps_existing_output(
+        input,
+        &output,
+        options.force,
+        options.on_collision,
+    ) {
         return Ok(skipped_output_exists(input, &output, input_size));
     }
 
@@ -738,7 +762,7 @@ pub fn convert_to_hevc_mp4_matched(
             }
             let temp_apng = tempfile::Builder::new()
                 .suffix(".apng")
-                .tempfile()
+                .tempfile_in(shared_utils::conversion::scratch_dir())
                 .map_err(|e| {
                     VidQualityError::ConversionError(format!("Failed to create temp APNG: {}", e))
                 })?;
@@ -799,7 +823,7 @@ pub fn convert_to_hevc_mp4_matched(
             // Create temporary APNG file
             let temp_apng = tempfile::Builder::new()
                 .suffix(".apng")
-                .tempfile()
+                .tempfile_in(shared_utils::conversion::scratch_dir())
                 .map_err(|e| {
                     VidQualityError::ConversionError(format!("Failed to create temp APNG: {}", e))
                 })?;
@@ -864,7 +888,7 @@ pub fn convert_to_hevc_mp4_matched(
                     // Create temporary APNG file
                     let temp_stream = tempfile::Builder::new()
                         .suffix(".apng")
-                        .tempfile()
+                        .tempfile_in(shared_utils::conversion::scratch_dir())
                         .map_err(|e| {
                             VidQualityError::ConversionError(format!(
                                 "Failed to create temp APNG: {}",
@@ -948,7 +972,19 @@ pub fn convert_to_hevc_mp4_matched(
         shared_utils::VideoEncoder::Hevc,
     );
 
-    let explore_result = if flag_mode.is_ultimate() {
+    let explore_result = if let Some(min_ssim) = options.min_ssim {
+        shared_utils::explore_hevc_with_gpu_coarse_full(
+            &final_input,
+            &temp_output,
+            vf_args,
+            actual_initial_crf,
+            flag_mode.is_ultimate(),
+            false,
+            options.allow_size_tolerance,
+            min_ssim,
+            options.child_threads,
+        )
+    } else if flag_mode.is_ultimate() {
         shared_utils::explore_hevc_with_gpu_coarse_ultimate(
             &final_input,
             &temp_output,
@@ -1160,11 +1196,13 @@ pub fn convert_to_hevc_mp4_matched(
         &output,
         options.force,
         Some(input),
+        options.strip,
+        options.on_collision,
     )? {
         return Ok(skipped_output_exists(input, &output, input_size));
     }
 
-    shared_utils::copy_metadata(input, &output);
+    shared_utils::copy_metadata(input, &output, options.strip);
     mark_as_processed(input);
 
     if options.should_delete_original() {
@@ -1230,7 +1268,12 @@ pub fn convert_to_hevc_mkv_lossless(
     let input_size = fs::metadata(input)?.len();
     let output = get_output_path(input, "mkv", options)?;
 
-    if output.exists() && !options.force {
+    if shared_utils::conversion::keeps_existing_output(
+        input,
+        &output,
+        options.force,
+        options.on_collision,
+    ) {
         return Ok(skipped_output_exists(input, &output, input_size));
     }
 
@@ -1260,6 +1303,7 @@ pub fn convert_to_hevc_mkv_lossless(
         cmd.arg(arg);
     }
 
+    cmd.args(options.strip.ffmpeg_args());
     cmd.arg(shared_utils::safe_path_arg(&temp_output).as_ref());
     let result = cmd.output();
 
@@ -1272,13 +1316,15 @@ pub fn convert_to_hevc_mkv_lossless(
                 &output,
                 options.force,
                 Some(input),
+                options.strip,
+                options.on_collision,
             )? {
                 return Ok(skipped_output_exists(input, &output, input_size));
             }
 
             let reduction = 1.0 - (output_size as f64 / input_size as f64);
 
-            shared_utils::copy_metadata(input, &output);
+            shared_utils::copy_metadata(input, &output, options.strip);
             mark_as_processed(input);
 
             if options.should_delete_original() {
@@ -1415,7 +1461,12 @@ pub fn convert_to_gif_apple_compat(
         fs::create_dir_all(parent)?;
     }
 
-    if output.exists() && !options.force {
+    if shared_utils::conversion::keeps_existing_output(
+        input,
+        &output,
+        options.force,
+        options.on_collision,
+    ) {
         return Ok(ConversionResult {
             success: true,
             input_path: input.display().to_string(),
@@ -1462,7 +1513,7 @@ pub fn convert_to_gif_apple_compat(
             // Create temporary APNG file
             let temp_apng = tempfile::Builder::new()
                 .suffix(".apng")
-                .tempfile()
+                .tempfile_in(shared_utils::conversion::scratch_dir())
                 .map_err(|e| {
                     VidQualityError::ConversionError(format!("Failed to create temp APNG: {}", e))
                 })?;
@@ -1526,7 +1577,7 @@ pub fn convert_to_gif_apple_compat(
             // Create temporary APNG file
             let temp_apng = tempfile::Builder::new()
                 .suffix(".apng")
-                .tempfile()
+                .tempfile_in(shared_utils::conversion::scratch_dir())
                 .map_err(|e| {
                     VidQualityError::ConversionError(format!("Failed to create temp APNG: {}", e))
                 })?;
@@ -1723,6 +1774,8 @@ pub fn convert_to_gif_apple_compat(
         &output,
         options.force,
         Some(input),
+        options.strip,
+        options.on_collision,
     )? {
         return Ok(ConversionResult {
             success: true,
@@ -1737,7 +1790,7 @@ pub fn convert_to_gif_apple_compat(
         });
     }
 
-    shared_utils::copy_metadata(input, &output);
+    shared_utils::copy_metadata(input, &output, options.strip);
     mark_as_processed(input);
 
     if options.should_delete_original() {
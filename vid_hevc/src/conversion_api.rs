@@ -10,8 +10,10 @@ use crate::{Result, VidQualityError};
 
 use shared_utils::analysis_cache::AnalysisCache;
 use shared_utils::conversion_types::{
-    ConversionConfig, ConversionOutput, ConversionStrategy, TargetVideoFormat,
+    AppleFallback, ConversionConfig, ConversionOutput, ConversionStrategy, SkipReason,
+    TargetVideoFormat,
 };
+use shared_utils::ffmpeg_process::FfmpegProcess;
 use std::path::Path;
 use std::path::PathBuf;
 use std::process::Command;
@@ -100,6 +102,12 @@ fn build_hdr_ffmpeg_args(detection: &VideoDetectionResult) -> Vec<String> {
         }
     }
 
+    // -color_range: a full-range (JPEG-style) source would otherwise be encoded and tagged as
+    // limited range, washing out blacks and whites.
+    args.extend(shared_utils::color_range_args(
+        detection.color_range.as_deref(),
+    ));
+
     args
 }
 
@@ -107,8 +115,15 @@ fn build_hdr_ffmpeg_args(detection: &VideoDetectionResult) -> Vec<String> {
 /// - If source is 10-bit (yuv420p10le, yuv422p10le, etc.) use yuv420p10le so that
 ///   the HDR signal range / precision is preserved in the output stream.
 /// - Otherwise default to yuv420p (8-bit SDR).
-fn hdr_pix_fmt(detection: &VideoDetectionResult) -> &'static str {
-    if detection.bit_depth >= 10 {
+///
+/// An explicit `--pix-fmt` (`pix_fmt_override`) wins over both.
+fn hdr_pix_fmt(
+    detection: &VideoDetectionResult,
+    pix_fmt_override: Option<shared_utils::PixelFormat>,
+) -> &'static str {
+    if let Some(pix_fmt) = pix_fmt_override {
+        pix_fmt.as_str()
+    } else if detection.bit_depth >= 10 {
         "yuv420p10le"
     } else {
         "yuv420p"
@@ -141,7 +156,7 @@ fn prepare_dv_rpu(detection: &VideoDetectionResult) -> Option<DvRpuResult> {
         return None;
     }
 
-    let temp_dir = match tempfile::TempDir::new() {
+    let temp_dir = match tempfile::TempDir::new_in(shared_utils::conversion::scratch_dir()) {
         Ok(d) => d,
         Err(e) => {
             warn!("Failed to create temp dir for DV RPU extraction: {}", e);
@@ -203,17 +218,69 @@ pub fn determine_strategy(result: &VideoDetectionResult) -> ConversionStrategy {
     determine_strategy_with_apple_compat(result, false)
 }
 
+/// Summary category for a `TargetVideoFormat::Skip` strategy, mirroring the checks in
+/// [`determine_strategy_with_apple_compat`].
+fn strategy_skip_reason(result: &VideoDetectionResult, apple_compat: bool) -> SkipReason {
+    // A remux candidate only becomes a skip when `--container` already matches.
+    if shared_utils::is_mp4_remux_candidate(result, &crate::detection_api::DetectedCodec::H265) {
+        return SkipReason::AlreadyOptimal;
+    }
+    let decision = if apple_compat {
+        shared_utils::should_skip_video_codec_apple_compat(result.codec.as_str())
+    } else {
+        shared_utils::should_skip_video_codec(result.codec.as_str())
+    };
+    decision.kind.unwrap_or(SkipReason::ModernLossy)
+}
+
 pub fn determine_strategy_with_apple_compat(
     result: &VideoDetectionResult,
     apple_compat: bool,
 ) -> ConversionStrategy {
+    let generation_guard = shared_utils::should_skip_same_codec_reencode(
+        result.codec.as_str(),
+        shared_utils::SourceCodec::H265,
+    );
+    // `--reconvert` on a source already in the target codec: re-encode it, skipping the
+    // remux shortcut and the modern-codec skips below.
+    let reconvert =
+        generation_guard.codec == shared_utils::SourceCodec::H265 && !generation_guard.should_skip;
+
+    // Already HEVC, just not in MP4: change the container instead of re-encoding.
+    if !reconvert
+        && shared_utils::is_mp4_remux_candidate(result, &crate::detection_api::DetectedCodec::H265)
+    {
+        return ConversionStrategy {
+            target: TargetVideoFormat::Remux,
+            reason: format!(
+                "Source is already HEVC in {} - remuxing to MP4 (stream copy, no re-encode)",
+                result.format
+            ),
+            command: String::new(),
+            preserve_audio: result.has_audio,
+            crf: 0.0,
+            lossless: false,
+        };
+    }
+
+    if generation_guard.should_skip {
+        return ConversionStrategy {
+            target: TargetVideoFormat::Skip,
+            reason: generation_guard.reason,
+            command: String::new(),
+            preserve_audio: false,
+            crf: 0.0,
+            lossless: false,
+        };
+    }
+
     let skip_decision = if apple_compat {
         shared_utils::should_skip_video_codec_apple_compat(result.codec.as_str())
     } else {
         shared_utils::should_skip_video_codec(result.codec.as_str())
     };
 
-    if skip_decision.should_skip {
+    if skip_decision.should_skip && !reconvert {
         return ConversionStrategy {
             target: TargetVideoFormat::Skip,
             reason: skip_decision.reason,
@@ -230,7 +297,7 @@ pub fn determine_strategy_with_apple_compat(
         } else {
             shared_utils::should_skip_video_codec(s)
         };
-        if unknown_skip.should_skip {
+        if unknown_skip.should_skip && !reconvert {
             return ConversionStrategy {
                 target: TargetVideoFormat::Skip,
                 reason: unknown_skip.reason,
@@ -283,16 +350,57 @@ pub fn determine_strategy_with_apple_compat(
     }
 }
 
+/// Simple wrapper: HEVC MP4 at CRF 18, overwriting existing output, metadata preserved.
 pub fn simple_convert(input: &Path, output_dir: Option<&Path>) -> Result<ConversionOutput> {
+    let config = ConversionConfig {
+        output_dir: output_dir.map(Path::to_path_buf),
+        force: true,
+        ..Default::default()
+    };
+    simple_convert_with_config(input, &config)
+}
+
+/// Single-pass conversion driven by `config`, without the skip/explore routing of `auto_convert`.
+///
+/// - `use_lossless`: HEVC lossless MKV; otherwise HEVC MP4 (MOV with `apple_compat`)
+/// - `match_quality`: CRF matched to the source via `match_mode` / `quality_bias`; otherwise CRF 18
+/// - `output_dir`, `base_dir`, `force`, `max_dimension`, `child_threads` (0 = automatic) and
+///   `delete_original` / `in_place` are honored; metadata is always preserved.
+pub fn simple_convert_with_config(
+    input: &Path,
+    config: &ConversionConfig,
+) -> Result<ConversionOutput> {
     // Validate input file (check symlinks, file type, readability)
     if let Err(e) = shared_utils::conversion::validate_input_file(input) {
         return Err(VidQualityError::ConversionError(e));
     }
 
-    let detection = crate::detection_api::detect_video_with_cache(input, None)?;
+    let mut detection = crate::detection_api::detect_video_with_cache(input, None)?;
+    config.apply_audio_policy(&mut detection);
+    shared_utils::set_audio_transcode(config.audio_transcode);
+    let _trim = if config.is_trimmed() {
+        Some(prepare_trim(&mut detection, config)?)
+    } else {
+        None
+    };
+
+    let lossless = config.use_lossless;
+    let target = if lossless {
+        TargetVideoFormat::HevcLosslessMkv
+    } else {
+        TargetVideoFormat::HevcMp4
+    };
+    let target_ext = if let Some(container) = config.container_for(target) {
+        container.extension()
+    } else if !lossless && config.apple_compat {
+        "MOV"
+    } else {
+        target.extension()
+    };
 
-    let output_dir = output_dir
-        .map(|p| p.to_path_buf())
+    let output_dir = config
+        .output_dir
+        .clone()
         .unwrap_or_else(|| input.parent().unwrap_or(Path::new(".")).to_path_buf());
 
     std::fs::create_dir_all(&output_dir)?;
@@ -303,30 +411,110 @@ pub fn simple_convert(input: &Path, output_dir: Option<&Path>) -> Result<Convers
         .unwrap_or("output");
     let input_ext = input.extension().and_then(|e| e.to_str()).unwrap_or("");
 
-    let output_path = if input_ext.eq_ignore_ascii_case("MP4") {
-        output_dir.join(format!("{}_hevc.MP4", stem))
+    let templated = shared_utils::conversion::templated_output_name(
+        input,
+        target_ext,
+        Some(target.codec_name()),
+        None,
+    )
+    .map_err(VidQualityError::ConversionError)?;
+    let output_path = if let Some(name) = templated {
+        output_dir.join(name)
+    } else if input_ext.eq_ignore_ascii_case(target_ext) {
+        output_dir.join(format!("{}_hevc.{}", stem, target_ext))
+    } else {
+        output_dir.join(format!("{}.{}", stem, target_ext))
+    };
+    let output_path = if config.output_dir.is_some() {
+        shared_utils::conversion::flatten_output_path(
+            input,
+            config.base_dir.as_deref(),
+            output_path,
+        )
     } else {
-        output_dir.join(format!("{}.MP4", stem))
+        output_path
     };
-    shared_utils::conversion::validate_output_path(&output_path, None)
+    let output_path =
+        shared_utils::conversion::resolve_output_collision(input, output_path, config.on_collision)
+            .map_err(VidQualityError::ConversionError)?;
+    shared_utils::conversion::validate_output_path(&output_path, config.base_dir.as_deref())
         .map_err(VidQualityError::ConversionError)?;
 
-    info!("🎬 Simple Mode: {} → HEVC MP4 (CRF 18)", input.display());
+    if shared_utils::conversion::keeps_existing_output(
+        input,
+        &output_path,
+        config.force,
+        config.on_collision,
+    ) {
+        return Err(VidQualityError::ConversionError(format!(
+            "Output exists: {} (set force to overwrite)",
+            output_path.display()
+        )));
+    }
+
+    let max_threads = if config.child_threads > 0 {
+        config.child_threads
+    } else {
+        shared_utils::thread_manager::get_balanced_thread_config(
+            shared_utils::thread_manager::WorkloadType::Video,
+        )
+        .child_threads
+    };
 
-    let max_threads = shared_utils::thread_manager::get_balanced_thread_config(
-        shared_utils::thread_manager::WorkloadType::Video,
-    )
-    .child_threads;
+    let crf = if lossless {
+        0.0
+    } else if config.match_quality {
+        calculate_matched_crf_with_options(
+            &detection,
+            config.match_mode,
+            config.quality_bias,
+            config.crf_bias,
+        )?
+        .round()
+    } else {
+        shared_utils::crf_constants::HEVC_CRF_VISUALLY_LOSSLESS
+    };
+
+    if lossless {
+        info!("🎬 Simple Mode: {} → HEVC Lossless MKV", input.display());
+    } else {
+        info!(
+            "🎬 Simple Mode: {} → HEVC {} (CRF {:.0})",
+            input.display(),
+            target_ext,
+            crf
+        );
+    }
 
     let temp_path = shared_utils::conversion::temp_path_for_output(&output_path);
     let _temp_guard = shared_utils::conversion::TempOutputGuard::new(temp_path.clone());
-    let output_size = execute_hevc_conversion(&detection, &temp_path, 18, max_threads)?;
+    let output_size = if lossless {
+        execute_hevc_lossless(
+            &detection,
+            &temp_path,
+            max_threads,
+            config.max_dimension,
+            config.encode_settings(),
+        )?
+    } else {
+        execute_hevc_conversion(
+            &detection,
+            &temp_path,
+            crf as u8,
+            max_threads,
+            config.max_dimension,
+            config.force_cfr,
+            config.encode_settings(),
+        )?
+    };
 
     if !shared_utils::conversion::commit_temp_to_output_with_metadata(
         &temp_path,
         &output_path,
-        true,
+        config.force,
         Some(input),
+        config.strip,
+        config.on_collision,
     )
     .map_err(|e| VidQualityError::ConversionError(e.to_string()))?
     {
@@ -335,30 +523,68 @@ pub fn simple_convert(input: &Path, output_dir: Option<&Path>) -> Result<Convers
         ));
     }
 
-    shared_utils::copy_metadata(input, &output_path);
+    shared_utils::copy_metadata(input, &output_path, config.strip);
+
+    if config.should_delete_original() {
+        let downscaled = config
+            .max_dimension
+            .and_then(|max| {
+                shared_utils::downscale_dimensions(detection.width, detection.height, max)
+            })
+            .is_some();
+        let delete = if lossless && !downscaled {
+            shared_utils::conversion::safe_delete_original_lossless
+        } else {
+            shared_utils::conversion::safe_delete_original
+        };
+        if let Err(e) = delete(
+            input,
+            &output_path,
+            shared_utils::conversion::MIN_OUTPUT_SIZE_BEFORE_DELETE_VIDEO,
+        ) {
+            warn!("   ⚠️  Safe delete failed: {}", e);
+        } else {
+            info!("   🗑️  Original deleted (integrity verified)");
+        }
+    }
 
     let size_ratio = output_size as f64 / detection.file_size as f64;
 
     info!("   ✅ Complete: {:.1}% of original", size_ratio * 100.0);
 
+    let (reason, message) = if lossless {
+        (
+            "Simple mode: HEVC Lossless".to_string(),
+            "Simple conversion successful (HEVC Lossless)".to_string(),
+        )
+    } else {
+        (
+            "Simple mode: HEVC High Quality".to_string(),
+            format!("Simple conversion successful (HEVC CRF {:.0})", crf),
+        )
+    };
+
     Ok(ConversionOutput {
         input_path: input.display().to_string(),
         output_path: output_path.display().to_string(),
         strategy: ConversionStrategy {
-            target: TargetVideoFormat::HevcMp4,
-            reason: "Simple mode: HEVC High Quality".to_string(),
+            target,
+            reason,
             command: String::new(),
             preserve_audio: detection.has_audio,
-            crf: 18.0,
-            lossless: false,
+            crf,
+            lossless,
         },
         input_size: detection.file_size,
         output_size,
         size_ratio,
         success: true,
-        message: "Simple conversion successful (HEVC CRF 18)".to_string(),
-        final_crf: 18.0,
+        message,
+        final_crf: crf,
         exploration_attempts: 0,
+        benchmark: None,
+        skip_reason: None,
+        ssim: None,
     })
 }
 
@@ -370,6 +596,299 @@ pub fn auto_convert_with_cache(
     input: &Path,
     config: &ConversionConfig,
     cache: Option<&AnalysisCache>,
+) -> Result<ConversionOutput> {
+    // Explore iterations report through the thread-local sink; file start/done are emitted here.
+    // Without a configured sink the one already on the thread (the batch bar) is kept.
+    let sink = config
+        .progress_sink
+        .clone()
+        .or_else(shared_utils::progress_sink::current);
+    let _sink_guard = shared_utils::progress_sink::install(sink.clone());
+    if let Some(dir) = &config.temp_dir {
+        shared_utils::conversion::set_temp_dir(dir.clone());
+    }
+    // Without a configured token the one already on the thread (the batch fail-fast) is kept.
+    let cancel_token = config
+        .cancel_token
+        .clone()
+        .or_else(shared_utils::cancellation::current);
+    let deadline = config
+        .per_file_timeout
+        .map(|timeout| shared_utils::cancellation::Deadline::start(cancel_token.clone(), timeout));
+    let _cancel_guard = shared_utils::cancellation::install(
+        deadline
+            .as_ref()
+            .map(|d| d.token())
+            .or_else(|| cancel_token.clone()),
+    );
+    if let Some(sink) = sink.as_deref() {
+        sink.on_file_start(input);
+    }
+    let started = std::time::Instant::now();
+    shared_utils::begin_file_profile();
+    let mut result = auto_convert_with_cache_inner(input, config, cache);
+    // Encode failures caused by a killed child are reported as the timeout/cancellation they are.
+    if result.is_err() {
+        if let Some(timeout) = config
+            .per_file_timeout
+            .filter(|_| deadline.as_ref().is_some_and(|d| d.timed_out()))
+        {
+            shared_utils::log_eprintln!(
+                "⏱️  TIMED OUT after {}s, encode killed: {}",
+                timeout.as_secs(),
+                input.display()
+            );
+            result = Err(VidQualityError::TimedOut {
+                path: input.to_path_buf(),
+                timeout,
+            });
+        } else if cancel_token.as_ref().is_some_and(|t| t.is_cancelled()) {
+            result = Err(VidQualityError::Cancelled);
+        }
+    }
+    if config.benchmark {
+        if let Ok(output) = &mut result {
+            output.benchmark = shared_utils::conversion_types::EncodeBenchmark::measure(
+                output,
+                started.elapsed(),
+                config.use_gpu,
+            );
+        }
+    }
+    if let Some(sink) = sink.as_deref() {
+        match &result {
+            Ok(output) => sink.on_file_done(output),
+            Err(e) => sink.on_file_failed(input, &e.to_string()),
+        }
+    }
+    shared_utils::finish_file_profile_with_log(
+        &input.file_name().unwrap_or_default().to_string_lossy(),
+    );
+    result
+}
+
+/// --apple-fallback x264: without a hardware HEVC encoder, CPU x265 is too slow on older
+/// machines, so Apple-compat SDR sources go to H.264 instead.
+fn uses_x264_fallback(
+    detection: &VideoDetectionResult,
+    strategy: &ConversionStrategy,
+    config: &ConversionConfig,
+) -> bool {
+    config.apple_compat
+        && config.apple_fallback == Some(AppleFallback::X264)
+        && strategy.target == TargetVideoFormat::HevcMp4
+        && !config.use_lossless
+        && !is_hdr_source(detection)
+        && shared_utils::GpuAccel::detect_for(config.deterministic)
+            .get_hevc_encoder()
+            .is_none()
+}
+
+fn output_extension<'a>(strategy: &'a ConversionStrategy, config: &ConversionConfig) -> &'a str {
+    if let Some(container) = config.container_for(strategy.target) {
+        container.extension()
+    } else if config.apple_compat && strategy.target == TargetVideoFormat::HevcMp4 {
+        "MOV"
+    } else {
+        strategy.target.extension()
+    }
+}
+
+/// Where the output of `input` goes, before `--on-collision` is applied. Creates nothing.
+fn planned_output_path(
+    input: &Path,
+    strategy: &ConversionStrategy,
+    config: &ConversionConfig,
+    x264_fallback: bool,
+) -> Result<PathBuf> {
+    let output_dir =
+        if let (Some(ref user_out), Some(ref base)) = (&config.output_dir, &config.base_dir) {
+            shared_utils::conversion::output_subdir(input, base, user_out)
+        } else {
+            config
+                .output_dir
+                .clone()
+                .unwrap_or_else(|| input.parent().unwrap_or(Path::new(".")).to_path_buf())
+        };
+
+    let stem = input
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("output");
+    let target_ext = output_extension(strategy, config);
+    let input_ext = input.extension().and_then(|e| e.to_str()).unwrap_or("");
+
+    let templated = shared_utils::conversion::templated_output_name(
+        input,
+        target_ext,
+        Some(if x264_fallback {
+            "h264"
+        } else {
+            strategy.target.codec_name()
+        }),
+        // Lossy encodes pick their CRF during exploration, after the name is fixed.
+        None,
+    )
+    .map_err(VidQualityError::ConversionError)?;
+    let output_path = if let Some(name) = templated {
+        output_dir.join(name)
+    } else if input_ext.eq_ignore_ascii_case(target_ext)
+        || (config.apple_compat && input_ext.eq_ignore_ascii_case("mov"))
+    {
+        let suffix = if x264_fallback { "h264" } else { "hevc" };
+        output_dir.join(format!("{}_{}.{}", stem, suffix, target_ext))
+    } else {
+        output_dir.join(format!("{}.{}", stem, target_ext))
+    };
+    Ok(if config.output_dir.is_some() {
+        shared_utils::conversion::flatten_output_path(
+            input,
+            config.base_dir.as_deref(),
+            output_path,
+        )
+    } else {
+        output_path
+    })
+}
+
+/// `-vf` args of the CRF encode: display size capped by `--max-dimension`, and `--denoise`.
+/// Frame-timing args (`--force-cfr`) are not filters; see [`explore_frame_rate_mode`].
+fn explore_vf_args(detection: &VideoDetectionResult, config: &ConversionConfig) -> Vec<String> {
+    let (display_width, display_height) = detection.display_dimensions();
+    let vf_args = shared_utils::get_ffmpeg_dimension_args_with_max_dimension(
+        display_width,
+        display_height,
+        false,
+        config.max_dimension,
+    );
+    shared_utils::with_denoise_filter(vf_args, config.denoise)
+}
+
+/// Installs the VFR/`--force-cfr` frame-timing args for the CRF encodes on this thread.
+fn explore_frame_rate_mode(
+    detection: &VideoDetectionResult,
+    config: &ConversionConfig,
+) -> shared_utils::FrameRateModeGuard {
+    shared_utils::encode_frame_rate_mode(shared_utils::frame_rate_mode_args(
+        detection.is_variable_frame_rate,
+        config.force_cfr,
+    ))
+}
+
+/// `--print-command`: the encode commands [`auto_convert`] would run for `input`, one shell
+/// line each, without converting anything. The CRF shown is where the search starts; the run
+/// may settle on another. Skipped files get a `#` comment saying why.
+pub fn encode_command_preview(input: &Path, config: &ConversionConfig) -> Result<Vec<String>> {
+    let mut detection = crate::detection_api::detect_video(input)?;
+    config.apply_audio_policy(&mut detection);
+    shared_utils::set_audio_transcode(config.audio_transcode);
+
+    let strategy = config.skip_redundant_remux(
+        determine_strategy_with_apple_compat(&detection, config.apple_compat),
+        input,
+    );
+    if !config.accepts_codec(&detection.codec) {
+        return Ok(vec![format!(
+            "# skipped: codec {} not in --only-codecs",
+            detection.codec.as_str()
+        )]);
+    }
+    if strategy.target == TargetVideoFormat::Skip {
+        return Ok(vec![format!("# skipped: {}", strategy.reason)]);
+    }
+
+    let x264_fallback = uses_x264_fallback(&detection, &strategy, config);
+    let output_path = planned_output_path(input, &strategy, config, x264_fallback)?;
+    let settings = config.encode_settings();
+    let ffmpeg = |args: Vec<String>| vec![shared_utils::shell_command_string("ffmpeg", &args)];
+    if strategy.target == TargetVideoFormat::Remux {
+        return Ok(ffmpeg(remux_command_args(
+            &detection,
+            &output_path,
+            config.strip,
+        )));
+    }
+    if strategy.target == TargetVideoFormat::HevcLosslessMkv || config.use_lossless {
+        let mut lines = Vec::new();
+        if detection.is_dolby_vision {
+            lines.push(
+                "# Dolby Vision: the run extracts the RPU with dovi_tool and adds it to -x265-params"
+                    .to_string(),
+            );
+        }
+        lines.extend(ffmpeg(hevc_lossless_args(
+            &detection,
+            &output_path,
+            config.child_threads,
+            config.max_dimension,
+            settings,
+            None,
+        )));
+        return Ok(lines);
+    }
+    if x264_fallback {
+        return Ok(ffmpeg(x264_args(
+            &detection,
+            &output_path,
+            calculate_matched_x264_crf(&detection)?,
+            config.child_threads,
+            config.max_dimension,
+            config.force_cfr,
+            settings,
+        )));
+    }
+
+    let grayscale = config.auto_grayscale
+        && !config.apple_compat
+        && shared_utils::detect_grayscale_video(input, &detection.pix_fmt, detection.duration_secs);
+    let _grayscale_guard = shared_utils::encode_grayscale(grayscale);
+    let _frame_rate_guard = explore_frame_rate_mode(&detection, config);
+    let crf = calculate_matched_crf_with_options(
+        &detection,
+        config.match_mode,
+        config.quality_bias,
+        config.crf_bias,
+    )?;
+    shared_utils::encode_command_preview(
+        input,
+        &output_path,
+        shared_utils::VideoEncoder::Hevc,
+        explore_vf_args(&detection, config),
+        crf,
+        config.child_threads,
+        settings,
+    )
+    .map_err(|e| VidQualityError::ConversionError(e.to_string()))
+}
+
+/// Result of a file whose output appeared while it was being encoded: the commit declined to
+/// replace it, so the original is kept and nothing is written.
+fn concurrent_output_skip(
+    input: &Path,
+    detection: &VideoDetectionResult,
+    strategy: &ConversionStrategy,
+) -> ConversionOutput {
+    ConversionOutput {
+        input_path: input.display().to_string(),
+        output_path: String::new(),
+        strategy: strategy.clone(),
+        input_size: detection.file_size,
+        output_size: 0,
+        size_ratio: 1.0,
+        success: true,
+        message: "Skipped: output was created concurrently".to_string(),
+        final_crf: 0.0,
+        exploration_attempts: 0,
+        benchmark: None,
+        skip_reason: Some(SkipReason::AlreadyProcessed),
+        ssim: None,
+    }
+}
+
+fn auto_convert_with_cache_inner(
+    input: &Path,
+    config: &ConversionConfig,
+    cache: Option<&AnalysisCache>,
 ) -> Result<ConversionOutput> {
     // Pause if the user is being prompted to exit via Ctrl+C
     shared_utils::ctrlc_guard::wait_if_prompt_active();
@@ -387,9 +906,11 @@ pub fn auto_convert_with_cache(
     shared_utils::progress_mode::set_log_context(&_label);
     let _log_guard = shared_utils::progress_mode::LogContextGuard;
 
-    // Skip Live Photos in Apple compat mode
-    if config.apple_compat && shared_utils::is_live_photo(input) {
-        let reason = "Live Photo detected in Apple compat mode";
+    // --live-photos skip (default): leave the MOV half alone, as the image tools do the still.
+    if shared_utils::live_photo_mode() == shared_utils::LivePhotoMode::Skip
+        && shared_utils::is_live_photo(input)
+    {
+        let reason = "Live Photo pair, skipped together with its still (--live-photos convert to convert both)";
         shared_utils::progress_mode::video_skipped(reason);
 
         let file_size = std::fs::metadata(input).map(|m| m.len()).unwrap_or(0);
@@ -407,7 +928,7 @@ pub fn auto_convert_with_cache(
             output_path: "".to_string(),
             strategy: ConversionStrategy {
                 target: TargetVideoFormat::Skip,
-                reason: "Live Photo detected in Apple compat mode".to_string(),
+                reason: "Live Photo pair (--live-photos skip)".to_string(),
                 command: "".to_string(),
                 preserve_audio: false,
                 crf: 0.0,
@@ -417,13 +938,70 @@ pub fn auto_convert_with_cache(
             output_size: 0,
             size_ratio: 0.0,
             success: true,
-            message: "Skipped Live Photo in Apple compat mode".to_string(),
+            message: "Skipped Live Photo motion half".to_string(),
             final_crf: 0.0,
             exploration_attempts: 0,
+            benchmark: None,
+            skip_reason: Some(SkipReason::LivePhoto),
+            ssim: None,
         });
     }
 
+    let probe_phase = shared_utils::phase_profile::phase(shared_utils::ProfilePhase::Probe);
+    if !config.skip_integrity_check {
+        match shared_utils::probe_integrity(input) {
+            shared_utils::MediaIntegrity::Ok => {}
+            shared_utils::MediaIntegrity::Truncated(reason) => {
+                warn!("   ⚠️  Truncated/corrupt source, skipping: {}", reason);
+                shared_utils::progress_mode::video_skipped(&format!(
+                    "Truncated source ({})",
+                    reason
+                ));
+
+                let file_size = std::fs::metadata(input).map(|m| m.len()).unwrap_or(0);
+
+                shared_utils::copy_on_skip_or_fail(
+                    input,
+                    config.output_dir.as_deref(),
+                    config.base_dir.as_deref(),
+                    false,
+                )
+                .map_err(|e| VidQualityError::GeneralError(e.to_string()))?;
+
+                return Ok(ConversionOutput {
+                    input_path: input.display().to_string(),
+                    output_path: "".to_string(),
+                    strategy: ConversionStrategy {
+                        target: TargetVideoFormat::Skip,
+                        reason: format!("Truncated source: {}", reason),
+                        command: "".to_string(),
+                        preserve_audio: false,
+                        crf: 0.0,
+                        lossless: false,
+                    },
+                    input_size: file_size,
+                    output_size: 0,
+                    size_ratio: 0.0,
+                    success: true,
+                    message: format!("Skipped truncated/corrupt source: {}", reason),
+                    final_crf: 0.0,
+                    exploration_attempts: 0,
+                    benchmark: None,
+                    skip_reason: Some(SkipReason::Corrupt),
+                    ssim: None,
+                });
+            }
+            // Detection below reports the real error for unreadable files.
+            shared_utils::MediaIntegrity::Unreadable(reason) => {
+                warn!("   ⚠️  Integrity probe could not read source: {}", reason);
+            }
+        }
+    }
+
     let mut detection = crate::detection_api::detect_video_with_cache(input, cache)?;
+    drop(probe_phase);
+    config.apply_audio_policy(&mut detection);
+    shared_utils::set_audio_transcode(config.audio_transcode);
 
     // Warn about dynamic HDR metadata that will be stripped during re-encode
     if detection.is_dolby_vision {
@@ -438,9 +1016,55 @@ pub fn auto_convert_with_cache(
         warn!("HDR10+ detected: dynamic metadata will be stripped to HDR10 static layer");
     }
 
-    let strategy = determine_strategy_with_apple_compat(&detection, config.apple_compat);
+    let strategy = config.skip_redundant_remux(
+        determine_strategy_with_apple_compat(&detection, config.apple_compat),
+        input,
+    );
+
+    if !config.accepts_codec(&detection.codec) {
+        let reason = format!(
+            "Skipped: codec {} not in --only-codecs",
+            detection.codec.as_str()
+        );
+        info!(
+            "   ⏭️  {}: codec {} not in --only-codecs, copying through",
+            input.display(),
+            detection.codec.as_str()
+        );
+        shared_utils::progress_mode::video_skipped(&reason);
+
+        shared_utils::copy_on_skip_or_fail(
+            input,
+            config.output_dir.as_deref(),
+            config.base_dir.as_deref(),
+            false,
+        )
+        .map_err(|e| VidQualityError::GeneralError(e.to_string()))?;
+
+        return Ok(ConversionOutput {
+            input_path: input.display().to_string(),
+            output_path: "".to_string(),
+            strategy,
+            input_size: detection.file_size,
+            output_size: 0,
+            size_ratio: 0.0,
+            success: true,
+            message: reason,
+            final_crf: 0.0,
+            exploration_attempts: 0,
+            benchmark: None,
+            skip_reason: Some(SkipReason::UserOverride),
+            ssim: None,
+        });
+    }
 
     if strategy.target == TargetVideoFormat::Skip {
+        if config.is_trimmed() {
+            warn!(
+                "   ⚠️  --start/--end ignored: source is skipped ({})",
+                strategy.reason
+            );
+        }
         shared_utils::progress_mode::video_skipped(&strategy.reason);
 
         shared_utils::copy_on_skip_or_fail(
@@ -462,53 +1086,56 @@ pub fn auto_convert_with_cache(
             message: "Skipped modern codec to avoid generation loss".to_string(),
             final_crf: 0.0,
             exploration_attempts: 0,
+            benchmark: None,
+            skip_reason: Some(strategy_skip_reason(&detection, config.apple_compat)),
+            ssim: None,
         });
     }
 
-    let output_dir =
-        if let (Some(ref user_out), Some(ref base)) = (&config.output_dir, &config.base_dir) {
-            let rel_path = input
-                .strip_prefix(base)
-                .unwrap_or(input)
-                .parent()
-                .unwrap_or(Path::new(""));
-            user_out.join(rel_path)
-        } else {
-            config
-                .output_dir
-                .clone()
-                .unwrap_or_else(|| input.parent().unwrap_or(Path::new(".")).to_path_buf())
-        };
-
-    std::fs::create_dir_all(&output_dir)?;
-
-    let stem = input
-        .file_stem()
-        .and_then(|s| s.to_str())
-        .unwrap_or("output");
-    let target_ext = if config.apple_compat && strategy.target == TargetVideoFormat::HevcMp4 {
-        "MOV"
+    // --start/--end: every read of the source is limited to the range, so the SSIM reference,
+    // the MS-SSIM long-video skip and sampling frame counts all see the trimmed duration.
+    let _trim = if config.is_trimmed() {
+        Some(prepare_trim(&mut detection, config)?)
     } else {
-        strategy.target.extension()
+        None
+    };
+    let strategy = if config.is_trimmed() && strategy.target == TargetVideoFormat::Remux {
+        ConversionStrategy {
+            target: TargetVideoFormat::HevcMp4,
+            reason: "Trimmed segment - stream copy cannot cut frame-accurately, re-encoding"
+                .to_string(),
+            crf: shared_utils::crf_constants::HEVC_CRF_VISUALLY_LOSSLESS,
+            ..strategy
+        }
+    } else {
+        strategy
     };
+
+    let x264_fallback = uses_x264_fallback(&detection, &strategy, config);
+    let target_ext = output_extension(&strategy, config);
     let input_ext = input.extension().and_then(|e| e.to_str()).unwrap_or("");
     // GIF as source has no Apple compatibility issue; do not show "APPLE COMPAT FALLBACK" for GIF→video.
     let source_is_gif = input_ext.eq_ignore_ascii_case("gif");
 
-    let output_path = if input_ext.eq_ignore_ascii_case(target_ext)
-        || (config.apple_compat && input_ext.eq_ignore_ascii_case("mov"))
-    {
-        output_dir.join(format!("{}_hevc.{}", stem, target_ext))
-    } else {
-        output_dir.join(format!("{}.{}", stem, target_ext))
-    };
+    let output_path = planned_output_path(input, &strategy, config, x264_fallback)?;
+    if let Some(parent) = output_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let output_path =
+        shared_utils::conversion::resolve_output_collision(input, output_path, config.on_collision)
+            .map_err(VidQualityError::ConversionError)?;
     shared_utils::conversion::validate_output_path(&output_path, config.base_dir.as_deref())
         .map_err(VidQualityError::ConversionError)?;
 
     shared_utils::path_validator::check_input_output_conflict(input, &output_path)
         .map_err(|e| VidQualityError::ConversionError(e.to_string()))?;
 
-    if output_path.exists() && !config.force {
+    if shared_utils::conversion::keeps_existing_output(
+        input,
+        &output_path,
+        config.force,
+        config.on_collision,
+    ) {
         shared_utils::progress_mode::video_skipped(&format!(
             "Output exists: {}",
             output_path.display()
@@ -524,6 +1151,9 @@ pub fn auto_convert_with_cache(
             message: format!("Skipped: output exists ({})", output_path.display()),
             final_crf: 0.0,
             exploration_attempts: 0,
+            benchmark: None,
+            skip_reason: Some(SkipReason::AlreadyProcessed),
+            ssim: None,
         });
     }
 
@@ -536,48 +1166,137 @@ pub fn auto_convert_with_cache(
     );
     info!("   Reason: {}", strategy.reason);
 
+    let downscale = config
+        .max_dimension
+        .and_then(|max| shared_utils::downscale_dimensions(detection.width, detection.height, max));
+    if let Some((w, h)) = downscale {
+        info!(
+            "   📐 Downscaling {}x{} → {}x{} (max dimension {})",
+            detection.width,
+            detection.height,
+            w,
+            h,
+            config.max_dimension.unwrap_or_default()
+        );
+    }
+
+    // Only the lossy explore path runs the `-vf` chain through the SSIM judge, so that is the
+    // only place `--denoise` is applied.
+    let mut denoised = None;
     let (output_size, final_crf, attempts, explore_result_opt) = match strategy.target {
         TargetVideoFormat::HevcLosslessMkv => {
             info!("   🚀 Using HEVC Lossless Mode");
-            let size = execute_hevc_lossless(&detection, &temp_path, config.child_threads)?;
+            let size = execute_hevc_lossless(
+                &detection,
+                &temp_path,
+                config.child_threads,
+                config.max_dimension,
+                config.encode_settings(),
+            )?;
             (size, 0.0, 0, None)
         }
         TargetVideoFormat::HevcMp4 => {
             if config.use_lossless {
                 info!("   🚀 Using HEVC Lossless Mode (forced)");
-                let size = execute_hevc_lossless(&detection, &temp_path, config.child_threads)?;
+                let size = execute_hevc_lossless(
+                    &detection,
+                    &temp_path,
+                    config.child_threads,
+                    config.max_dimension,
+                    config.encode_settings(),
+                )?;
                 (size, 0.0, 0, None)
-            } else {
-                let vf_args = shared_utils::get_ffmpeg_dimension_args(
-                    detection.width,
-                    detection.height,
-                    false,
+            } else if x264_fallback {
+                let crf = calculate_matched_x264_crf(&detection)?;
+                info!(
+                    "   🍎 No hardware HEVC encoder: Apple compat fallback to H.264 (libx264, CRF {:.1})",
+                    crf
                 );
+                let size = execute_x264_conversion(
+                    &detection,
+                    &temp_path,
+                    crf,
+                    config.child_threads,
+                    config.max_dimension,
+                    config.force_cfr,
+                    config.encode_settings(),
+                )?;
+                (size, crf, 0, None)
+            } else {
+                if detection.is_variable_frame_rate && config.force_cfr {
+                    warn!(
+                        "   ⚠️  VFR source re-timed to constant frame rate (--force-cfr): frames may be duplicated or dropped"
+                    );
+                }
+                let vf_args = explore_vf_args(&detection, config);
+                let _frame_rate_guard = explore_frame_rate_mode(&detection, config);
+                if let Some(level) = config.denoise {
+                    info!(
+                        "   🧽 Denoising ({}): SSIM is judged against the denoised reference",
+                        level.as_str()
+                    );
+                    denoised = Some(level);
+                }
                 let input_path = Path::new(&detection.file_path);
 
+                let grayscale = config.auto_grayscale
+                    && !config.apple_compat
+                    && shared_utils::detect_grayscale_video(
+                        input_path,
+                        &detection.pix_fmt,
+                        detection.duration_secs,
+                    );
+                if grayscale {
+                    info!("   ⚫ Grayscale content: encoding single-channel HEVC (4:0:0)");
+                }
+                let _grayscale_guard = shared_utils::encode_grayscale(grayscale);
+
                 // Log media info to log file only (for SSIM/quality context); not shown on terminal.
-                if let Ok(quality_analysis) =
+                let detected_content =
                     shared_utils::analyze_video_quality_from_detection(&detection)
-                {
-                    shared_utils::log_media_info_for_quality(&quality_analysis, input_path);
+                        .map(|mut quality_analysis| {
+                            quality_analysis.is_grayscale |= grayscale;
+                            shared_utils::log_media_info_for_quality(&quality_analysis, input_path);
+                            quality_analysis.content_type.to_content_type()
+                        })
+                        .unwrap_or_default();
+                let content = config.content_type.unwrap_or(detected_content);
+                if content != shared_utils::ContentType::Unknown {
+                    info!(
+                        "   🎞️  Content: {} (CRF search capped at {:.0})",
+                        content.as_str(),
+                        shared_utils::content_crf_cap(content, shared_utils::VideoEncoder::Hevc)
+                    );
                 }
-
-                let flag_mode = shared_utils::validate_flags_result_with_ultimate(
-                    config.explore_smaller,
-                    config.match_quality,
-                    config.require_compression,
-                    config.ultimate_mode,
-                )
-                .map_err(VidQualityError::ConversionError)?;
+                let _content_guard = shared_utils::search_content_type(content);
+
+                // --explore-mode names the search directly; the flag combination is only
+                // validated without it.
+                let (ultimate, mode_label) = match config.explore_mode {
+                    Some(mode) => (false, shared_utils::create_strategy(mode).description()),
+                    None => {
+                        let flag_mode = shared_utils::validate_flags_result_with_ultimate(
+                            config.explore_smaller,
+                            config.match_quality,
+                            config.require_compression,
+                            config.ultimate_mode,
+                        )
+                        .map_err(VidQualityError::ConversionError)?;
+                        (flag_mode.is_ultimate(), flag_mode.description_en())
+                    }
+                };
 
                 let use_gpu = config.use_gpu;
                 if !use_gpu {
                     info!("   🖥️  CPU Mode: Using libx265 for higher SSIM (≥0.98)");
                 }
 
-                let ultimate = flag_mode.is_ultimate();
-
-                let predicted_crf = calculate_matched_crf(&detection)?;
+                let predicted_crf = calculate_matched_crf_with_options(
+                    &detection,
+                    config.match_mode,
+                    config.quality_bias,
+                    config.crf_bias,
+                )?;
                 let warm_start_crf = if let Some(hint) = detection.precision.last_best_crf {
                     info!("   💡 Using cached CRF hint: {:.1} (warm start only)", hint);
                     Some(hint)
@@ -589,6 +1308,8 @@ pub fn auto_convert_with_cache(
                     Some(hint)
                 } else if let Some(hint) =
                     shared_utils::crf_constants::get_global_last_hit_crf_hevc()
+                        // One file's result must not steer the next file's search.
+                        .filter(|_| !config.deterministic)
                 {
                     info!("   💡 Using global last hit CRF: {:.1} (warm start only)", hint);
                     Some(hint)
@@ -596,14 +1317,32 @@ pub fn auto_convert_with_cache(
                     None
                 };
                 let search_crf = warm_start_crf.unwrap_or(predicted_crf);
+                let crf_confidence = matched_crf_confidence(&detection);
                 info!(
-                    "   {} {}: base CRF {:.1} → search anchor {:.1}",
+                    "   {} {}: base CRF {:.1} (confidence {:.0}%) → search anchor {:.1}",
                     if ultimate { "🔥" } else { "🔬" },
-                    flag_mode.description_en(),
+                    mode_label,
                     predicted_crf,
+                    crf_confidence * 100.0,
                     search_crf
                 );
-                let explore_result = if ultimate {
+                let _crf_prediction = shared_utils::encode_crf_prediction(Some((
+                    predicted_crf as f64,
+                    crf_confidence,
+                )));
+                let explore_result = if let Some(mode) = config.explore_mode {
+                    shared_utils::explore_with_mode(
+                        mode,
+                        input_path,
+                        &temp_path,
+                        shared_utils::VideoEncoder::Hevc,
+                        vf_args,
+                        search_crf,
+                        config.min_ssim,
+                        config.child_threads,
+                        config.encode_settings(),
+                    )
+                } else if ultimate {
                     shared_utils::explore_hevc_with_gpu_coarse_ultimate_warm_start(
                         input_path,
                         &temp_path,
@@ -612,7 +1351,9 @@ pub fn auto_convert_with_cache(
                         warm_start_crf,
                         ultimate,
                         config.allow_size_tolerance,
+                        Some(config.min_ssim),
                         config.child_threads,
+                        config.encode_settings(),
                     )
                 } else {
                     shared_utils::explore_hevc_with_gpu_coarse_full_warm_start(
@@ -626,6 +1367,7 @@ pub fn auto_convert_with_cache(
                         config.allow_size_tolerance,
                         config.min_ssim,
                         config.child_threads,
+                        config.encode_settings(),
                     )
                 }
                 .map_err(|e| VidQualityError::ConversionError(e.to_string()))?;
@@ -774,12 +1516,17 @@ pub fn auto_convert_with_cache(
                         source_is_gif,
                     ) {
                         warn!("   ⚠️  APPLE COMPAT FALLBACK: keeping best-effort HEVC output (CRF {:.1}, {} iters) to ensure iOS importability, despite missing quality/size targets", explore_result.optimal_crf, explore_result.iterations);
-                        shared_utils::conversion::commit_temp_to_output_with_metadata(
+                        if !shared_utils::conversion::commit_temp_to_output_with_metadata(
                             &temp_path,
                             &output_path,
                             config.force,
                             Some(input),
-                        )?;
+                            config.strip,
+                            config.on_collision,
+                        )? {
+                            info!("⏭️ Output was created concurrently, skipping overwrite");
+                            return Ok(concurrent_output_skip(input, &detection, &strategy));
+                        }
                         return Ok(ConversionOutput {
                             input_path: input.display().to_string(),
                             output_path: output_path.display().to_string(),
@@ -802,6 +1549,9 @@ pub fn auto_convert_with_cache(
                             ),
                             final_crf: explore_result.optimal_crf,
                             exploration_attempts: explore_result.iterations as u8,
+                            benchmark: None,
+                            skip_reason: None,
+                            ssim: None,
                         });
                     }
 
@@ -838,6 +1588,9 @@ pub fn auto_convert_with_cache(
                         message: fail_message,
                         final_crf: explore_result.optimal_crf,
                         exploration_attempts: explore_result.iterations as u8,
+                        benchmark: None,
+                        skip_reason: None,
+                        ssim: None,
                     });
                 }
 
@@ -849,6 +1602,19 @@ pub fn auto_convert_with_cache(
                 )
             }
         }
+        TargetVideoFormat::Remux => {
+            if downscale.is_some() {
+                warn!(
+                    "   ⚠️  --max-dimension not applied: remux copies the video stream unchanged"
+                );
+            }
+            info!(
+                "   📦 Remux only: copying streams into {} (no re-encode, quality check skipped)",
+                target_ext
+            );
+            let size = execute_remux(&detection, &temp_path, config.strip)?;
+            (size, 0.0, 0, None)
+        }
         TargetVideoFormat::Skip => unreachable!(),
         _ => unreachable!("HEVC tool should not return AV1/FFV1 target"),
     };
@@ -857,10 +1623,11 @@ pub fn auto_convert_with_cache(
     let cache_best_effort_hint =
         best_effort_status_for_cache(strategy.target, &explore_result_opt, final_crf);
 
-    if cache_exact_hint && final_crf > 0.0 {
+    if cache_exact_hint && final_crf > 0.0 && !config.deterministic {
         shared_utils::crf_constants::update_global_last_hit_crf_hevc(final_crf);
     }
-    if let Some(cache) = cache {
+    // Hints from a trimmed segment do not describe the whole source.
+    if let Some(cache) = cache.filter(|_| !config.is_trimmed()) {
         if cache_exact_hint || cache_best_effort_hint {
             if cache_exact_hint {
                 detection.precision.last_best_crf = Some(final_crf);
@@ -896,35 +1663,6 @@ pub fn auto_convert_with_cache(
         )));
     }
 
-    if !shared_utils::conversion::commit_temp_to_output_with_metadata(
-        &temp_path,
-        &output_path,
-        config.force,
-        Some(input),
-    )
-    .map_err(|e| {
-        VidQualityError::ConversionError(format!(
-            "Commit failed: {} (temp: {}, output: {})",
-            e,
-            temp_path.display(),
-            output_path.display()
-        ))
-    })? {
-        info!("⏭️ Output was created concurrently, skipping overwrite");
-        return Ok(ConversionOutput {
-            input_path: input.display().to_string(),
-            output_path: String::new(),
-            strategy: strategy.clone(),
-            input_size: detection.file_size,
-            output_size: 0,
-            size_ratio: 1.0,
-            success: true,
-            message: "Skipped: output was created concurrently".to_string(),
-            final_crf: 0.0,
-            exploration_attempts: 0,
-        });
-    }
-
     if let Some(ref result) = explore_result_opt {
         if let Some(false) = result.ms_ssim_passed {
             let score_str = result
@@ -947,6 +1685,17 @@ pub fn auto_convert_with_cache(
                     result.optimal_crf,
                     result.iterations
                 );
+                if !shared_utils::conversion::commit_temp_to_output_with_metadata(
+                    &temp_path,
+                    &output_path,
+                    config.force,
+                    Some(input),
+                    config.strip,
+                    config.on_collision,
+                )? {
+                    info!("⏭️ Output was created concurrently, skipping overwrite");
+                    return Ok(concurrent_output_skip(input, &detection, &strategy));
+                }
                 return Ok(ConversionOutput {
                     input_path: input.display().to_string(),
                     output_path: output_path.display().to_string(),
@@ -970,15 +1719,15 @@ pub fn auto_convert_with_cache(
                     ),
                     final_crf: result.optimal_crf,
                     exploration_attempts: result.iterations as u8,
+                    benchmark: None,
+                    skip_reason: None,
+                    ssim: None,
                 });
             }
 
-            if output_path.exists() {
-                cleanup_output_file(&output_path, "low MS-SSIM cleanup");
-                info!("   🗑️  Low MS-SSIM output deleted");
-            }
             if temp_path.exists() {
                 cleanup_output_file(&temp_path, "temporary output cleanup after low MS-SSIM");
+                info!("   🗑️  Low MS-SSIM output deleted");
             }
 
             shared_utils::copy_on_skip_or_fail(
@@ -1007,15 +1756,19 @@ pub fn auto_convert_with_cache(
                 message: format!("Skipped: MS-SSIM {} below target 0.90", score_str),
                 final_crf: result.optimal_crf,
                 exploration_attempts: result.iterations as u8,
+                benchmark: None,
+                skip_reason: None,
+                ssim: None,
             });
         }
     }
 
     let pre_metadata_size = output_size;
 
-    shared_utils::copy_metadata(input, &output_path);
+    // Validate the temp file (with metadata applied) so a rejected output never reaches output_path.
+    shared_utils::copy_metadata(input, &temp_path, config.strip);
 
-    let actual_output_size = std::fs::metadata(&output_path)
+    let actual_output_size = std::fs::metadata(&temp_path)
         .map(|m| m.len())
         .unwrap_or(output_size);
 
@@ -1023,7 +1776,7 @@ pub fn auto_convert_with_cache(
         shared_utils::video_explorer::detect_metadata_size(pre_metadata_size, actual_output_size);
 
     let input_stream_info = shared_utils::extract_stream_sizes(input);
-    let output_stream_info = shared_utils::extract_stream_sizes(&output_path);
+    let output_stream_info = shared_utils::extract_stream_sizes(&temp_path);
 
     let verify_result = shared_utils::verify_pure_media_compression(
         &input_stream_info,
@@ -1062,7 +1815,11 @@ pub fn auto_convert_with_cache(
     };
 
     // --- require_compression phase: primary decision by total file size, with video stream as diagnostic. ---
-    if config.require_compression && !total_within_tolerance {
+    // A remux is bit-identical, so its size only reflects the container; don't reject it.
+    if config.require_compression
+        && !total_within_tolerance
+        && strategy.target != TargetVideoFormat::Remux
+    {
         warn!("   ⚠️  COMPRESSION FAILED (total file comparison):");
         warn!(
             "   ⚠️  Total file: {} → {} ({:+.1}%)",
@@ -1098,6 +1855,17 @@ pub fn auto_convert_with_cache(
                 final_crf,
                 attempts
             );
+            if !shared_utils::conversion::commit_temp_to_output_with_metadata(
+                &temp_path,
+                &output_path,
+                config.force,
+                Some(input),
+                config.strip,
+                config.on_collision,
+            )? {
+                info!("⏭️ Output was created concurrently, skipping overwrite");
+                return Ok(concurrent_output_skip(input, &detection, &strategy));
+            }
             return Ok(ConversionOutput {
                 input_path: input.display().to_string(),
                 output_path: output_path.display().to_string(),
@@ -1120,18 +1888,18 @@ pub fn auto_convert_with_cache(
                 ),
                 final_crf,
                 exploration_attempts: attempts,
+                benchmark: None,
+                skip_reason: None,
+                ssim: None,
             });
         }
 
-        if output_path.exists() {
-            cleanup_output_file(&output_path, "compression failure cleanup");
-            info!("   🗑️  Output deleted (cannot compress by total file size)");
-        }
         if temp_path.exists() {
             cleanup_output_file(
                 &temp_path,
                 "temporary output cleanup after compression failure",
             );
+            info!("   🗑️  Output deleted (cannot compress by total file size)");
         }
 
         shared_utils::copy_on_skip_or_fail(
@@ -1171,26 +1939,71 @@ pub fn auto_convert_with_cache(
             ),
             final_crf,
             exploration_attempts: attempts,
+            benchmark: None,
+            skip_reason: None,
+            ssim: None,
         });
     }
 
-    if verify_result.video_compressed && verify_result.total_compression_ratio >= 1.0 {
-        warn!(
-            "   ⚠️  Video stream compressed ({:+.1}%) but total file larger ({:+.1}%)",
-            verify_result.video_size_change_percent(),
-            verify_result.total_size_change_percent()
-        );
-        warn!(
-            "   ⚠️  Cause: Container overhead (+{} bytes)",
-            verify_result.container_overhead_diff
-        );
+    if verify_result.video_compressed && verify_result.total_compression_ratio >= 1.0 {
+        warn!(
+            "   ⚠️  Video stream compressed ({:+.1}%) but total file larger ({:+.1}%)",
+            verify_result.video_size_change_percent(),
+            verify_result.total_size_change_percent()
+        );
+        warn!(
+            "   ⚠️  Cause: Container overhead (+{} bytes)",
+            verify_result.container_overhead_diff
+        );
+    }
+
+    if !shared_utils::conversion::commit_temp_to_output_with_metadata(
+        &temp_path,
+        &output_path,
+        config.force,
+        Some(input),
+        config.strip,
+        config.on_collision,
+    )
+    .map_err(|e| {
+        VidQualityError::ConversionError(format!(
+            "Commit failed: {} (temp: {}, output: {})",
+            e,
+            temp_path.display(),
+            output_path.display()
+        ))
+    })? {
+        info!("⏭️ Output was created concurrently, skipping overwrite");
+        return Ok(concurrent_output_skip(input, &detection, &strategy));
+    }
+
+    if shared_utils::provenance_enabled() {
+        let mut provenance = shared_utils::Provenance::new(detection.codec.as_str());
+        if !(strategy.lossless || config.use_lossless) {
+            provenance.crf = Some(final_crf);
+        }
+        provenance.ssim = explore_result_opt.as_ref().and_then(|r| r.ssim);
+        shared_utils::write_provenance(input, &output_path, &provenance, config.strip);
+    }
+
+    if config.quality_report {
+        let (ssim, psnr) = explore_result_opt
+            .as_ref()
+            .map_or((None, None), |r| (r.ssim, r.psnr));
+        shared_utils::write_quality_xattrs(&output_path, ssim, psnr, detection.codec.as_str());
     }
 
     let output_size = actual_output_size;
     let size_ratio = output_size as f64 / detection.file_size as f64;
 
     if config.should_delete_original() {
-        if let Err(e) = shared_utils::conversion::safe_delete_original(
+        // Lossless targets must also prove PSNR = ∞; a downscaled output can't, so it gets the decode check only.
+        let delete = if (strategy.lossless || config.use_lossless) && downscale.is_none() {
+            shared_utils::conversion::safe_delete_original_lossless
+        } else {
+            shared_utils::conversion::safe_delete_original
+        };
+        if let Err(e) = delete(
             input,
             &output_path,
             shared_utils::conversion::MIN_OUTPUT_SIZE_BEFORE_DELETE_VIDEO,
@@ -1218,16 +2031,73 @@ pub fn auto_convert_with_cache(
         output_size,
         size_ratio,
         success: true,
-        message: if attempts > 0 {
-            format!("Explored {} CRF values, final CRF: {}", attempts, final_crf)
-        } else {
-            "Conversion successful".to_string()
+        message: {
+            let base = if x264_fallback {
+                format!("Apple compat H.264 fallback (libx264 CRF {:.1})", final_crf)
+            } else if attempts > 0 {
+                format!("Explored {} CRF values, final CRF: {}", attempts, final_crf)
+            } else {
+                "Conversion successful".to_string()
+            };
+            let base = match downscale {
+                Some((w, h)) => format!(
+                    "{} (scaled {}x{} → {}x{})",
+                    base, detection.width, detection.height, w, h
+                ),
+                None => base,
+            };
+            match denoised {
+                Some(level) => format!("{} (denoised: {})", base, level.as_str()),
+                None => base,
+            }
         },
         final_crf,
         exploration_attempts: attempts,
+        benchmark: None,
+        skip_reason: None,
+        ssim: explore_result_opt.as_ref().and_then(|r| r.ssim),
     })
 }
 
+/// `--start` / `--end`: register the range so every ffmpeg read of the source gets input-side
+/// `-ss` / `-to` (cleared when the guard drops). Duration, frame count and size are scaled to the
+/// range; codec, bitrate and HDR fields keep describing the source, so strategy and CRF matching
+/// are unchanged.
+fn prepare_trim(
+    detection: &mut VideoDetectionResult,
+    config: &ConversionConfig,
+) -> Result<shared_utils::SourceTrimGuard> {
+    shared_utils::validate_trim_range(config.start_time, config.end_time, detection.duration_secs)
+        .map_err(VidQualityError::ConversionError)?;
+
+    let source_duration = detection.duration_secs;
+    let guard = shared_utils::trim_source(
+        Path::new(&detection.file_path),
+        config.start_time,
+        config.end_time,
+        source_duration,
+    );
+    let share = shared_utils::trimmed_share(Path::new(&detection.file_path));
+    if source_duration > 0.0 {
+        detection.duration_secs =
+            shared_utils::trimmed_duration(config.start_time, config.end_time, source_duration);
+    }
+    detection.frame_count = (detection.frame_count as f64 * share).round() as u64;
+    // Compare the output against the matching share of the source, not the whole file.
+    detection.file_size = (detection.file_size as f64 * share) as u64;
+    info!(
+        "   ✂️  Trimmed to {:.1}s of {:.1}s ({} frames)",
+        detection.duration_secs, source_duration, detection.frame_count
+    );
+    if detection.is_dolby_vision {
+        warn!("   ⚠️  Dolby Vision RPU is not carried into trimmed output (HDR10 base layer only)");
+        detection.is_dolby_vision = false;
+    }
+    detection.has_subtitles = false;
+    detection.subtitle_codec = None;
+    Ok(guard)
+}
+
 fn success_status_for_cache(
     target: TargetVideoFormat,
     explore_result: &Option<shared_utils::ExploreResult>,
@@ -1252,7 +2122,7 @@ fn best_effort_status_for_cache(
             .unwrap_or(false)
 }
 
-pub fn calculate_matched_crf(detection: &VideoDetectionResult) -> Result<f32> {
+fn build_quality_analysis(detection: &VideoDetectionResult) -> shared_utils::QualityAnalysis {
     let mut builder = shared_utils::VideoAnalysisBuilder::new()
         .basic(
             detection.codec.as_str(),
@@ -1289,9 +2159,39 @@ pub fn calculate_matched_crf(detection: &VideoDetectionResult) -> Result<f32> {
         builder = builder.gop(60, 2);
     }
 
-    let analysis = builder.build();
+    builder.build()
+}
+
+/// 0–1 trust in the matched CRF (see `shared_utils::predict_crf_with_confidence`); 0 when the
+/// quality analysis fails.
+fn matched_crf_confidence(detection: &VideoDetectionResult) -> f32 {
+    shared_utils::predict_crf_with_confidence(
+        &build_quality_analysis(detection),
+        shared_utils::EncoderType::Hevc,
+    )
+    .map(|(_, confidence)| confidence)
+    .unwrap_or(0.0)
+}
+
+pub fn calculate_matched_crf(detection: &VideoDetectionResult) -> Result<f32> {
+    calculate_matched_crf_with_options(
+        detection,
+        shared_utils::MatchMode::Quality,
+        shared_utils::QualityBias::Balanced,
+        0.0,
+    )
+}
+
+/// Matched CRF with an explicit match mode and bias (e.g. from `--preset`).
+pub fn calculate_matched_crf_with_options(
+    detection: &VideoDetectionResult,
+    mode: shared_utils::MatchMode,
+    bias: shared_utils::QualityBias,
+    crf_bias: f64,
+) -> Result<f32> {
+    let analysis = build_quality_analysis(detection);
 
-    match shared_utils::calculate_hevc_crf(&analysis) {
+    match shared_utils::calculate_hevc_crf_with_options(&analysis, mode, bias, crf_bias) {
         Ok(result) => {
             shared_utils::log_quality_analysis(&analysis, &result, shared_utils::EncoderType::Hevc);
             Ok(result.crf)
@@ -1303,26 +2203,146 @@ pub fn calculate_matched_crf(detection: &VideoDetectionResult) -> Result<f32> {
     }
 }
 
+/// Estimated output size in bytes for converting `detection` to `target` at `crf`, without
+/// encoding anything.
+///
+/// Lossy targets scale the source video stream by codec efficiency and by the distance
+/// between `crf` and the source's matched CRF (see `shared_utils::estimate_encoded_video_bytes`);
+/// lossless targets use raw frame bytes. Expect ±25% on typical footage.
+pub fn estimate_output_size(
+    detection: &VideoDetectionResult,
+    target: TargetVideoFormat,
+    crf: f64,
+) -> u64 {
+    let analysis = build_quality_analysis(detection);
+    let matched = match target {
+        TargetVideoFormat::Av1Mp4 => shared_utils::calculate_av1_crf(&analysis),
+        _ => shared_utils::calculate_hevc_crf(&analysis),
+    };
+    shared_utils::conversion_types::estimate_output_bytes(
+        detection,
+        target,
+        crf,
+        matched.ok().map(|m| m.crf as f64),
+    )
+}
+
+/// Video counterpart of `shared_utils::image_recommender::UpgradeRecommendation`: the codec a
+/// source should move to, the expected saving and an ffmpeg command that performs the upgrade.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct VideoUpgradeRecommendation {
+    pub current_codec: String,
+    pub recommended_codec: String,
+    pub reason: String,
+    /// Estimated saving in percent of the source file size.
+    pub expected_size_reduction: f64,
+    pub quality_preservation: String,
+    /// Ready-to-paste ffmpeg command; empty when no upgrade is recommended.
+    pub command: String,
+}
+
+/// Turn the auto-mode strategy for `detection` into a [`VideoUpgradeRecommendation`].
+pub fn recommend_video(detection: &VideoDetectionResult) -> VideoUpgradeRecommendation {
+    let strategy = determine_strategy(detection);
+    let current_codec = detection.codec.as_str().to_string();
+    let input = &detection.file_path;
+    let stem = Path::new(input).with_extension("");
+    // Suffixed so the command never overwrites an MP4 source in place.
+    let output = |ext: &str| format!("{}_hevc.{}", stem.display(), ext);
+    let pix_fmt = if detection.bit_depth >= 10 {
+        " -pix_fmt yuv420p10le"
+    } else {
+        ""
+    };
+
+    let (recommended_codec, quality_preservation, command) = match strategy.target {
+        TargetVideoFormat::Skip => {
+            return VideoUpgradeRecommendation {
+                recommended_codec: current_codec.clone(),
+                current_codec,
+                reason: strategy.reason,
+                expected_size_reduction: 0.0,
+                quality_preservation: "N/A".to_string(),
+                command: String::new(),
+            };
+        }
+        TargetVideoFormat::Remux => (
+            "HEVC",
+            "Bit-exact (stream copy)".to_string(),
+            format!(
+                "ffmpeg -i '{}' -map 0 -c copy -tag:v hvc1 -movflags +faststart '{}'",
+                input,
+                output("mp4")
+            ),
+        ),
+        TargetVideoFormat::HevcLosslessMkv => (
+            "HEVC",
+            "Mathematically Lossless".to_string(),
+            format!(
+                "ffmpeg -i '{}' -c:v libx265 -x265-params lossless=1{} -c:a copy '{}'",
+                input,
+                pix_fmt,
+                output("mkv")
+            ),
+        ),
+        _ => (
+            "HEVC",
+            format!("Visually matched (CRF {})", strategy.crf),
+            format!(
+                "ffmpeg -i '{}' -c:v libx265 -crf {} -preset medium{} -tag:v hvc1 -c:a copy -movflags +faststart '{}'",
+                input,
+                strategy.crf,
+                pix_fmt,
+                output("mp4")
+            ),
+        ),
+    };
+
+    let expected_size_reduction = if detection.file_size > 0 {
+        let estimate = estimate_output_size(detection, strategy.target, strategy.crf as f64);
+        ((1.0 - estimate as f64 / detection.file_size as f64) * 100.0).max(0.0)
+    } else {
+        0.0
+    };
+
+    VideoUpgradeRecommendation {
+        current_codec,
+        recommended_codec: recommended_codec.to_string(),
+        reason: strategy.reason,
+        expected_size_reduction,
+        quality_preservation,
+        command,
+    }
+}
+
+fn is_hdr_source(detection: &VideoDetectionResult) -> bool {
+    detection.bit_depth >= 10
+        || detection.is_dolby_vision
+        || detection.is_hdr10_plus
+        || detection.mastering_display.is_some()
+        || matches!(
+            detection.color_transfer.as_deref(),
+            Some("smpte2084") | Some("arib-std-b67")
+        )
+}
+
 fn execute_hevc_conversion(
     detection: &VideoDetectionResult,
     output: &Path,
     crf: u8,
     max_threads: usize,
+    max_dimension: Option<u32>,
+    force_cfr: bool,
+    settings: shared_utils::EncodeSettings,
 ) -> Result<u64> {
+    let _phase = shared_utils::phase_profile::phase(shared_utils::ProfilePhase::FinalEncode);
     // Attempt to extract DV RPU for injection (None = not DV or graceful fallback)
     let dv_rpu = prepare_dv_rpu(detection);
 
     // For HDR content (10-bit) we need additional x265 params to signal HDR correctly.
     // hdr-opt=1 enables SEI HDR metadata writing; repeat-headers=1 ensures SPS/PPS on
     // every keyframe so players always have the colour info available.
-    let is_hdr_content = detection.bit_depth >= 10
-        || detection.is_dolby_vision
-        || detection.is_hdr10_plus
-        || detection.mastering_display.is_some()
-        || matches!(
-            detection.color_transfer.as_deref(),
-            Some("smpte2084") | Some("arib-std-b67")
-        );
+    let is_hdr_content = is_hdr_source(detection);
 
     let mut x265_params = if is_hdr_content {
         format!(
@@ -1341,9 +2361,19 @@ fn execute_hevc_conversion(
             dv.profile_str
         ));
     }
+    x265_params.push_str(shared_utils::deterministic_x265_params(
+        settings.deterministic,
+    ));
 
-    let pix_fmt = hdr_pix_fmt(detection);
-    let vf_args = shared_utils::get_ffmpeg_dimension_args(detection.width, detection.height, false);
+    // Source bit depth is kept; --force-10bit upconverts 8-bit sources to main10.
+    let pix_fmt = shared_utils::hevc_pix_fmt(detection.bit_depth, settings.pix_fmt);
+    let (display_width, display_height) = detection.display_dimensions();
+    let vf_args = shared_utils::get_ffmpeg_dimension_args_with_max_dimension(
+        display_width,
+        display_height,
+        false,
+        max_dimension,
+    );
 
     let input_arg = shared_utils::safe_path_arg(Path::new(&detection.file_path))
         .as_ref()
@@ -1353,6 +2383,11 @@ fn execute_hevc_conversion(
         "-y".to_string(),
         "-threads".to_string(),
         max_threads.to_string(),
+    ];
+    args.extend(shared_utils::source_trim_args(Path::new(
+        &detection.file_path,
+    )));
+    args.extend([
         "-i".to_string(),
         input_arg,
         "-c:v".to_string(),
@@ -1360,19 +2395,21 @@ fn execute_hevc_conversion(
         "-crf".to_string(),
         crf.to_string(),
         "-preset".to_string(),
-        "medium".to_string(),
+        settings.preset.x26x_name().to_string(),
         "-pix_fmt".to_string(),
         pix_fmt.to_string(),
         "-tag:v".to_string(),
         "hvc1".to_string(),
         "-x265-params".to_string(),
         x265_params,
-    ];
+    ]);
+    args.extend(shared_utils::hevc_profile_args(pix_fmt));
 
     // Preserve variable frame rate (VFR) for iPhone slow-motion videos
-    if detection.is_variable_frame_rate {
-        args.extend(["-vsync".to_string(), "vfr".to_string()]);
-    }
+    args.extend(shared_utils::frame_rate_mode_args(
+        detection.is_variable_frame_rate,
+        force_cfr,
+    ));
 
     // Append HDR colour metadata args (color_primaries, color_trc, colorspace,
     // master_display, max_cll)
@@ -1383,9 +2420,10 @@ fn execute_hevc_conversion(
     }
 
     if detection.has_audio {
-        args.extend(shared_utils::audio_args_for_container(
+        args.extend(shared_utils::audio_args_for_encode(
             detection.audio_codec.as_deref(),
-            "mp4",
+            None,
+            shared_utils::container_of(output),
         ));
     } else {
         args.push("-an".to_string());
@@ -1395,18 +2433,69 @@ fn execute_hevc_conversion(
     args.extend(shared_utils::subtitle_args_for_container(
         detection.has_subtitles,
         detection.subtitle_codec.as_deref(),
-        "mp4",
+        shared_utils::container_of(output),
     ));
 
+    args.extend(settings.strip.ffmpeg_args());
+    args.extend(shared_utils::deterministic_args(settings.deterministic));
     args.push(output_arg);
 
-    let result = Command::new("ffmpeg").args(&args).output()?;
+    let process = FfmpegProcess::spawn(Command::new("ffmpeg").args(&args))?;
+    let (status, stderr) = process.wait_with_output()?;
 
-    if !result.status.success() {
+    if !status.success() {
         return Err(VidQualityError::FFmpegError {
             message: "FFmpeg command failed".to_string(),
-            stderr: String::from_utf8_lossy(&result.stderr).to_string(),
-            exit_code: result.status.code(),
+            stderr,
+            exit_code: status.code(),
+            command: None,
+            file_path: None,
+        });
+    }
+
+    Ok(std::fs::metadata(output)?.len())
+}
+
+/// Matched x264 CRF for the `--apple-fallback x264` path.
+pub fn calculate_matched_x264_crf(detection: &VideoDetectionResult) -> Result<f32> {
+    let analysis = build_quality_analysis(detection);
+
+    shared_utils::calculate_h264_crf(&analysis)
+        .map(|result| result.crf)
+        .map_err(|e| {
+            crate::VidQualityError::AnalysisError(format!("Quality analysis failed: {}", e))
+        })
+}
+
+/// H.264 (libx264) MP4/MOV encode for Apple compat when no hardware HEVC encoder exists.
+/// Only used for SDR sources; HDR stays on the HEVC route.
+fn execute_x264_conversion(
+    detection: &VideoDetectionResult,
+    output: &Path,
+    crf: f32,
+    max_threads: usize,
+    max_dimension: Option<u32>,
+    force_cfr: bool,
+    settings: shared_utils::EncodeSettings,
+) -> Result<u64> {
+    let _phase = shared_utils::phase_profile::phase(shared_utils::ProfilePhase::FinalEncode);
+    let args = x264_args(
+        detection,
+        output,
+        crf,
+        max_threads,
+        max_dimension,
+        force_cfr,
+        settings,
+    );
+    let process = FfmpegProcess::spawn(Command::new("ffmpeg").args(&args))?;
+    let (status, stderr) = process.wait_with_output()?;
+
+    if !status.success() {
+        return Err(VidQualityError::FFmpegError {
+            message: "FFmpeg command failed (libx264)".to_string(),
+            stderr,
+            exit_code: status.code(),
             command: None,
             file_path: None,
         });
@@ -1415,16 +2504,134 @@ fn execute_hevc_conversion(
     Ok(std::fs::metadata(output)?.len())
 }
 
+/// ffmpeg arguments of [`execute_x264_conversion`].
+fn x264_args(
+    detection: &VideoDetectionResult,
+    output: &Path,
+    crf: f32,
+    max_threads: usize,
+    max_dimension: Option<u32>,
+    force_cfr: bool,
+    settings: shared_utils::EncodeSettings,
+) -> Vec<String> {
+    let (display_width, display_height) = detection.display_dimensions();
+    let vf_args = shared_utils::get_ffmpeg_dimension_args_with_max_dimension(
+        display_width,
+        display_height,
+        false,
+        max_dimension,
+    );
+
+    let input_arg = shared_utils::safe_path_arg(Path::new(&detection.file_path))
+        .as_ref()
+        .to_string();
+    let output_arg = shared_utils::safe_path_arg(output).as_ref().to_string();
+    let mut args = vec![
+        "-y".to_string(),
+        "-threads".to_string(),
+        max_threads.to_string(),
+    ];
+    args.extend(shared_utils::source_trim_args(Path::new(
+        &detection.file_path,
+    )));
+    args.extend([
+        "-i".to_string(),
+        input_arg,
+        "-c:v".to_string(),
+        "libx264".to_string(),
+        "-crf".to_string(),
+        format!("{:.1}", crf),
+        "-preset".to_string(),
+        settings.preset.x26x_name().to_string(),
+        "-profile:v".to_string(),
+        "high".to_string(),
+        "-pix_fmt".to_string(),
+        "yuv420p".to_string(),
+        "-tag:v".to_string(),
+        "avc1".to_string(),
+    ]);
+    args.extend(shared_utils::color_range_args(
+        detection.color_range.as_deref(),
+    ));
+
+    args.extend(shared_utils::frame_rate_mode_args(
+        detection.is_variable_frame_rate,
+        force_cfr,
+    ));
+
+    for arg in &vf_args {
+        args.push(arg.clone());
+    }
+
+    if detection.has_audio {
+        args.extend(shared_utils::audio_args_for_encode(
+            detection.audio_codec.as_deref(),
+            None,
+            shared_utils::container_of(output),
+        ));
+    } else {
+        args.push("-an".to_string());
+    }
+
+    args.extend(shared_utils::subtitle_args_for_container(
+        detection.has_subtitles,
+        detection.subtitle_codec.as_deref(),
+        shared_utils::container_of(output),
+    ));
+
+    args.extend(settings.strip.ffmpeg_args());
+    args.extend(shared_utils::deterministic_args(settings.deterministic));
+    args.push(output_arg);
+    args
+}
+
 fn execute_hevc_lossless(
     detection: &VideoDetectionResult,
     output: &Path,
     max_threads: usize,
+    max_dimension: Option<u32>,
+    settings: shared_utils::EncodeSettings,
 ) -> Result<u64> {
+    let _phase = shared_utils::phase_profile::phase(shared_utils::ProfilePhase::FinalEncode);
     warn!("⚠️  HEVC Lossless encoding - this will be slow and produce large files!");
 
     // Attempt to extract DV RPU for injection (None = not DV or graceful fallback)
     let dv_rpu = prepare_dv_rpu(detection);
+    let args = hevc_lossless_args(
+        detection,
+        output,
+        max_threads,
+        max_dimension,
+        settings,
+        dv_rpu.as_ref(),
+    );
+
+    let process = FfmpegProcess::spawn(Command::new("ffmpeg").args(&args))?;
+    let (status, stderr) = process.wait_with_output()?;
+
+    if !status.success() {
+        return Err(VidQualityError::FFmpegError {
+            message: "FFmpeg command failed".to_string(),
+            stderr,
+            exit_code: status.code(),
+            command: None,
+            file_path: None,
+        });
+    }
 
+    Ok(std::fs::metadata(output)?.len())
+}
+
+/// ffmpeg arguments of [`execute_hevc_lossless`]; `dv_rpu` is the extracted Dolby Vision RPU
+/// to inject, if any.
+fn hevc_lossless_args(
+    detection: &VideoDetectionResult,
+    output: &Path,
+    max_threads: usize,
+    max_dimension: Option<u32>,
+    settings: shared_utils::EncodeSettings,
+    dv_rpu: Option<&DvRpuResult>,
+) -> Vec<String> {
     let is_hdr_content = detection.bit_depth >= 10
         || detection.is_dolby_vision
         || detection.is_hdr10_plus
@@ -1445,16 +2652,29 @@ fn execute_hevc_lossless(
     };
 
     // Inject DV RPU path and profile into x265 params when available
-    if let Some(ref dv) = dv_rpu {
+    if let Some(dv) = dv_rpu {
         x265_params.push_str(&format!(
             ":dolby-vision-rpu={}:dolby-vision-profile={}",
             dv.rpu_path.display(),
             dv.profile_str
         ));
     }
+    x265_params.push_str(shared_utils::deterministic_x265_params(
+        settings.deterministic,
+    ));
 
-    let pix_fmt = hdr_pix_fmt(detection);
-    let vf_args = shared_utils::get_ffmpeg_dimension_args(detection.width, detection.height, false);
+    let pix_fmt = hdr_pix_fmt(detection, settings.pix_fmt);
+    let (display_width, display_height) = detection.display_dimensions();
+    // MKV has no dependable display matrix: bake the rotation into the pixels.
+    let vf_args = shared_utils::with_rotation_filter(
+        shared_utils::get_ffmpeg_dimension_args_with_max_dimension(
+            display_width,
+            display_height,
+            false,
+            max_dimension,
+        ),
+        detection.rotation,
+    );
 
     let input_arg = shared_utils::safe_path_arg(Path::new(&detection.file_path))
         .as_ref()
@@ -1464,6 +2684,12 @@ fn execute_hevc_lossless(
         "-y".to_string(),
         "-threads".to_string(),
         max_threads.to_string(),
+        "-noautorotate".to_string(),
+    ];
+    args.extend(shared_utils::source_trim_args(Path::new(
+        &detection.file_path,
+    )));
+    args.extend([
         "-i".to_string(),
         input_arg,
         "-c:v".to_string(),
@@ -1476,7 +2702,7 @@ fn execute_hevc_lossless(
         "medium".to_string(),
         "-tag:v".to_string(),
         "hvc1".to_string(),
-    ];
+    ]);
 
     // Forward all HDR colour metadata
     args.extend(build_hdr_ffmpeg_args(detection));
@@ -1502,15 +2728,29 @@ fn execute_hevc_lossless(
         "mkv",
     ));
 
+    args.extend(settings.strip.ffmpeg_args());
+    args.extend(shared_utils::deterministic_args(settings.deterministic));
     args.push(output_arg);
+    args
+}
 
-    let result = Command::new("ffmpeg").args(&args).output()?;
+/// Stream-copy the source into the container of `output` (MP4 unless `--container` says
+/// otherwise); only the container changes, the video bitstream is untouched.
+fn execute_remux(
+    detection: &VideoDetectionResult,
+    output: &Path,
+    strip: shared_utils::StripPolicy,
+) -> Result<u64> {
+    let _phase = shared_utils::phase_profile::phase(shared_utils::ProfilePhase::FinalEncode);
+    let args = remux_command_args(detection, output, strip);
+    let process = FfmpegProcess::spawn(Command::new("ffmpeg").args(&args))?;
+    let (status, stderr) = process.wait_with_output()?;
 
-    if !result.status.success() {
+    if !status.success() {
         return Err(VidQualityError::FFmpegError {
-            message: "FFmpeg command failed".to_string(),
-            stderr: String::from_utf8_lossy(&result.stderr).to_string(),
-            exit_code: result.status.code(),
+            message: "FFmpeg remux failed".to_string(),
+            stderr,
+            exit_code: status.code(),
             command: None,
             file_path: None,
         });
@@ -1519,6 +2759,21 @@ fn execute_hevc_lossless(
     Ok(std::fs::metadata(output)?.len())
 }
 
+/// ffmpeg arguments of [`execute_remux`].
+fn remux_command_args(
+    detection: &VideoDetectionResult,
+    output: &Path,
+    strip: shared_utils::StripPolicy,
+) -> Vec<String> {
+    shared_utils::remux_args(
+        detection,
+        output,
+        Some("hvc1"),
+        shared_utils::container_of(output),
+        strip,
+    )
+}
+
 pub fn smart_convert(input: &Path, config: &ConversionConfig) -> Result<ConversionOutput> {
     auto_convert(input, config)
 }
@@ -1701,6 +2956,8 @@ mod tests {
             TargetVideoFormat::Skip,
             "HEVC should be skipped in normal mode"
         );
+        assert!(normal.reason.contains("already H.265"));
+        assert!(normal.reason.contains("generation loss"));
 
         let apple = determine_strategy_with_apple_compat(&detection, true);
         assert_eq!(
@@ -1710,6 +2967,26 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_strategy_hevc_in_mkv_remuxed() {
+        let detection = crate::detection_api::VideoDetectionResult {
+            file_path: "/test/video.mkv".to_string(),
+            format: "matroska,webm".to_string(),
+            codec: crate::detection_api::DetectedCodec::H265,
+            codec_long: "HEVC".to_string(),
+            has_audio: true,
+            audio_codec: Some("aac".to_string()),
+            file_size: 50_000_000,
+            ..Default::default()
+        };
+
+        for apple_compat in [false, true] {
+            let strategy = determine_strategy_with_apple_compat(&detection, apple_compat);
+            assert_eq!(strategy.target, TargetVideoFormat::Remux);
+            assert_eq!(strategy.target.extension(), "MP4");
+        }
+    }
+
     #[test]
     fn test_strategy_h264_converted_both_modes() {
         let detection = crate::detection_api::VideoDetectionResult {
@@ -1770,6 +3047,43 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_recommend_video() {
+        let h264 = crate::detection_api::VideoDetectionResult {
+            file_path: "/test/video.mp4".to_string(),
+            format: "mp4".to_string(),
+            codec: crate::detection_api::DetectedCodec::H264,
+            compression: crate::detection_api::CompressionType::Standard,
+            width: 1920,
+            height: 1080,
+            fps: 30.0,
+            duration_secs: 60.0,
+            bit_depth: 8,
+            file_size: 50_000_000,
+            bitrate: 6_666_666,
+            video_bitrate: Some(6_000_000),
+            has_audio: true,
+            ..Default::default()
+        };
+        let rec = recommend_video(&h264);
+        assert_eq!(rec.recommended_codec, "HEVC");
+        assert!(rec.expected_size_reduction > 0.0 && rec.expected_size_reduction < 100.0);
+        assert!(rec.command.contains("-c:v libx265 -crf 20"));
+        assert!(rec.command.ends_with("'/test/video_hevc.mp4'"));
+
+        let hevc = crate::detection_api::VideoDetectionResult {
+            file_path: "/test/video.mp4".to_string(),
+            format: "mp4".to_string(),
+            codec: crate::detection_api::DetectedCodec::H265,
+            file_size: 50_000_000,
+            ..Default::default()
+        };
+        let rec = recommend_video(&hevc);
+        assert_eq!(rec.recommended_codec, rec.current_codec);
+        assert_eq!(rec.expected_size_reduction, 0.0);
+        assert!(rec.command.is_empty());
+    }
+
     #[test]
     fn test_strict_apple_compat_routing() {
         use crate::detection_api::{ColorSpace, CompressionType, DetectedCodec};
@@ -2225,4 +3539,60 @@ mod tests {
         let apple = determine_strategy_with_apple_compat(&det, true);
         assert_ne!(apple.target, TargetVideoFormat::Skip);
     }
+
+    #[test]
+    fn test_full_range_source_keeps_color_range() {
+        let detection = crate::detection_api::VideoDetectionResult {
+            pix_fmt: "yuvj420p".to_string(),
+            color_range: Some("pc".to_string()),
+            ..Default::default()
+        };
+        let args = build_hdr_ffmpeg_args(&detection).join(" ");
+        assert!(args.contains("-color_range pc"));
+
+        let limited = crate::detection_api::VideoDetectionResult::default();
+        assert!(!build_hdr_ffmpeg_args(&limited).contains(&"-color_range".to_string()));
+    }
+
+    #[test]
+    fn test_color_range_round_trips_through_hevc() {
+        // Needs ffmpeg built with libx265; nothing to check without it.
+        let has_x265 = which::which("ffprobe").is_ok()
+            && Command::new("ffmpeg")
+                .args(["-hide_banner", "-encoders"])
+                .output()
+                .map(|out| String::from_utf8_lossy(&out.stdout).contains("libx265"))
+                .unwrap_or(false);
+        if !has_x265 {
+            return;
+        }
+
+        let dir = tempfile::tempdir().unwrap();
+        let input = dir.path().join("jpeg_range.mov");
+        let status = Command::new("ffmpeg")
+            .args(["-v", "error", "-y", "-f", "lavfi"])
+            .args(["-i", "testsrc2=size=64x64:rate=25:duration=0.5"])
+            .args(["-pix_fmt", "yuvj420p", "-c:v", "mjpeg"])
+            .arg(&input)
+            .status()
+            .unwrap();
+        assert!(status.success());
+
+        let detection = crate::detection_api::detect_video(&input).unwrap();
+        assert_eq!(detection.color_range.as_deref(), Some("pc"));
+
+        let output = dir.path().join("jpeg_range.mp4");
+        execute_hevc_conversion(
+            &detection,
+            &output,
+            28,
+            1,
+            None,
+            false,
+            shared_utils::EncodeSettings::default(),
+        )
+        .unwrap();
+        let encoded = crate::detection_api::detect_video(&output).unwrap();
+        assert_eq!(encoded.color_range.as_deref(), Some("pc"));
+    }
 }
@@ -0,0 +1,205 @@
+//! Image sequence → HEVC MP4
+//!
+//! Numbered frames are first assembled into a lossless FFV1 intermediate at the requested
+//! frame rate, then go through the same CRF exploration as animated images: every decoded
+//! output frame is compared with the source frames for SSIM before the clip is kept.
+
+use std::fs;
+use std::path::Path;
+
+use shared_utils::conversion::{ConversionResult, ConvertOptions};
+use shared_utils::ImageSequence;
+
+use crate::animated_image::get_input_dimensions;
+use crate::{Result, VidQualityError};
+
+pub fn convert_image_sequence(
+    spec: &Path,
+    fps: f64,
+    options: &ConvertOptions,
+) -> Result<ConversionResult> {
+    let sequence =
+        shared_utils::detect_image_sequence(spec).map_err(VidQualityError::ConversionError)?;
+    let input_size = sequence.total_bytes();
+    let label = sequence.ffmpeg_pattern().display().to_string();
+
+    if options.verbose {
+        eprintln!(
+            "   🎞️  Image sequence: {} frames ({} … {}) @ {} fps",
+            sequence.frame_count(),
+            sequence.start,
+            sequence.start + sequence.frame_count() as u64 - 1,
+            fps
+        );
+    }
+
+    // Output is named after the frame prefix, beside the frames unless --output is set.
+    let pseudo_input = sequence.dir.join(sequence.name());
+    let ext = if options.apple_compat { "MOV" } else { "MP4" };
+    let output = shared_utils::conversion::determine_output_path(
+        &pseudo_input,
+        ext,
+        &options.output_dir,
+        options.on_collision,
+    )
+    .map_err(VidQualityError::ConversionError)?;
+
+    if shared_utils::conversion::keeps_existing_output(
+        input,
+        &output,
+        options.force,
+        options.on_collision,
+    ) {
+        return Err(VidQualityError::ConversionError(format!(
+            "Output exists: {} (use --force to overwrite)",
+            output.display()
+        )));
+    }
+
+    let temp_output = shared_utils::conversion::temp_path_for_output(&output);
+    let _temp_guard = shared_utils::conversion::TempOutputGuard::new(temp_output.clone());
+    let intermediate = tempfile::Builder::new()
+        .suffix(".mkv")
+        .tempfile_in(shared_utils::conversion::scratch_dir())
+        .map_err(|e| {
+            VidQualityError::ConversionError(format!("Failed to create temp MKV: {}", e))
+        })?;
+    shared_utils::assemble_lossless_intermediate(&sequence, fps, intermediate.path())
+        .map_err(VidQualityError::ConversionError)?;
+
+    let explore_result = explore_sequence(&sequence, intermediate.path(), &temp_output, options)?;
+    drop(intermediate);
+
+    for log in &explore_result.log {
+        eprintln!("{}", log);
+    }
+
+    if !explore_result.quality_passed {
+        let ssim = explore_result
+            .ssim
+            .map(|s| format!("{:.4}", s))
+            .unwrap_or_else(|| "unavailable".to_string());
+        return Ok(ConversionResult {
+            success: false,
+            input_path: label,
+            output_path: None,
+            input_size,
+            output_size: None,
+            size_reduction: None,
+            message: format!(
+                "Skipped: SSIM {} below threshold {:.4}",
+                ssim, explore_result.actual_min_ssim
+            ),
+            skipped: true,
+            skip_reason: Some("quality_failed".to_string()),
+        });
+    }
+
+    if !shared_utils::conversion::commit_temp_to_output_with_metadata(
+        &temp_output,
+        &output,
+        options.force,
+        None,
+        options.strip,
+        options.on_collision,
+    )? {
+        return Err(VidQualityError::ConversionError(format!(
+            "Output exists: {} (use --force to overwrite)",
+            output.display()
+        )));
+    }
+
+    let output_size = fs::metadata(&output)?.len();
+    let reduction_pct = if input_size > 0 {
+        (1.0 - output_size as f64 / input_size as f64) * 100.0
+    } else {
+        0.0
+    };
+    let ssim_msg = explore_result
+        .ssim
+        .map(|s| format!(", SSIM: {:.4}", s))
+        .unwrap_or_default();
+
+    Ok(ConversionResult {
+        success: true,
+        input_path: label,
+        output_path: Some(output.display().to_string()),
+        input_size,
+        output_size: Some(output_size),
+        size_reduction: Some(reduction_pct),
+        message: format!(
+            "HEVC (CRF {:.1}, {} frames @ {} fps, {} iter{}): {:+.1}%",
+            explore_result.optimal_crf,
+            sequence.frame_count(),
+            fps,
+            explore_result.iterations,
+            ssim_msg,
+            -reduction_pct
+        ),
+        skipped: false,
+        skip_reason: None,
+    })
+}
+
+fn explore_sequence(
+    sequence: &ImageSequence,
+    intermediate: &Path,
+    temp_output: &Path,
+    options: &ConvertOptions,
+) -> Result<shared_utils::ExploreResult> {
+    let (width, height) = get_input_dimensions(intermediate)?;
+    let vf_args = shared_utils::get_ffmpeg_dimension_args(width, height, false);
+    let flag_mode = options
+        .flag_mode()
+        .map_err(VidQualityError::ConversionError)?;
+    let initial_crf = shared_utils::crf_constants::get_global_last_hit_crf_hevc()
+        .unwrap_or(shared_utils::crf_constants::HEVC_CRF_DEFAULT);
+
+    if options.verbose {
+        eprintln!(
+            "   {} Mode: CRF {:.1} ({} frames)",
+            flag_mode.description_en(),
+            initial_crf,
+            sequence.frame_count()
+        );
+    }
+
+    let result = if let Some(min_ssim) = options.min_ssim {
+        shared_utils::explore_hevc_with_gpu_coarse_full(
+            intermediate,
+            temp_output,
+            vf_args,
+            initial_crf,
+            flag_mode.is_ultimate(),
+            false,
+            options.allow_size_tolerance,
+            min_ssim,
+            options.child_threads,
+        )
+    } else if flag_mode.is_ultimate() {
+        shared_utils::explore_hevc_with_gpu_coarse_ultimate(
+            intermediate,
+            temp_output,
+            vf_args,
+            initial_crf,
+            true,
+            options.allow_size_tolerance,
+            options.child_threads,
+        )
+    } else {
+        shared_utils::explore_hevc_with_gpu_coarse(
+            intermediate,
+            temp_output,
+            vf_args,
+            initial_crf,
+            options.allow_size_tolerance,
+            options.child_threads,
+        )
+    }
+    .map_err(|e| VidQualityError::ConversionError(e.to_string()))?;
+
+    if result.quality_passed && result.optimal_crf > 0.0 {
+        shared_utils::crf_constants::update_global_last_hit_crf_hevc(result.optimal_crf);
+    }
+    Ok(result)
+}
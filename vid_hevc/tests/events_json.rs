@@ -0,0 +1,34 @@
+//! `run --events-json`: stdout carries only the JSON event stream, so it can be piped into `jq`.
+
+use std::process::Command;
+
+#[test]
+fn events_json_stdout_is_json_lines_only() {
+    let dir = tempfile::tempdir().unwrap();
+    let input = dir.path().join("in");
+    let output = dir.path().join("out");
+    std::fs::create_dir_all(&input).unwrap();
+    // Not a video: copied through to --output, which prints a human line for it.
+    std::fs::write(input.join("notes.txt"), b"not a video").unwrap();
+
+    let run = Command::new(env!("CARGO_BIN_EXE_vid-hevc"))
+        .current_dir(dir.path())
+        .env("MFB_SKIP_DISK_PRECHECK", "1")
+        .arg("run")
+        .arg(&input)
+        .arg("--output")
+        .arg(&output)
+        .arg("--events-json")
+        .output()
+        .unwrap();
+
+    let stdout = String::from_utf8_lossy(&run.stdout);
+    for line in stdout.lines().filter(|l| !l.trim().is_empty()) {
+        assert!(
+            serde_json::from_str::<serde_json::Value>(line).is_ok(),
+            "non-JSON line on stdout: {line:?}"
+        );
+    }
+    // The summary still reaches the user, on stderr.
+    assert!(!run.stderr.is_empty());
+}